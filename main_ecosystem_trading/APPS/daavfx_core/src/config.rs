@@ -0,0 +1,422 @@
+// ============================================
+// SHARED CONFIG MODEL
+// ============================================
+//
+// The dashboard config both apps load, save, import and export: general
+// settings, the risk / time / news filters and the engine -> group -> logic
+// tree. Fields one app doesn't use still round-trip through the other
+// (use_direct_price_grid and group_mode for the root app, the Buy/Sell filter
+// overrides and annotations for logic-canvas-main).
+//
+// The logic row is a type parameter. The apps drive different EA builds: the
+// root app keeps per-logic TP/SL, stores selectors as the text its UI sends
+// and exports trail step mode 4 as "Disabled", while logic-canvas-main types
+// its selectors with config_enums. Each app aliases these structs over its
+// own LogicConfig.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::obfuscation::{deobfuscate_string, obfuscate_string};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MTConfig<L> {
+    pub version: String,
+    pub platform: String, // "MT4" or "MT5"
+    pub timestamp: String,
+    pub total_inputs: usize,
+    #[serde(default)]
+    pub last_saved_at: Option<String>,
+    #[serde(default)]
+    pub last_saved_platform: Option<String>,
+    #[serde(default)]
+    pub current_set_name: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub comments: Option<String>,
+    /// Notes keyed by JSON pointer field path, e.g. /engines/0/groups/0/logics/1/grid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<BTreeMap<String, String>>,
+    pub general: GeneralConfig,
+    pub engines: Vec<EngineConfig<L>>,
+}
+
+// Not derived: that would require L: Default, and an empty config has no logic rows
+impl<L> Default for MTConfig<L> {
+    fn default() -> Self {
+        MTConfig {
+            version: String::new(),
+            platform: String::new(),
+            timestamp: String::new(),
+            total_inputs: 0,
+            last_saved_at: None,
+            last_saved_platform: None,
+            current_set_name: None,
+            tags: None,
+            comments: None,
+            annotations: None,
+            general: GeneralConfig::default(),
+            engines: Vec::new(),
+        }
+    }
+}
+
+impl<L> MTConfig<L> {
+    /// The general news filter plus the per-direction overrides
+    fn news_filters_mut(&mut self) -> impl Iterator<Item = &mut NewsFilterConfig> {
+        let general = &mut self.general;
+        std::iter::once(&mut general.news_filter)
+            .chain(general.news_filter_b.iter_mut())
+            .chain(general.news_filter_s.iter_mut())
+    }
+
+    pub fn obfuscate_sensitive_fields(&mut self) {
+        self.general.license_key = obfuscate_string(&self.general.license_key);
+        self.general.license_server_url = obfuscate_string(&self.general.license_server_url);
+        for filter in self.news_filters_mut() {
+            filter.api_key = obfuscate_string(&filter.api_key);
+            filter.api_url = obfuscate_string(&filter.api_url);
+        }
+    }
+
+    pub fn deobfuscate_sensitive_fields(&mut self) {
+        self.general.license_key = deobfuscate_string(&self.general.license_key);
+        self.general.license_server_url = deobfuscate_string(&self.general.license_server_url);
+        for filter in self.news_filters_mut() {
+            filter.api_key = deobfuscate_string(&filter.api_key);
+            filter.api_url = deobfuscate_string(&filter.api_url);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GeneralConfig {
+    // License
+    pub license_key: String,
+    pub license_server_url: String,
+    pub require_license: bool,
+    pub license_check_interval: i32,
+
+    // Config
+    pub config_file_name: String,
+    pub config_file_is_common: bool,
+
+    // Trading (GLOBAL)
+    pub allow_buy: bool,
+    pub allow_sell: bool,
+
+    // Logging
+    pub enable_logs: bool,
+
+    #[serde(default)]
+    pub use_direct_price_grid: bool,
+
+    #[serde(default)]
+    pub group_mode: Option<i32>,
+    #[serde(default)]
+    pub grid_unit: Option<i32>,
+    #[serde(default)]
+    pub pip_factor: Option<i32>,
+
+    // Compounding
+    pub compounding_enabled: bool,
+    pub compounding_type: String,
+    pub compounding_target: f64,
+    pub compounding_increase: f64,
+
+    // Restart Policy
+    pub restart_policy_power: String,
+    pub restart_policy_non_power: String,
+    pub close_non_power_on_power_close: bool,
+    pub hold_timeout_bars: i32,
+
+    // Global System
+    pub magic_number: i32,
+    pub magic_number_buy: i32,
+    pub magic_number_sell: i32,
+    pub max_slippage_points: f64,
+    #[serde(default)]
+    pub reverse_magic_base: i32,
+    #[serde(default)]
+    pub hedge_magic_base: i32,
+    #[serde(default)]
+    pub hedge_magic_independent: bool,
+
+    // Risk Management
+    pub risk_management: RiskManagementConfig,
+    #[serde(default)]
+    pub risk_management_b: Option<RiskManagementConfig>,
+    #[serde(default)]
+    pub risk_management_s: Option<RiskManagementConfig>,
+
+    // Time Filters
+    pub time_filters: TimeFiltersConfig,
+    #[serde(default)]
+    pub time_filters_b: Option<TimeFiltersConfig>,
+    #[serde(default)]
+    pub time_filters_s: Option<TimeFiltersConfig>,
+
+    // News Filter
+    pub news_filter: NewsFilterConfig,
+    #[serde(default)]
+    pub news_filter_b: Option<NewsFilterConfig>,
+    #[serde(default)]
+    pub news_filter_s: Option<NewsFilterConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RiskManagementConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub spread_filter_enabled: bool,
+    pub max_spread_points: f64,
+    pub equity_stop_enabled: bool,
+    pub equity_stop_value: f64,
+    pub drawdown_stop_enabled: bool,
+    pub max_drawdown_percent: f64,
+    #[serde(default)]
+    pub risk_action: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TimeFiltersConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub priority_settings: TimePrioritySettings,
+    pub sessions: Vec<SessionConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TimePrioritySettings {
+    pub news_filter_overrides_session: bool,
+    pub session_filter_overrides_news: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionConfig {
+    #[serde(default)]
+    pub session_number: i32,
+    pub enabled: bool,
+    pub day: i32,
+    pub start_hour: i32,
+    pub start_minute: i32,
+    pub end_hour: i32,
+    pub end_minute: i32,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub auto_restart: bool,
+    #[serde(default)]
+    pub restart_mode: String,
+    #[serde(default)]
+    pub restart_bars: i32,
+    #[serde(default)]
+    pub restart_minutes: i32,
+    #[serde(default)]
+    pub restart_pips: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NewsFilterConfig {
+    pub enabled: bool,
+    pub api_key: String,
+    pub api_url: String,
+    pub countries: String,
+    pub impact_level: i32,
+    pub minutes_before: i32,
+    pub minutes_after: i32,
+    /// Trigger action the root app exports as gInput_NewsAction;
+    /// logic-canvas-main uses the stop_ea / close_trades / auto_restart flags
+    #[serde(default)]
+    pub action: String,
+    #[serde(default = "default_true")]
+    pub stop_ea: bool,
+    #[serde(default)]
+    pub close_trades: bool,
+    #[serde(default = "default_true")]
+    pub auto_restart: bool,
+    #[serde(default)]
+    pub check_interval: i32,
+    #[serde(default)]
+    pub alert_minutes: i32,
+    #[serde(default = "default_true")]
+    pub filter_high_only: bool,
+    #[serde(default)]
+    pub filter_weekends: bool,
+    #[serde(default = "default_true")]
+    pub use_local_cache: bool,
+    #[serde(default = "default_3600")]
+    pub cache_duration: i32,
+    #[serde(default)]
+    pub fallback_on_error: String,
+    #[serde(default)]
+    pub filter_currencies: String,
+    #[serde(default = "default_true")]
+    pub include_speeches: bool,
+    #[serde(default = "default_true")]
+    pub include_reports: bool,
+    #[serde(default = "default_true")]
+    pub visual_indicator: bool,
+    #[serde(default)]
+    pub alert_before_news: bool,
+    #[serde(default)]
+    pub calendar_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineConfig<L> {
+    pub engine_id: String, // "A", "B", "C"
+    pub engine_name: String,
+    pub max_power_orders: i32,
+    pub groups: Vec<GroupConfig<L>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupConfig<L> {
+    pub group_number: u8, // 1-20 (all groups supported)
+    pub enabled: bool,
+
+    // ===== GROUP TRIGGER (Groups 2-20 only) =====
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_power_start: Option<i32>, // DEPRECATED: Use group_power_start_b and group_power_start_s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_power_start_b: Option<i32>, // gInput_GroupPowerStart_{P|BP|CP}{N}_Buy - Buy side threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_power_start_s: Option<i32>, // gInput_GroupPowerStart_{P|BP|CP}{N}_Sell - Sell side threshold
+
+    // ===== GROUP-LEVEL REVERSE/HEDGE CONTROLS (V17.04+) =====
+    #[serde(default)]
+    pub reverse_mode: bool, // gInput_Group{N}_ReverseMode
+    #[serde(default)]
+    pub hedge_mode: bool, // gInput_Group{N}_HedgeMode
+    #[serde(default = "default_logic_none")]
+    pub hedge_reference: String, // gInput_Group{N}_HedgeReference
+    #[serde(default)]
+    pub entry_delay_bars: i32, // gInput_Group{N}_EntryDelayBars
+
+    pub logics: Vec<L>,
+}
+
+fn default_true() -> bool {
+    true
+}
+fn default_3600() -> i32 {
+    3600
+}
+fn default_logic_none() -> String {
+    "Logic_None".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn config_json(general: Value) -> Value {
+        json!({
+            "version": "v19.0",
+            "platform": "MT4",
+            "timestamp": "",
+            "total_inputs": 0,
+            "general": general,
+            "engines": [{
+                "engine_id": "A",
+                "engine_name": "Engine A",
+                "max_power_orders": 7,
+                "groups": [{ "group_number": 1, "enabled": true, "logics": [{ "logic_id": "A_POWER_B_G1" }] }],
+            }],
+        })
+    }
+
+    fn general_json() -> Value {
+        json!({
+            "license_key": "LIC-1",
+            "license_server_url": "https://license.example",
+            "require_license": false,
+            "license_check_interval": 3600,
+            "config_file_name": "DAAVFX_Config.json",
+            "config_file_is_common": true,
+            "allow_buy": true,
+            "allow_sell": true,
+            "enable_logs": true,
+            "compounding_enabled": false,
+            "compounding_type": "Compound_Balance",
+            "compounding_target": 40.0,
+            "compounding_increase": 2.0,
+            "restart_policy_power": "Restart_Default",
+            "restart_policy_non_power": "Restart_Default",
+            "close_non_power_on_power_close": false,
+            "hold_timeout_bars": 10,
+            "magic_number": 777,
+            "magic_number_buy": 777,
+            "magic_number_sell": 778,
+            "max_slippage_points": 30.0,
+            "risk_management": {
+                "spread_filter_enabled": false,
+                "max_spread_points": 25.0,
+                "equity_stop_enabled": false,
+                "equity_stop_value": 35.0,
+                "drawdown_stop_enabled": false,
+                "max_drawdown_percent": 35.0,
+            },
+            "time_filters": { "sessions": [] },
+            "news_filter": {
+                "enabled": true,
+                "api_key": "news-key",
+                "api_url": "https://news.example",
+                "countries": "US",
+                "impact_level": 3,
+                "minutes_before": 30,
+                "minutes_after": 30,
+            },
+        })
+    }
+
+    #[test]
+    fn test_both_apps_configs_load() {
+        // Root app: group mode and news action, no Buy/Sell overrides
+        let mut root = general_json();
+        root["use_direct_price_grid"] = json!(true);
+        root["group_mode"] = json!(2);
+        root["news_filter"]["action"] = json!("TriggerAction_StopEA");
+        let config: MTConfig<Value> = serde_json::from_value(config_json(root)).unwrap();
+        assert!(config.general.use_direct_price_grid);
+        assert_eq!(config.general.group_mode, Some(2));
+        assert_eq!(config.general.news_filter.action, "TriggerAction_StopEA");
+        assert!(config.general.news_filter.stop_ea);
+        assert_eq!(config.general.news_filter.cache_duration, 3600);
+        assert_eq!(config.engines[0].groups[0].hedge_reference, "Logic_None");
+        assert_eq!(
+            config.engines[0].groups[0].logics[0]["logic_id"],
+            "A_POWER_B_G1"
+        );
+
+        // logic-canvas-main: Buy/Sell overrides, no root-only fields
+        let mut canvas = general_json();
+        canvas["news_filter_b"] = canvas["news_filter"].clone();
+        let config: MTConfig<Value> = serde_json::from_value(config_json(canvas)).unwrap();
+        assert!(!config.general.use_direct_price_grid);
+        assert_eq!(config.general.group_mode, None);
+        assert_eq!(config.general.news_filter.action, "");
+        assert!(config.general.news_filter_b.is_some());
+    }
+
+    #[test]
+    fn test_sensitive_fields_round_trip() {
+        let mut general = general_json();
+        general["news_filter_s"] = general["news_filter"].clone();
+        let mut config: MTConfig<Value> = serde_json::from_value(config_json(general)).unwrap();
+
+        config.obfuscate_sensitive_fields();
+        assert!(config.general.license_key.starts_with("ENC:"));
+        assert!(config.general.news_filter.api_key.starts_with("ENC:"));
+        let sell = config.general.news_filter_s.as_ref().unwrap();
+        assert!(sell.api_url.starts_with("ENC:"));
+
+        config.deobfuscate_sensitive_fields();
+        assert_eq!(config.general.license_key, "LIC-1");
+        assert_eq!(config.general.news_filter_s.unwrap().api_key, "news-key");
+    }
+}
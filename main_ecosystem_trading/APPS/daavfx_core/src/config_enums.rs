@@ -0,0 +1,583 @@
+// ============================================
+// CONFIG ENUM REGISTRY
+// ============================================
+//
+// The mode selectors of the apps' LogicConfig (trail method, trail step method/mode,
+// partial close mode/balance, trigger type/mode, strategy type, trading
+// mode) are typed as the enums here. Each enum owns its canonical spelling
+// ("Step_Percent", "TrailStepMode_PerOrder", "Counter Trend"), the legacy
+// spellings still accepted on input and the integer the EA reads from a
+// setfile. With the serde feature they (de)serialize as their spelling, so
+// an unknown value is rejected when a config is loaded instead of being
+// exported as whatever the fallback happens to be.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+pub trait ConfigEnum: Copy + Default + 'static {
+    /// Field family used in error messages, e.g. "trail step mode"
+    const NAME: &'static str;
+    const ALL: &'static [Self];
+
+    /// Spelling stored in configs
+    fn as_str(self) -> &'static str;
+
+    /// Integer written to setfiles; None for dashboard-only selectors
+    fn code(self) -> Option<i32>;
+
+    /// `code`, with 0 for dashboard-only selectors
+    fn ea_code(self) -> i32 {
+        self.code().unwrap_or(0)
+    }
+
+    /// Older spellings and codes still accepted on input, lowercase
+    fn aliases(self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Spelling the dashboard's selector lists; `as_str` unless overridden
+    fn label(self) -> String {
+        self.as_str().to_string()
+    }
+
+    /// Case-insensitive: canonical spelling, EA code, alias, or "<code> <name>"
+    /// as written by the trigger type decoder
+    fn parse(raw: &str) -> Result<Self, String> {
+        let s = raw.trim();
+        let name = match s.split_once(' ') {
+            Some((code, name)) if code.chars().all(|c| c.is_ascii_digit()) => name.trim(),
+            _ => s,
+        };
+        let lower = name.to_ascii_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|value| {
+                value.as_str().eq_ignore_ascii_case(name)
+                    || value.code().is_some_and(|code| code.to_string() == name)
+                    || value.aliases().contains(&lower.as_str())
+            })
+            .ok_or_else(|| {
+                let expected: Vec<&str> = Self::ALL.iter().map(|v| v.as_str()).collect();
+                format!(
+                    "Invalid {} '{}': expected one of {}",
+                    Self::NAME,
+                    raw,
+                    expected.join(", ")
+                )
+            })
+    }
+
+    fn from_code(code: i32) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|value| value.code() == Some(code))
+    }
+}
+
+/// EA integer for `raw`, with the selector's default standing in for
+/// values that don't parse
+pub fn encode<E: ConfigEnum>(raw: &str) -> i32 {
+    E::parse(raw).unwrap_or_default().ea_code()
+}
+
+/// `deserialize_with` for an Option selector field; blank becomes None
+#[cfg(feature = "serde")]
+pub fn canonical_opt<'de, D, E>(deserializer: D) -> Result<Option<E>, D::Error>
+where
+    D: Deserializer<'de>,
+    E: ConfigEnum,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(raw) if !raw.trim().is_empty() => {
+            E::parse(&raw).map(Some).map_err(serde::de::Error::custom)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Display, and with the serde feature (de)serialization as the selector's
+/// spelling. A blank value deserializes to the default.
+macro_rules! config_enum_traits {
+    ($($ty:ty),* $(,)?) => {$(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.label())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let raw = String::deserialize(deserializer)?;
+                if raw.trim().is_empty() {
+                    return Ok(Self::default());
+                }
+                Self::parse(&raw).map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+config_enum_traits!(
+    TrailMethod,
+    TrailStepMethod,
+    TrailStepMode,
+    PartialMode,
+    PartialBalance,
+    TriggerType,
+    TriggerMode,
+    StrategyType,
+    TradingMode,
+);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailMethod {
+    #[default]
+    Points,
+    AvgPercent,
+}
+
+impl ConfigEnum for TrailMethod {
+    const NAME: &'static str = "trail method";
+    const ALL: &'static [Self] = &[TrailMethod::Points, TrailMethod::AvgPercent];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TrailMethod::Points => "Points",
+            TrailMethod::AvgPercent => "AVG_Percent",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            TrailMethod::Points => 0,
+            TrailMethod::AvgPercent => 1,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            TrailMethod::Points => &[
+                "2",
+                "trail",
+                "trail_points",
+                "avg_points",
+                "trail_avg_points",
+                "percent",
+                "trail_profit_percent",
+            ],
+            TrailMethod::AvgPercent => &["trail_avg_percent"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailStepMethod {
+    #[default]
+    Points,
+    Percent,
+}
+
+impl ConfigEnum for TrailStepMethod {
+    const NAME: &'static str = "trail step method";
+    const ALL: &'static [Self] = &[TrailStepMethod::Points, TrailStepMethod::Percent];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TrailStepMethod::Points => "Step_Points",
+            TrailStepMethod::Percent => "Step_Percent",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            TrailStepMethod::Points => 0,
+            TrailStepMethod::Percent => 1,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            TrailStepMethod::Points => &["2", "step", "step_pips"],
+            TrailStepMethod::Percent => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailStepMode {
+    #[default]
+    Auto,
+    Fixed,
+    PerOrder,
+}
+
+impl ConfigEnum for TrailStepMode {
+    const NAME: &'static str = "trail step mode";
+    const ALL: &'static [Self] = &[
+        TrailStepMode::Auto,
+        TrailStepMode::Fixed,
+        TrailStepMode::PerOrder,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TrailStepMode::Auto => "TrailStepMode_Auto",
+            TrailStepMode::Fixed => "TrailStepMode_Fixed",
+            TrailStepMode::PerOrder => "TrailStepMode_PerOrder",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            TrailStepMode::Auto => 0,
+            TrailStepMode::Fixed => 1,
+            TrailStepMode::PerOrder => 3,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            // 4 was a separate "auto" variant in older EA builds
+            TrailStepMode::Auto => &["4"],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartialMode {
+    Low,
+    #[default]
+    Mid,
+    Aggressive,
+}
+
+impl ConfigEnum for PartialMode {
+    const NAME: &'static str = "partial close mode";
+    const ALL: &'static [Self] = &[PartialMode::Low, PartialMode::Mid, PartialMode::Aggressive];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            PartialMode::Low => "PartialMode_Low",
+            PartialMode::Mid => "PartialMode_Mid",
+            PartialMode::Aggressive => "PartialMode_Aggressive",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            PartialMode::Low => 0,
+            PartialMode::Mid => 1,
+            PartialMode::Aggressive => 2,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            PartialMode::Low => &[],
+            PartialMode::Mid => &["4", "partialmode_balanced"],
+            PartialMode::Aggressive => &["3", "partialmode_high"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartialBalance {
+    Aggressive,
+    #[default]
+    Balanced,
+    Conservative,
+}
+
+impl ConfigEnum for PartialBalance {
+    const NAME: &'static str = "partial close balance";
+    const ALL: &'static [Self] = &[
+        PartialBalance::Aggressive,
+        PartialBalance::Balanced,
+        PartialBalance::Conservative,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            PartialBalance::Aggressive => "PartialBalance_Aggressive",
+            PartialBalance::Balanced => "PartialBalance_Balanced",
+            PartialBalance::Conservative => "PartialBalance_Conservative",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            PartialBalance::Aggressive => 0,
+            PartialBalance::Balanced => 1,
+            PartialBalance::Conservative => 2,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TriggerType {
+    #[default]
+    Immediate,
+    AfterBars,
+    AfterSeconds,
+    AfterPips,
+    TimeFilter,
+    NewsFilter,
+    PowerAOppositeCount,
+}
+
+impl ConfigEnum for TriggerType {
+    const NAME: &'static str = "trigger type";
+    const ALL: &'static [Self] = &[
+        TriggerType::Immediate,
+        TriggerType::AfterBars,
+        TriggerType::AfterSeconds,
+        TriggerType::AfterPips,
+        TriggerType::TimeFilter,
+        TriggerType::NewsFilter,
+        TriggerType::PowerAOppositeCount,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TriggerType::Immediate => "Trigger_Immediate",
+            TriggerType::AfterBars => "Trigger_AfterBars",
+            TriggerType::AfterSeconds => "Trigger_AfterSeconds",
+            TriggerType::AfterPips => "Trigger_AfterPips",
+            TriggerType::TimeFilter => "Trigger_TimeFilter",
+            TriggerType::NewsFilter => "Trigger_NewsFilter",
+            TriggerType::PowerAOppositeCount => "Trigger_PowerAOppositeCount",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            TriggerType::Immediate => 0,
+            TriggerType::AfterBars => 1,
+            TriggerType::AfterSeconds => 2,
+            TriggerType::AfterPips => 3,
+            TriggerType::TimeFilter => 4,
+            TriggerType::NewsFilter => 5,
+            TriggerType::PowerAOppositeCount => 6,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            // Spellings found in early sample exports
+            TriggerType::Immediate => &["default", "triggertype_immediate"],
+            _ => &[],
+        }
+    }
+
+    /// The trigger type selector reads the code off the front: "3 Trigger_AfterPips"
+    fn label(self) -> String {
+        format!("{} {}", self.code().unwrap_or(0), self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TriggerMode {
+    #[default]
+    OnTick,
+    FirstTick,
+    WaitBar,
+}
+
+impl ConfigEnum for TriggerMode {
+    const NAME: &'static str = "trigger mode";
+    const ALL: &'static [Self] = &[
+        TriggerMode::OnTick,
+        TriggerMode::FirstTick,
+        TriggerMode::WaitBar,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TriggerMode::OnTick => "TriggerMode_OnTick",
+            TriggerMode::FirstTick => "TriggerMode_FirstTick",
+            TriggerMode::WaitBar => "TriggerMode_WaitBar",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        Some(match self {
+            TriggerMode::OnTick => 0,
+            TriggerMode::FirstTick => 1,
+            TriggerMode::WaitBar => 2,
+        })
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            TriggerMode::OnTick => &["ontick", "on_tick"],
+            TriggerMode::FirstTick => &["firsttick", "first_tick"],
+            TriggerMode::WaitBar => &["waitbar", "wait_bar"],
+        }
+    }
+}
+
+/// Written to setfiles by name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StrategyType {
+    #[default]
+    Trail,
+}
+
+impl ConfigEnum for StrategyType {
+    const NAME: &'static str = "strategy type";
+    const ALL: &'static [Self] = &[StrategyType::Trail];
+
+    fn as_str(self) -> &'static str {
+        "Trail"
+    }
+
+    fn code(self) -> Option<i32> {
+        None
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        &["trailing"]
+    }
+}
+
+/// Dashboard-only; exported as the reverse/hedge flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TradingMode {
+    #[default]
+    CounterTrend,
+    Hedge,
+    Reverse,
+}
+
+impl ConfigEnum for TradingMode {
+    const NAME: &'static str = "trading mode";
+    const ALL: &'static [Self] = &[
+        TradingMode::CounterTrend,
+        TradingMode::Hedge,
+        TradingMode::Reverse,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TradingMode::CounterTrend => "Counter Trend",
+            TradingMode::Hedge => "Hedge",
+            TradingMode::Reverse => "Reverse",
+        }
+    }
+
+    fn code(self) -> Option<i32> {
+        None
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            TradingMode::CounterTrend => &[
+                "countertrend",
+                "counter_trend",
+                "counter-trend",
+                "trend following",
+                "trend_following",
+                "trending",
+            ],
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accepts_codes_and_legacy_spellings() {
+        assert_eq!(TrailStepMode::parse("3"), Ok(TrailStepMode::PerOrder));
+        assert_eq!(TrailStepMode::parse("4"), Ok(TrailStepMode::Auto));
+        assert_eq!(
+            PartialMode::parse("PartialMode_Balanced"),
+            Ok(PartialMode::Mid)
+        );
+        assert_eq!(TrailMethod::parse("Trail"), Ok(TrailMethod::Points));
+        assert_eq!(
+            TrailStepMethod::parse("step_percent"),
+            Ok(TrailStepMethod::Percent)
+        );
+        assert_eq!(
+            TriggerType::parse("3 Trigger_AfterPips"),
+            Ok(TriggerType::AfterPips)
+        );
+        assert_eq!(
+            TradingMode::parse("Trending"),
+            Ok(TradingMode::CounterTrend)
+        );
+
+        let err = TrailStepMode::parse("TrailStepMode_Sometimes").unwrap_err();
+        assert!(err.contains("TrailStepMode_PerOrder"), "{}", err);
+        // Dashboard-only selectors have no numeric form
+        assert!(TradingMode::parse("0").is_err());
+    }
+
+    #[test]
+    fn test_ea_codes() {
+        assert_eq!(encode::<TrailStepMode>("TrailStepMode_PerOrder"), 3);
+        assert_eq!(encode::<PartialMode>("garbage"), 1);
+        assert_eq!(encode::<PartialBalance>("PartialBalance_Conservative"), 2);
+        assert_eq!(TriggerMode::from_code(2), Some(TriggerMode::WaitBar));
+        assert_eq!(TrailStepMode::from_code(2), None);
+        assert_eq!(TrailStepMethod::parse("1"), Ok(TrailStepMethod::Percent));
+        assert_eq!(TriggerType::AfterPips.label(), "3 Trigger_AfterPips");
+        assert_eq!(TradingMode::Hedge.ea_code(), 0);
+        assert_eq!(
+            TrailStepMode::PerOrder.to_string(),
+            "TrailStepMode_PerOrder"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Selectors {
+        mode: TrailStepMode,
+        #[serde(default, deserialize_with = "canonical_opt")]
+        trigger_mode: Option<TriggerMode>,
+        #[serde(default)]
+        trigger_type: Option<TriggerType>,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_canonicalizes_and_rejects() {
+        let parsed: Selectors = serde_json::from_str(
+            r#"{"mode": "1", "trigger_mode": "wait_bar", "trigger_type": "3"}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.mode, TrailStepMode::Fixed);
+        assert_eq!(parsed.trigger_mode, Some(TriggerMode::WaitBar));
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            r#"{"mode":"TrailStepMode_Fixed","trigger_mode":"TriggerMode_WaitBar","trigger_type":"3 Trigger_AfterPips"}"#
+        );
+
+        let parsed: Selectors =
+            serde_json::from_str(r#"{"mode": "", "trigger_mode": ""}"#).unwrap();
+        assert_eq!(parsed.mode, TrailStepMode::Auto);
+        assert_eq!(parsed.trigger_mode, None);
+
+        let err = serde_json::from_str::<Selectors>(r#"{"mode": "Sideways"}"#).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid trail step mode"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,173 @@
+// ============================================
+// EXPORT DEBOUNCING
+// ============================================
+//
+// Slider drags fire an active set export per tick, and every write makes
+// the EA reload. Exports are coalesced per target file: the first one in a
+// quiet period is written at once, later ones inside the window only
+// replace a pending export, and that pending export is written when the
+// window closes. A file sees at most one write per window and always ends
+// up with the last config sent.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Window used when the app has no debounce setting of its own
+pub const DEFAULT_WINDOW_MS: u64 = 400;
+
+/// Outcome of submitting an export
+pub enum Submitted<T> {
+    /// Written immediately, with the writer's result
+    Written(T),
+    /// Superseded or deferred; the latest export lands when the window closes
+    Coalesced,
+}
+
+type PendingWrite = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct TargetState {
+    last_write: Option<Instant>,
+    pending: Option<PendingWrite>,
+    flush_scheduled: bool,
+}
+
+static TARGETS: OnceLock<Mutex<HashMap<String, TargetState>>> = OnceLock::new();
+/// Serializes the writes themselves so a slow write and a flush never interleave
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+fn targets() -> &'static Mutex<HashMap<String, TargetState>> {
+    TARGETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn should_write_now(
+    last_write: Option<Instant>,
+    now: Instant,
+    window: Duration,
+    flush_scheduled: bool,
+) -> bool {
+    if window.is_zero() {
+        return true;
+    }
+    !flush_scheduled && last_write.map_or(true, |last| now.duration_since(last) >= window)
+}
+
+fn flush_after(target: String, delay: Duration) {
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        let pending = {
+            let mut targets = targets().lock().unwrap_or_else(|e| e.into_inner());
+            let state = targets.entry(target.clone()).or_default();
+            state.flush_scheduled = false;
+            state.last_write = Some(Instant::now());
+            state.pending.take()
+        };
+        if let Some(write) = pending {
+            let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            write();
+        }
+    });
+}
+
+/// Write now, or queue `write` as the pending export of `target` when
+/// another write of it happened less than `window` ago. A deferred write
+/// has no caller left to return to, so its error goes to `report`.
+pub fn submit<T, F>(
+    target: &str,
+    window: Duration,
+    write: F,
+    report: fn(&str, &str),
+) -> Submitted<Result<T, String>>
+where
+    T: 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let now = Instant::now();
+    let mut targets = targets().lock().unwrap_or_else(|e| e.into_inner());
+    let state = targets.entry(target.to_string()).or_default();
+
+    if should_write_now(state.last_write, now, window, state.flush_scheduled) {
+        state.last_write = Some(now);
+        drop(targets);
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        return Submitted::Written(write());
+    }
+
+    let name = target.to_string();
+    state.pending = Some(Box::new(move || {
+        if let Err(err) = write() {
+            report(&name, &err);
+        }
+    }));
+    if !state.flush_scheduled {
+        state.flush_scheduled = true;
+        let elapsed = state
+            .last_write
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        flush_after(target.to_string(), window.saturating_sub(elapsed));
+    }
+    Submitted::Coalesced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn ignore(_: &str, _: &str) {}
+
+    #[test]
+    fn test_should_write_now() {
+        let now = Instant::now();
+        let window = Duration::from_millis(400);
+        assert!(should_write_now(None, now, window, false));
+        assert!(!should_write_now(Some(now), now, window, false));
+        assert!(should_write_now(Some(now), now + window, window, false));
+        assert!(!should_write_now(Some(now), now + window, window, true));
+        assert!(should_write_now(Some(now), now, Duration::ZERO, true));
+    }
+
+    #[test]
+    fn test_burst_collapses_to_first_and_last_write() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let window = Duration::from_millis(50);
+        let mut immediate = 0;
+        for value in 0..5 {
+            let written = written.clone();
+            let submitted = submit(
+                "test-burst.set",
+                window,
+                move || {
+                    written.lock().unwrap().push(value);
+                    Ok(())
+                },
+                ignore,
+            );
+            if let Submitted::Written(result) = submitted {
+                result.unwrap();
+                immediate += 1;
+            }
+        }
+        assert_eq!(immediate, 1);
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(*written.lock().unwrap(), vec![0, 4]);
+
+        // Other targets aren't held back by this one
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let submitted = submit(
+            "test-other.set",
+            window,
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+            ignore,
+        );
+        assert!(matches!(submitted, Submitted::Written(Ok(()))));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,125 @@
+// ============================================
+// FILE HELPERS
+// ============================================
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Write through a temporary file in the same directory and rename it into
+/// place, so a crash mid-write never leaves a truncated setfile or config
+pub fn atomic_write(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    // Create a temporary file in the same directory
+    let tmp_extension = format!(
+        "{}.tmp",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    );
+    let tmp_path = if let Some(ext) = path.extension() {
+        path.with_extension(format!("{}.{}", ext.to_string_lossy(), tmp_extension))
+    } else {
+        path.with_extension(tmp_extension)
+    };
+
+    // Write to the temporary file
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write temporary file: {}", e))?;
+
+    // Rename temporary file to target file (atomic operation)
+    fs::rename(&tmp_path, path).map_err(|e| {
+        // Cleanup temp file if rename fails
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to commit file (rename failed): {}", e)
+    })?;
+
+    Ok(())
+}
+
+/// `<path>.bak<index>`; bak1 is the newest backup
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".bak{}", index));
+    path.with_file_name(name)
+}
+
+/// Shift <path>.bak1..bak{keep-1} up by one and copy `path` into .bak1, so
+/// an overwrite of a file a live EA reads can be rolled back. Returns false
+/// when there was nothing to back up (`path` doesn't exist yet).
+pub fn rotate_backups(path: &Path, keep: usize) -> Result<bool, String> {
+    if !path.is_file() || keep == 0 {
+        return Ok(false);
+    }
+    let read = |p: &Path| fs::read(p).map_err(|e| format!("Failed to read {}: {}", p.display(), e));
+    // Skip the copy when the newest backup already holds this exact file
+    let newest = backup_path(path, 1);
+    if newest.is_file() && read(&newest)? == read(path)? {
+        return Ok(true);
+    }
+
+    for index in (1..keep).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, index + 1))
+                .map_err(|e| format!("Failed to rotate backup {}: {}", from.display(), e))?;
+        }
+    }
+    fs::copy(path, &newest).map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+/// MetaTrader saves setfiles as UTF-16 LE with a BOM; hand-edited ones are UTF-8
+pub fn decode_setfile_bytes(bytes: Vec<u8>) -> Result<String, String> {
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        let u16_vec: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16(&u16_vec).map_err(|e| format!("Failed to parse UTF-16 .set file: {}", e))
+    } else {
+        String::from_utf8(bytes)
+            .map_err(|e| format!("Failed to parse .set file (not UTF-8 or UTF-16 LE): {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_setfile_bytes() {
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(
+                "gInput_Grid=300"
+                    .encode_utf16()
+                    .flat_map(|u| u.to_le_bytes()),
+            )
+            .collect();
+        assert_eq!(decode_setfile_bytes(utf16).unwrap(), "gInput_Grid=300");
+        assert_eq!(
+            decode_setfile_bytes(b"gInput_Grid=300".to_vec()).unwrap(),
+            "gInput_Grid=300"
+        );
+        assert!(decode_setfile_bytes(vec![0xC3, 0x28]).is_err());
+    }
+
+    #[test]
+    fn test_rotate_backups_keeps_newest_first() {
+        let dir = std::env::temp_dir().join(format!("daavfx_core_bak_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ACTIVE.set");
+        assert!(!rotate_backups(&path, 3).unwrap());
+
+        for version in ["v1", "v2", "v3", "v4"] {
+            fs::write(&path, version).unwrap();
+            assert!(rotate_backups(&path, 3).unwrap());
+        }
+        // Backing up an unchanged file doesn't push another copy
+        rotate_backups(&path, 3).unwrap();
+        assert_eq!(fs::read_to_string(backup_path(&path, 1)).unwrap(), "v4");
+        assert_eq!(fs::read_to_string(backup_path(&path, 3)).unwrap(), "v2");
+        assert!(!backup_path(&path, 4).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
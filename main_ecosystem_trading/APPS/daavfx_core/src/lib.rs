@@ -0,0 +1,16 @@
+//! Setfile and config logic shared by the two dashboard apps
+//! (`src-tauri` and `logic-canvas-main/src-tauri`).
+//!
+//! Only code that must behave the same in both apps lives here: the config
+//! model, setfile key naming, the config selector registry, secret
+//! obfuscation, file helpers, export debouncing and the safe mode switch.
+//! Tauri commands stay in the apps, behind each app's `tauri-app` feature.
+
+#[cfg(feature = "serde")]
+pub mod config;
+pub mod config_enums;
+pub mod debounce;
+pub mod fs;
+pub mod obfuscation;
+pub mod safe_mode;
+pub mod setfile_keys;
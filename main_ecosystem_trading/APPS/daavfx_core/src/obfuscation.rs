@@ -0,0 +1,70 @@
+// ============================================
+// ENCRYPTION / OBFUSCATION UTILITIES
+// ============================================
+//
+// Keeps license keys, API keys and tokens out of plain sight in the configs
+// and settings files the apps write. This is XOR + hex under a fixed key, so
+// it stops casual reading, not an attacker with the binary. Obfuscated values
+// carry an "ENC:" prefix; anything without it reads back unchanged.
+
+const OBFUSCATION_KEY: &str = "DAAVFX_SECURE_STORAGE_KEY_2024";
+
+pub fn obfuscate_string(input: &str) -> String {
+    if input.is_empty() {
+        return String::new();
+    }
+    // Check if already obfuscated to prevent double encryption
+    if input.starts_with("ENC:") {
+        return input.to_string();
+    }
+
+    // Simple XOR + Hex
+    let mut output = String::from("ENC:");
+    let key_bytes = OBFUSCATION_KEY.as_bytes();
+    for (i, b) in input.bytes().enumerate() {
+        let key_byte = key_bytes[i % key_bytes.len()];
+        let xored = b ^ key_byte;
+        output.push_str(&format!("{:02x}", xored));
+    }
+    output
+}
+
+pub fn deobfuscate_string(input: &str) -> String {
+    if !input.starts_with("ENC:") {
+        return input.to_string();
+    }
+    let hex_part = &input[4..];
+    let key_bytes = OBFUSCATION_KEY.as_bytes();
+    let mut decoded = Vec::new();
+
+    // Parse hex
+    let mut chars = hex_part.chars();
+    let mut idx = 0;
+    while let (Some(h1), Some(h2)) = (chars.next(), chars.next()) {
+        if let Ok(byte) = u8::from_str_radix(&format!("{}{}", h1, h2), 16) {
+            let key_byte = key_bytes[idx % key_bytes.len()];
+            decoded.push(byte ^ key_byte);
+            idx += 1;
+        } else {
+            return input.to_string(); // Fail safe
+        }
+    }
+
+    String::from_utf8(decoded).unwrap_or_else(|_| input.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_obfuscation_round_trips() {
+        let hidden = obfuscate_string("LIC-1234-ABCD");
+        assert!(hidden.starts_with("ENC:"));
+        assert_eq!(obfuscate_string(&hidden), hidden);
+        assert_eq!(deobfuscate_string(&hidden), "LIC-1234-ABCD");
+        assert_eq!(deobfuscate_string("plain"), "plain");
+        assert_eq!(deobfuscate_string("ENC:zz"), "ENC:zz");
+        assert_eq!(obfuscate_string(""), "");
+    }
+}
@@ -0,0 +1,65 @@
+// ============================================
+// SAFE MODE
+// ============================================
+//
+// Read-only switch for demos on machines attached to live accounts. While
+// it is on, anything that writes into terminal folders (Common Files
+// exports, ACTIVE.set, sync commands, tester files, MQL fixes, terminal
+// launches) fails with a SafeModeBlocked error. Most of those writers are
+// plain functions without access to an app state, so the flag is
+// process-wide; each app exposes its own get/set commands for it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Prefix of every error safe mode returns, for the UI to match on
+pub const SAFE_MODE_BLOCKED: &str = "SafeModeBlocked";
+
+static SAFE_MODE: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// The process-wide flag, for app states that keep a handle to it
+pub fn flag() -> Arc<AtomicBool> {
+    SAFE_MODE
+        .get_or_init(|| Arc::new(AtomicBool::new(false)))
+        .clone()
+}
+
+pub fn is_enabled() -> bool {
+    flag().load(Ordering::SeqCst)
+}
+
+pub fn set_enabled(enabled: bool) {
+    flag().store(enabled, Ordering::SeqCst);
+}
+
+fn check(enabled: bool, action: &str) -> Result<(), String> {
+    if enabled {
+        Err(format!(
+            "{}: {} is disabled while safe mode is on",
+            SAFE_MODE_BLOCKED, action
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Err(SafeModeBlocked) when safe mode is on; call before touching terminal folders
+pub fn ensure_writable(action: &str) -> Result<(), String> {
+    check(is_enabled(), action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_error_carries_prefix() {
+        assert!(check(false, "Exporting ACTIVE.set").is_ok());
+        let err = check(true, "Exporting ACTIVE.set").unwrap_err();
+        assert!(
+            err.starts_with("SafeModeBlocked: Exporting ACTIVE.set"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// ============================================
+// SETFILE KEY NAMING
+// ============================================
+//
+// How engines, logics and groups are spelled inside setfile keys
+// (gInput_Grid_BR3, gInput_StartBRepower...). Both apps write setfiles the
+// same EA reads, so these must never differ between them.
+
+fn engine_prefix(engine_id: &str) -> &'static str {
+    match engine_id {
+        "B" => "B",
+        "C" => "C",
+        _ => "",
+    }
+}
+
+/// "Repower" -> "R"; unknown logics fall back to Power
+fn logic_char(logic_name: &str) -> &'static str {
+    match logic_name.to_uppercase().as_str() {
+        "POWER" => "P",
+        "REPOWER" => "R",
+        "SCALPER" | "SCALP" => "S",
+        "STOPPER" => "ST",
+        "STO" => "STO",
+        "SCA" => "SCA",
+        "RPO" => "RPO",
+        _ => "P",
+    }
+}
+
+/// Per-group key suffix: ("B", 3, "Repower") -> "BR3"
+pub fn get_logic_suffix(engine_id: &str, group: u8, logic_name: &str) -> String {
+    format!(
+        "{}{}{}",
+        engine_prefix(engine_id),
+        logic_char(logic_name),
+        group
+    )
+}
+
+/// Group-less suffix: ("B", "Repower") -> "BR"
+pub fn get_logic_short(engine_id: &str, logic_name: &str) -> String {
+    format!("{}{}", engine_prefix(engine_id), logic_char(logic_name))
+}
+
+/// Logic code used in logic ids ("A_R1"); names are matched as spelled
+pub fn get_logic_code(logic_name: &str) -> &'static str {
+    match logic_name {
+        "Power" => "P",
+        "Repower" => "R",
+        "Scalp" | "Scalper" => "S",
+        "Stopper" => "ST",
+        "STO" => "STO",
+        "SCA" => "SCA",
+        "RPO" => "RPO",
+        _ => "P",
+    }
+}
+
+/// Name used in engine-wide keys: ("B", "Scalper") -> "BScalp"
+pub fn get_logic_global_key(engine_id: &str, logic_name: &str) -> String {
+    let name = match logic_name.to_uppercase().as_str() {
+        "POWER" => "Power",
+        "REPOWER" => "Repower",
+        "SCALPER" | "SCALP" => "Scalp",
+        "STOPPER" => "Stopper",
+        "STO" => "STO",
+        "SCA" => "SCA",
+        "RPO" => "RPO",
+        _ => "Power",
+    };
+
+    format!("{}{}", engine_prefix(engine_id), name)
+}
+
+/// Start-level key of a logic ("StartBRepower"); Power has none
+pub fn get_logic_start_key(engine_id: &str, logic_name: &str) -> Option<String> {
+    let suffix = match logic_name.to_uppercase().as_str() {
+        "REPOWER" => "Repower",
+        "SCALPER" | "SCALP" => "Scalp",
+        "STOPPER" => "Stopper",
+        "STO" => "STO",
+        "SCA" => "SCA",
+        "RPO" => "RPO",
+        _ => return None,
+    };
+
+    Some(format!("Start{}{}", engine_prefix(engine_id), suffix))
+}
+
+/// RPO closes its own engine's logics; engine C's RPO closes all three engines
+pub fn default_close_targets(engine_id: &str, logic_name: &str) -> String {
+    if logic_name.to_uppercase() != "RPO" {
+        return String::new();
+    }
+
+    match engine_id {
+        "A" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO".to_string(),
+        "B" => "B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO".to_string(),
+        "C" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO,B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO,C:Power,C:Repower,C:Scalp,C:Stopper,C:STO,C:SCA,C:RPO".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// EA code of a trigger action; numbers pass through. None for names it
+/// doesn't know: the apps' EA builds fall back differently, so each app
+/// picks its own code for those
+pub fn trigger_action_to_int(action: &str) -> Option<i32> {
+    let s = action.trim();
+    if let Ok(n) = s.parse::<i32>() {
+        return Some(n);
+    }
+    Some(match s {
+        "TriggerAction_None" => 0,
+        "TriggerAction_StopEA" => 1,
+        "TriggerAction_StopEA_KeepTrades" => 2,
+        "TriggerAction_CloseAll" => 3,
+        "TriggerAction_KeepEA_CloseTrades" => 4,
+        "TriggerAction_StopEA_CloseTrades" => 5,
+        "TriggerAction_PauseEA_CloseTrades" => 6,
+        "TriggerAction_PauseEA_KeepTrades" => 7,
+        "Action_Default" => 2,
+        "Action_CloseAll" => 3,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_naming() {
+        assert_eq!(get_logic_suffix("B", 3, "Repower"), "BR3");
+        assert_eq!(get_logic_suffix("A", 12, "scalper"), "S12");
+        assert_eq!(get_logic_short("C", "Stopper"), "CST");
+        assert_eq!(get_logic_global_key("B", "Scalper"), "BScalp");
+        assert_eq!(
+            get_logic_start_key("B", "Repower").as_deref(),
+            Some("StartBRepower")
+        );
+        assert_eq!(get_logic_start_key("A", "Power"), None);
+        assert!(default_close_targets("C", "RPO").starts_with("A:Power"));
+        assert_eq!(default_close_targets("A", "Power"), "");
+        assert_eq!(
+            trigger_action_to_int("TriggerAction_PauseEA_KeepTrades"),
+            Some(7)
+        );
+        assert_eq!(trigger_action_to_int(" 4 "), Some(4));
+        assert_eq!(trigger_action_to_int("Action_Default"), Some(2));
+        assert_eq!(trigger_action_to_int("TriggerAction_Sometimes"), None);
+        assert_eq!(trigger_action_to_int(""), None);
+    }
+}
@@ -0,0 +1,421 @@
+// ============================================
+// A/B DEPLOYMENTS
+// ============================================
+//
+// Runs two presets side by side on two terminals and keeps score. Each arm
+// is exported into its own terminal's MQL Files folder (Common Files is
+// shared by every terminal on the machine) with magic numbers that can't
+// collide with the other arm, and the assignment is recorded so a later
+// terminal history export can be split back into per-arm performance.
+
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::mt_bridge::{
+    atomic_write, import_json_file, import_set_file, write_massive_v19_setfile, MTConfig,
+};
+use crate::terminal_discovery::{discover_terminals, TerminalProfile};
+use crate::trade_history::{
+    build_equity_curve, load_history_file, max_drawdown, preset_magic_numbers, HistoryTrade,
+};
+
+const AB_TESTS_FILE: &str = "ab_tests.json";
+/// Magics a preset derives from one base: 3 engines x 7 logics x buy/sell
+const MAGIC_BLOCK: i32 = 42;
+const DEFAULT_MAGIC_OFFSET: i32 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbArm {
+    /// "A" or "B"
+    pub label: String,
+    pub preset: String,
+    pub terminal_id: String,
+    pub platform: String,
+    /// Base magics the arm trades under; each covers MAGIC_BLOCK numbers
+    pub magic_numbers: Vec<i32>,
+    pub set_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbTest {
+    pub id: String,
+    pub name: String,
+    /// Local time; history is in server time, so the edges are approximate
+    pub started_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub arms: Vec<AbArm>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArmPerformance {
+    pub label: String,
+    pub preset: String,
+    pub trades: usize,
+    pub net_profit: f64,
+    pub win_rate: f64,
+    /// Gross profit over gross loss; None without losing trades
+    pub profit_factor: Option<f64>,
+    pub max_drawdown: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbTestResults {
+    pub test: AbTest,
+    pub finished: bool,
+    pub arms: Vec<ArmPerformance>,
+    /// Arm with the higher net profit, once both have traded
+    pub leader: Option<String>,
+}
+
+fn ab_tests_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(AB_TESTS_FILE)
+}
+
+fn load_ab_tests() -> Vec<AbTest> {
+    std::fs::read_to_string(ab_tests_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_ab_tests(tests: &[AbTest]) -> Result<(), String> {
+    let path = ab_tests_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(tests)
+        .map_err(|e| format!("Failed to serialize A/B tests: {}", e))?;
+    atomic_write(&path, json)
+}
+
+fn in_block(magic: i32, bases: &[i32]) -> bool {
+    bases
+        .iter()
+        .any(|base| (*base..*base + MAGIC_BLOCK).contains(&magic))
+}
+
+fn blocks_overlap(a: &[i32], b: &[i32]) -> bool {
+    a.iter()
+        .any(|x| b.iter().any(|y| (x - y).abs() < MAGIC_BLOCK))
+}
+
+/// Shift `config`'s magics by multiples of `offset` until they can't collide
+/// with `taken`; returns the shift applied
+pub fn separate_magics(config: &mut MTConfig, taken: &[i32], offset: i32) -> Result<i32, String> {
+    if offset.abs() < MAGIC_BLOCK {
+        return Err(format!("Magic offset must be at least {}", MAGIC_BLOCK));
+    }
+    let mut shift = 0;
+    for _ in 0..100 {
+        let shifted: Vec<i32> = preset_magic_numbers(config)
+            .iter()
+            .map(|magic| magic + shift)
+            .collect();
+        if !blocks_overlap(&shifted, taken) {
+            let general = &mut config.general;
+            for magic in [
+                &mut general.magic_number,
+                &mut general.magic_number_buy,
+                &mut general.magic_number_sell,
+            ] {
+                if *magic != 0 {
+                    *magic += shift;
+                }
+            }
+            return Ok(shift);
+        }
+        shift += offset;
+    }
+    Err("Couldn't find free magic numbers for arm B".to_string())
+}
+
+async fn load_preset(path: &str) -> Result<MTConfig, String> {
+    if path.to_lowercase().ends_with(".json") {
+        import_json_file(path.to_string()).await
+    } else {
+        import_set_file(path.to_string(), None, None).await
+    }
+}
+
+/// Write the arm's set into the terminal's own MQL Files folder and point
+/// the EA at it instead of Common Files. Nothing is mirrored into Common
+/// Files, so neither arm touches the shared ACTIVE.set.
+fn export_arm(
+    mut config: MTConfig,
+    terminal: &TerminalProfile,
+    file_name: &str,
+) -> Result<String, String> {
+    let mql = if terminal.platform.eq_ignore_ascii_case("MT4") {
+        "MQL4"
+    } else {
+        "MQL5"
+    };
+    let files_dir = PathBuf::from(&terminal.data_path).join(mql).join("Files");
+    std::fs::create_dir_all(&files_dir)
+        .map_err(|e| format!("Failed to create {}: {}", files_dir.display(), e))?;
+    config.general.config_file_name = file_name.to_string();
+    config.general.config_file_is_common = false;
+    let path = files_dir.join(file_name);
+    write_massive_v19_setfile(config, &path, &terminal.platform)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Export `preset_a` and `preset_b` to the two terminal `profiles` with
+/// disjoint magic numbers and record the test for `duration_days`
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn deploy_ab_test(
+    preset_a: String,
+    preset_b: String,
+    profiles: Vec<String>,
+    duration_days: u32,
+    name: Option<String>,
+    magic_offset: Option<i32>,
+) -> Result<AbTest, String> {
+    crate::safe_mode::ensure_writable("Deploying an A/B test")?;
+    crate::licensing::ensure_licensed("Deploying an A/B test")?;
+    let [profile_a, profile_b] = profiles.as_slice() else {
+        return Err("An A/B test needs exactly two terminal profiles".to_string());
+    };
+    if profile_a == profile_b {
+        return Err("Both arms can't run on the same terminal".to_string());
+    }
+    if duration_days == 0 {
+        return Err("Duration must be at least one day".to_string());
+    }
+    let terminals = discover_terminals();
+    let terminal = |id: &str| {
+        terminals
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Terminal profile '{}' not found", id))
+    };
+    let (terminal_a, terminal_b) = (terminal(profile_a.as_str())?, terminal(profile_b.as_str())?);
+
+    let config_a = load_preset(&preset_a).await?;
+    let mut config_b = load_preset(&preset_b).await?;
+    let magics_a = preset_magic_numbers(&config_a);
+    separate_magics(
+        &mut config_b,
+        &magics_a,
+        magic_offset.unwrap_or(DEFAULT_MAGIC_OFFSET),
+    )?;
+    let magics_b = preset_magic_numbers(&config_b);
+
+    let started_at = Local::now().naive_local();
+    let id = format!("ab_{}", started_at.format("%Y%m%d_%H%M%S"));
+    let mut arms = Vec::new();
+    for (label, preset, config, terminal, magics) in [
+        ("A", preset_a, config_a, terminal_a, magics_a),
+        ("B", preset_b, config_b, terminal_b, magics_b),
+    ] {
+        let set_path = export_arm(config, &terminal, &format!("DAAVFX_{}_{}.set", id, label))?;
+        arms.push(AbArm {
+            label: label.to_string(),
+            preset,
+            terminal_id: terminal.id.clone(),
+            platform: terminal.platform.clone(),
+            magic_numbers: magics,
+            set_path,
+        });
+    }
+
+    let test = AbTest {
+        name: name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| id.clone()),
+        id,
+        started_at,
+        ends_at: started_at + Duration::days(duration_days as i64),
+        arms,
+    };
+    let mut tests = load_ab_tests();
+    tests.push(test.clone());
+    save_ab_tests(&tests)?;
+    Ok(test)
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_ab_tests() -> Result<Vec<AbTest>, String> {
+    Ok(load_ab_tests())
+}
+
+/// Split `trades` into per-arm performance over the test period
+pub fn arm_performance(test: &AbTest, trades: &[HistoryTrade]) -> Vec<ArmPerformance> {
+    test.arms
+        .iter()
+        .map(|arm| {
+            let selected: Vec<&HistoryTrade> = trades
+                .iter()
+                .filter(|t| in_block(t.magic, &arm.magic_numbers))
+                .filter(|t| t.close_time >= test.started_at && t.close_time <= test.ends_at)
+                .collect();
+            let wins = selected.iter().filter(|t| t.profit > 0.0).count();
+            let gross_profit: f64 = selected.iter().map(|t| t.profit.max(0.0)).sum();
+            let gross_loss: f64 = selected.iter().map(|t| (-t.profit).max(0.0)).sum();
+            ArmPerformance {
+                label: arm.label.clone(),
+                preset: arm.preset.clone(),
+                trades: selected.len(),
+                net_profit: gross_profit - gross_loss,
+                win_rate: if selected.is_empty() {
+                    0.0
+                } else {
+                    wins as f64 / selected.len() as f64 * 100.0
+                },
+                profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+                max_drawdown: max_drawdown(&build_equity_curve(&selected)),
+            }
+        })
+        .collect()
+}
+
+/// Per-arm results of a recorded test from one or more terminal history
+/// exports (one per terminal, or a merged one)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_ab_test_results(
+    test_id: String,
+    history_paths: Vec<String>,
+) -> Result<AbTestResults, String> {
+    let test = load_ab_tests()
+        .into_iter()
+        .find(|t| t.id == test_id)
+        .ok_or_else(|| format!("A/B test '{}' not found", test_id))?;
+    let mut trades = Vec::new();
+    for path in &history_paths {
+        trades.extend(load_history_file(path)?.trades);
+    }
+    // The same trade can appear in more than one export
+    trades.sort_by(|a, b| a.close_time.cmp(&b.close_time));
+    trades.dedup_by(|a, b| a.ticket.is_some() && a.ticket == b.ticket && a.magic == b.magic);
+
+    let arms = arm_performance(&test, &trades);
+    let leader = arms
+        .iter()
+        .all(|arm| arm.trades > 0)
+        .then(|| {
+            arms.iter()
+                .max_by(|a, b| a.net_profit.total_cmp(&b.net_profit))
+        })
+        .flatten()
+        .map(|arm| arm.label.clone());
+    Ok(AbTestResults {
+        finished: Local::now().naive_local() >= test.ends_at,
+        test,
+        arms,
+        leader,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn trade(magic: i32, close: &str, profit: f64) -> HistoryTrade {
+        HistoryTrade {
+            ticket: None,
+            magic,
+            symbol: "XAUUSD".to_string(),
+            open_time: None,
+            close_time: time(close),
+            volume: 0.1,
+            profit,
+        }
+    }
+
+    #[test]
+    fn test_separate_magics_offsets_colliding_arm() {
+        let config_a = create_full_v19_config();
+        let mut config_b = config_a.clone();
+        let magics_a = preset_magic_numbers(&config_a);
+
+        let shift = separate_magics(&mut config_b, &magics_a, DEFAULT_MAGIC_OFFSET).unwrap();
+        assert!(shift >= DEFAULT_MAGIC_OFFSET);
+        assert!(!blocks_overlap(&magics_a, &preset_magic_numbers(&config_b)));
+
+        // Already disjoint: untouched
+        let before = config_b.general.magic_number;
+        assert_eq!(
+            separate_magics(&mut config_b, &magics_a, DEFAULT_MAGIC_OFFSET),
+            Ok(0)
+        );
+        assert_eq!(config_b.general.magic_number, before);
+    }
+
+    #[test]
+    fn test_export_arm_writes_into_terminal_files() {
+        let root = std::env::temp_dir().join(format!("daavfx_ab_arm_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let terminal = |id: &str| TerminalProfile {
+            id: id.to_string(),
+            platform: "MT5".to_string(),
+            broker_name: String::new(),
+            data_path: root.join(id).to_string_lossy().to_string(),
+            common_files_path: String::new(),
+            profiles_path: String::new(),
+            source: crate::terminal_discovery::TerminalSource::Native,
+            wine_prefix: None,
+            last_modified: None,
+        };
+
+        let path_a = export_arm(create_full_v19_config(), &terminal("A"), "AB_A.set").unwrap();
+        let path_b = export_arm(create_full_v19_config(), &terminal("B"), "AB_B.set").unwrap();
+        assert_eq!(
+            PathBuf::from(&path_a),
+            root.join("A").join("MQL5").join("Files").join("AB_A.set")
+        );
+        let content = std::fs::read_to_string(&path_b).unwrap();
+        assert!(content.contains("gInput_1_AP_Buy_InitialLot="));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_arm_performance_splits_by_magic_block() {
+        let arm = |label: &str, magic: i32| AbArm {
+            label: label.to_string(),
+            preset: format!("{}.set", label),
+            terminal_id: label.to_string(),
+            platform: "MT5".to_string(),
+            magic_numbers: vec![magic],
+            set_path: String::new(),
+        };
+        let test = AbTest {
+            id: "ab_test".to_string(),
+            name: "ab".to_string(),
+            started_at: time("2026-03-01 00:00"),
+            ends_at: time("2026-03-08 00:00"),
+            arms: vec![arm("A", 777), arm("B", 1777)],
+        };
+        let trades = vec![
+            trade(777, "2026-03-02 10:00", 30.0),
+            trade(790, "2026-03-03 10:00", -10.0),
+            trade(1800, "2026-03-02 10:00", 5.0),
+            // Outside the period / other EA
+            trade(777, "2026-03-09 10:00", 100.0),
+            trade(5000, "2026-03-02 10:00", 100.0),
+        ];
+
+        let arms = arm_performance(&test, &trades);
+        assert_eq!(arms[0].trades, 2);
+        assert_eq!(arms[0].net_profit, 20.0);
+        assert_eq!(arms[0].win_rate, 50.0);
+        assert_eq!(arms[0].profit_factor, Some(3.0));
+        assert_eq!(arms[1].trades, 1);
+        assert_eq!(arms[1].profit_factor, None);
+    }
+}
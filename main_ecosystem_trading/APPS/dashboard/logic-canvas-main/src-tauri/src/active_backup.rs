@@ -0,0 +1,131 @@
+// ============================================
+// ACTIVE.SET HOT BACKUPS
+// ============================================
+//
+// Exports overwrite the set file a live EA is running from. Before each
+// overwrite the current file is rotated into <name>.bak1..bakN (bak1 is the
+// newest), so an accidental export can be rolled back with
+// restore_previous_active_set.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mt_bridge::get_mt_common_files_dir;
+use crate::naming;
+
+/// Backups kept per file
+pub const ACTIVE_SET_BACKUPS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSetRestore {
+    pub path: String,
+    /// SHA-256 of the file that was replaced, if there was one
+    pub replaced_hash: Option<String>,
+    pub restored_hash: String,
+    /// Backups left after this one was taken off the stack
+    pub remaining_backups: usize,
+}
+
+pub(crate) fn file_hash(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+pub(crate) use daavfx_core::fs::backup_path;
+
+/// Shift <path>.bak1..bak{keep-1} up by one and copy `path` into .bak1.
+/// Returns the hash of the file that was backed up, or None when `path` doesn't exist yet.
+pub(crate) fn rotate_backups(path: &Path, keep: usize) -> Result<Option<String>, String> {
+    if !daavfx_core::fs::rotate_backups(path, keep)? {
+        return Ok(None);
+    }
+    file_hash(path).map(Some)
+}
+
+/// Put <path>.bak1 back in place and shift the older backups down
+pub(crate) fn restore_backup(path: &Path, keep: usize) -> Result<ActiveSetRestore, String> {
+    let newest = backup_path(path, 1);
+    if !newest.is_file() {
+        return Err(format!("No backup of {} to restore", path.display()));
+    }
+    let replaced_hash = if path.is_file() {
+        Some(file_hash(path)?)
+    } else {
+        None
+    };
+    let restored_hash = file_hash(&newest)?;
+    fs::rename(&newest, path)
+        .map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+
+    let mut remaining_backups = 0;
+    for index in 2..=keep {
+        let from = backup_path(path, index);
+        if !from.exists() {
+            break;
+        }
+        fs::rename(&from, backup_path(path, index - 1))
+            .map_err(|e| format!("Failed to rotate backup {}: {}", from.display(), e))?;
+        remaining_backups += 1;
+    }
+
+    Ok(ActiveSetRestore {
+        path: path.to_string_lossy().to_string(),
+        replaced_hash,
+        restored_hash,
+        remaining_backups,
+    })
+}
+
+/// The active set file in Common Files (naming policy name unless `file_name` is given)
+pub(crate) fn active_set_path(file_name: Option<String>) -> Result<PathBuf, String> {
+    let name = file_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| naming::current().active_set_name);
+    if name.contains(['/', '\\', ':']) || name.contains("..") {
+        return Err(format!("Invalid set file name '{}'", name));
+    }
+    Ok(get_mt_common_files_dir()?.join(name))
+}
+
+/// Roll the active set file back to the version before the last export
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn restore_previous_active_set(file_name: Option<String>) -> Result<ActiveSetRestore, String> {
+    crate::safe_mode::ensure_writable("Restoring ACTIVE.set")?;
+    restore_backup(&active_set_path(file_name)?, ACTIVE_SET_BACKUPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_and_restore_backups() {
+        let dir = std::env::temp_dir().join(format!("daavfx_active_bak_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ACTIVE.set");
+        assert_eq!(rotate_backups(&path, 3).unwrap(), None);
+
+        for version in ["v1", "v2", "v3", "v4"] {
+            fs::write(&path, version).unwrap();
+            rotate_backups(&path, 3).unwrap();
+        }
+        // Re-exporting an unchanged file doesn't push another copy
+        rotate_backups(&path, 3).unwrap();
+        assert_eq!(fs::read_to_string(backup_path(&path, 1)).unwrap(), "v4");
+        assert_eq!(fs::read_to_string(backup_path(&path, 3)).unwrap(), "v2");
+        assert!(!backup_path(&path, 4).exists());
+
+        fs::write(&path, "oops").unwrap();
+        let restored = restore_backup(&path, 3).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v4");
+        assert_eq!(restored.remaining_backups, 2);
+        let oops_hash = hex::encode(Sha256::digest(b"oops"));
+        assert_eq!(restored.replaced_hash, Some(oops_hash));
+        assert_eq!(fs::read_to_string(backup_path(&path, 1)).unwrap(), "v3");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,215 @@
+// ============================================
+// FIELD ANNOTATIONS
+// ============================================
+//
+// Free-text notes on individual config fields ("grid widened for gold
+// volatility, 2024-05"), keyed by the same JSON pointer paths config patches
+// use. They ride along on MTConfig like tags and comments, are stored in the
+// vault JSON wrapper's metadata, and .set exports write each one as a
+// "; note:" line right above the key it describes. Notes whose field has no
+// key in the exported file go to the header so they are never lost.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use daavfx_core::setfile_keys::{get_logic_short, get_logic_suffix};
+
+use crate::mt_bridge::MTConfig;
+use crate::setfile_schema::logic_field_key;
+
+const NOTE_PREFIX: &str = "; note: ";
+
+/// One line, no surrounding whitespace; notes end up in "; ..." comment lines
+fn clean_note(note: &str) -> String {
+    note.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `path` must point at a single value of `config`, not a section or list
+fn validate_path(config: &MTConfig, path: &str) -> Result<(), String> {
+    if !path.starts_with('/') {
+        return Err(format!("Field path '{}' must start with '/'", path));
+    }
+    let root =
+        serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    match root.pointer(path) {
+        Some(Value::Object(_)) | Some(Value::Array(_)) => {
+            Err(format!("Field path '{}' is not a single field", path))
+        }
+        Some(_) => Ok(()),
+        None => Err(format!("Field path '{}' does not exist", path)),
+    }
+}
+
+/// Set (or with an empty note, clear) the note on `path`
+pub(crate) fn set_annotation(config: &mut MTConfig, path: &str, note: &str) -> Result<(), String> {
+    validate_path(config, path)?;
+    let note = clean_note(note);
+    let mut annotations = config.annotations.take().unwrap_or_default();
+    if note.is_empty() {
+        annotations.remove(path);
+    } else {
+        annotations.insert(path.to_string(), note);
+    }
+    config.annotations = Some(annotations).filter(|a| !a.is_empty());
+    Ok(())
+}
+
+/// Notes on `path` and every field below it; all notes when `path` is None
+pub(crate) fn annotations_under(config: &MTConfig, path: Option<&str>) -> BTreeMap<String, String> {
+    let prefix = path.map(|p| p.trim_end_matches('/')).unwrap_or("");
+    config
+        .annotations
+        .iter()
+        .flatten()
+        .filter(|(field, _)| {
+            prefix.is_empty()
+                || field.as_str() == prefix
+                || field
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .map(|(field, note)| (field.clone(), note.clone()))
+        .collect()
+}
+
+/// Flat .set key written for a field path, when the key schema knows the field
+fn setfile_key(config: &MTConfig, path: &str) -> Option<String> {
+    let tokens: Vec<&str> = path.strip_prefix('/')?.split('/').collect();
+    let ["engines", e, "groups", g, "logics", l, field] = tokens.as_slice() else {
+        return None;
+    };
+    let engine = config.engines.get(e.parse::<usize>().ok()?)?;
+    let group = engine.groups.get(g.parse::<usize>().ok()?)?;
+    let logic = group.logics.get(l.parse::<usize>().ok()?)?;
+    let suffix = get_logic_suffix(&engine.engine_id, group.group_number, &logic.logic_name);
+    let short = get_logic_short(&engine.engine_id, &logic.logic_name);
+    logic_field_key(field, group.group_number, &suffix, &short)
+}
+
+/// A config note resolved for export, before engines/groups are reordered
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SetNote {
+    pub path: String,
+    pub key: Option<String>,
+    pub note: String,
+}
+
+pub(crate) fn resolve_set_notes(config: &MTConfig) -> Vec<SetNote> {
+    annotations_under(config, None)
+        .into_iter()
+        .map(|(path, note)| SetNote {
+            key: setfile_key(config, &path),
+            path,
+            note,
+        })
+        .collect()
+}
+
+/// Put each note above its key's line; the rest follow the header comments
+pub(crate) fn insert_set_notes(lines: Vec<String>, notes: &[SetNote]) -> Vec<String> {
+    if notes.is_empty() {
+        return lines;
+    }
+    let mut placed = vec![false; notes.len()];
+    let mut body = Vec::with_capacity(lines.len() + notes.len());
+    for line in lines {
+        if let Some((key, _)) = line.split_once('=') {
+            for (i, note) in notes.iter().enumerate() {
+                if !placed[i] && note.key.as_deref() == Some(key) {
+                    body.push(format!("{}{}", NOTE_PREFIX, note.note));
+                    placed[i] = true;
+                }
+            }
+        }
+        body.push(line);
+    }
+
+    let header_len = body.iter().take_while(|line| line.starts_with(';')).count();
+    let unplaced = notes
+        .iter()
+        .zip(&placed)
+        .filter(|(_, placed)| !**placed)
+        .map(|(note, _)| format!("{}{}: {}", NOTE_PREFIX, note.path, note.note));
+    body.splice(header_len..header_len, unplaced);
+    body
+}
+
+/// Set the note on a config field (JSON pointer path); an empty note clears it
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_config_annotation(
+    mut config: MTConfig,
+    path: String,
+    note: Option<String>,
+) -> Result<MTConfig, String> {
+    set_annotation(&mut config, &path, note.as_deref().unwrap_or(""))?;
+    Ok(config)
+}
+
+/// Notes on `path` and the fields below it, or every note when `path` is omitted
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_config_annotations(
+    config: MTConfig,
+    path: Option<String>,
+) -> Result<BTreeMap<String, String>, String> {
+    Ok(annotations_under(&config, path.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    const GRID_PATH: &str = "/engines/0/groups/0/logics/0/grid";
+
+    #[test]
+    fn test_set_and_clear_annotation() {
+        let mut config = create_full_v19_config();
+        set_annotation(
+            &mut config,
+            GRID_PATH,
+            " grid widened for\ngold volatility ",
+        )
+        .unwrap();
+        assert_eq!(
+            annotations_under(&config, Some("/engines/0/groups/0")).get(GRID_PATH),
+            Some(&"grid widened for gold volatility".to_string())
+        );
+        assert!(annotations_under(&config, Some("/engines/0/groups/1")).is_empty());
+
+        assert!(set_annotation(&mut config, "/engines/0/groups/0", "x").is_err());
+        assert!(set_annotation(&mut config, "/general/no_such_field", "x").is_err());
+
+        set_annotation(&mut config, GRID_PATH, "").unwrap();
+        assert!(config.annotations.is_none());
+    }
+
+    #[test]
+    fn test_notes_sit_above_their_key() {
+        let mut config = create_full_v19_config();
+        set_annotation(&mut config, GRID_PATH, "wider for gold").unwrap();
+        set_annotation(&mut config, "/general/magic_number", "client A").unwrap();
+        let notes = resolve_set_notes(&config);
+        let key = notes
+            .iter()
+            .find_map(|n| n.key.clone())
+            .expect("grid resolves to a key");
+
+        let lines = vec![
+            "; DAAVFX Configuration Export".to_string(),
+            String::new(),
+            format!("{}=300.0", key),
+        ];
+        let lines = insert_set_notes(lines, &notes);
+        assert_eq!(
+            lines,
+            vec![
+                "; DAAVFX Configuration Export".to_string(),
+                "; note: /general/magic_number: client A".to_string(),
+                String::new(),
+                "; note: wider for gold".to_string(),
+                format!("{}=300.0", key),
+            ]
+        );
+    }
+}
@@ -0,0 +1,284 @@
+// ============================================
+// APPLICATION LOG
+// ============================================
+//
+// Where tracing output ends up besides the console: a daily rolling file
+// under the settings folder, and an in-memory ring of recent entries that
+// get_recent_logs serves so support can pull diagnostics from a user's
+// machine without a debugger. Instrumented commands run in a span; when it
+// closes the ring gets a "<command> finished" entry carrying the span's
+// fields and its duration.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::import_log::FieldCollector;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "daavfx";
+/// Days of log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+const RECENT_LOG_CAPACITY: usize = 2000;
+const DEFAULT_RECENT_COUNT: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Enclosing spans, outermost first, e.g. "export_set_file"
+    #[serde(default)]
+    pub span: Option<String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+static RECENT_LOGS: OnceLock<Mutex<VecDeque<LogEntry>>> = OnceLock::new();
+/// Keeps the file writer's worker thread alive for the life of the process
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+fn recent_logs() -> &'static Mutex<VecDeque<LogEntry>> {
+    RECENT_LOGS.get_or_init(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_CAPACITY)))
+}
+
+fn push(entry: LogEntry) {
+    if let Ok(mut logs) = recent_logs().lock() {
+        if logs.len() >= RECENT_LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(entry);
+    }
+}
+
+/// The newest `count` entries of any level, oldest first. Doesn't wait for
+/// the buffer lock, so it is safe to call from a panic hook.
+pub(crate) fn last_entries(count: usize) -> Vec<LogEntry> {
+    recent_logs()
+        .try_lock()
+        .map(|logs| {
+            let skip = logs.len().saturating_sub(count);
+            logs.iter().skip(skip).cloned().collect()
+        })
+        .unwrap_or_default()
+}
+
+fn log_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(LOG_DIR)
+}
+
+/// Rolling log files, newest first (file names end in the date)
+pub(crate) fn log_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(log_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// Writer for the rolling log file; None when the folder can't be used or
+/// the writer was already handed out
+pub(crate) fn file_writer() -> Option<NonBlocking> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir())
+        .ok()?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    FILE_GUARD.set(guard).ok()?;
+    Some(writer)
+}
+
+/// Entries at `min_level` or more severe, the newest `count` in chronological order
+fn select_entries(entries: &VecDeque<LogEntry>, min_level: Level, count: usize) -> Vec<LogEntry> {
+    let mut selected: Vec<LogEntry> = entries
+        .iter()
+        .rev()
+        .filter(|entry| {
+            Level::from_str(&entry.level)
+                .map(|level| level <= min_level)
+                .unwrap_or(true)
+        })
+        .take(count)
+        .cloned()
+        .collect();
+    selected.reverse();
+    selected
+}
+
+/// Start time and fields of an open span
+struct SpanTiming {
+    started: Instant,
+    fields: BTreeMap<String, String>,
+}
+
+/// Feeds the recent-log ring that get_recent_logs reads.
+pub struct RecentLogLayer;
+
+impl<S> Layer<S> for RecentLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut collector = FieldCollector::default();
+            attrs.record(&mut collector);
+            span.extensions_mut().insert(SpanTiming {
+                started: Instant::now(),
+                fields: collector.fields,
+            });
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                let mut collector = FieldCollector::default();
+                values.record(&mut collector);
+                timing.fields.extend(collector.fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let span = ctx.event_scope(event).map(|scope| {
+            scope
+                .from_root()
+                .map(|span| span.name())
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+        let mut collector = FieldCollector::default();
+        event.record(&mut collector);
+        let metadata = event.metadata();
+        push(LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: collector.message,
+            span,
+            fields: collector.fields,
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<SpanTiming>() else {
+            return;
+        };
+        let mut fields = timing.fields;
+        fields.insert(
+            "duration_ms".to_string(),
+            timing.started.elapsed().as_millis().to_string(),
+        );
+        let metadata = span.metadata();
+        push(LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: format!("{} finished", span.name()),
+            span: Some(span.name().to_string()),
+            fields,
+        });
+    }
+}
+
+/// Most recent log entries at `level` ("error", "warn", "info", "debug") or
+/// more severe; defaults to info and 200 entries
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_recent_logs(
+    level: Option<String>,
+    count: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = match level.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        Some(level) => {
+            Level::from_str(level).map_err(|_| format!("Unknown log level '{}'", level))?
+        }
+        None => Level::INFO,
+    };
+    let logs = recent_logs()
+        .lock()
+        .map_err(|_| "Log buffer is unavailable".to_string())?;
+    Ok(select_entries(
+        &logs,
+        min_level,
+        count.unwrap_or(DEFAULT_RECENT_COUNT),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tracing::instrument(skip_all, fields(file_path = %file_path))]
+    fn instrumented_export(file_path: &str) {
+        tracing::info!(keys = 12, "wrote setfile");
+        tracing::debug!("details");
+    }
+
+    #[test]
+    fn test_recent_logs_capture_events_and_span_duration() {
+        let subscriber =
+            tracing_subscriber::registry().with(RecentLogLayer.with_filter(LevelFilter::DEBUG));
+        tracing::subscriber::with_default(subscriber, || {
+            instrumented_export("C:/Common/Files/ACTIVE.set");
+        });
+
+        let logs = recent_logs().lock().unwrap().clone();
+        let info = select_entries(&logs, Level::INFO, 10);
+        let wrote = info
+            .iter()
+            .find(|entry| entry.message == "wrote setfile")
+            .expect("event recorded");
+        assert_eq!(wrote.span.as_deref(), Some("instrumented_export"));
+        assert_eq!(wrote.fields.get("keys").map(String::as_str), Some("12"));
+
+        let finished = info
+            .iter()
+            .find(|entry| entry.message == "instrumented_export finished")
+            .expect("span close recorded");
+        assert_eq!(
+            finished.fields.get("file_path").map(String::as_str),
+            Some("C:/Common/Files/ACTIVE.set")
+        );
+        assert!(finished.fields.contains_key("duration_ms"));
+
+        // Debug entries only show up when asked for
+        assert!(!info.iter().any(|entry| entry.message == "details"));
+        assert!(select_entries(&logs, Level::DEBUG, 10)
+            .iter()
+            .any(|entry| entry.message == "details"));
+    }
+}
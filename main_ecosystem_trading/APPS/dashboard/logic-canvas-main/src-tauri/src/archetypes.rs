@@ -0,0 +1,213 @@
+// ============================================
+// PRESET ARCHETYPES
+// ============================================
+//
+// Starting points for new users. Each archetype is the full v19 layout
+// (3 engines x 15 groups x 7 logics, buy and sell rows) with everything
+// switched off except a curated set of logics, so exports keep every input
+// while the editor opens on something that trades sensibly.
+
+use daavfx_core::config_enums::TradingMode;
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{create_full_v19_config, LogicConfig, MTConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchetypeInfo {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+}
+
+const ARCHETYPES: [(&str, &str, &str); 3] = [
+    (
+        "conservative",
+        "Conservative single engine",
+        "Engine A Power on group 1, small lots, wide grid and a 20% equity stop",
+    ),
+    (
+        "aggressive_hedge",
+        "Aggressive 3-engine hedge",
+        "Power and Repower on groups 1-3 of every engine; engines B and C hedge Engine A",
+    ),
+    (
+        "scalper",
+        "Scalper only",
+        "Engine A Scalper on groups 1-2 with a tight grid, flat lots and a spread filter",
+    ),
+];
+
+fn switch_everything_off(config: &mut MTConfig) {
+    for engine in &mut config.engines {
+        for group in &mut engine.groups {
+            group.enabled = false;
+            for logic in &mut group.logics {
+                logic.enabled = false;
+            }
+        }
+    }
+}
+
+/// Enable `logics` on `groups` of `engine_id` and tune them with `tune`
+fn enable_logics(
+    config: &mut MTConfig,
+    engine_id: &str,
+    groups: std::ops::RangeInclusive<u8>,
+    logics: &[&str],
+    tune: impl Fn(&mut LogicConfig),
+) {
+    let Some(engine) = config.engines.iter_mut().find(|e| e.engine_id == engine_id) else {
+        return;
+    };
+    for group in engine
+        .groups
+        .iter_mut()
+        .filter(|g| groups.contains(&g.group_number))
+    {
+        group.enabled = true;
+        for logic in &mut group.logics {
+            if logics
+                .iter()
+                .any(|name| logic.logic_name.eq_ignore_ascii_case(name))
+            {
+                logic.enabled = true;
+                tune(logic);
+            }
+        }
+    }
+}
+
+fn conservative(config: &mut MTConfig) {
+    config.engines[0].max_power_orders = 6;
+    enable_logics(config, "A", 1..=1, &["POWER"], |logic| {
+        logic.initial_lot = 0.01;
+        logic.multiplier = 1.2;
+        logic.grid = 500.0;
+        logic.trail_value = 3000.0;
+    });
+    let risk = &mut config.general.risk_management;
+    risk.enabled = true;
+    risk.equity_stop_enabled = true;
+    risk.equity_stop_value = 20.0;
+}
+
+fn aggressive_hedge(config: &mut MTConfig) {
+    for engine_id in ["A", "B", "C"] {
+        enable_logics(config, engine_id, 1..=3, &["POWER", "REPOWER"], |logic| {
+            logic.initial_lot = 0.02;
+            logic.multiplier = 1.5;
+            logic.grid = 250.0;
+            logic.trail_value = 2000.0;
+            // Engine A Power always trades counter trend; the others hedge it
+            if engine_id != "A" && logic.logic_name.eq_ignore_ascii_case("POWER") {
+                logic.trading_mode = Some(TradingMode::Hedge);
+                logic.hedge_enabled = true;
+                logic.hedge_reference = "Logic_Power".to_string();
+                logic.hedge_scale = 50.0;
+            }
+        });
+    }
+    let risk = &mut config.general.risk_management;
+    risk.enabled = true;
+    risk.drawdown_stop_enabled = true;
+    risk.max_drawdown_percent = 40.0;
+}
+
+fn scalper(config: &mut MTConfig) {
+    enable_logics(config, "A", 1..=2, &["SCALPER"], |logic| {
+        logic.initial_lot = 0.01;
+        logic.multiplier = 1.0;
+        logic.grid = 150.0;
+        logic.trail_value = 800.0;
+        logic.trail_step = 400.0;
+    });
+    let risk = &mut config.general.risk_management;
+    risk.enabled = true;
+    risk.spread_filter_enabled = true;
+    risk.max_spread_points = 20.0;
+}
+
+pub fn config_from_archetype(name: &str) -> Result<MTConfig, String> {
+    let key = name.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+    let (id, title, _) = ARCHETYPES
+        .iter()
+        .find(|(id, title, _)| *id == key || title.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("Unknown archetype '{}'", name))?;
+
+    let mut config = create_full_v19_config();
+    switch_everything_off(&mut config);
+    match *id {
+        "conservative" => conservative(&mut config),
+        "aggressive_hedge" => aggressive_hedge(&mut config),
+        _ => scalper(&mut config),
+    }
+    config.current_set_name = Some(title.to_string());
+    config.tags = Some(vec!["archetype".to_string(), id.to_string()]);
+    Ok(config)
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_config_archetypes() -> Result<Vec<ArchetypeInfo>, String> {
+    Ok(ARCHETYPES
+        .iter()
+        .map(|(name, title, description)| ArchetypeInfo {
+            name: name.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+        })
+        .collect())
+}
+
+/// Full config for an archetype, by name ("scalper") or title ("Scalper only")
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn create_config_from_archetype(name: String) -> Result<MTConfig, String> {
+    config_from_archetype(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_logics(config: &MTConfig) -> Vec<(String, u8, String)> {
+        let mut found = Vec::new();
+        for engine in &config.engines {
+            for group in engine.groups.iter().filter(|g| g.enabled) {
+                for logic in group.logics.iter().filter(|l| l.enabled) {
+                    found.push((
+                        engine.engine_id.clone(),
+                        group.group_number,
+                        logic.logic_name.clone(),
+                    ));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_archetypes_keep_full_layout_and_curate_logics() {
+        for info in list_config_archetypes().unwrap() {
+            let config = config_from_archetype(&info.title).unwrap();
+            assert_eq!(config.engines.len(), 3);
+            assert!(config.engines.iter().all(|e| e.groups.len() == 15));
+            assert!(!enabled_logics(&config).is_empty(), "{}", info.name);
+        }
+
+        let scalper = config_from_archetype("Scalper only").unwrap();
+        assert!(enabled_logics(&scalper)
+            .iter()
+            .all(|(engine, group, logic)| engine == "A" && *group <= 2 && logic == "SCALPER"));
+
+        let hedge = config_from_archetype("aggressive-hedge").unwrap();
+        let engine_b_power = hedge.engines[1].groups[0]
+            .logics
+            .iter()
+            .find(|l| l.logic_name == "POWER")
+            .unwrap();
+        assert!(engine_b_power.hedge_enabled);
+        assert!(!hedge.engines[0].groups[0].logics[0].hedge_enabled);
+
+        assert!(config_from_archetype("yolo").is_err());
+    }
+}
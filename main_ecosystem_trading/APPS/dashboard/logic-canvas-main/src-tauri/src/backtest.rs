@@ -0,0 +1,500 @@
+// ============================================
+// BACKTEST ORCHESTRATION (MT5 Strategy Tester)
+// ============================================
+//
+// One-click backtesting: export the config as ACTIVE.set into the terminal's
+// tester profile folder, write a tester .ini, launch terminal64.exe with
+// /config:<ini> (ShutdownTerminal=1 so it exits when done) and return the
+// report the tester produced.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::mt_bridge::{
+    atomic_write, get_terminal_root_path, sanitize_and_validate_path, write_massive_v19_setfile,
+    MTConfig,
+};
+use crate::naming;
+
+const DEFAULT_EXPERT: &str = "DAAVFX.ex5";
+const DEFAULT_TIMEOUT_SECS: u64 = 3600;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+const VALID_TIMEFRAMES: [&str; 21] = [
+    "M1", "M2", "M3", "M4", "M5", "M6", "M10", "M12", "M15", "M20", "M30", "H1", "H2", "H3", "H4",
+    "H6", "H8", "H12", "D1", "W1", "MN1",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestRequest {
+    pub config: MTConfig,
+    pub symbol: String,
+    pub timeframe: String,
+    /// YYYY.MM.DD or YYYY-MM-DD
+    pub from_date: String,
+    pub to_date: String,
+    /// Path to terminal64.exe, or the folder containing it
+    pub terminal_path: String,
+    /// Terminal data folder (the one holding MQL5/). Resolved from origin.txt when omitted.
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// Expert path relative to MQL5/Experts
+    #[serde(default)]
+    pub expert: Option<String>,
+    /// 0 every tick, 1 1-minute OHLC, 2 open prices, 4 real ticks
+    #[serde(default)]
+    pub model: Option<u8>,
+    #[serde(default)]
+    pub deposit: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub leverage: Option<u32>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestResult {
+    pub report_path: String,
+    pub ini_path: String,
+    pub set_path: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+/// Normalise a tester date to MT5's YYYY.MM.DD
+pub(crate) fn normalize_tester_date(value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    chrono::NaiveDate::parse_from_str(trimmed, "%Y.%m.%d")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(trimmed, "%Y-%m-%d"))
+        .map(|d| d.format("%Y.%m.%d").to_string())
+        .map_err(|_| format!("Invalid tester date '{}': expected YYYY.MM.DD", value))
+}
+
+fn normalize_timeframe(value: &str) -> Result<String, String> {
+    let upper = value.trim().to_uppercase();
+    if VALID_TIMEFRAMES.contains(&upper.as_str()) {
+        Ok(upper)
+    } else {
+        Err(format!("Invalid timeframe '{}'", value))
+    }
+}
+
+/// Report name (relative to the terminal data folder, no extension)
+fn report_stem(request: &BacktestRequest) -> String {
+    let safe_symbol: String = request
+        .symbol
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    format!(
+        "daavfx_bt_{}_{}_{}",
+        safe_symbol,
+        request.timeframe.trim().to_uppercase(),
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    )
+}
+
+/// Render the [Tester] section MT5 reads from /config:<ini>
+pub fn render_tester_ini(request: &BacktestRequest, report: &str) -> Result<String, String> {
+    if request.symbol.trim().is_empty() {
+        return Err("Symbol is required".to_string());
+    }
+    let timeframe = normalize_timeframe(&request.timeframe)?;
+    let from_date = normalize_tester_date(&request.from_date)?;
+    let to_date = normalize_tester_date(&request.to_date)?;
+    if from_date > to_date {
+        return Err(format!(
+            "FromDate {} is after ToDate {}",
+            from_date, to_date
+        ));
+    }
+    let model = request.model.unwrap_or(1);
+    if !matches!(model, 0 | 1 | 2 | 4) {
+        return Err(format!("Unsupported tester model {}", model));
+    }
+
+    let lines = [
+        "[Tester]".to_string(),
+        format!(
+            "Expert={}",
+            request.expert.as_deref().unwrap_or(DEFAULT_EXPERT)
+        ),
+        format!("ExpertParameters={}", naming::current().active_set_name),
+        format!("Symbol={}", request.symbol.trim()),
+        format!("Period={}", timeframe),
+        format!("Model={}", model),
+        "Optimization=0".to_string(),
+        format!("FromDate={}", from_date),
+        format!("ToDate={}", to_date),
+        "ForwardMode=0".to_string(),
+        format!("Deposit={}", request.deposit.unwrap_or(10000.0)),
+        format!("Currency={}", request.currency.as_deref().unwrap_or("USD")),
+        format!("Leverage={}", request.leverage.unwrap_or(100)),
+        format!("Report={}", report),
+        "ReplaceReport=1".to_string(),
+        "ShutdownTerminal=1".to_string(),
+        "Visual=0".to_string(),
+    ];
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+pub(crate) fn resolve_terminal_exe(terminal_path: &str) -> Result<PathBuf, String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(terminal_path))?;
+    let exe = if path.is_dir() {
+        path.join("terminal64.exe")
+    } else {
+        path
+    };
+    if !exe.is_file() {
+        return Err(format!("terminal64.exe not found at {}", exe.display()));
+    }
+    Ok(exe)
+}
+
+/// MT5 stores each install's data under %APPDATA%\MetaQuotes\Terminal\<hash>, with
+/// origin.txt (UTF-16) naming the install folder. Portable installs keep MQL5/ next
+/// to the executable.
+pub(crate) fn resolve_data_dir(
+    terminal_exe: &Path,
+    data_dir: Option<&str>,
+) -> Result<PathBuf, String> {
+    if let Some(dir) = data_dir.filter(|d| !d.trim().is_empty()) {
+        return sanitize_and_validate_path(&PathBuf::from(dir));
+    }
+
+    let install_dir = terminal_exe
+        .parent()
+        .ok_or_else(|| "Terminal path has no parent folder".to_string())?;
+    let install_norm = install_dir
+        .to_string_lossy()
+        .trim_end_matches('\\')
+        .to_lowercase();
+
+    if let Ok(root) = get_terminal_root_path() {
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let origin = entry.path().join("origin.txt");
+                if let Ok(bytes) = fs::read(&origin) {
+                    let text = decode_text(&bytes);
+                    if text.trim().trim_end_matches('\\').to_lowercase() == install_norm {
+                        return Ok(entry.path());
+                    }
+                }
+            }
+        }
+    }
+
+    if install_dir.join("MQL5").is_dir() {
+        return Ok(install_dir.to_path_buf());
+    }
+    Err(format!(
+        "Could not find the data folder for {}; set dataDir explicitly",
+        terminal_exe.display()
+    ))
+}
+
+/// Text from a UTF-16 LE (BOM) or UTF-8 file, as MT5 writes both
+pub(crate) fn decode_text(bytes: &[u8]) -> String {
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        let u16_vec: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&u16_vec)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// The tester writes `<report>.htm` by default (or .xml/.html depending on build)
+pub(crate) fn find_report(data_dir: &Path, report: &str, started: SystemTime) -> Option<PathBuf> {
+    ["htm", "html", "xml"]
+        .iter()
+        .map(|ext| data_dir.join(format!("{}.{}", report, ext)))
+        .find(|p| {
+            fs::metadata(p)
+                .and_then(|m| m.modified())
+                .map(|modified| modified >= started)
+                .unwrap_or(false)
+        })
+}
+
+/// Export `config` as the tester's ExpertParameters file (MQL5/Profiles/Tester/ACTIVE.set
+/// under the default naming policy). Only the tester profile is written; the Common Files
+/// ACTIVE.set the live EA reads is left alone. Still a terminal folder, so safe mode blocks it.
+pub(crate) fn write_tester_set(data_dir: &Path, config: &MTConfig) -> Result<PathBuf, String> {
+    crate::safe_mode::ensure_writable("Writing the tester set file")?;
+    let tester_profile_dir = data_dir.join("MQL5").join("Profiles").join("Tester");
+    fs::create_dir_all(&tester_profile_dir)
+        .map_err(|e| format!("Failed to create tester profile folder: {}", e))?;
+    let set_path = tester_profile_dir.join(naming::current().active_set_name);
+    write_massive_v19_setfile(config.clone(), &set_path, "MT5")?;
+    Ok(set_path)
+}
+
+pub fn run_backtest_with_task(
+    request: BacktestRequest,
+    task: &crate::TaskHandle,
+) -> Result<BacktestResult, String> {
+    // Writes the tester ini into the data folder and launches the terminal
+    crate::safe_mode::ensure_writable("Running a backtest")?;
+    task.report("preparing", 0, 4);
+    let terminal_exe = resolve_terminal_exe(&request.terminal_path)?;
+    let data_dir = resolve_data_dir(&terminal_exe, request.data_dir.as_deref())?;
+
+    let report = report_stem(&request);
+    let ini = render_tester_ini(&request, &report)?;
+
+    task.check()?;
+    task.report("writing_set", 1, 4);
+    let set_path = write_tester_set(&data_dir, &request.config)?;
+
+    let ini_path = data_dir.join("daavfx_tester.ini");
+    atomic_write(&ini_path, &ini)?;
+
+    task.check()?;
+    task.report("running_tester", 2, 4);
+    tracing::info!(
+        terminal = %terminal_exe.display(),
+        ini = %ini_path.display(),
+        "Launching strategy tester"
+    );
+    let started_wall = SystemTime::now();
+    let started = Instant::now();
+    let mut child = std::process::Command::new(&terminal_exe)
+        .arg(format!("/config:{}", ini_path.display()))
+        .spawn()
+        .map_err(|e| format!("Failed to launch terminal: {}", e))?;
+
+    let timeout = Duration::from_secs(request.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to wait for terminal: {}", e)),
+        }
+        if task.is_cancelled() || started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            task.check()?;
+            return Err(format!("Backtest timed out after {}s", timeout.as_secs()));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    task.report("collecting_report", 3, 4);
+    let report_path = find_report(&data_dir, &report, started_wall).ok_or_else(|| {
+        format!(
+            "Tester finished (exit code {:?}) but no report was produced at {}",
+            status.code(),
+            data_dir.join(&report).display()
+        )
+    })?;
+    task.report("done", 4, 4);
+
+    Ok(BacktestResult {
+        report_path: report_path.to_string_lossy().to_string(),
+        ini_path: ini_path.to_string_lossy().to_string(),
+        set_path: set_path.to_string_lossy().to_string(),
+        exit_code: status.code(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// ============================================
+// TESTER REPORTS
+// ============================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TesterReportSummary {
+    pub report_path: String,
+    pub net_profit: f64,
+    pub gross_profit: Option<f64>,
+    pub gross_loss: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub expected_payoff: Option<f64>,
+    pub recovery_factor: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    pub max_drawdown_percent: Option<f64>,
+    pub total_trades: Option<u32>,
+}
+
+/// First number in a report cell ("1 234.56 (12.34%)" -> 1234.56)
+fn report_number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .replace('\u{a0}', "")
+        .replace("&nbsp;", "")
+        .chars()
+        .skip_while(|c| !(c.is_ascii_digit() || *c == '-'))
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | ' '))
+        .filter(|c| *c != ' ')
+        .collect();
+    cleaned.parse().ok()
+}
+
+/// Number directly followed by '%' ("12.34% (1 234.56)" or "1 234.56 (12.34%)")
+fn report_percent(value: &str) -> Option<f64> {
+    let regex = regex::Regex::new(r"(-?\d+(?:\.\d+)?)\s*%").unwrap();
+    regex.captures(value).and_then(|c| c[1].parse().ok())
+}
+
+/// Extract the summary block of an MT5 Strategy Tester HTML report
+pub fn parse_tester_report_html(html: &str) -> TesterReportSummary {
+    let cell_regex =
+        regex::Regex::new(r"(?is)<td[^>]*>\s*([^<:]{2,60}):\s*</td>\s*<td[^>]*>\s*(?:<b>)?([^<]*)")
+            .unwrap();
+    let mut fields: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    for caps in cell_regex.captures_iter(html) {
+        fields
+            .entry(caps[1].trim().to_lowercase())
+            .or_insert_with(|| caps[2].trim().to_string());
+    }
+    let number = |label: &str| fields.get(label).and_then(|v| report_number(v));
+
+    let equity_dd = fields.get("equity drawdown maximal");
+    TesterReportSummary {
+        report_path: String::new(),
+        net_profit: number("total net profit").unwrap_or(0.0),
+        gross_profit: number("gross profit"),
+        gross_loss: number("gross loss"),
+        profit_factor: number("profit factor"),
+        expected_payoff: number("expected payoff"),
+        recovery_factor: number("recovery factor"),
+        sharpe_ratio: number("sharpe ratio"),
+        max_drawdown: equity_dd.and_then(|v| report_number(v)),
+        max_drawdown_percent: fields
+            .get("equity drawdown relative")
+            .and_then(|v| report_percent(v))
+            .or_else(|| equity_dd.and_then(|v| report_percent(v))),
+        total_trades: number("total trades").map(|t| t as u32),
+    }
+}
+
+/// Read a tester report (.htm, UTF-16 or UTF-8) into a summary
+pub fn parse_tester_report(path: &Path) -> Result<TesterReportSummary, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read report: {}", e))?;
+    let html = decode_text(&bytes);
+    let mut summary = parse_tester_report_html(&html);
+    summary.report_path = path.to_string_lossy().to_string();
+    Ok(summary)
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn parse_backtest_report(report_path: String) -> Result<TesterReportSummary, String> {
+    let sanitized_path = sanitize_and_validate_path(&PathBuf::from(report_path))?;
+    parse_tester_report(&sanitized_path)
+}
+
+/// Export the config, run the MT5 Strategy Tester headlessly and return the report path
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn run_backtest(
+    request: BacktestRequest,
+    task_id: Option<String>,
+) -> Result<BacktestResult, String> {
+    let task = crate::task_manager().begin(task_id, "run_backtest");
+    let task_for_run = task.clone();
+    let result =
+        tokio::task::spawn_blocking(move || run_backtest_with_task(request, &task_for_run))
+            .await
+            .map_err(|e| format!("Backtest task failed: {}", e))
+            .and_then(|r| r);
+    crate::task_manager().finish(&task.id);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> BacktestRequest {
+        BacktestRequest {
+            config: crate::mt_bridge::create_full_v19_config(),
+            symbol: "EURUSD".to_string(),
+            timeframe: "h1".to_string(),
+            from_date: "2024-01-01".to_string(),
+            to_date: "2024.06.30".to_string(),
+            terminal_path: String::new(),
+            data_dir: None,
+            expert: Some("DAAVFX\\DAAVFX_v19.ex5".to_string()),
+            model: None,
+            deposit: None,
+            currency: None,
+            leverage: None,
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_write_tester_set_writes_only_the_tester_profile() {
+        let data_dir =
+            std::env::temp_dir().join(format!("daavfx_tester_set_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&data_dir);
+
+        let set_path = write_tester_set(&data_dir, &request().config).unwrap();
+        assert_eq!(
+            set_path.parent().unwrap(),
+            data_dir.join("MQL5").join("Profiles").join("Tester")
+        );
+        let content = fs::read_to_string(&set_path).unwrap();
+        assert!(content.contains("gInput_1_AP_Buy_InitialLot="));
+        assert!(!PathBuf::from(format!("{}.keymap.json", set_path.display())).exists());
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_render_tester_ini() {
+        let ini = render_tester_ini(&request(), "reports\\bt").unwrap();
+        assert!(ini.starts_with("[Tester]\r\n"));
+        assert!(ini.contains("Expert=DAAVFX\\DAAVFX_v19.ex5\r\n"));
+        assert!(ini.contains("ExpertParameters=ACTIVE.set\r\n"));
+        assert!(ini.contains("Period=H1\r\n"));
+        assert!(ini.contains("Model=1\r\n"));
+        assert!(ini.contains("FromDate=2024.01.01\r\n"));
+        assert!(ini.contains("ToDate=2024.06.30\r\n"));
+        assert!(ini.contains("Report=reports\\bt\r\n"));
+        assert!(ini.contains("ShutdownTerminal=1\r\n"));
+    }
+
+    #[test]
+    fn test_parse_tester_report_html() {
+        let html = "<table>\
+            <tr><td nowrap>Total Net Profit:</td><td nowrap><b>1 234.56</b></td>\
+            <td nowrap>Balance Drawdown Absolute:</td><td nowrap><b>10.00</b></td></tr>\
+            <tr><td nowrap>Profit Factor:</td><td nowrap><b>1.85</b></td></tr>\
+            <tr><td nowrap>Equity Drawdown Maximal:</td><td nowrap><b>512.30 (4.87%)</b></td></tr>\
+            <tr><td nowrap>Equity Drawdown Relative:</td><td nowrap><b>5.12% (530.00)</b></td></tr>\
+            <tr><td nowrap>Total Trades:</td><td nowrap><b>342</b></td></tr>\
+            </table>";
+        let summary = parse_tester_report_html(html);
+        assert_eq!(summary.net_profit, 1234.56);
+        assert_eq!(summary.profit_factor, Some(1.85));
+        assert_eq!(summary.max_drawdown, Some(512.30));
+        assert_eq!(summary.max_drawdown_percent, Some(5.12));
+        assert_eq!(summary.total_trades, Some(342));
+        assert_eq!(summary.sharpe_ratio, None);
+    }
+
+    #[test]
+    fn test_render_tester_ini_rejects_bad_input() {
+        let mut req = request();
+        req.timeframe = "H5".to_string();
+        assert!(render_tester_ini(&req, "r").is_err());
+
+        let mut req = request();
+        req.from_date = "2025.01.01".to_string();
+        assert!(render_tester_ini(&req, "r").unwrap_err().contains("after"));
+
+        let mut req = request();
+        req.model = Some(3);
+        assert!(render_tester_ini(&req, "r").is_err());
+    }
+}
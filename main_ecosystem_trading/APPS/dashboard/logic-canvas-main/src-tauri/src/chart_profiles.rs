@@ -0,0 +1,330 @@
+// ============================================
+// CHART PROFILES
+// ============================================
+//
+// Read-only view of the charts a terminal has open. MetaTrader keeps each
+// chart of a profile as a .chr file (MT4: profiles/<name>/, MT5:
+// MQL5/Profiles/Charts/<name>/) and saved templates as .tpl files; both are
+// <chart> blocks whose <expert> section names the attached EA and lists its
+// inputs. The set a chart runs is identified by gInput_ConfigFileName.
+// Nothing here writes to the terminal folder.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use daavfx_core::fs::decode_setfile_bytes;
+
+use crate::naming;
+use crate::terminal_discovery::resolve_terminal_data_path;
+
+const CONFIG_FILE_INPUT: &str = "gInput_ConfigFileName";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedChart {
+    /// Chart profile folder name; None for templates
+    pub profile: Option<String>,
+    pub file: String,
+    pub template: bool,
+    pub symbol: String,
+    /// "M1" ... "MN1", or the raw period when it has no standard name
+    pub timeframe: String,
+    pub expert_name: String,
+    /// File name from gInput_ConfigFileName, when the chart sets one
+    pub set_name: Option<String>,
+}
+
+/// The parts of a .chr/.tpl file the dashboard cares about
+#[derive(Debug, Default, PartialEq)]
+struct ChartFile {
+    symbol: String,
+    timeframe: String,
+    expert_name: Option<String>,
+    inputs: HashMap<String, String>,
+}
+
+/// MT4/MT5 chart files are ANSI, UTF-8 or UTF-16 LE with BOM
+fn decode_chart_bytes(bytes: Vec<u8>) -> String {
+    decode_setfile_bytes(bytes.clone())
+        .unwrap_or_else(|_| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn timeframe_from_minutes(minutes: u32) -> String {
+    match minutes {
+        1 => "M1".to_string(),
+        5 => "M5".to_string(),
+        15 => "M15".to_string(),
+        30 => "M30".to_string(),
+        60 => "H1".to_string(),
+        240 => "H4".to_string(),
+        1440 => "D1".to_string(),
+        10080 => "W1".to_string(),
+        43200 => "MN1".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// MT5 stores the period as a unit (0 minutes, 1 hours, 2 days, 3 weeks, 4 months) and a size
+fn timeframe_from_mt5(period_type: u32, size: u32) -> String {
+    match period_type {
+        0 => format!("M{}", size),
+        1 => format!("H{}", size),
+        2 => format!("D{}", size),
+        3 => format!("W{}", size),
+        4 => format!("MN{}", size),
+        other => format!("{}:{}", other, size),
+    }
+}
+
+fn parse_chart_file(text: &str) -> ChartFile {
+    let mut chart = ChartFile::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut period: Option<u32> = None;
+    let mut period_type: Option<u32> = None;
+    let mut period_size: Option<u32> = None;
+
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if let Some(tag) = line.strip_prefix("</").and_then(|t| t.strip_suffix('>')) {
+            if stack
+                .last()
+                .is_some_and(|open| open.eq_ignore_ascii_case(tag))
+            {
+                stack.pop();
+            }
+            continue;
+        }
+        if let Some(tag) = line.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+            stack.push(tag.to_ascii_lowercase());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let path: Vec<&str> = stack.iter().map(String::as_str).collect();
+        match path.as_slice() {
+            ["chart"] => match key {
+                "symbol" => chart.symbol = value.to_string(),
+                "period" => period = value.parse().ok(),
+                "period_type" => period_type = value.parse().ok(),
+                "period_size" => period_size = value.parse().ok(),
+                _ => {}
+            },
+            ["chart", "expert"] if key == "name" => {
+                chart.expert_name = Some(value.to_string());
+            }
+            ["chart", "expert", "inputs"] => {
+                chart.inputs.insert(key.to_string(), value.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    chart.timeframe = match (period_type, period_size, period) {
+        (Some(unit), Some(size), _) => timeframe_from_mt5(unit, size),
+        (_, _, Some(minutes)) => timeframe_from_minutes(minutes),
+        _ => String::new(),
+    };
+    chart
+}
+
+/// Last path component of the set path, whichever separator the terminal used
+fn set_file_name(value: &str) -> Option<String> {
+    let name = value.rsplit(['\\', '/']).next().unwrap_or(value).trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// EA names are matched on the naming prefix ("DAAVFX_" -> "DAAVFX")
+fn is_daavfx_expert(expert_name: &str, ea_name: &str) -> bool {
+    expert_name
+        .to_ascii_uppercase()
+        .contains(&ea_name.to_ascii_uppercase())
+}
+
+fn read_attached_chart(path: &Path, profile: Option<&str>, ea_name: &str) -> Option<AttachedChart> {
+    let bytes = fs::read(path).ok()?;
+    let chart = parse_chart_file(&decode_chart_bytes(bytes));
+    let expert_name = chart.expert_name?;
+    if !is_daavfx_expert(&expert_name, ea_name) {
+        return None;
+    }
+    Some(AttachedChart {
+        profile: profile.map(str::to_string),
+        file: path.to_string_lossy().to_string(),
+        template: profile.is_none(),
+        symbol: chart.symbol,
+        timeframe: chart.timeframe,
+        expert_name,
+        set_name: chart
+            .inputs
+            .get(CONFIG_FILE_INPUT)
+            .and_then(|value| set_file_name(value)),
+    })
+}
+
+fn files_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Charts and templates with the EA attached under one terminal data folder
+pub(crate) fn scan_attached_charts(data_path: &Path, ea_name: &str) -> Vec<AttachedChart> {
+    let profile_roots = [
+        data_path.join("profiles"),
+        data_path.join("MQL5").join("Profiles").join("Charts"),
+    ];
+    let template_dirs = [
+        data_path.join("templates"),
+        data_path.join("MQL5").join("Profiles").join("Templates"),
+    ];
+
+    let mut charts = Vec::new();
+    for root in &profile_roots {
+        let mut profiles: Vec<PathBuf> = fs::read_dir(root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        profiles.sort();
+        for profile_dir in profiles {
+            let name = profile_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            charts.extend(
+                files_with_extension(&profile_dir, "chr")
+                    .iter()
+                    .filter_map(|file| read_attached_chart(file, Some(&name), ea_name)),
+            );
+        }
+    }
+    for dir in &template_dirs {
+        charts.extend(
+            files_with_extension(dir, "tpl")
+                .iter()
+                .filter_map(|file| read_attached_chart(file, None, ea_name)),
+        );
+    }
+    charts
+}
+
+/// Charts (and templates) in a terminal that have the EA attached, with the set each one loads.
+/// `profile` is a terminal id from list_terminal_profiles or a terminal data folder.
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn list_attached_charts(profile: String) -> Result<Vec<AttachedChart>, String> {
+    let data_path = resolve_terminal_data_path(&profile)?;
+    let policy = naming::current();
+    let ea_name = match policy.prefix.trim_end_matches('_') {
+        "" => "DAAVFX",
+        name => name,
+    };
+    Ok(scan_attached_charts(&data_path, ea_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MT4_CHART: &str = "<chart>\r\nid=1\r\nsymbol=XAUUSD\r\nperiod=60\r\n\
+        <window>\r\n<indicator>\r\nname=main\r\n</indicator>\r\n</window>\r\n\
+        <expert>\r\nname=DAAVFX\r\nflags=279\r\n<inputs>\r\n\
+        gInput_ConfigFileName=C:\\Users\\trader\\DAAVFX_Gold.set\r\ngInput_Grid=300\r\n\
+        </inputs>\r\n</expert>\r\n</chart>\r\n";
+
+    const MT5_CHART: &str = "<chart>\nsymbol=EURUSD\nperiod_type=1\nperiod_size=4\n\
+        <expert>\nname=DAAVFX_EA\npath=Experts\\DAAVFX_EA.ex5\n<inputs>\n\
+        gInput_ConfigFileName=DAAVFX_MT5_Config.set\n</inputs>\n</expert>\n</chart>\n";
+
+    #[test]
+    fn test_parse_chart_files() {
+        let mt4 = parse_chart_file(MT4_CHART);
+        assert_eq!(mt4.symbol, "XAUUSD");
+        assert_eq!(mt4.timeframe, "H1");
+        assert_eq!(mt4.expert_name.as_deref(), Some("DAAVFX"));
+        assert_eq!(
+            mt4.inputs.get("gInput_Grid").map(String::as_str),
+            Some("300")
+        );
+
+        let mt5 = parse_chart_file(MT5_CHART);
+        assert_eq!(mt5.timeframe, "H4");
+        assert_eq!(mt5.expert_name.as_deref(), Some("DAAVFX_EA"));
+
+        let bare = parse_chart_file("<chart>\nsymbol=GBPUSD\nperiod=15\n</chart>\n");
+        assert_eq!(bare.timeframe, "M15");
+        assert_eq!(bare.expert_name, None);
+    }
+
+    #[test]
+    fn test_scan_lists_only_charts_with_the_ea() {
+        let data = std::env::temp_dir().join(format!("daavfx_charts_{}", std::process::id()));
+        let mt4_profile = data.join("profiles").join("Gold");
+        let mt5_profile = data
+            .join("MQL5")
+            .join("Profiles")
+            .join("Charts")
+            .join("Default");
+        let templates = data.join("templates");
+        for dir in [&mt4_profile, &mt5_profile, &templates] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(mt4_profile.join("chart01.chr"), MT4_CHART).unwrap();
+        fs::write(
+            mt4_profile.join("chart02.chr"),
+            "<chart>\nsymbol=GBPUSD\nperiod=15\n</chart>\n",
+        )
+        .unwrap();
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(MT5_CHART.encode_utf16().flat_map(|u| u.to_le_bytes()))
+            .collect();
+        fs::write(mt5_profile.join("chart01.chr"), utf16).unwrap();
+        fs::write(templates.join("gold.tpl"), MT4_CHART).unwrap();
+
+        let charts = scan_attached_charts(&data, "DAAVFX");
+        let summary: Vec<_> = charts
+            .iter()
+            .map(|c| {
+                (
+                    c.profile.as_deref(),
+                    c.symbol.as_str(),
+                    c.set_name.as_deref(),
+                    c.template,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("Gold"), "XAUUSD", Some("DAAVFX_Gold.set"), false),
+                (
+                    Some("Default"),
+                    "EURUSD",
+                    Some("DAAVFX_MT5_Config.set"),
+                    false
+                ),
+                (None, "XAUUSD", Some("DAAVFX_Gold.set"), true),
+            ]
+        );
+        assert!(scan_attached_charts(&data, "OTHER").is_empty());
+
+        let _ = fs::remove_dir_all(&data);
+    }
+}
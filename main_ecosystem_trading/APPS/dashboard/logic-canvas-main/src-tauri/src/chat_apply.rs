@@ -0,0 +1,428 @@
+// ============================================
+// CHAT COMMAND APPLICATION
+// ============================================
+//
+// Turns a predicted intent plus extracted slots into an edit of the managed
+// config. Every applied change pushes an undo snapshot, and a macro step
+// while one is being recorded; dry runs return the same change list without
+// touching state. Field locks are honoured.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chat_preprocessor::CommandSlots;
+use crate::field_locks::LockViolation;
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::sweep::set_directional;
+
+#[cfg(feature = "tauri-app")]
+use crate::chat_macros::MacroStep;
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::enforce_field_locks;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+/// Fields chat commands may write, with their allowed range
+pub const CHAT_FIELDS: [(&str, f64, f64); 10] = [
+    ("initial_lot", 0.01, 100.0),
+    ("last_lot", 0.01, 100.0),
+    ("multiplier", 0.1, 10.0),
+    ("grid", 1.0, 100_000.0),
+    ("trail_value", 0.0, 100_000.0),
+    ("trail_start", 0.0, 100_000.0),
+    ("trail_step", 0.0, 100_000.0),
+    ("start_level", 0.0, 100.0),
+    ("reverse_scale", 0.0, 1000.0),
+    ("hedge_scale", 0.0, 1000.0),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatChange {
+    pub engine: String,
+    pub group: u8,
+    pub logic_id: String,
+    pub field: String,
+    pub old_value: Option<f64>,
+    pub new_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatApplyResult {
+    pub intent: String,
+    pub applied: bool,
+    pub dry_run: bool,
+    pub changes: Vec<ChatChange>,
+    /// Locked fields the change touches (only returned in warn mode)
+    pub violations: Vec<LockViolation>,
+    pub message: String,
+    /// Updated config when the change was applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<MTConfig>,
+}
+
+fn field_range(field: &str) -> Result<(f64, f64), String> {
+    CHAT_FIELDS
+        .iter()
+        .find(|(name, _, _)| *name == field)
+        .map(|(_, min, max)| (*min, *max))
+        .ok_or_else(|| format!("Field '{}' can't be changed from chat", field))
+}
+
+/// Effective value of a field for the row's side (or the base value)
+pub fn logic_field_value(logic: &LogicConfig, field: &str, direction: Option<&str>) -> Option<f64> {
+    let pick = |base: Option<f64>, buy: Option<f64>, sell: Option<f64>| match direction {
+        Some("Buy") => buy.or(base),
+        Some("Sell") => sell.or(base),
+        _ => base,
+    };
+    let int = |v: Option<i32>| v.map(f64::from);
+    match field {
+        "initial_lot" => pick(
+            Some(logic.initial_lot),
+            logic.initial_lot_b,
+            logic.initial_lot_s,
+        ),
+        "multiplier" => pick(
+            Some(logic.multiplier),
+            logic.multiplier_b,
+            logic.multiplier_s,
+        ),
+        "grid" => pick(Some(logic.grid), logic.grid_b, logic.grid_s),
+        "trail_value" => pick(
+            Some(logic.trail_value),
+            logic.trail_value_b,
+            logic.trail_value_s,
+        ),
+        "trail_start" => pick(
+            Some(logic.trail_start),
+            logic.trail_start_b,
+            logic.trail_start_s,
+        ),
+        "trail_step" => pick(
+            Some(logic.trail_step),
+            logic.trail_step_b,
+            logic.trail_step_s,
+        ),
+        "reverse_scale" => pick(
+            Some(logic.reverse_scale),
+            logic.reverse_scale_b,
+            logic.reverse_scale_s,
+        ),
+        "hedge_scale" => pick(
+            Some(logic.hedge_scale),
+            logic.hedge_scale_b,
+            logic.hedge_scale_s,
+        ),
+        "last_lot" => pick(logic.last_lot, logic.last_lot_b, logic.last_lot_s),
+        "start_level" => pick(
+            int(logic.start_level),
+            int(logic.start_level_b),
+            int(logic.start_level_s),
+        ),
+        _ => None,
+    }
+}
+
+fn set_logic_field(l: &mut LogicConfig, field: &str, direction: Option<&str>, value: f64) {
+    match field {
+        "initial_lot" => set_directional(
+            &mut l.initial_lot,
+            &mut l.initial_lot_b,
+            &mut l.initial_lot_s,
+            direction,
+            value,
+        ),
+        "multiplier" => set_directional(
+            &mut l.multiplier,
+            &mut l.multiplier_b,
+            &mut l.multiplier_s,
+            direction,
+            value,
+        ),
+        "grid" => set_directional(&mut l.grid, &mut l.grid_b, &mut l.grid_s, direction, value),
+        "trail_value" => set_directional(
+            &mut l.trail_value,
+            &mut l.trail_value_b,
+            &mut l.trail_value_s,
+            direction,
+            value,
+        ),
+        "trail_start" => set_directional(
+            &mut l.trail_start,
+            &mut l.trail_start_b,
+            &mut l.trail_start_s,
+            direction,
+            value,
+        ),
+        "trail_step" => set_directional(
+            &mut l.trail_step,
+            &mut l.trail_step_b,
+            &mut l.trail_step_s,
+            direction,
+            value,
+        ),
+        "reverse_scale" => set_directional(
+            &mut l.reverse_scale,
+            &mut l.reverse_scale_b,
+            &mut l.reverse_scale_s,
+            direction,
+            value,
+        ),
+        "hedge_scale" => set_directional(
+            &mut l.hedge_scale,
+            &mut l.hedge_scale_b,
+            &mut l.hedge_scale_s,
+            direction,
+            value,
+        ),
+        "last_lot" => match direction {
+            Some("Buy") => l.last_lot_b = Some(value),
+            Some("Sell") => l.last_lot_s = Some(value),
+            _ => l.last_lot = Some(value),
+        },
+        "start_level" => {
+            let level = Some(value.round() as i32);
+            match direction {
+                Some("Buy") => l.start_level_b = level,
+                Some("Sell") => l.start_level_s = level,
+                _ => l.start_level = level,
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Logic rows the slots select. Rows of the other side are skipped when a
+/// direction is given.
+fn selected<'a>(
+    config: &'a mut MTConfig,
+    slots: &'a CommandSlots,
+) -> impl Iterator<Item = (String, u8, &'a mut LogicConfig)> + 'a {
+    let direction = slots.direction.as_deref();
+    config.engines.iter_mut().flat_map(move |engine| {
+        let engine_id = engine.engine_id.clone();
+        let engine_ok = slots
+            .engine
+            .as_ref()
+            .map_or(true, |e| e.eq_ignore_ascii_case(&engine_id));
+        engine.groups.iter_mut().flat_map(move |group| {
+            let group_number = group.group_number;
+            let group_ok = engine_ok && slots.group.map_or(true, |g| g == group_number);
+            let engine_id = engine_id.clone();
+            group.logics.iter_mut().filter_map(move |logic| {
+                let logic_ok = slots
+                    .logic
+                    .as_ref()
+                    .map_or(true, |l| l.eq_ignore_ascii_case(&logic.logic_name));
+                let side_ok = match direction {
+                    Some("Buy") => logic.allow_buy,
+                    Some("Sell") => logic.allow_sell,
+                    _ => true,
+                };
+                (group_ok && logic_ok && side_ok).then(|| (engine_id.clone(), group_number, logic))
+            })
+        })
+    })
+}
+
+/// Plan (and apply to `config`) the edit an intent + slots describe
+pub fn apply_slots(
+    config: &mut MTConfig,
+    intent: &str,
+    slots: &CommandSlots,
+) -> Result<Vec<ChatChange>, String> {
+    let field = slots
+        .field
+        .clone()
+        .ok_or_else(|| "Which parameter? No field was recognised in the command".to_string())?;
+    let direction = slots.direction.as_deref();
+
+    match intent.to_ascii_uppercase().as_str() {
+        "SET" => {
+            let (min, max) = field_range(&field)?;
+            let value = slots
+                .numbers
+                .first()
+                .map(|n| n.value)
+                .ok_or_else(|| format!("No value given for {}", field))?;
+            if !value.is_finite() || value < min || value > max {
+                return Err(format!(
+                    "{} = {} is outside the allowed range {}..{}",
+                    field, value, min, max
+                ));
+            }
+            let mut changes = Vec::new();
+            for (engine, group, logic) in selected(config, slots) {
+                let old_value = logic_field_value(logic, &field, direction);
+                if old_value == Some(value) {
+                    continue;
+                }
+                set_logic_field(logic, &field, direction, value);
+                changes.push(ChatChange {
+                    engine,
+                    group,
+                    logic_id: logic.logic_id.clone(),
+                    field: field.clone(),
+                    old_value,
+                    new_value: value,
+                });
+            }
+            Ok(changes)
+        }
+        "QUERY" => {
+            field_range(&field)?;
+            Ok(selected(config, slots)
+                .filter_map(|(engine, group, logic)| {
+                    let value = logic_field_value(logic, &field, direction)?;
+                    Some(ChatChange {
+                        engine,
+                        group,
+                        logic_id: logic.logic_id.clone(),
+                        field: field.clone(),
+                        old_value: Some(value),
+                        new_value: value,
+                    })
+                })
+                .collect())
+        }
+        other => Err(format!(
+            "Intent {} can't be applied to the config yet",
+            other
+        )),
+    }
+}
+
+/// Store `updated` as the config behind `handle`, with `previous` (the config the
+/// change was computed from) as its undo step
+#[cfg(feature = "tauri-app")]
+pub(crate) async fn commit_change(
+    state: &MTBridgeState,
+    handle: Option<&str>,
+    previous: MTConfig,
+    updated: MTConfig,
+) -> Result<(), String> {
+    let mut configs = state.configs.write().await;
+    let handle = configs.store(handle, previous)?;
+    configs.commit(Some(&handle), updated)?;
+    Ok(())
+}
+
+/// Apply a predicted intent and its slots to the config behind `handle` (default:
+/// the active one). `config` seeds it when the frontend holds the current one;
+/// with `dry_run` only the change preview is returned.
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn apply_chat_intent(
+    intent: String,
+    slots: CommandSlots,
+    dry_run: Option<bool>,
+    config: Option<MTConfig>,
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<ChatApplyResult, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let current = match config {
+        Some(config) => config,
+        None => state
+            .configs
+            .read()
+            .await
+            .get(handle.as_deref())
+            .map(|open| open.config.clone())
+            .map_err(|_| "No config loaded to apply the command to".to_string())?,
+    };
+    let mut updated = current.clone();
+    let changes = apply_slots(&mut updated, &intent, &slots)?;
+    let read_only = intent.eq_ignore_ascii_case("QUERY");
+    let violations = if read_only || changes.is_empty() {
+        Vec::new()
+    } else {
+        enforce_field_locks(&state, &updated).await?
+    };
+
+    let apply = !dry_run && !read_only && !changes.is_empty();
+    if apply {
+        commit_change(&state, handle.as_deref(), current, updated.clone()).await?;
+        if let Some(recording) = state.macro_recording.write().await.as_mut() {
+            recording.steps.push(MacroStep {
+                intent: intent.to_ascii_uppercase(),
+                slots: slots.clone(),
+            });
+        }
+    }
+
+    let message = if read_only {
+        format!("{} value(s) found", changes.len())
+    } else if changes.is_empty() {
+        "Nothing to change; values already match".to_string()
+    } else if apply {
+        format!("Changed {} value(s)", changes.len())
+    } else {
+        format!("Would change {} value(s)", changes.len())
+    };
+    Ok(ChatApplyResult {
+        intent: intent.to_ascii_uppercase(),
+        applied: apply,
+        dry_run,
+        changes,
+        violations,
+        message,
+        config: apply.then_some(updated),
+    })
+}
+
+/// Restore the config behind `handle` from before its last applied chat change
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn undo_chat_change(
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<MTConfig, String> {
+    state.configs.write().await.undo(handle.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_preprocessor::extract_slots;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_set_targets_only_selected_sell_rows() {
+        let mut config = create_full_v19_config();
+        let slots = extract_slots("set grid to 250 on B3 scalp sell");
+        let changes = apply_slots(&mut config, "SET", &slots).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].logic_id, "B_SCALPER_S_G3");
+        assert_eq!(changes[0].new_value, 250.0);
+
+        let row = config.engines[1].groups[2]
+            .logics
+            .iter()
+            .find(|l| l.logic_id == "B_SCALPER_S_G3")
+            .unwrap();
+        assert_eq!(logic_field_value(row, "grid", Some("Sell")), Some(250.0));
+
+        // Re-applying is a no-op
+        assert!(apply_slots(&mut config, "SET", &slots).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_rejects_out_of_range_and_unknown_intent() {
+        let mut config = create_full_v19_config();
+        let slots = extract_slots("set lot to 500 on A1 power");
+        assert!(apply_slots(&mut config, "SET", &slots).is_err());
+        assert!(apply_slots(&mut config, "COPY", &slots).is_err());
+
+        let query = apply_slots(
+            &mut config,
+            "QUERY",
+            &extract_slots("what is the grid on A1 power buy"),
+        )
+        .unwrap();
+        assert_eq!(query.len(), 1);
+    }
+}
@@ -1,35 +1,132 @@
 //! Tauri commands for the chat neural network
 
-use crate::chat_neural::{generate_training_data, TinyNeural};
+use crate::chat_dataset::{read_dataset_file, ChatDataset, DatasetExample};
+use crate::chat_eval::{evaluate, label_prediction, EvalOptions, EvaluationReport, UNCERTAIN};
+use crate::chat_neural::{ModelSnapshot, TinyNeural};
+use crate::chat_preprocessor::{
+    build_config_path, extract_slots, preprocess, CommandSlots, NumberSlot, SlotUnit,
+};
+use crate::quantize::Precision;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::State;
 
+/// Model loaded at startup and updated after training and corrections
+const DEFAULT_CHAT_MODEL: &str = "chat_intent";
+const CHAT_SETTINGS_FILE: &str = "chat_settings.json";
+
 /// Neural network state - persists across commands
 pub struct ChatNeuralState {
     pub network: Mutex<TinyNeural>,
     pub trained: Mutex<bool>,
+    /// Predictions below this confidence are reported as UNCERTAIN
+    pub confidence_threshold: Mutex<f32>,
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+struct ChatSettings {
+    #[serde(default)]
+    confidence_threshold: f32,
+    /// Weights the transformer and diffusion models run inference on
+    #[serde(default)]
+    inference_precision: Precision,
+    #[serde(default)]
+    fallback_thresholds: FallbackThresholds,
+}
+
+fn load_chat_settings() -> ChatSettings {
+    std::fs::read_to_string(chat_models_dir().join(CHAT_SETTINGS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_chat_settings(settings: &ChatSettings) -> Result<(), String> {
+    std::fs::create_dir_all(chat_models_dir())
+        .map_err(|e| format!("Failed to create models folder: {}", e))?;
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize chat settings: {}", e))?;
+    crate::mt_bridge::atomic_write(&chat_models_dir().join(CHAT_SETTINGS_FILE), &json)
 }
 
 impl Default for ChatNeuralState {
     fn default() -> Self {
+        // Pick up last session's model (and its corrections) when one exists
+        let (network, trained) = chat_model_path(DEFAULT_CHAT_MODEL)
+            .ok()
+            .filter(|path| path.exists())
+            .and_then(|path| match TinyNeural::load(&path) {
+                Ok(loaded) => Some(loaded),
+                Err(e) => {
+                    eprintln!("Ignoring saved chat model: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_else(|| (TinyNeural::new(), false));
         ChatNeuralState {
-            network: Mutex::new(TinyNeural::new()),
-            trained: Mutex::new(false),
+            network: Mutex::new(network),
+            trained: Mutex::new(trained),
+            confidence_threshold: Mutex::new(load_chat_settings().confidence_threshold),
         }
     }
 }
 
+fn chat_models_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join("models")
+}
+
+fn chat_model_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("Invalid model name '{}'", name));
+    }
+    Ok(chat_models_dir().join(format!("{}.json", name)))
+}
+
+fn save_network(net: &TinyNeural, trained: bool, name: &str) -> Result<PathBuf, String> {
+    let path = chat_model_path(name)?;
+    std::fs::create_dir_all(chat_models_dir())
+        .map_err(|e| format!("Failed to create models folder: {}", e))?;
+    net.save(&path, trained)?;
+    Ok(path)
+}
+
+/// Keep the default model in step with the in-memory one
+fn autosave(state: &ChatNeuralState) {
+    let result = state
+        .network
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|net| {
+            let trained = *state.trained.lock().map_err(|e| e.to_string())?;
+            save_network(&net, trained, DEFAULT_CHAT_MODEL)
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to autosave chat model: {}", e);
+    }
+}
+
 /// Train the neural network on trading config commands
 #[tauri::command]
 pub fn train_chat_neural(state: State<'_, ChatNeuralState>) -> Result<String, String> {
     let mut net = state.network.lock().map_err(|e| e.to_string())?;
 
-    let examples = generate_training_data();
+    let examples = ChatDataset::load()?.training_examples();
     println!("Training on {} examples...", examples.len());
 
     net.train(&examples, 100, 0.1);
+    drop(net);
 
     *state.trained.lock().map_err(|e| e.to_string())? = true;
+    autosave(&state);
 
     Ok(format!("Trained on {} examples!", examples.len()))
 }
@@ -41,16 +138,71 @@ pub fn predict_intent(
     input: String,
 ) -> Result<IntentPrediction, String> {
     let net = state.network.lock().map_err(|e| e.to_string())?;
+    let threshold = *state.confidence_threshold.lock().map_err(|e| e.to_string())?;
 
     let (intent, prob) = net.predict(&input);
+    let label = label_prediction(intent.as_str(), prob, threshold);
+    let best_guess = (label == UNCERTAIN).then(|| intent.as_str().to_string());
 
     Ok(IntentPrediction {
-        intent: intent.as_str().to_string(),
+        intent: label,
         confidence: prob,
         input,
+        best_guess,
+        candidates: Vec::new(),
     })
 }
 
+/// Set the confidence below which predict_intent answers UNCERTAIN (0 disables)
+#[tauri::command]
+pub fn set_intent_confidence_threshold(
+    state: State<'_, ChatNeuralState>,
+    threshold: f32,
+) -> Result<f32, String> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!("Threshold {} must be between 0 and 1", threshold));
+    }
+    save_chat_settings(&ChatSettings {
+        confidence_threshold: threshold,
+        ..load_chat_settings()
+    })?;
+    *state.confidence_threshold.lock().map_err(|e| e.to_string())? = threshold;
+    Ok(threshold)
+}
+
+#[tauri::command]
+pub fn get_intent_confidence_threshold(state: State<'_, ChatNeuralState>) -> Result<f32, String> {
+    Ok(*state.confidence_threshold.lock().map_err(|e| e.to_string())?)
+}
+
+/// Cross-validate the intent network on the training dataset. Uses k-fold
+/// (default 5) unless `holdout` gives a test fraction; `threshold` defaults
+/// to the configured confidence threshold.
+#[tauri::command]
+pub async fn evaluate_chat_model(
+    state: State<'_, ChatNeuralState>,
+    folds: Option<usize>,
+    holdout: Option<f32>,
+    epochs: Option<usize>,
+    threshold: Option<f32>,
+) -> Result<EvaluationReport, String> {
+    let defaults = EvalOptions::default();
+    let options = EvalOptions {
+        folds: folds.unwrap_or(defaults.folds),
+        holdout,
+        epochs: epochs.unwrap_or(defaults.epochs),
+        threshold: match threshold {
+            Some(t) => t,
+            None => *state.confidence_threshold.lock().map_err(|e| e.to_string())?,
+        },
+        ..defaults
+    };
+    let examples = ChatDataset::load()?.training_examples();
+    tokio::task::spawn_blocking(move || evaluate(&examples, &options))
+        .await
+        .map_err(|e| format!("Evaluation task failed: {}", e))?
+}
+
 /// Learn from a user correction
 #[tauri::command]
 pub fn learn_correction(
@@ -60,6 +212,8 @@ pub fn learn_correction(
 ) -> Result<(), String> {
     let mut net = state.network.lock().map_err(|e| e.to_string())?;
     net.learn_correction(&wrong, &correct);
+    drop(net);
+    autosave(&state);
     Ok(())
 }
 
@@ -70,21 +224,355 @@ pub fn is_trained(state: State<'_, ChatNeuralState>) -> Result<bool, String> {
     Ok(*trained)
 }
 
+/// Save the current network under `name` (default: the startup model)
+#[tauri::command]
+pub fn save_chat_model(
+    state: State<'_, ChatNeuralState>,
+    name: Option<String>,
+) -> Result<String, String> {
+    let net = state.network.lock().map_err(|e| e.to_string())?;
+    let trained = *state.trained.lock().map_err(|e| e.to_string())?;
+    let name = name.unwrap_or_else(|| DEFAULT_CHAT_MODEL.to_string());
+    let path = save_network(&net, trained, &name)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Swap a saved model into the running chat network
+#[tauri::command]
+pub fn load_chat_model(
+    state: State<'_, ChatNeuralState>,
+    name: Option<String>,
+) -> Result<bool, String> {
+    let name = name.unwrap_or_else(|| DEFAULT_CHAT_MODEL.to_string());
+    let (loaded, trained) = TinyNeural::load(&chat_model_path(&name)?)?;
+    *state.network.lock().map_err(|e| e.to_string())? = loaded;
+    *state.trained.lock().map_err(|e| e.to_string())? = trained;
+    Ok(trained)
+}
+
+/// Saved chat models, newest first
+#[tauri::command]
+pub fn list_chat_models() -> Result<Vec<ChatModelInfo>, String> {
+    let dir = chat_models_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read models folder: {}", e))?;
+    let mut models: Vec<ChatModelInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let snapshot: ModelSnapshot = serde_json::from_str(&content).ok()?;
+            Some(ChatModelInfo {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                format_version: snapshot.format_version,
+                saved_at: snapshot.saved_at,
+                trained: snapshot.trained,
+                corrections: snapshot.corrections.len(),
+                size_bytes: content.len() as u64,
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(models)
+}
+
+/// Saved model summary for the models list
+#[derive(serde::Serialize)]
+pub struct ChatModelInfo {
+    pub name: String,
+    pub format_version: u32,
+    pub saved_at: String,
+    pub trained: bool,
+    pub corrections: usize,
+    pub size_bytes: u64,
+}
+
+// ============================================
+// TRAINING DATASET COMMANDS
+// ============================================
+
+/// Training examples, optionally filtered by intent and/or text
+#[tauri::command]
+pub fn list_training_examples(
+    intent: Option<String>,
+    search: Option<String>,
+) -> Result<Vec<DatasetExample>, String> {
+    let intent = intent
+        .filter(|i| !i.trim().is_empty())
+        .map(|i| crate::chat_dataset::normalize_intent(&i))
+        .transpose()?;
+    let search = search.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+    Ok(ChatDataset::load()?
+        .examples
+        .into_iter()
+        .filter(|e| intent.as_ref().map_or(true, |i| &e.intent == i))
+        .filter(|e| search.as_ref().map_or(true, |s| e.text.to_lowercase().contains(s)))
+        .collect())
+}
+
+#[tauri::command]
+pub fn add_training_example(text: String, intent: String) -> Result<DatasetExample, String> {
+    let mut dataset = ChatDataset::load()?;
+    let example = dataset.add(&text, &intent, "user")?;
+    dataset.save()?;
+    Ok(example)
+}
+
+#[tauri::command]
+pub fn update_training_example(
+    id: u64,
+    text: Option<String>,
+    intent: Option<String>,
+) -> Result<DatasetExample, String> {
+    let mut dataset = ChatDataset::load()?;
+    let example = dataset.update(id, text.as_deref(), intent.as_deref())?;
+    dataset.save()?;
+    Ok(example)
+}
+
+#[tauri::command]
+pub fn delete_training_example(id: u64) -> Result<(), String> {
+    let mut dataset = ChatDataset::load()?;
+    dataset.remove(id)?;
+    dataset.save()
+}
+
+/// Number of examples per intent label
+#[tauri::command]
+pub fn get_training_intent_counts() -> Result<BTreeMap<String, usize>, String> {
+    Ok(ChatDataset::load()?.intent_counts())
+}
+
+/// Write the dataset to `path` as JSONL
+#[tauri::command]
+pub fn export_training_dataset(path: String) -> Result<usize, String> {
+    let dataset = ChatDataset::load()?;
+    let path = crate::mt_bridge::sanitize_and_validate_path(&PathBuf::from(path))?;
+    crate::mt_bridge::atomic_write(&path, &dataset.to_jsonl()?)?;
+    Ok(dataset.examples.len())
+}
+
+/// Merge (or with `replace`, swap in) a JSONL dataset; bad lines are reported
+#[tauri::command]
+pub fn import_training_dataset(
+    path: String,
+    replace: Option<bool>,
+) -> Result<DatasetImport, String> {
+    let path = crate::mt_bridge::sanitize_and_validate_path(&PathBuf::from(path))?;
+    let (imported, errors) = read_dataset_file(&path)?;
+    let dataset = if replace.unwrap_or(false) {
+        imported
+    } else {
+        let mut dataset = ChatDataset::load()?;
+        dataset.merge(imported);
+        dataset
+    };
+    dataset.save()?;
+    Ok(DatasetImport {
+        total: dataset.examples.len(),
+        errors,
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct DatasetImport {
+    pub total: usize,
+    pub errors: Vec<String>,
+}
+
 /// Result of intent prediction
 #[derive(serde::Serialize)]
 pub struct IntentPrediction {
     pub intent: String,
     pub confidence: f32,
     pub input: String,
+    /// Top intent when `intent` is UNCERTAIN
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_guess: Option<String>,
+    /// Ranked alternatives, for offering suggestions
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<IntentCandidate>,
+}
+
+// ============================================
+// CONVERSATION CONTEXT
+// ============================================
+
+/// Turns kept for follow-up resolution
+const MAX_TURNS: usize = 10;
+/// Words that mark a message as referring back to the previous one
+const FOLLOW_UP_WORDS: [&str; 9] = [
+    "same", "it", "that", "those", "also", "too", "instead", "again", "now",
+];
+
+/// Engine/group/logic/field the conversation is currently about
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ConversationFocus {
+    pub engine: Option<String>,
+    pub group: Option<u8>,
+    pub logic: Option<String>,
+    pub direction: Option<String>,
+    pub field: Option<String>,
+    /// Last value set, so "make it 2x" has something to scale
+    pub value: Option<f64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConversationTurn {
+    pub input: String,
+    pub intent: Option<String>,
+    pub slots: CommandSlots,
+    pub follow_up: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct Conversation {
+    pub turns: VecDeque<ConversationTurn>,
+    pub focus: ConversationFocus,
+}
+
+impl Conversation {
+    /// Resolve a message against the focus, record it and return the turn
+    pub fn resolve(&mut self, input: &str, intent: Option<String>) -> ConversationTurn {
+        let command = preprocess(input);
+        let lower = command.to_lowercase();
+        let words: Vec<&str> = lower.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+        let mut slots = extract_slots(&command);
+
+        // "2x" with no field named means "two times the last value"; the
+        // slot extractor reads it as a multiplier setting on its own
+        let names_field =
+            slots.field.as_deref() != Some("multiplier") || lower.contains("mult");
+        let scale = if words.contains(&"double") {
+            Some(2.0)
+        } else if words.contains(&"halve") || words.contains(&"half") {
+            Some(0.5)
+        } else {
+            slots
+                .numbers
+                .iter()
+                .find(|n| n.unit == Some(SlotUnit::Times) && !names_field)
+                .map(|n| n.value)
+        };
+        let scale = scale.filter(|_| self.focus.value.is_some());
+        let refers_back = FOLLOW_UP_WORDS.iter().any(|w| words.contains(w));
+        let follow_up = !self.turns.is_empty()
+            && (refers_back
+                || scale.is_some()
+                || (slots.field.is_none() && slots.numbers.is_empty()));
+
+        let mut intent = intent;
+        if follow_up {
+            let focus = &self.focus;
+            if slots.engine.is_none() && slots.group.is_none() {
+                slots.engine = focus.engine.clone();
+                slots.group = focus.group;
+            }
+            slots.logic = slots.logic.take().or_else(|| focus.logic.clone());
+            slots.direction = slots.direction.take().or_else(|| focus.direction.clone());
+            if scale.is_some() && !names_field {
+                slots.field = None;
+            }
+            slots.field = slots.field.take().or_else(|| focus.field.clone());
+            if let (Some(factor), Some(previous)) = (scale, focus.value) {
+                // "make it 2x" scales the last value instead of setting 2
+                slots.numbers.retain(|n| n.unit != Some(SlotUnit::Times));
+                slots.numbers.insert(
+                    0,
+                    NumberSlot {
+                        value: previous * factor,
+                        unit: None,
+                        text: format!("{} x {}", previous, factor),
+                    },
+                );
+                intent = Some("SET".to_string());
+            } else if slots.numbers.is_empty() {
+                // "now do the same for sell" repeats the previous value
+                if let Some(previous) = focus.value {
+                    slots.numbers.push(NumberSlot {
+                        value: previous,
+                        unit: None,
+                        text: previous.to_string(),
+                    });
+                }
+            }
+            if intent.is_none() {
+                intent = self.turns.back().and_then(|t| t.intent.clone());
+            }
+        }
+        slots.config_path = build_config_path(&slots);
+
+        let focus = &mut self.focus;
+        if slots.engine.is_some() || slots.group.is_some() {
+            focus.engine = slots.engine.clone();
+            focus.group = slots.group;
+        }
+        focus.logic = slots.logic.clone().or(focus.logic.take());
+        focus.direction = slots.direction.clone();
+        focus.field = slots.field.clone().or(focus.field.take());
+        if let Some(number) = slots.numbers.first() {
+            focus.value = Some(number.value);
+        }
+
+        let turn = ConversationTurn {
+            input: input.to_string(),
+            intent,
+            slots,
+            follow_up,
+        };
+        self.turns.push_back(turn.clone());
+        while self.turns.len() > MAX_TURNS {
+            self.turns.pop_front();
+        }
+        turn
+    }
+}
+
+/// Conversation state - persists across commands for the session
+#[derive(Default)]
+pub struct ConversationState {
+    pub conversation: Mutex<Conversation>,
+}
+
+/// Resolve a chat message (and its predicted intent) using the conversation so far
+#[tauri::command]
+pub fn resolve_chat_turn(
+    state: State<'_, ConversationState>,
+    input: String,
+    intent: Option<String>,
+) -> Result<ConversationTurn, String> {
+    let mut conversation = state.conversation.lock().map_err(|e| e.to_string())?;
+    Ok(conversation.resolve(&input, intent))
+}
+
+#[tauri::command]
+pub fn get_conversation_context(
+    state: State<'_, ConversationState>,
+) -> Result<Conversation, String> {
+    Ok(state.conversation.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub fn reset_conversation(state: State<'_, ConversationState>) -> Result<(), String> {
+    *state.conversation.lock().map_err(|e| e.to_string())? = Conversation::default();
+    Ok(())
 }
 
 // ============================================
 // TRANSFORMER COMMANDS - Pure Rust Transformer
 // ============================================
 
-use crate::diffusion_refine::{DiffusionDenoiser, ExtractedParameter, TransformerWithDiffusion};
+use crate::diffusion_refine::{
+    refinement_confidence, DiffusionDenoiser, ExtractedParameter, TransformerWithDiffusion,
+};
+use crate::subword_tokenizer::SubwordTokenizer;
 use crate::trading_transformer::{
-    generate_training_data as gen_transformer_data, TradingTransformer, TransformerIntent,
+    generate_training_data as gen_transformer_data, DecodeOptions, IntentCandidate,
+    TradingTransformer, TransformerIntent,
 };
 
 /// Transformer state
@@ -93,16 +581,30 @@ pub struct TransformerState {
     pub trained: Mutex<bool>,
 }
 
+const TRANSFORMER_VOCAB_FILE: &str = "transformer_vocab.json";
+
 impl Default for TransformerState {
     fn default() -> Self {
+        let mut transformer = TradingTransformer::new();
+        // Reuse the vocabulary from the last training run, if there is one
+        let vocab_path = chat_models_dir().join(TRANSFORMER_VOCAB_FILE);
+        if vocab_path.exists() {
+            match SubwordTokenizer::load(&vocab_path)
+                .and_then(|tokenizer| transformer.set_tokenizer(tokenizer))
+            {
+                Ok(()) => println!("Loaded transformer vocabulary from {:?}", vocab_path),
+                Err(e) => eprintln!("Ignoring transformer vocabulary: {}", e),
+            }
+        }
+        transformer.set_precision(load_chat_settings().inference_precision);
         TransformerState {
-            transformer: Mutex::new(TradingTransformer::new()),
+            transformer: Mutex::new(transformer),
             trained: Mutex::new(false),
         }
     }
 }
 
-/// Train the transformer
+/// Train the transformer, relearning its subword vocabulary first
 #[tauri::command]
 pub fn train_transformer(state: State<'_, TransformerState>) -> Result<String, String> {
     let mut tf = state.transformer.lock().map_err(|e| e.to_string())?;
@@ -110,30 +612,63 @@ pub fn train_transformer(state: State<'_, TransformerState>) -> Result<String, S
     let examples = gen_transformer_data();
     println!("Training Transformer on {} examples...", examples.len());
 
+    let tokenizer = TradingTransformer::train_tokenizer(&examples);
+    tokenizer.save(&chat_models_dir().join(TRANSFORMER_VOCAB_FILE))?;
+    let vocab_size = tokenizer.vocab_size();
+    tf.set_tokenizer(tokenizer)?;
+
     tf.train(&examples, 50, 0.05);
 
     *state.trained.lock().map_err(|e| e.to_string())? = true;
 
     Ok(format!(
-        "Transformer trained on {} examples!",
-        examples.len()
+        "Transformer trained on {} examples ({} token vocabulary)!",
+        examples.len(),
+        vocab_size
     ))
 }
 
-/// Predict with transformer
+#[derive(serde::Serialize)]
+pub struct TokenizedInput {
+    pub tokens: Vec<String>,
+    pub ids: Vec<usize>,
+    /// Words the vocabulary couldn't cover
+    pub unknown: usize,
+}
+
+/// Show how the transformer tokenizer splits an input
+#[tauri::command]
+pub fn tokenize_transformer_input(
+    state: State<'_, TransformerState>,
+    input: String,
+) -> Result<TokenizedInput, String> {
+    let tf = state.transformer.lock().map_err(|e| e.to_string())?;
+    let tokenizer = tf.tokenizer();
+    let ids = tokenizer.encode(&input);
+    Ok(TokenizedInput {
+        tokens: tokenizer.tokens(&ids),
+        unknown: ids.iter().filter(|&&id| id == tokenizer.unk_id()).count(),
+        ids,
+    })
+}
+
+/// Predict with transformer; `options` selects greedy, beam or sampled decoding
 #[tauri::command]
 pub fn predict_transformer(
     state: State<'_, TransformerState>,
     input: String,
+    options: Option<DecodeOptions>,
 ) -> Result<IntentPrediction, String> {
     let tf = state.transformer.lock().map_err(|e| e.to_string())?;
 
-    let (intent, prob) = tf.predict(&input);
+    let decoded = tf.predict_decoded(&input, &options.unwrap_or_default());
 
     Ok(IntentPrediction {
-        intent: intent.as_str().to_string(),
-        confidence: prob,
+        intent: decoded.intent.as_str().to_string(),
+        confidence: decoded.confidence,
         input,
+        best_guess: None,
+        candidates: decoded.candidates,
     })
 }
 
@@ -153,14 +688,57 @@ pub struct DiffusionState {
     pub denoiser: Mutex<DiffusionDenoiser>,
     pub pipeline: Mutex<TransformerWithDiffusion>,
     pub trained: Mutex<bool>,
+    /// Minimum confidence for each stage of predict_with_diffusion
+    pub thresholds: Mutex<FallbackThresholds>,
+}
+
+/// Confidence a stage needs before its answer is accepted; below it the
+/// next stage is tried (the rule-based preprocessor always answers)
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FallbackThresholds {
+    pub diffusion: f32,
+    pub transformer: f32,
+}
+
+impl Default for FallbackThresholds {
+    fn default() -> Self {
+        FallbackThresholds {
+            diffusion: 0.5,
+            transformer: 0.4,
+        }
+    }
+}
+
+/// Which stage of the fallback chain produced a prediction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PredictionStage {
+    Diffusion,
+    Transformer,
+    RuleBased,
+}
+
+/// One stage tried while predicting
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StageAttempt {
+    pub stage: PredictionStage,
+    pub intent: String,
+    pub confidence: f32,
+    pub accepted: bool,
 }
 
 impl Default for DiffusionState {
     fn default() -> Self {
+        let precision = load_chat_settings().inference_precision;
+        let mut denoiser = DiffusionDenoiser::new();
+        denoiser.set_precision(precision);
+        let mut pipeline = TransformerWithDiffusion::new();
+        pipeline.set_precision(precision);
         DiffusionState {
-            denoiser: Mutex::new(DiffusionDenoiser::new()),
-            pipeline: Mutex::new(TransformerWithDiffusion::new()),
+            denoiser: Mutex::new(denoiser),
+            pipeline: Mutex::new(pipeline),
             trained: Mutex::new(false),
+            thresholds: Mutex::new(load_chat_settings().fallback_thresholds),
         }
     }
 }
@@ -186,31 +764,164 @@ pub fn train_diffusion_pipeline(state: State<'_, DiffusionState>) -> Result<Stri
     ))
 }
 
-/// Predict with diffusion refinement - returns intent + extracted parameters
-#[tauri::command]
-pub fn predict_with_diffusion(
-    state: State<'_, DiffusionState>,
+/// Parameter named in the command with the number given for it, taken
+/// verbatim ("set grid to 600" -> grid = 600)
+fn slot_parameters(slots: &CommandSlots) -> Vec<ExtractedParameter> {
+    match (&slots.field, slots.numbers.first()) {
+        (Some(field), Some(number)) => vec![ExtractedParameter {
+            name: field.clone(),
+            value: number.value as f32,
+            confidence: 1.0,
+            is_denoised: false,
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Diffusion, then transformer, then the rule-based preprocessor: the first
+/// stage reaching its threshold answers. Later stages only run when needed.
+fn run_fallback_chain(
     input: String,
+    thresholds: &FallbackThresholds,
+    diffusion: impl FnOnce(&str) -> Result<(String, f32, Vec<ExtractedParameter>), String>,
+    transformer: impl FnOnce(&str) -> Result<(String, f32), String>,
 ) -> Result<DiffusionPrediction, String> {
-    let pipeline = state.pipeline.lock().map_err(|e| e.to_string())?;
+    let known = |intent: &str| intent != TransformerIntent::Unknown.as_str();
+    let mut attempts = Vec::new();
+
+    let (intent, intent_confidence, parameters) = diffusion(&input)?;
+    let confidence = refinement_confidence(intent_confidence, &parameters);
+    let accepted = known(&intent) && confidence >= thresholds.diffusion;
+    attempts.push(StageAttempt {
+        stage: PredictionStage::Diffusion,
+        intent: intent.clone(),
+        confidence,
+        accepted,
+    });
+    if accepted {
+        return Ok(DiffusionPrediction {
+            intent,
+            confidence,
+            parameters,
+            input,
+            stage: PredictionStage::Diffusion,
+            attempts,
+        });
+    }
 
-    let (intent, confidence, params) = pipeline.predict_with_params(&input);
+    let extraction = crate::chat_preprocessor::extract_command(&input);
+    let (intent, confidence) = transformer(&input)?;
+    let accepted = known(&intent) && confidence >= thresholds.transformer;
+    attempts.push(StageAttempt {
+        stage: PredictionStage::Transformer,
+        intent: intent.clone(),
+        confidence,
+        accepted,
+    });
+    if accepted {
+        return Ok(DiffusionPrediction {
+            intent,
+            confidence,
+            parameters: slot_parameters(&extraction.slots),
+            input,
+            stage: PredictionStage::Transformer,
+            attempts,
+        });
+    }
 
+    let rule_intent = if extraction.is_greeting {
+        None
+    } else {
+        crate::chat_preprocessor::rule_based_intent(&extraction.command)
+    };
+    let (intent, confidence) = match rule_intent {
+        Some(intent) => (intent.to_string(), extraction.confidence),
+        None => (TransformerIntent::Unknown.as_str().to_string(), 0.0),
+    };
+    attempts.push(StageAttempt {
+        stage: PredictionStage::RuleBased,
+        intent: intent.clone(),
+        confidence,
+        accepted: true,
+    });
     Ok(DiffusionPrediction {
         intent,
         confidence,
-        parameters: params,
+        parameters: slot_parameters(&extraction.slots),
         input,
+        stage: PredictionStage::RuleBased,
+        attempts,
     })
 }
 
+/// Predict with diffusion refinement - returns intent + extracted parameters.
+/// Low-confidence answers fall back to the transformer, then to the
+/// rule-based preprocessor; `stage` says which one answered.
+#[tauri::command]
+pub fn predict_with_diffusion(
+    state: State<'_, DiffusionState>,
+    transformer: State<'_, TransformerState>,
+    input: String,
+) -> Result<DiffusionPrediction, String> {
+    let thresholds = *state.thresholds.lock().map_err(|e| e.to_string())?;
+    run_fallback_chain(
+        input,
+        &thresholds,
+        |input| {
+            let pipeline = state.pipeline.lock().map_err(|e| e.to_string())?;
+            Ok(pipeline.predict_with_params(input))
+        },
+        |input| {
+            let tf = transformer.transformer.lock().map_err(|e| e.to_string())?;
+            let (intent, confidence) = tf.predict(input);
+            Ok((intent.as_str().to_string(), confidence))
+        },
+    )
+}
+
 /// Result of diffusion-enhanced prediction
 #[derive(serde::Serialize)]
 pub struct DiffusionPrediction {
     pub intent: String,
+    /// Confidence of the stage that answered
     pub confidence: f32,
     pub parameters: Vec<ExtractedParameter>,
     pub input: String,
+    pub stage: PredictionStage,
+    /// Every stage tried, in order
+    pub attempts: Vec<StageAttempt>,
+}
+
+/// Set the acceptance thresholds of the diffusion fallback chain
+#[tauri::command]
+pub fn set_fallback_thresholds(
+    state: State<'_, DiffusionState>,
+    thresholds: FallbackThresholds,
+) -> Result<FallbackThresholds, String> {
+    for (stage, value) in [
+        ("diffusion", thresholds.diffusion),
+        ("transformer", thresholds.transformer),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!(
+                "{} threshold {} must be between 0 and 1",
+                stage, value
+            ));
+        }
+    }
+    save_chat_settings(&ChatSettings {
+        fallback_thresholds: thresholds,
+        ..load_chat_settings()
+    })?;
+    *state.thresholds.lock().map_err(|e| e.to_string())? = thresholds;
+    Ok(thresholds)
+}
+
+#[tauri::command]
+pub fn get_fallback_thresholds(
+    state: State<'_, DiffusionState>,
+) -> Result<FallbackThresholds, String> {
+    Ok(*state.thresholds.lock().map_err(|e| e.to_string())?)
 }
 
 /// Extract a specific parameter with diffusion denoising
@@ -223,3 +934,278 @@ pub fn extract_parameter(
     let denoiser = denoiser.denoiser.lock().map_err(|e| e.to_string())?;
     Ok(denoiser.extract_parameter(&text, &param_name))
 }
+
+// ============================================
+// QUANTIZED INFERENCE
+// ============================================
+
+/// Run the transformer and diffusion models on f32 or int8 weights; the
+/// choice is saved and applied again at startup
+#[tauri::command]
+pub fn set_inference_precision(
+    transformer: State<'_, TransformerState>,
+    diffusion: State<'_, DiffusionState>,
+    precision: Precision,
+) -> Result<Precision, String> {
+    save_chat_settings(&ChatSettings {
+        inference_precision: precision,
+        ..load_chat_settings()
+    })?;
+    transformer
+        .transformer
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_precision(precision);
+    diffusion
+        .denoiser
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_precision(precision);
+    diffusion
+        .pipeline
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_precision(precision);
+    Ok(precision)
+}
+
+#[tauri::command]
+pub fn get_inference_precision(state: State<'_, TransformerState>) -> Result<Precision, String> {
+    Ok(state.transformer.lock().map_err(|e| e.to_string())?.precision())
+}
+
+/// Latency of one precision in a benchmark run
+#[derive(serde::Serialize)]
+pub struct PrecisionTiming {
+    pub precision: Precision,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    /// Weight storage read per inference
+    pub weight_bytes: usize,
+    pub intent: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct InferenceBenchmark {
+    pub input: String,
+    pub iterations: usize,
+    pub f32: PrecisionTiming,
+    pub int8: PrecisionTiming,
+    /// f32 mean latency / int8 mean latency
+    pub speedup: f64,
+    /// Largest difference between the two intent distributions
+    pub max_probability_diff: f32,
+    pub same_intent: bool,
+}
+
+fn time_precision(
+    tf: &mut TradingTransformer,
+    precision: Precision,
+    input: &str,
+    iterations: usize,
+) -> (PrecisionTiming, Vec<f32>) {
+    tf.set_precision(precision);
+    let probs = tf.forward(input);
+    let mut samples: Vec<f64> = (0..iterations)
+        .map(|_| {
+            let started = std::time::Instant::now();
+            std::hint::black_box(tf.forward(input));
+            started.elapsed().as_secs_f64() * 1000.0
+        })
+        .collect();
+    samples.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f64| samples[((samples.len() - 1) as f64 * p).round() as usize];
+    let (intent, _) = tf.predict(input);
+    let timing = PrecisionTiming {
+        precision,
+        mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+        p50_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        weight_bytes: tf.inference_weight_bytes(),
+        intent: intent.as_str().to_string(),
+    };
+    (timing, probs)
+}
+
+/// Time transformer inference on f32 and int8 weights with the same input,
+/// then restore the configured precision
+#[tauri::command]
+pub fn benchmark_inference(
+    state: State<'_, TransformerState>,
+    iterations: Option<usize>,
+    input: Option<String>,
+) -> Result<InferenceBenchmark, String> {
+    let iterations = iterations.unwrap_or(20).clamp(1, 1000);
+    let input = input.unwrap_or_else(|| "set grid to 600 on power buy group 1".to_string());
+    let mut tf = state.transformer.lock().map_err(|e| e.to_string())?;
+    let configured = tf.precision();
+
+    let (f32_timing, f32_probs) = time_precision(&mut tf, Precision::F32, &input, iterations);
+    let (int8_timing, int8_probs) = time_precision(&mut tf, Precision::Int8, &input, iterations);
+    tf.set_precision(configured);
+
+    let max_probability_diff = f32_probs
+        .iter()
+        .zip(&int8_probs)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    Ok(InferenceBenchmark {
+        speedup: f32_timing.mean_ms / int8_timing.mean_ms.max(f64::EPSILON),
+        same_intent: f32_timing.intent == int8_timing.intent,
+        max_probability_diff,
+        input,
+        iterations,
+        f32: f32_timing,
+        int8: int8_timing,
+    })
+}
+
+// ============================================
+// ONNX EXPORT / IMPORT
+// ============================================
+
+/// Export "chat" (the intent network) or "transformer" as an ONNX file
+#[tauri::command]
+pub fn export_model_onnx(
+    chat: State<'_, ChatNeuralState>,
+    transformer: State<'_, TransformerState>,
+    model: String,
+    path: String,
+) -> Result<String, String> {
+    let path = crate::mt_bridge::sanitize_and_validate_path(&PathBuf::from(path))?;
+    let onnx = match model.trim().to_ascii_lowercase().as_str() {
+        "chat" => {
+            let net = chat.network.lock().map_err(|e| e.to_string())?;
+            let trained = *chat.trained.lock().map_err(|e| e.to_string())?;
+            crate::onnx::export_chat_model(&net, trained)
+        }
+        "transformer" => {
+            let tf = transformer.transformer.lock().map_err(|e| e.to_string())?;
+            crate::onnx::export_transformer(&tf)
+        }
+        other => return Err(format!("Unknown model '{}' (use chat or transformer)", other)),
+    };
+    crate::mt_bridge::atomic_write(&path, onnx.to_bytes())?;
+    Ok(format!(
+        "Exported {} model ({} tensors) to {:?}",
+        model,
+        onnx.initializers.len(),
+        path
+    ))
+}
+
+/// Replace the chat network or transformer weights with an ONNX file
+/// laid out like the export (see crate::onnx for tensor names)
+#[tauri::command]
+pub fn import_model_onnx(
+    chat: State<'_, ChatNeuralState>,
+    transformer: State<'_, TransformerState>,
+    model: String,
+    path: String,
+) -> Result<String, String> {
+    let path = crate::mt_bridge::sanitize_and_validate_path(&PathBuf::from(path))?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read ONNX file: {}", e))?;
+    let onnx = crate::onnx::OnnxModel::from_bytes(&bytes)?;
+    match model.trim().to_ascii_lowercase().as_str() {
+        "chat" => {
+            let mut net = chat.network.lock().map_err(|e| e.to_string())?;
+            let imported = crate::onnx::import_chat_model(&onnx, &net)?;
+            *net = imported;
+            drop(net);
+            let trained = onnx.metadata.get("daavfx.trained").map_or(true, |t| t != "false");
+            *chat.trained.lock().map_err(|e| e.to_string())? = trained;
+            autosave(&chat);
+        }
+        "transformer" => {
+            let mut tf = transformer.transformer.lock().map_err(|e| e.to_string())?;
+            crate::onnx::import_transformer(&onnx, &mut tf)?;
+            *transformer.trained.lock().map_err(|e| e.to_string())? = true;
+        }
+        other => return Err(format!("Unknown model '{}' (use chat or transformer)", other)),
+    }
+    Ok(format!("Imported {} model from {:?}", model, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain_stages() {
+        let thresholds = FallbackThresholds::default();
+        let grid = |confidence| ExtractedParameter {
+            name: "grid".to_string(),
+            value: 600.0,
+            confidence,
+            is_denoised: true,
+        };
+        let diffusion_says = |confidence: f32, param: f32| {
+            move |_: &str| Ok::<_, String>(("SET".to_string(), confidence, vec![grid(param)]))
+        };
+        let untouched = |_: &str| -> Result<(String, f32), String> {
+            panic!("transformer should not run")
+        };
+
+        let confident = run_fallback_chain(
+            "set grid to 600".into(),
+            &thresholds,
+            diffusion_says(0.9, 0.9),
+            untouched,
+        )
+        .unwrap();
+        assert_eq!(confident.stage, PredictionStage::Diffusion);
+        assert_eq!(confident.attempts.len(), 1);
+
+        // Implausible parameters drag diffusion below its threshold
+        let fallback = run_fallback_chain(
+            "set grid to 600".into(),
+            &thresholds,
+            diffusion_says(0.9, 0.2),
+            |_| Ok(("SET".to_string(), 0.7)),
+        )
+        .unwrap();
+        assert_eq!(fallback.stage, PredictionStage::Transformer);
+        assert_eq!(fallback.parameters[0].name, "grid");
+        assert!(!fallback.parameters[0].is_denoised);
+        assert!(!fallback.attempts[0].accepted);
+
+        let rules = run_fallback_chain(
+            "hey show grid".into(),
+            &thresholds,
+            diffusion_says(0.2, 0.9),
+            |_| Ok(("UNKNOWN".to_string(), 0.9)),
+        )
+        .unwrap();
+        assert_eq!(rules.stage, PredictionStage::RuleBased);
+        assert_eq!(rules.intent, "QUERY");
+        assert_eq!(rules.attempts.len(), 3);
+    }
+
+    #[test]
+    fn test_conversation_follow_ups() {
+        let mut conversation = Conversation::default();
+        let first = conversation.resolve("set grid to 250 on B3 scalp buy", Some("SET".to_string()));
+        assert!(!first.follow_up);
+
+        let same = conversation.resolve("now do the same for sell", None);
+        assert!(same.follow_up);
+        assert_eq!(same.intent.as_deref(), Some("SET"));
+        assert_eq!(same.slots.numbers[0].value, 250.0);
+        assert_eq!(
+            same.slots.config_path.as_deref(),
+            Some("engines.B.groups.3.logics.SCALPER.grid_s")
+        );
+
+        let doubled = conversation.resolve("make it 2x", None);
+        assert_eq!(doubled.slots.numbers[0].value, 500.0);
+        assert_eq!(doubled.slots.field.as_deref(), Some("grid"));
+        assert_eq!(doubled.slots.direction.as_deref(), Some("Sell"));
+
+        // A fresh, fully specified command replaces the focus
+        let other = conversation.resolve("set lot to 0.02 on A1 power", Some("SET".to_string()));
+        assert!(!other.follow_up);
+        assert_eq!(conversation.focus.engine.as_deref(), Some("A"));
+        assert_eq!(conversation.focus.field.as_deref(), Some("initial_lot"));
+    }
+}
@@ -0,0 +1,282 @@
+//! Labeled training data for the chat intent network
+//!
+//! The dataset lives as JSONL next to the saved models so examples can be
+//! reviewed, corrected and shared. It starts out as the built-in examples
+//! from `generate_training_data` and is only written once it is edited.
+
+use crate::chat_neural::{generate_training_data, Intent, TrainingExample};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const DATASET_FILE: &str = "chat_dataset.jsonl";
+
+/// One labeled example as stored on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetExample {
+    pub id: u64,
+    pub text: String,
+    pub intent: String,
+    /// Where the example came from, e.g. "builtin" or "user"
+    #[serde(default = "default_source")]
+    pub source: String,
+}
+
+fn default_source() -> String {
+    "user".to_string()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChatDataset {
+    pub examples: Vec<DatasetExample>,
+}
+
+pub fn dataset_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join("models")
+        .join(DATASET_FILE)
+}
+
+/// Canonical intent label ("SET", "QUERY", ...) for a user-supplied one
+pub fn normalize_intent(label: &str) -> Result<String, String> {
+    Intent::parse(label)
+        .map(|intent| intent.as_str().to_string())
+        .ok_or_else(|| format!("Unknown intent '{}'", label.trim()))
+}
+
+impl ChatDataset {
+    pub fn builtin() -> Self {
+        let examples = generate_training_data()
+            .into_iter()
+            .enumerate()
+            .map(|(i, ex)| DatasetExample {
+                id: i as u64 + 1,
+                text: ex.text,
+                intent: ex.intent.as_str().to_string(),
+                source: "builtin".to_string(),
+            })
+            .collect();
+        ChatDataset { examples }
+    }
+
+    /// The saved dataset, or the built-in examples if none was saved yet
+    pub fn load() -> Result<Self, String> {
+        let path = dataset_path();
+        if !path.exists() {
+            return Ok(Self::builtin());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read training dataset: {}", e))?;
+        let (dataset, errors) = Self::parse_jsonl(&content);
+        if let Some(first) = errors.first() {
+            return Err(format!("Training dataset is corrupt: {}", first));
+        }
+        Ok(dataset)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = dataset_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create models folder: {}", e))?;
+        }
+        crate::mt_bridge::atomic_write(&path, &self.to_jsonl()?)
+    }
+
+    pub fn to_jsonl(&self) -> Result<String, String> {
+        let mut out = String::new();
+        for example in &self.examples {
+            let line = serde_json::to_string(example)
+                .map_err(|e| format!("Failed to serialize example: {}", e))?;
+            out.push_str(&line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Parse JSONL; bad lines are reported as "line N: reason" and skipped.
+    /// Lines may omit `id`, which is then assigned.
+    pub fn parse_jsonl(content: &str) -> (Self, Vec<String>) {
+        #[derive(Deserialize)]
+        struct Line {
+            #[serde(default)]
+            id: Option<u64>,
+            text: String,
+            intent: String,
+            #[serde(default = "default_source")]
+            source: String,
+        }
+
+        let mut dataset = ChatDataset::default();
+        let mut errors = Vec::new();
+        for (n, raw) in content.lines().enumerate() {
+            if raw.trim().is_empty() {
+                continue;
+            }
+            let parsed = serde_json::from_str::<Line>(raw)
+                .map_err(|e| e.to_string())
+                .and_then(|line| normalize_intent(&line.intent).map(|intent| (intent, line)));
+            match parsed {
+                Ok((intent, line)) if !line.text.trim().is_empty() => {
+                    let id = line
+                        .id
+                        .filter(|id| dataset.get(*id).is_none())
+                        .unwrap_or_else(|| dataset.next_id());
+                    dataset.examples.push(DatasetExample {
+                        id,
+                        text: line.text.trim().to_string(),
+                        intent,
+                        source: line.source,
+                    });
+                }
+                Ok(_) => errors.push(format!("line {}: empty text", n + 1)),
+                Err(e) => errors.push(format!("line {}: {}", n + 1, e)),
+            }
+        }
+        (dataset, errors)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.examples.iter().map(|e| e.id).max().unwrap_or(0) + 1
+    }
+
+    pub fn get(&self, id: u64) -> Option<&DatasetExample> {
+        self.examples.iter().find(|e| e.id == id)
+    }
+
+    pub fn add(
+        &mut self,
+        text: &str,
+        intent: &str,
+        source: &str,
+    ) -> Result<DatasetExample, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Example text is required".to_string());
+        }
+        let example = DatasetExample {
+            id: self.next_id(),
+            text: text.to_string(),
+            intent: normalize_intent(intent)?,
+            source: source.to_string(),
+        };
+        self.examples.push(example.clone());
+        Ok(example)
+    }
+
+    pub fn update(
+        &mut self,
+        id: u64,
+        text: Option<&str>,
+        intent: Option<&str>,
+    ) -> Result<DatasetExample, String> {
+        let intent = intent.map(normalize_intent).transpose()?;
+        let example = self
+            .examples
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| format!("No training example with id {}", id))?;
+        if let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) {
+            example.text = text.to_string();
+        }
+        if let Some(intent) = intent {
+            example.intent = intent;
+        }
+        Ok(example.clone())
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<(), String> {
+        let before = self.examples.len();
+        self.examples.retain(|e| e.id != id);
+        if self.examples.len() == before {
+            return Err(format!("No training example with id {}", id));
+        }
+        Ok(())
+    }
+
+    /// Append imported examples with fresh ids, skipping exact duplicates
+    pub fn merge(&mut self, other: ChatDataset) -> usize {
+        let mut added = 0;
+        for example in other.examples {
+            let duplicate = self
+                .examples
+                .iter()
+                .any(|e| e.text == example.text && e.intent == example.intent);
+            if !duplicate {
+                let id = self.next_id();
+                self.examples.push(DatasetExample { id, ..example });
+                added += 1;
+            }
+        }
+        added
+    }
+
+    pub fn intent_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for example in &self.examples {
+            *counts.entry(example.intent.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn training_examples(&self) -> Vec<TrainingExample> {
+        self.examples
+            .iter()
+            .filter_map(|e| {
+                Some(TrainingExample {
+                    text: e.text.clone(),
+                    intent: Intent::parse(&e.intent)?,
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn read_dataset_file(path: &Path) -> Result<(ChatDataset, Vec<String>), String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read dataset file: {}", e))?;
+    Ok(ChatDataset::parse_jsonl(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_edit_and_jsonl_round_trip() {
+        let mut dataset = ChatDataset::builtin();
+        let builtin = dataset.examples.len();
+        let added = dataset.add("bump lots on group 3", "set", "user").unwrap();
+        assert_eq!(added.intent, "SET");
+        assert!(dataset.add("whatever", "dance", "user").is_err());
+
+        dataset.update(added.id, None, Some("query")).unwrap();
+        assert_eq!(dataset.get(added.id).unwrap().intent, "QUERY");
+
+        let (parsed, errors) = ChatDataset::parse_jsonl(&dataset.to_jsonl().unwrap());
+        assert!(errors.is_empty());
+        assert_eq!(parsed.examples, dataset.examples);
+
+        dataset.remove(added.id).unwrap();
+        assert_eq!(dataset.examples.len(), builtin);
+        assert!(dataset.remove(added.id).is_err());
+    }
+
+    #[test]
+    fn test_import_reports_bad_lines_and_skips_duplicates() {
+        let content = "{\"text\":\"show grid\",\"intent\":\"QUERY\"}\n\
+                       not json\n\
+                       {\"text\":\"copy A to B\",\"intent\":\"teleport\"}\n";
+        let (imported, errors) = ChatDataset::parse_jsonl(content);
+        assert_eq!(imported.examples.len(), 1);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("line 2"));
+
+        let mut dataset = ChatDataset::default();
+        assert_eq!(dataset.merge(imported.clone()), 1);
+        assert_eq!(dataset.merge(imported), 0);
+        assert_eq!(dataset.intent_counts().get("QUERY"), Some(&1));
+    }
+}
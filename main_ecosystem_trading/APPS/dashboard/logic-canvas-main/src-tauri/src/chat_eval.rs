@@ -0,0 +1,253 @@
+//! Evaluation of the chat intent network
+//!
+//! Trains fresh networks on part of the dataset and scores them on the rest,
+//! either k-fold or with a single held-out fraction. Predictions below the
+//! confidence threshold count as "UNCERTAIN" so a cutoff can be tuned against
+//! the same report predict_intent will honour.
+
+use crate::chat_neural::{TinyNeural, TrainingExample};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const UNCERTAIN: &str = "UNCERTAIN";
+
+#[derive(Debug, Clone)]
+pub struct EvalOptions {
+    /// Number of folds; ignored when `holdout` is set
+    pub folds: usize,
+    /// Fraction of examples held out for a single train/test split
+    pub holdout: Option<f32>,
+    pub epochs: usize,
+    pub learning_rate: f32,
+    pub threshold: f32,
+    pub seed: u64,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        EvalOptions {
+            folds: 5,
+            holdout: None,
+            epochs: 100,
+            learning_rate: 0.1,
+            threshold: 0.0,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentMetrics {
+    pub intent: String,
+    pub support: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationReport {
+    pub method: String,
+    pub evaluated: usize,
+    pub threshold: f32,
+    /// Share of all test examples predicted correctly (uncertain counts as wrong)
+    pub accuracy: f64,
+    /// Accuracy over the predictions that cleared the threshold
+    pub confident_accuracy: f64,
+    pub uncertain: usize,
+    pub per_intent: Vec<IntentMetrics>,
+    /// Row and column labels of the matrix; the last column may be UNCERTAIN
+    pub labels: Vec<String>,
+    /// confusion[actual][predicted]
+    pub confusion: Vec<Vec<usize>>,
+}
+
+/// Label for a prediction, or UNCERTAIN when it falls under the threshold
+pub fn label_prediction(intent: &str, confidence: f32, threshold: f32) -> String {
+    if confidence < threshold {
+        UNCERTAIN.to_string()
+    } else {
+        intent.to_string()
+    }
+}
+
+/// Test-set index groups for each split
+fn splits(len: usize, options: &EvalOptions) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..len).collect();
+    order.shuffle(&mut StdRng::seed_from_u64(options.seed));
+    match options.holdout {
+        Some(fraction) => {
+            let test = ((len as f32 * fraction.clamp(0.05, 0.5)).round() as usize).max(1);
+            vec![order[..test.min(len)].to_vec()]
+        }
+        None => {
+            let folds = options.folds.clamp(2, len.max(2));
+            (0..folds)
+                .map(|f| order.iter().copied().skip(f).step_by(folds).collect())
+                .collect()
+        }
+    }
+}
+
+/// Score (actual, predicted) label pairs
+pub fn build_report(
+    method: String,
+    threshold: f32,
+    pairs: &[(String, String)],
+) -> EvaluationReport {
+    let mut labels: Vec<String> = pairs.iter().map(|(actual, _)| actual.clone()).collect();
+    labels.extend(
+        pairs
+            .iter()
+            .map(|(_, predicted)| predicted.clone())
+            .filter(|p| p != UNCERTAIN),
+    );
+    labels.sort();
+    labels.dedup();
+    if pairs.iter().any(|(_, p)| p == UNCERTAIN) {
+        labels.push(UNCERTAIN.to_string());
+    }
+    let index: BTreeMap<&str, usize> = labels
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (l.as_str(), i))
+        .collect();
+
+    let mut confusion = vec![vec![0usize; labels.len()]; labels.len()];
+    for (actual, predicted) in pairs {
+        confusion[index[actual.as_str()]][index[predicted.as_str()]] += 1;
+    }
+
+    let ratio = |num: usize, den: usize| {
+        if den == 0 {
+            0.0
+        } else {
+            num as f64 / den as f64
+        }
+    };
+    let correct = pairs.iter().filter(|(a, p)| a == p).count();
+    let uncertain = pairs.iter().filter(|(_, p)| p == UNCERTAIN).count();
+
+    let per_intent = labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| label.as_str() != UNCERTAIN)
+        .map(|(i, label)| {
+            let tp = confusion[i][i];
+            let predicted: usize = confusion.iter().map(|row| row[i]).sum();
+            let support: usize = confusion[i].iter().sum();
+            let precision = ratio(tp, predicted);
+            let recall = ratio(tp, support);
+            let f1 = if precision + recall > 0.0 {
+                2.0 * precision * recall / (precision + recall)
+            } else {
+                0.0
+            };
+            IntentMetrics {
+                intent: label.clone(),
+                support,
+                precision,
+                recall,
+                f1,
+            }
+        })
+        .filter(|m| m.support > 0)
+        .collect();
+
+    EvaluationReport {
+        method,
+        evaluated: pairs.len(),
+        threshold,
+        accuracy: ratio(correct, pairs.len()),
+        confident_accuracy: ratio(correct, pairs.len() - uncertain),
+        uncertain,
+        per_intent,
+        labels,
+        confusion,
+    }
+}
+
+/// Train and score fresh networks over the configured splits
+pub fn evaluate(
+    examples: &[TrainingExample],
+    options: &EvalOptions,
+) -> Result<EvaluationReport, String> {
+    if examples.len() < 4 {
+        return Err("Need at least 4 training examples to evaluate".to_string());
+    }
+    let test_sets = splits(examples.len(), options);
+    let mut pairs = Vec::new();
+    for test in &test_sets {
+        let train: Vec<TrainingExample> = examples
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !test.contains(i))
+            .map(|(_, ex)| ex.clone())
+            .collect();
+        let mut net = TinyNeural::new();
+        net.train(&train, options.epochs, options.learning_rate);
+        for &i in test {
+            let (intent, confidence) = net.predict(&examples[i].text);
+            pairs.push((
+                examples[i].intent.as_str().to_string(),
+                label_prediction(intent.as_str(), confidence, options.threshold),
+            ));
+        }
+    }
+    let method = match options.holdout {
+        Some(fraction) => format!("holdout {:.0}%", fraction.clamp(0.05, 0.5) * 100.0),
+        None => format!("{}-fold", test_sets.len()),
+    };
+    Ok(build_report(method, options.threshold, &pairs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_metrics_and_uncertain_column() {
+        let pair = |a: &str, p: &str| (a.to_string(), p.to_string());
+        let pairs = vec![
+            pair("SET", "SET"),
+            pair("SET", "SET"),
+            pair("SET", "QUERY"),
+            pair("QUERY", "QUERY"),
+            pair("QUERY", UNCERTAIN),
+        ];
+        let report = build_report("test".to_string(), 0.5, &pairs);
+        assert_eq!(report.labels, vec!["QUERY", "SET", UNCERTAIN]);
+        assert_eq!(report.confusion[1], vec![1, 2, 0]);
+        assert!((report.accuracy - 0.6).abs() < 1e-9);
+        assert!((report.confident_accuracy - 0.75).abs() < 1e-9);
+        assert_eq!(report.uncertain, 1);
+
+        let set = report
+            .per_intent
+            .iter()
+            .find(|m| m.intent == "SET")
+            .unwrap();
+        assert!((set.precision - 1.0).abs() < 1e-9);
+        assert!((set.recall - 2.0 / 3.0).abs() < 1e-9);
+        let query = report
+            .per_intent
+            .iter()
+            .find(|m| m.intent == "QUERY")
+            .unwrap();
+        assert!((query.precision - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kfold_splits_cover_every_example_once() {
+        let options = EvalOptions::default();
+        let mut seen: Vec<usize> = splits(23, &options).into_iter().flatten().collect();
+        seen.sort();
+        assert_eq!(seen, (0..23).collect::<Vec<_>>());
+        assert_eq!(label_prediction("SET", 0.2, 0.4), UNCERTAIN);
+    }
+}
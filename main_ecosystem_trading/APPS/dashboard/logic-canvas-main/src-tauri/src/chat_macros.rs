@@ -0,0 +1,351 @@
+// ============================================
+// CHAT MACROS
+// ============================================
+//
+// While recording, every chat intent applied through apply_chat_intent is
+// appended as a step. The stopped recording is saved under a name in app
+// data and can be replayed against any config ("apply my conservative
+// setup"). Steps store intent + slots, not values resolved against the
+// config they were recorded on, so a macro stays meaningful elsewhere.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::chat_apply::{apply_slots, ChatChange};
+use crate::chat_preprocessor::CommandSlots;
+use crate::field_locks::LockViolation;
+use crate::mt_bridge::{atomic_write, MTConfig};
+
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::enforce_field_locks;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+const CHAT_MACROS_FILE: &str = "chat_macros.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStep {
+    pub intent: String,
+    pub slots: CommandSlots,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMacro {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<MacroStep>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStepResult {
+    pub intent: String,
+    pub changes: Vec<ChatChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroRunResult {
+    pub name: String,
+    pub applied: bool,
+    pub dry_run: bool,
+    pub steps: Vec<MacroStepResult>,
+    /// Locked fields the macro touches (only returned in warn mode)
+    pub violations: Vec<LockViolation>,
+    pub message: String,
+    /// Updated config when the macro was applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<MTConfig>,
+}
+
+fn chat_macros_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(CHAT_MACROS_FILE)
+}
+
+/// Macros are looked up case- and whitespace-insensitively
+fn macro_key(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+pub fn load_macros() -> Result<BTreeMap<String, ChatMacro>, String> {
+    let path = chat_macros_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read macros: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse macros: {}", e))
+}
+
+fn save_macros(macros: &BTreeMap<String, ChatMacro>) -> Result<(), String> {
+    let path = chat_macros_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(macros)
+        .map_err(|e| format!("Failed to serialize macros: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+pub fn find_macro(name: &str) -> Result<ChatMacro, String> {
+    load_macros()?
+        .remove(&macro_key(name))
+        .ok_or_else(|| format!("No macro named '{}'", name.trim()))
+}
+
+/// Saved macro a chat command asks for ("apply my conservative setup",
+/// "run macro scalp tweaks"), matched against the macro names
+pub fn macro_in_command<'a>(
+    command: &str,
+    macros: &'a BTreeMap<String, ChatMacro>,
+) -> Option<&'a ChatMacro> {
+    let words: Vec<String> = macro_key(command)
+        .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect();
+    let first = words.first()?.as_str();
+    if !["apply", "run", "play", "replay", "use", "load"].contains(&first) {
+        return None;
+    }
+    let rest: Vec<&str> = words[1..]
+        .iter()
+        .map(String::as_str)
+        .filter(|w| !["my", "the", "macro"].contains(w))
+        .collect();
+    let wanted = rest.join(" ");
+    macros.get(&wanted).or_else(|| {
+        // "apply my conservative setup" for a macro saved as "conservative"
+        macros
+            .iter()
+            .filter(|(key, _)| wanted.starts_with(&format!("{} ", key)))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, m)| m)
+    })
+}
+
+/// Replay every step on `config`; a failing step aborts the whole run
+pub fn run_steps(
+    config: &mut MTConfig,
+    steps: &[MacroStep],
+) -> Result<Vec<MacroStepResult>, String> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let changes = apply_slots(config, &step.intent, &step.slots)
+                .map_err(|e| format!("Step {} ({}): {}", i + 1, step.intent, e))?;
+            Ok(MacroStepResult {
+                intent: step.intent.clone(),
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// Start recording applied chat commands into a new macro
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn record_macro_start(
+    name: String,
+    description: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<(), String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("Macro name is required".to_string());
+    }
+    let mut recording = state.macro_recording.write().await;
+    if let Some(current) = recording.as_ref() {
+        return Err(format!("Already recording macro '{}'", current.name));
+    }
+    *recording = Some(ChatMacro {
+        name,
+        description: description.unwrap_or_default(),
+        steps: Vec::new(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    });
+    Ok(())
+}
+
+/// Stop recording and save the macro; `discard` drops it instead
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn record_macro_stop(
+    discard: Option<bool>,
+    state: State<'_, MTBridgeState>,
+) -> Result<Option<ChatMacro>, String> {
+    let recorded = state
+        .macro_recording
+        .write()
+        .await
+        .take()
+        .ok_or_else(|| "No macro is being recorded".to_string())?;
+    if discard.unwrap_or(false) {
+        return Ok(None);
+    }
+    if recorded.steps.is_empty() {
+        return Err(format!(
+            "Macro '{}' recorded no changes and was not saved",
+            recorded.name
+        ));
+    }
+    let mut macros = load_macros()?;
+    macros.insert(macro_key(&recorded.name), recorded.clone());
+    save_macros(&macros)?;
+    Ok(Some(recorded))
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn list_macros() -> Result<Vec<ChatMacro>, String> {
+    Ok(load_macros()?.into_values().collect())
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn delete_macro(name: String) -> Result<(), String> {
+    let mut macros = load_macros()?;
+    macros
+        .remove(&macro_key(&name))
+        .ok_or_else(|| format!("No macro named '{}'", name.trim()))?;
+    save_macros(&macros)
+}
+
+/// Name of the saved macro a chat message asks to run, if any
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn match_macro_command(input: String) -> Result<Option<String>, String> {
+    let command = crate::chat_preprocessor::preprocess(&input);
+    Ok(macro_in_command(&command, &load_macros()?).map(|m| m.name.clone()))
+}
+
+/// Replay a saved macro against `config` (default: the config behind `handle`).
+/// With `dry_run` only the per-step change preview is returned.
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn run_macro(
+    name: String,
+    dry_run: Option<bool>,
+    config: Option<MTConfig>,
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<MacroRunResult, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let chat_macro = find_macro(&name)?;
+    let current = match config {
+        Some(config) => config,
+        None => state
+            .configs
+            .read()
+            .await
+            .get(handle.as_deref())
+            .map(|open| open.config.clone())
+            .map_err(|_| "No config loaded to run the macro on".to_string())?,
+    };
+    let mut updated = current.clone();
+    let steps = run_steps(&mut updated, &chat_macro.steps)?;
+    let changed: usize = steps.iter().map(|s| s.changes.len()).sum();
+    let violations = if changed == 0 {
+        Vec::new()
+    } else {
+        enforce_field_locks(&state, &updated).await?
+    };
+
+    let apply = !dry_run && changed > 0;
+    if apply {
+        crate::chat_apply::commit_change(&state, handle.as_deref(), current, updated.clone())
+            .await?;
+    }
+
+    let message = if changed == 0 {
+        format!("Macro '{}': nothing to change", chat_macro.name)
+    } else if apply {
+        format!("Macro '{}' changed {} value(s)", chat_macro.name, changed)
+    } else {
+        format!(
+            "Macro '{}' would change {} value(s)",
+            chat_macro.name, changed
+        )
+    };
+    Ok(MacroRunResult {
+        name: chat_macro.name,
+        applied: apply,
+        dry_run,
+        steps,
+        violations,
+        message,
+        config: apply.then_some(updated),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat_preprocessor::extract_slots;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn step(intent: &str, command: &str) -> MacroStep {
+        MacroStep {
+            intent: intent.to_string(),
+            slots: extract_slots(command),
+        }
+    }
+
+    #[test]
+    fn test_run_steps_replays_in_order_and_aborts_on_error() {
+        let steps = vec![
+            step("SET", "set grid to 300 on A1 power buy"),
+            step("SET", "set lot to 0.02 on A1 power buy"),
+        ];
+        let mut config = create_full_v19_config();
+        let results = run_steps(&mut config, &steps).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].changes[0].logic_id, "A_POWER_B_G1");
+        assert_eq!(results[1].changes[0].new_value, 0.02);
+
+        let bad = vec![steps[0].clone(), step("SET", "set lot to 500 on A1 power")];
+        let err = run_steps(&mut create_full_v19_config(), &bad).unwrap_err();
+        assert!(err.starts_with("Step 2"));
+        assert_eq!(
+            macro_key("  My  Conservative Setup"),
+            "my conservative setup"
+        );
+    }
+
+    #[test]
+    fn test_macro_in_command() {
+        let mut macros = BTreeMap::new();
+        for name in ["conservative", "conservative setup", "scalp tweaks"] {
+            macros.insert(
+                macro_key(name),
+                ChatMacro {
+                    name: name.to_string(),
+                    description: String::new(),
+                    steps: Vec::new(),
+                    created_at: String::new(),
+                },
+            );
+        }
+        let found = |cmd: &str| macro_in_command(cmd, &macros).map(|m| m.name.as_str());
+        assert_eq!(
+            found("apply my conservative setup"),
+            Some("conservative setup")
+        );
+        assert_eq!(found("run macro Scalp Tweaks"), Some("scalp tweaks"));
+        assert_eq!(found("apply my conservative one"), Some("conservative"));
+        assert_eq!(found("set grid to 100"), None);
+        assert_eq!(found("apply my aggressive setup"), None);
+    }
+}
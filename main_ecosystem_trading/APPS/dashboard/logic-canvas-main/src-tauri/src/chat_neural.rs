@@ -18,6 +18,8 @@ const EMBED_DIM: usize = 32;
 const HIDDEN_DIM: usize = 64;
 /// Number of output intents
 const NUM_INTENTS: usize = 16;
+/// Version written into saved models
+pub const MODEL_FORMAT_VERSION: u32 = 1;
 
 /// Intent types for trading config commands
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
@@ -64,6 +66,14 @@ impl Intent {
             Intent::Unknown => "UNKNOWN",
         }
     }
+
+    /// Parse a label written by `as_str` (case-insensitive)
+    pub fn parse(label: &str) -> Option<Self> {
+        let label = label.trim().to_ascii_uppercase();
+        (0..NUM_INTENTS)
+            .map(Intent::from_index)
+            .find(|intent| intent.as_str() == label)
+    }
 }
 
 /// Character vocabulary for the model
@@ -118,7 +128,7 @@ impl Vocabulary {
 }
 
 /// Training example: input text + expected intent
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TrainingExample {
     pub text: String,
     pub intent: Intent,
@@ -387,28 +397,114 @@ impl TinyNeural {
         }
     }
 
-    /// Save model to file
-    pub fn save(&self, path: &PathBuf) -> Result<(), std::io::Error> {
-        let mut content = String::new();
-        content.push_str(&format!("VOCAB_SIZE:{}\n", self.vocab.idx_to_char.len()));
-
-        // Save embeddings
-        content.push_str("EMBEDDINGS:\n");
-        for (i, v) in self.embeddings.iter().enumerate() {
-            content.push_str(&format!("{},", v));
-            if (i + 1) % EMBED_DIM == 0 {
-                content.push('\n');
-            }
+    /// Vocabulary characters in token-id order (the '<' pad and '>' end last)
+    pub fn vocab_chars(&self) -> String {
+        let mut chars: Vec<(&char, &usize)> = self.vocab.char_to_idx.iter().collect();
+        chars.sort_by_key(|(_, &idx)| idx);
+        chars.into_iter().map(|(&c, _)| c).collect()
+    }
+
+    /// Capture the weights and learned corrections in the versioned format
+    pub fn to_snapshot(&self, trained: bool) -> ModelSnapshot {
+        ModelSnapshot {
+            format_version: MODEL_FORMAT_VERSION,
+            saved_at: chrono::Utc::now().to_rfc3339(),
+            trained,
+            vocab_size: self.vocab.idx_to_char.len(),
+            embed_dim: EMBED_DIM,
+            hidden_dim: HIDDEN_DIM,
+            max_seq_len: MAX_SEQ_LEN,
+            num_intents: NUM_INTENTS,
+            embeddings: self.embeddings.clone(),
+            fc1_weights: self.fc1_weights.clone(),
+            fc1_bias: self.fc1_bias.clone(),
+            fc2_weights: self.fc2_weights.clone(),
+            fc2_bias: self.fc2_bias.clone(),
+            output_weights: self.output_weights.clone(),
+            output_bias: self.output_bias.clone(),
+            corrections: self.corrections.clone(),
         }
+    }
 
-        // Save corrections
-        content.push_str("\nCORRECTIONS:\n");
-        for (k, v) in &self.corrections {
-            content.push_str(&format!("{}:{}\n", k, v));
+    /// Rebuild a network from a snapshot, rejecting newer formats and
+    /// snapshots taken with different layer sizes
+    pub fn from_snapshot(snapshot: ModelSnapshot) -> Result<Self, String> {
+        if snapshot.format_version > MODEL_FORMAT_VERSION {
+            return Err(format!(
+                "Model format v{} is newer than supported v{}",
+                snapshot.format_version, MODEL_FORMAT_VERSION
+            ));
         }
+        let vocab = Vocabulary::new();
+        let vocab_size = vocab.idx_to_char.len();
+        let expected = [
+            (snapshot.vocab_size, vocab_size),
+            (snapshot.embed_dim, EMBED_DIM),
+            (snapshot.hidden_dim, HIDDEN_DIM),
+            (snapshot.max_seq_len, MAX_SEQ_LEN),
+            (snapshot.num_intents, NUM_INTENTS),
+            (snapshot.embeddings.len(), vocab_size * EMBED_DIM),
+            (snapshot.fc1_weights.len(), MAX_SEQ_LEN * EMBED_DIM * HIDDEN_DIM),
+            (snapshot.fc1_bias.len(), HIDDEN_DIM),
+            (snapshot.fc2_weights.len(), HIDDEN_DIM * HIDDEN_DIM),
+            (snapshot.fc2_bias.len(), HIDDEN_DIM),
+            (snapshot.output_weights.len(), HIDDEN_DIM * NUM_INTENTS),
+            (snapshot.output_bias.len(), NUM_INTENTS),
+        ];
+        if expected.iter().any(|(got, want)| got != want) {
+            return Err("Model architecture does not match this build".to_string());
+        }
+        Ok(TinyNeural {
+            vocab,
+            embeddings: snapshot.embeddings,
+            fc1_weights: snapshot.fc1_weights,
+            fc1_bias: snapshot.fc1_bias,
+            fc2_weights: snapshot.fc2_weights,
+            fc2_bias: snapshot.fc2_bias,
+            output_weights: snapshot.output_weights,
+            output_bias: snapshot.output_bias,
+            corrections: snapshot.corrections,
+        })
+    }
 
-        fs::write(path, content)
+    /// Save model to file
+    pub fn save(&self, path: &PathBuf, trained: bool) -> Result<(), String> {
+        let json = serde_json::to_string(&self.to_snapshot(trained))
+            .map_err(|e| format!("Failed to serialize model: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write model: {}", e))
     }
+
+    /// Load a model saved with `save`; returns the network and its trained flag
+    pub fn load(path: &PathBuf) -> Result<(Self, bool), String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read model: {}", e))?;
+        let snapshot: ModelSnapshot =
+            serde_json::from_str(&json).map_err(|e| format!("Failed to parse model: {}", e))?;
+        let trained = snapshot.trained;
+        Ok((Self::from_snapshot(snapshot)?, trained))
+    }
+}
+
+/// On-disk model format. Bump MODEL_FORMAT_VERSION when fields change meaning.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ModelSnapshot {
+    pub format_version: u32,
+    pub saved_at: String,
+    #[serde(default)]
+    pub trained: bool,
+    pub vocab_size: usize,
+    pub embed_dim: usize,
+    pub hidden_dim: usize,
+    pub max_seq_len: usize,
+    pub num_intents: usize,
+    pub embeddings: Vec<f32>,
+    pub fc1_weights: Vec<f32>,
+    pub fc1_bias: Vec<f32>,
+    pub fc2_weights: Vec<f32>,
+    pub fc2_bias: Vec<f32>,
+    pub output_weights: Vec<f32>,
+    pub output_bias: Vec<f32>,
+    #[serde(default)]
+    pub corrections: HashMap<String, String>,
 }
 
 /// Generate training data from semantic engine rules
@@ -960,4 +1056,24 @@ mod tests {
         // Should still recognize as SET even with typo
         assert!(matches!(result.0, Intent::Set));
     }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut net = TinyNeural::new();
+        net.train(&generate_training_data(), 5, 0.1);
+        net.learn_correction("too", "to");
+        let before = net.forward("set grid too 600");
+
+        let path = std::env::temp_dir().join(format!("chat_model_{}.json", std::process::id()));
+        net.save(&path, true).unwrap();
+        let (loaded, trained) = TinyNeural::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(trained);
+        assert_eq!(loaded.forward("set grid too 600"), before);
+
+        let mut newer = net.to_snapshot(true);
+        newer.format_version = MODEL_FORMAT_VERSION + 1;
+        assert!(TinyNeural::from_snapshot(newer).is_err());
+    }
 }
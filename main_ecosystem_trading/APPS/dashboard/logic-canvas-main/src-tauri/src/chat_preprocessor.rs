@@ -8,8 +8,14 @@
 //! - "hi, can you change multiplier to 1.5?" → "change multiplier to 1.5"
 //! - "what's the grid?" → "what's the grid"
 //! - "bro make it more aggressive" → "make it more aggressive"
+//!
+//! A second pass pulls typed slots out of the command (numbers with units,
+//! engine/group/logic references, direction), so
+//! "set grid to 250 on B3 scalp sell" maps to
+//! `engines.B.groups.3.logics.SCALPER.grid_s`.
 
 use regex::Regex;
+use std::sync::OnceLock;
 
 /// Greeting patterns to strip
 static GREETING_PATTERNS: &[(&str, &str)] = &[
@@ -67,12 +73,67 @@ static COMMAND_STARTERS: &[&str] = &[
     "increase", "decrease", "multiply", "add", "subtract",
 ];
 
+/// Leading verb -> intent, for when no model is confident enough
+static INTENT_KEYWORDS: &[(&str, &str)] = &[
+    ("set", "SET"), ("change", "SET"), ("update", "SET"), ("modify", "SET"),
+    ("adjust", "SET"), ("increase", "SET"), ("decrease", "SET"),
+    ("multiply", "SET"), ("add", "SET"), ("subtract", "SET"),
+    ("show", "QUERY"), ("display", "QUERY"), ("list", "QUERY"), ("query", "QUERY"),
+    ("find", "QUERY"), ("search", "QUERY"), ("what", "QUERY"), ("what's", "QUERY"),
+    ("whats", "QUERY"),
+    ("make", "SEMANTIC"),
+    ("copy", "COPY"), ("clone", "COPY"), ("duplicate", "COPY"),
+    ("compare", "COMPARE"), ("diff", "COMPARE"),
+    ("reset", "RESET"), ("restore", "RESET"), ("revert", "RESET"),
+    ("import", "IMPORT"), ("load", "IMPORT"),
+];
+
+/// A lookup verb plus one of these nouns asks for vault presets, not a value
+static RETRIEVAL_VERBS: &[&str] = &["find", "search", "list", "show", "get", "which"];
+static RETRIEVAL_NOUNS: &[&str] = &[
+    "preset", "presets", "setfile", "setfiles", "set file", "set files", "vault",
+];
+
 /// Check if input starts with a command starter
 fn has_command_starter(s: &str) -> bool {
     let lower = s.to_lowercase();
     COMMAND_STARTERS.iter().any(|starter| lower.starts_with(starter))
 }
 
+/// "find my XAUUSD presets ..." - a search of the vault
+pub fn is_retrieval_command(command: &str) -> bool {
+    let lower = command.to_lowercase();
+    let Some(first) = lower.split_whitespace().next() else {
+        return false;
+    };
+    RETRIEVAL_VERBS.contains(&first)
+        && RETRIEVAL_NOUNS.iter().any(|noun| {
+            lower
+                .match_indices(noun)
+                .any(|(at, _)| is_word_at(&lower, at, noun.len()))
+        })
+}
+
+fn is_word_at(text: &str, at: usize, len: usize) -> bool {
+    let before = text[..at].chars().next_back();
+    let after = text[at + len..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+/// Intent from the command's leading verb ("show grid" -> QUERY); vault
+/// lookups are SEARCH
+pub fn rule_based_intent(command: &str) -> Option<&'static str> {
+    if is_retrieval_command(command) {
+        return Some("SEARCH");
+    }
+    let lower = command.to_lowercase();
+    let first = lower.split_whitespace().next()?;
+    INTENT_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| *keyword == first)
+        .map(|(_, intent)| *intent)
+}
+
 /// Preprocess user input to extract the actual command
 pub fn preprocess(input: &str) -> String {
     let mut result = input.trim().to_string();
@@ -122,14 +183,17 @@ pub fn extract_command(input: &str) -> CommandExtraction {
             is_greeting: true,
             confidence: 1.0,
             greeting_type: detect_greeting_type(input),
+            slots: CommandSlots::default(),
         };
     }
     
+    let slots = extract_slots(&processed);
     CommandExtraction {
         command: processed,
         is_greeting: false,
         confidence: 0.9,
         greeting_type: None,
+        slots,
     }
 }
 
@@ -157,6 +221,7 @@ pub struct CommandExtraction {
     pub is_greeting: bool,
     pub confidence: f32,
     pub greeting_type: Option<GreetingType>,
+    pub slots: CommandSlots,
 }
 
 /// Types of greetings
@@ -200,6 +265,214 @@ pub fn preprocess_command(input: String) -> CommandExtraction {
     extract_command(&input)
 }
 
+// ============================================
+// SLOT EXTRACTION
+// ============================================
+
+/// Unit attached to a number in the command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SlotUnit {
+    Lots,
+    Pips,
+    Points,
+    Percent,
+    Times,
+}
+
+/// A number found in the command, e.g. "250 pips"
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NumberSlot {
+    pub value: f64,
+    pub unit: Option<SlotUnit>,
+    pub text: String,
+}
+
+/// Typed pieces of a parameter command
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CommandSlots {
+    pub numbers: Vec<NumberSlot>,
+    /// LogicConfig field, e.g. "grid" or "initial_lot"
+    pub field: Option<String>,
+    pub engine: Option<String>,
+    pub group: Option<u8>,
+    /// Canonical logic name, e.g. "SCALPER"
+    pub logic: Option<String>,
+    /// "Buy" or "Sell"; None means both sides
+    pub direction: Option<String>,
+    /// Field-lock style path, "*" for anything not mentioned
+    pub config_path: Option<String>,
+}
+
+/// Phrases for each logic field, most specific first
+static FIELD_ALIASES: &[(&str, &str)] = &[
+    ("initial lot", "initial_lot"),
+    ("lot size", "initial_lot"),
+    ("last lot", "last_lot"),
+    ("trail start", "trail_start"),
+    ("trail step", "trail_step"),
+    ("start level", "start_level"),
+    ("reverse scale", "reverse_scale"),
+    ("hedge scale", "hedge_scale"),
+    ("multiplier", "multiplier"),
+    ("mult", "multiplier"),
+    ("grid", "grid"),
+    ("spacing", "grid"),
+    ("trailing", "trail_value"),
+    ("trail", "trail_value"),
+    ("lots", "initial_lot"),
+    ("lot", "initial_lot"),
+];
+
+static LOGIC_ALIASES: &[(&str, &str)] = &[
+    ("repower", "REPOWER"),
+    ("power", "POWER"),
+    ("scalper", "SCALPER"),
+    ("scalping", "SCALPER"),
+    ("scalp", "SCALPER"),
+    ("stopper", "STOPPER"),
+    ("sto", "STO"),
+    ("sca", "SCA"),
+    ("rpo", "RPO"),
+];
+
+struct SlotPatterns {
+    engine: Regex,
+    group: Regex,
+    compact: Regex,
+    number: Regex,
+}
+
+fn slot_patterns() -> &'static SlotPatterns {
+    static PATTERNS: OnceLock<SlotPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| SlotPatterns {
+        engine: Regex::new(r"\bengine\s*([abc])\b").unwrap(),
+        group: Regex::new(r"\b(?:group|grp|g)\s*(\d{1,2})\b").unwrap(),
+        // "B3", "b-3", "A12"
+        compact: Regex::new(r"\b([abc])-?(\d{1,2})\b").unwrap(),
+        number: Regex::new(
+            r"(-?\d+(?:\.\d+)?)\s*(lots?\b|pips?\b|points?\b|pts\b|percent\b|%|x\b)?",
+        )
+        .unwrap(),
+    })
+}
+
+fn has_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .collect::<Vec<_>>()
+        .windows(word.split(' ').count())
+        .any(|w| w.join(" ") == word)
+}
+
+fn parse_unit(unit: &str) -> Option<SlotUnit> {
+    match unit {
+        "lot" | "lots" => Some(SlotUnit::Lots),
+        "pip" | "pips" => Some(SlotUnit::Pips),
+        "point" | "points" | "pts" => Some(SlotUnit::Points),
+        "%" | "percent" => Some(SlotUnit::Percent),
+        "x" => Some(SlotUnit::Times),
+        _ => None,
+    }
+}
+
+/// Extract typed slots from a (preprocessed) command
+pub fn extract_slots(command: &str) -> CommandSlots {
+    let text = command.to_lowercase();
+    let patterns = slot_patterns();
+    let mut slots = CommandSlots::default();
+    // Byte ranges already used by references, so "B3" isn't read as the number 3
+    let mut claimed: Vec<(usize, usize)> = Vec::new();
+
+    if let Some(c) = patterns.engine.captures(&text) {
+        slots.engine = Some(c[1].to_uppercase());
+        claimed.push((c.get(0).unwrap().start(), c.get(0).unwrap().end()));
+    }
+    if let Some(c) = patterns.group.captures(&text) {
+        slots.group = c[1].parse().ok();
+        claimed.push((c.get(0).unwrap().start(), c.get(0).unwrap().end()));
+    }
+    if let Some(c) = patterns.compact.captures(&text) {
+        slots.engine.get_or_insert_with(|| c[1].to_uppercase());
+        if slots.group.is_none() {
+            slots.group = c[2].parse().ok();
+        }
+        claimed.push((c.get(0).unwrap().start(), c.get(0).unwrap().end()));
+    }
+    slots.group = slots.group.filter(|g| (1..=20).contains(g));
+
+    for c in patterns.number.captures_iter(&text) {
+        let whole = c.get(0).unwrap();
+        let num = c.get(1).unwrap();
+        let inside_word = text[..num.start()]
+            .chars()
+            .last()
+            .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_');
+        if inside_word || claimed.iter().any(|&(s, e)| num.start() >= s && num.end() <= e) {
+            continue;
+        }
+        if let Ok(value) = num.as_str().parse::<f64>() {
+            slots.numbers.push(NumberSlot {
+                value,
+                unit: c.get(2).and_then(|u| parse_unit(u.as_str())),
+                text: whole.as_str().trim().to_string(),
+            });
+        }
+    }
+
+    slots.logic = LOGIC_ALIASES
+        .iter()
+        .find(|(alias, _)| has_word(&text, alias))
+        .map(|(_, name)| name.to_string());
+
+    let buy = ["buy", "buys", "long"].iter().any(|w| has_word(&text, w));
+    let sell = ["sell", "sells", "short"].iter().any(|w| has_word(&text, w));
+    slots.direction = match (buy, sell) {
+        (true, false) => Some("Buy".to_string()),
+        (false, true) => Some("Sell".to_string()),
+        _ => None,
+    };
+
+    slots.field = FIELD_ALIASES
+        .iter()
+        .find(|(alias, _)| has_word(&text, alias))
+        .map(|(_, field)| field.to_string())
+        .or_else(|| {
+            // Fall back to what the unit implies
+            slots.numbers.iter().find_map(|n| match n.unit? {
+                SlotUnit::Lots => Some("initial_lot".to_string()),
+                SlotUnit::Pips | SlotUnit::Points => Some("grid".to_string()),
+                SlotUnit::Times => Some("multiplier".to_string()),
+                SlotUnit::Percent => None,
+            })
+        });
+
+    slots.config_path = build_config_path(&slots);
+    slots
+}
+
+/// Field-lock style path for the slots, or None without a field
+pub fn build_config_path(slots: &CommandSlots) -> Option<String> {
+    let field = slots.field.as_ref()?;
+    let suffix = match slots.direction.as_deref() {
+        Some("Buy") => "_b",
+        Some("Sell") => "_s",
+        _ => "",
+    };
+    Some(format!(
+        "engines.{}.groups.{}.logics.{}.{}{}",
+        slots.engine.as_deref().unwrap_or("*"),
+        slots.group.map_or("*".to_string(), |g| g.to_string()),
+        slots.logic.as_deref().unwrap_or("*"),
+        field,
+        suffix
+    ))
+}
+
+/// Tauri command: extract typed slots from user input
+#[tauri::command]
+pub fn extract_command_slots(input: String) -> CommandSlots {
+    extract_slots(&preprocess(&input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +509,53 @@ mod tests {
         assert!(!result.is_greeting);
         assert_eq!(result.command, "set grid to 600");
     }
+
+    #[test]
+    fn test_rule_based_intent() {
+        assert_eq!(rule_based_intent(&preprocess("hey set grid to 600")), Some("SET"));
+        assert_eq!(rule_based_intent("What's the grid"), Some("QUERY"));
+        assert_eq!(rule_based_intent("make it safer"), Some("SEMANTIC"));
+        assert_eq!(rule_based_intent("settings please"), None);
+        assert_eq!(
+            rule_based_intent("find my XAUUSD presets with drawdown stop under 20%"),
+            Some("SEARCH")
+        );
+        assert!(!is_retrieval_command("show grid on the vaulted group"));
+        assert!(!is_retrieval_command("set presets"));
+        assert_eq!(rule_based_intent(""), None);
+    }
+
+    #[test]
+    fn test_extract_slots_compact_reference() {
+        let slots = extract_slots("set grid to 250 on B3 scalp sell");
+        assert_eq!(slots.engine.as_deref(), Some("B"));
+        assert_eq!(slots.group, Some(3));
+        assert_eq!(slots.logic.as_deref(), Some("SCALPER"));
+        assert_eq!(slots.direction.as_deref(), Some("Sell"));
+        assert_eq!(slots.numbers.len(), 1);
+        assert_eq!(slots.numbers[0].value, 250.0);
+        assert_eq!(
+            slots.config_path.as_deref(),
+            Some("engines.B.groups.3.logics.SCALPER.grid_s")
+        );
+    }
+
+    #[test]
+    fn test_extract_slots_units_and_words() {
+        let slots = extract_slots("change engine a group 12 power lot to 0.05 lots");
+        assert_eq!(slots.engine.as_deref(), Some("A"));
+        assert_eq!(slots.group, Some(12));
+        assert_eq!(slots.field.as_deref(), Some("initial_lot"));
+        assert_eq!(slots.numbers[0].unit, Some(SlotUnit::Lots));
+        assert_eq!(slots.direction, None);
+
+        // Unit decides the field when none is named
+        let slots = extract_slots("make repower 1.5x");
+        assert_eq!(slots.logic.as_deref(), Some("REPOWER"));
+        assert_eq!(slots.field.as_deref(), Some("multiplier"));
+        assert_eq!(
+            slots.config_path.as_deref(),
+            Some("engines.*.groups.*.logics.REPOWER.multiplier")
+        );
+    }
 }
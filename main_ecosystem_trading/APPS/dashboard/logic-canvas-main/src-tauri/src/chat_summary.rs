@@ -0,0 +1,310 @@
+//! Plain-English preset summaries
+//!
+//! Turns an MTConfig into a few short phrases ("Engine A: Power+Repower on
+//! groups 1-3, 0.02 lots, 1.5x multiplier, 300pt grid") for vault tooltips
+//! and export comments. A value shared by every active logic is stated
+//! once; values that differ are given as a min-max range.
+
+use serde::Serialize;
+
+use crate::enum_labels::{label, Locale};
+use crate::mt_bridge::{EngineConfig, GeneralConfig, LogicConfig, MTConfig};
+
+/// Beyond this many distinct logics an engine line just gives the count
+const MAX_NAMED_LOGICS: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSummary {
+    /// Everything on one line, e.g. for a tooltip
+    pub text: String,
+    /// One phrase per engine, including engines that are off
+    pub engines: Vec<String>,
+    /// Direction, risk limits and filters
+    pub general: String,
+}
+
+/// "POWER" -> "Power"; short codes like "STO" stay as they are
+fn logic_label(name: &str) -> String {
+    if name.len() <= 3 {
+        return name.to_uppercase();
+    }
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// [1, 2, 3, 5] -> "1-3, 5"
+fn group_ranges(groups: &[u8]) -> String {
+    let mut sorted = groups.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for g in sorted {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == g => *end = g,
+            _ => ranges.push((g, g)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Up to four decimals, no trailing zeros
+fn number(value: f64) -> String {
+    format!("{}", (value * 10_000.0).round() / 10_000.0)
+}
+
+/// "0.02" when every value agrees, "0.01-0.05" otherwise
+fn value_range(values: &[f64]) -> Option<String> {
+    let min = values.iter().copied().reduce(f64::min)?;
+    let max = values.iter().copied().reduce(f64::max)?;
+    if number(min) == number(max) {
+        Some(number(min))
+    } else {
+        Some(format!("{}-{}", number(min), number(max)))
+    }
+}
+
+/// Values of one field for every direction a logic trades
+fn directional(logic: &LogicConfig, base: f64, buy: Option<f64>, sell: Option<f64>) -> Vec<f64> {
+    let mut values = Vec::with_capacity(2);
+    if logic.allow_buy {
+        values.push(buy.unwrap_or(base));
+    }
+    if logic.allow_sell {
+        values.push(sell.unwrap_or(base));
+    }
+    values
+}
+
+fn engine_phrase(engine: &EngineConfig) -> String {
+    // Logic names per enabled group, merging groups that run the same set
+    let mut runs: Vec<(Vec<String>, Vec<u8>)> = Vec::new();
+    let mut active: Vec<&LogicConfig> = Vec::new();
+    for group in engine.groups.iter().filter(|g| g.enabled) {
+        let mut names: Vec<String> = Vec::new();
+        for logic in &group.logics {
+            if !logic.enabled || !(logic.allow_buy || logic.allow_sell) {
+                continue;
+            }
+            active.push(logic);
+            let label = logic_label(&logic.logic_name);
+            if !names.contains(&label) {
+                names.push(label);
+            }
+        }
+        if names.is_empty() {
+            continue;
+        }
+        match runs.iter_mut().find(|(run_names, _)| *run_names == names) {
+            Some((_, groups)) => groups.push(group.group_number),
+            None => runs.push((names, vec![group.group_number])),
+        }
+    }
+    if active.is_empty() {
+        return format!("Engine {}: off", engine.engine_id);
+    }
+
+    let mut parts: Vec<String> = runs
+        .iter()
+        .map(|(names, groups)| {
+            let logics = if names.len() > MAX_NAMED_LOGICS {
+                format!("{} logics", names.len())
+            } else {
+                names.join("+")
+            };
+            let noun = if groups.len() == 1 { "group" } else { "groups" };
+            format!("{} on {} {}", logics, noun, group_ranges(groups))
+        })
+        .collect();
+
+    let collect = |field: fn(&LogicConfig) -> (f64, Option<f64>, Option<f64>)| -> Vec<f64> {
+        active
+            .iter()
+            .flat_map(|logic| {
+                let (base, buy, sell) = field(logic);
+                directional(logic, base, buy, sell)
+            })
+            .collect()
+    };
+    let lots = collect(|l| (l.initial_lot, l.initial_lot_b, l.initial_lot_s));
+    let multipliers = collect(|l| (l.multiplier, l.multiplier_b, l.multiplier_s));
+    let grids = collect(|l| (l.grid, l.grid_b, l.grid_s));
+    if let Some(lots) = value_range(&lots) {
+        parts.push(format!("{} lots", lots));
+    }
+    if let Some(multiplier) = value_range(&multipliers) {
+        parts.push(format!("{}x multiplier", multiplier));
+    }
+    if let Some(grid) = value_range(&grids) {
+        parts.push(format!("{}pt grid", grid));
+    }
+
+    let buys = active.iter().any(|l| l.allow_buy);
+    let sells = active.iter().any(|l| l.allow_sell);
+    if buys != sells {
+        parts.push(if buys { "buy only" } else { "sell only" }.to_string());
+    }
+    format!("Engine {}: {}", engine.engine_id, parts.join(", "))
+}
+
+fn general_phrase(general: &GeneralConfig) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    match (general.allow_buy, general.allow_sell) {
+        (false, false) => parts.push("trading disabled".to_string()),
+        (true, false) => parts.push("buy only".to_string()),
+        (false, true) => parts.push("sell only".to_string()),
+        (true, true) => {}
+    }
+
+    let risk = &general.risk_management;
+    if risk.equity_stop_enabled {
+        parts.push(format!("equity stop {}%", number(risk.equity_stop_value)));
+    }
+    if risk.drawdown_stop_enabled {
+        parts.push(format!(
+            "drawdown stop {}%",
+            number(risk.max_drawdown_percent)
+        ));
+    }
+    if risk.spread_filter_enabled {
+        parts.push(format!("max spread {}pt", number(risk.max_spread_points)));
+    }
+
+    let filters = &general.time_filters;
+    if filters.enabled {
+        let sessions = filters.sessions.iter().filter(|s| s.enabled).count();
+        let noun = if sessions == 1 { "session" } else { "sessions" };
+        parts.push(format!("{} trading {}", sessions, noun));
+    }
+    let news = &general.news_filter;
+    if news.enabled {
+        parts.push(format!(
+            "news filter {}/{} min",
+            news.minutes_before, news.minutes_after
+        ));
+    }
+    if general.compounding_enabled {
+        parts.push(format!(
+            "compounding {}",
+            label(&general.compounding_type, Locale::En).to_lowercase()
+        ));
+    }
+
+    if parts.is_empty() {
+        "no risk limits".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+pub fn summarize(config: &MTConfig) -> ConfigSummary {
+    let engines: Vec<String> = config.engines.iter().map(engine_phrase).collect();
+    let general = general_phrase(&config.general);
+
+    let mut phrases: Vec<&str> = engines
+        .iter()
+        .map(String::as_str)
+        .filter(|phrase| !phrase.ends_with(": off"))
+        .collect();
+    if phrases.is_empty() {
+        phrases.push("All engines off");
+    }
+    ConfigSummary {
+        text: format!("{}; {}", phrases.join("; "), general),
+        engines,
+        general,
+    }
+}
+
+/// Short human-readable summary of a config, for tooltips and export comments
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn summarize_config(config: MTConfig) -> Result<ConfigSummary, String> {
+    Ok(summarize(&config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_group_ranges_and_labels() {
+        assert_eq!(group_ranges(&[5, 1, 2, 3, 9, 10]), "1-3, 5, 9-10");
+        assert_eq!(group_ranges(&[4]), "4");
+        assert_eq!(logic_label("REPOWER"), "Repower");
+        assert_eq!(logic_label("sto"), "STO");
+        assert_eq!(value_range(&[0.02, 0.02]).as_deref(), Some("0.02"));
+        assert_eq!(value_range(&[300.0, 100.0]).as_deref(), Some("100-300"));
+        assert_eq!(value_range(&[]), None);
+    }
+
+    #[test]
+    fn test_summarize_config() {
+        let mut config = create_full_v19_config();
+        let engine = &mut config.engines[0];
+        for group in &mut engine.groups {
+            group.enabled = group.group_number <= 3;
+            for logic in &mut group.logics {
+                logic.enabled = logic.logic_name == "POWER" || logic.logic_name == "REPOWER";
+                logic.initial_lot = 0.02;
+                logic.grid = 300.0;
+            }
+        }
+        for engine in &mut config.engines[1..] {
+            for group in &mut engine.groups {
+                group.enabled = false;
+            }
+        }
+        config.general.risk_management.equity_stop_enabled = true;
+        config.general.risk_management.equity_stop_value = 35.0;
+        config.general.risk_management.drawdown_stop_enabled = false;
+        config.general.risk_management.spread_filter_enabled = false;
+        config.general.time_filters.enabled = false;
+        config.general.news_filter.enabled = false;
+        config.general.compounding_enabled = false;
+
+        let summary = summarize(&config);
+        assert_eq!(
+            summary.engines[0],
+            "Engine A: Power+Repower on groups 1-3, 0.02 lots, 1.5x multiplier, 300pt grid"
+        );
+        assert_eq!(summary.engines[1], "Engine B: off");
+        assert_eq!(summary.general, "equity stop 35%");
+        assert_eq!(
+            summary.text,
+            "Engine A: Power+Repower on groups 1-3, 0.02 lots, 1.5x multiplier, \
+             300pt grid; equity stop 35%"
+        );
+
+        // Direction-specific values widen the range; one-sided engines say so
+        let logic = &mut config.engines[0].groups[0].logics[0];
+        logic.grid_b = Some(150.0);
+        for logic in config.engines[0]
+            .groups
+            .iter_mut()
+            .flat_map(|g| &mut g.logics)
+        {
+            logic.allow_sell = false;
+            logic.allow_buy = true;
+        }
+        let engine = summarize(&config).engines.remove(0);
+        assert!(engine.contains("150-300pt grid"), "{}", engine);
+        assert!(engine.ends_with("buy only"), "{}", engine);
+    }
+}
@@ -0,0 +1,211 @@
+// ============================================
+// CLOUD-SYNCED FOLDERS
+// ============================================
+//
+// Vaults often live in OneDrive, Dropbox or Google Drive folders. Sync
+// clients briefly lock files they upload and replace them with placeholders,
+// so the rename at the end of atomic_write can fail and leave its .tmp file
+// behind. Writes into a synced folder are retried with backoff, fall back to
+// an in-place write, and are read back to check the SHA-256 of what landed.
+// Stray .tmp files are swept from the vault at startup, and the vault
+// location/listing commands warn when the vault is inside a synced folder.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::mt_bridge::atomic_write;
+
+const SYNC_WRITE_ATTEMPTS: u32 = 5;
+const SYNC_WRITE_BACKOFF: Duration = Duration::from_millis(100);
+/// Temp files younger than this may belong to a write still in progress
+const ORPHAN_TMP_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+/// Vault root plus one level of category folders
+const ORPHAN_SCAN_DEPTH: usize = 2;
+
+/// Sync client whose folder contains `path`, from the folder names they create
+pub(crate) fn sync_provider(path: &Path) -> Option<&'static str> {
+    for dir in path.ancestors() {
+        if dir.join(".dropbox").is_file() {
+            return Some("Dropbox");
+        }
+        let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_lowercase()) else {
+            continue;
+        };
+        let provider = match name.as_str() {
+            "onedrive" => Some("OneDrive"),
+            n if n.starts_with("onedrive - ") => Some("OneDrive"),
+            "dropbox" => Some("Dropbox"),
+            n if n.starts_with("dropbox (") => Some("Dropbox"),
+            "google drive" | "googledrive" | "my drive" => Some("Google Drive"),
+            "icloud drive" | "iclouddrive" | "mobile documents" => Some("iCloud Drive"),
+            "box sync" => Some("Box"),
+            "pclouddrive" => Some("pCloud"),
+            _ => None,
+        };
+        if provider.is_some() {
+            return provider;
+        }
+    }
+    None
+}
+
+/// Warning for the UI when `path` is inside a synced folder
+pub(crate) fn sync_warning(path: &Path) -> Option<String> {
+    sync_provider(path).map(|provider| {
+        format!(
+            "This vault is inside a {} folder. Saves are retried while the sync client \
+             holds files, but avoid editing the same preset on two machines at once.",
+            provider
+        )
+    })
+}
+
+fn verify_written(path: &Path, expected: &[u8]) -> Result<(), String> {
+    let written =
+        fs::read(path).map_err(|e| format!("Failed to read back {}: {}", path.display(), e))?;
+    if Sha256::digest(&written) != Sha256::digest(expected) {
+        return Err(format!(
+            "Content of {} does not match what was written",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// atomic_write with retries; the last attempt overwrites in place, which sync
+/// placeholders accept when they refuse a rename
+pub(crate) fn cloud_safe_write(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    let content = content.as_ref();
+    let mut last_error = String::new();
+    for attempt in 0..SYNC_WRITE_ATTEMPTS {
+        if attempt > 0 {
+            std::thread::sleep(SYNC_WRITE_BACKOFF * 2u32.pow(attempt - 1));
+        }
+        let written = if attempt + 1 < SYNC_WRITE_ATTEMPTS {
+            atomic_write(path, content)
+        } else {
+            fs::write(path, content).map_err(|e| format!("Failed to write file: {}", e))
+        };
+        match written.and_then(|_| verify_written(path, content)) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                tracing::warn!(path = ?path, attempt, error = %e, "Synced folder write failed");
+                last_error = e;
+            }
+        }
+    }
+    Err(format!(
+        "{} (gave up after {} attempts; is the sync client holding the file?)",
+        last_error, SYNC_WRITE_ATTEMPTS
+    ))
+}
+
+/// cloud_safe_write inside synced folders, a plain atomic_write everywhere else
+pub(crate) fn sync_aware_write(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    if sync_provider(path).is_some() {
+        cloud_safe_write(path, content)
+    } else {
+        atomic_write(path, content)
+    }
+}
+
+/// atomic_write temp files are named "<file>.<nanos>.tmp"
+fn is_atomic_write_tmp(name: &str) -> bool {
+    name.strip_suffix(".tmp")
+        .and_then(|rest| rest.rsplit_once('.'))
+        .is_some_and(|(stem, nanos)| {
+            !stem.is_empty() && nanos.len() >= 10 && nanos.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+/// Delete atomic_write temp files under `dir` older than `min_age`; returns how many
+pub(crate) fn cleanup_orphaned_tmp_files(dir: &Path, min_age: Duration) -> usize {
+    fn sweep(dir: &Path, min_age: Duration, depth: usize, removed: &mut usize) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth > 1 {
+                    sweep(&path, min_age, depth - 1, removed);
+                }
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let old_enough = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age >= min_age);
+            if is_atomic_write_tmp(&name) && old_enough && fs::remove_file(&path).is_ok() {
+                *removed += 1;
+            }
+        }
+    }
+
+    let mut removed = 0;
+    sweep(dir, min_age, ORPHAN_SCAN_DEPTH, &mut removed);
+    removed
+}
+
+/// Startup sweep of the active vault
+pub(crate) fn cleanup_vault_tmp_files() {
+    let vault = crate::vault_location::active_vault_root();
+    let removed = cleanup_orphaned_tmp_files(&vault, ORPHAN_TMP_MIN_AGE);
+    if removed > 0 {
+        tracing::info!(path = ?vault, removed, "Removed orphaned vault temp files");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_provider_detection() {
+        let onedrive = Path::new("/Users/trader/OneDrive - Acme/Vault_Presets/gold.set");
+        assert_eq!(sync_provider(onedrive), Some("OneDrive"));
+        assert_eq!(
+            sync_provider(Path::new("/home/trader/Dropbox/vault")),
+            Some("Dropbox")
+        );
+        assert_eq!(
+            sync_provider(Path::new("/home/trader/Documents/DAAVFX_Vault")),
+            None
+        );
+        assert!(sync_warning(onedrive).unwrap().contains("OneDrive"));
+    }
+
+    #[test]
+    fn test_orphaned_tmp_files_are_removed() {
+        assert!(is_atomic_write_tmp("gold.set.1718000000123456789.tmp"));
+        assert!(!is_atomic_write_tmp("notes.tmp"));
+        assert!(!is_atomic_write_tmp("gold.set"));
+
+        let dir = std::env::temp_dir().join(format!("daavfx_sync_{}", std::process::id()));
+        fs::create_dir_all(dir.join("Gold")).unwrap();
+        fs::write(dir.join("gold.set"), "x").unwrap();
+        fs::write(dir.join("gold.set.1718000000123456789.tmp"), "x").unwrap();
+        fs::write(dir.join("Gold").join("a.json.1718000000123456789.tmp"), "x").unwrap();
+        fs::write(dir.join("notes.tmp"), "x").unwrap();
+
+        // Fresh temp files may still be in use
+        assert_eq!(
+            cleanup_orphaned_tmp_files(&dir, Duration::from_secs(3600)),
+            0
+        );
+        assert_eq!(cleanup_orphaned_tmp_files(&dir, Duration::ZERO), 2);
+        assert!(dir.join("gold.set").exists());
+        assert!(dir.join("notes.tmp").exists());
+
+        let written = dir.join("synced.set");
+        cloud_safe_write(&written, "gInput_Grid=300").unwrap();
+        assert_eq!(fs::read_to_string(&written).unwrap(), "gInput_Grid=300");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,405 @@
+// ============================================
+// OPEN CONFIG HANDLES
+// ============================================
+//
+// The editor can keep several configs open at once (one per tab). Each open
+// config is addressed by a handle; mutation commands take the handle and
+// change the config here, so the frontend doesn't ship the full MTConfig over
+// IPC on every edit. Commands called without a handle use the active config.
+// Every change re-checks the config against the user's constraints, so the
+// diagnostics for a tab are always those of its current config. Bulk edits
+// (field copy, scaling, direction mirroring) edit logic rows as JSON objects
+// and go through apply_bulk_edit for the dry-run / lock check / commit steps.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+use crate::constraints::{ConstraintDiagnostic, ConstraintSet};
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::enforce_field_locks;
+use crate::field_locks::LockViolation;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+use crate::mt_bridge::{LogicConfig, MTConfig};
+
+/// Undo depth kept per open config
+pub(crate) const MAX_UNDO: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct OpenConfig {
+    pub label: String,
+    pub config: MTConfig,
+    /// Configs from before each applied change, newest last
+    pub undo_stack: Vec<MTConfig>,
+    /// Constraint violations of `config`
+    pub diagnostics: Vec<ConstraintDiagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHandleInfo {
+    pub handle: String,
+    pub label: String,
+    pub platform: String,
+    pub active: bool,
+    pub undo_depth: usize,
+    pub constraint_violations: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct OpenConfigs {
+    /// Open configs in the order they were opened (tab order)
+    entries: Vec<(String, OpenConfig)>,
+    active: Option<String>,
+    next_id: u64,
+    constraints: ConstraintSet,
+}
+
+impl OpenConfigs {
+    pub fn with_constraints(constraints: ConstraintSet) -> Self {
+        Self {
+            constraints,
+            ..Self::default()
+        }
+    }
+
+    pub fn constraints(&self) -> &ConstraintSet {
+        &self.constraints
+    }
+
+    /// Replace the constraint set and re-check every open config
+    pub fn set_constraints(&mut self, constraints: ConstraintSet) {
+        self.constraints = constraints;
+        for (_, entry) in &mut self.entries {
+            entry.diagnostics = self.constraints.check(&entry.config);
+        }
+    }
+
+    fn recheck(&mut self, handle: Option<&str>) -> Result<(), String> {
+        let index = self.position(handle)?;
+        let entry = &mut self.entries[index].1;
+        entry.diagnostics = self.constraints.check(&entry.config);
+        Ok(())
+    }
+
+    /// Open `config` under a new handle and make it the active one
+    pub fn open(&mut self, config: MTConfig, label: Option<String>) -> String {
+        self.next_id += 1;
+        let handle = format!("cfg-{}", self.next_id);
+        let label = label
+            .filter(|l| !l.trim().is_empty())
+            .or_else(|| config.current_set_name.clone())
+            .unwrap_or_else(|| format!("Config {}", self.next_id));
+        let diagnostics = self.constraints.check(&config);
+        self.entries.push((
+            handle.clone(),
+            OpenConfig {
+                label,
+                config,
+                undo_stack: Vec::new(),
+                diagnostics,
+            },
+        ));
+        self.active = Some(handle.clone());
+        handle
+    }
+
+    fn position(&self, handle: Option<&str>) -> Result<usize, String> {
+        let handle = match handle {
+            Some(handle) => handle,
+            None => self
+                .active
+                .as_deref()
+                .ok_or_else(|| "No config is open".to_string())?,
+        };
+        self.entries
+            .iter()
+            .position(|(open, _)| open == handle)
+            .ok_or_else(|| format!("Unknown config handle '{}'", handle))
+    }
+
+    /// `handle`, or the active handle when none is given
+    pub fn resolve(&self, handle: Option<&str>) -> Result<String, String> {
+        Ok(self.entries[self.position(handle)?].0.clone())
+    }
+
+    pub fn get(&self, handle: Option<&str>) -> Result<&OpenConfig, String> {
+        let index = self.position(handle)?;
+        Ok(&self.entries[index].1)
+    }
+
+    pub fn get_mut(&mut self, handle: Option<&str>) -> Result<&mut OpenConfig, String> {
+        let index = self.position(handle)?;
+        Ok(&mut self.entries[index].1)
+    }
+
+    /// Replace the config behind `handle`; without a handle or open config a new one is opened
+    pub fn store(&mut self, handle: Option<&str>, config: MTConfig) -> Result<String, String> {
+        if handle.is_none() && self.active.is_none() {
+            return Ok(self.open(config, None));
+        }
+        self.get_mut(handle)?.config = config;
+        self.recheck(handle)?;
+        self.resolve(handle)
+    }
+
+    /// Replace the config behind `handle` and remember the previous one for undo
+    pub fn commit(&mut self, handle: Option<&str>, config: MTConfig) -> Result<String, String> {
+        let entry = self.get_mut(handle)?;
+        let previous = std::mem::replace(&mut entry.config, config);
+        entry.undo_stack.push(previous);
+        if entry.undo_stack.len() > MAX_UNDO {
+            entry.undo_stack.remove(0);
+        }
+        self.recheck(handle)?;
+        self.resolve(handle)
+    }
+
+    /// Restore the config from before the last commit
+    pub fn undo(&mut self, handle: Option<&str>) -> Result<MTConfig, String> {
+        let entry = self.get_mut(handle)?;
+        let previous = entry
+            .undo_stack
+            .pop()
+            .ok_or_else(|| "Nothing to undo".to_string())?;
+        entry.config = previous.clone();
+        self.recheck(handle)?;
+        Ok(previous)
+    }
+
+    pub fn duplicate(&mut self, handle: &str, label: Option<String>) -> Result<String, String> {
+        let source = self.get(Some(handle))?;
+        let label = label.unwrap_or_else(|| format!("{} (copy)", source.label));
+        let config = source.config.clone();
+        Ok(self.open(config, Some(label)))
+    }
+
+    pub fn close(&mut self, handle: &str) -> Result<(), String> {
+        let index = self.position(Some(handle))?;
+        self.entries.remove(index);
+        if self.active.as_deref() == Some(handle) {
+            // Fall back to the neighbouring tab, like closing a browser tab
+            let next = index.min(self.entries.len().saturating_sub(1));
+            self.active = self.entries.get(next).map(|(open, _)| open.clone());
+        }
+        Ok(())
+    }
+
+    pub fn activate(&mut self, handle: &str) -> Result<(), String> {
+        self.active = Some(self.resolve(Some(handle))?);
+        Ok(())
+    }
+
+    pub fn list(&self) -> Vec<ConfigHandleInfo> {
+        self.entries
+            .iter()
+            .map(|(handle, entry)| ConfigHandleInfo {
+                handle: handle.clone(),
+                label: entry.label.clone(),
+                platform: entry.config.platform.clone(),
+                active: self.active.as_deref() == Some(handle.as_str()),
+                undo_depth: entry.undo_stack.len(),
+                constraint_violations: entry.diagnostics.len(),
+            })
+            .collect()
+    }
+}
+
+/// Where a bulk edit landed; flattened into each edit's report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkEditOutcome {
+    pub handle: String,
+    /// True when nothing was committed (dry run or no changes)
+    pub dry_run: bool,
+    pub violations: Vec<LockViolation>,
+}
+
+pub(crate) trait BulkEditReport {
+    fn has_changes(&self) -> bool;
+    fn outcome_mut(&mut self) -> &mut BulkEditOutcome;
+}
+
+/// A logic row as a JSON object, so bulk edits can address fields by name
+pub(crate) fn row_object(logic: &LogicConfig) -> Result<Map<String, Value>, String> {
+    match serde_json::to_value(logic) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err("Logic did not serialize to an object".to_string()),
+        Err(e) => Err(format!("Failed to serialize logic: {}", e)),
+    }
+}
+
+/// `mask` is a field name, or a prefix with a trailing '*' ("trail_*")
+pub(crate) fn mask_matches(mask: &str, field: &str) -> bool {
+    match mask.strip_suffix('*') {
+        Some(prefix) => field.starts_with(prefix),
+        None => field == mask,
+    }
+}
+
+/// Run `edit` on the config behind `handle` (default: the active one) and commit
+/// the result as one undoable change, unless `dry_run` or nothing changed
+#[cfg(feature = "tauri-app")]
+pub(crate) async fn apply_bulk_edit<R: BulkEditReport>(
+    state: &MTBridgeState,
+    handle: Option<String>,
+    dry_run: Option<bool>,
+    edit: impl FnOnce(&MTConfig) -> Result<(MTConfig, R), String>,
+) -> Result<R, String> {
+    let (current, handle) = {
+        let configs = state.configs.read().await;
+        let handle = configs.resolve(handle.as_deref())?;
+        (configs.get(Some(&handle))?.config.clone(), handle)
+    };
+    let (patched, mut report) = edit(&current)?;
+    let dry_run = dry_run.unwrap_or(false) || !report.has_changes();
+    let outcome = report.outcome_mut();
+    outcome.handle = handle.clone();
+    outcome.dry_run = dry_run;
+    if dry_run {
+        return Ok(report);
+    }
+    report.outcome_mut().violations = enforce_field_locks(state, &patched).await?;
+    state.configs.write().await.commit(Some(&handle), patched)?;
+    Ok(report)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn open_config_handle(
+    config: MTConfig,
+    label: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<String, String> {
+    Ok(state.configs.write().await.open(config, label))
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn duplicate_config_handle(
+    handle: String,
+    label: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<String, String> {
+    state.configs.write().await.duplicate(&handle, label)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn close_config_handle(
+    handle: String,
+    state: State<'_, MTBridgeState>,
+) -> Result<(), String> {
+    state.configs.write().await.close(&handle)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn activate_config_handle(
+    handle: String,
+    state: State<'_, MTBridgeState>,
+) -> Result<(), String> {
+    state.configs.write().await.activate(&handle)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn list_config_handles(
+    state: State<'_, MTBridgeState>,
+) -> Result<Vec<ConfigHandleInfo>, String> {
+    Ok(state.configs.read().await.list())
+}
+
+/// Full config behind `handle` (default: the active one), e.g. when a tab is first shown
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_config_by_handle(
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<MTConfig, String> {
+    Ok(state
+        .configs
+        .read()
+        .await
+        .get(handle.as_deref())?
+        .config
+        .clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_handles_keep_configs_and_undo_separate() {
+        let mut open = OpenConfigs::default();
+        assert!(open.get(None).is_err());
+
+        let first = open.open(create_full_v19_config(), Some("Client A".to_string()));
+        let second = open.duplicate(&first, None).unwrap();
+        assert_eq!(open.resolve(None).unwrap(), second);
+
+        let mut changed = open.get(Some(&second)).unwrap().config.clone();
+        changed.general.magic_number = 4242;
+        open.commit(Some(&second), changed).unwrap();
+        assert_ne!(
+            open.get(Some(&first)).unwrap().config.general.magic_number,
+            4242
+        );
+        assert_eq!(open.get(None).unwrap().undo_stack.len(), 1);
+
+        let restored = open.undo(Some(&second)).unwrap();
+        assert_ne!(restored.general.magic_number, 4242);
+        assert!(open.undo(Some(&first)).is_err());
+
+        let listed = open.list();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[1].label, "Client A (copy)");
+
+        open.close(&second).unwrap();
+        assert_eq!(open.resolve(None).unwrap(), first);
+        assert!(open.close(&second).is_err());
+    }
+
+    #[test]
+    fn test_commit_and_undo_recheck_constraints() {
+        let rule = crate::constraints::Constraint {
+            id: "trail".to_string(),
+            expression: "trail_start <= trail_value".to_string(),
+            enabled: true,
+            severity: Default::default(),
+            message: None,
+        };
+        let mut open = OpenConfigs::with_constraints(ConstraintSet {
+            constraints: vec![rule],
+        });
+        let mut config = create_full_v19_config();
+        for logic in config
+            .engines
+            .iter_mut()
+            .flat_map(|e| &mut e.groups)
+            .flat_map(|g| &mut g.logics)
+        {
+            logic.trail_start = logic.trail_value;
+        }
+        let handle = open.open(config.clone(), None);
+        assert!(open.get(None).unwrap().diagnostics.is_empty());
+
+        config.engines[0].groups[0].logics[0].trail_start += 5.0;
+        open.commit(Some(&handle), config).unwrap();
+        assert_eq!(open.get(None).unwrap().diagnostics.len(), 1);
+        assert_eq!(open.list()[0].constraint_violations, 1);
+
+        open.undo(None).unwrap();
+        assert!(open.get(None).unwrap().diagnostics.is_empty());
+
+        open.set_constraints(ConstraintSet {
+            constraints: Vec::new(),
+        });
+        assert!(open.constraints().constraints.is_empty());
+    }
+}
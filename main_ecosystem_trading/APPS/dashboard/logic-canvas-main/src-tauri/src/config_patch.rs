@@ -0,0 +1,216 @@
+// ============================================
+// CONFIG PATCHES
+// ============================================
+//
+// Field-level edits to an open config: the frontend sends JSON-pointer style
+// operations ("set /engines/0/groups/2/logics/1/grid = 250") instead of the
+// whole MTConfig. All operations of a request are applied together to a
+// copy, which must still deserialize as an MTConfig before it replaces the
+// config behind the handle.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::field_locks::LockViolation;
+use crate::mt_bridge::MTConfig;
+
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::enforce_field_locks;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchOp {
+    /// Replace the value at `path` (or add a missing optional field)
+    Set,
+    /// Clear an optional field
+    Remove,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPatch {
+    pub op: PatchOp,
+    /// JSON pointer (RFC 6901) into the config, e.g. /engines/0/groups/2/logics/1/grid
+    pub path: String,
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPatchResult {
+    pub handle: String,
+    /// Paths whose value actually changed
+    pub modified_paths: Vec<String>,
+    pub violations: Vec<LockViolation>,
+}
+
+fn pointer_tokens(path: &str) -> Result<Vec<String>, String> {
+    let rest = path
+        .strip_prefix('/')
+        .ok_or_else(|| format!("Patch path '{}' must start with '/'", path))?;
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn apply_op(root: &mut Value, patch: &ConfigPatch) -> Result<bool, String> {
+    let tokens = pointer_tokens(&patch.path)?;
+    let (last, parents) = tokens
+        .split_last()
+        .ok_or_else(|| "Patch path can't be empty".to_string())?;
+
+    let mut parent = &mut *root;
+    for token in parents {
+        parent = match parent {
+            Value::Object(map) => map.get_mut(token.as_str()),
+            Value::Array(items) => token.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Patch path '{}' does not exist", patch.path))?;
+    }
+
+    let target = match parent {
+        Value::Object(map) => {
+            if patch.op == PatchOp::Set && !map.contains_key(last.as_str()) {
+                map.insert(last.clone(), Value::Null);
+            }
+            map.get_mut(last.as_str())
+        }
+        Value::Array(items) => last.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Patch path '{}' does not exist", patch.path))?;
+
+    let value = match patch.op {
+        PatchOp::Set => patch.value.clone(),
+        PatchOp::Remove => Value::Null,
+    };
+    if *target == value {
+        return Ok(false);
+    }
+    *target = value;
+    Ok(true)
+}
+
+/// Apply `patches` to a copy of `config`; returns the patched config and the
+/// paths that changed. Nothing is applied when any patch fails.
+pub fn apply_patches(
+    config: &MTConfig,
+    patches: &[ConfigPatch],
+) -> Result<(MTConfig, Vec<String>), String> {
+    let mut root =
+        serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let mut modified = Vec::new();
+    for patch in patches {
+        if apply_op(&mut root, patch)? && !modified.contains(&patch.path) {
+            modified.push(patch.path.clone());
+        }
+    }
+
+    let patched: MTConfig =
+        serde_json::from_value(root).map_err(|e| format!("Patch rejected: {}", e))?;
+
+    // Unknown keys deserialize fine and are silently dropped; catch them here
+    let reserialized =
+        serde_json::to_value(&patched).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    if let Some(path) = modified.iter().find(|path| {
+        patches
+            .iter()
+            .any(|p| &p.path == *path && p.op == PatchOp::Set)
+            && reserialized.pointer(path).is_none()
+    }) {
+        return Err(format!("Patch rejected: unknown field '{}'", path));
+    }
+    Ok((patched, modified))
+}
+
+/// Apply field-level patches to the config behind `handle` (default: the active one)
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn apply_config_patch(
+    handle: Option<String>,
+    patches: Vec<ConfigPatch>,
+    state: State<'_, MTBridgeState>,
+) -> Result<ConfigPatchResult, String> {
+    let (current, handle) = {
+        let configs = state.configs.read().await;
+        let handle = configs.resolve(handle.as_deref())?;
+        (configs.get(Some(&handle))?.config.clone(), handle)
+    };
+
+    let (patched, modified_paths) = apply_patches(&current, &patches)?;
+    if modified_paths.is_empty() {
+        return Ok(ConfigPatchResult {
+            handle,
+            modified_paths,
+            violations: Vec::new(),
+        });
+    }
+
+    let violations = enforce_field_locks(&state, &patched).await?;
+    state.configs.write().await.commit(Some(&handle), patched)?;
+
+    Ok(ConfigPatchResult {
+        handle,
+        modified_paths,
+        violations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn set(path: &str, value: Value) -> ConfigPatch {
+        ConfigPatch {
+            op: PatchOp::Set,
+            path: path.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_apply_patches_sets_values_and_reports_paths() {
+        let config = create_full_v19_config();
+        let current_grid = config.engines[0].groups[2].logics[1].grid;
+        let patches = vec![
+            set(
+                "/engines/0/groups/2/logics/1/grid",
+                serde_json::json!(250.0),
+            ),
+            set(
+                "/general/magic_number",
+                serde_json::json!(config.general.magic_number),
+            ),
+        ];
+
+        let (patched, modified) = apply_patches(&config, &patches).unwrap();
+        assert_ne!(current_grid, 250.0);
+        assert_eq!(patched.engines[0].groups[2].logics[1].grid, 250.0);
+        // Unchanged values aren't reported
+        assert_eq!(modified, vec!["/engines/0/groups/2/logics/1/grid"]);
+    }
+
+    #[test]
+    fn test_apply_patches_rejects_bad_paths_and_types() {
+        let config = create_full_v19_config();
+        assert!(apply_patches(&config, &[set("/engines/99/grid", serde_json::json!(1))]).is_err());
+        assert!(apply_patches(
+            &config,
+            &[set("general/magic_number", serde_json::json!(1))]
+        )
+        .is_err());
+        let typo = set("/general/magic_numbr", serde_json::json!(1));
+        assert!(apply_patches(&config, &[typo]).is_err());
+        let wrong_type = set("/general/magic_number", serde_json::json!("abc"));
+        assert!(apply_patches(&config, &[wrong_type]).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+// ============================================
+// BULK FIELD SCALING
+// ============================================
+//
+// Quick risk-up / risk-down across the whole matrix: multiply or offset one
+// numeric logic field (or a "prefix*" family) in every row a selector picks,
+// e.g. "all initial_lot in engine B" or "all grid values". Lots are clamped
+// to the broker's min/max and snapped to its lot step, so halving 0.01 lots
+// doesn't produce an order the broker rejects; distances and counts never go
+// below zero. The result is one undoable commit.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::config_handles::{mask_matches, row_object, BulkEditOutcome, BulkEditReport};
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::symbols::SymbolSpec;
+
+#[cfg(feature = "tauri-app")]
+use crate::config_handles::apply_bulk_edit;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use crate::symbols;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+/// Words a text selector may contain that don't narrow anything
+const FILLER_WORDS: &[&str] = &["all", "every", "the", "values", "value", "in", "of", "for"];
+
+/// Which rows and fields to scale; unset filters match everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleSelector {
+    /// Logic field name, or a prefix with a trailing '*' ("trail_*")
+    pub field: String,
+    pub engine_id: Option<String>,
+    pub group_number: Option<u8>,
+    pub logic_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaledField {
+    /// JSON pointer, e.g. /engines/1/groups/0/logics/2/initial_lot
+    pub path: String,
+    pub logic_id: String,
+    pub field: String,
+    pub from: f64,
+    pub to: f64,
+    /// True when a broker limit or zero floor moved the value
+    pub clamped: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleReport {
+    #[serde(flatten)]
+    pub outcome: BulkEditOutcome,
+    pub selector: ScaleSelector,
+    pub fields: Vec<String>,
+    /// Rows the selector matched
+    pub rows: usize,
+    pub changes: Vec<ScaledField>,
+    /// Spec the lots were clamped to; None means the default 0.01 / 100 lot limits
+    pub symbol: Option<SymbolSpec>,
+}
+
+impl BulkEditReport for ScaleReport {
+    fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    fn outcome_mut(&mut self) -> &mut BulkEditOutcome {
+        &mut self.outcome
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScaleOp {
+    Factor(f64),
+    Delta(f64),
+}
+
+impl ScaleOp {
+    pub fn from_args(factor: Option<f64>, delta: Option<f64>) -> Result<Self, String> {
+        match (factor, delta) {
+            (Some(factor), None) if factor.is_finite() && factor > 0.0 => {
+                Ok(ScaleOp::Factor(factor))
+            }
+            (Some(_), None) => Err("Factor must be a positive number".to_string()),
+            (None, Some(delta)) if delta.is_finite() => Ok(ScaleOp::Delta(delta)),
+            (None, Some(_)) => Err("Delta must be a number".to_string()),
+            _ => Err("Give either a factor or a delta".to_string()),
+        }
+    }
+
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            ScaleOp::Factor(factor) => value * factor,
+            ScaleOp::Delta(delta) => value + delta,
+        }
+    }
+}
+
+/// "all initial_lot in engine B", "grid group 3", "trail_* for logic power"
+pub fn parse_selector(text: &str) -> Result<ScaleSelector, String> {
+    let mut selector = ScaleSelector::default();
+    let mut fields = Vec::new();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        let lower = word.to_ascii_lowercase();
+        let mut value = |what: &str| {
+            words
+                .next()
+                .ok_or_else(|| format!("'{}' needs a value in selector '{}'", what, text.trim()))
+        };
+        match lower.as_str() {
+            "engine" => selector.engine_id = Some(value("engine")?.to_ascii_uppercase()),
+            "group" => {
+                let number = value("group")?;
+                selector.group_number = Some(
+                    number
+                        .parse()
+                        .map_err(|_| format!("Invalid group number '{}'", number))?,
+                );
+            }
+            "logic" => selector.logic_name = Some(value("logic")?.to_ascii_uppercase()),
+            _ if FILLER_WORDS.contains(&lower.as_str()) => {}
+            _ => fields.push(lower),
+        }
+    }
+    match fields.as_slice() {
+        [field] => selector.field = field.clone(),
+        [] => return Err(format!("Selector '{}' names no field", text.trim())),
+        _ => {
+            return Err(format!(
+                "Selector '{}' names several fields: {}",
+                text.trim(),
+                fields.join(", ")
+            ))
+        }
+    }
+    Ok(selector)
+}
+
+fn is_lot_field(field: &str) -> bool {
+    field.contains("lot")
+}
+
+fn row_matches(
+    selector: &ScaleSelector,
+    engine_id: &str,
+    group_number: u8,
+    logic: &LogicConfig,
+) -> bool {
+    selector
+        .engine_id
+        .as_deref()
+        .map_or(true, |id| id == "*" || id.eq_ignore_ascii_case(engine_id))
+        && selector.group_number.map_or(true, |n| n == group_number)
+        && selector
+            .logic_name
+            .as_deref()
+            .map_or(true, |name| name.eq_ignore_ascii_case(&logic.logic_name))
+}
+
+/// Broker-valid lot closest to `lot`: inside min/max and on the lot step
+fn clamp_lot(lot: f64, spec: Option<&SymbolSpec>) -> f64 {
+    let (min_lot, max_lot, lot_step) =
+        spec.map_or((0.01, 100.0, 0.01), |s| (s.min_lot, s.max_lot, s.lot_step));
+    let snapped = if lot_step > 0.0 {
+        (lot / lot_step).round() * lot_step
+    } else {
+        lot
+    };
+    (snapped.clamp(min_lot, max_lot.max(min_lot)) * 1e8).round() / 1e8
+}
+
+/// `config` with the selected fields scaled in every matching row
+pub fn scale_fields(
+    config: &MTConfig,
+    selector: &ScaleSelector,
+    op: ScaleOp,
+    spec: Option<&SymbolSpec>,
+) -> Result<(MTConfig, ScaleReport), String> {
+    let mask = selector.field.trim();
+    if mask.is_empty() {
+        return Err("Select a field to scale".to_string());
+    }
+
+    let mut patched = config.clone();
+    let mut rows = 0;
+    let mut fields = BTreeSet::new();
+    let mut unknown = true;
+    let mut changes = Vec::new();
+    for (e, engine) in patched.engines.iter_mut().enumerate() {
+        for (g, group) in engine.groups.iter_mut().enumerate() {
+            for (l, logic) in group.logics.iter_mut().enumerate() {
+                if !row_matches(selector, &engine.engine_id, group.group_number, logic) {
+                    continue;
+                }
+                rows += 1;
+                let mut row = row_object(logic)?;
+                let mut changed = false;
+                for (field, value) in row.iter_mut() {
+                    if !mask_matches(mask, field) {
+                        continue;
+                    }
+                    unknown = false;
+                    // Booleans and names aren't scaled; optional overrides that are unset stay unset
+                    let Some(before) = value.as_f64() else {
+                        continue;
+                    };
+                    fields.insert(field.clone());
+                    let is_integer = value.is_i64() || value.is_u64();
+                    let raw = op.apply(before);
+                    let after = if is_lot_field(field) {
+                        // 0 leaves the lot unset (EA default / no cap)
+                        if before == 0.0 {
+                            continue;
+                        }
+                        clamp_lot(raw, spec)
+                    } else if is_integer {
+                        raw.round().max(0.0)
+                    } else {
+                        (raw.max(0.0) * 1e8).round() / 1e8
+                    };
+                    if after == before {
+                        continue;
+                    }
+                    changes.push(ScaledField {
+                        path: format!("/engines/{}/groups/{}/logics/{}/{}", e, g, l, field),
+                        logic_id: logic.logic_id.clone(),
+                        field: field.clone(),
+                        from: before,
+                        to: after,
+                        clamped: (after - raw).abs() > 1e-9,
+                    });
+                    *value = if is_integer {
+                        Value::from(after as i64)
+                    } else {
+                        Value::from(after)
+                    };
+                    changed = true;
+                }
+                if changed {
+                    *logic = serde_json::from_value(Value::Object(row)).map_err(|err| {
+                        format!("Scaled values don't fit {}: {}", logic.logic_id, err)
+                    })?;
+                }
+            }
+        }
+    }
+    if rows == 0 {
+        return Err("No logic rows match the selector".to_string());
+    }
+    if unknown {
+        return Err(format!("Unknown logic field '{}'", mask));
+    }
+    if fields.is_empty() {
+        return Err(format!("'{}' selects no numeric fields", mask));
+    }
+
+    let report = ScaleReport {
+        selector: selector.clone(),
+        fields: fields.into_iter().collect(),
+        rows,
+        changes,
+        symbol: spec.cloned(),
+        ..ScaleReport::default()
+    };
+    Ok((patched, report))
+}
+
+/// Multiply (`factor`) or offset (`delta`) the fields `selector` picks, e.g. "all
+/// initial_lot in engine B", in the config behind `handle` (default: the active
+/// one). Lots are clamped to `symbol`'s broker limits; `dry_run` only reports.
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn scale_config_fields(
+    handle: Option<String>,
+    selector: String,
+    factor: Option<f64>,
+    delta: Option<f64>,
+    symbol: Option<String>,
+    csv_path: Option<String>,
+    dry_run: Option<bool>,
+    state: State<'_, MTBridgeState>,
+) -> Result<ScaleReport, String> {
+    let op = ScaleOp::from_args(factor, delta)?;
+    let selector = parse_selector(&selector)?;
+    let spec = match symbol.filter(|s| !s.trim().is_empty()) {
+        Some(symbol) => Some(
+            symbols::lookup(&symbol, csv_path)?
+                .ok_or_else(|| format!("No spec known for symbol '{}'", symbol))?,
+        ),
+        None => None,
+    };
+    apply_bulk_edit(&state, handle, dry_run, |current| {
+        scale_fields(current, &selector, op, spec.as_ref())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+    use crate::symbols::catalog_spec;
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            parse_selector("all initial_lot in engine b").unwrap(),
+            ScaleSelector {
+                field: "initial_lot".to_string(),
+                engine_id: Some("B".to_string()),
+                ..ScaleSelector::default()
+            }
+        );
+        let selector = parse_selector("trail_* group 3 logic power").unwrap();
+        assert_eq!(selector.field, "trail_*");
+        assert_eq!(selector.group_number, Some(3));
+        assert_eq!(selector.logic_name.as_deref(), Some("POWER"));
+        assert_eq!(parse_selector("all grid values").unwrap().field, "grid");
+
+        assert!(parse_selector("all values in engine A").is_err());
+        assert!(parse_selector("grid multiplier").is_err());
+        assert!(parse_selector("grid group x").is_err());
+        assert!(parse_selector("grid engine").is_err());
+    }
+
+    #[test]
+    fn test_scale_lots_with_broker_clamping() {
+        let mut config = create_full_v19_config();
+        for group in &mut config.engines[1].groups {
+            for logic in &mut group.logics {
+                logic.initial_lot = 0.04;
+            }
+        }
+        let spec = catalog_spec("EURUSD").unwrap();
+        let selector = parse_selector("all initial_lot in engine B").unwrap();
+
+        let (patched, report) =
+            scale_fields(&config, &selector, ScaleOp::Factor(0.55), Some(&spec)).unwrap();
+        assert_eq!(report.rows, config.engines[1].groups.len() * 14);
+        assert_eq!(report.fields, vec!["initial_lot".to_string()]);
+        for logic in patched.engines[1].groups.iter().flat_map(|g| &g.logics) {
+            // 0.022 snaps back onto the 0.01 lot step
+            assert!((logic.initial_lot - 0.02).abs() < 1e-9);
+        }
+        assert_eq!(
+            serde_json::to_value(&patched.engines[0]).unwrap(),
+            serde_json::to_value(&config.engines[0]).unwrap()
+        );
+        assert!(report.changes.iter().all(|c| c.clamped));
+
+        let (patched, _) =
+            scale_fields(&config, &selector, ScaleOp::Delta(-1.0), Some(&spec)).unwrap();
+        assert!(patched.engines[1]
+            .groups
+            .iter()
+            .flat_map(|g| &g.logics)
+            .all(|l| l.initial_lot == spec.min_lot));
+    }
+
+    #[test]
+    fn test_scale_grid_values() {
+        let config = create_full_v19_config();
+        let selector = parse_selector("all grid values").unwrap();
+        let (patched, report) =
+            scale_fields(&config, &selector, ScaleOp::Factor(2.0), None).unwrap();
+        let before = &config.engines[2].groups[4].logics[3];
+        let after = &patched.engines[2].groups[4].logics[3];
+        assert_eq!(after.grid, before.grid * 2.0);
+        assert!(report
+            .changes
+            .iter()
+            .all(|c| c.field == "grid" && !c.clamped));
+
+        let (patched, _) = scale_fields(&config, &selector, ScaleOp::Delta(-1e9), None).unwrap();
+        assert!(patched.engines[0].groups[0]
+            .logics
+            .iter()
+            .all(|l| l.grid == 0.0));
+
+        let unknown = ScaleSelector {
+            field: "gird".to_string(),
+            ..ScaleSelector::default()
+        };
+        assert!(scale_fields(&config, &unknown, ScaleOp::Factor(2.0), None).is_err());
+        let names = ScaleSelector {
+            field: "logic_name".to_string(),
+            ..ScaleSelector::default()
+        };
+        assert!(scale_fields(&config, &names, ScaleOp::Factor(2.0), None).is_err());
+        assert!(ScaleOp::from_args(Some(0.0), None).is_err());
+        assert!(ScaleOp::from_args(Some(2.0), Some(1.0)).is_err());
+    }
+}
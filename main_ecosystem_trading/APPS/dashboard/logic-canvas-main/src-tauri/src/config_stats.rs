@@ -0,0 +1,188 @@
+// ============================================
+// CONFIG STATS
+// ============================================
+//
+// Aggregate numbers for the editor header and engine cards (enabled logics,
+// worst-case lots, reverse/hedge groups, sessions, magic numbers). Computed
+// here from the config behind a handle instead of walking the whole MTConfig
+// in the frontend after every edit.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::mt_bridge::{EngineConfig, MTConfig};
+use crate::risk::{ladder_levels, ladder_params};
+
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineStats {
+    pub engine_id: String,
+    pub enabled_groups: usize,
+    /// Enabled logics inside enabled groups
+    pub enabled_logics: usize,
+    /// Lots with every enabled ladder fully opened, buy and sell added up
+    pub worst_case_lots: f64,
+    /// Enabled groups with group reverse/hedge mode or any logic reversing/hedging
+    pub reverse_groups: Vec<u8>,
+    pub hedge_groups: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigStats {
+    pub handle: String,
+    pub engines: Vec<EngineStats>,
+    pub enabled_logics: usize,
+    pub worst_case_lots: f64,
+    /// Enabled sessions across the shared, buy and sell time filters
+    pub sessions_enabled: usize,
+    /// Distinct non-zero magic numbers (global, per direction, reverse/hedge bases)
+    pub magic_numbers: Vec<i32>,
+}
+
+fn engine_stats(engine: &EngineConfig) -> EngineStats {
+    let max_orders = engine.max_power_orders.max(1) as usize;
+    let mut stats = EngineStats {
+        engine_id: engine.engine_id.clone(),
+        enabled_groups: 0,
+        enabled_logics: 0,
+        worst_case_lots: 0.0,
+        reverse_groups: Vec::new(),
+        hedge_groups: Vec::new(),
+    };
+
+    for group in engine.groups.iter().filter(|g| g.enabled) {
+        stats.enabled_groups += 1;
+        let logics: Vec<_> = group.logics.iter().filter(|l| l.enabled).collect();
+        stats.enabled_logics += logics.len();
+
+        for logic in &logics {
+            for (is_buy, allowed) in [(true, logic.allow_buy), (false, logic.allow_sell)] {
+                if !allowed {
+                    continue;
+                }
+                let ladder = ladder_params(logic, is_buy, max_orders);
+                if ladder.initial_lot > 0.0 {
+                    stats.worst_case_lots += ladder_levels(&ladder)
+                        .iter()
+                        .map(|(_, lot)| lot)
+                        .sum::<f64>();
+                }
+            }
+        }
+
+        let reverse = logics.iter().any(|l| {
+            l.reverse_enabled
+                || l.reverse_enabled_b == Some(true)
+                || l.reverse_enabled_s == Some(true)
+        });
+        if group.reverse_mode || reverse {
+            stats.reverse_groups.push(group.group_number);
+        }
+        let hedge = logics.iter().any(|l| {
+            l.hedge_enabled || l.hedge_enabled_b == Some(true) || l.hedge_enabled_s == Some(true)
+        });
+        if group.hedge_mode || hedge {
+            stats.hedge_groups.push(group.group_number);
+        }
+    }
+    stats
+}
+
+pub fn config_stats(handle: String, config: &MTConfig) -> ConfigStats {
+    let engines: Vec<EngineStats> = config.engines.iter().map(engine_stats).collect();
+
+    let general = &config.general;
+    let sessions_enabled = [
+        Some(&general.time_filters),
+        general.time_filters_b.as_ref(),
+        general.time_filters_s.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|filters| filters.enabled)
+    .flat_map(|filters| filters.sessions.iter())
+    .filter(|session| session.enabled)
+    .count();
+
+    let magic_numbers: BTreeSet<i32> = [
+        general.magic_number,
+        general.magic_number_buy,
+        general.magic_number_sell,
+        general.reverse_magic_base,
+        general.hedge_magic_base,
+    ]
+    .into_iter()
+    .filter(|magic| *magic != 0)
+    .collect();
+
+    ConfigStats {
+        handle,
+        enabled_logics: engines.iter().map(|e| e.enabled_logics).sum(),
+        worst_case_lots: engines.iter().map(|e| e.worst_case_lots).sum(),
+        engines,
+        sessions_enabled,
+        magic_numbers: magic_numbers.into_iter().collect(),
+    }
+}
+
+/// Stats for the config behind `handle` (default: the active one)
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_config_summary(
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<ConfigStats, String> {
+    let configs = state.configs.read().await;
+    let handle = configs.resolve(handle.as_deref())?;
+    Ok(config_stats(
+        handle.clone(),
+        &configs.get(Some(&handle))?.config,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_stats_follow_enabled_logics() {
+        let mut config = create_full_v19_config();
+        for engine in &mut config.engines {
+            for group in &mut engine.groups {
+                group.enabled = group.group_number == 1;
+                group.reverse_mode = false;
+                group.hedge_mode = false;
+                for logic in &mut group.logics {
+                    logic.enabled = false;
+                }
+            }
+        }
+        let engine = &mut config.engines[0];
+        engine.max_power_orders = 3;
+        let logic = &mut engine.groups[0].logics[0];
+        logic.enabled = true;
+        logic.allow_buy = true;
+        logic.allow_sell = false;
+        logic.initial_lot = 0.1;
+        logic.initial_lot_b = None;
+        logic.multiplier = 2.0;
+        logic.multiplier_b = None;
+        logic.hedge_enabled = true;
+
+        let stats = config_stats("cfg-1".to_string(), &config);
+        assert_eq!(stats.enabled_logics, 1);
+        assert_eq!(stats.engines[0].enabled_logics, 1);
+        // 0.1 + 0.2 + 0.4
+        assert!((stats.worst_case_lots - 0.7).abs() < 1e-9);
+        assert_eq!(stats.engines[0].hedge_groups, vec![1]);
+        assert!(stats.engines[0].reverse_groups.is_empty());
+        assert!(!stats.magic_numbers.contains(&0));
+    }
+}
@@ -0,0 +1,169 @@
+// ============================================
+// CONFIG WIRE CASE
+// ============================================
+//
+// MTConfig and its sections serialize with snake_case field names, which is
+// what vault files, field locks and patch paths are keyed on, while the newer
+// command payloads are camelCase. This layer converts a config's JSON between
+// the two so camelCase frontends can read and write configs without their own
+// field mappers. Inbound JSON may use either spelling (or a mix); it is folded
+// back to snake_case before it reaches serde.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::mt_bridge::MTConfig;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireCase {
+    #[default]
+    Snake,
+    Camel,
+}
+
+/// `trail_step_2_b` -> `trailStep2B`
+pub(crate) fn to_camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = !out.is_empty();
+        } else if upper_next {
+            out.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `trailStep2B` -> `trail_step_2_b`; snake_case names come back unchanged
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        let boundary = match prev {
+            Some(p) if p != '_' => {
+                c.is_ascii_uppercase() || (c.is_ascii_digit() && !p.is_ascii_digit())
+            }
+            _ => false,
+        };
+        if boundary {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+        prev = Some(c);
+    }
+    out
+}
+
+/// Map field whose keys are data (JSON pointers), not field names
+const ANNOTATIONS_FIELD: &str = "annotations";
+
+/// Rename every object key in `value`; keys are field names everywhere except
+/// inside `annotations`, which is copied as is
+pub(crate) fn convert_keys(value: Value, case: WireCase) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, v)| {
+                    if key == ANNOTATIONS_FIELD {
+                        return (key, v);
+                    }
+                    let key = match case {
+                        WireCase::Snake => to_snake_case(&key),
+                        WireCase::Camel => to_camel_case(&key),
+                    };
+                    (key, convert_keys(v, case))
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(|v| convert_keys(v, case)).collect())
+        }
+        other => other,
+    }
+}
+
+pub(crate) fn config_to_wire(config: &MTConfig, case: WireCase) -> Result<Value, String> {
+    let value =
+        serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    Ok(match case {
+        WireCase::Snake => value,
+        WireCase::Camel => convert_keys(value, WireCase::Camel),
+    })
+}
+
+pub(crate) fn config_from_wire(value: Value) -> Result<MTConfig, String> {
+    serde_json::from_value(convert_keys(value, WireCase::Snake))
+        .map_err(|e| format!("Invalid config JSON: {}", e))
+}
+
+/// Config JSON with field names in `case` (snake_case when omitted)
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn config_to_json(config: MTConfig, case: Option<WireCase>) -> Result<Value, String> {
+    config_to_wire(&config, case.unwrap_or_default())
+}
+
+/// Config from JSON using snake_case or camelCase field names
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn config_from_json(value: Value) -> Result<MTConfig, String> {
+    config_from_wire(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn collect_keys(value: &Value, keys: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, v) in map {
+                    keys.push(key.clone());
+                    collect_keys(v, keys);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_keys(v, keys)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(to_camel_case("trail_step_2_b"), "trailStep2B");
+        assert_eq!(to_snake_case("trailStep2B"), "trail_step_2_b");
+        assert_eq!(to_snake_case("initial_lot"), "initial_lot");
+        assert_eq!(to_snake_case("0"), "0");
+    }
+
+    #[test]
+    fn test_every_config_field_round_trips() {
+        let snake = config_to_wire(&create_full_v19_config(), WireCase::Snake).unwrap();
+        let mut keys = Vec::new();
+        collect_keys(&snake, &mut keys);
+        for key in keys {
+            assert_eq!(to_snake_case(&to_camel_case(&key)), key);
+        }
+    }
+
+    #[test]
+    fn test_camel_and_snake_payloads_agree() {
+        let mut config = create_full_v19_config();
+        let pointer = "/engines/0/groups/0/logics/1/grid".to_string();
+        config.annotations = Some([(pointer.clone(), "Wider grid".to_string())].into());
+        let snake = config_to_wire(&config, WireCase::Snake).unwrap();
+        let camel = config_to_wire(&config, WireCase::Camel).unwrap();
+        assert!(camel["general"].get("magicNumber").is_some());
+        assert!(camel["annotations"].get(&pointer).is_some());
+
+        let from_camel = config_from_wire(camel).unwrap();
+        let from_snake = config_from_wire(snake.clone()).unwrap();
+        assert_eq!(config_to_wire(&from_camel, WireCase::Snake).unwrap(), snake);
+        assert_eq!(config_to_wire(&from_snake, WireCase::Snake).unwrap(), snake);
+        assert_eq!(from_camel.annotations, config.annotations);
+        assert_eq!(from_snake.annotations, config.annotations);
+    }
+}
@@ -0,0 +1,662 @@
+// ============================================
+// PARAMETER CONSTRAINTS
+// ============================================
+//
+// User-defined relations between logic fields, e.g.
+//   trail_start <= trail_value
+//   last_lot >= initial_lot * multiplier ^ start_level if last_lot > 0
+// Each constraint is one comparison of two arithmetic expressions (+ - * / ^
+// and parentheses over numbers and logic field names), optionally guarded
+// by "if <comparison>". It is checked on every logic row of an open config
+// whenever that config changes; rows that leave a field unset are skipped.
+// A violation names the row and, when one side is a bare field, the value
+// that would satisfy it, addressed like apply_config_patch so the editor can
+// offer it as a one-click fix.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+use crate::config_handles::row_object;
+use crate::mt_bridge::{atomic_write, MTConfig};
+
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+const CONSTRAINTS_FILE: &str = "constraints.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConstraintSeverity {
+    #[default]
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Constraint {
+    pub id: String,
+    pub expression: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub severity: ConstraintSeverity,
+    /// Shown instead of the expression when set
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintSet {
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+}
+
+impl Default for ConstraintSet {
+    /// The relations most presets are expected to keep
+    fn default() -> Self {
+        let rule = |id: &str, expression: &str| Constraint {
+            id: id.to_string(),
+            expression: expression.to_string(),
+            enabled: true,
+            severity: ConstraintSeverity::Warning,
+            message: None,
+        };
+        Self {
+            constraints: vec![
+                rule("trail-start-within-trail", "trail_start <= trail_value"),
+                rule(
+                    "last-lot-covers-progression",
+                    "last_lot >= initial_lot * multiplier ^ start_level if last_lot > 0",
+                ),
+                rule("hedge-within-reverse", "hedge_scale <= reverse_scale"),
+            ],
+        }
+    }
+}
+
+/// Value that would satisfy a violated constraint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintFix {
+    /// JSON pointer of the field to change, e.g. /engines/0/groups/2/logics/1/trail_start
+    pub path: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintDiagnostic {
+    pub constraint_id: String,
+    pub expression: String,
+    pub severity: ConstraintSeverity,
+    pub message: String,
+    /// JSON pointer of the logic row
+    pub path: String,
+    pub engine_id: String,
+    pub group_number: u8,
+    pub logic_id: String,
+    /// Evaluated left and right side of the comparison
+    pub left: f64,
+    pub right: f64,
+    pub fix: Option<ConstraintFix>,
+}
+
+// ---------- expressions ----------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Field(String),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Le,
+    Lt,
+    Ge,
+    Gt,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    fn holds(self, left: f64, right: f64) -> bool {
+        const EPS: f64 = 1e-9;
+        match self {
+            Comparison::Le => left <= right + EPS,
+            Comparison::Lt => left < right - EPS,
+            Comparison::Ge => left + EPS >= right,
+            Comparison::Gt => left > right + EPS,
+            Comparison::Eq => (left - right).abs() <= EPS,
+            Comparison::Ne => (left - right).abs() > EPS,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Le => "<=",
+            Comparison::Lt => "<",
+            Comparison::Ge => ">=",
+            Comparison::Gt => ">",
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Relation {
+    left: Expr,
+    comparison: Comparison,
+    right: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    relation: Relation,
+    guard: Option<Relation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    Compare(Comparison),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(
+                    number
+                        .parse()
+                        .map_err(|_| format!("Invalid number '{}'", number))?,
+                ));
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let ident: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(ident.to_ascii_lowercase()));
+            }
+            '+' | '-' | '*' | '/' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '(' | ')' => {
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+                i += 1;
+            }
+            '<' | '>' | '=' | '!' => {
+                let (comparison, len) = match (c, next) {
+                    ('<', Some('=')) => (Comparison::Le, 2),
+                    ('>', Some('=')) => (Comparison::Ge, 2),
+                    ('=', Some('=')) => (Comparison::Eq, 2),
+                    ('!', Some('=')) => (Comparison::Ne, 2),
+                    ('<', _) => (Comparison::Lt, 1),
+                    ('>', _) => (Comparison::Gt, 1),
+                    ('=', _) => (Comparison::Eq, 1),
+                    _ => return Err("'!' must be followed by '='".to_string()),
+                };
+                tokens.push(Token::Compare(comparison));
+                i += len;
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn rule(&mut self) -> Result<Rule, String> {
+        let relation = self.relation()?;
+        let guard = match self.peek() {
+            Some(Token::Ident(word)) if word == "if" => {
+                self.pos += 1;
+                Some(self.relation()?)
+            }
+            _ => None,
+        };
+        match self.peek() {
+            None => Ok(Rule { relation, guard }),
+            Some(token) => Err(format!("Unexpected {:?} after the comparison", token)),
+        }
+    }
+
+    fn relation(&mut self) -> Result<Relation, String> {
+        let left = self.sum()?;
+        let comparison = match self.next() {
+            Some(Token::Compare(comparison)) => comparison,
+            _ => return Err("Expected a comparison (<=, <, >=, >, ==, !=)".to_string()),
+        };
+        let right = self.sum()?;
+        Ok(Relation {
+            left,
+            comparison,
+            right,
+        })
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut expr = self.product()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.product()?));
+        }
+        Ok(expr)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Op('-')) {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.atom()?;
+        if self.peek() == Some(&Token::Op('^')) {
+            self.pos += 1;
+            // Right-associative: a ^ b ^ c == a ^ (b ^ c)
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Ident(name)) if name != "if" => Ok(Expr::Field(name)),
+            Some(Token::Open) => {
+                let expr = self.sum()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Missing ')'".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Expression ends too early".to_string()),
+        }
+    }
+}
+
+fn parse_rule(expression: &str) -> Result<Rule, String> {
+    let tokens = tokenize(expression)?;
+    Parser { tokens, pos: 0 }
+        .rule()
+        .map_err(|e| format!("Invalid constraint '{}': {}", expression.trim(), e))
+}
+
+/// None when a field is unset or not numeric, or the result isn't finite
+fn eval(expr: &Expr, row: &Map<String, Value>) -> Option<f64> {
+    let value = match expr {
+        Expr::Number(n) => *n,
+        Expr::Field(name) => row.get(name)?.as_f64()?,
+        Expr::Neg(inner) => -eval(inner, row)?,
+        Expr::Binary(op, left, right) => {
+            let (left, right) = (eval(left, row)?, eval(right, row)?);
+            match op {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                '/' => left / right,
+                _ => left.powf(right),
+            }
+        }
+    };
+    value.is_finite().then_some(value)
+}
+
+/// Set the bare field on one side to the other side's value, rounded the way that keeps it satisfied
+fn suggest_fix(
+    relation: &Relation,
+    row: &Map<String, Value>,
+    prefix: &str,
+    left: f64,
+    right: f64,
+) -> Option<ConstraintFix> {
+    use Comparison::*;
+    let (field, target, round_up) = match (&relation.left, &relation.right, relation.comparison) {
+        (Expr::Field(field), _, Le | Eq) => (field, right, false),
+        (Expr::Field(field), _, Ge) => (field, right, true),
+        (_, Expr::Field(field), Le) => (field, left, true),
+        (_, Expr::Field(field), Ge | Eq) => (field, left, false),
+        _ => return None,
+    };
+    let integer = row.get(field).is_some_and(|v| v.is_i64() || v.is_u64());
+    let value = match (integer, relation.comparison) {
+        (true, Eq) => target.round(),
+        (true, _) if round_up => target.ceil(),
+        (true, _) => target.floor(),
+        (false, _) => (target * 1e8).round() / 1e8,
+    };
+    Some(ConstraintFix {
+        path: format!("{}/{}", prefix, field),
+        value,
+    })
+}
+
+impl ConstraintSet {
+    /// Every constraint parses; called before the set is saved
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = std::collections::BTreeSet::new();
+        for constraint in &self.constraints {
+            if constraint.id.trim().is_empty() {
+                return Err("Every constraint needs an id".to_string());
+            }
+            if !ids.insert(constraint.id.as_str()) {
+                return Err(format!("Duplicate constraint id '{}'", constraint.id));
+            }
+            parse_rule(&constraint.expression)?;
+        }
+        Ok(())
+    }
+
+    /// Violations of the enabled constraints on every logic row of `config`
+    pub fn check(&self, config: &MTConfig) -> Vec<ConstraintDiagnostic> {
+        let rules: Vec<(&Constraint, Rule)> = self
+            .constraints
+            .iter()
+            .filter(|c| c.enabled)
+            .filter_map(|c| parse_rule(&c.expression).ok().map(|rule| (c, rule)))
+            .collect();
+        let mut diagnostics = Vec::new();
+        if rules.is_empty() {
+            return diagnostics;
+        }
+        for (e, engine) in config.engines.iter().enumerate() {
+            for (g, group) in engine.groups.iter().enumerate() {
+                for (l, logic) in group.logics.iter().enumerate() {
+                    let Ok(row) = row_object(logic) else {
+                        continue;
+                    };
+                    let prefix = format!("/engines/{}/groups/{}/logics/{}", e, g, l);
+                    for (constraint, rule) in &rules {
+                        if let Some(guard) = &rule.guard {
+                            let applies = eval(&guard.left, &row)
+                                .zip(eval(&guard.right, &row))
+                                .is_some_and(|(a, b)| guard.comparison.holds(a, b));
+                            if !applies {
+                                continue;
+                            }
+                        }
+                        let relation = &rule.relation;
+                        let (Some(left), Some(right)) =
+                            (eval(&relation.left, &row), eval(&relation.right, &row))
+                        else {
+                            continue;
+                        };
+                        if relation.comparison.holds(left, right) {
+                            continue;
+                        }
+                        let message = constraint.message.clone().unwrap_or_else(|| {
+                            format!(
+                                "{}: {} is not {} {}",
+                                constraint.expression.trim(),
+                                left,
+                                relation.comparison.symbol(),
+                                right
+                            )
+                        });
+                        diagnostics.push(ConstraintDiagnostic {
+                            constraint_id: constraint.id.clone(),
+                            expression: constraint.expression.clone(),
+                            severity: constraint.severity,
+                            message,
+                            path: prefix.clone(),
+                            engine_id: engine.engine_id.clone(),
+                            group_number: group.group_number,
+                            logic_id: logic.logic_id.clone(),
+                            left,
+                            right,
+                            fix: suggest_fix(relation, &row, &prefix, left, right),
+                        });
+                    }
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+// ---------- settings ----------
+
+fn constraints_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(CONSTRAINTS_FILE)
+}
+
+pub(crate) fn load_constraints() -> ConstraintSet {
+    std::fs::read_to_string(constraints_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_constraints(constraints: &ConstraintSet) -> Result<(), String> {
+    let path = constraints_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(constraints)
+        .map_err(|e| format!("Failed to serialize constraints: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_constraints(state: State<'_, MTBridgeState>) -> Result<ConstraintSet, String> {
+    Ok(state.configs.read().await.constraints().clone())
+}
+
+/// Replace the constraint set and re-check every open config against it
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn set_constraints(
+    constraints: ConstraintSet,
+    state: State<'_, MTBridgeState>,
+) -> Result<ConstraintSet, String> {
+    constraints.validate()?;
+    save_constraints(&constraints)?;
+    state
+        .configs
+        .write()
+        .await
+        .set_constraints(constraints.clone());
+    Ok(constraints)
+}
+
+/// Violations found when the config behind `handle` (default: the active one) last changed
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_constraint_diagnostics(
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<Vec<ConstraintDiagnostic>, String> {
+    Ok(state
+        .configs
+        .read()
+        .await
+        .get(handle.as_deref())?
+        .diagnostics
+        .clone())
+}
+
+/// Check an unsaved config (or a draft constraint set) without storing either
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn check_constraints(
+    config: MTConfig,
+    constraints: Option<ConstraintSet>,
+) -> Result<Vec<ConstraintDiagnostic>, String> {
+    let constraints = constraints.unwrap_or_else(load_constraints);
+    constraints.validate()?;
+    Ok(constraints.check(&config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn row(fields: &[(&str, Value)]) -> Map<String, Value> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_eval_expressions() {
+        let values = row(&[
+            ("initial_lot", Value::from(0.01)),
+            ("multiplier", Value::from(2.0)),
+            ("start_level", Value::from(3)),
+        ]);
+        let rule = parse_rule("last_lot >= Initial_Lot * multiplier ^ start_level").unwrap();
+        assert_eq!(eval(&rule.relation.right, &values), Some(0.08));
+        assert_eq!(eval(&rule.relation.left, &values), None);
+
+        let rule = parse_rule("-(2 + 3) * 2 ^ 3 ^ 2 / 4 == 1").unwrap();
+        assert_eq!(eval(&rule.relation.left, &values), Some(-640.0));
+        assert!(parse_rule("a <= b if c > 0").unwrap().guard.is_some());
+
+        for invalid in [
+            "grid",
+            "grid <= ",
+            "grid <= (1",
+            "grid <= 1 2",
+            "grid ! 1",
+            "a $ b",
+        ] {
+            assert!(parse_rule(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_check_constraints_with_fixes() {
+        let mut config = create_full_v19_config();
+        for logic in config
+            .engines
+            .iter_mut()
+            .flat_map(|e| &mut e.groups)
+            .flat_map(|g| &mut g.logics)
+        {
+            logic.trail_start = logic.trail_value;
+            logic.hedge_scale = 0.0;
+            logic.last_lot = Some(0.0);
+        }
+        let logic = &mut config.engines[1].groups[2].logics[1];
+        logic.trail_value = 10.0;
+        logic.trail_start = 25.0;
+        let logic = &mut config.engines[0].groups[0].logics[2];
+        logic.hedge_scale = 150.0;
+        logic.reverse_scale = 100.0;
+
+        let set = ConstraintSet::default();
+        set.validate().unwrap();
+        let new = set.check(&config);
+        assert_eq!(new.len(), 2);
+
+        let trail = new
+            .iter()
+            .find(|d| d.constraint_id == "trail-start-within-trail")
+            .unwrap();
+        assert_eq!(trail.path, "/engines/1/groups/2/logics/1");
+        assert_eq!(trail.logic_id, "B_POWER_S_G3");
+        assert_eq!(
+            trail.fix,
+            Some(ConstraintFix {
+                path: "/engines/1/groups/2/logics/1/trail_start".to_string(),
+                value: 10.0,
+            })
+        );
+        let hedge = new
+            .iter()
+            .find(|d| d.constraint_id == "hedge-within-reverse")
+            .unwrap();
+        assert_eq!(hedge.fix.as_ref().unwrap().value, 100.0);
+
+        let mut disabled = set.clone();
+        disabled
+            .constraints
+            .iter_mut()
+            .for_each(|c| c.enabled = false);
+        assert!(disabled.check(&config).is_empty());
+
+        let mut duplicate = set.clone();
+        duplicate.constraints.push(set.constraints[0].clone());
+        assert!(duplicate.validate().is_err());
+    }
+
+    #[test]
+    fn test_guard_and_integer_fix() {
+        let rule = parse_rule("start_level >= 2 * grid_steps if last_lot > 0").unwrap();
+        let mut values = row(&[
+            ("start_level", Value::from(1)),
+            ("grid_steps", Value::from(1.5)),
+            ("last_lot", Value::from(0.0)),
+        ]);
+        let guard = rule.guard.as_ref().unwrap();
+        assert!(!guard.comparison.holds(
+            eval(&guard.left, &values).unwrap(),
+            eval(&guard.right, &values).unwrap()
+        ));
+
+        values.insert("last_lot".to_string(), Value::from(0.5));
+        let fix = suggest_fix(&rule.relation, &values, "/x", 1.0, 3.0).unwrap();
+        assert_eq!(fix.path, "/x/start_level");
+        assert_eq!(fix.value, 3.0);
+    }
+}
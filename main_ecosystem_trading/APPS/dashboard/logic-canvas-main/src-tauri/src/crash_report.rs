@@ -0,0 +1,289 @@
+// ============================================
+// CRASH REPORTS
+// ============================================
+//
+// A panic hook that turns "it just closed" into a file support can read:
+// panic message and location, thread, backtrace, app version and the last
+// entries of the recent-log ring, one JSON file per panic under
+// daavfx/crash_reports. It sees panics from command handlers and
+// background threads alike. Uploading is opt-in and per report; nothing
+// leaves the machine unless the user enables it in settings.
+
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Once;
+
+use crate::app_log::{self, LogEntry};
+use crate::mt_bridge::atomic_write;
+
+const CRASH_DIR: &str = "crash_reports";
+const SETTINGS_FILE: &str = "crash_reporting.json";
+/// Log entries attached to each report
+const CRASH_LOG_LINES: usize = 200;
+/// Oldest reports beyond this are deleted when a new one is written
+const MAX_CRASH_REPORTS: usize = 50;
+const UPLOAD_TIMEOUT_SECS: u64 = 20;
+
+static HOOK_INSTALLED: Once = Once::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: String,
+    pub app_version: String,
+    pub thread: String,
+    pub message: String,
+    /// "src/mt_bridge.rs:1234:9"
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub recent_logs: Vec<LogEntry>,
+    #[serde(default)]
+    pub uploaded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub timestamp: String,
+    pub app_version: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub uploaded: bool,
+}
+
+impl From<CrashReport> for CrashReportSummary {
+    fn from(report: CrashReport) -> Self {
+        CrashReportSummary {
+            id: report.id,
+            timestamp: report.timestamp,
+            app_version: report.app_version,
+            message: report.message,
+            location: report.location,
+            uploaded: report.uploaded,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportSettings {
+    /// Off by default; upload_crash_report refuses to send until enabled
+    pub upload_enabled: bool,
+    /// HTTPS endpoint that receives the report JSON
+    pub upload_url: String,
+}
+
+fn daavfx_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+}
+
+fn crash_dir() -> PathBuf {
+    daavfx_dir().join(CRASH_DIR)
+}
+
+fn settings_path() -> PathBuf {
+    daavfx_dir().join(SETTINGS_FILE)
+}
+
+fn load_settings() -> CrashReportSettings {
+    std::fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn report_path(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || id.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid crash report id '{}'", id));
+    }
+    Ok(crash_dir().join(format!("{}.json", id)))
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create crash report folder: {}", e))?;
+    let path = report_path(&report.id)?;
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    atomic_write(&path, &json)?;
+    prune_reports(MAX_CRASH_REPORTS);
+    Ok(path)
+}
+
+/// Report files, newest first (ids start with a sortable timestamp)
+fn report_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(crash_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+fn prune_reports(keep: usize) {
+    for path in report_files().into_iter().skip(keep) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn read_report(path: &PathBuf) -> Result<CrashReport, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read crash report: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse crash report: {}", e))
+}
+
+/// Install the crash-report panic hook in front of the existing one. Safe
+/// to call more than once.
+pub fn install_panic_hook() {
+    HOOK_INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let now = chrono::Local::now();
+            let report = CrashReport {
+                id: format!(
+                    "crash-{}-{}",
+                    now.format("%Y%m%d-%H%M%S"),
+                    &uuid::Uuid::new_v4().simple().to_string()[..8]
+                ),
+                timestamp: now.to_rfc3339(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                thread: std::thread::current()
+                    .name()
+                    .unwrap_or("<unnamed>")
+                    .to_string(),
+                message: payload_message(info.payload()),
+                location: info.location().map(|l| l.to_string()),
+                backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+                recent_logs: app_log::last_entries(CRASH_LOG_LINES),
+                uploaded: false,
+            };
+            match write_report(&report) {
+                Ok(path) => tracing::error!(
+                    panic = %report.message,
+                    file_path = %path.display(),
+                    "Panic captured in crash report"
+                ),
+                Err(err) => tracing::error!(error = %err, "Failed to write crash report"),
+            }
+            previous(info);
+        }));
+    });
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_crash_reports() -> Result<Vec<CrashReportSummary>, String> {
+    Ok(report_files()
+        .iter()
+        .filter_map(|path| read_report(path).ok())
+        .map(CrashReportSummary::from)
+        .collect())
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_crash_report(id: String) -> Result<CrashReport, String> {
+    read_report(&report_path(&id)?)
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn delete_crash_report(id: String) -> Result<(), String> {
+    std::fs::remove_file(report_path(&id)?)
+        .map_err(|e| format!("Failed to delete crash report: {}", e))
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_crash_report_settings() -> Result<CrashReportSettings, String> {
+    Ok(load_settings())
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_crash_report_settings(
+    settings: CrashReportSettings,
+) -> Result<CrashReportSettings, String> {
+    if settings.upload_enabled && !settings.upload_url.trim().starts_with("https://") {
+        return Err("Crash report upload needs an https:// URL".to_string());
+    }
+    let path = settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize crash report settings: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(settings)
+}
+
+/// Send one report to the configured endpoint; requires upload to be enabled
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn upload_crash_report(id: String) -> Result<CrashReportSummary, String> {
+    let settings = load_settings();
+    if !settings.upload_enabled {
+        return Err("Crash report upload is disabled in settings".to_string());
+    }
+    let path = report_path(&id)?;
+    let mut report = read_report(&path)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(settings.upload_url.trim())
+        .json(&report)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to upload crash report: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Crash report upload returned HTTP {}",
+            response.status()
+        ));
+    }
+
+    report.uploaded = true;
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(report.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_message_and_report_ids() {
+        let literal: Box<dyn Any + Send> = Box::new("index out of bounds");
+        let formatted: Box<dyn Any + Send> = Box::new(format!("bad group {}", 21));
+        let other: Box<dyn Any + Send> = Box::new(42);
+        assert_eq!(payload_message(literal.as_ref()), "index out of bounds");
+        assert_eq!(payload_message(formatted.as_ref()), "bad group 21");
+        assert_eq!(payload_message(other.as_ref()), "Box<dyn Any>");
+
+        assert!(report_path("crash-20261016-101500-ab12cd34").is_ok());
+        assert!(report_path("../settings").is_err());
+        assert!(report_path("").is_err());
+    }
+}
@@ -8,6 +8,7 @@
 //!
 //! Pure Rust implementation - no external ML dependencies.
 
+use crate::quantize::{Precision, QuantizedLinear};
 use rand::Rng;
 use std::collections::HashMap;
 
@@ -138,6 +139,16 @@ pub struct ExtractedParameter {
     pub is_denoised: bool,
 }
 
+/// Overall confidence of a diffusion prediction: the intent confidence
+/// scaled by how plausible the extracted parameters are on average
+pub fn refinement_confidence(intent_confidence: f32, params: &[ExtractedParameter]) -> f32 {
+    if params.is_empty() {
+        return intent_confidence;
+    }
+    let mean = params.iter().map(|p| p.confidence).sum::<f32>() / params.len() as f32;
+    (intent_confidence * mean).clamp(0.0, 1.0)
+}
+
 /// Diffusion denoiser - learns to remove noise from parameters
 pub struct DiffusionDenoiser {
     /// Embedding dimension
@@ -149,6 +160,8 @@ pub struct DiffusionDenoiser {
     b1: Vec<f32>,
     w2: Vec<f32>,
     b2: Vec<f32>,
+    /// Int8 copies of w1/w2 when running quantized
+    quantized: Option<(QuantizedLinear, QuantizedLinear)>,
     /// Known parameters
     known_params: HashMap<String, ParameterPattern>,
 }
@@ -170,31 +183,61 @@ impl DiffusionDenoiser {
                 .map(|_| rng.gen::<f32>() * 0.1)
                 .collect(),
             b2: vec![0.0f32; embed_dim],
+            quantized: None,
             known_params: get_known_parameters(),
         }
     }
 
+    pub fn precision(&self) -> Precision {
+        if self.quantized.is_some() {
+            Precision::Int8
+        } else {
+            Precision::F32
+        }
+    }
+
+    /// Switch the denoising MLP between f32 and int8 weights
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.quantized = match precision {
+            Precision::F32 => None,
+            Precision::Int8 => Some((
+                QuantizedLinear::from_in_out(&self.w1, self.embed_dim, self.hidden_dim),
+                QuantizedLinear::from_in_out(&self.w2, self.hidden_dim, self.embed_dim),
+            )),
+        };
+    }
+
     /// Forward pass - denoise a noisy parameter embedding
     fn denoise_step(&self, x: &[f32], noise_level: f32) -> Vec<f32> {
         // Simple MLP denoiser
         let mut hidden = vec![0.0f32; self.hidden_dim];
+        let mut output = vec![0.0f32; self.embed_dim];
 
-        // First layer
-        for i in 0..self.hidden_dim {
-            hidden[i] = self.b1[i];
-            for j in 0..self.embed_dim {
-                hidden[i] += x[j] * self.w1[j * self.hidden_dim + i];
+        if let Some((w1, w2)) = &self.quantized {
+            for (h, (p, b)) in hidden.iter_mut().zip(w1.forward(x).iter().zip(&self.b1)) {
+                *h = p + b;
+                *h = *h * 0.5 * (1.0 + (*h * 1.702).tanh());
+            }
+            for (o, (p, b)) in output.iter_mut().zip(w2.forward(&hidden).iter().zip(&self.b2)) {
+                *o = p + b;
+            }
+        } else {
+            // First layer
+            for i in 0..self.hidden_dim {
+                hidden[i] = self.b1[i];
+                for j in 0..self.embed_dim {
+                    hidden[i] += x[j] * self.w1[j * self.hidden_dim + i];
+                }
+                // GELU activation
+                hidden[i] = hidden[i] * 0.5 * (1.0 + (hidden[i] * 1.702).tanh());
             }
-            // GELU activation
-            hidden[i] = hidden[i] * 0.5 * (1.0 + (hidden[i] * 1.702).tanh());
-        }
 
-        // Second layer
-        let mut output = vec![0.0f32; self.embed_dim];
-        for i in 0..self.embed_dim {
-            output[i] = self.b2[i];
-            for j in 0..self.hidden_dim {
-                output[i] += hidden[j] * self.w2[j * self.embed_dim + i];
+            // Second layer
+            for i in 0..self.embed_dim {
+                output[i] = self.b2[i];
+                for j in 0..self.hidden_dim {
+                    output[i] += hidden[j] * self.w2[j * self.embed_dim + i];
+                }
             }
         }
 
@@ -412,6 +455,12 @@ impl TransformerWithDiffusion {
         (intent_str, confidence, params)
     }
 
+    /// Run both the transformer and the denoiser at `precision`
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.transformer.set_precision(precision);
+        self.denoiser.set_precision(precision);
+    }
+
     /// Train the transformer component
     pub fn train(
         &mut self,
@@ -458,4 +507,31 @@ mod tests {
         let result = denoiser.refine_semantic("SEMANTIC", "make it more aggressive");
         println!("Semantic params: {:?}", result);
     }
+
+    #[test]
+    fn test_refinement_confidence() {
+        let param = |confidence| ExtractedParameter {
+            name: "grid".to_string(),
+            value: 600.0,
+            confidence,
+            is_denoised: true,
+        };
+        assert_eq!(refinement_confidence(0.8, &[]), 0.8);
+        assert!((refinement_confidence(0.8, &[param(1.0), param(0.5)]) - 0.6).abs() < 1e-6);
+        assert_eq!(refinement_confidence(0.9, &[param(0.0)]), 0.0);
+    }
+
+    #[test]
+    fn test_quantized_denoise_step_close_to_f32() {
+        let mut denoiser = DiffusionDenoiser::new();
+        let x: Vec<f32> = (0..denoiser.embed_dim).map(|i| (i as f32 / 32.0) - 1.0).collect();
+        let exact = denoiser.denoise_step(&x, 0.3);
+
+        denoiser.set_precision(Precision::Int8);
+        assert_eq!(denoiser.precision(), Precision::Int8);
+        let quantized = denoiser.denoise_step(&x, 0.3);
+        for (q, e) in quantized.iter().zip(&exact) {
+            assert!((q - e).abs() < 0.05 * e.abs().max(1.0), "{} vs {}", q, e);
+        }
+    }
 }
@@ -0,0 +1,237 @@
+// ============================================
+// BUY / SELL DIRECTION MIRRORING
+// ============================================
+//
+// Most users tune the Buy side carefully and want Sell to match. The
+// per-direction overrides live in paired option fields (grid_b / grid_s,
+// trail_step_mode_3_b / trail_step_mode_3_s, ...), which the editor only
+// sets one at a time. Mirroring copies every *_b value of a row onto its
+// *_s twin (or the reverse), including unset overrides, so the mirrored
+// side falls back to the base value exactly like the source does. Rows are
+// picked with a clipboard-style scope; split Buy/Sell rows of a v19 config
+// are mirrored with copy_logic_params instead.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config_handles::{row_object, BulkEditOutcome, BulkEditReport};
+use crate::logic_copy::LogicFieldChange;
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::param_clipboard::BlockAddress;
+
+#[cfg(feature = "tauri-app")]
+use crate::config_handles::apply_bulk_edit;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Buy,
+    Sell,
+}
+
+impl Direction {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "buy" | "b" => Ok(Direction::Buy),
+            "sell" | "s" => Ok(Direction::Sell),
+            other => Err(format!("Unknown direction '{}' (use Buy or Sell)", other)),
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Direction::Buy => "_b",
+            Direction::Sell => "_s",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorReport {
+    #[serde(flatten)]
+    pub outcome: BulkEditOutcome,
+    pub from: Direction,
+    pub to: Direction,
+    /// Rows the scope matched
+    pub rows: usize,
+    pub changes: Vec<LogicFieldChange>,
+}
+
+impl BulkEditReport for MirrorReport {
+    fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    fn outcome_mut(&mut self) -> &mut BulkEditOutcome {
+        &mut self.outcome
+    }
+}
+
+fn in_scope(
+    scope: Option<&BlockAddress>,
+    engine_id: &str,
+    group_number: u8,
+    logic: &LogicConfig,
+) -> bool {
+    let Some(scope) = scope else {
+        return true;
+    };
+    (scope.engine_id == "*" || scope.engine_id.eq_ignore_ascii_case(engine_id))
+        && scope.group_number.map_or(true, |n| n == group_number)
+        && scope.logic.as_deref().map_or(true, |l| {
+            l == logic.logic_id || l.eq_ignore_ascii_case(&logic.logic_name)
+        })
+}
+
+/// `config` with the `from` overrides of every row in `scope` (None: all rows) copied to `to`
+pub fn mirror_direction(
+    config: &MTConfig,
+    from: Direction,
+    to: Direction,
+    scope: Option<&BlockAddress>,
+) -> Result<(MTConfig, MirrorReport), String> {
+    if from == to {
+        return Err("Pick two different directions to mirror".to_string());
+    }
+    let mut patched = config.clone();
+    let mut rows = 0;
+    let mut changes = Vec::new();
+    for (e, engine) in patched.engines.iter_mut().enumerate() {
+        for (g, group) in engine.groups.iter_mut().enumerate() {
+            for (l, logic) in group.logics.iter_mut().enumerate() {
+                if !in_scope(scope, &engine.engine_id, group.group_number, logic) {
+                    continue;
+                }
+                rows += 1;
+                let mut row = row_object(logic)?;
+                // Every *_b field has a *_s twin; unset ones are left out of the JSON,
+                // so collect the pairs from whichever side is present
+                let mut bases: Vec<String> = row
+                    .keys()
+                    .filter_map(|key| {
+                        key.strip_suffix(from.suffix())
+                            .or_else(|| key.strip_suffix(to.suffix()))
+                    })
+                    .map(str::to_string)
+                    .collect();
+                bases.sort();
+                bases.dedup();
+                let mut changed = false;
+                for base in bases {
+                    let source = format!("{}{}", base, from.suffix());
+                    let target = format!("{}{}", base, to.suffix());
+                    let after = row.get(&source).cloned().unwrap_or(Value::Null);
+                    let before = row.get(&target).cloned().unwrap_or(Value::Null);
+                    if before == after {
+                        continue;
+                    }
+                    changes.push(LogicFieldChange {
+                        path: format!("/engines/{}/groups/{}/logics/{}/{}", e, g, l, target),
+                        logic_id: logic.logic_id.clone(),
+                        field: target.clone(),
+                        from: before,
+                        to: after.clone(),
+                    });
+                    row.insert(target, after);
+                    changed = true;
+                }
+                if changed {
+                    *logic = serde_json::from_value(Value::Object(row)).map_err(|err| {
+                        format!("Mirrored values don't fit {}: {}", logic.logic_id, err)
+                    })?;
+                }
+            }
+        }
+    }
+    if rows == 0 {
+        return Err("No logic rows match the scope".to_string());
+    }
+
+    let report = MirrorReport {
+        outcome: BulkEditOutcome::default(),
+        from,
+        to,
+        rows,
+        changes,
+    };
+    Ok((patched, report))
+}
+
+/// Copy every Buy (`from`, default "Buy") override of the rows in `scope` onto the
+/// Sell side (`to`, default "Sell") in the config behind `handle` (default: the
+/// active one); `dry_run` only reports what would change
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn mirror_direction_params(
+    handle: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    scope: Option<BlockAddress>,
+    dry_run: Option<bool>,
+    state: State<'_, MTBridgeState>,
+) -> Result<MirrorReport, String> {
+    let from = Direction::parse(from.as_deref().unwrap_or("Buy"))?;
+    let to = Direction::parse(to.as_deref().unwrap_or(match from {
+        Direction::Buy => "Sell",
+        Direction::Sell => "Buy",
+    }))?;
+    apply_bulk_edit(&state, handle, dry_run, |current| {
+        mirror_direction(current, from, to, scope.as_ref())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+    use daavfx_core::config_enums::TrailStepMode;
+
+    #[test]
+    fn test_mirror_buy_overrides_to_sell() {
+        let mut config = create_full_v19_config();
+        for group in &mut config.engines[0].groups {
+            for logic in &mut group.logics {
+                logic.grid_b = Some(450.0);
+                logic.trail_step_mode_3_b = Some(TrailStepMode::Auto);
+                logic.hedge_enabled_s = Some(true);
+            }
+        }
+        config.engines[1].groups[0].logics[0].grid_b = Some(10.0);
+
+        let scope = BlockAddress {
+            engine_id: "a".to_string(),
+            group_number: None,
+            logic: None,
+        };
+        let (patched, report) =
+            mirror_direction(&config, Direction::Buy, Direction::Sell, Some(&scope)).unwrap();
+        assert_eq!(report.rows, 15 * 14);
+        for logic in patched.engines[0].groups.iter().flat_map(|g| &g.logics) {
+            assert_eq!(logic.grid_s, Some(450.0));
+            assert_eq!(logic.trail_step_mode_3_s, Some(TrailStepMode::Auto));
+            // Unset on the Buy side, so Sell falls back to the base value too
+            assert_eq!(logic.hedge_enabled_s, None);
+            assert_eq!(logic.grid_b, Some(450.0));
+        }
+        assert_eq!(patched.engines[1].groups[0].logics[0].grid_s, None);
+        assert!(report
+            .changes
+            .iter()
+            .any(|c| c.path == "/engines/0/groups/0/logics/0/hedge_enabled_s" && c.to.is_null()));
+        assert_eq!(report.changes.len(), 15 * 14 * 3);
+
+        let (reverse, report) =
+            mirror_direction(&patched, Direction::Sell, Direction::Buy, Some(&scope)).unwrap();
+        assert!(report.changes.is_empty());
+        assert_eq!(reverse.engines[0].groups[0].logics[0].grid_b, Some(450.0));
+
+        assert!(mirror_direction(&config, Direction::Buy, Direction::Buy, None).is_err());
+        assert!(Direction::parse("short").is_err());
+        assert_eq!(Direction::parse(" SELL ").unwrap(), Direction::Sell);
+    }
+}
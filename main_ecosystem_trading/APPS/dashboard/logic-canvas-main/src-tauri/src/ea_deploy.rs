@@ -0,0 +1,226 @@
+// ============================================
+// EA DEPLOYMENT
+// ============================================
+//
+// Copies a compiled EA (.ex4/.ex5) into the Experts folder of each selected
+// terminal. The binary it replaces is rotated into <name>.bak1..bakN like
+// ACTIVE.set exports, and each copy leaves a <name>.deploy.json stamp
+// recording which build is installed. With `restart`, terminals that were
+// running are closed gracefully and relaunched so they load the new build.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::active_backup::rotate_backups;
+use crate::mt_bridge::atomic_write;
+use crate::terminal_discovery::{find_terminal, resolve_terminal_data_path};
+use crate::terminal_processes::restart_terminal_process;
+
+/// Previous binaries kept per terminal
+pub const EA_BACKUPS: usize = 5;
+
+const STAMP_SUFFIX: &str = ".deploy.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EaDeployStamp {
+    pub file_name: String,
+    /// SHA-256 of the deployed binary
+    pub sha256: String,
+    /// SHA-256 of the binary it replaced, if there was one
+    pub previous_sha256: Option<String>,
+    pub source_path: String,
+    pub app_version: String,
+    pub deployed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EaDeployment {
+    /// Terminal id or data folder as passed in
+    pub terminal: String,
+    pub target_path: Option<String>,
+    pub stamp: Option<EaDeployStamp>,
+    pub error: Option<String>,
+    /// Pid of the relaunched terminal; None when not restarted or it was not running
+    pub restarted_pid: Option<u32>,
+    pub restart_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EaDeployReport {
+    pub deployments: Vec<EaDeployment>,
+}
+
+/// MQL4/Experts for .ex4, MQL5/Experts for .ex5; the terminal must be that platform
+fn experts_dir(data_path: &Path, binary: &Path) -> Result<PathBuf, String> {
+    let extension = binary
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mql = match extension.as_str() {
+        "ex4" => "MQL4",
+        "ex5" => "MQL5",
+        _ => {
+            return Err(format!(
+                "{} is not a compiled EA (.ex4 or .ex5)",
+                binary.display()
+            ))
+        }
+    };
+    let mql_dir = data_path.join(mql);
+    if !mql_dir.is_dir() {
+        return Err(format!(
+            "{} has no {} folder; .{} builds only run on {}",
+            data_path.display(),
+            mql,
+            extension,
+            if mql == "MQL4" { "MT4" } else { "MT5" }
+        ));
+    }
+    Ok(mql_dir.join("Experts"))
+}
+
+fn stamp_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(STAMP_SUFFIX);
+    target.with_file_name(name)
+}
+
+/// Copy `binary` into one terminal, backing up and stamping as it goes
+pub(crate) fn deploy_to_terminal(
+    binary: &Path,
+    data_path: &Path,
+) -> Result<(PathBuf, EaDeployStamp), String> {
+    let experts = experts_dir(data_path, binary)?;
+    fs::create_dir_all(&experts)
+        .map_err(|e| format!("Failed to create {}: {}", experts.display(), e))?;
+    let file_name = binary
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = experts.join(&file_name);
+    let bytes =
+        fs::read(binary).map_err(|e| format!("Failed to read {}: {}", binary.display(), e))?;
+
+    let previous_sha256 = rotate_backups(&target, EA_BACKUPS)?;
+    // Written to a temp file and renamed, so the terminal never loads a half-copied binary
+    atomic_write(&target, &bytes)?;
+
+    let stamp = EaDeployStamp {
+        file_name,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+        previous_sha256,
+        source_path: binary.to_string_lossy().to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        deployed_at: chrono::Local::now().to_rfc3339(),
+    };
+    let json = serde_json::to_string_pretty(&stamp)
+        .map_err(|e| format!("Failed to serialize deploy stamp: {}", e))?;
+    atomic_write(&stamp_path(&target), &json)?;
+    Ok((target, stamp))
+}
+
+/// Copy a compiled EA into each terminal's Experts folder (terminal ids from
+/// list_terminal_profiles or data folders), restarting the running ones if `restart`
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn deploy_ea(
+    binary_path: String,
+    profiles: Vec<String>,
+    restart: Option<bool>,
+) -> Result<EaDeployReport, String> {
+    crate::safe_mode::ensure_writable("Deploying the EA")?;
+    crate::licensing::ensure_licensed("Deploying the EA")?;
+    let binary = PathBuf::from(&binary_path);
+    if !binary.is_file() {
+        return Err(format!("EA binary not found: {}", binary_path));
+    }
+    if profiles.is_empty() {
+        return Err("Select at least one terminal to deploy to".to_string());
+    }
+    let restart = restart.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        let deployments = profiles
+            .into_iter()
+            .map(|terminal| deploy_one(&binary, terminal, restart))
+            .collect();
+        EaDeployReport { deployments }
+    })
+    .await
+    .map_err(|e| format!("EA deployment failed: {}", e))
+}
+
+fn deploy_one(binary: &Path, terminal: String, restart: bool) -> EaDeployment {
+    let deployed = resolve_terminal_data_path(&terminal).and_then(|data_path| {
+        deploy_to_terminal(binary, &data_path).map(|deployed| (data_path, deployed))
+    });
+    match deployed {
+        Ok((data_path, (target, stamp))) => {
+            let restarted = restart.then(|| {
+                let wine_prefix = find_terminal(&terminal).and_then(|t| t.wine_prefix);
+                restart_terminal_process(&data_path, wine_prefix.as_deref())
+            });
+            let (restarted_pid, restart_error) = match restarted {
+                Some(Ok(pid)) => (pid, None),
+                Some(Err(e)) => (None, Some(e)),
+                None => (None, None),
+            };
+            EaDeployment {
+                terminal,
+                target_path: Some(target.to_string_lossy().to_string()),
+                stamp: Some(stamp),
+                error: None,
+                restarted_pid,
+                restart_error,
+            }
+        }
+        Err(e) => EaDeployment {
+            terminal,
+            target_path: None,
+            stamp: None,
+            error: Some(e),
+            restarted_pid: None,
+            restart_error: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::active_backup::backup_path;
+
+    #[test]
+    fn test_deploy_backs_up_and_stamps() {
+        let root = std::env::temp_dir().join(format!("daavfx_deploy_{}", std::process::id()));
+        let data = root.join("terminal");
+        fs::create_dir_all(data.join("MQL4")).unwrap();
+        let binary = root.join("DAAVFX.ex4");
+
+        fs::write(&binary, b"build 1").unwrap();
+        let (target, first) = deploy_to_terminal(&binary, &data).unwrap();
+        assert_eq!(target, data.join("MQL4").join("Experts").join("DAAVFX.ex4"));
+        assert_eq!(first.previous_sha256, None);
+
+        fs::write(&binary, b"build 2").unwrap();
+        let (_, second) = deploy_to_terminal(&binary, &data).unwrap();
+        assert_eq!(second.previous_sha256, Some(first.sha256.clone()));
+        assert_eq!(fs::read(&target).unwrap(), b"build 2");
+        assert_eq!(fs::read(backup_path(&target, 1)).unwrap(), b"build 1");
+
+        let stamp: EaDeployStamp =
+            serde_json::from_str(&fs::read_to_string(stamp_path(&target)).unwrap()).unwrap();
+        assert_eq!(stamp.sha256, second.sha256);
+
+        let mt5_build = root.join("DAAVFX.ex5");
+        fs::write(&mt5_build, b"build 1").unwrap();
+        assert!(deploy_to_terminal(&mt5_build, &data).is_err());
+        assert!(experts_dir(&data, &root.join("DAAVFX.mq4")).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
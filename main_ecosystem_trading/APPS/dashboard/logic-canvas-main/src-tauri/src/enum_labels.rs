@@ -0,0 +1,399 @@
+// ============================================
+// ENUM LABEL CATALOG
+// ============================================
+//
+// Display names for the identifier-style values configs carry
+// (TriggerAction_StopEA, PartialMode_Mid, Compound_Equity...). The values
+// themselves stay as they are in configs and setfiles; this only decides
+// what a person reads. Legacy spellings resolve to their canonical entry,
+// and identifiers missing from the catalog are shown as-is.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    En,
+    Es,
+    Ru,
+}
+
+impl Locale {
+    /// "es", "es-MX", "ru_RU"...; languages without a catalog fall back to English
+    pub fn from_tag(tag: &str) -> Locale {
+        let language = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "es" => Locale::Es,
+            "ru" => Locale::Ru,
+            _ => Locale::En,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Locale::En => 0,
+            Locale::Es => 1,
+            Locale::Ru => 2,
+        }
+    }
+}
+
+struct CatalogEntry {
+    group: &'static str,
+    id: &'static str,
+    /// EN, ES, RU
+    labels: [&'static str; 3],
+}
+
+const fn entry(group: &'static str, id: &'static str, labels: [&'static str; 3]) -> CatalogEntry {
+    CatalogEntry { group, id, labels }
+}
+
+const CATALOG: &[CatalogEntry] = &[
+    entry(
+        "triggerAction",
+        "TriggerAction_None",
+        ["None", "Ninguna", "Нет"],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_StopEA",
+        ["Stop EA", "Detener EA", "Остановить советник"],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_StopEA_KeepTrades",
+        [
+            "Stop EA, keep trades",
+            "Detener EA, mantener operaciones",
+            "Остановить советник, сохранить сделки",
+        ],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_CloseAll",
+        [
+            "Close all trades",
+            "Cerrar todas las operaciones",
+            "Закрыть все сделки",
+        ],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_KeepEA_CloseTrades",
+        [
+            "Keep EA running, close trades",
+            "Mantener EA, cerrar operaciones",
+            "Оставить советник, закрыть сделки",
+        ],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_StopEA_CloseTrades",
+        [
+            "Stop EA, close trades",
+            "Detener EA, cerrar operaciones",
+            "Остановить советник, закрыть сделки",
+        ],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_PauseEA_CloseTrades",
+        [
+            "Pause EA, close trades",
+            "Pausar EA, cerrar operaciones",
+            "Приостановить советник, закрыть сделки",
+        ],
+    ),
+    entry(
+        "triggerAction",
+        "TriggerAction_PauseEA_KeepTrades",
+        [
+            "Pause EA, keep trades",
+            "Pausar EA, mantener operaciones",
+            "Приостановить советник, сохранить сделки",
+        ],
+    ),
+    entry(
+        "triggerMode",
+        "TriggerMode_OnTick",
+        ["Every tick", "En cada tick", "На каждом тике"],
+    ),
+    entry(
+        "triggerMode",
+        "TriggerMode_FirstTick",
+        [
+            "First tick only",
+            "Solo el primer tick",
+            "Только первый тик",
+        ],
+    ),
+    entry(
+        "triggerMode",
+        "TriggerMode_WaitBar",
+        [
+            "Wait for a new bar",
+            "Esperar una nueva barra",
+            "Ждать новый бар",
+        ],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_Immediate",
+        ["Immediately", "Inmediatamente", "Немедленно"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_AfterBars",
+        ["After N bars", "Tras N barras", "Через N баров"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_AfterSeconds",
+        ["After N seconds", "Tras N segundos", "Через N секунд"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_AfterPips",
+        ["After N pips", "Tras N pips", "Через N пипсов"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_TimeFilter",
+        ["Time filter", "Filtro horario", "Фильтр по времени"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_NewsFilter",
+        ["News filter", "Filtro de noticias", "Фильтр новостей"],
+    ),
+    entry(
+        "triggerType",
+        "Trigger_PowerAOppositeCount",
+        [
+            "Power A opposite order count",
+            "Órdenes opuestas de Power A",
+            "Встречные ордера Power A",
+        ],
+    ),
+    entry("trailMethod", "Points", ["Points", "Puntos", "Пункты"]),
+    entry(
+        "trailMethod",
+        "AVG_Percent",
+        [
+            "Average price, percent",
+            "Precio medio, porcentaje",
+            "Средняя цена, процент",
+        ],
+    ),
+    entry(
+        "trailStepMethod",
+        "Step_Points",
+        ["Step in points", "Paso en puntos", "Шаг в пунктах"],
+    ),
+    entry(
+        "trailStepMethod",
+        "Step_Percent",
+        ["Step in percent", "Paso en porcentaje", "Шаг в процентах"],
+    ),
+    entry(
+        "trailStepMode",
+        "TrailStepMode_Auto",
+        ["Automatic", "Automático", "Автоматически"],
+    ),
+    entry(
+        "trailStepMode",
+        "TrailStepMode_Fixed",
+        ["Fixed", "Fijo", "Фиксированный"],
+    ),
+    entry(
+        "trailStepMode",
+        "TrailStepMode_PerOrder",
+        ["Per order", "Por orden", "Для каждого ордера"],
+    ),
+    entry("partialMode", "PartialMode_Low", ["Low", "Bajo", "Низкий"]),
+    entry(
+        "partialMode",
+        "PartialMode_Mid",
+        ["Balanced", "Equilibrado", "Сбалансированный"],
+    ),
+    entry(
+        "partialMode",
+        "PartialMode_Aggressive",
+        ["Aggressive", "Agresivo", "Агрессивный"],
+    ),
+    entry(
+        "partialBalance",
+        "PartialBalance_Aggressive",
+        ["Aggressive", "Agresivo", "Агрессивный"],
+    ),
+    entry(
+        "partialBalance",
+        "PartialBalance_Balanced",
+        ["Balanced", "Equilibrado", "Сбалансированный"],
+    ),
+    entry(
+        "partialBalance",
+        "PartialBalance_Conservative",
+        ["Conservative", "Conservador", "Консервативный"],
+    ),
+    entry(
+        "compoundingType",
+        "Compound_Balance",
+        ["From balance", "Sobre el balance", "От баланса"],
+    ),
+    entry(
+        "compoundingType",
+        "Compound_Equity",
+        ["From equity", "Sobre el capital", "От эквити"],
+    ),
+    entry(
+        "restartPolicy",
+        "Restart_Default",
+        [
+            "Default restart",
+            "Reinicio predeterminado",
+            "Перезапуск по умолчанию",
+        ],
+    ),
+    entry(
+        "tradingMode",
+        "Counter Trend",
+        ["Counter trend", "Contratendencia", "Контртренд"],
+    ),
+    entry("tradingMode", "Hedge", ["Hedge", "Cobertura", "Хедж"]),
+    entry("tradingMode", "Reverse", ["Reverse", "Inverso", "Реверс"]),
+    entry(
+        "strategyType",
+        "Trail",
+        ["Trailing", "Trailing", "Трейлинг"],
+    ),
+];
+
+/// Legacy spellings the importers still accept, mapped to the catalog id
+const ALIASES: &[(&str, &str)] = &[
+    ("Action_Default", "TriggerAction_StopEA_KeepTrades"),
+    ("Action_CloseAll", "TriggerAction_CloseAll"),
+    ("PartialMode_Balanced", "PartialMode_Mid"),
+    ("PartialMode_High", "PartialMode_Aggressive"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnumLabel {
+    /// Field family, e.g. "triggerAction"
+    pub group: String,
+    /// Value as stored in configs and setfiles
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnumLabels {
+    /// Locale the labels are in, after falling back
+    pub locale: Locale,
+    pub labels: Vec<EnumLabel>,
+    /// Legacy id -> catalog id
+    pub aliases: Vec<(String, String)>,
+}
+
+fn find_entry(id: &str) -> Option<&'static CatalogEntry> {
+    // Decoded trigger types carry their number: "3 Trigger_AfterPips"
+    let id = id
+        .trim()
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim();
+    let id = ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(id))
+        .map(|(_, canonical)| *canonical)
+        .unwrap_or(id);
+    CATALOG
+        .iter()
+        .find(|entry| entry.id.eq_ignore_ascii_case(id))
+}
+
+/// Display name of `id`, or `id` itself when the catalog doesn't know it
+pub fn label(id: &str, locale: Locale) -> String {
+    find_entry(id)
+        .map(|entry| entry.labels[locale.index()].to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+pub fn catalog(locale: Locale) -> EnumLabels {
+    EnumLabels {
+        locale,
+        labels: CATALOG
+            .iter()
+            .map(|entry| EnumLabel {
+                group: entry.group.to_string(),
+                id: entry.id.to_string(),
+                label: entry.labels[locale.index()].to_string(),
+            })
+            .collect(),
+        aliases: ALIASES
+            .iter()
+            .map(|(alias, id)| (alias.to_string(), id.to_string()))
+            .collect(),
+    }
+}
+
+/// Display names for every known enum value in `locale` ("en", "es", "ru";
+/// defaults to English)
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_enum_labels(locale: Option<String>) -> Result<EnumLabels, String> {
+    Ok(catalog(Locale::from_tag(locale.as_deref().unwrap_or("en"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_lookup() {
+        assert_eq!(label("TriggerAction_StopEA", Locale::En), "Stop EA");
+        assert_eq!(label("triggeraction_stopea", Locale::Es), "Detener EA");
+        assert_eq!(
+            label("PartialMode_Balanced", Locale::Ru),
+            "Сбалансированный"
+        );
+        assert_eq!(label("3 Trigger_AfterPips", Locale::En), "After N pips");
+        assert_eq!(label("Compound_Custom", Locale::Es), "Compound_Custom");
+
+        assert_eq!(Locale::from_tag("es-MX"), Locale::Es);
+        assert_eq!(Locale::from_tag("ru_RU"), Locale::Ru);
+        assert_eq!(Locale::from_tag("de"), Locale::En);
+    }
+
+    #[test]
+    fn test_catalog_is_complete() {
+        for (i, entry) in CATALOG.iter().enumerate() {
+            assert!(
+                entry.labels.iter().all(|l| !l.trim().is_empty()),
+                "{}",
+                entry.id
+            );
+            assert!(
+                !CATALOG[..i]
+                    .iter()
+                    .any(|other| other.id.eq_ignore_ascii_case(entry.id)),
+                "duplicate {}",
+                entry.id
+            );
+        }
+        for (alias, id) in ALIASES {
+            assert!(
+                CATALOG.iter().any(|entry| entry.id == *id),
+                "{} -> {}",
+                alias,
+                id
+            );
+        }
+    }
+}
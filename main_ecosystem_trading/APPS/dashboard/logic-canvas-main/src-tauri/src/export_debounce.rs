@@ -0,0 +1,105 @@
+// ============================================
+// EXPORT DEBOUNCING
+// ============================================
+//
+// Slider drags fire an active set export per tick, and every write makes
+// the EA reload. Exports are coalesced per target file: the first one in a
+// quiet period is written at once, later ones inside the window only
+// replace a pending export, and that pending export is written when the
+// window closes. A file sees at most one write per window and always ends
+// up with the last config sent. The coalescing lives in daavfx_core so the
+// root app's active set export behaves the same; the window setting is here.
+
+use daavfx_core::debounce::{self, DEFAULT_WINDOW_MS};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::mt_bridge::atomic_write;
+
+pub(crate) use daavfx_core::debounce::Submitted;
+
+const DEBOUNCE_FILE: &str = "export_debounce.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportDebounceSettings {
+    /// Minimum time between two writes of the same file; 0 disables coalescing
+    pub window_ms: u64,
+}
+
+impl Default for ExportDebounceSettings {
+    fn default() -> Self {
+        ExportDebounceSettings {
+            window_ms: DEFAULT_WINDOW_MS,
+        }
+    }
+}
+
+fn settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(DEBOUNCE_FILE)
+}
+
+fn current() -> ExportDebounceSettings {
+    std::fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Configured coalescing window
+pub(crate) fn window() -> Duration {
+    Duration::from_millis(current().window_ms)
+}
+
+/// Coalesce `write` with other exports of `target` inside the configured
+/// window; deferred failures are logged
+pub(crate) fn submit<T, F>(target: &str, window: Duration, write: F) -> Submitted<Result<T, String>>
+where
+    T: 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    debounce::submit(target, window, write, |file_path, err| {
+        tracing::warn!(file_path = %file_path, error = %err, "Deferred export failed");
+    })
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_export_debounce_settings() -> Result<ExportDebounceSettings, String> {
+    Ok(current())
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_export_debounce_settings(
+    settings: ExportDebounceSettings,
+) -> Result<ExportDebounceSettings, String> {
+    if settings.window_ms > 10_000 {
+        return Err("Debounce window can't exceed 10 seconds".to_string());
+    }
+    let path = settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize debounce settings: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_default_to_core_window() {
+        let settings: ExportDebounceSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.window_ms, DEFAULT_WINDOW_MS);
+        assert!(
+            set_export_debounce_settings(ExportDebounceSettings { window_ms: 20_000 }).is_err()
+        );
+    }
+}
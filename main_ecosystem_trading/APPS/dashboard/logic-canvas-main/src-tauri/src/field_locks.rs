@@ -0,0 +1,304 @@
+// ============================================
+// FIELD LOCKS
+// ============================================
+//
+// Hand-tuned parameters can be locked so chat commands, imports and bulk
+// edits can't silently overwrite them. A lock is a dotted path into the
+// config JSON where array elements are addressed by their id (engine "A",
+// group "1", logic "POWER"), by index, or with "*" for every element:
+// `engines.A.groups.*.logics.POWER.grid_b`. Each lock remembers the values
+// it matched when it was created, so later edits are compared against those.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{atomic_write, MTConfig};
+
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+const FIELD_LOCKS_FILE: &str = "field_locks.json";
+// Most specific first: logic rows share a logic_name per direction but not a logic_id
+const ID_FIELDS: [&str; 4] = ["logic_id", "engine_id", "group_number", "logic_name"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LockMode {
+    /// Reject the save/import when a locked field would change
+    #[default]
+    Refuse,
+    /// Let it through and report the changed fields
+    Warn,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldLocks {
+    /// Lock pattern -> concrete path -> value at the time it was locked
+    #[serde(default)]
+    pub locks: BTreeMap<String, BTreeMap<String, Value>>,
+    #[serde(default)]
+    pub mode: LockMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockViolation {
+    /// The lock that matched
+    pub lock: String,
+    /// Concrete path of the changed field
+    pub path: String,
+    pub locked_value: Value,
+    pub new_value: Value,
+}
+
+fn field_locks_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(FIELD_LOCKS_FILE)
+}
+
+pub(crate) fn load_field_locks() -> FieldLocks {
+    std::fs::read_to_string(field_locks_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_field_locks(locks: &FieldLocks) -> Result<(), String> {
+    let path = field_locks_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(locks)
+        .map_err(|e| format!("Failed to serialize field locks: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+fn element_ids(element: &Value) -> Vec<String> {
+    ID_FIELDS
+        .iter()
+        .filter_map(|field| match element.get(*field)? {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every concrete (path, value) the lock pattern resolves to in `root`
+pub fn resolve_lock<'a>(root: &'a Value, pattern: &str) -> Vec<(String, &'a Value)> {
+    let mut current = vec![(String::new(), root)];
+    for segment in pattern.split('.').filter(|s| !s.is_empty()) {
+        let mut next = Vec::new();
+        for (path, value) in current {
+            let join = |key: &str| {
+                if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                }
+            };
+            match value {
+                Value::Object(map) => {
+                    if let Some(child) = map.get(segment) {
+                        next.push((join(segment), child));
+                    }
+                }
+                Value::Array(items) => {
+                    for (idx, item) in items.iter().enumerate() {
+                        let ids = element_ids(item);
+                        let matches = segment == "*"
+                            || ids.iter().any(|id| id.eq_ignore_ascii_case(segment))
+                            || (ids.is_empty() && segment.parse::<usize>() == Ok(idx));
+                        if matches {
+                            let key = ids.first().cloned().unwrap_or_else(|| idx.to_string());
+                            next.push((join(&key), item));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn config_value(config: &MTConfig) -> Result<Value, String> {
+    serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
+impl FieldLocks {
+    /// Lock `pattern`, recording the current value of every field it matches
+    pub fn lock(&mut self, pattern: &str, config: &MTConfig) -> Result<usize, String> {
+        let pattern = pattern.trim().trim_matches('.');
+        let root = config_value(config)?;
+        let snapshot: BTreeMap<String, Value> = resolve_lock(&root, pattern)
+            .into_iter()
+            .map(|(path, value)| (path, value.clone()))
+            .collect();
+        if snapshot.is_empty() {
+            return Err(format!("Lock path '{}' matches no field", pattern));
+        }
+        let count = snapshot.len();
+        self.locks.insert(pattern.to_string(), snapshot);
+        Ok(count)
+    }
+
+    /// Locked fields whose value in `candidate` differs from the locked value.
+    /// Fields missing from the candidate are left alone, not reported.
+    pub fn find_violations(&self, candidate: &MTConfig) -> Result<Vec<LockViolation>, String> {
+        let root = config_value(candidate)?;
+        let mut violations = Vec::new();
+        for (lock, fields) in &self.locks {
+            for (path, locked) in fields {
+                if let Some((_, current)) = resolve_lock(&root, path).into_iter().next() {
+                    if current != locked {
+                        violations.push(LockViolation {
+                            lock: lock.clone(),
+                            path: path.clone(),
+                            locked_value: locked.clone(),
+                            new_value: current.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Apply the lock mode: Refuse turns violations into an error, Warn returns them
+    pub fn check(&self, candidate: &MTConfig) -> Result<Vec<LockViolation>, String> {
+        let violations = self.find_violations(candidate)?;
+        if self.mode == LockMode::Refuse && !violations.is_empty() {
+            let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+            return Err(format!(
+                "Refused: {} locked field(s) would change: {}",
+                violations.len(),
+                paths.join(", ")
+            ));
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(feature = "tauri-app")]
+pub(crate) async fn enforce_field_locks(
+    state: &MTBridgeState,
+    candidate: &MTConfig,
+) -> Result<Vec<LockViolation>, String> {
+    state.field_locks.read().await.check(candidate)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_field_locks(state: State<'_, MTBridgeState>) -> Result<FieldLocks, String> {
+    Ok(state.field_locks.read().await.clone())
+}
+
+/// Lock parameter paths at their values in `config` (default: the config
+/// behind `handle`); `mode` switches between refusing and warning
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn lock_fields(
+    paths: Vec<String>,
+    config: Option<MTConfig>,
+    mode: Option<LockMode>,
+    handle: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<FieldLocks, String> {
+    let config = match config {
+        Some(config) => config,
+        None => state
+            .configs
+            .read()
+            .await
+            .get(handle.as_deref())
+            .map(|open| open.config.clone())
+            .map_err(|_| "No config loaded to lock fields against".to_string())?,
+    };
+    let mut locks = state.field_locks.write().await;
+    let mut updated = locks.clone();
+    for path in paths.iter().filter(|p| !p.trim().is_empty()) {
+        updated.lock(path, &config)?;
+    }
+    if let Some(mode) = mode {
+        updated.mode = mode;
+    }
+    save_field_locks(&updated)?;
+    *locks = updated.clone();
+    Ok(updated)
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn unlock_fields(
+    paths: Vec<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<FieldLocks, String> {
+    let mut locks = state.field_locks.write().await;
+    for path in paths {
+        locks.locks.remove(path.trim().trim_matches('.'));
+    }
+    save_field_locks(&locks)?;
+    Ok(locks.clone())
+}
+
+/// Check an imported or AI-edited config against the locks before applying it
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn check_field_locks(
+    config: MTConfig,
+    state: State<'_, MTBridgeState>,
+) -> Result<Vec<LockViolation>, String> {
+    enforce_field_locks(&state, &config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_resolve_lock_by_id_and_wildcard() {
+        let config = create_full_v19_config();
+        let root = serde_json::to_value(&config).unwrap();
+        // Buy and Sell rows of the logic, reported by their logic_id
+        let hits = resolve_lock(&root, "engines.A.groups.1.logics.POWER.initial_lot");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            hits[0].0,
+            "engines.A.groups.1.logics.A_POWER_B_G1.initial_lot"
+        );
+
+        let all = resolve_lock(&root, "engines.*.max_power_orders");
+        assert_eq!(all.len(), config.engines.len());
+        assert!(resolve_lock(&root, "general.no_such_field").is_empty());
+    }
+
+    #[test]
+    fn test_locks_refuse_and_warn() {
+        let tuned = create_full_v19_config();
+        let mut locks = FieldLocks::default();
+        assert_eq!(locks.lock("general.magic_number", &tuned).unwrap(), 1);
+        assert!(locks.lock("general.no_such_field", &tuned).is_err());
+
+        let mut candidate = tuned.clone();
+        candidate.general.magic_number += 1;
+        candidate.engines[0].max_power_orders += 1;
+        assert!(locks.check(&candidate).is_err());
+
+        locks.mode = LockMode::Warn;
+        let violations = locks.check(&candidate).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "general.magic_number");
+        assert!(locks.check(&tuned).unwrap().is_empty());
+    }
+}
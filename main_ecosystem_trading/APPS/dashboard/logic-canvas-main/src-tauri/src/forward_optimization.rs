@@ -0,0 +1,272 @@
+// ============================================
+// FORWARD OPTIMIZATION SETS
+// ============================================
+//
+// MT5 forward optimization takes a .set whose inputs carry the optimizer
+// flags inline (value||start||step||stop||Y). From one config and a list of
+// selected v19 keys this writes that file plus a baseline with every input
+// fixed, which is what the winning pass is re-tested against on the forward
+// period. Keys are checked against the rendered v19 layout, so a typo or a
+// key that moved between EA builds fails here instead of silently not
+// being optimized.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{
+    atomic_write, render_massive_v19_setfile_lines, sanitize_and_validate_path, MTConfig,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizedInput {
+    /// v19 setfile key, e.g. gInput_1_AP_Buy_InitialLot
+    pub key: String,
+    /// Range defaults to half to twice the current value in 10 steps
+    #[serde(default)]
+    pub start: Option<f64>,
+    #[serde(default)]
+    pub step: Option<f64>,
+    #[serde(default)]
+    pub stop: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardOptimizationSets {
+    /// Set with optimizer ranges on the selected inputs
+    pub optimize_path: String,
+    /// Same inputs, all fixed, for re-testing on the forward period
+    pub forward_path: String,
+    pub optimized_keys: Vec<String>,
+    pub fixed_inputs: usize,
+}
+
+fn format_number(value: f64) -> String {
+    let text = format!("{:.8}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// `(start, step, stop)` for `input` whose current value is `current`
+fn optimization_range(input: &OptimizedInput, current: f64) -> Result<(f64, f64, f64), String> {
+    let start = input.start.unwrap_or(current * 0.5);
+    let stop = input.stop.unwrap_or(current * 2.0);
+    let step = input.step.unwrap_or((stop - start) / 10.0);
+    if ![start, step, stop].iter().all(|v| v.is_finite()) {
+        return Err(format!("Range for {} isn't a finite number", input.key));
+    }
+    if start > stop {
+        return Err(format!("Range for {} starts above its stop", input.key));
+    }
+    if step <= 0.0 {
+        return Err(format!(
+            "Range for {} needs a positive step (current value {})",
+            input.key,
+            format_number(current)
+        ));
+    }
+    Ok((start, step, stop))
+}
+
+/// Rewrite rendered v19 lines into the (optimize, forward) pair. Errors
+/// list every selected key the layout doesn't have.
+pub fn forward_optimization_lines(
+    lines: &[String],
+    selection: &[OptimizedInput],
+) -> Result<(Vec<String>, Vec<String>, Vec<String>), String> {
+    let layout: HashMap<String, (&str, &str)> = lines
+        .iter()
+        .filter(|line| !line.trim_start().starts_with(';'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_lowercase(), (key.trim(), value.trim())))
+        .collect();
+
+    let mut ranges: HashMap<String, (f64, f64, f64)> = HashMap::new();
+    let mut optimized_keys = Vec::new();
+    let mut unknown = Vec::new();
+    for input in selection {
+        let lookup = input.key.trim().to_ascii_lowercase();
+        let Some((key, value)) = layout.get(&lookup) else {
+            unknown.push(input.key.clone());
+            continue;
+        };
+        if ranges.contains_key(&lookup) {
+            return Err(format!("{} is selected twice", key));
+        }
+        let current: f64 = value
+            .parse()
+            .map_err(|_| format!("{} = '{}' isn't numeric and can't be ranged", key, value))?;
+        ranges.insert(lookup, optimization_range(input, current)?);
+        optimized_keys.push(key.to_string());
+    }
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Not in the v19 setfile layout: {}",
+            unknown.join(", ")
+        ));
+    }
+    if optimized_keys.is_empty() {
+        return Err("Select at least one input to optimize".to_string());
+    }
+
+    let mut optimize = Vec::with_capacity(lines.len());
+    let mut forward = Vec::with_capacity(lines.len());
+    for line in lines {
+        let Some((key, value)) = line
+            .split_once('=')
+            .filter(|_| !line.trim_start().starts_with(';'))
+        else {
+            optimize.push(line.clone());
+            forward.push(line.clone());
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        // Text inputs (enums, comments) have no optimizer fields
+        if value.parse::<f64>().is_err() {
+            optimize.push(line.clone());
+            forward.push(line.clone());
+            continue;
+        }
+        let fixed = format!("{}={}||{}||0||{}||N", key, value, value, value);
+        match ranges.get(&key.to_ascii_lowercase()) {
+            Some((start, step, stop)) => optimize.push(format!(
+                "{}={}||{}||{}||{}||Y",
+                key,
+                value,
+                format_number(*start),
+                format_number(*step),
+                format_number(*stop)
+            )),
+            None => optimize.push(fixed.clone()),
+        }
+        forward.push(fixed);
+    }
+    Ok((optimize, forward, optimized_keys))
+}
+
+/// Write `<name>_optimize.set` and `<name>_forward.set` for an MT5 forward
+/// optimization of the selected inputs
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn export_forward_optimization_sets(
+    config: MTConfig,
+    selection: Vec<OptimizedInput>,
+    output_dir: String,
+    name: String,
+) -> Result<ForwardOptimizationSets, String> {
+    if name.trim().is_empty() {
+        return Err("A set name is required".to_string());
+    }
+    let output_dir = sanitize_and_validate_path(&PathBuf::from(&output_dir))?;
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let task = crate::task_manager().begin(None, "export_forward_optimization_sets");
+    let lines = render_massive_v19_setfile_lines(config, "MT5", &task);
+    crate::task_manager().finish(&task.id);
+    let (optimize, forward, optimized_keys) = forward_optimization_lines(&lines?, &selection)?;
+
+    let optimize_path = output_dir.join(format!("{}_optimize.set", name.trim()));
+    let forward_path = output_dir.join(format!("{}_forward.set", name.trim()));
+    atomic_write(&optimize_path, optimize.join("\n"))?;
+    atomic_write(&forward_path, forward.join("\n"))?;
+
+    Ok(ForwardOptimizationSets {
+        optimize_path: optimize_path.to_string_lossy().to_string(),
+        forward_path: forward_path.to_string_lossy().to_string(),
+        fixed_inputs: optimize.iter().filter(|line| line.ends_with("||N")).count(),
+        optimized_keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Vec<String> {
+        [
+            "; DAAVFX v19",
+            "gInput_1_AP_Buy_InitialLot=0.02",
+            "gInput_1_AP_Buy_Grid=300",
+            "gInput_1_AP_Buy_TrailMethod=Points",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
+
+    fn input(
+        key: &str,
+        start: Option<f64>,
+        step: Option<f64>,
+        stop: Option<f64>,
+    ) -> OptimizedInput {
+        OptimizedInput {
+            key: key.to_string(),
+            start,
+            step,
+            stop,
+        }
+    }
+
+    #[test]
+    fn test_forward_optimization_pair_flags_only_selected_inputs() {
+        let selection = vec![input(
+            "ginput_1_ap_buy_grid",
+            Some(200.0),
+            Some(50.0),
+            Some(400.0),
+        )];
+        let (optimize, forward, keys) = forward_optimization_lines(&layout(), &selection).unwrap();
+
+        assert_eq!(keys, vec!["gInput_1_AP_Buy_Grid"]);
+        assert_eq!(optimize[2], "gInput_1_AP_Buy_Grid=300||200||50||400||Y");
+        assert_eq!(
+            optimize[1],
+            "gInput_1_AP_Buy_InitialLot=0.02||0.02||0||0.02||N"
+        );
+        assert_eq!(forward[2], "gInput_1_AP_Buy_Grid=300||300||0||300||N");
+        // Comments and text inputs pass through untouched
+        assert_eq!(optimize[0], "; DAAVFX v19");
+        assert_eq!(forward[3], "gInput_1_AP_Buy_TrailMethod=Points");
+    }
+
+    #[test]
+    fn test_forward_optimization_rejects_keys_outside_layout() {
+        let selection = vec![
+            input("gInput_1_AP_Buy_Grid", None, None, None),
+            input("gInput_1_AP_Buy_Gird", None, None, None),
+            input("gInput_99_ZZ_Buy_Grid", None, None, None),
+        ];
+        let err = forward_optimization_lines(&layout(), &selection).unwrap_err();
+        assert!(
+            err.contains("gInput_1_AP_Buy_Gird, gInput_99_ZZ_Buy_Grid"),
+            "{}",
+            err
+        );
+
+        let text = vec![input("gInput_1_AP_Buy_TrailMethod", None, None, None)];
+        assert!(forward_optimization_lines(&layout(), &text).is_err());
+    }
+
+    #[test]
+    fn test_forward_optimization_keys_exist_in_v19_layout() {
+        let task = crate::task_manager().begin(None, "test");
+        let lines = render_massive_v19_setfile_lines(
+            crate::mt_bridge::create_full_v19_config(),
+            "MT5",
+            &task,
+        )
+        .unwrap();
+        let selection = vec![input(
+            "gInput_1_AP_Buy_InitialLot",
+            Some(0.01),
+            Some(0.01),
+            Some(0.05),
+        )];
+        let (optimize, _, _) = forward_optimization_lines(&lines, &selection).unwrap();
+        assert!(optimize
+            .iter()
+            .any(|line| line.starts_with("gInput_1_AP_Buy_InitialLot=") && line.ends_with("||Y")));
+    }
+}
@@ -0,0 +1,246 @@
+//! Structured logging for setfile/config imports.
+//!
+//! Each import runs inside a `setfile_import` span. `ImportLogLayer` collects the
+//! events emitted inside that span and, when the span closes, keeps the log as the
+//! "last import" so the UI can fetch it for diagnostics. Because the buffer lives
+//! in the span's extensions, concurrent imports never share state.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter, Targets};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+pub const IMPORT_SPAN_NAME: &str = "setfile_import";
+
+/// Entries beyond this are counted in `dropped_entries` instead of stored.
+const MAX_IMPORT_LOG_ENTRIES: usize = 5000;
+
+/// Environment variable holding an `EnvFilter` directive for console output.
+const LOG_FILTER_ENV: &str = "DAAVFX_LOG";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportLog {
+    pub import_id: String,
+    pub kind: String,
+    pub file_path: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub entries: Vec<ImportLogEntry>,
+    pub dropped_entries: usize,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+impl ImportLog {
+    fn push(&mut self, entry: ImportLogEntry, level: &Level) {
+        if *level == Level::WARN {
+            self.warnings += 1;
+        } else if *level == Level::ERROR {
+            self.errors += 1;
+        }
+        if self.entries.len() >= MAX_IMPORT_LOG_ENTRIES {
+            self.dropped_entries += 1;
+        } else {
+            self.entries.push(entry);
+        }
+    }
+}
+
+static LAST_IMPORT_LOG: OnceLock<Mutex<Option<ImportLog>>> = OnceLock::new();
+
+fn get_last_import_log_slot() -> &'static Mutex<Option<ImportLog>> {
+    LAST_IMPORT_LOG.get_or_init(|| Mutex::new(None))
+}
+
+/// Log of the most recently completed import, if any.
+pub fn last_import_log() -> Option<ImportLog> {
+    get_last_import_log_slot()
+        .lock()
+        .ok()
+        .and_then(|slot| slot.clone())
+}
+
+/// Create the span an import runs under. Enter it (or use `in_scope`) for the
+/// whole import so every event is attributed to it.
+pub fn import_span(kind: &str, file_path: &str) -> tracing::Span {
+    let import_id = uuid::Uuid::new_v4().to_string();
+    tracing::info_span!(
+        "setfile_import",
+        import_id = %import_id,
+        kind = %kind,
+        file_path = %file_path
+    )
+}
+
+/// Install the global subscriber: console output filtered by `DAAVFX_LOG`
+/// (default `info`), the rolling log file and recent-log ring from `app_log`,
+/// plus the import log collector. Safe to call more than once.
+pub fn init_tracing() {
+    let console_filter =
+        EnvFilter::try_from_env(LOG_FILTER_ENV).unwrap_or_else(|_| EnvFilter::new("info"));
+    // Our own debug output is worth keeping for support; dependencies stay at info
+    let app_filter = || {
+        Targets::new()
+            .with_default(Level::INFO)
+            .with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG)
+    };
+    let file_layer = crate::app_log::file_writer().map(|writer| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_filter(app_filter())
+    });
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(console_filter))
+        .with(file_layer)
+        .with(crate::app_log::RecentLogLayer.with_filter(app_filter()))
+        .with(ImportLogLayer.with_filter(LevelFilter::DEBUG));
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+#[derive(Default)]
+pub(crate) struct FieldCollector {
+    pub(crate) message: String,
+    pub(crate) fields: BTreeMap<String, String>,
+}
+
+impl FieldCollector {
+    fn take(&mut self, name: &str) -> String {
+        self.fields.remove(name).unwrap_or_default()
+    }
+}
+
+impl Visit for FieldCollector {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+/// Collects events emitted inside `setfile_import` spans.
+pub struct ImportLogLayer;
+
+impl<S> Layer<S> for ImportLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != IMPORT_SPAN_NAME {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let mut collector = FieldCollector::default();
+            attrs.record(&mut collector);
+            span.extensions_mut().insert(ImportLog {
+                import_id: collector.take("import_id"),
+                kind: collector.take("kind"),
+                file_path: collector.take("file_path"),
+                started_at: chrono::Local::now().to_rfc3339(),
+                finished_at: None,
+                entries: Vec::new(),
+                dropped_entries: 0,
+                warnings: 0,
+                errors: 0,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let scope = match ctx.event_scope(event) {
+            Some(scope) => scope,
+            None => return,
+        };
+        // Attribute the event to the innermost enclosing import span.
+        for span in scope {
+            let mut extensions = span.extensions_mut();
+            if let Some(log) = extensions.get_mut::<ImportLog>() {
+                let mut collector = FieldCollector::default();
+                event.record(&mut collector);
+                let metadata = event.metadata();
+                log.push(
+                    ImportLogEntry {
+                        timestamp: chrono::Local::now().to_rfc3339(),
+                        level: metadata.level().to_string(),
+                        target: metadata.target().to_string(),
+                        message: collector.message,
+                        fields: collector.fields,
+                    },
+                    metadata.level(),
+                );
+                return;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(mut log) = span.extensions_mut().remove::<ImportLog>() {
+                log.finished_at = Some(chrono::Local::now().to_rfc3339());
+                if let Ok(mut slot) = get_last_import_log_slot().lock() {
+                    *slot = Some(log);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_span_collects_events_into_last_log() {
+        let subscriber =
+            tracing_subscriber::registry().with(ImportLogLayer.with_filter(LevelFilter::DEBUG));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("outside any import");
+            import_span("set", "C:/presets/test.set").in_scope(|| {
+                tracing::info!(keys = 42, "parsed setfile");
+                tracing::warn!("unknown logic abbreviation");
+                tracing::trace!("filtered out");
+            });
+        });
+
+        let log = last_import_log().expect("import log recorded");
+        assert_eq!(log.kind, "set");
+        assert_eq!(log.file_path, "C:/presets/test.set");
+        assert!(!log.import_id.is_empty());
+        assert!(log.finished_at.is_some());
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[0].message, "parsed setfile");
+        assert_eq!(log.entries[0].fields.get("keys").map(String::as_str), Some("42"));
+        assert_eq!(log.warnings, 1);
+        assert_eq!(log.errors, 0);
+    }
+}
@@ -0,0 +1,473 @@
+// ============================================
+// IMPORT MAPPING PROFILES
+// ============================================
+//
+// Migrating a client from another EA means translating its .set inputs
+// (Lots, PipStep, LotMultiplier, ...) into MTConfig fields. A mapping profile
+// is a TOML file that records that translation once:
+//
+// ```toml
+// name = "generic_grid"
+// base = "conservative"          # archetype to start from (default: full v19)
+//
+// [[rules]]
+// source = "PipStep"
+// aliases = ["GridStep"]
+// target = "grid"                # logic field, or a JSON pointer like /general/magic_number
+// scale = 10.0                   # pips -> points
+// engine = "A"
+// group = 1
+// logic = "POWER"
+//
+// [constants]
+// "/general/allow_buy" = true
+// ```
+//
+// The mapped values go through config_patch, so a typo in a target is
+// rejected instead of silently dropped.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::archetypes::config_from_archetype;
+use crate::backtest::decode_text;
+use crate::config_patch::{apply_patches, ConfigPatch, PatchOp};
+use crate::mt_bridge::{create_full_v19_config, sanitize_and_validate_path, MTConfig};
+
+const PROFILE_DIR: &str = "import_mappings";
+const PROFILE_EXTENSION: &str = "toml";
+
+/// Profiles shipped with the app; user profiles with the same name win
+const BUILTIN_PROFILES: [(&str, &str); 1] = [("generic_grid", GENERIC_GRID_PROFILE)];
+
+const GENERIC_GRID_PROFILE: &str = r#"
+name = "generic_grid"
+description = "Single-basket grid EAs (Lots, LotMultiplier, PipStep) onto Engine A Power"
+base = "conservative"
+
+[[rules]]
+source = "MagicNumber"
+aliases = ["Magic", "MagicNo"]
+target = "/general/magic_number"
+
+[[rules]]
+source = "Lots"
+aliases = ["LotSize", "StartLot", "InitialLot"]
+target = "initial_lot"
+engine = "A"
+group = 1
+logic = "POWER"
+
+[[rules]]
+source = "LotMultiplier"
+aliases = ["Multiplier", "LotExponent"]
+target = "multiplier"
+engine = "A"
+group = 1
+logic = "POWER"
+
+[[rules]]
+source = "PipStep"
+aliases = ["GridStep", "Step"]
+target = "grid"
+scale = 10.0
+engine = "A"
+group = 1
+logic = "POWER"
+
+[[rules]]
+source = "TrailingStop"
+aliases = ["TrailStop"]
+target = "trail_value"
+scale = 10.0
+engine = "A"
+group = 1
+logic = "POWER"
+
+[[rules]]
+source = "MaxTrades"
+aliases = ["MaxOrders"]
+target = "/engines/0/max_power_orders"
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingRule {
+    /// Input name in the foreign .set file (case-insensitive)
+    pub source: String,
+    /// Other spellings of the same input across EA versions
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// A logic field name, or a JSON pointer (leading '/') into the config
+    pub target: String,
+    /// Numeric values become value * scale + offset
+    #[serde(default)]
+    pub scale: Option<f64>,
+    #[serde(default)]
+    pub offset: Option<f64>,
+    /// Enum translation, e.g. "0" = "Points"; applied before type conversion
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+    /// Scope for logic field targets; None maps onto every logic
+    #[serde(default)]
+    pub engine: Option<String>,
+    #[serde(default)]
+    pub group: Option<u8>,
+    #[serde(default)]
+    pub logic: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingProfile {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Archetype the import starts from; the full v19 config when unset
+    #[serde(default)]
+    pub base: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<MappingRule>,
+    /// Fixed values keyed by JSON pointer, set whatever the source file holds
+    #[serde(default)]
+    pub constants: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingProfileInfo {
+    pub name: String,
+    pub description: String,
+    /// File the profile was loaded from; None for built-ins
+    pub path: Option<String>,
+    pub rules: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappedImport {
+    pub config: MTConfig,
+    pub profile: String,
+    /// Source inputs that a rule consumed
+    pub mapped_inputs: Vec<String>,
+    /// Source inputs no rule knows about, for extending the profile
+    pub unmapped_inputs: Vec<String>,
+    /// Rules whose input wasn't in the file (target keeps the base value)
+    pub missing_inputs: Vec<String>,
+    pub modified_paths: Vec<String>,
+}
+
+fn profile_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(PROFILE_DIR)
+}
+
+pub fn parse_profile(content: &str) -> Result<MappingProfile, String> {
+    let profile: MappingProfile =
+        toml::from_str(content).map_err(|e| format!("Invalid mapping profile: {}", e))?;
+    if profile.name.trim().is_empty() {
+        return Err("Mapping profile needs a name".to_string());
+    }
+    for rule in &profile.rules {
+        if rule.source.trim().is_empty() || rule.target.trim().is_empty() {
+            return Err(format!(
+                "Mapping profile '{}' has a rule without source or target",
+                profile.name
+            ));
+        }
+    }
+    Ok(profile)
+}
+
+/// `profile` is a path to a .toml file, or the name of a saved or built-in profile
+fn load_profile(profile: &str) -> Result<MappingProfile, String> {
+    let as_path = PathBuf::from(profile);
+    let is_file_path = as_path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case(PROFILE_EXTENSION))
+        .unwrap_or(false);
+    let path = if is_file_path {
+        Some(sanitize_and_validate_path(&as_path)?)
+    } else {
+        let saved = profile_dir().join(format!("{}.{}", profile, PROFILE_EXTENSION));
+        saved.exists().then_some(saved)
+    };
+
+    if let Some(path) = path {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read mapping profile: {}", e))?;
+        return parse_profile(&content);
+    }
+    BUILTIN_PROFILES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(profile))
+        .map(|(_, content)| parse_profile(content))
+        .unwrap_or_else(|| Err(format!("Unknown mapping profile '{}'", profile)))
+}
+
+/// Key/value inputs of a .set file, keyed by lowercase name
+pub(crate) fn read_set_inputs(content: &str) -> BTreeMap<String, (String, String)> {
+    let mut inputs = BTreeMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let Some((key, raw)) = line.split_once('=') else {
+            continue;
+        };
+        // Strip MT4 (||) and MT5 (,F=) optimization ranges
+        let value = raw.split("||").next().unwrap_or("");
+        let value = value.split(",F=").next().unwrap_or("").trim();
+        let key = key.trim();
+        inputs.insert(key.to_lowercase(), (key.to_string(), value.to_string()));
+    }
+    inputs
+}
+
+/// JSON pointers a rule writes to in `config`
+fn rule_targets(rule: &MappingRule, config: &MTConfig) -> Vec<String> {
+    if rule.target.starts_with('/') {
+        return vec![rule.target.clone()];
+    }
+    let mut targets = Vec::new();
+    for (e, engine) in config.engines.iter().enumerate() {
+        if !rule
+            .engine
+            .as_ref()
+            .map_or(true, |id| id.eq_ignore_ascii_case(&engine.engine_id))
+        {
+            continue;
+        }
+        for (g, group) in engine.groups.iter().enumerate() {
+            if !rule.group.map_or(true, |n| n == group.group_number) {
+                continue;
+            }
+            for (l, logic) in group.logics.iter().enumerate() {
+                if rule
+                    .logic
+                    .as_ref()
+                    .map_or(true, |name| name.eq_ignore_ascii_case(&logic.logic_name))
+                {
+                    targets.push(format!(
+                        "/engines/{}/groups/{}/logics/{}/{}",
+                        e, g, l, rule.target
+                    ));
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// Convert a raw input to the JSON type already at the target; optional
+/// fields that are unset fall back to number, then bool, then string
+fn convert_value(rule: &MappingRule, raw: &str, current: Option<&Value>) -> Result<Value, String> {
+    let raw = rule
+        .values
+        .iter()
+        .find(|(from, _)| from.eq_ignore_ascii_case(raw))
+        .map(|(_, to)| to.as_str())
+        .unwrap_or(raw);
+    let number = || -> Result<f64, String> {
+        let value: f64 = raw
+            .parse()
+            .map_err(|_| format!("Input '{}' = '{}' is not a number", rule.source, raw))?;
+        Ok(value * rule.scale.unwrap_or(1.0) + rule.offset.unwrap_or(0.0))
+    };
+    let boolean = || match raw.to_ascii_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    };
+
+    Ok(match current {
+        Some(Value::Number(n)) if n.is_f64() => serde_json::json!(number()?),
+        Some(Value::Number(_)) => serde_json::json!(number()?.round() as i64),
+        Some(Value::Bool(_)) => Value::Bool(
+            boolean()
+                .ok_or_else(|| format!("Input '{}' = '{}' is not a bool", rule.source, raw))?,
+        ),
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        _ => match (number(), boolean()) {
+            (Ok(value), _) => serde_json::json!(value),
+            (Err(_), Some(flag)) => Value::Bool(flag),
+            (Err(_), None) => Value::String(raw.to_string()),
+        },
+    })
+}
+
+/// Translate foreign .set content into an MTConfig using `profile`
+pub fn apply_mapping(content: &str, profile: &MappingProfile) -> Result<MappedImport, String> {
+    let base = match profile.base.as_deref() {
+        Some(archetype) => config_from_archetype(archetype)?,
+        None => create_full_v19_config(),
+    };
+    let base_value =
+        serde_json::to_value(&base).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let inputs = read_set_inputs(content);
+
+    let mut patches = Vec::new();
+    let mut consumed: HashMap<String, String> = HashMap::new();
+    let mut missing_inputs = Vec::new();
+    for rule in &profile.rules {
+        let found = std::iter::once(&rule.source)
+            .chain(rule.aliases.iter())
+            .find_map(|name| inputs.get(&name.to_lowercase()));
+        let Some((key, raw)) = found else {
+            missing_inputs.push(rule.source.clone());
+            continue;
+        };
+        consumed.insert(key.to_lowercase(), key.clone());
+
+        let targets = rule_targets(rule, &base);
+        if targets.is_empty() {
+            return Err(format!(
+                "Rule for '{}' doesn't match any logic in the config",
+                rule.source
+            ));
+        }
+        for path in targets {
+            let value = convert_value(rule, raw, base_value.pointer(&path))?;
+            patches.push(ConfigPatch {
+                op: PatchOp::Set,
+                path,
+                value,
+            });
+        }
+    }
+    for (path, value) in &profile.constants {
+        patches.push(ConfigPatch {
+            op: PatchOp::Set,
+            path: path.clone(),
+            value: serde_json::to_value(value)
+                .map_err(|e| format!("Invalid constant '{}': {}", path, e))?,
+        });
+    }
+
+    let (config, modified_paths) = apply_patches(&base, &patches)
+        .map_err(|e| format!("Mapping profile '{}': {}", profile.name, e))?;
+
+    let mut mapped_inputs: Vec<String> = consumed.values().cloned().collect();
+    mapped_inputs.sort();
+    let unmapped_inputs = inputs
+        .iter()
+        .filter(|(lower, _)| !consumed.contains_key(*lower))
+        .map(|(_, (key, _))| key.clone())
+        .collect();
+
+    Ok(MappedImport {
+        config,
+        profile: profile.name.clone(),
+        mapped_inputs,
+        unmapped_inputs,
+        missing_inputs,
+        modified_paths,
+    })
+}
+
+/// Import another EA's .set file through a mapping profile (name or .toml path)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn import_set_file_with_mapping(path: String, profile: String) -> Result<MappedImport, String> {
+    let profile = load_profile(&profile)?;
+    let path = sanitize_and_validate_path(&PathBuf::from(&path))?;
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read .set file: {}", e))?;
+    apply_mapping(&decode_text(&bytes), &profile)
+}
+
+/// Built-in profiles plus the ones saved under the settings folder
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_import_mapping_profiles() -> Result<Vec<MappingProfileInfo>, String> {
+    let mut profiles: BTreeMap<String, MappingProfileInfo> = BTreeMap::new();
+    for (_, content) in BUILTIN_PROFILES {
+        let profile = parse_profile(content)?;
+        profiles.insert(
+            profile.name.to_lowercase(),
+            MappingProfileInfo {
+                name: profile.name,
+                description: profile.description,
+                path: None,
+                rules: profile.rules.len(),
+            },
+        );
+    }
+
+    if let Ok(entries) = fs::read_dir(profile_dir()) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let is_profile = path
+                .extension()
+                .map(|ext| ext.eq_ignore_ascii_case(PROFILE_EXTENSION))
+                .unwrap_or(false);
+            // Broken profiles surface when they're used, not here
+            let Some(profile) = is_profile
+                .then(|| fs::read_to_string(&path).ok())
+                .flatten()
+                .and_then(|content| parse_profile(&content).ok())
+            else {
+                continue;
+            };
+            profiles.insert(
+                profile.name.to_lowercase(),
+                MappingProfileInfo {
+                    name: profile.name,
+                    description: profile.description,
+                    path: Some(path.to_string_lossy().to_string()),
+                    rules: profile.rules.len(),
+                },
+            );
+        }
+    }
+    Ok(profiles.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOREIGN_SET: &str = "; saved by SomeGridEA\r\n\
+        Magic=4242\r\n\
+        LotSize=0.03||0.01||0.01||0.1||N\r\n\
+        LotExponent=1.4\r\n\
+        PipStep=25\r\n\
+        MaxTrades=8\r\n\
+        UseNewsFilter=true\r\n";
+
+    #[test]
+    fn test_generic_grid_profile_maps_foreign_inputs() {
+        let profile = load_profile("generic_grid").unwrap();
+        let imported = apply_mapping(FOREIGN_SET, &profile).unwrap();
+        let config = &imported.config;
+
+        let power = config.engines[0].groups[0]
+            .logics
+            .iter()
+            .find(|l| l.logic_name.eq_ignore_ascii_case("POWER"))
+            .unwrap();
+        assert_eq!(config.general.magic_number, 4242);
+        assert_eq!(power.initial_lot, 0.03);
+        assert_eq!(power.multiplier, 1.4);
+        assert_eq!(power.grid, 250.0);
+        assert_eq!(config.engines[0].max_power_orders, 8);
+
+        assert_eq!(imported.unmapped_inputs, vec!["UseNewsFilter"]);
+        assert_eq!(imported.missing_inputs, vec!["TrailingStop"]);
+    }
+
+    #[test]
+    fn test_mapping_rejects_unknown_targets() {
+        let profile = parse_profile(
+            "name = \"typo\"\n\
+             [[rules]]\n\
+             source = \"PipStep\"\n\
+             target = \"gird\"\n\
+             engine = \"A\"\n\
+             group = 1\n",
+        )
+        .unwrap();
+        let err = apply_mapping(FOREIGN_SET, &profile).unwrap_err();
+        assert!(err.contains("unknown field"), "{}", err);
+    }
+}
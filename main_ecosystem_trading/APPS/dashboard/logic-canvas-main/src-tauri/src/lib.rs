@@ -1,20 +1,92 @@
 mod mt_bridge;
+mod setfile_format;
+mod setfile_schema;
+mod logic_defaults;
+mod setfile_writers;
+mod naming;
+mod os_paths;
+mod terminal_discovery;
+mod chart_profiles;
+mod terminal_processes;
 pub mod mql_rust_compiler;
+pub mod mql_lint;
+pub mod import_log;
+mod app_log;
+mod crash_report;
+mod backtest;
+mod optimization;
+mod walk_forward;
+mod sweep;
+mod randomize;
+mod forward_optimization;
+mod units;
+mod symbols;
+mod validation;
+mod active_backup;
+mod ea_deploy;
+mod export_debounce;
+mod trade_history;
+mod ab_test;
+mod workspace;
+mod vault_search;
+mod vault_metadata;
+mod vault_location;
+mod cloud_sync;
+mod vault_git;
+mod vault_backend;
+mod optimizer;
+mod risk;
+mod news;
+mod sessions;
+mod timezone;
+mod licensing;
+mod preset_signing;
+mod preset_feed;
+mod url_import;
+mod redaction;
+mod field_locks;
+mod safe_mode;
 mod mql_compiler;
 pub mod headless;
 mod chat_neural;
+mod chat_dataset;
+mod chat_eval;
+mod chat_apply;
+mod chat_macros;
+mod config_handles;
+mod config_patch;
+mod param_clipboard;
+mod logic_copy;
+mod config_scale;
+mod direction_mirror;
+mod constraints;
+mod support_bundle;
+mod config_wire;
+mod annotations;
+mod config_stats;
+mod archetypes;
+mod import_mapping;
+mod chat_summary;
+mod enum_labels;
 mod chat_commands;
 mod chat_preprocessor;
+mod subword_tokenizer;
+mod onnx;
+mod quantize;
 mod trading_transformer;
 mod diffusion_refine;
 mod tinyllm_command;
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
 use tauri::Emitter;
 
 #[cfg(feature = "tauri-app")]
 use mt_bridge::MTBridgeState;
 
-use chat_commands::{ChatNeuralState, TransformerState, DiffusionState};
+use chat_commands::{ChatNeuralState, ConversationState, TransformerState, DiffusionState};
 
 // Re-export headless API for CLI
 pub use headless::handle_message_headless;
@@ -39,16 +111,232 @@ pub use mt_bridge::{
   LogicConfig,
 };
 
+// ============================================
+// LONG-RUNNING TASKS (cancellation + progress)
+// ============================================
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TaskProgress {
+  pub task_id: String,
+  pub kind: String,
+  pub stage: String,
+  pub current: usize,
+  pub total: usize,
+  pub cancelled: bool,
+  pub started_at: i64,
+}
+
+/// Payload of the `task-progress` event, emitted whenever a task enters a new
+/// section or its whole-number percentage changes, plus once when it finishes.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProgressEvent {
+  pub task_id: String,
+  pub kind: String,
+  pub section: String,
+  pub percent: f64,
+  pub keys_processed: usize,
+  pub total_keys: usize,
+  pub done: bool,
+}
+
+pub const PROGRESS_EVENT: &str = "task-progress";
+
+type ProgressSink = Box<dyn Fn(&ProgressEvent) + Send + Sync>;
+
+static PROGRESS_SINK: OnceLock<ProgressSink> = OnceLock::new();
+
+/// Route progress events somewhere (the Tauri event bus in `run`). Only the first
+/// sink is kept; without one, progress is still visible via `list_tasks`.
+pub fn set_progress_sink(sink: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> bool {
+  PROGRESS_SINK.set(Box::new(sink)).is_ok()
+}
+
+fn emit_progress(event: &ProgressEvent) {
+  if let Some(sink) = PROGRESS_SINK.get() {
+    sink(event);
+  }
+}
+
+fn progress_percent(current: usize, total: usize) -> f64 {
+  if total == 0 {
+    0.0
+  } else {
+    (current.min(total) as f64 / total as f64) * 100.0
+  }
+}
+
+/// Handed to a long-running operation; it polls `check()` at safe points and
+/// reports progress so the UI can show it via `list_tasks` and `task-progress` events.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+  pub id: String,
+  cancelled: Arc<AtomicBool>,
+  progress: Arc<Mutex<TaskProgress>>,
+}
+
+impl TaskHandle {
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::SeqCst)
+  }
+
+  /// Err once cancel_task has been called for this task - propagate it with `?`
+  pub fn check(&self) -> Result<(), String> {
+    if self.is_cancelled() {
+      Err(format!("Task {} was cancelled", self.id))
+    } else {
+      Ok(())
+    }
+  }
+
+  pub fn report(&self, stage: &str, current: usize, total: usize) {
+    let event = match self.progress.lock() {
+      Ok(mut progress) => {
+        let changed = progress.stage != stage
+          || progress_percent(progress.current, progress.total).floor()
+            != progress_percent(current, total).floor();
+        progress.stage = stage.to_string();
+        progress.current = current;
+        progress.total = total;
+        if changed {
+          Some(progress_event(&progress, false))
+        } else {
+          None
+        }
+      }
+      Err(_) => None,
+    };
+    if let Some(event) = event {
+      emit_progress(&event);
+    }
+  }
+
+  pub fn progress(&self) -> TaskProgress {
+    let mut progress = match self.progress.lock() {
+      Ok(p) => p.clone(),
+      Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    progress.cancelled = self.is_cancelled();
+    progress
+  }
+}
+
+fn progress_event(progress: &TaskProgress, done: bool) -> ProgressEvent {
+  ProgressEvent {
+    task_id: progress.task_id.clone(),
+    kind: progress.kind.clone(),
+    section: progress.stage.clone(),
+    percent: if done && !progress.cancelled { 100.0 } else { progress_percent(progress.current, progress.total) },
+    keys_processed: progress.current,
+    total_keys: progress.total,
+    done,
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct TaskManager {
+  tasks: Mutex<HashMap<String, TaskHandle>>,
+}
+
+impl TaskManager {
+  /// Register a task under the caller-supplied id (so the UI can cancel it before the
+  /// command returns) or a fresh uuid
+  pub fn begin(&self, task_id: Option<String>, kind: &str) -> TaskHandle {
+    let id = task_id
+      .filter(|id| !id.trim().is_empty())
+      .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let handle = TaskHandle {
+      id: id.clone(),
+      cancelled: Arc::new(AtomicBool::new(false)),
+      progress: Arc::new(Mutex::new(TaskProgress {
+        task_id: id.clone(),
+        kind: kind.to_string(),
+        stage: "starting".to_string(),
+        current: 0,
+        total: 0,
+        cancelled: false,
+        started_at: chrono::Local::now().timestamp_millis(),
+      })),
+    };
+    if let Ok(mut tasks) = self.tasks.lock() {
+      tasks.insert(id, handle.clone());
+    }
+    handle
+  }
+
+  pub fn finish(&self, task_id: &str) {
+    let finished = match self.tasks.lock() {
+      Ok(mut tasks) => tasks.remove(task_id),
+      Err(_) => None,
+    };
+    if let Some(handle) = finished {
+      emit_progress(&progress_event(&handle.progress(), true));
+    }
+  }
+
+  pub fn cancel(&self, task_id: &str) -> bool {
+    match self.tasks.lock() {
+      Ok(tasks) => match tasks.get(task_id) {
+        Some(handle) => {
+          handle.cancelled.store(true, Ordering::SeqCst);
+          true
+        }
+        None => false,
+      },
+      Err(_) => false,
+    }
+  }
+
+  pub fn list(&self) -> Vec<TaskProgress> {
+    self
+      .tasks
+      .lock()
+      .map(|tasks| tasks.values().map(|t| t.progress()).collect())
+      .unwrap_or_default()
+  }
+}
+
+static TASK_MANAGER: OnceLock<TaskManager> = OnceLock::new();
+
+pub fn task_manager() -> &'static TaskManager {
+  TASK_MANAGER.get_or_init(TaskManager::default)
+}
+
+/// Request cancellation; returns false if the task already finished (or never existed)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn cancel_task(task_id: String) -> Result<bool, String> {
+  Ok(task_manager().cancel(&task_id))
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_tasks() -> Result<Vec<TaskProgress>, String> {
+  Ok(task_manager().list())
+}
+
 #[cfg(feature = "tauri-app")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+  import_log::init_tracing();
+  crash_report::install_panic_hook();
+
   tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
     .manage(MTBridgeState::new())
     .manage(ChatNeuralState::default())
+    .manage(ConversationState::default())
     .manage(TransformerState::default())
     .manage(DiffusionState::default())
     .setup(|app| {
+      let progress_handle = app.handle().clone();
+      set_progress_sink(move |event| {
+        let _ = progress_handle.emit(PROGRESS_EVENT, event);
+      });
+
+      // Sweep temp files left in the vault by interrupted saves
+      std::thread::spawn(cloud_sync::cleanup_vault_tmp_files);
+
+      // Terminal writes stay locked until the stored license is validated
+      tauri::async_runtime::spawn(licensing::validate_on_startup());
+
       // Start silicon monitoring - emits every 2 seconds
       let app_handle = app.handle().clone();
       std::thread::spawn(move || {
@@ -85,30 +373,195 @@ pub fn run() {
       mt_bridge::import_set_file,
       mt_bridge::export_json_file,
       mt_bridge::import_json_file,
+      url_import::import_from_url,
       mt_bridge::write_text_file,
       mt_bridge::parse_massive_setfile,
       mt_bridge::list_vault_files,
+      vault_search::search_vault,
+      vault_search::build_vault_query,
+      vault_search::chat_search_vault,
+      vault_metadata::edit_vault_metadata,
+      vault_location::get_vault_location,
+      vault_location::set_vault_location,
+      vault_location::select_vault_location,
+      vault_location::remove_vault_location,
+      vault_location::set_vault_backend,
+      vault_backend::sync_vault_cache,
+      vault_git::get_vault_git_status,
+      vault_git::init_vault_git,
+      vault_git::pull_vault,
+      vault_git::push_vault,
+      vault_git::resolve_vault_conflict,
+      trade_history::get_trading_hours_heatmap,
+      trade_history::get_equity_curve,
+      ab_test::deploy_ab_test,
+      ab_test::list_ab_tests,
+      ab_test::get_ab_test_results,
       mt_bridge::open_vault_folder,
+      terminal_discovery::list_terminal_profiles,
+      chart_profiles::list_attached_charts,
+      terminal_processes::list_terminal_processes,
+      terminal_processes::launch_terminal,
+      terminal_processes::close_terminal,
+      workspace::open_workspace,
+      workspace::save_workspace,
+      config_handles::open_config_handle,
+      config_handles::duplicate_config_handle,
+      config_handles::close_config_handle,
+      config_handles::activate_config_handle,
+      config_handles::list_config_handles,
+      config_handles::get_config_by_handle,
+      config_patch::apply_config_patch,
+      param_clipboard::copy_param_block,
+      param_clipboard::paste_param_block,
+      logic_copy::copy_logic_params,
+      config_scale::scale_config_fields,
+      direction_mirror::mirror_direction_params,
+      constraints::get_constraints,
+      constraints::set_constraints,
+      constraints::get_constraint_diagnostics,
+      constraints::check_constraints,
+      config_wire::config_to_json,
+      config_wire::config_from_json,
+      annotations::set_config_annotation,
+      annotations::get_config_annotations,
+      config_stats::get_config_summary,
+      archetypes::list_config_archetypes,
+      archetypes::create_config_from_archetype,
+      import_mapping::import_set_file_with_mapping,
+      import_mapping::list_import_mapping_profiles,
       mt_bridge::save_to_vault,
       mt_bridge::_export_vault_file,
       mt_bridge::_delete_from_vault,
       mt_bridge::get_vault_size,
       mt_bridge::export_massive_v19_setfile,
+      mt_bridge::render_set_preview,
+      setfile_format::list_setfile_formats,
+      setfile_format::detect_setfile_format,
+      setfile_schema::list_setfile_keys,
+      setfile_schema::get_setfile_key_reference,
+      naming::get_naming_policy,
+      naming::set_naming_policy,
+      mt_bridge::get_parse_cache_stats,
+      mt_bridge::clear_parse_cache,
+      mt_bridge::get_last_import_log,
+      app_log::get_recent_logs,
+      crash_report::list_crash_reports,
+      crash_report::get_crash_report,
+      crash_report::delete_crash_report,
+      crash_report::get_crash_report_settings,
+      crash_report::set_crash_report_settings,
+      crash_report::upload_crash_report,
+      support_bundle::export_support_bundle,
+      backtest::run_backtest,
+      backtest::parse_backtest_report,
+      walk_forward::generate_walk_forward_plan,
+      walk_forward::collect_walk_forward_results,
+      sweep::generate_parameter_sweep,
+      randomize::generate_randomized_variants,
+      forward_optimization::export_forward_optimization_sets,
+      units::convert_config_units,
+      units::describe_distance,
+      symbols::get_symbol_spec,
+      symbols::list_symbol_specs,
+      validation::validate_config,
+      active_backup::restore_previous_active_set,
+      ea_deploy::deploy_ea,
+      export_debounce::get_export_debounce_settings,
+      export_debounce::set_export_debounce_settings,
+      optimizer::start_optimization_run,
+      optimizer::advance_optimization_run,
+      optimizer::inspect_optimization_run,
+      risk::simulate_risk,
+      risk::calculate_exposure,
+      risk::advise_position_size,
+      risk::compute_grid_ladder,
+      news::refresh_news_calendar,
+      news::get_upcoming_news,
+      news::generate_news_csv,
+      news::import_news_csv,
+      sessions::preview_session_schedule,
+      timezone::get_broker_timezones,
+      timezone::set_broker_timezone,
+      timezone::convert_sessions_timezone,
+      licensing::get_machine_fingerprint,
+      licensing::activate_license,
+      licensing::validate_license,
+      licensing::deactivate_license,
+      licensing::get_license_status,
+      preset_signing::generate_preset_signing_key,
+      preset_signing::set_preset_public_key,
+      preset_signing::sign_preset,
+      preset_signing::verify_preset,
+      preset_feed::get_preset_feed,
+      preset_feed::set_preset_feed,
+      preset_feed::publish_preset,
+      preset_feed::check_for_preset_updates,
+      field_locks::get_field_locks,
+      field_locks::lock_fields,
+      field_locks::unlock_fields,
+      field_locks::check_field_locks,
+      safe_mode::get_safe_mode,
+      safe_mode::set_safe_mode,
+      optimization::parse_optimization_results,
+      optimization::load_optimization_pass,
+      mt_bridge::validate_set_against_source,
+      mt_bridge::list_lint_rules,
+      mt_bridge::set_lint_config,
+      mt_bridge::initialize_mql_compiler,
+      mt_bridge::run_precompilation_pipeline,
+      cancel_task,
+      list_tasks,
       // Chat neural network commands
       chat_commands::train_chat_neural,
       chat_commands::predict_intent,
       chat_commands::learn_correction,
       chat_commands::is_trained,
+      chat_commands::set_intent_confidence_threshold,
+      chat_commands::get_intent_confidence_threshold,
+      chat_commands::evaluate_chat_model,
+      chat_commands::resolve_chat_turn,
+      chat_commands::get_conversation_context,
+      chat_commands::reset_conversation,
+      chat_commands::save_chat_model,
+      chat_commands::load_chat_model,
+      chat_commands::list_chat_models,
+      chat_commands::list_training_examples,
+      chat_commands::add_training_example,
+      chat_commands::update_training_example,
+      chat_commands::delete_training_example,
+      chat_commands::get_training_intent_counts,
+      chat_commands::export_training_dataset,
+      chat_commands::import_training_dataset,
       // Transformer commands
       chat_commands::train_transformer,
       chat_commands::predict_transformer,
       chat_commands::is_transformer_trained,
+      chat_commands::tokenize_transformer_input,
+      chat_commands::export_model_onnx,
+      chat_commands::import_model_onnx,
       // Diffusion commands
       chat_commands::train_diffusion_pipeline,
       chat_commands::predict_with_diffusion,
       chat_commands::extract_parameter,
+      chat_commands::set_fallback_thresholds,
+      chat_commands::get_fallback_thresholds,
+      chat_commands::set_inference_precision,
+      chat_commands::get_inference_precision,
+      chat_commands::benchmark_inference,
       // Chat preprocessor commands
       chat_preprocessor::preprocess_command,
+      chat_preprocessor::extract_command_slots,
+      chat_apply::apply_chat_intent,
+      chat_apply::undo_chat_change,
+      chat_summary::summarize_config,
+      enum_labels::get_enum_labels,
+      chat_macros::record_macro_start,
+      chat_macros::record_macro_stop,
+      chat_macros::list_macros,
+      chat_macros::delete_macro,
+      chat_macros::match_macro_command,
+      chat_macros::run_macro,
       // TinyLLM commands
       tinyllm_command::process_command,
       tinyllm_command::get_silicon_status,
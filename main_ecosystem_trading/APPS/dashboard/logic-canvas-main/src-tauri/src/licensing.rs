@@ -0,0 +1,686 @@
+// ============================================
+// LICENSE SERVER CLIENT
+// ============================================
+//
+// Talks to the server configured in `license_server_url` so the dashboard can
+// enforce licensing itself instead of relying on the EA alone. Every request
+// carries an HMAC-SHA256 signature keyed by the license key plus this
+// machine's fingerprint. Responses are signed with the same key over the
+// request's nonce, the response timestamp and the body, so a captured reply
+// can't be replayed to a later request. The last successful validation is
+// cached (signed, so it can't be edited by hand) and honoured offline for a
+// grace period, but only when the server can't be reached at all.
+//
+// The key and server of the last activation are kept (obfuscated, like the
+// config's copy) so the license is validated at startup. Until that check
+// says the license is valid, writes to live terminals (Common Files and
+// ACTIVE.set exports, EA deployment) fail with a LicenseRequired error;
+// editing, the vault and backtests keep working.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+use crate::mt_bridge::{atomic_write, deobfuscate_string, obfuscate_string};
+use crate::url_import::{is_secure_url, secure_redirect_policy};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const LICENSE_CACHE_FILE: &str = "license_cache.json";
+const LICENSE_SETTINGS_FILE: &str = "license_settings.json";
+const OFFLINE_GRACE_DAYS: i64 = 7;
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const SIGNATURE_HEADER: &str = "X-DAAVFX-Signature";
+const TIMESTAMP_HEADER: &str = "X-DAAVFX-Timestamp";
+/// Responses timestamped further than this from the local clock are rejected
+const MAX_RESPONSE_AGE_SECS: i64 = 300;
+
+/// Prefix of the error terminal writes return without a valid license
+pub const LICENSE_REQUIRED: &str = "LicenseRequired";
+
+/// Outcome of the latest validation in this process; None until one finished
+static CURRENT_STATUS: OnceLock<RwLock<Option<LicenseStatus>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseStatus {
+    pub valid: bool,
+    /// "active", "expired", "revoked", "deactivated", "invalid" or "unknown"
+    pub status: String,
+    pub fingerprint: String,
+    pub expires_at: Option<String>,
+    pub last_validated_at: Option<String>,
+    /// True when the answer comes from the cache because the server was unreachable
+    pub offline: bool,
+    pub grace_remaining_hours: Option<i64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerResponse {
+    valid: bool,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseCache {
+    /// SHA-256 of the license key; the key itself is never written to disk
+    pub key_hash: String,
+    pub fingerprint: String,
+    pub status: String,
+    pub expires_at: Option<String>,
+    pub activated_at: Option<String>,
+    pub last_validated_at: String,
+    /// HMAC over the fields above, keyed by the license key
+    pub signature: String,
+}
+
+/// Key and server of the last activation, both obfuscated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseSettings {
+    license_key: String,
+    server_url: String,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+pub fn hmac_hex(key: &str, message: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn hmac_verify(key: &str, message: &str, signature_hex: &str) -> bool {
+    let expected = match hex::decode(signature_hex.trim()) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+    let mut mac =
+        HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Stable per-machine identifier: hash of host, user and home folder
+pub fn machine_fingerprint() -> String {
+    let host = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default();
+    let user = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default();
+    let home = dirs::home_dir()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let raw = format!("{}|{}|{}|{}", std::env::consts::OS, host, user, home);
+    sha256_hex(raw.as_bytes())[..32].to_string()
+}
+
+/// Canonical string the request signature covers
+pub fn signing_payload(path: &str, timestamp: i64, nonce: &str, body: &str) -> String {
+    format!("POST\n{}\n{}\n{}\n{}", path, timestamp, nonce, body)
+}
+
+/// Canonical string the response signature covers
+pub fn response_signing_payload(nonce: &str, timestamp: i64, body: &str) -> String {
+    format!("RESPONSE\n{}\n{}\n{}", nonce, timestamp, body)
+}
+
+/// Check that a response was signed for the request carrying `nonce` and is fresh
+fn verify_response(
+    license_key: &str,
+    nonce: &str,
+    timestamp: Option<&str>,
+    signature: Option<&str>,
+    body: &str,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return Err("License server response is not signed".to_string());
+    };
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| "License server response has an invalid timestamp".to_string())?;
+    if !hmac_verify(
+        license_key,
+        &response_signing_payload(nonce, timestamp, body),
+        signature,
+    ) {
+        return Err("License server response signature is invalid".to_string());
+    }
+    if (now.timestamp() - timestamp).abs() > MAX_RESPONSE_AGE_SECS {
+        return Err("License server response is stale".to_string());
+    }
+    Ok(())
+}
+
+/// Why a request produced no usable answer
+#[derive(Debug)]
+enum SendError {
+    /// The server couldn't be reached; the offline cache may answer instead
+    Unreachable(String),
+    /// The server answered, but the answer can't be trusted or used
+    Rejected(String),
+}
+
+impl From<SendError> for String {
+    fn from(error: SendError) -> Self {
+        match error {
+            SendError::Unreachable(e) | SendError::Rejected(e) => e,
+        }
+    }
+}
+
+impl LicenseCache {
+    fn payload(&self) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.key_hash,
+            self.fingerprint,
+            self.status,
+            self.expires_at.as_deref().unwrap_or(""),
+            self.activated_at.as_deref().unwrap_or(""),
+            self.last_validated_at
+        )
+    }
+
+    fn sign(mut self, license_key: &str) -> Self {
+        self.signature = hmac_hex(license_key, &self.payload());
+        self
+    }
+
+    fn is_authentic(&self, license_key: &str) -> bool {
+        self.key_hash == sha256_hex(license_key.as_bytes())
+            && hmac_verify(license_key, &self.payload(), &self.signature)
+    }
+}
+
+fn license_cache_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(LICENSE_CACHE_FILE)
+}
+
+fn load_license_cache() -> Option<LicenseCache> {
+    let content = std::fs::read_to_string(license_cache_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_license_cache(cache: &LicenseCache) -> Result<(), String> {
+    let path = license_cache_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Failed to serialize license cache: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+fn license_settings_path() -> PathBuf {
+    license_cache_path().with_file_name(LICENSE_SETTINGS_FILE)
+}
+
+fn load_license_settings() -> Option<LicenseSettings> {
+    let content = std::fs::read_to_string(license_settings_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_license_settings(license_key: &str, server_url: &str) -> Result<(), String> {
+    let settings = LicenseSettings {
+        license_key: obfuscate_string(license_key),
+        server_url: obfuscate_string(server_url),
+    };
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize license settings: {}", e))?;
+    atomic_write(&license_settings_path(), &json)
+}
+
+fn current_status() -> &'static RwLock<Option<LicenseStatus>> {
+    CURRENT_STATUS.get_or_init(|| RwLock::new(None))
+}
+
+fn remember(status: &LicenseStatus) {
+    if let Ok(mut current) = current_status().write() {
+        *current = Some(status.clone());
+    }
+}
+
+fn unlicensed(status: &str, message: String) -> LicenseStatus {
+    LicenseStatus {
+        valid: false,
+        status: status.to_string(),
+        fingerprint: machine_fingerprint(),
+        expires_at: None,
+        last_validated_at: None,
+        offline: false,
+        grace_remaining_hours: None,
+        message: Some(message),
+    }
+}
+
+fn check_licensed(status: Option<&LicenseStatus>, action: &str) -> Result<(), String> {
+    match status {
+        Some(status) if status.valid => Ok(()),
+        Some(status) => Err(format!(
+            "{}: {} needs a valid license (license is {})",
+            LICENSE_REQUIRED, action, status.status
+        )),
+        None => Err(format!(
+            "{}: {} needs a valid license; the license check hasn't finished",
+            LICENSE_REQUIRED, action
+        )),
+    }
+}
+
+/// Err(LicenseRequired) unless the latest validation found a valid license;
+/// call before writing into a live terminal
+pub(crate) fn ensure_licensed(action: &str) -> Result<(), String> {
+    let current = current_status()
+        .read()
+        .map_err(|_| "License status is unavailable".to_string())?;
+    check_licensed(current.as_ref(), action)
+}
+
+/// Validate the license of the last activation, so terminal writes unlock without
+/// the user opening the license screen
+pub async fn validate_on_startup() {
+    let Some(settings) = load_license_settings() else {
+        remember(&unlicensed(
+            "unknown",
+            "No license is activated on this machine".to_string(),
+        ));
+        return;
+    };
+    match validate_license(settings.license_key, settings.server_url).await {
+        Ok(status) if status.valid => tracing::info!("License is valid"),
+        Ok(status) => tracing::warn!(status = %status.status, "License is not valid"),
+        Err(e) => tracing::warn!(error = %e, "License validation failed"),
+    }
+}
+
+fn is_expired(expires_at: Option<&str>, now: DateTime<Utc>) -> bool {
+    expires_at
+        .and_then(|e| DateTime::parse_from_rfc3339(e).ok())
+        .map(|e| e.with_timezone(&Utc) <= now)
+        .unwrap_or(false)
+}
+
+/// Decide from the cache alone (server unreachable)
+pub fn offline_status(
+    cache: Option<&LicenseCache>,
+    license_key: &str,
+    fingerprint: &str,
+    now: DateTime<Utc>,
+    error: &str,
+) -> LicenseStatus {
+    let mut status = LicenseStatus {
+        valid: false,
+        status: "unknown".to_string(),
+        fingerprint: fingerprint.to_string(),
+        expires_at: None,
+        last_validated_at: None,
+        offline: true,
+        grace_remaining_hours: None,
+        message: Some(format!("License server unreachable: {}", error)),
+    };
+    let cache = match cache {
+        Some(cache) if cache.is_authentic(license_key) && cache.fingerprint == fingerprint => cache,
+        _ => return status,
+    };
+    status.status = cache.status.clone();
+    status.expires_at = cache.expires_at.clone();
+    status.last_validated_at = Some(cache.last_validated_at.clone());
+
+    let last = match DateTime::parse_from_rfc3339(&cache.last_validated_at) {
+        Ok(t) => t.with_timezone(&Utc),
+        Err(_) => return status,
+    };
+    let remaining = last + Duration::days(OFFLINE_GRACE_DAYS) - now;
+    status.grace_remaining_hours = Some(remaining.num_hours().max(0));
+    status.valid = cache.status == "active"
+        && remaining > Duration::zero()
+        && !is_expired(cache.expires_at.as_deref(), now);
+    status
+}
+
+struct LicenseRequest {
+    license_key: String,
+    server_url: String,
+}
+
+impl LicenseRequest {
+    fn new(license_key: &str, server_url: &str) -> Result<Self, String> {
+        let license_key = deobfuscate_string(license_key).trim().to_string();
+        let server_url = deobfuscate_string(server_url)
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        if license_key.is_empty() {
+            return Err("No license key configured".to_string());
+        }
+        // Plain http:// would let anyone on the path answer for the server
+        if !reqwest::Url::parse(&server_url).is_ok_and(|url| is_secure_url(&url)) {
+            return Err(format!(
+                "License server URL must use https:// '{}'",
+                server_url
+            ));
+        }
+        Ok(Self {
+            license_key,
+            server_url,
+        })
+    }
+
+    async fn send(&self, endpoint: &str, fingerprint: &str) -> Result<ServerResponse, SendError> {
+        let path = format!("/{}", endpoint);
+        let body = serde_json::json!({
+            "licenseKeyHash": sha256_hex(self.license_key.as_bytes()),
+            "fingerprint": fingerprint,
+            "appVersion": env!("CARGO_PKG_VERSION"),
+        })
+        .to_string();
+        let timestamp = Utc::now().timestamp();
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let signature = hmac_hex(
+            &self.license_key,
+            &signing_payload(&path, timestamp, &nonce, &body),
+        );
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .redirect(secure_redirect_policy())
+            .build()
+            .map_err(|e| SendError::Unreachable(format!("Failed to create HTTP client: {}", e)))?;
+        let response = client
+            .post(format!("{}{}", self.server_url, path))
+            .header("Content-Type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header("X-DAAVFX-Nonce", &nonce)
+            .header("X-DAAVFX-Fingerprint", fingerprint)
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                SendError::Unreachable(format!("Failed to reach license server: {}", e))
+            })?;
+
+        let status = response.status();
+        // A gateway error means the license server itself wasn't reached
+        if status.is_server_error() {
+            return Err(SendError::Unreachable(format!(
+                "License server returned HTTP {}",
+                status
+            )));
+        }
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let response_timestamp = header(TIMESTAMP_HEADER);
+        let response_signature = header(SIGNATURE_HEADER);
+        let text = response.text().await.map_err(|e| {
+            SendError::Unreachable(format!("Failed to read license server response: {}", e))
+        })?;
+        verify_response(
+            &self.license_key,
+            &nonce,
+            response_timestamp.as_deref(),
+            response_signature.as_deref(),
+            &text,
+            Utc::now(),
+        )
+        .map_err(SendError::Rejected)?;
+        if !status.is_success() && status.as_u16() != 403 {
+            return Err(SendError::Rejected(format!(
+                "License server returned HTTP {}",
+                status
+            )));
+        }
+        serde_json::from_str(&text).map_err(|e| {
+            SendError::Rejected(format!("Failed to parse license server response: {}", e))
+        })
+    }
+
+    fn record(
+        &self,
+        fingerprint: &str,
+        response: &ServerResponse,
+        activated: bool,
+    ) -> Result<LicenseStatus, String> {
+        let now = Utc::now().to_rfc3339();
+        let status = response.status.clone().unwrap_or_else(|| {
+            if response.valid {
+                "active".to_string()
+            } else {
+                "invalid".to_string()
+            }
+        });
+        let previous = load_license_cache();
+        let cache = LicenseCache {
+            key_hash: sha256_hex(self.license_key.as_bytes()),
+            fingerprint: fingerprint.to_string(),
+            status: status.clone(),
+            expires_at: response.expires_at.clone(),
+            activated_at: if activated {
+                Some(now.clone())
+            } else {
+                previous
+                    .filter(|p| p.is_authentic(&self.license_key))
+                    .and_then(|p| p.activated_at)
+            },
+            last_validated_at: now.clone(),
+            signature: String::new(),
+        }
+        .sign(&self.license_key);
+        save_license_cache(&cache)?;
+
+        Ok(LicenseStatus {
+            valid: response.valid && status == "active",
+            status,
+            fingerprint: fingerprint.to_string(),
+            expires_at: response.expires_at.clone(),
+            last_validated_at: Some(now),
+            offline: false,
+            grace_remaining_hours: None,
+            message: response.message.clone(),
+        })
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_machine_fingerprint() -> Result<String, String> {
+    Ok(machine_fingerprint())
+}
+
+/// Bind the license to this machine
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn activate_license(
+    license_key: String,
+    server_url: String,
+) -> Result<LicenseStatus, String> {
+    let request = LicenseRequest::new(&license_key, &server_url)?;
+    let fingerprint = machine_fingerprint();
+    let response = request.send("activate", &fingerprint).await?;
+    let status = request.record(&fingerprint, &response, true)?;
+    if status.valid {
+        save_license_settings(&request.license_key, &request.server_url)?;
+    }
+    remember(&status);
+    Ok(status)
+}
+
+/// Validate online, falling back to the signed cache within the grace period when the
+/// server can't be reached. A reply that fails verification is an error, not a fallback.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn validate_license(
+    license_key: String,
+    server_url: String,
+) -> Result<LicenseStatus, String> {
+    let request = LicenseRequest::new(&license_key, &server_url)?;
+    let fingerprint = machine_fingerprint();
+    let status = match request.send("validate", &fingerprint).await {
+        Ok(response) => request.record(&fingerprint, &response, false)?,
+        Err(SendError::Rejected(e)) => {
+            remember(&unlicensed("invalid", e.clone()));
+            return Err(e);
+        }
+        Err(SendError::Unreachable(e)) => offline_status(
+            load_license_cache().as_ref(),
+            &request.license_key,
+            &fingerprint,
+            Utc::now(),
+            &e,
+        ),
+    };
+    remember(&status);
+    Ok(status)
+}
+
+/// Outcome of the latest validation; None while the startup check is still running
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_license_status() -> Option<LicenseStatus> {
+    current_status().read().ok().and_then(|s| s.clone())
+}
+
+/// Release this machine's activation and clear the local cache
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn deactivate_license(
+    license_key: String,
+    server_url: String,
+) -> Result<LicenseStatus, String> {
+    let request = LicenseRequest::new(&license_key, &server_url)?;
+    let fingerprint = machine_fingerprint();
+    let response = request.send("deactivate", &fingerprint).await?;
+    for path in [license_cache_path(), license_settings_path()] {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+    let status = LicenseStatus {
+        valid: false,
+        status: "deactivated".to_string(),
+        fingerprint,
+        expires_at: None,
+        last_validated_at: None,
+        offline: false,
+        grace_remaining_hours: None,
+        message: response.message,
+    };
+    remember(&status);
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(key: &str, validated: DateTime<Utc>) -> LicenseCache {
+        LicenseCache {
+            key_hash: sha256_hex(key.as_bytes()),
+            fingerprint: "fp".to_string(),
+            status: "active".to_string(),
+            expires_at: None,
+            activated_at: None,
+            last_validated_at: validated.to_rfc3339(),
+            signature: String::new(),
+        }
+        .sign(key)
+    }
+
+    #[test]
+    fn test_hmac_signing_is_deterministic_and_verifiable() {
+        let payload = signing_payload("/validate", 1_700_000_000, "n1", "{}");
+        assert_eq!(payload, "POST\n/validate\n1700000000\nn1\n{}");
+        let sig = hmac_hex("secret", &payload);
+        assert_eq!(sig, hmac_hex("secret", &payload));
+        assert!(hmac_verify("secret", &payload, &sig));
+        assert!(!hmac_verify("other", &payload, &sig));
+    }
+
+    #[test]
+    fn test_response_signature_binds_nonce_and_timestamp() {
+        let now = Utc::now();
+        let body = r#"{"valid":true,"status":"active"}"#;
+        let ts = now.timestamp();
+        let sig = hmac_hex("KEY-1", &response_signing_payload("n1", ts, body));
+        let ts_text = ts.to_string();
+        let verify = |nonce: &str, ts: Option<&str>, sig: Option<&str>, at: DateTime<Utc>| {
+            verify_response("KEY-1", nonce, ts, sig, body, at)
+        };
+
+        assert!(verify("n1", Some(&ts_text), Some(&sig), now).is_ok());
+        // Replayed to a later request: different nonce
+        assert!(verify("n2", Some(&ts_text), Some(&sig), now).is_err());
+        // Replayed later with the same nonce: stale
+        let later = now + Duration::seconds(MAX_RESPONSE_AGE_SECS + 1);
+        assert!(verify("n1", Some(&ts_text), Some(&sig), later).is_err());
+        // Timestamp swapped for a fresh one: signature no longer matches
+        let fresh = later.timestamp().to_string();
+        assert!(verify("n1", Some(&fresh), Some(&sig), later).is_err());
+        // Body-only signature (the old scheme) is not accepted
+        let body_only = hmac_hex("KEY-1", body);
+        assert!(verify("n1", Some(&ts_text), Some(&body_only), now).is_err());
+        assert!(verify("n1", None, Some(&sig), now).is_err());
+        assert!(verify("n1", Some(&ts_text), None, now).is_err());
+    }
+
+    #[test]
+    fn test_terminal_writes_need_a_valid_license() {
+        let err = check_licensed(None, "Deploying the EA").unwrap_err();
+        assert!(
+            err.starts_with("LicenseRequired: Deploying the EA"),
+            "{}",
+            err
+        );
+        let mut status = unlicensed("expired", "Renew".to_string());
+        assert!(check_licensed(Some(&status), "Deploying the EA").is_err());
+        status.valid = true;
+        assert!(check_licensed(Some(&status), "Deploying the EA").is_ok());
+
+        assert!(LicenseRequest::new("KEY-1", "http://license.example.com").is_err());
+        assert!(LicenseRequest::new("KEY-1", "https://license.example.com/").is_ok());
+        assert!(LicenseRequest::new("", "https://license.example.com").is_err());
+    }
+
+    #[test]
+    fn test_offline_grace_period_and_tampering() {
+        let now = Utc::now();
+        let fresh = cache("KEY-1", now - Duration::days(2));
+        let status = offline_status(Some(&fresh), "KEY-1", "fp", now, "timeout");
+        assert!(status.valid);
+        assert!(status.offline);
+        assert!(status.grace_remaining_hours.unwrap() > 24 * 4);
+
+        let old = cache("KEY-1", now - Duration::days(OFFLINE_GRACE_DAYS + 1));
+        assert!(!offline_status(Some(&old), "KEY-1", "fp", now, "timeout").valid);
+
+        let mut tampered = fresh.clone();
+        tampered.status = "active".to_string();
+        tampered.last_validated_at = now.to_rfc3339();
+        assert!(!offline_status(Some(&tampered), "KEY-1", "fp", now, "timeout").valid);
+        assert!(!offline_status(Some(&fresh), "KEY-2", "fp", now, "timeout").valid);
+        assert!(!offline_status(Some(&fresh), "KEY-1", "other-machine", now, "timeout").valid);
+    }
+}
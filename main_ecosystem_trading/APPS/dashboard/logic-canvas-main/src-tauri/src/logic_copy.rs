@@ -0,0 +1,343 @@
+// ============================================
+// BULK LOGIC FIELD COPY
+// ============================================
+//
+// Replicates selected fields of one logic row onto many others in the same
+// config, e.g. group 1 Power trail settings onto groups 2-15 of every engine,
+// which in the editor takes hundreds of clicks. Targets are addressed like
+// clipboard blocks: a logic row on its own, or a group / engine ("*" for all
+// engines) meaning every row in it with the source's logic name and buy/sell
+// direction. Fields are picked by name, with a trailing '*' matching a prefix
+// ("trail_*"). All targets change in one undoable commit and every changed
+// field is reported.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::config_handles::{mask_matches, row_object, BulkEditOutcome, BulkEditReport};
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::param_clipboard::BlockAddress;
+
+#[cfg(feature = "tauri-app")]
+use crate::config_handles::apply_bulk_edit;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+/// A row's identity and direction; never copied
+const IDENTITY_FIELDS: &[&str] = &["logic_name", "logic_id", "allow_buy", "allow_sell"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicFieldChange {
+    /// JSON pointer, e.g. /engines/1/groups/4/logics/0/trail_value
+    pub path: String,
+    pub logic_id: String,
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicCopyReport {
+    #[serde(flatten)]
+    pub outcome: BulkEditOutcome,
+    pub source_logic_id: String,
+    /// Fields the mask selected
+    pub fields: Vec<String>,
+    /// Rows the targets resolved to
+    pub target_rows: usize,
+    pub changes: Vec<LogicFieldChange>,
+}
+
+impl BulkEditReport for LogicCopyReport {
+    fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    fn outcome_mut(&mut self) -> &mut BulkEditOutcome {
+        &mut self.outcome
+    }
+}
+
+type RowIndex = (usize, usize, usize);
+
+fn same_direction(a: &LogicConfig, b: &LogicConfig) -> bool {
+    a.allow_buy == b.allow_buy && a.allow_sell == b.allow_sell
+}
+
+/// Rows `address` covers; groups and engines expand to the rows that look `like` the source
+fn resolve_rows(
+    config: &MTConfig,
+    address: &BlockAddress,
+    like: Option<&LogicConfig>,
+) -> Result<Vec<RowIndex>, String> {
+    let engines: Vec<usize> = config
+        .engines
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| {
+            address.engine_id == "*" || e.engine_id.eq_ignore_ascii_case(&address.engine_id)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    if engines.is_empty() {
+        return Err(format!("Config has no engine {}", address.engine_id));
+    }
+
+    let mut rows = Vec::new();
+    let mut group_found = false;
+    for e in engines {
+        let engine = &config.engines[e];
+        for (g, group) in engine.groups.iter().enumerate() {
+            if address
+                .group_number
+                .is_some_and(|n| n != group.group_number)
+            {
+                continue;
+            }
+            group_found = true;
+            match &address.logic {
+                Some(logic) => {
+                    let by_id = group.logics.iter().position(|l| l.logic_id == *logic);
+                    let by_name: Vec<usize> = group
+                        .logics
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, l)| l.logic_name.eq_ignore_ascii_case(logic))
+                        .map(|(i, _)| i)
+                        .collect();
+                    let index = match (by_id, by_name.as_slice()) {
+                        (Some(index), _) => index,
+                        (None, [index]) => *index,
+                        (None, []) => {
+                            return Err(format!(
+                                "Engine {} group {} has no logic {}",
+                                engine.engine_id, group.group_number, logic
+                            ))
+                        }
+                        (None, _) => return Err(format!(
+                            "Engine {} group {} has several {} rows; address one by its logic id",
+                            engine.engine_id, group.group_number, logic
+                        )),
+                    };
+                    rows.push((e, g, index));
+                }
+                None => {
+                    let Some(like) = like else {
+                        return Err("Copy from a single logic row".to_string());
+                    };
+                    rows.extend(
+                        group
+                            .logics
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, l)| {
+                                l.logic_name.eq_ignore_ascii_case(&like.logic_name)
+                                    && same_direction(l, like)
+                            })
+                            .map(|(l, _)| (e, g, l)),
+                    );
+                }
+            }
+        }
+    }
+    if let (Some(group_number), false) = (address.group_number, group_found) {
+        return Err(format!(
+            "Engine {} has no group {}",
+            address.engine_id, group_number
+        ));
+    }
+    Ok(rows)
+}
+
+/// Fields of the row objects selected by `field_mask`; unknown names and identity fields are errors
+fn select_fields(
+    field_mask: &[String],
+    available: &BTreeSet<String>,
+) -> Result<Vec<String>, String> {
+    for mask in field_mask.iter().map(|m| m.trim()) {
+        if IDENTITY_FIELDS.contains(&mask) {
+            return Err(format!("{} identifies the row and can't be copied", mask));
+        }
+        if !available.iter().any(|field| mask_matches(mask, field)) {
+            return Err(format!("Unknown logic field '{}'", mask));
+        }
+    }
+    let fields: Vec<String> = available
+        .iter()
+        .filter(|field| !IDENTITY_FIELDS.contains(&field.as_str()))
+        .filter(|field| {
+            field_mask
+                .iter()
+                .any(|mask| mask_matches(mask.trim(), field))
+        })
+        .cloned()
+        .collect();
+    if fields.is_empty() {
+        return Err("Select at least one field to copy".to_string());
+    }
+    Ok(fields)
+}
+
+/// `config` with the masked fields of the `from` row copied onto every row of `targets`
+pub fn copy_logic_fields(
+    config: &MTConfig,
+    from: &BlockAddress,
+    targets: &[BlockAddress],
+    field_mask: &[String],
+) -> Result<(MTConfig, LogicCopyReport), String> {
+    if from.logic.is_none() || from.group_number.is_none() || from.engine_id == "*" {
+        return Err("Copy from a single logic row".to_string());
+    }
+    let source_row = resolve_rows(config, from, None)?
+        .first()
+        .copied()
+        .ok_or_else(|| "Source logic not found".to_string())?;
+    let source = &config.engines[source_row.0].groups[source_row.1].logics[source_row.2];
+
+    let mut rows: Vec<RowIndex> = Vec::new();
+    for target in targets {
+        for row in resolve_rows(config, target, Some(source))? {
+            if row != source_row && !rows.contains(&row) {
+                rows.push(row);
+            }
+        }
+    }
+    if rows.is_empty() {
+        return Err("No target rows match the source logic".to_string());
+    }
+
+    // Optional _b/_s fields are left out of the JSON when unset, so look at every row
+    let source_fields = row_object(source)?;
+    let mut available: BTreeSet<String> = source_fields.keys().cloned().collect();
+    for &(e, g, l) in &rows {
+        available.extend(row_object(&config.engines[e].groups[g].logics[l])?.into_keys());
+    }
+    let fields = select_fields(field_mask, &available)?;
+
+    let mut patched = config.clone();
+    let mut changes = Vec::new();
+    for &(e, g, l) in &rows {
+        let logic = &mut patched.engines[e].groups[g].logics[l];
+        let mut row = row_object(logic)?;
+        for field in &fields {
+            let after = source_fields.get(field).cloned().unwrap_or(Value::Null);
+            let before = row.get(field).cloned().unwrap_or(Value::Null);
+            if before == after {
+                continue;
+            }
+            changes.push(LogicFieldChange {
+                path: format!("/engines/{}/groups/{}/logics/{}/{}", e, g, l, field),
+                logic_id: logic.logic_id.clone(),
+                field: field.clone(),
+                from: before,
+                to: after.clone(),
+            });
+            row.insert(field.clone(), after);
+        }
+        let copied: LogicConfig = serde_json::from_value(Value::Object(row))
+            .map_err(|err| format!("Copied values don't fit {}: {}", logic.logic_id, err))?;
+        *logic = copied;
+    }
+
+    let report = LogicCopyReport {
+        source_logic_id: source.logic_id.clone(),
+        fields,
+        target_rows: rows.len(),
+        changes,
+        ..LogicCopyReport::default()
+    };
+    Ok((patched, report))
+}
+
+/// Copy `field_mask` fields of the `from` logic row onto every row of `to` in the config
+/// behind `handle` (default: the active one); `dry_run` only reports what would change
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn copy_logic_params(
+    handle: Option<String>,
+    from: BlockAddress,
+    to: Vec<BlockAddress>,
+    field_mask: Vec<String>,
+    dry_run: Option<bool>,
+    state: State<'_, MTBridgeState>,
+) -> Result<LogicCopyReport, String> {
+    apply_bulk_edit(&state, handle, dry_run, |current| {
+        copy_logic_fields(current, &from, &to, &field_mask)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn address(engine: &str, group: Option<u8>, logic: Option<&str>) -> BlockAddress {
+        BlockAddress {
+            engine_id: engine.to_string(),
+            group_number: group,
+            logic: logic.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_copy_trail_settings_to_all_groups() {
+        let mut config = create_full_v19_config();
+        let source = &mut config.engines[0].groups[0].logics[0];
+        source.trail_value = 77.0;
+        source.trail_start = 12.0;
+        source.grid = 999.0;
+
+        let targets: Vec<BlockAddress> = (2..=15).map(|g| address("*", Some(g), None)).collect();
+        let (patched, report) = copy_logic_fields(
+            &config,
+            &address("A", Some(1), Some("A_POWER_B_G1")),
+            &targets,
+            &["trail_*".to_string()],
+        )
+        .unwrap();
+
+        // Buy POWER rows of groups 2-15 in all three engines
+        assert_eq!(report.target_rows, 3 * 14);
+        assert!(report.fields.iter().all(|f| f.starts_with("trail_")));
+        for engine in &patched.engines {
+            for group in engine.groups.iter().filter(|g| g.group_number > 1) {
+                let buy = group
+                    .logics
+                    .iter()
+                    .find(|l| l.logic_id.contains("_POWER_B_"))
+                    .unwrap();
+                let sell = group
+                    .logics
+                    .iter()
+                    .find(|l| l.logic_id.contains("_POWER_S_"))
+                    .unwrap();
+                assert_eq!(buy.trail_value, 77.0);
+                assert_eq!(buy.trail_start, 12.0);
+                assert_ne!(buy.grid, 999.0);
+                assert_ne!(sell.trail_value, 77.0);
+            }
+        }
+        assert!(report
+            .changes
+            .iter()
+            .any(|c| c.path == "/engines/2/groups/14/logics/0/trail_value" && c.to == 77.0));
+        assert!(report.changes.iter().all(|c| c.from != c.to));
+
+        let from = address("A", Some(1), Some("A_POWER_B_G1"));
+        assert!(copy_logic_fields(&config, &from, &targets, &["trail_vlaue".to_string()]).is_err());
+        assert!(copy_logic_fields(&config, &from, &targets, &["logic_id".to_string()]).is_err());
+        assert!(copy_logic_fields(
+            &config,
+            &address("A", Some(1), None),
+            &targets,
+            &["grid".to_string()]
+        )
+        .is_err());
+    }
+}
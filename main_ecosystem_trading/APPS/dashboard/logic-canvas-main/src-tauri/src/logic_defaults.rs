@@ -0,0 +1,184 @@
+// ============================================
+// LOGIC DEFAULTS
+// ============================================
+//
+// Default values of a LogicConfig, one row per logic type. create_default_logic,
+// the v19 seed config, the serde default fns and the flat parser fallbacks all
+// read this table; the setfile key schema documents the same values and a test
+// keeps the two in step.
+
+use daavfx_core::config_enums::{
+    PartialBalance, PartialMode, StrategyType, TradingMode, TrailMethod, TrailStepMethod,
+    TrailStepMode,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LogicDefaults {
+    pub logic_name: &'static str,
+    pub initial_lot: f64,
+    pub multiplier: f64,
+    pub grid: f64,
+    pub trail_method: TrailMethod,
+    pub trail_value: f64,
+    pub trail_start: f64,
+    pub trail_step: f64,
+    pub trail_step_method: TrailStepMethod,
+    /// StartLevel assumed when an imported file has none; Power has no start level
+    pub start_level: Option<i32>,
+    pub last_lot: f64,
+    pub close_targets: &'static str,
+    pub order_count_reference: &'static str,
+    pub reset_lot_on_restart: bool,
+    pub strategy_type: StrategyType,
+    pub trading_mode: TradingMode,
+    pub allow_buy: bool,
+    pub allow_sell: bool,
+    pub reverse_enabled: bool,
+    pub hedge_enabled: bool,
+    pub reverse_scale: f64,
+    pub hedge_scale: f64,
+    pub reverse_reference: &'static str,
+    pub hedge_reference: &'static str,
+    pub trail_step_mode: TrailStepMode,
+    pub trail_step_cycle: i32,
+    pub trail_step_balance: f64,
+    pub close_partial: bool,
+    pub close_partial_cycle: i32,
+    pub close_partial_mode: PartialMode,
+    pub close_partial_balance: PartialBalance,
+    pub close_partial_trail_step_mode: TrailStepMode,
+    pub close_partial_profit_threshold: f64,
+}
+
+/// Values shared by every logic type; rows below only list what differs
+pub(crate) const BASE_LOGIC: LogicDefaults = LogicDefaults {
+    logic_name: "",
+    initial_lot: 0.02,
+    multiplier: 1.2,
+    grid: 300.0,
+    trail_method: TrailMethod::Points,
+    trail_value: 3000.0,
+    trail_start: 1.0,
+    trail_step: 1500.0,
+    trail_step_method: TrailStepMethod::Points,
+    start_level: Some(100),
+    last_lot: 0.12,
+    close_targets: "",
+    order_count_reference: "Logic_None",
+    reset_lot_on_restart: false,
+    strategy_type: StrategyType::Trail,
+    trading_mode: TradingMode::CounterTrend,
+    allow_buy: true,
+    allow_sell: true,
+    reverse_enabled: false,
+    hedge_enabled: false,
+    reverse_scale: 100.0,
+    hedge_scale: 50.0,
+    reverse_reference: "Logic_None",
+    hedge_reference: "Logic_None",
+    trail_step_mode: TrailStepMode::Auto,
+    trail_step_cycle: 1,
+    trail_step_balance: 0.0,
+    close_partial: false,
+    close_partial_cycle: 1,
+    close_partial_mode: PartialMode::Mid,
+    close_partial_balance: PartialBalance::Balanced,
+    close_partial_trail_step_mode: TrailStepMode::Auto,
+    close_partial_profit_threshold: 0.0,
+};
+
+pub(crate) const LOGIC_DEFAULTS: [LogicDefaults; 7] = [
+    LogicDefaults {
+        logic_name: "Power",
+        start_level: None,
+        last_lot: 0.63,
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "Repower",
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "Scalper",
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "Stopper",
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "STO",
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "SCA",
+        ..BASE_LOGIC
+    },
+    LogicDefaults {
+        logic_name: "RPO",
+        ..BASE_LOGIC
+    },
+];
+
+/// Row for `logic_name` (case-insensitive); unknown names get the shared values
+pub(crate) fn logic_defaults(logic_name: &str) -> &'static LogicDefaults {
+    LOGIC_DEFAULTS
+        .iter()
+        .find(|d| d.logic_name.eq_ignore_ascii_case(logic_name))
+        .unwrap_or(&BASE_LOGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setfile_schema::{self as schema, KeySpec};
+
+    fn assert_schema_default(spec: &KeySpec, expected: &str) {
+        assert_eq!(spec.default, expected, "schema default of {}", spec.name);
+    }
+
+    fn schema_f64(spec: &KeySpec) -> f64 {
+        spec.default.parse().unwrap()
+    }
+
+    #[test]
+    fn test_logic_lookup() {
+        assert_eq!(logic_defaults("power").last_lot, 0.63);
+        assert_eq!(logic_defaults("power").start_level, None);
+        assert_eq!(logic_defaults("Scalper").start_level, Some(100));
+        assert_eq!(logic_defaults("Unknown").grid, BASE_LOGIC.grid);
+    }
+
+    #[test]
+    fn test_schema_defaults_match_table() {
+        for row in &LOGIC_DEFAULTS {
+            assert_eq!(schema_f64(&schema::INITIAL_LOT), row.initial_lot);
+            assert_eq!(schema_f64(&schema::MULTIPLIER), row.multiplier);
+            assert_eq!(schema_f64(&schema::GRID), row.grid);
+            assert_eq!(schema_f64(&schema::TRAIL_VALUE), row.trail_value);
+            assert_eq!(schema_f64(&schema::TRAIL_START), row.trail_start);
+            assert_eq!(schema_f64(&schema::TRAIL_STEP), row.trail_step);
+            assert_eq!(
+                schema_f64(&schema::TRAIL_STEP_CYCLE) as i32,
+                row.trail_step_cycle
+            );
+            assert_eq!(
+                schema_f64(&schema::TRAIL_STEP_BALANCE),
+                row.trail_step_balance
+            );
+            assert_schema_default(&schema::TRAIL_METHOD, row.trail_method.as_str());
+            assert_schema_default(&schema::TRAIL_STEP_METHOD, row.trail_step_method.as_str());
+            assert_schema_default(&schema::TRAIL_STEP_MODE, row.trail_step_mode.as_str());
+            assert_schema_default(&schema::STRATEGY_TYPE, row.strategy_type.as_str());
+            assert_schema_default(&schema::TRADING_MODE, row.trading_mode.as_str());
+            assert_eq!(
+                schema::decode_bool(schema::ALLOW_BUY.default),
+                row.allow_buy
+            );
+            assert_eq!(
+                schema::decode_bool(schema::ALLOW_SELL.default),
+                row.allow_sell
+            );
+        }
+    }
+}
@@ -0,0 +1,450 @@
+// MQL Lint Rules - registry of the checks run by the MQL Rust Compiler
+// Each rule has a stable id (== CompilationError.error_type), a default severity,
+// and can be disabled or re-graded per project via .daavfxlint.toml
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::mql_rust_compiler::{
+    parse_input_declarations, CompilationError, ErrorSeverity, MQLInputDeclaration,
+};
+
+pub const LINT_CONFIG_FILE: &str = ".daavfxlint.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintRule {
+    pub id: String,
+    pub description: String,
+    pub default_severity: ErrorSeverity,
+}
+
+/// Rule as currently applied (after .daavfxlint.toml overrides)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintRuleInfo {
+    pub id: String,
+    pub description: String,
+    pub default_severity: ErrorSeverity,
+    pub severity: ErrorSeverity,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintRuleOverride {
+    pub enabled: Option<bool>,
+    pub severity: Option<ErrorSeverity>,
+}
+
+/// Contents of .daavfxlint.toml:
+///
+/// ```toml
+/// [rules.magic_number_literal]
+/// enabled = false
+///
+/// [rules.unchecked_order_send]
+/// severity = "error"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintConfig {
+    pub rules: BTreeMap<String, LintRuleOverride>,
+}
+
+impl LintConfig {
+    /// Load the lint config from a project directory; a missing file means "all defaults"
+    pub fn load(project_dir: &Path) -> Result<Self, String> {
+        let path = project_dir.join(LINT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", LINT_CONFIG_FILE, e))?;
+        Self::parse(&content)
+    }
+
+    /// Parse and validate the contents of a .daavfxlint.toml
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| format!("Invalid {}: {}", LINT_CONFIG_FILE, e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Like `load`, but a broken config is logged and replaced by the defaults so
+    /// the compiler still comes up
+    pub fn load_or_default(project_dir: &Path) -> Self {
+        Self::load(project_dir).unwrap_or_else(|e| {
+            tracing::warn!("{}; using default lint rules", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, project_dir: &Path) -> Result<(), String> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize lint config: {}", e))?;
+        fs::write(project_dir.join(LINT_CONFIG_FILE), content)
+            .map_err(|e| format!("Failed to write {}: {}", LINT_CONFIG_FILE, e))
+    }
+
+    pub fn is_enabled(&self, rule_id: &str) -> bool {
+        self.rules
+            .get(rule_id)
+            .and_then(|o| o.enabled)
+            .unwrap_or(true)
+    }
+
+    pub fn severity_for(&self, rule: &LintRule) -> ErrorSeverity {
+        self.rules
+            .get(&rule.id)
+            .and_then(|o| o.severity.clone())
+            .unwrap_or_else(|| rule.default_severity.clone())
+    }
+
+    /// Drop errors from disabled rules and apply severity overrides.
+    /// Errors whose type isn't a registered rule pass through untouched.
+    pub fn apply(&self, errors: Vec<CompilationError>) -> Vec<CompilationError> {
+        let rules = builtin_rules();
+        errors
+            .into_iter()
+            .filter(|e| self.is_enabled(&e.error_type))
+            .map(|mut e| {
+                if let Some(rule) = rules.iter().find(|r| r.id == e.error_type) {
+                    e.severity = self.severity_for(rule);
+                }
+                e
+            })
+            .collect()
+    }
+
+    /// Unknown rule ids are almost always typos in the toml - reject them instead of ignoring
+    pub fn validate(&self) -> Result<(), String> {
+        let known: HashSet<String> = builtin_rules().into_iter().map(|r| r.id).collect();
+        let unknown: Vec<&String> = self.rules.keys().filter(|k| !known.contains(*k)).collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Unknown lint rule(s) in {}: {:?}",
+                LINT_CONFIG_FILE, unknown
+            ))
+        }
+    }
+}
+
+fn rule(id: &str, description: &str, default_severity: ErrorSeverity) -> LintRule {
+    LintRule {
+        id: id.to_string(),
+        description: description.to_string(),
+        default_severity,
+    }
+}
+
+/// Every rule the compiler knows about, in reporting order
+pub fn builtin_rules() -> Vec<LintRule> {
+    vec![
+        rule(
+            "undeclared_identifier",
+            "gRuntime_G*_TriggerType_* identifiers used without a declaration",
+            ErrorSeverity::Error,
+        ),
+        rule(
+            "duplicate_definition",
+            "Symbol declared more than once across the project",
+            ErrorSeverity::Error,
+        ),
+        rule(
+            "circular_dependency",
+            "#include cycle between project files",
+            ErrorSeverity::Warning,
+        ),
+        rule(
+            "macro_redefinition",
+            "#define shadows a built-in (Ask, Bid, Digits, Bars, Point)",
+            ErrorSeverity::Warning,
+        ),
+        rule(
+            "unused_input",
+            "input/extern declared but never read anywhere in the project (includes resolved)",
+            ErrorSeverity::Warning,
+        ),
+        rule(
+            "magic_number_literal",
+            "Hardcoded magic number instead of the configured input",
+            ErrorSeverity::Warning,
+        ),
+        rule(
+            "unchecked_order_send",
+            "OrderSend result discarded - failed orders go unnoticed",
+            ErrorSeverity::Warning,
+        ),
+        rule(
+            "division_before_normalize",
+            "Division inside NormalizeDouble without a zero guard",
+            ErrorSeverity::Info,
+        ),
+    ]
+}
+
+pub fn list_rules(config: &LintConfig) -> Vec<LintRuleInfo> {
+    builtin_rules()
+        .into_iter()
+        .map(|r| LintRuleInfo {
+            severity: config.severity_for(&r),
+            enabled: config.is_enabled(&r.id),
+            id: r.id,
+            description: r.description,
+            default_severity: r.default_severity,
+        })
+        .collect()
+}
+
+fn lint_error(
+    rule_id: &str,
+    message: String,
+    file: &str,
+    line: usize,
+    column: usize,
+    suggested_fix: Option<String>,
+) -> CompilationError {
+    let severity = builtin_rules()
+        .into_iter()
+        .find(|r| r.id == rule_id)
+        .map(|r| r.default_severity)
+        .unwrap_or(ErrorSeverity::Warning);
+    CompilationError {
+        error_type: rule_id.to_string(),
+        message,
+        file: file.to_string(),
+        line,
+        column,
+        severity,
+        suggested_fix,
+    }
+}
+
+/// Strip // comments so commented-out code doesn't trigger source rules
+fn code_part(line: &str) -> &str {
+    match line.find("//") {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+/// Inputs declared somewhere in `sources` (file, content) but never referenced outside their
+/// own declaration, counting identifier usage across every file so cross-include reads count
+pub fn find_dead_inputs(sources: &[(String, String)]) -> Vec<MQLInputDeclaration> {
+    let ident_regex = Regex::new(r"\b[A-Za-z_]\w*\b").unwrap();
+    let mut usage: HashMap<&str, usize> = HashMap::new();
+    for (_, content) in sources {
+        for line in content.lines() {
+            for m in ident_regex.find_iter(code_part(line)) {
+                *usage.entry(m.as_str()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let declarations: Vec<MQLInputDeclaration> = sources
+        .iter()
+        .flat_map(|(file, content)| parse_input_declarations(content, file))
+        .collect();
+    let mut declared_count: HashMap<&str, usize> = HashMap::new();
+    for input in &declarations {
+        *declared_count.entry(input.name.as_str()).or_insert(0) += 1;
+    }
+
+    declarations
+        .iter()
+        .filter(|input| {
+            let uses = usage.get(input.name.as_str()).copied().unwrap_or(0);
+            uses <= declared_count[input.name.as_str()]
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn dead_input_errors(dead_inputs: &[MQLInputDeclaration]) -> Vec<CompilationError> {
+    dead_inputs
+        .iter()
+        .map(|input| {
+            lint_error(
+                "unused_input",
+                format!("input '{}' is declared but never read", input.name),
+                &input.file,
+                input.line,
+                1,
+                Some(format!(
+                    "// Remove line {}: {} {} - also drop gInput key from exported setfiles",
+                    input.line, input.data_type, input.name
+                )),
+            )
+        })
+        .collect()
+}
+
+pub fn check_magic_number_literals(content: &str, file: &str) -> Vec<CompilationError> {
+    let magic_regex = Regex::new(
+        r"(?:OrderMagicNumber\(\)\s*[!=]=|SetExpertMagicNumber\s*\(|PositionGetInteger\s*\(\s*POSITION_MAGIC\s*\)\s*[!=]=)\s*(\d+)",
+    )
+    .unwrap();
+
+    let mut errors = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        for caps in magic_regex.captures_iter(code_part(line)) {
+            let literal = caps.get(1).unwrap();
+            errors.push(lint_error(
+                "magic_number_literal",
+                format!("magic number literal {} - use the magic number input", literal.as_str()),
+                file,
+                line_num + 1,
+                literal.start() + 1,
+                None,
+            ));
+        }
+    }
+    errors
+}
+
+pub fn check_unchecked_order_send(content: &str, file: &str) -> Vec<CompilationError> {
+    // A statement that *starts* with OrderSend( throws its ticket/bool result away
+    let call_regex = Regex::new(r"^\s*OrderSend\s*\(").unwrap();
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| call_regex.is_match(code_part(line)))
+        .map(|(line_num, line)| {
+            lint_error(
+                "unchecked_order_send",
+                "OrderSend result is not checked".to_string(),
+                file,
+                line_num + 1,
+                line.find("OrderSend").unwrap_or(0) + 1,
+                Some(
+                    "int ticket = OrderSend(...);\nif (ticket < 0) Print(\"OrderSend failed: \", GetLastError());"
+                        .to_string(),
+                ),
+            )
+        })
+        .collect()
+}
+
+pub fn check_division_before_normalize(content: &str, file: &str) -> Vec<CompilationError> {
+    let normalize_regex = Regex::new(r"NormalizeDouble\s*\(([^,;]*)/([^,;]*),").unwrap();
+
+    let mut errors = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        let code = code_part(line);
+        if let Some(m) = normalize_regex.find(code) {
+            errors.push(lint_error(
+                "division_before_normalize",
+                "division inside NormalizeDouble - divisor may be zero".to_string(),
+                file,
+                line_num + 1,
+                m.start() + 1,
+                Some("Guard the divisor (e.g. if (divisor != 0)) before normalizing".to_string()),
+            ));
+        }
+    }
+    errors
+}
+
+/// Run all source-level rules over one file
+pub fn run_source_rules(content: &str, file: &str) -> Vec<CompilationError> {
+    let mut errors = Vec::new();
+    errors.extend(check_magic_number_literals(content, file));
+    errors.extend(check_unchecked_order_send(content, file));
+    errors.extend(check_division_before_normalize(content, file));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "input int gInput_Used = 1;\n\
+                          input int gInput_Orphan = 2;\n\
+                          void OnTick() {\n\
+                          \x20  if (OrderMagicNumber() == 12345) return;\n\
+                          \x20  OrderSend(Symbol(), OP_BUY, gInput_Used, Ask, 3, 0, 0);\n\
+                          \x20  double lot = NormalizeDouble(balance / risk, 2);\n\
+                          }\n";
+
+    #[test]
+    fn test_source_rules_flag_each_pattern() {
+        let errors = run_source_rules(SAMPLE, "sample.mq4");
+        let types: Vec<&str> = errors.iter().map(|e| e.error_type.as_str()).collect();
+
+        assert!(types.contains(&"magic_number_literal"));
+        assert!(types.contains(&"unchecked_order_send"));
+        assert!(types.contains(&"division_before_normalize"));
+    }
+
+    #[test]
+    fn test_dead_inputs_resolved_across_files() {
+        let sources = vec![
+            ("main.mq4".to_string(), SAMPLE.to_string()),
+            (
+                "Include/Risk.mqh".to_string(),
+                "extern double gInput_RiskOnly = 1.0;\n\
+                 double Risk() { return gInput_Orphan * 2; }\n"
+                    .to_string(),
+            ),
+        ];
+
+        let dead: Vec<String> = find_dead_inputs(&sources)
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        assert_eq!(dead, vec!["gInput_RiskOnly".to_string()]);
+
+        let errors = dead_input_errors(&find_dead_inputs(&sources));
+        assert_eq!(errors[0].file, "Include/Risk.mqh");
+        assert!(errors[0].suggested_fix.as_ref().unwrap().contains("line 1"));
+    }
+
+    #[test]
+    fn test_lint_config_disables_and_regrades() {
+        let config: LintConfig = toml::from_str(
+            "[rules.magic_number_literal]\nenabled = false\n\n[rules.unchecked_order_send]\nseverity = \"error\"\n",
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let errors = config.apply(run_source_rules(SAMPLE, "sample.mq4"));
+        assert!(!errors.iter().any(|e| e.error_type == "magic_number_literal"));
+        let order_send = errors
+            .iter()
+            .find(|e| e.error_type == "unchecked_order_send")
+            .unwrap();
+        assert!(matches!(order_send.severity, ErrorSeverity::Error));
+    }
+
+    #[test]
+    fn test_lint_config_rejects_unknown_rules() {
+        let config: LintConfig = toml::from_str("[rules.no_such_rule]\nenabled = false\n").unwrap();
+        assert!(config.validate().is_err());
+        assert!(LintConfig::parse("[rules.no_such_rule]\nenabled = false\n").is_err());
+    }
+
+    #[test]
+    fn test_broken_lint_config_falls_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!("daavfx_lint_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(LINT_CONFIG_FILE);
+        fs::write(&path, "[rules.magic_numbr_literal]\nenabled = false\n").unwrap();
+        assert!(LintConfig::load(&dir).is_err());
+        assert!(LintConfig::load_or_default(&dir).rules.is_empty());
+
+        fs::write(&path, "[rules.magic_number_literal\n").unwrap();
+        assert!(LintConfig::load_or_default(&dir).rules.is_empty());
+
+        fs::write(&path, "[rules.magic_number_literal]\nenabled = false\n").unwrap();
+        assert!(!LintConfig::load_or_default(&dir).is_enabled("magic_number_literal"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
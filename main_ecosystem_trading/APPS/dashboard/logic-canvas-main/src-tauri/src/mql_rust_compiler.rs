@@ -10,6 +10,9 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::mql_lint::{self, LintConfig};
+use crate::TaskHandle;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MQLProject {
     pub root_path: PathBuf,
@@ -37,6 +40,16 @@ pub enum SymbolType {
     Include,
 }
 
+/// An `input`/`sinput`/`extern` declaration - i.e. a key the EA accepts from a .set file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MQLInputDeclaration {
+    pub name: String,
+    pub data_type: String,
+    pub default_value: Option<String>,
+    pub file: String,
+    pub line: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompilationError {
     pub error_type: String,
@@ -50,8 +63,11 @@ pub struct CompilationError {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ErrorSeverity {
+    #[serde(alias = "error")]
     Error,
+    #[serde(alias = "warning")]
     Warning,
+    #[serde(alias = "info")]
     Info,
 }
 
@@ -66,6 +82,9 @@ pub struct ValidationReport {
     pub error_by_file: HashMap<String, usize>,
     pub errors: Vec<CompilationError>,
     pub suggestions: Vec<String>,
+    /// Inputs declared but never read once includes are resolved (empty if unused_input is disabled)
+    #[serde(default)]
+    pub dead_inputs: Vec<MQLInputDeclaration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +123,8 @@ pub struct MQLRustCompiler {
     error_patterns: Vec<ErrorPattern>,
     last_validation: Arc<Mutex<Option<SystemTime>>>,
     validation_cache: Arc<Mutex<HashMap<String, Vec<CompilationError>>>>,
+    lint_config: LintConfig,
+    dead_inputs: Vec<MQLInputDeclaration>,
 }
 
 #[derive(Debug, Clone)]
@@ -123,9 +144,12 @@ impl MQLRustCompiler {
             error_patterns: Vec::new(),
             last_validation: Arc::new(Mutex::new(None)),
             validation_cache: Arc::new(Mutex::new(HashMap::new())),
+            lint_config: LintConfig::default(),
+            dead_inputs: Vec::new(),
         };
 
         compiler.initialize_error_patterns();
+        compiler.lint_config = LintConfig::load_or_default(&compiler.lint_config_dir());
         Ok(compiler)
     }
 
@@ -172,9 +196,12 @@ impl MQLRustCompiler {
             error_patterns: Vec::new(),
             last_validation: Arc::new(Mutex::new(None)),
             validation_cache: Arc::new(Mutex::new(HashMap::new())),
+            lint_config: LintConfig::default(),
+            dead_inputs: Vec::new(),
         };
 
         compiler.initialize_error_patterns();
+        compiler.lint_config = LintConfig::load_or_default(&compiler.lint_config_dir());
         Ok(compiler)
     }
 
@@ -250,7 +277,89 @@ impl MQLRustCompiler {
         errors.extend(self.detect_duplicate_definitions()?);
         errors.extend(self.detect_circular_dependencies()?);
         errors.extend(self.detect_macro_conflicts()?);
+        errors.extend(self.detect_source_lint_issues()?);
+        errors.extend(self.detect_dead_inputs()?);
+
+        Ok(self.lint_config.apply(errors))
+    }
+
+    /// Directory holding .daavfxlint.toml - next to the EA sources
+    pub fn lint_config_dir(&self) -> PathBuf {
+        self.project
+            .main_files
+            .first()
+            .and_then(|f| f.parent())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| self.project.root_path.clone())
+    }
+
+    pub fn lint_config(&self) -> &LintConfig {
+        &self.lint_config
+    }
+
+    /// Replace the active lint config; cached results were produced under the old rules
+    pub fn set_lint_config(&mut self, config: LintConfig) -> Result<(), String> {
+        config.validate()?;
+        self.lint_config = config;
+        *self.last_validation.lock().unwrap() = None;
+        Ok(())
+    }
+
+    /// Main files plus every file they #include (transitively), read once
+    fn collect_project_sources(&self) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let include_regex = Regex::new(r#"#include\s*["<]([^">]+)[">]"#)?;
+        let mut sources = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending: Vec<PathBuf> = self.project.main_files.clone();
+
+        while let Some(file) = pending.pop() {
+            let key = file.canonicalize().unwrap_or_else(|_| file.clone());
+            if !visited.insert(key) {
+                continue;
+            }
+            let content = match fs::read_to_string(&file) {
+                Ok(c) => c,
+                Err(_) => continue, // unresolved or unreadable include - nothing to index
+            };
+
+            for caps in include_regex.captures_iter(&content) {
+                if let Some(resolved) = self.resolve_include(&file, caps.get(1).unwrap().as_str()) {
+                    pending.push(resolved);
+                }
+            }
+            sources.push((file.to_string_lossy().to_string(), content));
+        }
+
+        Ok(sources)
+    }
+
+    /// Resolve an #include the way MetaEditor does: next to the including file, then Include dirs
+    fn resolve_include(&self, from: &Path, include: &str) -> Option<PathBuf> {
+        let include = include.replace('\\', "/");
+        let local = from.parent().map(|dir| dir.join(&include));
+        local
+            .into_iter()
+            .chain(self.project.include_paths.iter().map(|dir| dir.join(&include)))
+            .find(|candidate| candidate.is_file())
+    }
+
+    fn detect_dead_inputs(&mut self) -> Result<Vec<CompilationError>, Box<dyn std::error::Error>> {
+        let sources = self.collect_project_sources()?;
+        self.dead_inputs = mql_lint::find_dead_inputs(&sources);
+        Ok(mql_lint::dead_input_errors(&self.dead_inputs))
+    }
 
+    fn detect_source_lint_issues(
+        &self,
+    ) -> Result<Vec<CompilationError>, Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        for main_file in &self.project.main_files {
+            let content = fs::read_to_string(main_file)?;
+            errors.extend(mql_lint::run_source_rules(
+                &content,
+                &main_file.to_string_lossy(),
+            ));
+        }
         Ok(errors)
     }
 
@@ -334,6 +443,44 @@ impl MQLRustCompiler {
         Ok(())
     }
 
+    /// Collect every input/extern declaration from the main files and include tree
+    pub fn collect_input_declarations(
+        &self,
+    ) -> Result<Vec<MQLInputDeclaration>, Box<dyn std::error::Error>> {
+        let mut inputs = Vec::new();
+
+        for main_file in &self.project.main_files {
+            let content = fs::read_to_string(main_file)?;
+            inputs.extend(parse_input_declarations(
+                &content,
+                &main_file.to_string_lossy(),
+            ));
+        }
+
+        for include_path in &self.project.include_paths {
+            Self::collect_include_inputs(include_path, &mut inputs)?;
+        }
+
+        Ok(inputs)
+    }
+
+    fn collect_include_inputs(
+        include_path: &Path,
+        inputs: &mut Vec<MQLInputDeclaration>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for entry in fs::read_dir(include_path)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                Self::collect_include_inputs(&path, inputs)?;
+            } else if path.extension().map_or(false, |ext| ext == "mqh") {
+                let content = fs::read_to_string(&path)?;
+                inputs.extend(parse_input_declarations(&content, &path.to_string_lossy()));
+            }
+        }
+        Ok(())
+    }
+
     fn parse_includes(&mut self, include_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         for entry in fs::read_dir(include_path)? {
             let entry = entry?;
@@ -649,6 +796,11 @@ impl MQLRustCompiler {
             error_by_file,
             errors,
             suggestions,
+            dead_inputs: if self.lint_config.is_enabled("unused_input") {
+                self.dead_inputs.clone()
+            } else {
+                Vec::new()
+            },
         };
 
         Ok(report)
@@ -691,6 +843,17 @@ impl MQLRustCompiler {
             ));
         }
 
+        let dead_inputs = errors
+            .iter()
+            .filter(|e| e.error_type == "unused_input")
+            .count();
+        if dead_inputs > 0 {
+            suggestions.push(format!(
+                "{} inputs are never read. Removing them shrinks the v19 setfiles and the input list in MT.",
+                dead_inputs
+            ));
+        }
+
         if suggestions.is_empty() {
             suggestions.push(
                 "Code analysis complete. Consider running full compilation test.".to_string(),
@@ -704,23 +867,45 @@ impl MQLRustCompiler {
     pub fn run_precompilation_pipeline(
         &mut self,
     ) -> Result<PrecompilationResult, Box<dyn std::error::Error>> {
+        self.run_precompilation_pipeline_with_task(None)
+    }
+
+    /// Pre-compilation pipeline that reports each phase to `task` and stops between
+    /// phases once the task is cancelled
+    pub fn run_precompilation_pipeline_with_task(
+        &mut self,
+        task: Option<&TaskHandle>,
+    ) -> Result<PrecompilationResult, Box<dyn std::error::Error>> {
+        let checkpoint = |phase: &str, index: usize| -> Result<(), String> {
+            if let Some(task) = task {
+                task.check()?;
+                task.report(phase, index, 4);
+            }
+            Ok(())
+        };
+
         println!("🦀 Running MQL Pre-compilation Pipeline");
 
         // Phase 1: Syntax and structure validation
         println!("📊 Phase 1: Syntax validation...");
+        checkpoint("validation", 0)?;
         let validation_report = self.analyze_with_context()?;
 
         // Phase 2: Dependency analysis
         println!("🔗 Phase 2: Dependency analysis...");
+        checkpoint("dependencies", 1)?;
         let dependency_issues = self.analyze_dependencies_advanced()?;
 
         // Phase 3: Performance analysis
         println!("⚡ Phase 3: Performance analysis...");
+        checkpoint("performance", 2)?;
         let performance_warnings = self.analyze_performance_patterns()?;
 
         // Phase 4: Generate fixes
         println!("🔧 Phase 4: Generating fixes...");
+        checkpoint("fixes", 3)?;
         let auto_fixes = self.generate_fixes(&validation_report.errors)?;
+        checkpoint("done", 4)?;
 
         let result = PrecompilationResult {
             validation_report,
@@ -823,3 +1008,26 @@ impl MQLRustCompiler {
         ]
     }
 }
+
+/// Extract input/sinput/extern declarations from MQL source
+pub fn parse_input_declarations(content: &str, file: &str) -> Vec<MQLInputDeclaration> {
+    let input_regex = Regex::new(
+        r"^\s*(?:sinput|input|extern)\s+(?:const\s+)?(?:unsigned\s+)?(\w+)\s+(\w+)\s*(?:=\s*([^;]*))?;",
+    )
+    .unwrap();
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(line_num, line)| {
+            let caps = input_regex.captures(line)?;
+            Some(MQLInputDeclaration {
+                name: caps.get(2).unwrap().as_str().to_string(),
+                data_type: caps.get(1).unwrap().as_str().to_string(),
+                default_value: caps.get(3).map(|m| m.as_str().trim().to_string()),
+                file: file.to_string(),
+                line: line_num + 1,
+            })
+        })
+        .collect()
+}
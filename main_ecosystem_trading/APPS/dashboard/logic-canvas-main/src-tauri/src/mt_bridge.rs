@@ -3,21 +3,67 @@
 
 use notify::{Event, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::{Read, Seek, SeekFrom};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{Mutex as AsyncMutex, RwLock};
 #[cfg(feature = "tauri-app")]
 use tauri::{Emitter, State};
 
+use daavfx_core::config_enums::{
+    canonical_opt, ConfigEnum, PartialBalance, PartialMode, StrategyType, TradingMode, TrailMethod,
+    TrailStepMethod, TrailStepMode, TriggerMode, TriggerType,
+};
+pub use daavfx_core::config::{
+    GeneralConfig, NewsFilterConfig, RiskManagementConfig, SessionConfig, TimeFiltersConfig,
+    TimePrioritySettings,
+};
+pub(crate) use daavfx_core::fs::atomic_write;
+pub(crate) use daavfx_core::obfuscation::{deobfuscate_string, obfuscate_string};
+use daavfx_core::fs::decode_setfile_bytes;
+use daavfx_core::setfile_keys::{get_logic_code, get_logic_short, get_logic_suffix};
+
+use crate::active_backup::{self, ACTIVE_SET_BACKUPS};
+use crate::annotations;
+use crate::chat_macros::ChatMacro;
+use crate::cloud_sync::{sync_aware_write, sync_warning};
+use crate::config_handles::OpenConfigs;
+use crate::constraints::load_constraints;
+use crate::export_debounce;
+use crate::field_locks::{load_field_locks, FieldLocks};
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::{enforce_field_locks, LockViolation};
+
 // Import the MQL Rust Compiler
+use crate::import_log::{self, ImportLog};
+use crate::logic_defaults::{logic_defaults, BASE_LOGIC};
+use crate::mql_lint::{self, LintConfig, LintRuleInfo};
+use crate::naming;
+use crate::os_paths;
+use crate::setfile_format;
+use crate::setfile_schema::{
+    decode_bool, ALLOW_BUY, ALLOW_SELL, GRID, INITIAL_LOT, MULTIPLIER, STRATEGY_TYPE, TRADING_MODE,
+    TRAIL_METHOD, TRAIL_START, TRAIL_STEP, TRAIL_STEP_BALANCE, TRAIL_STEP_CYCLE,
+    TRAIL_STEP_METHOD, TRAIL_STEP_MODE, TRAIL_VALUE,
+};
+use crate::setfile_writers::{
+    CleanMathWriter, EngineWriter, GeneralWriter, LogicWriteOptions, NewsFilterWriter,
+    RiskWriter, SectionWriter, SetfileLayout, Side, TimeFilterWriter, trigger_action_code,
+};
+use crate::terminal_discovery;
+use crate::vault_git;
 use crate::mql_rust_compiler::{
-    CompilationError, MQLRustCompiler, PrecompilationResult, ValidationReport,
+    CompilationError, MQLInputDeclaration, MQLRustCompiler, PrecompilationResult,
+    ValidationReport,
+};
+use crate::redaction::{
+    apply_redaction, redact_comments, ExportAudience, ExportRedaction, RedactionPolicy,
 };
 
 // Path validation and sanitization utilities
-fn sanitize_and_validate_path(path: &PathBuf) -> Result<PathBuf, String> {
+pub(crate) fn sanitize_and_validate_path(path: &PathBuf) -> Result<PathBuf, String> {
     // 1. Resolve to absolute path
     let absolute_path = if path.is_absolute() {
         path.clone()
@@ -122,322 +168,14 @@ fn validate_path_within_base(path: &PathBuf, base_dir: &PathBuf) -> Result<PathB
     }
 }
 
-// Atomic write helper to prevent file corruption
-fn atomic_write(path: &PathBuf, content: &str) -> Result<(), String> {
-    // Create a temporary file in the same directory
-    let tmp_extension = format!(
-        "{}.tmp",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos()
-    );
-    let tmp_path = if let Some(ext) = path.extension() {
-        path.with_extension(format!("{}.{}", ext.to_string_lossy(), tmp_extension))
-    } else {
-        path.with_extension(tmp_extension)
-    };
-
-    // Write to the temporary file
-    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write temporary file: {}", e))?;
-
-    // Rename temporary file to target file (atomic operation)
-    fs::rename(&tmp_path, path).map_err(|e| {
-        // Cleanup temp file if rename fails
-        let _ = fs::remove_file(&tmp_path);
-        format!("Failed to commit file (rename failed): {}", e)
-    })?;
-
-    Ok(())
-}
-
 // ============================================
-// ENCRYPTION / OBFUSCATION UTILITIES
+// CONFIG MODEL
 // ============================================
 
-const OBFUSCATION_KEY: &str = "DAAVFX_SECURE_STORAGE_KEY_2024";
-
-fn obfuscate_string(input: &str) -> String {
-    if input.is_empty() {
-        return String::new();
-    }
-    // Check if already obfuscated to prevent double encryption
-    if input.starts_with("ENC:") {
-        return input.to_string();
-    }
-
-    // Simple XOR + Hex
-    let mut output = String::from("ENC:");
-    let key_bytes = OBFUSCATION_KEY.as_bytes();
-    for (i, b) in input.bytes().enumerate() {
-        let key_byte = key_bytes[i % key_bytes.len()];
-        let xored = b ^ key_byte;
-        output.push_str(&format!("{:02x}", xored));
-    }
-    output
-}
-
-fn deobfuscate_string(input: &str) -> String {
-    if !input.starts_with("ENC:") {
-        return input.to_string();
-    }
-    let hex_part = &input[4..];
-    let key_bytes = OBFUSCATION_KEY.as_bytes();
-    let mut decoded = Vec::new();
-
-    // Parse hex
-    let mut chars = hex_part.chars();
-    let mut idx = 0;
-    while let (Some(h1), Some(h2)) = (chars.next(), chars.next()) {
-        if let Ok(byte) = u8::from_str_radix(&format!("{}{}", h1, h2), 16) {
-            let key_byte = key_bytes[idx % key_bytes.len()];
-            decoded.push(byte ^ key_byte);
-            idx += 1;
-        } else {
-            return input.to_string(); // Fail safe
-        }
-    }
-
-    String::from_utf8(decoded).unwrap_or_else(|_| input.to_string())
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct MTConfig {
-    pub version: String,
-    pub platform: String, // "MT4" or "MT5"
-    pub timestamp: String,
-    pub total_inputs: usize,
-    #[serde(default)]
-    pub last_saved_at: Option<String>,
-    #[serde(default)]
-    pub last_saved_platform: Option<String>,
-    #[serde(default)]
-    pub current_set_name: Option<String>,
-    #[serde(default)]
-    pub tags: Option<Vec<String>>,
-    #[serde(default)]
-    pub comments: Option<String>,
-    pub general: GeneralConfig,
-    pub engines: Vec<EngineConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct GeneralConfig {
-    // License
-    pub license_key: String,
-    pub license_server_url: String,
-    pub require_license: bool,
-    pub license_check_interval: i32,
-
-    // Config
-    pub config_file_name: String,
-    pub config_file_is_common: bool,
-
-    // Trading (GLOBAL)
-    pub allow_buy: bool,
-    pub allow_sell: bool,
-
-    // Logging
-    pub enable_logs: bool,
-
-    #[serde(default)]
-    pub grid_unit: Option<i32>,
-    #[serde(default)]
-    pub pip_factor: Option<i32>,
-
-    // Compounding
-    pub compounding_enabled: bool,
-    pub compounding_type: String,
-    pub compounding_target: f64,
-    pub compounding_increase: f64,
-
-    // Restart Policy
-    pub restart_policy_power: String,
-    pub restart_policy_non_power: String,
-    pub close_non_power_on_power_close: bool,
-    pub hold_timeout_bars: i32,
-
-    // Global System
-    pub magic_number: i32,
-    pub magic_number_buy: i32,
-    pub magic_number_sell: i32,
-    pub max_slippage_points: f64,
-    #[serde(default)]
-    pub reverse_magic_base: i32,
-    #[serde(default)]
-    pub hedge_magic_base: i32,
-    #[serde(default)]
-    pub hedge_magic_independent: bool,
-
-    // Risk Management
-    pub risk_management: RiskManagementConfig,
-    #[serde(default)]
-    pub risk_management_b: Option<RiskManagementConfig>,
-    #[serde(default)]
-    pub risk_management_s: Option<RiskManagementConfig>,
-
-    // Time Filters
-    pub time_filters: TimeFiltersConfig,
-    #[serde(default)]
-    pub time_filters_b: Option<TimeFiltersConfig>,
-    #[serde(default)]
-    pub time_filters_s: Option<TimeFiltersConfig>,
-
-    // News Filter
-    pub news_filter: NewsFilterConfig,
-    #[serde(default)]
-    pub news_filter_b: Option<NewsFilterConfig>,
-    #[serde(default)]
-    pub news_filter_s: Option<NewsFilterConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct RiskManagementConfig {
-    #[serde(default)]
-    pub enabled: bool,
-    pub spread_filter_enabled: bool,
-    pub max_spread_points: f64,
-    pub equity_stop_enabled: bool,
-    pub equity_stop_value: f64,
-    pub drawdown_stop_enabled: bool,
-    pub max_drawdown_percent: f64,
-    #[serde(default)]
-    pub risk_action: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct TimeFiltersConfig {
-    #[serde(default)]
-    pub enabled: bool,
-    #[serde(default)]
-    pub priority_settings: TimePrioritySettings,
-    pub sessions: Vec<SessionConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct TimePrioritySettings {
-    pub news_filter_overrides_session: bool,
-    pub session_filter_overrides_news: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct SessionConfig {
-    #[serde(default)]
-    pub session_number: i32,
-    pub enabled: bool,
-    pub day: i32,
-    pub start_hour: i32,
-    pub start_minute: i32,
-    pub end_hour: i32,
-    pub end_minute: i32,
-    #[serde(default)]
-    pub action: String,
-    #[serde(default)]
-    pub auto_restart: bool,
-    #[serde(default)]
-    pub restart_mode: String,
-    #[serde(default)]
-    pub restart_bars: i32,
-    #[serde(default)]
-    pub restart_minutes: i32,
-    #[serde(default)]
-    pub restart_pips: i32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct NewsFilterConfig {
-    pub enabled: bool,
-    pub api_key: String,
-    pub api_url: String,
-    pub countries: String,
-    pub impact_level: i32,
-    pub minutes_before: i32,
-    pub minutes_after: i32,
-    #[serde(default = "default_true")]
-    pub stop_ea: bool,
-    #[serde(default)]
-    pub close_trades: bool,
-    #[serde(default = "default_true")]
-    pub auto_restart: bool,
-    #[serde(default)]
-    pub check_interval: i32,
-    #[serde(default)]
-    pub alert_minutes: i32,
-    #[serde(default = "default_true")]
-    pub filter_high_only: bool,
-    #[serde(default)]
-    pub filter_weekends: bool,
-    #[serde(default = "default_true")]
-    pub use_local_cache: bool,
-    #[serde(default = "default_3600")]
-    pub cache_duration: i32,
-    #[serde(default)]
-    pub fallback_on_error: String,
-    #[serde(default)]
-    pub filter_currencies: String,
-    #[serde(default = "default_true")]
-    pub include_speeches: bool,
-    #[serde(default = "default_true")]
-    pub include_reports: bool,
-    #[serde(default = "default_true")]
-    pub visual_indicator: bool,
-    #[serde(default)]
-    pub alert_before_news: bool,
-    #[serde(default)]
-    pub calendar_file: Option<String>,
-}
-
-fn default_3600() -> i32 { 3600 }
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EngineConfig {
-    pub engine_id: String, // "A", "B", "C"
-    pub engine_name: String,
-    pub max_power_orders: i32,
-    pub groups: Vec<GroupConfig>,
-}
-
-impl MTConfig {
-    pub fn obfuscate_sensitive_fields(&mut self) {
-        self.general.license_key = obfuscate_string(&self.general.license_key);
-        self.general.license_server_url = obfuscate_string(&self.general.license_server_url);
-        self.general.news_filter.api_key = obfuscate_string(&self.general.news_filter.api_key);
-        self.general.news_filter.api_url = obfuscate_string(&self.general.news_filter.api_url);
-    }
-
-    pub fn deobfuscate_sensitive_fields(&mut self) {
-        self.general.license_key = deobfuscate_string(&self.general.license_key);
-        self.general.license_server_url = deobfuscate_string(&self.general.license_server_url);
-        self.general.news_filter.api_key = deobfuscate_string(&self.general.news_filter.api_key);
-        self.general.news_filter.api_url = deobfuscate_string(&self.general.news_filter.api_url);
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GroupConfig {
-    pub group_number: u8, // 1-20 (all groups supported)
-    pub enabled: bool,
-
-    // ===== GROUP TRIGGER (Groups 2-20 only) =====
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub group_power_start: Option<i32>, // DEPRECATED: Use group_power_start_b and group_power_start_s
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub group_power_start_b: Option<i32>, // gInput_GroupPowerStart_{P|BP|CP}{N}_Buy - Buy side threshold
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub group_power_start_s: Option<i32>, // gInput_GroupPowerStart_{P|BP|CP}{N}_Sell - Sell side threshold
-
-    // ===== GROUP-LEVEL REVERSE/HEDGE CONTROLS (V17.04+) =====
-    #[serde(default)]
-    pub reverse_mode: bool, // gInput_Group{N}_ReverseMode
-    #[serde(default)]
-    pub hedge_mode: bool, // gInput_Group{N}_HedgeMode
-    #[serde(default = "default_logic_none")]
-    pub hedge_reference: String, // gInput_Group{N}_HedgeReference
-    #[serde(default)]
-    pub entry_delay_bars: i32, // gInput_Group{N}_EntryDelayBars
-
-    pub logics: Vec<LogicConfig>,
-}
+/// The shared config model over this app's logic rows
+pub type MTConfig = daavfx_core::config::MTConfig<LogicConfig>;
+pub type EngineConfig = daavfx_core::config::EngineConfig<LogicConfig>;
+pub type GroupConfig = daavfx_core::config::GroupConfig<LogicConfig>;
 
 fn default_true() -> bool {
     true
@@ -445,23 +183,23 @@ fn default_true() -> bool {
 fn default_logic_none() -> String {
     "Logic_None".to_string()
 }
-fn default_trail_step_mode() -> String {
-    "TrailStepMode_Auto".to_string()
+fn default_trail_step_mode() -> TrailStepMode {
+    BASE_LOGIC.trail_step_mode
 }
-fn default_strategy_trail() -> String {
-    "Trail".to_string()
+fn default_strategy_trail() -> StrategyType {
+    BASE_LOGIC.strategy_type
 }
-fn default_mode_counter_trend() -> String {
-    "Counter Trend".to_string()
+fn default_mode_counter_trend() -> Option<TradingMode> {
+    Some(BASE_LOGIC.trading_mode)
 }
 fn default_close_partial_cycle() -> i32 {
-    1
+    BASE_LOGIC.close_partial_cycle
 }
-fn default_partial_mode() -> String {
-    "PartialMode_Mid".to_string()
+fn default_partial_mode() -> PartialMode {
+    BASE_LOGIC.close_partial_mode
 }
-fn default_partial_balance() -> String {
-    "PartialBalance_Balanced".to_string()
+fn default_partial_balance() -> PartialBalance {
+    BASE_LOGIC.close_partial_balance
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -487,7 +225,7 @@ pub struct LogicConfig {
     pub grid_b: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub grid_s: Option<f64>,
-    pub trail_method: String,
+    pub trail_method: TrailMethod,
     pub trail_value: f64,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_value_b: Option<f64>,
@@ -503,7 +241,7 @@ pub struct LogicConfig {
     pub trail_step_b: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_s: Option<f64>,
-    pub trail_step_method: String,
+    pub trail_step_method: TrailStepMethod,
 
     // ===== LOGIC-SPECIFIC (5 fields + Buy/Sell variants) =====
     #[serde(alias = "startLevel", skip_serializing_if = "Option::is_none")]
@@ -542,17 +280,23 @@ pub struct LogicConfig {
 
     // ===== MODE SELECTORS (Dashboard Only / Mapped) =====
     #[serde(default = "default_strategy_trail")]
-    pub strategy_type: String,
+    pub strategy_type: StrategyType,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub strategy_type_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, StrategyType>")]
+    pub strategy_type_b: Option<StrategyType>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub strategy_type_s: Option<String>,
-    #[serde(default = "default_mode_counter_trend")]
-    pub trading_mode: String,
+    #[serde(deserialize_with = "canonical_opt::<_, StrategyType>")]
+    pub strategy_type_s: Option<StrategyType>,
+    /// None (a blank value) lets the hedge/reverse flags decide the mode on import
+    #[serde(default = "default_mode_counter_trend", skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "canonical_opt::<_, TradingMode>")]
+    pub trading_mode: Option<TradingMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trading_mode_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TradingMode>")]
+    pub trading_mode_b: Option<TradingMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trading_mode_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TradingMode>")]
+    pub trading_mode_s: Option<TradingMode>,
     #[serde(default = "default_true")]
     pub allow_buy: bool, // gInput_AllowBuy_{suffix}
     #[serde(default = "default_true")]
@@ -598,11 +342,13 @@ pub struct LogicConfig {
 
     // ===== TRAIL STEP ADVANCED (3 fields + Buy/Sell variants) =====
     #[serde(default = "default_trail_step_mode")]
-    pub trail_step_mode: String, // gInput_TrailStepMode_{suffix}
+    pub trail_step_mode: TrailStepMode, // gInput_TrailStepMode_{suffix}
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_s: Option<TrailStepMode>,
     #[serde(default = "default_one")]
     pub trail_step_cycle: i32, // gInput_TrailStepCycle_{suffix} (1=always)
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -624,11 +370,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_2_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_2: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_2: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_2_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_2_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_2_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_2_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_2: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -642,11 +391,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_2_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_2: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_2: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_2_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_2_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_2_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_2_s: Option<TrailStepMode>,
 
     #[serde(default)]
     pub trail_step_3: Option<f64>,
@@ -655,11 +407,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_3_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_3: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_3: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_3_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_3_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_3_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_3_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_3: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -673,11 +428,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_3_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_3: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_3: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_3_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_3_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_3_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_3_s: Option<TrailStepMode>,
 
     #[serde(default)]
     pub trail_step_4: Option<f64>,
@@ -686,11 +444,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_4_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_4: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_4: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_4_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_4_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_4_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_4_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_4: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -704,11 +465,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_4_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_4: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_4: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_4_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_4_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_4_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_4_s: Option<TrailStepMode>,
 
     #[serde(default)]
     pub trail_step_5: Option<f64>,
@@ -717,11 +481,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_5_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_5: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_5: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_5_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_5_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_5_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_5_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_5: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -735,11 +502,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_5_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_5: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_5: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_5_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_5_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_5_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_5_s: Option<TrailStepMode>,
 
     #[serde(default)]
     pub trail_step_6: Option<f64>,
@@ -748,11 +518,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_6_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_6: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_6: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_6_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_6_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_6_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_6_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_6: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -766,11 +539,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_6_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_6: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_6: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_6_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_6_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_6_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_6_s: Option<TrailStepMode>,
 
     #[serde(default)]
     pub trail_step_7: Option<f64>,
@@ -779,11 +555,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_7_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_method_7: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_7: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_7_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_7_b: Option<TrailStepMethod>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_method_7_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMethod>")]
+    pub trail_step_method_7_s: Option<TrailStepMethod>,
     #[serde(default)]
     pub trail_step_cycle_7: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -797,11 +576,14 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trail_step_balance_7_s: Option<f64>,
     #[serde(default)]
-    pub trail_step_mode_7: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_7: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_7_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_7_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trail_step_mode_7_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub trail_step_mode_7_s: Option<TrailStepMode>,
 
     // ===== CLOSE PARTIAL (active contract + legacy compatibility) + Buy/Sell variants =====
     pub close_partial: bool,
@@ -816,23 +598,29 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub close_partial_cycle_s: Option<i32>,
     #[serde(default = "default_partial_mode")]
-    pub close_partial_mode: String,
+    pub close_partial_mode: PartialMode,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_b: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_s: Option<PartialMode>,
     #[serde(default = "default_partial_balance")]
-    pub close_partial_balance: String,
+    pub close_partial_balance: PartialBalance,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_b: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_s: Option<PartialBalance>,
     #[serde(default = "default_trail_step_mode")]
-    pub close_partial_trail_step_mode: String, // gInput_ClosePartialTrailStepMode_{suffix}
+    pub close_partial_trail_step_mode: TrailStepMode, // gInput_ClosePartialTrailStepMode_{suffix}
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_trail_step_mode_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub close_partial_trail_step_mode_b: Option<TrailStepMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_trail_step_mode_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TrailStepMode>")]
+    pub close_partial_trail_step_mode_s: Option<TrailStepMode>,
     #[serde(default)]
     pub close_partial_profit_threshold: f64, // gInput_ClosePartialProfitThreshold_{suffix}
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -854,17 +642,23 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub close_partial_cycle_2_s: Option<i32>,
     #[serde(default)]
-    pub close_partial_mode_2: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_2: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_2_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_2_b: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_2_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_2_s: Option<PartialMode>,
     #[serde(default)]
-    pub close_partial_balance_2: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_2: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_2_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_2_b: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_2_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_2_s: Option<PartialBalance>,
     #[serde(default)]
     pub close_partial_profit_threshold_2: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -885,17 +679,23 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub close_partial_cycle_3_s: Option<i32>,
     #[serde(default)]
-    pub close_partial_mode_3: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_3: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_3_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_3_b: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_3_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_3_s: Option<PartialMode>,
     #[serde(default)]
-    pub close_partial_balance_3: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_3: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_3_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_3_b: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_3_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_3_s: Option<PartialBalance>,
     #[serde(default)]
     pub close_partial_profit_threshold_3: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -916,17 +716,23 @@ pub struct LogicConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub close_partial_cycle_4_s: Option<i32>,
     #[serde(default)]
-    pub close_partial_mode_4: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_4: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_4_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_4_b: Option<PartialMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_mode_4_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialMode>")]
+    pub close_partial_mode_4_s: Option<PartialMode>,
     #[serde(default)]
-    pub close_partial_balance_4: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_4: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_4_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_4_b: Option<PartialBalance>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub close_partial_balance_4_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, PartialBalance>")]
+    pub close_partial_balance_4_s: Option<PartialBalance>,
     #[serde(default)]
     pub close_partial_profit_threshold_4: Option<f64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -936,17 +742,23 @@ pub struct LogicConfig {
 
     // ===== TRIGGERS (optional) + Buy/Sell variants =====
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_type: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerType>")]
+    pub trigger_type: Option<TriggerType>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_type_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerType>")]
+    pub trigger_type_b: Option<TriggerType>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_type_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerType>")]
+    pub trigger_type_s: Option<TriggerType>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_mode: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerMode>")]
+    pub trigger_mode: Option<TriggerMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_mode_b: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerMode>")]
+    pub trigger_mode_b: Option<TriggerMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub trigger_mode_s: Option<String>,
+    #[serde(deserialize_with = "canonical_opt::<_, TriggerMode>")]
+    pub trigger_mode_s: Option<TriggerMode>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trigger_bars: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -975,58 +787,107 @@ pub struct LogicConfig {
 }
 
 fn default_scale() -> f64 {
-    100.0
+    BASE_LOGIC.reverse_scale
 }
 fn default_half_scale() -> f64 {
-    50.0
+    BASE_LOGIC.hedge_scale
 }
 fn default_one() -> i32 {
-    1
+    BASE_LOGIC.trail_step_cycle
 }
 
 // Total fields (V17.04+): 3 + 8 + 5 + 6 + 8 + 3 + 5 = 38 fields
 // Power has some optional, Non-Power has all
 
+/// Shared bridge state. Locks are async (tokio) so a command awaiting IO never holds
+/// a std guard across an await; heavy work runs on spawn_blocking with clones of the Arcs.
 #[derive(Debug, Clone)]
 pub struct MTBridgeState {
-    pub config: Arc<Mutex<Option<MTConfig>>>,
-    pub mt4_path: Arc<Mutex<Option<PathBuf>>>,
-    pub mt5_path: Arc<Mutex<Option<PathBuf>>>,
-    pub watcher: Arc<Mutex<Option<notify::RecommendedWatcher>>>,
-    pub mql_compiler: Arc<Mutex<Option<MQLRustCompiler>>>,
+    /// Configs open in the editor, addressed by handle
+    pub configs: Arc<RwLock<OpenConfigs>>,
+    pub mt4_path: Arc<RwLock<Option<PathBuf>>>,
+    pub mt5_path: Arc<RwLock<Option<PathBuf>>>,
+    pub watcher: Arc<AsyncMutex<Option<notify::RecommendedWatcher>>>,
+    pub mql_compiler: Arc<AsyncMutex<Option<MQLRustCompiler>>>,
+    pub field_locks: Arc<RwLock<FieldLocks>>,
+    /// Chat macro being recorded, if any
+    pub macro_recording: Arc<RwLock<Option<ChatMacro>>>,
+    /// Shared with safe_mode::flag() so stateless writers see it too
+    pub safe_mode: Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl MTBridgeState {
     pub fn new() -> Self {
         Self {
-            config: Arc::new(Mutex::new(None)),
-            mt4_path: Arc::new(Mutex::new(None)),
-            mt5_path: Arc::new(Mutex::new(None)),
-            watcher: Arc::new(Mutex::new(None)),
-            mql_compiler: Arc::new(Mutex::new(None)),
+            configs: Arc::new(RwLock::new(OpenConfigs::with_constraints(
+                load_constraints(),
+            ))),
+            mt4_path: Arc::new(RwLock::new(None)),
+            mt5_path: Arc::new(RwLock::new(None)),
+            watcher: Arc::new(AsyncMutex::new(None)),
+            mql_compiler: Arc::new(AsyncMutex::new(None)),
+            field_locks: Arc::new(RwLock::new(load_field_locks())),
+            macro_recording: Arc::new(RwLock::new(None)),
+            safe_mode: crate::safe_mode::flag(),
         }
     }
 
-    pub fn initialize_compiler(&self) -> Result<(), String> {
-        let mt4_path = self.mt4_path.lock().unwrap();
-        let mt5_path = self.mt5_path.lock().unwrap();
+    /// Configured terminal path for "MT4" / "MT5"
+    pub async fn platform_path(&self, platform: &str) -> Result<PathBuf, String> {
+        match platform {
+            "MT4" => self.mt4_path.read().await.clone().ok_or("MT4 path not set".to_string()),
+            "MT5" => self.mt5_path.read().await.clone().ok_or("MT5 path not set".to_string()),
+            _ => Err("Invalid platform".to_string()),
+        }
+    }
 
-        let mt4_str = mt4_path
+    pub async fn initialize_compiler(&self) -> Result<(), String> {
+        let mt4_str = self
+            .mt4_path
+            .read()
+            .await
             .as_ref()
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
-        let mt5_str = mt5_path
+        let mt5_str = self
+            .mt5_path
+            .read()
+            .await
             .as_ref()
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        match MQLRustCompiler::new_for_dashboard(&mt4_str, &mt5_str) {
-            Ok(compiler) => {
-                *self.mql_compiler.lock().unwrap() = Some(compiler);
-                Ok(())
+        // Project discovery walks the EA folders - keep it off the async runtime
+        let compiler = tokio::task::spawn_blocking(move || {
+            MQLRustCompiler::new_for_dashboard(&mt4_str, &mt5_str).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| format!("Failed to initialize MQL compiler: {}", e))?
+        .map_err(|e| format!("Failed to initialize MQL compiler: {}", e))?;
+
+        *self.mql_compiler.lock().await = Some(compiler);
+        Ok(())
+    }
+
+    /// Run `f` against the compiler on the blocking pool. Other bridge commands (vault,
+    /// export, config) don't touch this lock, so a long pipeline run no longer stalls them.
+    pub async fn with_compiler<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut MQLRustCompiler) -> Result<T, String> + Send + 'static,
+    {
+        let compiler = self.mql_compiler.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut guard = compiler.blocking_lock();
+            match guard.as_mut() {
+                Some(compiler) => f(compiler),
+                None => {
+                    Err("MQL Compiler not initialized. Please set MT4/MT5 paths first.".to_string())
+                }
             }
-            Err(e) => Err(format!("Failed to initialize MQL compiler: {}", e)),
-        }
+        })
+        .await
+        .map_err(|e| format!("MQL compiler task failed: {}", e))?
     }
 }
 
@@ -1036,31 +897,30 @@ impl MTBridgeState {
 #[tauri::command]
 pub async fn load_mt_config(
     platform: String,
+    handle: Option<String>,
     state: State<'_, MTBridgeState>,
 ) -> Result<MTConfig, String> {
-    let config_path = match platform.as_str() {
-        "MT4" => {
-            let path = state.mt4_path.lock().unwrap();
-            path.clone().ok_or("MT4 path not set")?
-        }
-        "MT5" => {
-            let path = state.mt5_path.lock().unwrap();
-            path.clone().ok_or("MT5 path not set")?
-        }
-        _ => return Err("Invalid platform".to_string()),
-    };
+    let config_path = state.platform_path(&platform).await?;
 
-    // Sanitize and validate the path before reading
-    let sanitized_path = sanitize_and_validate_path(&config_path)?;
-    let resolved_path = resolve_mt_config_path(&sanitized_path, "DAAVFX_Config.json");
+    let config = tokio::task::spawn_blocking(move || -> Result<MTConfig, String> {
+        // Sanitize and validate the path before reading
+        let sanitized_path = sanitize_and_validate_path(&config_path)?;
+        let resolved_path =
+            resolve_mt_config_path(&sanitized_path, &naming::current().config_name);
 
-    let json_str =
-        fs::read_to_string(&resolved_path).map_err(|e| format!("Failed to read config: {}", e))?;
+        let json_str = fs::read_to_string(&resolved_path)
+            .map_err(|e| format!("Failed to read config: {}", e))?;
 
-    let config: MTConfig =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse config: {}", e))?;
+        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse config: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Failed to load config: {}", e))??;
 
-    *state.config.lock().unwrap() = Some(config.clone());
+    state
+        .configs
+        .write()
+        .await
+        .store(handle.as_deref(), config.clone())?;
 
     Ok(config)
 }
@@ -1070,37 +930,31 @@ pub async fn load_mt_config(
 pub async fn save_mt_config(
     platform: String,
     config: MTConfig,
+    handle: Option<String>,
     state: State<'_, MTBridgeState>,
-) -> Result<(), String> {
-    let config_path = match platform.as_str() {
-        "MT4" => {
-            let path = state.mt4_path.lock().unwrap();
-            path.clone().ok_or("MT4 path not set")?
-        }
-        "MT5" => {
-            let path = state.mt5_path.lock().unwrap();
-            path.clone().ok_or("MT5 path not set")?
-        }
-        _ => return Err("Invalid platform".to_string()),
-    };
+) -> Result<Vec<LockViolation>, String> {
+    let config_path = state.platform_path(&platform).await?;
+    let violations = enforce_field_locks(&state, &config).await?;
 
     // Sanitize and validate the path before writing
     let sanitized_path = sanitize_and_validate_path(&config_path)?;
     let default_file_name = if config.general.config_file_name.trim().is_empty() {
-        "DAAVFX_Config.json"
+        naming::current().config_name
     } else {
-        config.general.config_file_name.as_str()
+        config.general.config_file_name.clone()
     };
-    let resolved_path = resolve_mt_config_path(&sanitized_path, default_file_name);
+    let resolved_path = resolve_mt_config_path(&sanitized_path, &default_file_name);
 
     let json_str = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    atomic_write(&resolved_path, &json_str)?;
+    tokio::task::spawn_blocking(move || atomic_write(&resolved_path, &json_str))
+        .await
+        .map_err(|e| format!("Failed to save config: {}", e))??;
 
-    *state.config.lock().unwrap() = Some(config);
+    state.configs.write().await.store(handle.as_deref(), config)?;
 
-    Ok(())
+    Ok(violations)
 }
 
 #[cfg(feature = "tauri-app")]
@@ -1121,10 +975,10 @@ pub async fn set_mt_path(
 
     match platform.as_str() {
         "MT4" => {
-            *state.mt4_path.lock().unwrap() = Some(sanitized_path);
+            *state.mt4_path.write().await = Some(sanitized_path);
         }
         "MT5" => {
-            *state.mt5_path.lock().unwrap() = Some(sanitized_path);
+            *state.mt5_path.write().await = Some(sanitized_path);
         }
         _ => return Err("Invalid platform".to_string()),
     }
@@ -1139,17 +993,7 @@ pub async fn start_file_watcher(
     app_handle: tauri::AppHandle,
     state: State<'_, MTBridgeState>,
 ) -> Result<(), String> {
-    let config_path = match platform.as_str() {
-        "MT4" => {
-            let path = state.mt4_path.lock().unwrap();
-            path.clone().ok_or("MT4 path not set")?
-        }
-        "MT5" => {
-            let path = state.mt5_path.lock().unwrap();
-            path.clone().ok_or("MT5 path not set")?
-        }
-        _ => return Err("Invalid platform".to_string()),
-    };
+    let config_path = state.platform_path(&platform).await?;
 
     let (tx, rx) = std::sync::mpsc::channel();
 
@@ -1160,13 +1004,13 @@ pub async fn start_file_watcher(
     })
     .map_err(|e| format!("Failed to create watcher: {}", e))?;
 
-    let resolved_path = resolve_mt_config_path(&config_path, "DAAVFX_Config.json");
+    let resolved_path = resolve_mt_config_path(&config_path, &naming::current().config_name);
 
     watcher
         .watch(resolved_path.as_path(), RecursiveMode::NonRecursive)
         .map_err(|e| format!("Failed to watch path: {}", e))?;
 
-    *state.watcher.lock().unwrap() = Some(watcher);
+    *state.watcher.lock().await = Some(watcher);
 
     std::thread::spawn(move || {
         let mut consecutive_errors = 0;
@@ -1179,7 +1023,7 @@ pub async fn start_file_watcher(
                 Err(_) => {
                     consecutive_errors += 1;
                     if consecutive_errors >= 3 {
-                        println!("[WATCHER] File watcher channel closed, exiting thread");
+                        tracing::warn!("File watcher channel closed, exiting thread");
                         break;
                     }
                     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -1195,16 +1039,20 @@ pub async fn start_file_watcher(
 pub async fn get_default_mt4_path() -> Result<String, String> {
     let mut possible_paths: Vec<PathBuf> = Vec::new();
 
-    if let Some(home) = dirs::home_dir() {
-        possible_paths.push(home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files"));
+    if let Ok(common) = os_paths::common_files_dir() {
+        possible_paths.push(common);
     }
 
-    possible_paths.extend([
-        PathBuf::from("C:\\Program Files\\MetaTrader 4\\MQL4\\Files\\Common"),
-        PathBuf::from("C:\\Program Files (x86)\\MetaTrader 4\\MQL4\\Files\\Common"),
-        PathBuf::from("C:\\Program Files\\MetaTrader 4"),
-        PathBuf::from("C:\\Program Files (x86)\\MetaTrader 4"),
-    ]);
+    let installs: Vec<PathBuf> = os_paths::program_files_dirs()
+        .into_iter()
+        .map(|dir| dir.join("MetaTrader 4"))
+        .collect();
+    possible_paths.extend(
+        installs
+            .iter()
+            .map(|install| install.join("MQL4").join("Files").join("Common")),
+    );
+    possible_paths.extend(installs);
 
     for path in possible_paths {
         if path.exists() {
@@ -1219,17 +1067,23 @@ pub async fn get_default_mt4_path() -> Result<String, String> {
 pub async fn get_default_mt5_path() -> Result<String, String> {
     let mut possible_paths: Vec<PathBuf> = Vec::new();
 
-    if let Some(home) = dirs::home_dir() {
-        possible_paths.push(home.join("AppData\\Roaming\\MetaQuotes\\Terminal64\\Common\\Files"));
-        possible_paths.push(home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files"));
+    if let Ok(metaquotes) = os_paths::metaquotes_dir() {
+        possible_paths.push(metaquotes.join("Terminal64").join("Common").join("Files"));
+    }
+    if let Ok(common) = os_paths::common_files_dir() {
+        possible_paths.push(common);
     }
 
-    possible_paths.extend([
-        PathBuf::from("C:\\Program Files\\MetaTrader 5\\MQL5\\Files\\Common"),
-        PathBuf::from("C:\\Program Files (x86)\\MetaTrader 5\\MQL5\\Files\\Common"),
-        PathBuf::from("C:\\Program Files\\MetaTrader 5"),
-        PathBuf::from("C:\\Program Files (x86)\\MetaTrader 5"),
-    ]);
+    let installs: Vec<PathBuf> = os_paths::program_files_dirs()
+        .into_iter()
+        .map(|dir| dir.join("MetaTrader 5"))
+        .collect();
+    possible_paths.extend(
+        installs
+            .iter()
+            .map(|install| install.join("MQL5").join("Files").join("Common")),
+    );
+    possible_paths.extend(installs);
 
     for path in possible_paths {
         if path.exists() {
@@ -1281,38 +1135,8 @@ fn generate_optimization_hint(field: &str, value: f64) -> String {
     }
 }
 
-fn get_optimization_values(field: &str, value: f64) -> (i32, f64, f64, f64) {
-    if !value.is_finite() {
-        return (0, 0.0, 0.0, 0.0);
-    }
-
-    match field {
-        "initial_lot" => {
-            let base = if value > 0.0 { value } else { 0.01 };
-            let step = 0.01_f64;
-            let min = 0.01_f64;
-            let max = (base * 3.0).max(0.03);
-            (1, min, step, max)
-        }
-        "multiplier" => {
-            let base = if value > 1.0 { value } else { 1.5 };
-            let step = 0.1_f64;
-            let min = 1.0_f64;
-            let max = (base * 2.0).max(3.0);
-            (1, min, step, max)
-        }
-        "grid" => {
-            let base = if value > 0.0 { value } else { 10.0 };
-            let step = 5.0_f64;
-            let min = 5.0_f64;
-            let max = (base * 3.0).max(50.0);
-            (1, min, step, max)
-        }
-        _ => (0, 0.0, 0.0, 0.0),
-    }
-}
-
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(file_path = %file_path, platform = %platform), err)]
 pub fn export_set_file(
     config: MTConfig,
     file_path: String,
@@ -1321,18 +1145,168 @@ pub fn export_set_file(
     trade_direction: Option<String>, // "BUY", "SELL", or "BOTH" (default)
     tags: Option<Vec<String>>,
     comments: Option<String>,
+    redaction: Option<ExportRedaction>,
+    deterministic: Option<DeterministicExport>,
+    format: Option<String>, // registered setfile format id, "v17" when unset
 ) -> Result<(), String> {
-    // Add debug logging to see what parameters are received
-    println!("[DEBUG] export_set_file called with:");
-    println!("[DEBUG]   file_path: {}", file_path);
-    println!("[DEBUG]   platform: {}", platform);
-    println!("[DEBUG]   include_optimization_hints: {}", include_optimization_hints);
-    println!("[DEBUG]   trade_direction: {:?}", trade_direction);
+    tracing::debug!(
+        file_path = %file_path,
+        platform = %platform,
+        include_optimization_hints,
+        ?trade_direction,
+        ?format,
+        "export_set_file called"
+    );
 
     // Sanitize and validate the file path
     let path_buf = PathBuf::from(&file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
+    let format = setfile_format::find_format(
+        format.as_deref().unwrap_or(setfile_format::DEFAULT_EXPORT_FORMAT),
+    )?;
+
+    let (mut config, comments) = redact_for_export(config, comments, redaction.as_ref());
+    // Resolve note keys while engine/group indices still match the annotation paths
+    let notes = annotations::resolve_set_notes(&config);
+    if let Some(deterministic) = &deterministic {
+        deterministic.prepare_config(&mut config);
+    }
+    let options = setfile_format::RenderOptions {
+        platform,
+        file_path: file_path.clone(),
+        include_optimization_hints,
+        trade_direction,
+        tags,
+        comments,
+    };
+    let task = crate::task_manager().begin(None, "export_set_file");
+    let lines = format.render(config, &options, &task);
+    crate::task_manager().finish(&task.id);
+    let mut lines = annotations::insert_set_notes(lines?, &notes);
+    if let Some(deterministic) = &deterministic {
+        lines = deterministic.finish_lines(lines);
+    }
+
+    // Write file
+    let content = lines.join("\n");
+    sync_aware_write(&sanitized_path, &content)?;
+    mirror_setfile_to_mt_common_files(&sanitized_path, &content, None);
+
+    Ok(())
+}
+
+/// Export option for presets kept in version control: the same config always
+/// renders the same bytes, so a diff only shows values that actually changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeterministicExport {
+    /// Written in place of the export time; the timestamp is left out when unset
+    pub timestamp: Option<String>,
+}
+
+impl DeterministicExport {
+    /// Sort engines, groups and logics so their order in the editor can't reorder the
+    /// output, and pin the config's own timestamps
+    fn prepare_config(&self, config: &mut MTConfig) {
+        let logic_rank = |name: &str| {
+            ["POWER", "REPOWER", "SCALPER", "STOPPER", "STO", "SCA", "RPO"]
+                .iter()
+                .position(|known| known.eq_ignore_ascii_case(name))
+                .unwrap_or(usize::MAX)
+        };
+
+        config.engines.sort_by(|a, b| a.engine_id.cmp(&b.engine_id));
+        for engine in &mut config.engines {
+            engine.groups.sort_by_key(|group| group.group_number);
+            for group in &mut engine.groups {
+                group.logics.sort_by(|a, b| {
+                    logic_rank(&a.logic_name)
+                        .cmp(&logic_rank(&b.logic_name))
+                        .then_with(|| a.logic_id.cmp(&b.logic_id))
+                });
+            }
+        }
+
+        config.timestamp = self.timestamp.clone().unwrap_or_default();
+        config.last_saved_at = None;
+    }
+
+    /// Fix the "; Generated" header and normalize float values of rendered .set lines
+    fn finish_lines(&self, lines: Vec<String>) -> Vec<String> {
+        lines
+            .into_iter()
+            .filter_map(|line| {
+                if line.starts_with("; Generated") {
+                    return self
+                        .timestamp
+                        .as_ref()
+                        .map(|timestamp| format!("; Generated: {}", timestamp));
+                }
+                if line.starts_with(';') {
+                    return Some(line);
+                }
+                match line.split_once('=') {
+                    Some((key, value)) => match normalize_float_value(value) {
+                        Some(value) => Some(format!("{}={}", key, value)),
+                        None => Some(line),
+                    },
+                    None => Some(line),
+                }
+            })
+            .collect()
+    }
+}
+
+/// "0.30000000000000004" -> "0.3", "1.50" -> "1.5", "-0.0" -> "0.0". Values that aren't a
+/// plain decimal (integers, enums, ranges) give None and are written as-is.
+fn normalize_float_value(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if !trimmed.contains('.') || trimmed.contains(['e', 'E']) {
+        return None;
+    }
+    let parsed: f64 = trimmed.parse().ok()?;
+    if !parsed.is_finite() {
+        return None;
+    }
+
+    let mut formatted = format!("{:.8}", parsed);
+    while formatted.ends_with('0') && !formatted.ends_with(".0") {
+        formatted.pop();
+    }
+    if formatted == "-0.0" {
+        formatted = "0.0".to_string();
+    }
+    Some(formatted)
+}
+
+/// Apply the export audience's redaction policy to the config and the
+/// free-text comments written into the file header. No option writes as-is.
+fn redact_for_export(
+    mut config: MTConfig,
+    comments: Option<String>,
+    redaction: Option<&ExportRedaction>,
+) -> (MTConfig, Option<String>) {
+    match redaction {
+        Some(redaction) => {
+            let policy = redaction.resolved_policy();
+            apply_redaction(&mut config, &policy);
+            (config, redact_comments(comments, &policy))
+        }
+        None => (config, comments),
+    }
+}
 
+/// Render the flat (v17) .set lines exactly as export_set_file writes them, without touching
+/// disk
+pub(crate) fn render_set_file_lines(
+    config: &MTConfig,
+    file_path: &str,
+    platform: &str,
+    include_optimization_hints: bool,
+    trade_direction: Option<&str>,
+    tags: Option<Vec<String>>,
+    comments: Option<String>,
+) -> Vec<String> {
     let mut lines: Vec<String> = Vec::new();
 
     // Header comment
@@ -1358,920 +1332,52 @@ pub fn export_set_file(
 
     lines.push(String::new());
 
-    // General Settings
-    lines.push("; === GENERAL SETTINGS ===".to_string());
-    lines.push(format!(
-        "gInput_MagicNumber={}",
-        config.general.magic_number
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberBuy={}",
-        config.general.magic_number_buy
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberSell={}",
-        config.general.magic_number_sell
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberPowerBuy={}",
-        config.general.magic_number_buy
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberPowerSell={}",
-        config.general.magic_number_sell
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberReverseBase={}",
-        config.general.reverse_magic_base
-    ));
-    lines.push(format!(
-        "gInput_MagicNumberHedgeBase={}",
-        config.general.hedge_magic_base
-    ));
-    lines.push(format!(
-        "gInput_HedgeMagicIndependent={}",
-        if config.general.hedge_magic_independent {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_MaxSlippage={}",
-        (config.general.max_slippage_points.round() as i32)
-    ));
-    lines.push(format!(
-        "gInput_MaxSlippagePoints={:.1}",
-        config.general.max_slippage_points
-    ));
-    lines.push(format!(
-        "gInput_allowBuy={}",
-        if config.general.allow_buy { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_allowSell={}",
-        if config.general.allow_sell { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_EnableLogs={}",
-        if config.general.enable_logs { 1 } else { 0 }
-    ));
-    // Lazy Fix: Auto-point to self for absolute paths to support >1100 inputs via EA loader
-    // This allows the EA to re-read the .set file from disk to bypass MT5 input limits
-    let is_absolute =
-        file_path.contains(":") || file_path.starts_with("\\\\") || file_path.starts_with("/");
-    if is_absolute {
-        lines.push(format!("gInput_ConfigFileName={}", file_path));
-        lines.push(format!("gInput_ConfigFileIsCommon=0"));
-    } else {
-        lines.push(format!(
-            "gInput_ConfigFileName={}",
-            config.general.config_file_name
-        ));
-        lines.push(format!(
-            "gInput_ConfigFileIsCommon={}",
-            if config.general.config_file_is_common {
-                1
-            } else {
-                0
-            }
-        ));
-    }
-
-    lines.push(String::new());
-
-    // Compounding
-    lines.push("; === COMPOUNDING ===".to_string());
-    lines.push(format!(
-        "gInput_Input_Compounding={}",
-        if config.general.compounding_enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_Input_CompoundingType={}",
-        config.general.compounding_type
-    ));
-    lines.push(format!(
-        "gInput_Input_CompoundingTarget={:.1}",
-        config.general.compounding_target
-    ));
-    lines.push(format!(
-        "gInput_Input_CompoundIncrease={:.1}",
-        config.general.compounding_increase
-    ));
-    lines.push(String::new());
-
-    // Risk Management
-    lines.push("; === RISK MANAGEMENT ===".to_string());
-    lines.push(format!(
-        "gInput_UseSpreadFilter={}",
-        if config.general.risk_management.spread_filter_enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_MaxSpreadPoints={:.1}",
-        config.general.risk_management.max_spread_points
-    ));
-    lines.push(format!(
-        "gInput_UseEquityStop={}",
-        if config.general.risk_management.equity_stop_enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_EquityStopValue={:.1}",
-        config.general.risk_management.equity_stop_value
-    ));
-    lines.push(format!(
-        "gInput_UseDrawdownStop={}",
-        if config.general.risk_management.drawdown_stop_enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_MaxDrawdownPercent={:.1}",
-        config.general.risk_management.max_drawdown_percent
-    ));
-    if let Some(risk_action) = config.general.risk_management.risk_action.as_deref() {
-        lines.push(format!(
-            "gInput_RiskAction={}",
-            trigger_action_to_int(risk_action)
-        ));
-    }
-    lines.push(String::new());
-
-    lines.push("; === CLEAN MATH ===".to_string());
-    lines.push(format!(
-        "gInput_GridUnit={}",
-        config
-            .general
-            .grid_unit
-            .map(|v| v.to_string())
-            .unwrap_or_default()
-    ));
-    lines.push(format!(
-        "gInput_PipFactor={}",
-        config
-            .general
-            .pip_factor
-            .map(|v| v.to_string())
-            .unwrap_or_default()
-    ));
-    lines.push(String::new());
-
-    // News Filter
-    lines.push("; === NEWS FILTER ===".to_string());
-    lines.push(format!(
-        "gInput_EnableNewsFilter={}",
-        if config.general.news_filter.enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_NewsFilterEnabled={}",
-        if config.general.news_filter.enabled {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_NewsAPIKey={}",
-        config.general.news_filter.api_key
-    ));
-    lines.push(format!(
-        "gInput_NewsAPIURL={}",
-        config.general.news_filter.api_url
-    ));
-    lines.push(format!(
-        "gInput_NewsFilterCountries={}",
-        config.general.news_filter.countries
-    ));
-    lines.push(format!(
-        "gInput_NewsImpactLevel={}",
-        config.general.news_filter.impact_level
-    ));
-    lines.push(format!(
-        "gInput_MinutesBeforeNews={}",
-        config.general.news_filter.minutes_before
-    ));
-    lines.push(format!(
-        "gInput_MinutesAfterNews={}",
-        config.general.news_filter.minutes_after
-    ));
-    // Convert 3 boolean fields to news action enum
-    let nf = &config.general.news_filter;
-    let news_action = if !nf.stop_ea {
-        0
-    } else if nf.close_trades && nf.auto_restart {
-        6
-    } else if nf.close_trades && !nf.auto_restart {
-        5
-    } else if !nf.close_trades && nf.auto_restart {
-        7
-    } else {
-        2
-    };
-    lines.push(format!("gInput_NewsAction={}", news_action));
-    lines.push(format!("gInput_NewsStopEA={}", if nf.stop_ea { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsCloseTrades={}", if nf.close_trades { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsAutoRestart={}", if nf.auto_restart { 1 } else { 0 }));
-    lines.push(format!(
-        "gInput_NewsCheckInterval={}",
-        config.general.news_filter.check_interval
-    ));
-    lines.push(format!(
-        "gInput_FilterHighImpactOnly={}",
-        if config.general.news_filter.filter_high_only { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_FilterWeekendNews={}",
-        if config.general.news_filter.filter_weekends { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_UseLocalNewsCache={}",
-        if config.general.news_filter.use_local_cache { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_NewsCacheDuration={}",
-        config.general.news_filter.cache_duration
-    ));
-    lines.push(format!(
-        "gInput_NewsFallbackOnError={}",
-        config.general.news_filter.fallback_on_error
-    ));
-    lines.push(format!(
-        "gInput_FilterCurrencies={}",
-        config.general.news_filter.filter_currencies
-    ));
-    lines.push(format!(
-        "gInput_IncludeSpeeches={}",
-        if config.general.news_filter.include_speeches { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_IncludeReports={}",
-        if config.general.news_filter.include_reports { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_NewsVisualIndicator={}",
-        if config.general.news_filter.visual_indicator { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_AlertBeforeNews={}",
-        if config.general.news_filter.alert_before_news { 1 } else { 0 }
-    ));
-    lines.push(format!(
-        "gInput_AlertMinutesBefore={}",
-        config.general.news_filter.alert_minutes
-    ));
-    if let Some(cf) = config.general.news_filter.calendar_file.as_deref() {
-        lines.push(format!("gInput_NewsCalendarFile={}", cf));
-    }
-    lines.push(String::new());
-
-    // Time Filters / Sessions
-    lines.push("; === TIME FILTERS ===".to_string());
-    lines.push(format!(
-        "gInput_NewsFilterOverridesSession={}",
-        if config
-            .general
-            .time_filters
-            .priority_settings
-            .news_filter_overrides_session
-        {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_SessionFilterOverridesNews={}",
-        if config
-            .general
-            .time_filters
-            .priority_settings
-            .session_filter_overrides_news
-        {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_NewsOverridesSession={}",
-        if config
-            .general
-            .time_filters
-            .priority_settings
-            .news_filter_overrides_session
-        {
-            1
-        } else {
-            0
-        }
-    ));
-    lines.push(format!(
-        "gInput_SessionOverridesNews={}",
-        if config
-            .general
-            .time_filters
-            .priority_settings
-            .session_filter_overrides_news
-        {
-            1
-        } else {
-            0
-        }
-    ));
-    let any_session_enabled = config
-        .general
-        .time_filters
-        .sessions
-        .iter()
-        .any(|s| s.enabled);
-    lines.push(format!(
-        "gInput_SessionFilterEnabled={}",
-        if any_session_enabled { 1 } else { 0 }
-    ));
-
-    for (i, session) in config.general.time_filters.sessions.iter().enumerate() {
-        let session_num = i + 1;
-        let n = session.enabled as i32; // 0 or 1
-        lines.push(format!("gInput_Session{}Enabled={}", session_num, n));
-        lines.push(format!("gInput_Session{}Day={}", session_num, session.day));
-        lines.push(format!(
-            "gInput_Session{}StartHour={}",
-            session_num, session.start_hour
-        ));
-        lines.push(format!(
-            "gInput_Session{}StartMinute={}",
-            session_num, session.start_minute
-        ));
-        lines.push(format!(
-            "gInput_Session{}EndHour={}",
-            session_num, session.end_hour
-        ));
-        lines.push(format!(
-            "gInput_Session{}EndMinute={}",
-            session_num, session.end_minute
-        ));
-        lines.push(format!(
-            "gInput_Session{}Action={}",
-            session_num,
-            trigger_action_to_int(&session.action)
-        ));
-    }
-    lines.push(String::new());
-
-    // Engine and Logic configs
-    eprintln!("[EXPORT DEBUG] Starting engine loop, engines count: {}", config.engines.len());
-    for engine in &config.engines {
-        lines.push(format!(";"));
-        lines.push(format!("; === ENGINE {} ===", engine.engine_id));
-        eprintln!("[EXPORT DEBUG] Processing engine: {}, groups count: {}", engine.engine_id, engine.groups.len());
-
-        for group in &engine.groups {
-            eprintln!("[EXPORT DEBUG] Processing group: {}, group_number: {}", group.group_number, group.group_number);
-            lines.push(format!("; --- Group {} ---", group.group_number));
-
-            // GroupPowerStart thresholds (groups 2-20): ONLY Engine A Power controls group progression (V3 behavior).
-            // B/C engines always mirror A's group at EA runtime, so no BP/CP thresholds are needed.
-            if group.group_number > 1 && engine.engine_id == "A" {
-                let base_key = format!("P{}", group.group_number);
-                    
-                // Use new Buy/Sell specific fields, fallback to legacy group_power_start
-                let gps_b = group.group_power_start_b.or(group.group_power_start);
-                let gps_s = group.group_power_start_s.or(group.group_power_start);
-                    
-                if let Some(v) = gps_b {
-                    lines.push(format!("gInput_GroupPowerStart_{}_Buy={}", base_key, v));
-                }
-                if let Some(v) = gps_s {
-                    lines.push(format!("gInput_GroupPowerStart_{}_Sell={}", base_key, v));
-                }
-            }
-
-            // Group-level Reverse/Hedge controls (V17.04+)
-            lines.push(format!(
-                "gInput_Group{}_ReverseMode={}",
-                group.group_number,
-                if group.reverse_mode { 1 } else { 0 }
-            ));
-            lines.push(format!(
-                "gInput_Group{}_HedgeMode={}",
-                group.group_number,
-                if group.hedge_mode { 1 } else { 0 }
-            ));
-            lines.push(format!(
-                "gInput_Group{}_HedgeReference={}",
-                group.group_number, group.hedge_reference
-            ));
-            lines.push(format!(
-                "gInput_Group{}_EntryDelayBars={}",
-                group.group_number, group.entry_delay_bars
-            ));
-
-            for logic in &group.logics {
-                let suffix =
-                    get_logic_suffix(&engine.engine_id, group.group_number, &logic.logic_name);
-                let short = get_logic_short(&engine.engine_id, &logic.logic_name);
-
-                // No _Enabled/_Start key — all logics always enabled.
-                // Trading is controlled by StartLevel, trigger type, risk management, etc.
-
-                // Base params: Initial/Last lot are Group 1-only by contract.
-                if group.group_number == 1 {
-                    let initial_key = format!("gInput_Initial_loT_{}", suffix);
-                    lines.push(format!("{}={:.2}", initial_key, logic.initial_lot));
-
-                    if let Some(v) = logic.initial_lot_b {
-                        if v > 0.0 {
-                            lines.push(format!("gInput_Initial_loT_{}_B={:.2}", suffix, v));
-                        }
-                    }
-                    if let Some(v) = logic.initial_lot_s {
-                        if v > 0.0 {
-                            lines.push(format!("gInput_Initial_loT_{}_S={:.2}", suffix, v));
-                        }
-                    }
-
-                    if let Some(ll) = logic.last_lot {
-                        let upper = logic.logic_name.to_uppercase();
-                        if upper == "POWER" {
-                            lines.push(format!("gInput_LastLotPower_{}={:.2}", suffix, ll));
-                        } else if upper == "REPOWER" {
-                            lines.push(format!("gInput_LastLotRepower_{}={:.2}", suffix, ll));
-                        } else {
-                            lines.push(format!("gInput_LastLot_{}={:.2}", suffix, ll));
-                        }
-                    }
-                }
-
-                // Strategy Type & Trading Mode
-                lines.push(format!(
-                    "gInput_G{}_{}_StrategyType={}",
-                    group.group_number, short, logic.strategy_type
-                ));
-                lines.push(format!(
-                    "gInput_G{}_{}_TradingMode={}",
-                    group.group_number, short, logic.trading_mode
-                ));
-
-                // Apply trade direction override if specified
-                let (allow_buy, allow_sell) = match trade_direction.as_deref() {
-                    Some("BUY") => (true, false),
-                    Some("SELL") => (false, true),
-                    Some("BOTH") | None => (logic.allow_buy, logic.allow_sell),
-                    _ => (logic.allow_buy, logic.allow_sell),
-                };
-                lines.push(format!(
-                    "gInput_AllowBuy_{}={}",
-                    suffix,
-                    if allow_buy { 1 } else { 0 }
-                ));
-                lines.push(format!(
-                    "gInput_AllowSell_{}={}",
-                    suffix,
-                    if allow_sell { 1 } else { 0 }
-                ));
-
-                if include_optimization_hints && group.group_number == 1 {
-                    let initial_key = format!("gInput_Initial_loT_{}", suffix);
-                    let (f, start, step, stop) =
-                        get_optimization_values("initial_lot", logic.initial_lot);
-                    lines.push(format!("{},F={}", initial_key, f));
-                    lines.push(format!("{},1={:.2}", initial_key, start));
-                    lines.push(format!("{},2={:.2}", initial_key, step));
-                    lines.push(format!("{},3={:.2}", initial_key, stop));
-                }
-
-                let mult_key = format!("gInput_Mult_{}", suffix);
-                lines.push(format!("{}={:.2}", mult_key, logic.multiplier));
-                if let Some(v) = logic.multiplier_b {
-                    if v > 0.0 {
-                        lines.push(format!("gInput_Mult_{}_B={:.2}", suffix, v));
-                    }
-                }
-                if let Some(v) = logic.multiplier_s {
-                    if v > 0.0 {
-                        lines.push(format!("gInput_Mult_{}_S={:.2}", suffix, v));
-                    }
-                }
-                if include_optimization_hints {
-                    let (f, start, step, stop) =
-                        get_optimization_values("multiplier", logic.multiplier);
-                    lines.push(format!("{},F={}", mult_key, f));
-                    lines.push(format!("{},1={:.2}", mult_key, start));
-                    lines.push(format!("{},2={:.2}", mult_key, step));
-                    lines.push(format!("{},3={:.2}", mult_key, stop));
-                }
-
-                let grid_key = format!("gInput_Grid_{}", suffix);
-                lines.push(format!("{}={:.1}", grid_key, logic.grid));
-                if let Some(v) = logic.grid_b {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_Grid_{}_B={:.1}", suffix, v));
-                    }
-                }
-                if let Some(v) = logic.grid_s {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_Grid_{}_S={:.1}", suffix, v));
-                    }
-                }
-                if include_optimization_hints {
-                    let (f, start, step, stop) = get_optimization_values("grid", logic.grid);
-                    lines.push(format!("{},F={}", grid_key, f));
-                    lines.push(format!("{},1={:.1}", grid_key, start));
-                    lines.push(format!("{},2={:.1}", grid_key, step));
-                    lines.push(format!("{},3={:.1}", grid_key, stop));
-                }
-
-                // Trail params - use correct MT4/MT5 variable names
-                lines.push(format!("gInput_Trail_{}={}", suffix, logic.trail_method));
-                lines.push(format!(
-                    "gInput_TrailValue_{}={:.1}",
-                    suffix, logic.trail_value
-                ));
-                if let Some(v) = logic.trail_value_b {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_TrailValue_{}_B={:.1}", suffix, v));
-                    }
-                }
-                if let Some(v) = logic.trail_value_s {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_TrailValue_{}_S={:.1}", suffix, v));
-                    }
-                }
-                lines.push(format!(
-                    "gInput_Trail_Start_{}={:.1}",
-                    suffix, logic.trail_start
-                ));
-                if let Some(v) = logic.trail_start_b {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_Trail_Start_{}_B={:.1}", suffix, v));
-                    }
-                }
-                if let Some(v) = logic.trail_start_s {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_Trail_Start_{}_S={:.1}", suffix, v));
-                    }
-                }
-                lines.push(format!(
-                    "gInput_TrailStep_{}={:.1}",
-                    suffix, logic.trail_step
-                ));
-                if let Some(v) = logic.trail_step_b {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_TrailStep_{}_B={:.1}", suffix, v));
-                    }
-                }
-                if let Some(v) = logic.trail_step_s {
-                    if v >= 0.0 {
-                        lines.push(format!("gInput_TrailStep_{}_S={:.1}", suffix, v));
-                    }
-                }
-                lines.push(format!(
-                    "gInput_TrailStepMethod_{}={}",
-                    suffix,
-                    encode_trail_step_method(&logic.trail_step_method)
-                ));
-
-                // Trail Step Advanced (V17.04+)
-                lines.push(format!(
-                    "gInput_TrailStepMode_{}={}",
-                    suffix,
-                    encode_trail_step_mode(&logic.trail_step_mode)
-                ));
-                lines.push(format!(
-                    "gInput_TrailStepCycle_{}={}",
-                    suffix, logic.trail_step_cycle
-                ));
-                lines.push(format!(
-                    "gInput_TrailStepBalance_{}={:.2}",
-                    suffix, logic.trail_step_balance
-                ));
-
-                // Trail Step Extended (Levels 2-7)
-                // Level 2
-                if let Some(v) = logic.trail_step_2 {
-                    lines.push(format!("gInput_TrailStep2_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_2 {
-                    lines.push(format!("gInput_TrailStepMethod2_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_2 {
-                    lines.push(format!("gInput_TrailStepCycle2_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_2 {
-                    lines.push(format!("gInput_TrailStepBalance2_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_2 {
-                    lines.push(format!("gInput_TrailStepMode2_{}={}", suffix, v));
-                }
-
-                // Level 3
-                if let Some(v) = logic.trail_step_3 {
-                    lines.push(format!("gInput_TrailStep3_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_3 {
-                    lines.push(format!("gInput_TrailStepMethod3_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_3 {
-                    lines.push(format!("gInput_TrailStepCycle3_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_3 {
-                    lines.push(format!("gInput_TrailStepBalance3_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_3 {
-                    lines.push(format!("gInput_TrailStepMode3_{}={}", suffix, v));
-                }
-
-                // Level 4
-                if let Some(v) = logic.trail_step_4 {
-                    lines.push(format!("gInput_TrailStep4_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_4 {
-                    lines.push(format!("gInput_TrailStepMethod4_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_4 {
-                    lines.push(format!("gInput_TrailStepCycle4_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_4 {
-                    lines.push(format!("gInput_TrailStepBalance4_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_4 {
-                    lines.push(format!("gInput_TrailStepMode4_{}={}", suffix, v));
-                }
-
-                // Level 5
-                if let Some(v) = logic.trail_step_5 {
-                    lines.push(format!("gInput_TrailStep5_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_5 {
-                    lines.push(format!("gInput_TrailStepMethod5_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_5 {
-                    lines.push(format!("gInput_TrailStepCycle5_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_5 {
-                    lines.push(format!("gInput_TrailStepBalance5_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_5 {
-                    lines.push(format!("gInput_TrailStepMode5_{}={}", suffix, v));
-                }
-
-                // Level 6
-                if let Some(v) = logic.trail_step_6 {
-                    lines.push(format!("gInput_TrailStep6_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_6 {
-                    lines.push(format!("gInput_TrailStepMethod6_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_6 {
-                    lines.push(format!("gInput_TrailStepCycle6_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_6 {
-                    lines.push(format!("gInput_TrailStepBalance6_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_6 {
-                    lines.push(format!("gInput_TrailStepMode6_{}={}", suffix, v));
-                }
-
-                // Level 7
-                if let Some(v) = logic.trail_step_7 {
-                    lines.push(format!("gInput_TrailStep7_{}={:.1}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_method_7 {
-                    lines.push(format!("gInput_TrailStepMethod7_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_cycle_7 {
-                    lines.push(format!("gInput_TrailStepCycle7_{}={}", suffix, v));
-                }
-                if let Some(v) = logic.trail_step_balance_7 {
-                    lines.push(format!("gInput_TrailStepBalance7_{}={:.2}", suffix, v));
-                }
-                if let Some(ref v) = logic.trail_step_mode_7 {
-                    lines.push(format!("gInput_TrailStepMode7_{}={}", suffix, v));
-                }
-
-                // Logic specific
-                if group.group_number == 1 {
-                    if let Some(sl) = logic.start_level {
-                        if let Some(start_key) =
-                            get_logic_start_key(&engine.engine_id, &logic.logic_name)
-                        {
-                            lines.push(format!("gInput_{}={}", start_key, sl));
-                        }
-                    }
-                }
-
-                lines.push(format!(
-                    "gInput_CloseTargets_{}={}",
-                    suffix, logic.close_targets
-                ));
-
-                if group.group_number == 1 {
-                    let logic_key = get_logic_global_key(&engine.engine_id, &logic.logic_name);
-                    // Export EXACTLY what the frontend provides - NO defaults
-                    lines.push(format!(
-                        "gInput_CloseTargets_{}={}",
-                        logic_key, logic.close_targets
-                    ));
-                }
-                if group.group_number == 1 {
-                    let logic_key = get_logic_global_key(&engine.engine_id, &logic.logic_name);
-                    lines.push(format!(
-                        "gInput_{}_OrderCountReference={}",
-                        logic_key, logic.order_count_reference
-                    ));
-                }
-                lines.push(format!(
-                    "gInput_MaxPowerOrders_{}={}",
-                    suffix, engine.max_power_orders
-                ));
-                lines.push(format!(
-                    "gInput_OrderCountReference_{}={}",
-                    suffix, logic.order_count_reference
-                ));
-                lines.push(format!(
-                    "gInput_ResetLotOnRestart_{}={}",
-                    suffix,
-                    if logic.reset_lot_on_restart { 1 } else { 0 }
-                ));
-
-                // Reverse/Hedge per-logic (V17.04+ full structure)
-                lines.push(format!(
-                    "gInput_G{}_{}_ReverseEnabled={}",
-                    group.group_number,
-                    short,
-                    if logic.reverse_enabled { 1 } else { 0 }
-                ));
-                lines.push(format!(
-                    "gInput_G{}_{}_HedgeEnabled={}",
-                    group.group_number,
-                    short,
-                    if logic.hedge_enabled { 1 } else { 0 }
-                ));
-                lines.push(format!(
-                    "gInput_G{}_Scale_{}_Reverse={:.1}",
-                    group.group_number, short, logic.reverse_scale
-                ));
-                lines.push(format!(
-                    "gInput_G{}_Scale_{}_Hedge={:.1}",
-                    group.group_number, short, logic.hedge_scale
-                ));
-                lines.push(format!(
-                    "gInput_G{}_{}_ReverseReference={}",
-                    group.group_number, short, logic.reverse_reference
-                ));
-                lines.push(format!(
-                    "gInput_G{}_{}_HedgeReference={}",
-                    group.group_number, short, logic.hedge_reference
-                ));
-
-                // Close Partial (active simplified contract)
-                lines.push(format!(
-                    "gInput_ClosePartial_{}={}",
-                    suffix,
-                    if logic.close_partial { 1 } else { 0 }
-                ));
-                lines.push(format!(
-                    "gInput_ClosePartialMode_{}={}",
-                    suffix,
-                    encode_partial_mode(&logic.close_partial_mode)
-                ));
-                lines.push(format!(
-                    "gInput_ClosePartialProfitThreshold_{}={:.2}",
-                    suffix, logic.close_partial_profit_threshold
-                ));
-
-                // Close Partial Extended (Levels 2-4)
-                if let Some(v) = logic.close_partial_2 {
-                    lines.push(format!(
-                        "gInput_ClosePartial2_{}={}",
-                        suffix,
-                        if v { 1 } else { 0 }
-                    ));
-                }
-                if let Some(ref v) = logic.close_partial_mode_2 {
-                    lines.push(format!(
-                        "gInput_ClosePartialMode2_{}={}",
-                        suffix,
-                        encode_partial_mode(v)
-                    ));
-                }
-                if let Some(v) = logic.close_partial_profit_threshold_2 {
-                    lines.push(format!(
-                        "gInput_ClosePartialProfitThreshold2_{}={:.2}",
-                        suffix, v
-                    ));
-                }
-
-                if let Some(v) = logic.close_partial_3 {
-                    lines.push(format!(
-                        "gInput_ClosePartial3_{}={}",
-                        suffix,
-                        if v { 1 } else { 0 }
-                    ));
-                }
-                if let Some(ref v) = logic.close_partial_mode_3 {
-                    lines.push(format!(
-                        "gInput_ClosePartialMode3_{}={}",
-                        suffix,
-                        encode_partial_mode(v)
-                    ));
-                }
-                if let Some(v) = logic.close_partial_profit_threshold_3 {
-                    lines.push(format!(
-                        "gInput_ClosePartialProfitThreshold3_{}={:.2}",
-                        suffix, v
-                    ));
-                }
-
-                if let Some(v) = logic.close_partial_4 {
-                    lines.push(format!(
-                        "gInput_ClosePartial4_{}={}",
-                        suffix,
-                        if v { 1 } else { 0 }
-                    ));
-                }
-                if let Some(ref v) = logic.close_partial_mode_4 {
-                    lines.push(format!(
-                        "gInput_ClosePartialMode4_{}={}",
-                        suffix,
-                        encode_partial_mode(v)
-                    ));
-                }
-                if let Some(v) = logic.close_partial_profit_threshold_4 {
-                    lines.push(format!(
-                        "gInput_ClosePartialProfitThreshold4_{}={:.2}",
-                        suffix, v
-                    ));
-                }
-
-                if let Some(tt) = &logic.trigger_type {
-                    lines.push(format!(
-                        "gInput_TriggerType_{}={}",
-                        suffix,
-                        normalize_trigger_type(tt)
-                    ));
-                }
-                let trigger_mode_out = logic
-                    .trigger_mode
-                    .as_deref()
-                    .unwrap_or("TriggerMode_OnTick");
-                lines.push(format!(
-                    "gInput_TriggerMode_{}={}",
-                    suffix,
-                    encode_trigger_mode(trigger_mode_out)
-                ));
-                if let Some(tb) = logic.trigger_bars {
-                    lines.push(format!("gInput_TriggerBars_{}={}", suffix, tb));
-                }
-                if let Some(tm) = logic.trigger_seconds {
-                    lines.push(format!("gInput_TriggerSeconds_{}={}", suffix, tm));
-                }
-                if let Some(tp) = logic.trigger_pips {
-                    lines.push(format!("gInput_TriggerPips_{}={:.1}", suffix, tp));
-                }
-
-                lines.push(String::new());
-            }
-        }
+    let general = &config.general;
+    let sections: [&dyn SectionWriter; 5] = [
+        &GeneralWriter { general, file_path },
+        &RiskWriter {
+            risk: &general.risk_management,
+            layout: SetfileLayout::Flat,
+            side: Side::Both,
+        },
+        &CleanMathWriter { general },
+        &NewsFilterWriter {
+            news: &general.news_filter,
+            layout: SetfileLayout::Flat,
+        },
+        &TimeFilterWriter {
+            time_filters: &general.time_filters,
+        },
+    ];
+    for section in sections {
+        section.write(&mut lines);
+        lines.push(String::new());
     }
 
-    // Write file
-    let legacy_content = lines.join("\n");
-    atomic_write(&sanitized_path, &legacy_content)?;
-    mirror_setfile_to_mt_common_files(&sanitized_path, &legacy_content, None);
+    // Engine and Logic configs
+    tracing::debug!(engines = config.engines.len(), "Starting engine loop");
+    let options = LogicWriteOptions {
+        include_optimization_hints,
+        trade_direction,
+    };
+    for engine in &config.engines {
+        EngineWriter { engine, options }.write(&mut lines);
+    }
 
-    Ok(())
+    lines
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(platform = %platform), err)]
 pub fn export_set_file_to_mt_common_files(
     config: MTConfig,
     platform: String,
     include_optimization_hints: bool,
 ) -> Result<String, String> {
-    let common_dir = if let Some(home) = dirs::home_dir() {
-        home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files")
-    } else {
-        return Err("Home directory not found".to_string());
-    };
-    let file_name = format!("DAAVFX_{}_Config.set", platform);
+    crate::safe_mode::ensure_writable("Exporting to Common Files")?;
+    crate::licensing::ensure_licensed("Exporting to Common Files")?;
+    let common_dir = os_paths::common_files_dir()?;
+    let file_name = naming::current().platform_set_name(&platform);
     let file_path = common_dir.join(file_name);
     let path_str = file_path.to_string_lossy().to_string();
     export_set_file(
@@ -2282,33 +1388,146 @@ pub fn export_set_file_to_mt_common_files(
         None,
         None,
         None,
+        None,
+        None,
+        None,
     )?;
     Ok(path_str)
 }
 
 /// Export massive v19 setfile format: gInput_{Group}_{Engine}{Logic}_{Direction}_{Param}
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(file_path = %file_path, platform = %platform), err)]
 pub fn export_massive_v19_setfile(
     mut config: MTConfig,
     file_path: String,
     platform: String,
     export_keymap_json: Option<bool>,
+    task_id: Option<String>,
+    redaction: Option<ExportRedaction>,
+    deterministic: Option<DeterministicExport>,
 ) -> Result<(), String> {
-    // DEBUG: Log what we received immediately
-    eprintln!("[EXPORT DEBUG] ========== EXPORT MASSIVE V19 SETFILE ==========");
-    eprintln!("[EXPORT DEBUG] Number of engines: {}", config.engines.len());
-    for (ei, engine) in config.engines.iter().enumerate() {
-        eprintln!("[EXPORT DEBUG] Engine {}: ID={}, groups={}", ei, engine.engine_id, engine.groups.len());
-        for (gi, group) in engine.groups.iter().enumerate() {
-            eprintln!("[EXPORT DEBUG]   Group {}: number={}, gps={:?}, gps_b={:?}, gps_s={:?}",
-                gi, group.group_number, group.group_power_start, group.group_power_start_b, group.group_power_start_s);
-        }
-    }
-    eprintln!("[EXPORT DEBUG] ==============================================");
+    let task = crate::task_manager().begin(task_id, "export_massive_v19_setfile");
+    let result = export_massive_v19_setfile_with_task(
+        config,
+        file_path,
+        platform,
+        export_keymap_json,
+        redaction,
+        deterministic,
+        &task,
+    );
+    crate::task_manager().finish(&task.id);
+    result
+}
 
+fn export_massive_v19_setfile_with_task(
+    config: MTConfig,
+    file_path: String,
+    platform: String,
+    export_keymap_json: Option<bool>,
+    redaction: Option<ExportRedaction>,
+    deterministic: Option<DeterministicExport>,
+    task: &crate::TaskHandle,
+) -> Result<(), String> {
     let path_buf = PathBuf::from(&file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
 
+    // Without a redaction option gInput_NewsAPIKey is written as-is
+    let (mut config, _) = redact_for_export(config, None, redaction.as_ref());
+    if let Some(deterministic) = &deterministic {
+        deterministic.prepare_config(&mut config);
+    }
+    let mut lines = render_massive_v19_setfile_lines(config, &platform, task)?;
+    if let Some(deterministic) = &deterministic {
+        lines = deterministic.finish_lines(lines);
+    }
+
+    // Write to file
+    task.check()?;
+    task.report("writing", MASSIVE_V19_TOTAL_SCOPES, MASSIVE_V19_TOTAL_SCOPES);
+    let content = lines.join("\n");
+    atomic_write(&sanitized_path, &content)?;
+
+    let mut keymap_json_for_mirror: Option<String> = None;
+    if export_keymap_json.unwrap_or(true) {
+        use std::collections::BTreeMap;
+
+        let keymap_path = PathBuf::from(format!("{}.keymap.json", file_path));
+        let sanitized_keymap_path = sanitize_and_validate_path(&keymap_path)?;
+
+        let mut map: BTreeMap<String, String> = BTreeMap::new();
+        let mut dup_keys: u32 = 0;
+        for line in &lines {
+            let s = line.trim();
+            if s.is_empty() || s.starts_with(';') {
+                continue;
+            }
+            if let Some((k, v)) = s.split_once('=') {
+                if map.insert(k.to_string(), v.to_string()).is_some() {
+                    dup_keys += 1;
+                }
+            }
+        }
+        if dup_keys > 0 {
+            return Err(format!(
+                "export_massive_v19_setfile: duplicate keys detected in output (dup_keys={})",
+                dup_keys
+            ));
+        }
+        let json = serde_json::to_string_pretty(&map).map_err(|e| e.to_string())?;
+        atomic_write(&sanitized_keymap_path, &json)?;
+        keymap_json_for_mirror = Some(json);
+    }
+
+    mirror_setfile_to_mt_common_files(
+        &sanitized_path,
+        &content,
+        keymap_json_for_mirror.as_deref(),
+    );
+
+    Ok(())
+}
+
+/// Render and write a massive v19 setfile to `path` only: no keymap and no mirror into
+/// Common Files, so tester, sweep and candidate sets never replace the live ACTIVE.set
+pub(crate) fn write_massive_v19_setfile(
+    config: MTConfig,
+    path: &Path,
+    platform: &str,
+) -> Result<(), String> {
+    let sanitized_path = sanitize_and_validate_path(&path.to_path_buf())?;
+    let task = crate::task_manager().begin(None, "write_massive_v19_setfile");
+    let lines = render_massive_v19_setfile_lines(config, platform, &task);
+    crate::task_manager().finish(&task.id);
+    atomic_write(&sanitized_path, lines?.join("\n"))
+}
+
+/// Engines x groups the massive v19 exporter walks, used for task progress
+const MASSIVE_V19_TOTAL_SCOPES: usize = 3 * 15;
+
+/// Render the massive v19 lines exactly as export_massive_v19_setfile writes them, without
+/// touching disk
+pub(crate) fn render_massive_v19_setfile_lines(
+    mut config: MTConfig,
+    platform: &str,
+    task: &crate::TaskHandle,
+) -> Result<Vec<String>, String> {
+    task.report("preparing", 0, 0);
+    tracing::debug!(engines = config.engines.len(), "Rendering massive v19 setfile");
+    for engine in &config.engines {
+        for group in &engine.groups {
+            tracing::trace!(
+                engine = %engine.engine_id,
+                group = group.group_number,
+                gps = ?group.group_power_start,
+                gps_b = ?group.group_power_start_b,
+                gps_s = ?group.group_power_start_s,
+                "Group power start"
+            );
+        }
+    }
+
     let mut lines: Vec<String> = Vec::new();
     // Temporary export diagnostics for start-level rewrite tracing.
     let mut missing_scope_count: usize = 0;
@@ -2336,11 +1555,11 @@ pub fn export_massive_v19_setfile(
                 any_hedge_mode = true;
             }
             for logic in &group.logics {
-                let mode = normalize_trading_mode(&logic.trading_mode);
-                if mode == "Reverse" {
+                let mode = logic.trading_mode.unwrap_or_default();
+                if mode == TradingMode::Reverse {
                     any_reverse_mode = true;
                 }
-                if mode == "Hedge" {
+                if mode == TradingMode::Hedge {
                     any_hedge_mode = true;
                 }
             }
@@ -2415,21 +1634,10 @@ pub fn export_massive_v19_setfile(
     
     // ===== CLEAN MATH =====
     lines.push(String::new());
-    lines.push("; === CLEAN MATH ===".to_string());
-    let key_grid_unit = "gInput_GridUnit".to_string();
-    let grid_unit_fallback = config
-        .general
-        .grid_unit
-        .map(|v| v.to_string())
-        .unwrap_or_else(|| "".to_string());
-    lines.push(format!("{}={}", key_grid_unit, grid_unit_fallback));
-    let key_pip_factor = "gInput_PipFactor".to_string();
-    let pip_factor_fallback = config
-        .general
-        .pip_factor
-        .map(|v| v.to_string())
-        .unwrap_or_else(|| "".to_string());
-    lines.push(format!("{}={}", key_pip_factor, pip_factor_fallback));
+    CleanMathWriter {
+        general: &config.general,
+    }
+    .write(&mut lines);
     
     // ===== GROUP THRESHOLDS =====
     lines.push(String::new());
@@ -2460,98 +1668,36 @@ pub fn export_massive_v19_setfile(
     
     // ===== RISK MANAGEMENT =====
     lines.push(String::new());
-    lines.push("; === RISK MANAGEMENT ===".to_string());
-    let rm = &config.general.risk_management;
-    lines.push(format!("gInput_RiskManagementEnabled={}", if rm.enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_UseSpreadFilter={}", if rm.spread_filter_enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_MaxSpreadPoints={:.1}", rm.max_spread_points));
-    lines.push(format!("gInput_UseEquityStop={}", if rm.equity_stop_enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_EquityStopValue={:.1}", rm.equity_stop_value));
-    lines.push(format!("gInput_UseDrawdownStop={}", if rm.drawdown_stop_enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_MaxDrawdownPercent={:.1}", rm.max_drawdown_percent));
-    let risk_action_raw = rm
-        .risk_action
-        .as_ref()
-        .map(|s| trigger_action_to_int(s).to_string())
-        .unwrap_or_else(|| "".to_string());
-    let key_risk_action = "gInput_RiskAction".to_string();
-    lines.push(format!("{}={}", key_risk_action, risk_action_raw));
+    RiskWriter {
+        risk: &config.general.risk_management,
+        layout: SetfileLayout::Massive,
+        side: Side::Both,
+    }
+    .write(&mut lines);
     
     // ===== RISK MANAGEMENT BUY/SELL =====
-    if let Some(rm_b) = &config.general.risk_management_b {
-        lines.push(String::new());
-        lines.push("; === RISK MANAGEMENT BUY ===".to_string());
-        lines.push(format!("gInput_RiskManagementEnabled_Buy={}", if rm_b.enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_UseSpreadFilter_Buy={}", if rm_b.spread_filter_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_MaxSpreadPoints_Buy={:.1}", rm_b.max_spread_points));
-        lines.push(format!("gInput_UseEquityStop_Buy={}", if rm_b.equity_stop_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_EquityStopValue_Buy={:.1}", rm_b.equity_stop_value));
-        lines.push(format!("gInput_UseDrawdownStop_Buy={}", if rm_b.drawdown_stop_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_MaxDrawdownPercent_Buy={:.1}", rm_b.max_drawdown_percent));
-    }
-    if let Some(rm_s) = &config.general.risk_management_s {
-        lines.push(String::new());
-        lines.push("; === RISK MANAGEMENT SELL ===".to_string());
-        lines.push(format!("gInput_RiskManagementEnabled_Sell={}", if rm_s.enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_UseSpreadFilter_Sell={}", if rm_s.spread_filter_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_MaxSpreadPoints_Sell={:.1}", rm_s.max_spread_points));
-        lines.push(format!("gInput_UseEquityStop_Sell={}", if rm_s.equity_stop_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_EquityStopValue_Sell={:.1}", rm_s.equity_stop_value));
-        lines.push(format!("gInput_UseDrawdownStop_Sell={}", if rm_s.drawdown_stop_enabled { 1 } else { 0 }));
-        lines.push(format!("gInput_MaxDrawdownPercent_Sell={:.1}", rm_s.max_drawdown_percent));
+    for (risk, side) in [
+        (&config.general.risk_management_b, Side::Buy),
+        (&config.general.risk_management_s, Side::Sell),
+    ] {
+        if let Some(risk) = risk {
+            lines.push(String::new());
+            RiskWriter {
+                risk,
+                layout: SetfileLayout::Massive,
+                side,
+            }
+            .write(&mut lines);
+        }
     }
     
     // ===== NEWS FILTER =====
     lines.push(String::new());
-    lines.push("; === NEWS FILTER ===".to_string());
-    let nf = &config.general.news_filter;
-    lines.push(format!("gInput_EnableNewsFilter={}", if nf.enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsFilterEnabled={}", if nf.enabled { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsAPIKey={}", nf.api_key));
-    lines.push(format!("gInput_NewsAPIURL={}", nf.api_url));
-    lines.push(format!("gInput_NewsFilterCountries={}", nf.countries));
-    lines.push(format!("gInput_NewsImpactLevel={}", nf.impact_level));
-    lines.push(format!("gInput_MinutesBeforeNews={}", nf.minutes_before));
-    lines.push(format!("gInput_MinutesAfterNews={}", nf.minutes_after));
-    // Convert 3 boolean fields to news action enum
-    // stop_ea=false -> 0 (None)
-    // stop_ea=true, close=false, restart=false -> 2 (StopEA_KeepTrades)
-    // stop_ea=true, close=false, restart=true -> 7 (PauseEA_KeepTrades)
-    // stop_ea=true, close=true, restart=false -> 5 (StopEA_CloseTrades)
-    // stop_ea=true, close=true, restart=true -> 6 (PauseEA_CloseTrades)
-    let news_action = if !nf.stop_ea {
-        0 // TriggerAction_None
-    } else if nf.close_trades && nf.auto_restart {
-        6 // TriggerAction_PauseEA_CloseTrades
-    } else if nf.close_trades && !nf.auto_restart {
-        5 // TriggerAction_StopEA_CloseTrades
-    } else if !nf.close_trades && nf.auto_restart {
-        7 // TriggerAction_PauseEA_KeepTrades
-    } else {
-        2 // TriggerAction_StopEA_KeepTrades (default)
-    };
-    lines.push(format!("gInput_NewsAction={}", news_action));
-    lines.push(format!("gInput_NewsStopEA={}", if nf.stop_ea { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsCloseTrades={}", if nf.close_trades { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsAutoRestart={}", if nf.auto_restart { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsCheckInterval={}", nf.check_interval));
-    lines.push(format!("gInput_FilterHighImpactOnly={}", if nf.filter_high_only { 1 } else { 0 }));
-    lines.push(format!("gInput_FilterWeekendNews={}", if nf.filter_weekends { 1 } else { 0 }));
-    lines.push(format!("gInput_UseLocalNewsCache={}", if nf.use_local_cache { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsCacheDuration={}", nf.cache_duration));
-    lines.push(format!("gInput_NewsFallbackOnError={}", nf.fallback_on_error));
-    lines.push(format!("gInput_FilterCurrencies={}", nf.filter_currencies));
-    lines.push(format!("gInput_IncludeSpeeches={}", if nf.include_speeches { 1 } else { 0 }));
-    lines.push(format!("gInput_IncludeReports={}", if nf.include_reports { 1 } else { 0 }));
-    lines.push(format!("gInput_NewsVisualIndicator={}", if nf.visual_indicator { 1 } else { 0 }));
-    lines.push(format!("gInput_AlertBeforeNews={}", if nf.alert_before_news { 1 } else { 0 }));
-    lines.push(format!("gInput_AlertMinutesBefore={}", nf.alert_minutes));
-    let key_news_calendar = "gInput_NewsCalendarFile".to_string();
-    let news_calendar_fallback = nf
-        .calendar_file
-        .clone()
-        .unwrap_or_else(|| "".to_string());
-    lines.push(format!("{}={}", key_news_calendar, news_calendar_fallback));
+    NewsFilterWriter {
+        news: &config.general.news_filter,
+        layout: SetfileLayout::Massive,
+    }
+    .write(&mut lines);
     
     // ===== NEWS FILTER BUY/SELL =====
     if let Some(nf_b) = &config.general.news_filter_b {
@@ -2618,7 +1764,7 @@ pub fn export_massive_v19_setfile(
         lines.push(format!(
             "gInput_Session{}Action={}",
             s.session_number,
-            trigger_action_to_int(&s.action)
+            trigger_action_code(&s.action)
         ));
     }
     
@@ -2650,7 +1796,7 @@ pub fn export_massive_v19_setfile(
             lines.push(format!(
                 "gInput_Session{}Action_Buy={}",
                 s.session_number,
-                trigger_action_to_int(&s.action)
+                trigger_action_code(&s.action)
             ));
         }
     }
@@ -2681,27 +1827,20 @@ pub fn export_massive_v19_setfile(
             lines.push(format!(
                 "gInput_Session{}Action_Sell={}",
                 s.session_number,
-                trigger_action_to_int(&s.action)
+                trigger_action_code(&s.action)
             ));
         }
     }
     
     lines.push(String::new());
 
-    let encode_trail_method = |raw: &str| -> i32 {
-        let upper = raw.to_uppercase();
-        if upper.contains("AVG") {
-            1
-        } else {
-            0
-        }
-    };
-
     let logic_names = ["POWER", "REPOWER", "SCALPER", "STOPPER", "STO", "SCA", "RPO"];
     let directions = ["Buy", "Sell"];
 
     let engine_ids = ["A", "B", "C"];
-    for engine_id in &engine_ids {
+    let total_scopes = MASSIVE_V19_TOTAL_SCOPES;
+    for (engine_idx, engine_id) in engine_ids.iter().enumerate() {
+        task.check()?;
         let Some(engine) = config.engines.iter().find(|e| e.engine_id == *engine_id) else {
             missing_scope_count += 1;
             if missing_scope_samples.len() < 24 {
@@ -2710,6 +1849,11 @@ pub fn export_massive_v19_setfile(
             continue;
         };
         for group_num in 1..=15 {
+            task.report(
+                &format!("engine_{}_group_{}", engine_id, group_num),
+                engine_idx * 15 + (group_num as usize - 1),
+                total_scopes,
+            );
             let group_num_u8 = group_num as u8;
             let Some(group) = engine.groups.iter().find(|g| g.group_number == group_num_u8) else {
                 missing_scope_count += 1;
@@ -2823,9 +1967,9 @@ pub fn export_massive_v19_setfile(
                         enabled_rows_count += 1;
                     }
                     let effective_mode = if is_engine_a_power(&engine.engine_id, logic_name) {
-                        "Counter Trend".to_string()
+                        TradingMode::CounterTrend
                     } else {
-                        normalize_trading_mode(&logic.trading_mode)
+                        logic.trading_mode.unwrap_or_default()
                     };
 
                     let (
@@ -2835,8 +1979,8 @@ pub fn export_massive_v19_setfile(
                         export_hedge_reference,
                         export_reverse_scale,
                         export_hedge_scale,
-                    ) = match effective_mode.as_str() {
-                        "Hedge" => (
+                    ) = match effective_mode {
+                        TradingMode::Hedge => (
                             false,
                             true,
                             "Logic_None".to_string(),
@@ -2848,7 +1992,7 @@ pub fn export_massive_v19_setfile(
                             100.0,
                             logic.hedge_scale,
                         ),
-                        "Reverse" => (
+                        TradingMode::Reverse => (
                             true,
                             false,
                             if logic.reverse_reference.trim().is_empty() {
@@ -2935,10 +2079,10 @@ pub fn export_massive_v19_setfile(
 
                     let key_grid_behavior =
                         format!("gInput_{}_{}_{}_GridBehavior", group_num, v19_suffix, direction);
-                    let behavior_val = match effective_mode.as_str() {
-                        "Hedge" => 1,
-                        "Reverse" => 2,
-                        _ => 0, // "Counter Trend" or default
+                    let behavior_val = match effective_mode {
+                        TradingMode::Hedge => 1,
+                        TradingMode::Reverse => 2,
+                        TradingMode::CounterTrend => 0,
                     };
                     lines.push(format!(
                         "{}={}",
@@ -2951,7 +2095,7 @@ pub fn export_massive_v19_setfile(
                         group_num,
                         v19_suffix,
                         direction,
-                        encode_trail_method(&logic.trail_method)
+                        logic.trail_method.ea_code()
                     ));
                     lines.push(format!(
                         "gInput_{}_{}_{}_TrailValue={:.1}",
@@ -2977,14 +2121,14 @@ pub fn export_massive_v19_setfile(
                         group_num,
                         v19_suffix,
                         direction,
-                        encode_trail_step_method(&logic.trail_step_method)
+                        logic.trail_step_method.ea_code()
                     ));
                     lines.push(format!(
                         "gInput_{}_{}_{}_TrailStepMode={}",
                         group_num,
                         v19_suffix,
                         direction,
-                        encode_trail_step_mode(&logic.trail_step_mode)
+                        logic.trail_step_mode.ea_code()
                     ));
                     lines.push(format!(
                         "gInput_{}_{}_{}_TrailStepCycle={}",
@@ -3010,20 +2154,20 @@ pub fn export_massive_v19_setfile(
                         logic.trail_step_7,
                     ];
                     let step_methods = [
-                        logic.trail_step_method_2.as_deref(),
-                        logic.trail_step_method_3.as_deref(),
-                        logic.trail_step_method_4.as_deref(),
-                        logic.trail_step_method_5.as_deref(),
-                        logic.trail_step_method_6.as_deref(),
-                        logic.trail_step_method_7.as_deref(),
+                        logic.trail_step_method_2,
+                        logic.trail_step_method_3,
+                        logic.trail_step_method_4,
+                        logic.trail_step_method_5,
+                        logic.trail_step_method_6,
+                        logic.trail_step_method_7,
                     ];
                     let step_modes = [
-                        logic.trail_step_mode_2.as_deref(),
-                        logic.trail_step_mode_3.as_deref(),
-                        logic.trail_step_mode_4.as_deref(),
-                        logic.trail_step_mode_5.as_deref(),
-                        logic.trail_step_mode_6.as_deref(),
-                        logic.trail_step_mode_7.as_deref(),
+                        logic.trail_step_mode_2,
+                        logic.trail_step_mode_3,
+                        logic.trail_step_mode_4,
+                        logic.trail_step_mode_5,
+                        logic.trail_step_mode_6,
+                        logic.trail_step_mode_7,
                     ];
                     let step_cycles = [
                         logic.trail_step_cycle_2,
@@ -3056,7 +2200,7 @@ pub fn export_massive_v19_setfile(
                             v19_suffix,
                             direction,
                             n,
-                            encode_trail_step_method(step_methods[i].unwrap_or(&logic.trail_step_method))
+                            step_methods[i].unwrap_or(logic.trail_step_method).ea_code()
                         ));
                         lines.push(format!(
                             "gInput_{}_{}_{}_TrailStepMode{}={}",
@@ -3064,7 +2208,7 @@ pub fn export_massive_v19_setfile(
                             v19_suffix,
                             direction,
                             n,
-                            encode_trail_step_mode(step_modes[i].unwrap_or(&logic.trail_step_mode))
+                            step_modes[i].unwrap_or(logic.trail_step_mode).ea_code()
                         ));
                         let key_step_cycle = format!(
                             "gInput_{}_{}_{}_TrailStepCycle{}",
@@ -3098,8 +2242,7 @@ pub fn export_massive_v19_setfile(
                         format!("gInput_{}_{}_{}_TriggerType", group_num, v19_suffix, direction);
                     let trigger_type_fallback = logic
                         .trigger_type
-                        .as_deref()
-                        .map(normalize_trigger_type)
+                        .map(|trigger| trigger.ea_code().to_string())
                         .unwrap_or_else(|| "".to_string());
                     if trigger_type_fallback == "0" {
                         trigger_type_immediate_count += 1;
@@ -3111,12 +2254,8 @@ pub fn export_massive_v19_setfile(
                     ));
                     let key_trigger_mode =
                         format!("gInput_{}_{}_{}_TriggerMode", group_num, v19_suffix, direction);
-                    let trigger_mode_fallback = logic
-                        .trigger_mode
-                        .as_deref()
-                        .map(encode_trigger_mode)
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| encode_trigger_mode("TriggerMode_OnTick").to_string());
+                    let trigger_mode_fallback =
+                        logic.trigger_mode.unwrap_or_default().ea_code().to_string();
                     if trigger_mode_fallback == "0" {
                         trigger_mode_ontick_count += 1;
                     }
@@ -3211,10 +2350,10 @@ pub fn export_massive_v19_setfile(
 
                     let partial_enabled = [Some(logic.close_partial), logic.close_partial_2, logic.close_partial_3, logic.close_partial_4];
                     let partial_modes = [
-                        Some(logic.close_partial_mode.as_str()),
-                        logic.close_partial_mode_2.as_deref(),
-                        logic.close_partial_mode_3.as_deref(),
-                        logic.close_partial_mode_4.as_deref(),
+                        Some(logic.close_partial_mode),
+                        logic.close_partial_mode_2,
+                        logic.close_partial_mode_3,
+                        logic.close_partial_mode_4,
                     ];
                     let partial_profit_thresholds = [
                         Some(logic.close_partial_profit_threshold),
@@ -3237,7 +2376,7 @@ pub fn export_massive_v19_setfile(
                             .map(|v| if v { "1".to_string() } else { "0".to_string() })
                             .unwrap_or_else(|| "".to_string());
                         let mode_value = partial_modes[idx]
-                            .map(|v| encode_partial_mode(v).to_string())
+                            .map(|v| v.ea_code().to_string())
                             .unwrap_or_else(|| "".to_string());
                         let threshold_value = partial_profit_thresholds[idx]
                             .map(|v| format!("{:.2}", v))
@@ -3351,98 +2490,188 @@ pub fn export_massive_v19_setfile(
     let group_power_start_min = group_power_start_values.iter().min().copied().unwrap_or(0);
     let group_power_start_max = group_power_start_values.iter().max().copied().unwrap_or(0);
     let total_directional_rows: usize = 15 * 3 * 7 * 2;
-    println!(
-        "[SETFILE][EXPORT] Massive v19 diagnostics: missing_scope={}, start_under4_non_power={}, start_missing_non_power={}, enabled_rows={}/{}, trigger_immediate={}/{}, trigger_ontick={}/{}, gps_count={}, gps_min={}, gps_max={}, key_lines={}",
-        missing_scope_count,
-        start_level_under4_count,
-        start_level_missing_count,
-        enabled_rows_count,
-        total_directional_rows,
-        trigger_type_immediate_count,
-        total_directional_rows,
-        trigger_mode_ontick_count,
-        total_directional_rows,
-        group_power_start_values.len(),
-        group_power_start_min,
-        group_power_start_max,
-        key_lines
+    tracing::info!(
+        missing_scope = missing_scope_count,
+        start_under4_non_power = start_level_under4_count,
+        start_missing_non_power = start_level_missing_count,
+        enabled_rows = enabled_rows_count,
+        trigger_immediate = trigger_type_immediate_count,
+        trigger_ontick = trigger_mode_ontick_count,
+        directional_rows = total_directional_rows,
+        gps_count = group_power_start_values.len(),
+        gps_min = group_power_start_min,
+        gps_max = group_power_start_max,
+        key_lines,
+        "Massive v19 diagnostics"
     );
     if !missing_scope_samples.is_empty() {
-        println!(
-            "[SETFILE][EXPORT] missing-scope samples: {}",
-            missing_scope_samples.join(" | ")
-        );
+        tracing::warn!(samples = %missing_scope_samples.join(" | "), "Missing-scope rows");
     }
     if !start_level_under4_samples.is_empty() {
-        println!(
-            "[SETFILE][EXPORT] start<4 samples: {}",
-            start_level_under4_samples.join(" | ")
-        );
+        tracing::warn!(samples = %start_level_under4_samples.join(" | "), "Start level below 4");
     }
     if !start_level_missing_samples.is_empty() {
-        println!(
-            "[SETFILE][EXPORT] start missing samples: {}",
-            start_level_missing_samples.join(" | ")
-        );
+        tracing::warn!(samples = %start_level_missing_samples.join(" | "), "Start level missing");
     }
-    // Write to file
-    let content = lines.join("\n");
-    atomic_write(&sanitized_path, &content)?;
+    Ok(lines)
+}
 
-    let mut keymap_json_for_mirror: Option<String> = None;
-    if export_keymap_json.unwrap_or(true) {
-        use std::collections::BTreeMap;
+// =============================================================================
+// SET FILE PREVIEW
+// =============================================================================
 
-        let keymap_path = PathBuf::from(format!("{}.keymap.json", file_path));
-        let sanitized_keymap_path = sanitize_and_validate_path(&keymap_path)?;
+/// Options for render_set_preview; mirrors the arguments of the matching export command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPreviewOptions {
+    /// Target path; the flat formats embed it as gInput_ConfigFileName
+    pub file_path: Option<String>,
+    pub platform: Option<String>,
+    #[serde(default)]
+    pub include_optimization_hints: bool,
+    pub trade_direction: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub comments: Option<String>,
+    pub redaction: Option<ExportRedaction>,
+    pub deterministic: Option<DeterministicExport>,
+    /// Cut the preview after this many lines (whole file when unset)
+    pub max_lines: Option<usize>,
+}
 
-        let mut map: BTreeMap<String, String> = BTreeMap::new();
-        let mut dup_keys: u32 = 0;
-        for line in &lines {
-            let s = line.trim();
-            if s.is_empty() || s.starts_with(';') {
-                continue;
-            }
-            if let Some((k, v)) = s.split_once('=') {
-                if map.insert(k.to_string(), v.to_string()).is_some() {
-                    dup_keys += 1;
-                }
-            }
-        }
-        if dup_keys > 0 {
-            return Err(format!(
-                "export_massive_v19_setfile: duplicate keys detected in output (dup_keys={})",
-                dup_keys
-            ));
+/// A "; === NAME ===" section header and the 0-based line it starts on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPreviewSection {
+    pub title: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPreview {
+    pub format: String,
+    pub content: String,
+    pub total_lines: usize,
+    pub truncated: bool,
+    /// Sections of the full output, including those cut from `content`
+    pub sections: Vec<SetPreviewSection>,
+}
+
+fn preview_sections(lines: &[String]) -> Vec<SetPreviewSection> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let title = text.trim().strip_prefix("; ===")?.strip_suffix("===")?.trim();
+            Some(SetPreviewSection {
+                title: title.to_string(),
+                line,
+            })
+        })
+        .collect()
+}
+
+/// Build the preview from rendered lines, cutting after `max_lines` with a marker that
+/// names the sections left out
+fn build_set_preview(format: &str, lines: Vec<String>, max_lines: Option<usize>) -> SetPreview {
+    let total_lines = lines.len();
+    let sections = preview_sections(&lines);
+    let limit = max_lines.unwrap_or(total_lines);
+
+    let content = if total_lines > limit {
+        let hidden: Vec<&str> = sections
+            .iter()
+            .filter(|section| section.line >= limit)
+            .map(|section| section.title.as_str())
+            .collect();
+        let mut shown = lines[..limit].to_vec();
+        shown.push(format!("; ... {} more lines", total_lines - limit));
+        if !hidden.is_empty() {
+            shown.push(format!("; ... sections not shown: {}", hidden.join(", ")));
         }
-        let json = serde_json::to_string_pretty(&map).map_err(|e| e.to_string())?;
-        atomic_write(&sanitized_keymap_path, &json)?;
-        keymap_json_for_mirror = Some(json);
+        shown.join("\n")
+    } else {
+        lines.join("\n")
+    };
+
+    SetPreview {
+        format: format.to_string(),
+        content,
+        total_lines,
+        truncated: total_lines > limit,
+        sections,
     }
+}
 
-    mirror_setfile_to_mt_common_files(
-        &sanitized_path,
-        &content,
-        keymap_json_for_mirror.as_deref(),
-    );
+/// Render the text export_set_file would write for `config` in any registered setfile
+/// format ("v17", "massive_v19"...), without touching disk
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn render_set_preview(
+    config: MTConfig,
+    format: String,
+    options: Option<SetPreviewOptions>,
+) -> Result<SetPreview, String> {
+    let options = options.unwrap_or_default();
+    let platform = options.platform.unwrap_or_else(|| config.platform.clone());
+    let format = setfile_format::find_format(&format)?;
+
+    let (mut config, comments) =
+        redact_for_export(config, options.comments, options.redaction.as_ref());
+    if let Some(deterministic) = &options.deterministic {
+        deterministic.prepare_config(&mut config);
+    }
+    let render_options = setfile_format::RenderOptions {
+        platform,
+        file_path: options.file_path.unwrap_or_default(),
+        include_optimization_hints: options.include_optimization_hints,
+        trade_direction: options.trade_direction,
+        tags: options.tags,
+        comments,
+    };
+    let task = crate::task_manager().begin(None, "render_set_preview");
+    let lines = format.render(config, &render_options, &task);
+    crate::task_manager().finish(&task.id);
+    let lines = lines?;
+    let lines = match &options.deterministic {
+        Some(deterministic) => deterministic.finish_lines(lines),
+        None => lines,
+    };
 
-    Ok(())
+    Ok(build_set_preview(format.id(), lines, options.max_lines))
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(platform = %platform), err)]
 pub fn export_massive_v19_setfile_to_mt_common_files(
     config: MTConfig,
     platform: String,
     file_name: Option<String>,
 ) -> Result<String, String> {
+    crate::safe_mode::ensure_writable("Exporting to Common Files")?;
+    crate::licensing::ensure_licensed("Exporting to Common Files")?;
     let common_dir = get_mt_common_files_dir()?;
     let setfile_name = normalize_common_setfile_name(file_name)?;
     let file_path = common_dir.join(&setfile_name);
     let path_str = file_path.to_string_lossy().to_string();
-    export_massive_v19_setfile(config, path_str.clone(), platform, Some(true))?;
+    export_massive_v19_setfile(config, path_str.clone(), platform, Some(true), None, None, None)?;
     Ok(path_str)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSetExport {
+    pub path: String,
+    pub active_set_path: String,
+    /// SHA-256 of the active set file this export replaced
+    pub previous_hash: Option<String>,
+    /// Where that file was backed up (.bak1)
+    pub backup_path: Option<String>,
+    /// True when this export was folded into a pending one that is written
+    /// once the debounce window closes; previous_hash/backup_path are then empty
+    #[serde(default)]
+    pub coalesced: bool,
+}
+
 #[derive(Serialize)]
 pub struct ActiveSetStatus {
     pub path: String,
@@ -3453,12 +2682,8 @@ pub struct ActiveSetStatus {
     pub last_modified_ms: Option<u64>,
 }
 
-fn get_mt_common_files_dir() -> Result<PathBuf, String> {
-    if let Some(home) = dirs::home_dir() {
-        Ok(home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files"))
-    } else {
-        Err("Home directory not found".to_string())
-    }
+pub(crate) fn get_mt_common_files_dir() -> Result<PathBuf, String> {
+    os_paths::common_files_dir()
 }
 
 fn mirror_setfile_to_mt_common_files(
@@ -3466,47 +2691,72 @@ fn mirror_setfile_to_mt_common_files(
     set_content: &str,
     keymap_json: Option<&str>,
 ) {
+    if crate::safe_mode::is_enabled() {
+        tracing::info!("Setfile mirror skipped: safe mode is on");
+        return;
+    }
     let common_dir = match get_mt_common_files_dir() {
         Ok(dir) => dir,
         Err(err) => {
-            println!("[SETFILE][MIRROR] WARN: cannot resolve MT common files dir: {}", err);
+            tracing::warn!(error = %err, "Setfile mirror: cannot resolve MT common files dir");
             return;
         }
     };
 
     if let Err(err) = fs::create_dir_all(&common_dir) {
-        println!(
-            "[SETFILE][MIRROR] WARN: cannot create common files dir '{}': {}",
-            common_dir.display(),
-            err
+        tracing::warn!(
+            dir = %common_dir.display(),
+            error = %err,
+            "Setfile mirror: cannot create common files dir"
         );
         return;
     }
+    mirror_setfile_into(&common_dir, source_set_path, set_content, keymap_json);
+}
 
+fn mirror_setfile_into(
+    common_dir: &Path,
+    source_set_path: &PathBuf,
+    set_content: &str,
+    keymap_json: Option<&str>,
+) {
     let source_name = source_set_path
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("config.set")
         .to_string();
 
+    let active_set_name = naming::current().active_set_name;
     let mut target_names: Vec<String> = vec![source_name.clone()];
-    if !source_name.eq_ignore_ascii_case("ACTIVE.set") {
-        target_names.push("ACTIVE.set".to_string());
+    if !source_name.eq_ignore_ascii_case(&active_set_name) {
+        target_names.push(active_set_name.clone());
     }
 
     for target_name in target_names {
         let target_set = common_dir.join(&target_name);
+        // The live EA reads the active set; keep what it was running on. When the
+        // source is the active set itself it has already been overwritten by the caller.
+        let is_source = fs::canonicalize(&target_set)
+            .ok()
+            .is_some_and(|target| fs::canonicalize(source_set_path).ok() == Some(target));
+        if target_name.eq_ignore_ascii_case(&active_set_name) && !is_source {
+            if let Err(err) = active_backup::rotate_backups(&target_set, ACTIVE_SET_BACKUPS) {
+                tracing::warn!(
+                    file_path = %target_set.display(),
+                    error = %err,
+                    "Setfile mirror skipped: cannot back up the active set"
+                );
+                continue;
+            }
+        }
         match atomic_write(&target_set, set_content) {
-            Ok(_) => println!(
-                "[SETFILE][MIRROR] set mirrored to '{}'",
-                target_set.display()
-            ),
+            Ok(_) => tracing::info!(file_path = %target_set.display(), "Setfile mirrored"),
             Err(err) => {
-                println!(
-                    "[SETFILE][MIRROR] WARN: failed to mirror '{}' to '{}': {}",
-                    source_set_path.display(),
-                    target_set.display(),
-                    err
+                tracing::warn!(
+                    source = %source_set_path.display(),
+                    file_path = %target_set.display(),
+                    error = %err,
+                    "Setfile mirror failed"
                 );
                 continue;
             }
@@ -3515,16 +2765,13 @@ fn mirror_setfile_to_mt_common_files(
         if let Some(json) = keymap_json {
             let target_keymap = common_dir.join(format!("{}.keymap.json", target_name));
             if let Err(err) = atomic_write(&target_keymap, json) {
-                println!(
-                    "[SETFILE][MIRROR] WARN: failed to mirror keymap '{}': {}",
-                    target_keymap.display(),
-                    err
+                tracing::warn!(
+                    file_path = %target_keymap.display(),
+                    error = %err,
+                    "Keymap mirror failed"
                 );
             } else {
-                println!(
-                    "[SETFILE][MIRROR] keymap mirrored to '{}'",
-                    target_keymap.display()
-                );
+                tracing::info!(file_path = %target_keymap.display(), "Keymap mirrored");
             }
         }
     }
@@ -3552,9 +2799,9 @@ fn normalize_common_setfile_name(file_name: Option<String>) -> Result<String, St
 }
 
 fn _get_mt4_common_files_dir() -> Result<PathBuf, String> {
-    if let Some(home) = dirs::home_dir() {
-        let base_path = home.join("AppData\\Roaming\\MetaQuotes\\Terminal");
-        let common_files = base_path.join("Common\\Files");
+    if let Ok(metaquotes) = os_paths::metaquotes_dir() {
+        let base_path = metaquotes.join("Terminal");
+        let common_files = base_path.join("Common").join("Files");
         if common_files.exists() {
             return Ok(common_files);
         }
@@ -3576,9 +2823,9 @@ fn _get_mt4_common_files_dir() -> Result<PathBuf, String> {
 }
 
 fn _get_mt5_common_files_dir() -> Result<PathBuf, String> {
-    if let Some(home) = dirs::home_dir() {
-        let base_path = home.join("AppData\\Roaming\\MetaQuotes\\Terminal64");
-        let common_files = base_path.join("Common\\Files");
+    if let Ok(metaquotes) = os_paths::metaquotes_dir() {
+        let base_path = metaquotes.join("Terminal64");
+        let common_files = base_path.join("Common").join("Files");
         if common_files.exists() {
             return Ok(common_files);
         }
@@ -3599,30 +2846,51 @@ fn _get_mt5_common_files_dir() -> Result<PathBuf, String> {
     }
 }
 
-fn decode_setfile_bytes(bytes: Vec<u8>) -> Result<String, String> {
-    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
-        let u16_vec: Vec<u16> = bytes[2..]
-            .chunks_exact(2)
-            .map(|c| u16::from_le_bytes([c[0], c[1]]))
-            .collect();
-        String::from_utf16(&u16_vec).map_err(|e| format!("Failed to parse UTF-16 .set file: {}", e))
-    } else {
-        String::from_utf8(bytes)
-            .map_err(|e| format!("Failed to parse .set file (not UTF-8 or UTF-16 LE): {}", e))
-    }
-}
-
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(platform = %platform), err)]
 pub fn export_active_set_file_to_mt_common_files(
     config: MTConfig,
     platform: String,
     include_optimization_hints: bool,
     file_name: Option<String>,
-) -> Result<String, String> {
+) -> Result<ActiveSetExport, String> {
+    crate::safe_mode::ensure_writable("Exporting the active set file")?;
+    crate::licensing::ensure_licensed("Exporting the active set file")?;
     let common_dir = get_mt_common_files_dir()?;
     let setfile_name = normalize_common_setfile_name(file_name)?;
     let file_path = common_dir.join(&setfile_name);
     let path_str = file_path.to_string_lossy().to_string();
+    let active_path = active_backup::active_set_path(None)?;
+
+    // Slider drags send an export per tick; collapse them per target file
+    let target = path_str.clone();
+    let submitted = export_debounce::submit(&target, export_debounce::window(), move || {
+        write_active_set_file(config, platform, include_optimization_hints, path_str)
+    });
+    match submitted {
+        export_debounce::Submitted::Written(result) => result,
+        export_debounce::Submitted::Coalesced => Ok(ActiveSetExport {
+            path: target,
+            active_set_path: active_path.to_string_lossy().to_string(),
+            previous_hash: None,
+            backup_path: None,
+            coalesced: true,
+        }),
+    }
+}
+
+fn write_active_set_file(
+    config: MTConfig,
+    platform: String,
+    include_optimization_hints: bool,
+    path_str: String,
+) -> Result<ActiveSetExport, String> {
+    // Deferred writes run after the window; safe mode may have been turned on since
+    crate::safe_mode::ensure_writable("Exporting the active set file")?;
+    // export_set_file mirrors into the active set file; keep what the EA was running
+    let active_path = active_backup::active_set_path(None)?;
+    let previous_hash = active_backup::rotate_backups(&active_path, ACTIVE_SET_BACKUPS)?;
+
     export_set_file(
         config,
         path_str.clone(),
@@ -3631,16 +2899,30 @@ pub fn export_active_set_file_to_mt_common_files(
         None,
         None,
         None,
+        None,
+        None,
+        None,
     )?;
-    Ok(path_str)
+    Ok(ActiveSetExport {
+        path: path_str,
+        active_set_path: active_path.to_string_lossy().to_string(),
+        backup_path: previous_hash
+            .as_ref()
+            .map(|_| active_backup::backup_path(&active_path, 1).to_string_lossy().to_string()),
+        previous_hash,
+        coalesced: false,
+    })
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(file_path = %source_file_path, terminal = %terminal_type), err)]
 pub fn _export_vault_file_to_mt_common_files(
     source_file_path: String,
     terminal_type: String,
     custom_common_files_path: Option<String>,
 ) -> Result<String, String> {
+    crate::safe_mode::ensure_writable("Exporting to Common Files")?;
+    crate::licensing::ensure_licensed("Exporting to Common Files")?;
     // Read the source file from vault
     let source_path = PathBuf::from(&source_file_path);
     if !source_path.exists() {
@@ -3652,7 +2934,7 @@ pub fn _export_vault_file_to_mt_common_files(
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Config");
-    let target_name = format!("DAAVFX_{}.set", file_stem);
+    let target_name = naming::current().prefixed(&format!("{}.set", file_stem));
 
     // Get MT common files directory - use custom path if provided, otherwise auto-detect
     let common_dir = if let Some(custom_path) = custom_common_files_path {
@@ -3770,20 +3052,44 @@ pub fn get_active_set_status(file_name: Option<String>) -> Result<ActiveSetStatu
     })
 }
 
-/// Import config from MT4/MT5 .set file format
+/// Import config from MT4/MT5 .set file format. `format` forces one of the registered
+/// setfile formats ("legacy", "v17", "massive_v19", "tester_ini"); detected when unset.
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
-pub async fn import_set_file(file_path: String) -> Result<MTConfig, String> {
-    println!("[SETFILE] Rust: Importing setfile: {}", file_path);
+pub async fn import_set_file(
+    file_path: String,
+    task_id: Option<String>,
+    format: Option<String>,
+) -> Result<MTConfig, String> {
+    let task = crate::task_manager().begin(task_id, "import_set_file");
+    let span = import_log::import_span("set", &file_path);
+    let task_for_import = task.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        span.in_scope(|| import_set_file_logged(&file_path, format.as_deref(), &task_for_import))
+    })
+    .await
+    .map_err(|e| format!("Setfile import task failed: {}", e))
+    .and_then(|r| r);
+    crate::task_manager().finish(&task.id);
+    result
+}
+
+fn import_set_file_logged(
+    file_path: &str,
+    format: Option<&str>,
+    task: &crate::TaskHandle,
+) -> Result<MTConfig, String> {
+    tracing::info!(file_path, "Importing setfile");
+    task.report("reading", 0, 0);
 
     // Sanitize and validate the file path
-    let path_buf = PathBuf::from(&file_path);
+    let path_buf = PathBuf::from(file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
 
     // Check file size (allow large setfiles for massive input configs)
     let metadata =
         fs::metadata(&sanitized_path).map_err(|e| format!("Failed to get file metadata: {}", e))?;
     let file_size = metadata.len();
-    println!("[SETFILE] Rust: File size: {} bytes", file_size);
+    tracing::debug!("File size: {} bytes", file_size);
 
     if metadata.len() > 50 * 1024 * 1024 {
         return Err("File too large (max 50MB)".to_string());
@@ -3792,30 +3098,70 @@ pub async fn import_set_file(file_path: String) -> Result<MTConfig, String> {
     let bytes =
         fs::read(&sanitized_path).map_err(|e| format!("Failed to read .set file: {}", e))?;
 
-    // Handle UTF-16 LE (Common in MT4/MT5)
-    let content = if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
-        let u16_vec: Vec<u16> = bytes[2..]
-            .chunks_exact(2)
-            .map(|c| u16::from_le_bytes([c[0], c[1]]))
-            .collect();
-        String::from_utf16(&u16_vec)
-            .map_err(|e| format!("Failed to parse UTF-16 .set file: {}", e))?
-    } else {
-        // Fallback to UTF-8
-        String::from_utf8(bytes)
-            .map_err(|e| format!("Failed to parse .set file (not UTF-8 or UTF-16 LE): {}", e))?
+    let cache_kind = match format {
+        Some(format) => format!("set:{}", format.trim().to_lowercase()),
+        None => "set".to_string(),
     };
+    let cache_key = parse_cache_key(&cache_kind, &bytes);
+    if let Some(config) = parse_cache_get(cache_key) {
+        tracing::info!("Parse cache hit, content unchanged");
+        return Ok(config);
+    }
+
+    let config = parse_set_file_bytes(bytes, format, task).map_err(|e| {
+        tracing::error!(error = %e, "Setfile import failed");
+        e
+    })?;
+    parse_cache_put(cache_key, &sanitized_path, &config);
+    Ok(config)
+}
+
+/// Structured log of the most recent import (set or JSON), for diagnostics
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_last_import_log() -> Result<Option<ImportLog>, String> {
+    Ok(import_log::last_import_log())
+}
+
+/// Decode and parse raw .set bytes into an MTConfig (no caching, no file access).
+/// `format` picks a registered setfile format by id; the content decides when unset.
+fn parse_set_file_bytes(
+    bytes: Vec<u8>,
+    format: Option<&str>,
+    task: &crate::TaskHandle,
+) -> Result<MTConfig, String> {
+    let content = decode_setfile_bytes(bytes)?;
+    tracing::debug!("Content length: {} chars", content.len());
+
+    let format = setfile_format::resolve_format(format, &content)?;
+    tracing::info!(format = format.id(), "Parsing setfile");
+    format.parse(&content, task)
+}
 
-    println!("[SETFILE] Rust: Content length: {} chars", content.len());
+/// Key/value pairs of a .set file plus the "; Tags:" and "; Comments:" header lines
+pub(crate) struct SetfileValues {
+    pub values: std::collections::HashMap<String, String>,
+    pub tags: Option<Vec<String>>,
+    pub comments: Option<String>,
+}
 
+/// Collect the key=value lines of a .set file, dropping MT optimization ranges
+pub(crate) fn read_setfile_values(
+    content: &str,
+    task: &crate::TaskHandle,
+) -> Result<SetfileValues, String> {
     let mut values: std::collections::HashMap<String, String> = std::collections::HashMap::new();
     let mut tags: Option<Vec<String>> = None;
     let mut comments: Option<String> = None;
 
     // Parse .set file (key=value format)
+    let total_lines = content.lines().count();
     let mut line_count = 0;
     let mut key_count = 0;
     for line in content.lines() {
+        if line_count % 2000 == 0 {
+            task.check()?;
+            task.report("parsing_keys", line_count, total_lines);
+        }
         line_count += 1;
         let line = line.trim();
         if line.is_empty() {
@@ -3869,58 +3215,85 @@ pub async fn import_set_file(file_path: String) -> Result<MTConfig, String> {
         }
     }
 
-    println!(
-        "[SETFILE] Rust: Parsed {} lines, {} key-value pairs",
+    tracing::info!(
+        "Parsed {} lines, {} key-value pairs",
         line_count, key_count
     );
+    task.check()?;
+    task.report("building_config", total_lines, total_lines);
 
-    let is_v19_massive = values.keys().any(|k| parse_v19_key(k).is_some());
-    if is_v19_massive {
-        let v19_validation = validate_v19_setfile(&content);
-        if !v19_validation.is_valid {
-            return Err(format!("Invalid v19 massive setfile: {:?}", v19_validation.errors));
-        }
-
-        let mut config = build_config_from_v19_setfile(&content)?;
-        if let Ok(legacy_overlay) = build_config_from_values(&values) {
-            // Preserve full global metadata and group trigger thresholds from the same source file.
-            // v19 parser remains authoritative for directional logic rows.
-            config.general = legacy_overlay.general;
-            for engine in &mut config.engines {
-                if let Some(legacy_engine) = legacy_overlay
-                    .engines
-                    .iter()
-                    .find(|e| e.engine_id == engine.engine_id)
-                {
-                    for group in &mut engine.groups {
-                        if let Some(legacy_group) = legacy_engine
-                            .groups
-                            .iter()
-                            .find(|g| g.group_number == group.group_number)
-                        {
-                            group.group_power_start = legacy_group.group_power_start;
-                        }
+    Ok(SetfileValues {
+        values,
+        tags,
+        comments,
+    })
+}
+
+/// Build a config from a massive v19 file. The v19 rows are authoritative for logics; the
+/// flat keys of the same file still supply general settings and group thresholds.
+pub(crate) fn build_config_from_v19_values(
+    content: &str,
+    parsed: SetfileValues,
+) -> Result<MTConfig, String> {
+    let SetfileValues {
+        values,
+        tags,
+        comments,
+    } = parsed;
+
+    let v19_validation = validate_v19_setfile(content);
+    if !v19_validation.is_valid {
+        return Err(format!("Invalid v19 massive setfile: {:?}", v19_validation.errors));
+    }
+
+    let mut config = build_config_from_v19_setfile(content)?;
+    if let Ok(legacy_overlay) = build_config_from_values(&values) {
+        // Preserve full global metadata and group trigger thresholds from the same source file.
+        // v19 parser remains authoritative for directional logic rows.
+        config.general = legacy_overlay.general;
+        for engine in &mut config.engines {
+            if let Some(legacy_engine) = legacy_overlay
+                .engines
+                .iter()
+                .find(|e| e.engine_id == engine.engine_id)
+            {
+                for group in &mut engine.groups {
+                    if let Some(legacy_group) = legacy_engine
+                        .groups
+                        .iter()
+                        .find(|g| g.group_number == group.group_number)
+                    {
+                        group.group_power_start = legacy_group.group_power_start;
                     }
                 }
             }
         }
-        normalize_config_mode_contract(&mut config);
-        config.tags = tags;
-        config.comments = comments;
-        config.deobfuscate_sensitive_fields();
-        return Ok(config);
     }
+    normalize_config_mode_contract(&mut config);
+    config.tags = tags;
+    config.comments = comments;
+    config.deobfuscate_sensitive_fields();
+    Ok(config)
+}
+
+/// Build a config from a flat (legacy / v17) file
+pub(crate) fn build_config_from_flat_values(parsed: SetfileValues) -> Result<MTConfig, String> {
+    let SetfileValues {
+        values,
+        tags,
+        comments,
+    } = parsed;
 
     // Debug: Show ALL keys that match gInput pattern
     let ginput_keys: Vec<&String> = values.keys().filter(|k| k.starts_with("gInput_")).collect();
-    println!("[SETFILE] Rust: Total gInput keys found: {}", ginput_keys.len());
+    tracing::info!("Total gInput keys found: {}", ginput_keys.len());
 
     // Show sample of logic keys
     let logic_keys: Vec<&String> = ginput_keys.iter().filter(|k| {
         let parts: Vec<&str> = k.split('_').collect();
         parts.len() >= 4 && parts[2].parse::<u8>().is_ok()
     }).take(20).cloned().collect();
-    println!("[SETFILE] Rust: Sample logic keys: {:?}", logic_keys);
+    tracing::debug!("Sample logic keys: {:?}", logic_keys);
 
     // Count keys by pattern
     let mut ap_count = 0;
@@ -3937,7 +3310,7 @@ pub async fn import_set_file(file_path: String) -> Result<MTConfig, String> {
         if key.contains("_CP_") { cp_count += 1; }
         if key.contains("_CR_") { cr_count += 1; }
     }
-    println!("[SETFILE] Rust: Keys by pattern - AP:{} AR:{} BP:{} BR:{} CP:{} CR:{}",
+    tracing::debug!("Keys by pattern - AP:{} AR:{} BP:{} BR:{} CP:{} CR:{}",
              ap_count, ar_count, bp_count, br_count, cp_count, cr_count);
 
     // Build config from parsed values
@@ -3953,6 +3326,8 @@ pub async fn import_set_file(file_path: String) -> Result<MTConfig, String> {
 pub struct VaultMetadata {
     pub tags: Option<Vec<String>>,
     pub comments: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3963,19 +3338,31 @@ pub struct VaultJson {
 
 /// Export config to JSON format (proper MT4/MT5 compatible)
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(file_path = %file_path), err)]
 pub async fn export_json_file(
     config: MTConfig,
     file_path: String,
     tags: Option<Vec<String>>,
     comments: Option<String>,
+    redaction: Option<ExportRedaction>,
+    deterministic: Option<DeterministicExport>,
 ) -> Result<(), String> {
     // Sanitize and validate the file path
     let path_buf = PathBuf::from(&file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
 
-    let json_str = if tags.is_some() || comments.is_some() {
+    let (mut config, comments) = redact_for_export(config, comments, redaction.as_ref());
+    if let Some(deterministic) = &deterministic {
+        deterministic.prepare_config(&mut config);
+    }
+    let json_str = if tags.is_some() || comments.is_some() || config.annotations.is_some() {
+        let annotations = config.annotations.take();
         let wrapper = VaultJson {
-            metadata: VaultMetadata { tags, comments },
+            metadata: VaultMetadata {
+                tags,
+                comments,
+                annotations,
+            },
             config,
         };
         serde_json::to_string_pretty(&wrapper)
@@ -3985,7 +3372,7 @@ pub async fn export_json_file(
             .map_err(|e| format!("Failed to serialize config: {}", e))?
     };
 
-    atomic_write(&sanitized_path, &json_str)?;
+    sync_aware_write(&sanitized_path, &json_str)?;
 
     Ok(())
 }
@@ -3993,32 +3380,195 @@ pub async fn export_json_file(
 /// Import config from JSON format
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
 pub async fn import_json_file(file_path: String) -> Result<MTConfig, String> {
+    import_log::import_span("json", &file_path).in_scope(|| import_json_file_logged(&file_path))
+}
+
+fn import_json_file_logged(file_path: &str) -> Result<MTConfig, String> {
+    tracing::info!(file_path, "Importing JSON config");
+
     // Sanitize and validate the file path
-    let path_buf = PathBuf::from(&file_path);
+    let path_buf = PathBuf::from(file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
 
     let json_str = fs::read_to_string(&sanitized_path)
         .map_err(|e| format!("Failed to read JSON file: {}", e))?;
 
+    let cache_key = parse_cache_key("json", json_str.as_bytes());
+    if let Some(config) = parse_cache_get(cache_key) {
+        tracing::info!("Parse cache hit, content unchanged");
+        return Ok(config);
+    }
+
+    let config = parse_json_config(&json_str).map_err(|e| {
+        tracing::error!(error = %e, "JSON import failed");
+        e
+    })?;
+    parse_cache_put(cache_key, &sanitized_path, &config);
+    Ok(config)
+}
+
+fn parse_json_config(json_str: &str) -> Result<MTConfig, String> {
     // Try parsing as VaultJson first
-    if let Ok(wrapper) = serde_json::from_str::<VaultJson>(&json_str) {
+    if let Ok(wrapper) = serde_json::from_str::<VaultJson>(json_str) {
         let mut config = wrapper.config;
         config.tags = wrapper.metadata.tags;
         config.comments = wrapper.metadata.comments;
+        if wrapper.metadata.annotations.is_some() {
+            config.annotations = wrapper.metadata.annotations;
+        }
         config.deobfuscate_sensitive_fields(); // Deobfuscate
         return Ok(config);
     }
 
     // Fallback to raw MTConfig
     let mut config: MTConfig =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse JSON file: {}", e))?;
+        serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON file: {}", e))?;
     config.deobfuscate_sensitive_fields(); // Deobfuscate
 
     Ok(config)
 }
 
+// ============================================
+// PARSE CACHE (keyed by file content hash)
+// ============================================
+
+/// Parsed configs kept around; v19 configs are large, so this stays small
+const PARSE_CACHE_CAPACITY: usize = 8;
+
+struct ParseCacheEntry {
+    config: MTConfig,
+    source_path: PathBuf,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct ParseCache {
+    entries: HashMap<u64, ParseCacheEntry>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub cached_files: Vec<String>,
+}
+
+static PARSE_CACHE: std::sync::OnceLock<std::sync::Mutex<ParseCache>> =
+    std::sync::OnceLock::new();
+
+fn get_parse_cache() -> &'static std::sync::Mutex<ParseCache> {
+    PARSE_CACHE.get_or_init(|| std::sync::Mutex::new(ParseCache::default()))
+}
+
+/// Hash of the raw bytes (plus format and length), so renamed/copied files still hit
+fn parse_cache_key(kind: &str, bytes: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    kind.hash(&mut hasher);
+    bytes.len().hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn parse_cache_get(key: u64) -> Option<MTConfig> {
+    let mut cache = get_parse_cache().lock().ok()?;
+    cache.tick += 1;
+    let tick = cache.tick;
+    match cache.entries.get_mut(&key) {
+        Some(entry) => {
+            entry.last_used = tick;
+            let config = entry.config.clone();
+            cache.hits += 1;
+            Some(config)
+        }
+        None => {
+            cache.misses += 1;
+            None
+        }
+    }
+}
+
+fn parse_cache_put(key: u64, source_path: &PathBuf, config: &MTConfig) {
+    let Ok(mut cache) = get_parse_cache().lock() else {
+        return;
+    };
+    if cache.entries.len() >= PARSE_CACHE_CAPACITY && !cache.entries.contains_key(&key) {
+        if let Some(oldest) = cache
+            .entries
+            .iter()
+            .min_by_key(|(_, e)| e.last_used)
+            .map(|(k, _)| *k)
+        {
+            cache.entries.remove(&oldest);
+        }
+    }
+    cache.tick += 1;
+    let tick = cache.tick;
+    cache.entries.insert(
+        key,
+        ParseCacheEntry {
+            config: config.clone(),
+            source_path: source_path.clone(),
+            last_used: tick,
+        },
+    );
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_parse_cache_stats() -> Result<ParseCacheStats, String> {
+    let cache = get_parse_cache()
+        .lock()
+        .map_err(|_| "Parse cache poisoned".to_string())?;
+    let lookups = cache.hits + cache.misses;
+    Ok(ParseCacheStats {
+        entries: cache.entries.len(),
+        capacity: PARSE_CACHE_CAPACITY,
+        hits: cache.hits,
+        misses: cache.misses,
+        hit_rate: if lookups > 0 {
+            cache.hits as f64 / lookups as f64
+        } else {
+            0.0
+        },
+        cached_files: cache
+            .entries
+            .values()
+            .map(|e| e.source_path.to_string_lossy().to_string())
+            .collect(),
+    })
+}
+
+/// Drop cached parses for one file, or everything when no path is given.
+/// Returns the number of entries removed.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn clear_parse_cache(file_path: Option<String>) -> Result<usize, String> {
+    let mut cache = get_parse_cache()
+        .lock()
+        .map_err(|_| "Parse cache poisoned".to_string())?;
+    let before = cache.entries.len();
+    match file_path {
+        Some(path) => {
+            let target = sanitize_and_validate_path(&PathBuf::from(&path))?;
+            cache.entries.retain(|_, e| e.source_path != target);
+        }
+        None => {
+            cache.entries.clear();
+            cache.hits = 0;
+            cache.misses = 0;
+        }
+    }
+    Ok(before - cache.entries.len())
+}
+
 /// Write text content to a file (for exporting generated setfile content)
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(file_path = %file_path, bytes = content.len()), err)]
 pub async fn write_text_file(file_path: String, content: String) -> Result<(), String> {
     // Sanitize and validate the file path
     let path_buf = PathBuf::from(&file_path);
@@ -4060,6 +3610,9 @@ pub fn _export_set_file_with_validation(
         None,
         None,
         None,
+        None,
+        None,
+        None,
     )?;
 
     let mut warnings: Vec<String> = Vec::new();
@@ -4137,6 +3690,10 @@ pub struct VaultFile {
 pub struct VaultListing {
     pub vault_path: String,
     pub files: Vec<VaultFile>,
+    /// Set when the vault is inside a OneDrive/Dropbox/... folder
+    pub sync_warning: Option<String>,
+    /// Set when a remote (S3/WebDAV) vault couldn't be refreshed; the cached files are listed
+    pub remote_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -4145,58 +3702,7 @@ pub struct TerminalLogTail {
     pub lines: Vec<String>,
 }
 
-fn get_vault_path() -> PathBuf {
-    // 1. Try explicit absolute path (Development environment fallback)
-    // Removed hardcoded legacy path to avoid saving to wrong directory
-
-    // 2. Search upwards from current directory
-    let mut current = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    println!("Searching for Vault starting from: {:?}", current);
-
-    // Prefer repo presets folder if we're running from the repo
-    for i in 0..15 {
-        let repo_candidate = current.join("APPS").join("dashboard").join("Vault_Presets");
-        if repo_candidate.exists() && repo_candidate.is_dir() {
-            println!("Found Vault (repo) at level {}: {:?}", i, repo_candidate);
-            return repo_candidate;
-        }
-
-        let candidate = current.join("Vault_Presets");
-        if candidate.exists() && candidate.is_dir() {
-            println!("Found Vault at level {}: {:?}", i, candidate);
-            return candidate;
-        }
-
-        // Legacy nesting
-        let candidate_nested = current
-            .join("daavfx_trading_ecosystem_6.0")
-            .join("Vault_Presets");
-        if candidate_nested.exists() && candidate_nested.is_dir() {
-            println!("Found Vault nested at level {}: {:?}", i, candidate_nested);
-            return candidate_nested;
-        }
-
-        if !current.pop() {
-            break;
-        }
-    }
-
-    // 3. Fallback: Use Documents/DAAVFX_Vault
-    if let Some(docs) = dirs::document_dir() {
-        let vault = docs.join("DAAVFX_Vault");
-        if !vault.exists() {
-            let _ = fs::create_dir_all(&vault);
-        }
-        println!("Vault not found, falling back to Documents: {:?}", vault);
-        return vault;
-    }
-
-    // Ultimate fallback
-    println!("Vault not found, using default 'Vault_Presets'");
-    PathBuf::from("Vault_Presets")
-}
-
-fn resolve_vault_path(vault_path_override: Option<String>) -> Result<PathBuf, String> {
+pub(crate) fn resolve_vault_path(vault_path_override: Option<String>) -> Result<PathBuf, String> {
     if let Some(raw_path) = vault_path_override {
         let trimmed = raw_path.trim();
         if !trimmed.is_empty() {
@@ -4204,119 +3710,63 @@ fn resolve_vault_path(vault_path_override: Option<String>) -> Result<PathBuf, St
         }
     }
 
-    sanitize_and_validate_path(&get_vault_path())
+    sanitize_and_validate_path(&crate::vault_location::active_vault_root())
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
-pub async fn list_vault_files(vault_path_override: Option<String>) -> Result<VaultListing, String> {
+#[tracing::instrument(skip_all, err)]
+pub async fn list_vault_files(
+    vault_path_override: Option<String>,
+    task_id: Option<String>,
+) -> Result<VaultListing, String> {
+    let task = crate::task_manager().begin(task_id, "list_vault_files");
+    // Mirror a remote vault into its cache first; listing falls back to the cache on failure
+    let remote_error = if vault_path_override.is_none() {
+        task.report("syncing_remote", 0, 0);
+        crate::vault_backend::refresh_active_cache().await.err()
+    } else {
+        None
+    };
+    if let Some(e) = &remote_error {
+        tracing::warn!(error = %e, "Failed to refresh remote vault");
+    }
+    // The directory walk and header reads block, so they run on the blocking pool
+    let span = tracing::Span::current();
+    let task_for_scan = task.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        span.in_scope(|| list_vault_files_with_task(vault_path_override, &task_for_scan))
+    })
+    .await
+    .map_err(|e| format!("Vault listing task failed: {}", e))
+    .and_then(|r| r)
+    .map(|listing| VaultListing {
+        remote_error,
+        ..listing
+    });
+    crate::task_manager().finish(&task.id);
+    result
+}
+
+fn list_vault_files_with_task(
+    vault_path_override: Option<String>,
+    task: &crate::TaskHandle,
+) -> Result<VaultListing, String> {
     let vault_path = resolve_vault_path(vault_path_override)?;
     if !vault_path.exists() {
         return Ok(VaultListing {
             vault_path: vault_path.to_string_lossy().to_string(),
             files: Vec::new(),
+            sync_warning: sync_warning(&vault_path),
+            remote_error: None,
         });
     }
 
-    let mut files = Vec::new();
-
-    // Helper to process a directory
-    let process_dir = |dir: PathBuf,
-                       category: Option<String>|
-     -> Result<Vec<VaultFile>, std::io::Error> {
-        let mut dir_files = Vec::new();
-        if dir.exists() && dir.is_dir() {
-            for entry in fs::read_dir(dir)? {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_file() {
-                        if let Some(ext) = path.extension() {
-                            let ext_str = ext.to_string_lossy().to_lowercase();
-                            if ext_str == "set" || ext_str == "json" {
-                                let metadata = entry.metadata()?;
-                                let modified =
-                                    metadata.modified().unwrap_or(std::time::SystemTime::now());
-                                let datetime: chrono::DateTime<chrono::Local> = modified.into();
-
-                                // Extract tags/comments/magic from header
-                                let mut tags = None;
-                                let mut comments = None;
-                                let mut magic_number = None;
-
-                                if let Ok(content) = fs::read_to_string(&path) {
-                                    if ext_str == "json" {
-                                        if let Ok(wrapper) =
-                                            serde_json::from_str::<VaultJson>(&content)
-                                        {
-                                            tags = wrapper.metadata.tags;
-                                            comments = wrapper.metadata.comments;
-                                            magic_number =
-                                                Some(wrapper.config.general.magic_number);
-                                        } else if let Ok(config) =
-                                            serde_json::from_str::<MTConfig>(&content)
-                                        {
-                                            magic_number = Some(config.general.magic_number);
-                                        }
-                                    } else {
-                                        // Check first 200 lines for metadata and magic number
-                                        for line in content.lines().take(200) {
-                                            if line.starts_with("; Tags: ") {
-                                                tags = Some(
-                                                    line.trim_start_matches("; Tags: ")
-                                                        .split(',')
-                                                        .map(|s| s.trim().to_string())
-                                                        .collect(),
-                                                );
-                                            } else if line.starts_with("; Comments: ") {
-                                                comments = Some(
-                                                    line.trim_start_matches("; Comments: ")
-                                                        .to_string(),
-                                                );
-                                            } else if line.contains("gInput_MagicNumber=")
-                                                || line.contains("MagicNumber=")
-                                            {
-                                                let parts: Vec<&str> = line.split('=').collect();
-                                                if parts.len() >= 2 {
-                                                    let val_str = parts[1]
-                                                        .split(';')
-                                                        .next()
-                                                        .unwrap_or("")
-                                                        .trim();
-                                                    if let Ok(val) = val_str.parse::<i32>() {
-                                                        magic_number = Some(val);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-
-                                dir_files.push(VaultFile {
-                                    name: path
-                                        .file_name()
-                                        .unwrap_or_default()
-                                        .to_string_lossy()
-                                        .to_string(),
-                                    path: path.to_string_lossy().to_string(),
-                                    last_modified: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
-                                    size: metadata.len(),
-                                    category: category.clone(),
-                                    tags,
-                                    comments,
-                                    magic_number,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        Ok(dir_files)
-    };
+    // Collect candidates first so progress can be reported against a known total
+    task.report("scanning", 0, 0);
+    let mut candidates: Vec<(PathBuf, Option<String>)> = Vec::new();
 
     // 1. Root files
-    if let Ok(mut root_files) = process_dir(vault_path.clone(), None) {
-        files.append(&mut root_files);
-    }
+    collect_vault_candidates(&vault_path, None, &mut candidates);
 
     // 2. Subdirectories (Categories)
     if let Ok(entries) = fs::read_dir(&vault_path) {
@@ -4329,23 +3779,127 @@ pub async fn list_vault_files(vault_path_override: Option<String>) -> Result<Vau
                         .unwrap_or_default()
                         .to_string_lossy()
                         .to_string();
-                    if let Ok(mut cat_files) = process_dir(path, Some(category_name)) {
-                        files.append(&mut cat_files);
-                    }
+                    collect_vault_candidates(&path, Some(category_name), &mut candidates);
                 }
             }
         }
     }
 
+    let total = candidates.len();
+    let mut files = Vec::with_capacity(total);
+    for (index, (path, category)) in candidates.into_iter().enumerate() {
+        task.check()?;
+        task.report("reading_metadata", index, total);
+        if let Ok(file) = read_vault_file_entry(&path, category) {
+            files.push(file);
+        }
+    }
+    task.report("reading_metadata", total, total);
+
     // Sort by modified date (newest first)
     files.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
 
     Ok(VaultListing {
+        sync_warning: sync_warning(&vault_path),
+        remote_error: None,
         vault_path: vault_path.to_string_lossy().to_string(),
         files,
     })
 }
 
+/// Push every .set/.json file directly inside `dir` onto `out`
+fn collect_vault_candidates(
+    dir: &PathBuf,
+    category: Option<String>,
+    out: &mut Vec<(PathBuf, Option<String>)>,
+) {
+    if !dir.is_dir() {
+        return;
+    }
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(ext) = path.extension() {
+                let ext_str = ext.to_string_lossy().to_lowercase();
+                if ext_str == "set" || ext_str == "json" {
+                    out.push((path, category.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Read size/mtime and extract tags/comments/magic from a vault file header
+fn read_vault_file_entry(
+    path: &PathBuf,
+    category: Option<String>,
+) -> Result<VaultFile, std::io::Error> {
+    let ext_str = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified().unwrap_or(std::time::SystemTime::now());
+    let datetime: chrono::DateTime<chrono::Local> = modified.into();
+
+    // Extract tags/comments/magic from header
+    let mut tags = None;
+    let mut comments = None;
+    let mut magic_number = None;
+
+    if let Ok(content) = fs::read_to_string(path) {
+        if ext_str == "json" {
+            if let Ok(wrapper) = serde_json::from_str::<VaultJson>(&content) {
+                tags = wrapper.metadata.tags;
+                comments = wrapper.metadata.comments;
+                magic_number = Some(wrapper.config.general.magic_number);
+            } else if let Ok(config) = serde_json::from_str::<MTConfig>(&content) {
+                magic_number = Some(config.general.magic_number);
+            }
+        } else {
+            // Check first 200 lines for metadata and magic number
+            for line in content.lines().take(200) {
+                if line.starts_with("; Tags: ") {
+                    tags = Some(
+                        line.trim_start_matches("; Tags: ")
+                            .split(',')
+                            .map(|s| s.trim().to_string())
+                            .collect(),
+                    );
+                } else if line.starts_with("; Comments: ") {
+                    comments = Some(line.trim_start_matches("; Comments: ").to_string());
+                } else if line.contains("gInput_MagicNumber=") || line.contains("MagicNumber=") {
+                    let parts: Vec<&str> = line.split('=').collect();
+                    if parts.len() >= 2 {
+                        let val_str = parts[1].split(';').next().unwrap_or("").trim();
+                        if let Ok(val) = val_str.parse::<i32>() {
+                            magic_number = Some(val);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(VaultFile {
+        name: path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        path: path.to_string_lossy().to_string(),
+        last_modified: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        size: metadata.len(),
+        category,
+        tags,
+        comments,
+        magic_number,
+    })
+}
+
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
 pub async fn open_vault_folder(vault_path_override: Option<String>) -> Result<(), String> {
     let vault_path = resolve_vault_path(vault_path_override)?;
@@ -4356,17 +3910,7 @@ pub async fn open_vault_folder(vault_path_override: Option<String>) -> Result<()
         return Err("Vault path is not a folder".to_string());
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&vault_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-        return Ok(());
-    }
-
-    #[allow(unreachable_code)]
-    Err("Open folder not supported on this OS".to_string())
+    os_paths::open_folder(&vault_path)
 }
 
 struct VaultSizeCache {
@@ -4479,9 +4023,8 @@ pub async fn get_vault_size(
     Ok(VaultSizeResult { total_size })
 }
 
-fn get_terminal_root_path() -> Result<PathBuf, String> {
-    let appdata = std::env::var("APPDATA").map_err(|e| format!("APPDATA not available: {}", e))?;
-    Ok(PathBuf::from(appdata).join("MetaQuotes").join("Terminal"))
+pub(crate) fn get_terminal_root_path() -> Result<PathBuf, String> {
+    os_paths::terminal_data_root()
 }
 
 fn find_latest_terminal_log(root: &PathBuf) -> Option<PathBuf> {
@@ -4554,17 +4097,7 @@ pub async fn open_mt_terminal_root() -> Result<(), String> {
         return Err("Terminal folder not found".to_string());
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&root)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-        return Ok(());
-    }
-
-    #[allow(unreachable_code)]
-    Err("Open folder not supported on this OS".to_string())
+    os_paths::open_folder(&root)
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
@@ -4591,7 +4124,7 @@ pub async fn _export_vault_file(filename: String, target_path: String) -> Result
     let config = if filename.to_lowercase().ends_with(".json") {
         import_json_file(filename.clone()).await?
     } else {
-        import_set_file(filename.clone()).await?
+        import_set_file(filename.clone(), None, None).await?
     };
 
     // 2. Write to target (plain text)
@@ -4609,6 +4142,9 @@ pub async fn _export_vault_file(filename: String, target_path: String) -> Result
             None,
             None,
             None,
+            None,
+            None,
+            None,
         )?;
     }
 
@@ -4616,6 +4152,7 @@ pub async fn _export_vault_file(filename: String, target_path: String) -> Result
 }
 
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, fields(name = %name), err)]
 pub async fn save_to_vault(
     config: MTConfig,
     name: String,
@@ -4656,7 +4193,10 @@ pub async fn save_to_vault(
 
     // Obfuscate sensitive fields before saving to vault (local storage)
     let mut config_safe = config.clone();
-    config_safe.obfuscate_sensitive_fields();
+    apply_redaction(
+        &mut config_safe,
+        &RedactionPolicy::for_audience(ExportAudience::Internal),
+    );
 
     let file_format = format.unwrap_or_else(|| "set".to_string());
 
@@ -4666,20 +4206,27 @@ pub async fn save_to_vault(
         let file_path = validated_file_path;
 
         // Use wrapper for JSON metadata
-        if tags.is_some() || comments.is_some() {
+        if tags.is_some() || comments.is_some() || config_safe.annotations.is_some() {
+            let annotations = config_safe.annotations.take();
             let wrapper = VaultJson {
-                metadata: VaultMetadata { tags, comments },
+                metadata: VaultMetadata {
+                    tags,
+                    comments,
+                    annotations,
+                },
                 config: config_safe,
             };
             let json_str = serde_json::to_string_pretty(&wrapper)
                 .map_err(|e| format!("Failed to serialize config with metadata: {}", e))?;
-            atomic_write(&file_path, &json_str)?;
+            sync_aware_write(&file_path, &json_str)?;
         } else {
             // Legacy/Simple format
             let json_str = serde_json::to_string_pretty(&config_safe)
                 .map_err(|e| format!("Failed to serialize config: {}", e))?;
-            atomic_write(&file_path, &json_str)?;
+            sync_aware_write(&file_path, &json_str)?;
         }
+        vault_git::auto_commit_preset(&vault_root, &file_path, "Save");
+        upload_vault_preset(&vault_root, &file_path).await?;
     } else {
         let file_path_buf = vault_path.join(format!("{}.set", safe_name));
         let validated_file_path = validate_path_within_base(&file_path_buf, &vault_root)?;
@@ -4693,12 +4240,24 @@ pub async fn save_to_vault(
             None,
             tags,
             comments,
+            None,
+            None,
+            None,
         )?;
+        vault_git::auto_commit_preset(&vault_root, &file_path, "Save");
+        upload_vault_preset(&vault_root, &file_path).await?;
     }
 
     Ok(())
 }
 
+/// Upload a saved preset when the vault is backed by S3/WebDAV; the local copy is kept either way
+async fn upload_vault_preset(vault_root: &Path, file_path: &Path) -> Result<(), String> {
+    crate::vault_backend::upload_preset(vault_root, file_path)
+        .await
+        .map_err(|e| format!("Saved locally, but uploading to the remote vault failed: {}", e))
+}
+
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
 pub async fn _delete_from_vault(
     filename: String,
@@ -4708,57 +4267,14 @@ pub async fn _delete_from_vault(
     let file_path_buf = vault_root.join(filename);
     let validated_file_path = validate_path_within_base(&file_path_buf, &vault_root)?;
 
-    if validated_file_path.exists() {
-        fs::remove_file(validated_file_path)
-            .map_err(|e| format!("Failed to delete file: {}", e))?;
-    }
-
-    Ok(())
-}
-
-// Helper functions for suffix generation
-fn get_logic_suffix(engine_id: &str, group: u8, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let logic_char = match logic_name.to_uppercase().as_str() {
-        "POWER" => "P",
-        "REPOWER" => "R",
-        "SCALPER" | "SCALP" => "S",
-        "STOPPER" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    };
-
-    format!("{}{}{}", prefix, logic_char, group)
-}
-
-fn get_logic_short(engine_id: &str, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let logic_char = match logic_name.to_uppercase().as_str() {
-        "POWER" => "P",
-        "REPOWER" => "R",
-        "SCALPER" | "SCALP" => "S",
-        "STOPPER" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    };
+    if validated_file_path.exists() {
+        fs::remove_file(&validated_file_path)
+            .map_err(|e| format!("Failed to delete file: {}", e))?;
+        vault_git::auto_commit_preset(&vault_root, &validated_file_path, "Delete");
+    }
+    crate::vault_backend::delete_preset(&vault_root, &validated_file_path).await?;
 
-    format!("{}{}", prefix, logic_char)
+    Ok(())
 }
 
 // V19 massive setfile format: gInput_{Group}_{Engine}{Logic}_{Direction}_{Param}
@@ -4785,26 +4301,6 @@ fn get_v19_suffix(engine_id: &str, logic_name: &str) -> String {
     format!("{}{}", engine_prefix, logic_code)
 }
 
-fn trigger_action_to_int(action: &str) -> i32 {
-    let s = action.trim();
-    if let Ok(n) = s.parse::<i32>() {
-        return n;
-    }
-    match s {
-        "TriggerAction_None" => 0,
-        "TriggerAction_StopEA" => 1,
-        "TriggerAction_StopEA_KeepTrades" => 2,
-        "TriggerAction_CloseAll" => 3,
-        "TriggerAction_KeepEA_CloseTrades" => 4,
-        "TriggerAction_StopEA_CloseTrades" => 5,
-        "TriggerAction_PauseEA_CloseTrades" => 6,
-        "TriggerAction_PauseEA_KeepTrades" => 7,
-        "Action_Default" => 2,
-        "Action_CloseAll" => 3,
-        _ => 2,
-    }
-}
-
 fn trigger_action_from_int(n: i32) -> &'static str {
     match n {
         0 => "TriggerAction_None",
@@ -4854,160 +4350,6 @@ fn news_flags_from_action_int(action: i32) -> (bool, bool, bool) {
     }
 }
 
-fn get_logic_global_key(engine_id: &str, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let name = match logic_name.to_uppercase().as_str() {
-        "POWER" => "Power",
-        "REPOWER" => "Repower",
-        "SCALPER" | "SCALP" => "Scalp",
-        "STOPPER" => "Stopper",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "Power",
-    };
-
-    format!("{}{}", prefix, name)
-}
-
-fn default_close_targets(engine_id: &str, logic_name: &str) -> String {
-    let upper = logic_name.to_uppercase();
-    if upper != "RPO" {
-        return String::new();
-    }
-
-    match engine_id {
-        "A" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO".to_string(),
-        "B" => "B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO".to_string(),
-        "C" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO,B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO,C:Power,C:Repower,C:Scalp,C:Stopper,C:STO,C:SCA,C:RPO".to_string(),
-        _ => String::new(),
-    }
-}
-
-fn normalize_trigger_type(raw: &str) -> String {
-    let s = raw.trim();
-    if s.is_empty() {
-        return "0".to_string();
-    }
-
-    let mut digits = String::new();
-    for ch in s.chars() {
-        if ch.is_ascii_digit() {
-            digits.push(ch);
-        } else {
-            break;
-        }
-    }
-    if !digits.is_empty() {
-        return digits;
-    }
-
-    match s {
-        "Trigger_Immediate" => "0".to_string(),
-        "Trigger_AfterBars" => "1".to_string(),
-        "Trigger_AfterSeconds" => "2".to_string(),
-        "Trigger_AfterPips" => "3".to_string(),
-        "Trigger_TimeFilter" => "4".to_string(),
-        "Trigger_NewsFilter" => "5".to_string(),
-        "Trigger_PowerAOppositeCount" => "6".to_string(),
-        _ => "0".to_string(),
-    }
-}
-
-fn trigger_type_code(raw: &str) -> i32 {
-    normalize_trigger_type(raw)
-        .parse::<i32>()
-        .ok()
-        .unwrap_or(0)
-}
-
-fn normalize_trigger_mode(raw: &str) -> String {
-    let mode = raw.trim().to_ascii_lowercase();
-    match mode.as_str() {
-        "1" | "triggermode_firsttick" | "firsttick" | "first_tick" => {
-            "TriggerMode_FirstTick".to_string()
-        }
-        "2" | "triggermode_waitbar" | "waitbar" | "wait_bar" => {
-            "TriggerMode_WaitBar".to_string()
-        }
-        _ => "TriggerMode_OnTick".to_string(),
-    }
-}
-
-fn encode_trigger_mode(raw: &str) -> i32 {
-    match normalize_trigger_mode(raw).as_str() {
-        "TriggerMode_FirstTick" => 1,
-        "TriggerMode_WaitBar" => 2,
-        _ => 0,
-    }
-}
-
-fn encode_trail_step_method(raw: &str) -> i32 {
-    match raw {
-        "Step_Percent" => 1,
-        _ => 0,
-    }
-}
-
-fn encode_trail_step_mode(raw: &str) -> i32 {
-    match raw {
-        "TrailStepMode_Auto" => 0,
-        "TrailStepMode_Fixed" => 1,
-        "TrailStepMode_PerOrder" => 3,
-        _ => 0,
-    }
-}
-
-fn encode_partial_mode(raw: &str) -> i32 {
-    match raw {
-        "PartialMode_Low" => 0,
-        "PartialMode_Mid" | "PartialMode_Balanced" => 1,
-        "PartialMode_Aggressive" | "PartialMode_High" => 2,
-        _ => 1,
-    }
-}
-
-fn encode_partial_balance(raw: &str) -> i32 {
-    match raw {
-        "PartialBalance_Aggressive" => 0,
-        "PartialBalance_Balanced" => 1,
-        "PartialBalance_Conservative" => 2,
-        _ => 1,
-    }
-}
-
-fn get_logic_start_key(engine_id: &str, logic_name: &str) -> Option<String> {
-    let upper = logic_name.to_uppercase();
-    if upper == "POWER" {
-        return None;
-    }
-
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let suffix = match upper.as_str() {
-        "REPOWER" => "Repower",
-        "SCALPER" | "SCALP" => "Scalp",
-        "STOPPER" => "Stopper",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => return None,
-    };
-
-    Some(format!("Start{}{}", prefix, suffix))
-}
-
 // Parse helper functions - defined at module level for reuse
 fn get_bool(values: &std::collections::HashMap<String, String>, key: &str) -> bool {
     values
@@ -5108,8 +4450,8 @@ fn build_config_from_values(
         .filter(|k| k.contains("TriggerMinutes"))
         .count();
     if legacy_trigger_minute_keys > 0 {
-        println!(
-            "[SETFILE] WARN: Detected {} legacy TriggerMinutes key(s); mapping to trigger_seconds.",
+        tracing::warn!(
+            "Detected {} legacy TriggerMinutes key(s); mapping to trigger_seconds.",
             legacy_trigger_minute_keys
         );
     }
@@ -5158,11 +4500,17 @@ fn build_config_from_values(
         ),
         require_license: get_bool(values, "gInput_RequireLicense"),
         license_check_interval: get_i32(values, "gInput_LicenseCheckInterval", 3600),
-        config_file_name: get_string(values, "gInput_ConfigFileName", "DAAVFX_Config.json"),
+        config_file_name: get_string(
+            values,
+            "gInput_ConfigFileName",
+            &naming::current().config_name,
+        ),
         config_file_is_common: get_bool(values, "gInput_ConfigFileIsCommon"),
         allow_buy: get_bool(values, "gInput_allowBuy"),
         allow_sell: get_bool(values, "gInput_allowSell"),
         enable_logs: get_bool(values, "gInput_EnableLogs"),
+        use_direct_price_grid: false,
+        group_mode: None,
         grid_unit: Some(get_i32(values, "gInput_GridUnit", 10)),
         pip_factor: Some(get_i32(values, "gInput_PipFactor", 1)),
         compounding_enabled: get_bool(values, "gInput_Input_Compounding"),
@@ -5225,6 +4573,7 @@ fn build_config_from_values(
             impact_level: get_i32(values, "gInput_NewsImpactLevel", 3),
             minutes_before: get_i32(values, "gInput_MinutesBeforeNews", 30),
             minutes_after: get_i32(values, "gInput_MinutesAfterNews", 30),
+            action: String::new(),
             // Convert action enum to 3 boolean fields
             stop_ea: get_bool_with_default(values, "gInput_NewsStopEA", news_action_flags.0),
             close_trades: get_bool_with_default(values, "gInput_NewsCloseTrades", news_action_flags.1),
@@ -5291,6 +4640,7 @@ fn build_config_from_values(
                 impact_level: get_i32(values, "gInput_NewsImpactLevel_Buy", 3),
                 minutes_before: get_i32(values, "gInput_MinutesBeforeNews_Buy", 30),
                 minutes_after: get_i32(values, "gInput_MinutesAfterNews_Buy", 30),
+                action: String::new(),
                 stop_ea: get_bool(values, "gInput_NewsStopEA_Buy"),
                 close_trades: get_bool(values, "gInput_NewsCloseTrades_Buy"),
                 auto_restart: get_bool(values, "gInput_NewsAutoRestart_Buy"),
@@ -5321,6 +4671,7 @@ fn build_config_from_values(
                 impact_level: get_i32(values, "gInput_NewsImpactLevel_Sell", 3),
                 minutes_before: get_i32(values, "gInput_MinutesBeforeNews_Sell", 30),
                 minutes_after: get_i32(values, "gInput_MinutesAfterNews_Sell", 30),
+                action: String::new(),
                 stop_ea: get_bool(values, "gInput_NewsStopEA_Sell"),
                 close_trades: get_bool(values, "gInput_NewsCloseTrades_Sell"),
                 auto_restart: get_bool(values, "gInput_NewsAutoRestart_Sell"),
@@ -5409,13 +4760,13 @@ fn build_config_from_values(
     let engines = build_engines_from_values(values)?;
 
     // Debug: Show engine summary
-    println!(
-        "[SETFILE] Rust: Built {} engines from setfile",
+    tracing::info!(
+        "Built {} engines from setfile",
         engines.len()
     );
     for engine in &engines {
-        println!(
-            "[SETFILE] Rust:   Engine {}: {} groups",
+        tracing::debug!(
+            "Engine {}: {} groups",
             engine.engine_id,
             engine.groups.len()
         );
@@ -5431,6 +4782,7 @@ fn build_config_from_values(
         current_set_name: None,
         tags: None,
         comments: None,
+        annotations: None,
         general,
         engines,
     };
@@ -5472,7 +4824,7 @@ fn parse_parameter_name(name: &str) -> Option<ParsedParameter> {
 
     if parts.len() < 3 {
         if name.contains("_AP_") || name.contains("_AR_") || name.contains("_BP_") {
-            println!("[SETFILE] Rust: Failed to parse (too few parts): {}", name);
+            tracing::warn!("Failed to parse (too few parts): {}", name);
         }
         return None;
     }
@@ -5498,7 +4850,7 @@ fn parse_parameter_name(name: &str) -> Option<ParsedParameter> {
     let group = match group_token.parse::<u8>() {
         Ok(g) if g >= 1 && g <= 20 => g,
         _ => {
-            println!("[SETFILE] Rust: Invalid group number '{}' in: {}", group_str, name);
+            tracing::warn!("Invalid group number '{}' in: {}", group_str, name);
             return None;
         }
     };
@@ -5543,7 +4895,7 @@ fn parse_parameter_name(name: &str) -> Option<ParsedParameter> {
         "Scalper" => "Scalper",
         "Stopper" => "Stopper",
         _ => {
-            println!("[SETFILE] Rust: Unknown logic abbreviation '{}' in: {}", logic_abbr, name);
+            tracing::warn!("Unknown logic abbreviation '{}' in: {}", logic_abbr, name);
             logic_abbr
         }
     };
@@ -5551,14 +4903,15 @@ fn parse_parameter_name(name: &str) -> Option<ParsedParameter> {
     // Join the remaining parts to form the parameter name
     let param_name = param_parts.join("_");
 
-    // Debug logging for parsed parameters - using AtomicUsize for thread safety
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    static PARSE_COUNT: AtomicUsize = AtomicUsize::new(0);
-    let count = PARSE_COUNT.fetch_add(1, Ordering::SeqCst);
-    if count < 20 {
-        println!("[SETFILE] Rust: Parsed '{}' -> engine={} group={} logic={} direction={} param={}",
-                 name, engine_char, group, logic_name, direction, param_name);
-    }
+    tracing::trace!(
+        key = name,
+        engine = %engine_char,
+        group,
+        logic = logic_name,
+        direction,
+        param = %param_name,
+        "Parsed parameter name"
+    );
 
     Some(ParsedParameter {
         param_name,
@@ -5634,8 +4987,8 @@ fn build_engines_from_values(
     let total_params = values.len();
     let v4_params: Vec<&String> = values.keys().filter(|k| k.starts_with("gInput_")).collect();
 
-    println!(
-        "[SETFILE] Rust: Total V4 parameters: {} / {}",
+    tracing::info!(
+        "Total V4 parameters: {} / {}",
         v4_params.len(),
         total_params
     );
@@ -5689,56 +5042,58 @@ fn build_engines_from_values(
         }
     }
 
-    println!(
-        "[SETFILE] Rust: Successfully parsed {} parameters",
+    tracing::info!(
+        "Successfully parsed {} parameters",
         parsed_count
     );
-    println!(
-        "[SETFILE] Rust: Failed to parse {} parameters",
-        failed_params.len()
-    );
+    if !failed_params.is_empty() {
+        tracing::warn!(
+            "Failed to parse {} parameters",
+            failed_params.len()
+        );
+    }
 
     // Show ALL failed params if there are issues
     if !failed_params.is_empty() && failed_params.len() < 50 {
-        println!("[SETFILE] Rust: ALL failed params:");
+        tracing::debug!("ALL failed params:");
         for param in &failed_params {
-            println!("[SETFILE] Rust:   FAILED: {}", param);
+            tracing::debug!("FAILED: {}", param);
         }
     } else if !failed_params.is_empty() {
-        println!("[SETFILE] Rust: First 50 failed params:");
+        tracing::debug!("First 50 failed params:");
         for param in failed_params.iter().take(50) {
-            println!("[SETFILE] Rust:   FAILED: {}", param);
+            tracing::debug!("FAILED: {}", param);
         }
     }
 
     // Debug: Show engine data structure
-    println!("[SETFILE] Rust: Engine data structure:");
+    tracing::debug!("Engine data structure:");
     for (engine, groups) in &engine_data {
-        println!(
-            "[SETFILE] Rust:   Engine {}: {} groups",
+        tracing::debug!(
+            "Engine {}: {} groups",
             engine,
             groups.len()
         );
         let mut total_logics = 0;
         let mut total_directions = 0;
         for (group_num, logics) in groups {
-            println!(
-                "[SETFILE] Rust:     Group {}: {} logics",
+            tracing::debug!(
+                "Group {}: {} logics",
                 group_num,
                 logics.len()
             );
             for (logic, directions) in logics {
                 total_logics += 1;
-                println!(
-                    "[SETFILE] Rust:       {}: {} directions",
+                tracing::debug!(
+                    "{}: {} directions",
                     logic,
                     directions.len()
                 );
                 total_directions += directions.len();
             }
         }
-        println!(
-            "[SETFILE] Rust:     Total: {} logics, {} directions",
+        tracing::debug!(
+            "Total: {} logics, {} directions",
             total_logics, total_directions
         );
     }
@@ -5797,14 +5152,14 @@ fn build_engines_from_values(
         }
     }
 
-    println!(
-        "[SETFILE] Rust: Final config - {} engines, {} groups, {} logics, {} directions",
+    tracing::info!(
+        "Final config - {} engines, {} groups, {} logics, {} directions",
         engines.len(),
         total_groups,
         total_logics,
         total_directions
     );
-    println!("[SETFILE] Rust: Expected: 3 engines, 15 groups/logic, 7 logics, 630 directions");
+    tracing::debug!("Expected: 3 engines, 15 groups/logic, 7 logics, 630 directions");
 
     Ok(engines)
 }
@@ -5854,8 +5209,8 @@ fn build_group_config(
             && fallback_value.is_some()
             && (engine_id == "B" || engine_id == "C")
         {
-            println!(
-                "[SETFILE] WARN: {} missing; using {} fallback for Engine {} Group {}.",
+            tracing::warn!(
+                "{} missing; using {} fallback for Engine {} Group {}.",
                 key_primary, key_fallback_p, engine_id, group_num
             );
         }
@@ -5905,34 +5260,28 @@ fn build_logic_config(
 ) -> Result<LogicConfig, String> {
     // Build the logic suffix for looking up values
     let logic_suffix = get_logic_suffix(engine_id, group_num, logic_name);
+    let defaults = logic_defaults(logic_name);
     let short_logic = get_logic_short(engine_id, logic_name);
 
     // Get Buy and Sell parameter maps
     let buy_params = direction_data.get("Buy");
     let sell_params = direction_data.get("Sell");
 
-    // Debug logging for first few logics - using AtomicUsize for thread safety
-    use std::sync::atomic::{AtomicUsize, Ordering};
-    static LOGIC_DEBUG_COUNT: AtomicUsize = AtomicUsize::new(0);
-    let count = LOGIC_DEBUG_COUNT.fetch_add(1, Ordering::SeqCst);
-    let should_log = count < 10;
-
-    if should_log {
-        println!("[SETFILE] Rust: Building logic config for Engine {} Group {} Logic '{}' (suffix: {})",
-                 engine_id, group_num, logic_name, logic_suffix);
-        if let Some(buy) = buy_params {
-            println!("[SETFILE] Rust:   Buy params available: {}", buy.len());
-            if let Some(initial_lot) = buy.get("InitialLot") {
-                println!("[SETFILE] Rust:   InitialLot (Buy): {}", initial_lot);
-            }
-        } else {
-            println!("[SETFILE] Rust:   NO Buy params!");
-        }
-        if let Some(sell) = sell_params {
-            println!("[SETFILE] Rust:   Sell params available: {}", sell.len());
-        } else {
-            println!("[SETFILE] Rust:   NO Sell params!");
-        }
+    tracing::debug!(
+        engine = engine_id,
+        group = group_num,
+        logic = logic_name,
+        suffix = %logic_suffix,
+        buy_params = buy_params.map(|p| p.len()).unwrap_or(0),
+        sell_params = sell_params.map(|p| p.len()).unwrap_or(0),
+        initial_lot_buy = ?buy_params.and_then(|p| p.get("InitialLot")),
+        "Building logic config"
+    );
+    if buy_params.is_none() {
+        tracing::warn!(engine = engine_id, group = group_num, logic = logic_name, "No Buy params");
+    }
+    if sell_params.is_none() {
+        tracing::warn!(engine = engine_id, group = group_num, logic = logic_name, "No Sell params");
     }
 
     // Helper to get parameter value with fallback
@@ -5990,9 +5339,13 @@ fn build_logic_config(
             .unwrap_or(default)
     };
 
-    let get_param_bool_multi = |variants: &[&str]| -> bool {
-        let v = get_param_multi(variants, "0");
-        v == "1" || v.to_lowercase() == "true"
+    let get_param_bool_multi = |variants: &[&str], default: bool| -> bool {
+        let v = get_param_multi(variants, "");
+        if v.is_empty() {
+            default
+        } else {
+            decode_bool(&v)
+        }
     };
 
     let get_dir_f64 = |variants: &[&str], direction: &str| -> Option<f64> {
@@ -6129,37 +5482,40 @@ fn build_logic_config(
     };
 
     // Parse base parameters with multiple name variants for compatibility
-    let initial_lot = get_param_f64_multi(&["InitialLot", "Initial_loT"], 0.02);
-    let initial_lot_b = get_dir_f64(&["InitialLot", "Initial_loT"], "Buy");
-    let initial_lot_s = get_dir_f64(&["InitialLot", "Initial_loT"], "Sell");
+    let initial_lot = get_param_f64_multi(INITIAL_LOT.lookup, defaults.initial_lot);
+    let initial_lot_b = get_dir_f64(INITIAL_LOT.lookup, "Buy");
+    let initial_lot_s = get_dir_f64(INITIAL_LOT.lookup, "Sell");
 
-    let multiplier = get_param_f64_multi(&["Multiplier", "Mult"], 1.2);
-    let multiplier_b = get_dir_f64(&["Multiplier", "Mult"], "Buy");
-    let multiplier_s = get_dir_f64(&["Multiplier", "Mult"], "Sell");
+    let multiplier = get_param_f64_multi(MULTIPLIER.lookup, defaults.multiplier);
+    let multiplier_b = get_dir_f64(MULTIPLIER.lookup, "Buy");
+    let multiplier_s = get_dir_f64(MULTIPLIER.lookup, "Sell");
 
-    let grid = get_param_f64_multi(&["Grid"], 300.0);
-    let grid_b = get_dir_f64(&["Grid"], "Buy");
-    let grid_s = get_dir_f64(&["Grid"], "Sell");
+    let grid = get_param_f64_multi(GRID.lookup, defaults.grid);
+    let grid_b = get_dir_f64(GRID.lookup, "Buy");
+    let grid_s = get_dir_f64(GRID.lookup, "Sell");
 
-    let trail_method = get_param_multi(&["TrailMethod", "Trail"], "0");
-    let trail_value = get_param_f64_multi(&["TrailValue"], 3000.0);
-    let trail_value_b = get_dir_f64(&["TrailValue"], "Buy");
-    let trail_value_s = get_dir_f64(&["TrailValue"], "Sell");
+    let trail_method = decode_setfile_enum(&get_param_multi(TRAIL_METHOD.lookup, "")).unwrap_or(defaults.trail_method);
+    let trail_value = get_param_f64_multi(TRAIL_VALUE.lookup, defaults.trail_value);
+    let trail_value_b = get_dir_f64(TRAIL_VALUE.lookup, "Buy");
+    let trail_value_s = get_dir_f64(TRAIL_VALUE.lookup, "Sell");
 
-    let trail_start = get_param_f64_multi(&["TrailStart", "Trail_Start"], 1.0);
-    let trail_start_b = get_dir_f64(&["TrailStart", "Trail_Start"], "Buy");
-    let trail_start_s = get_dir_f64(&["TrailStart", "Trail_Start"], "Sell");
+    let trail_start = get_param_f64_multi(TRAIL_START.lookup, defaults.trail_start);
+    let trail_start_b = get_dir_f64(TRAIL_START.lookup, "Buy");
+    let trail_start_s = get_dir_f64(TRAIL_START.lookup, "Sell");
 
-    let trail_step = get_param_f64_multi(&["TrailStep"], 1500.0);
-    let trail_step_b = get_dir_f64(&["TrailStep"], "Buy");
-    let trail_step_s = get_dir_f64(&["TrailStep"], "Sell");
+    let trail_step = get_param_f64_multi(TRAIL_STEP.lookup, defaults.trail_step);
+    let trail_step_b = get_dir_f64(TRAIL_STEP.lookup, "Buy");
+    let trail_step_s = get_dir_f64(TRAIL_STEP.lookup, "Sell");
 
-    let trail_step_method = get_param_multi(&["TrailStepMethod"], "0");
+    let trail_step_method = decode_setfile_enum(&get_param_multi(TRAIL_STEP_METHOD.lookup, "")).unwrap_or(defaults.trail_step_method);
 
     // Parse logic-specific parameters
     let is_power_logic = logic_name.eq_ignore_ascii_case("power");
     let start_level = if !is_power_logic {
-        Some(get_param_i32_multi(&["StartLevel", &format!("Start{}", logic_name)], 100))
+        Some(get_param_i32_multi(
+            &["StartLevel", &format!("Start{}", logic_name)],
+            defaults.start_level.unwrap_or_default(),
+        ))
     } else {
         None
     };
@@ -6167,20 +5523,20 @@ fn build_logic_config(
     let start_level_s = if !is_power_logic { get_dir_i32(&["StartLevel", &format!("Start{}", logic_name)], "Sell") } else { None };
 
     let last_lot = if !is_power_logic {
-        Some(get_param_f64_multi(&["LastLot", &format!("LastLot{}", logic_name)], 0.12))
+        Some(get_param_f64_multi(&["LastLot", &format!("LastLot{}", logic_name)], defaults.last_lot))
     } else {
-        Some(get_param_f64_multi(&["LastLot", "LastLotPower"], 0.63))
+        Some(get_param_f64_multi(&["LastLot", "LastLotPower"], defaults.last_lot))
     };
     let last_lot_b = get_dir_f64(&["LastLot", &format!("LastLot{}", logic_name)], "Buy");
     let last_lot_s = get_dir_f64(&["LastLot", &format!("LastLot{}", logic_name)], "Sell");
 
     // Parse close_targets - NO default fallback, use empty string if not found
-    let close_targets = get_param_multi(&["CloseTargets"], "");
+    let close_targets = get_param_multi(&["CloseTargets"], defaults.close_targets);
     let close_targets_b = get_dir_string(&["CloseTargets"], "Buy");
     let close_targets_s = get_dir_string(&["CloseTargets"], "Sell");
     let order_count_reference = get_param_multi(
         &["OrderCountRef", "OrderCountReference", "OrderCountReferenceLogic"],
-        "Logic_None",
+        defaults.order_count_reference,
     );
     let order_count_reference_b = get_dir_string(&["OrderCountRef", "OrderCountReference", "OrderCountReferenceLogic"], "Buy");
     let order_count_reference_s = get_dir_string(&["OrderCountRef", "OrderCountReference", "OrderCountReferenceLogic"], "Sell");
@@ -6205,80 +5561,79 @@ fn build_logic_config(
     }
     let group_order_count_reference_b = get_dir_string(&["GroupOrderCountRef", "GroupOrderCountReference", "GroupOrderCountReferenceLogic"], "Buy");
     let group_order_count_reference_s = get_dir_string(&["GroupOrderCountRef", "GroupOrderCountReference", "GroupOrderCountReferenceLogic"], "Sell");
-    let reset_lot_on_restart = get_param_bool_multi(&["ResetLotOnRestart"]);
+    let reset_lot_on_restart = get_param_bool_multi(&["ResetLotOnRestart"], defaults.reset_lot_on_restart);
     let reset_lot_on_restart_b = get_dir_bool(&["ResetLotOnRestart"], "Buy");
     let reset_lot_on_restart_s = get_dir_bool(&["ResetLotOnRestart"], "Sell");
 
     // Parse mode selectors
-    let strategy_type = get_param_multi(&["StrategyType"], "Trail");
-    let strategy_type_b = get_dir_string(&["StrategyType"], "Buy");
-    let strategy_type_s = get_dir_string(&["StrategyType"], "Sell");
-    let trading_mode = get_param_multi(&["TradingMode"], "Counter Trend");
-    let trading_mode_b = get_dir_string(&["TradingMode"], "Buy");
-    let trading_mode_s = get_dir_string(&["TradingMode"], "Sell");
-    let allow_buy = get_param_bool_multi(&["AllowBuy"]);
-    let allow_sell = get_param_bool_multi(&["AllowSell"]);
+    let strategy_type = decode_setfile_enum(&get_param_multi(STRATEGY_TYPE.lookup, "")).unwrap_or(defaults.strategy_type);
+    let strategy_type_b = get_dir_string(STRATEGY_TYPE.lookup, "Buy").and_then(|v| decode_setfile_enum(&v));
+    let strategy_type_s = get_dir_string(STRATEGY_TYPE.lookup, "Sell").and_then(|v| decode_setfile_enum(&v));
+    let trading_mode = decode_setfile_enum(&get_param_multi(TRADING_MODE.lookup, "")).or(Some(defaults.trading_mode));
+    let trading_mode_b = get_dir_string(TRADING_MODE.lookup, "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trading_mode_s = get_dir_string(TRADING_MODE.lookup, "Sell").and_then(|v| decode_setfile_enum(&v));
+    let allow_buy = get_param_bool_multi(ALLOW_BUY.lookup, defaults.allow_buy);
+    let allow_sell = get_param_bool_multi(ALLOW_SELL.lookup, defaults.allow_sell);
 
     // Parse reverse/hedge parameters with multiple name variants
-    let reverse_enabled = get_param_bool_multi(&["ReverseEnabled", &format!("G{}_{}_ReverseEnabled", group_num, short_logic)]);
+    let reverse_enabled = get_param_bool_multi(&["ReverseEnabled", &format!("G{}_{}_ReverseEnabled", group_num, short_logic)], defaults.reverse_enabled);
     let reverse_enabled_b = get_dir_bool(&["ReverseEnabled", &format!("G{}_{}_ReverseEnabled", group_num, short_logic)], "Buy");
     let reverse_enabled_s = get_dir_bool(&["ReverseEnabled", &format!("G{}_{}_ReverseEnabled", group_num, short_logic)], "Sell");
-    let hedge_enabled = get_param_bool_multi(&["HedgeEnabled", &format!("G{}_{}_HedgeEnabled", group_num, short_logic)]);
+    let hedge_enabled = get_param_bool_multi(&["HedgeEnabled", &format!("G{}_{}_HedgeEnabled", group_num, short_logic)], defaults.hedge_enabled);
     let hedge_enabled_b = get_dir_bool(&["HedgeEnabled", &format!("G{}_{}_HedgeEnabled", group_num, short_logic)], "Buy");
     let hedge_enabled_s = get_dir_bool(&["HedgeEnabled", &format!("G{}_{}_HedgeEnabled", group_num, short_logic)], "Sell");
     let reverse_scale = get_param_f64_multi(
         &["ReverseScale", &format!("G{}_Scale_{}_Reverse", group_num, short_logic)],
-        100.0,
+        defaults.reverse_scale,
     );
     let reverse_scale_b = get_dir_f64(&["ReverseScale", &format!("G{}_Scale_{}_Reverse", group_num, short_logic)], "Buy");
     let reverse_scale_s = get_dir_f64(&["ReverseScale", &format!("G{}_Scale_{}_Reverse", group_num, short_logic)], "Sell");
-    let hedge_scale = get_param_f64_multi(&["HedgeScale", &format!("G{}_Scale_{}_Hedge", group_num, short_logic)], 50.0);
+    let hedge_scale = get_param_f64_multi(&["HedgeScale", &format!("G{}_Scale_{}_Hedge", group_num, short_logic)], defaults.hedge_scale);
     let hedge_scale_b = get_dir_f64(&["HedgeScale", &format!("G{}_Scale_{}_Hedge", group_num, short_logic)], "Buy");
     let hedge_scale_s = get_dir_f64(&["HedgeScale", &format!("G{}_Scale_{}_Hedge", group_num, short_logic)], "Sell");
     let reverse_reference = get_param_multi(
         &["ReverseReference", &format!("G{}_{}_ReverseReference", group_num, short_logic)],
-        "Logic_None",
+        defaults.reverse_reference,
     );
     let reverse_reference_b = get_dir_string(&["ReverseReference", &format!("G{}_{}_ReverseReference", group_num, short_logic)], "Buy");
     let reverse_reference_s = get_dir_string(&["ReverseReference", &format!("G{}_{}_ReverseReference", group_num, short_logic)], "Sell");
     let hedge_reference = get_param_multi(
         &["HedgeReference", &format!("G{}_{}_HedgeReference", group_num, short_logic)],
-        "Logic_None",
+        defaults.hedge_reference,
     );
     let hedge_reference_b = get_dir_string(&["HedgeReference", &format!("G{}_{}_HedgeReference", group_num, short_logic)], "Buy");
     let hedge_reference_s = get_dir_string(&["HedgeReference", &format!("G{}_{}_HedgeReference", group_num, short_logic)], "Sell");
 
     // Parse trail step advanced parameters with multiple name variants
-    let trail_step_mode = get_param_multi(&["TrailStepMode"], "TrailStepMode_Auto");
-    let trail_step_mode_b = get_dir_string(&["TrailStepMode"], "Buy");
-    let trail_step_mode_s = get_dir_string(&["TrailStepMode"], "Sell");
-    let trail_step_cycle = get_param_i32_multi(&["TrailStepCycle"], 1);
-    let trail_step_cycle_b = get_dir_i32(&["TrailStepCycle"], "Buy");
-    let trail_step_cycle_s = get_dir_i32(&["TrailStepCycle"], "Sell");
-    let trail_step_balance = get_param_f64_multi(&["TrailStepBalance"], 0.0);
-    let trail_step_balance_b = get_dir_f64(&["TrailStepBalance"], "Buy");
-    let trail_step_balance_s = get_dir_f64(&["TrailStepBalance"], "Sell");
+    let trail_step_mode = decode_setfile_enum(&get_param_multi(TRAIL_STEP_MODE.lookup, "")).unwrap_or(defaults.trail_step_mode);
+    let trail_step_mode_b = get_dir_string(TRAIL_STEP_MODE.lookup, "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_s = get_dir_string(TRAIL_STEP_MODE.lookup, "Sell").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_cycle = get_param_i32_multi(TRAIL_STEP_CYCLE.lookup, defaults.trail_step_cycle);
+    let trail_step_cycle_b = get_dir_i32(TRAIL_STEP_CYCLE.lookup, "Buy");
+    let trail_step_cycle_s = get_dir_i32(TRAIL_STEP_CYCLE.lookup, "Sell");
+    let trail_step_balance = get_param_f64_multi(TRAIL_STEP_BALANCE.lookup, defaults.trail_step_balance);
+    let trail_step_balance_b = get_dir_f64(TRAIL_STEP_BALANCE.lookup, "Buy");
+    let trail_step_balance_s = get_dir_f64(TRAIL_STEP_BALANCE.lookup, "Sell");
 
     // Parse close partial parameters with multiple name variants
-    let close_partial = get_param_bool_multi(&["PartialEnabled1", "ClosePartial"]);
+    let close_partial = get_param_bool_multi(&["PartialEnabled1", "ClosePartial"], defaults.close_partial);
     let close_partial_b = get_dir_bool(&["PartialEnabled1", "ClosePartial"], "Buy");
     let close_partial_s = get_dir_bool(&["PartialEnabled1", "ClosePartial"], "Sell");
-    let close_partial_cycle = get_param_i32_multi(&["PartialCycle1", "ClosePartialCycle"], 1);
+    let close_partial_cycle = get_param_i32_multi(&["PartialCycle1", "ClosePartialCycle"], defaults.close_partial_cycle);
     let close_partial_cycle_b = get_dir_i32(&["PartialCycle1", "ClosePartialCycle"], "Buy");
     let close_partial_cycle_s = get_dir_i32(&["PartialCycle1", "ClosePartialCycle"], "Sell");
-    let close_partial_mode = get_param_multi(&["PartialMode1", "ClosePartialMode"], "PartialMode_Mid");
-    let close_partial_mode_b = get_dir_string(&["PartialMode1", "ClosePartialMode"], "Buy");
-    let close_partial_mode_s = get_dir_string(&["PartialMode1", "ClosePartialMode"], "Sell");
-    let close_partial_balance = get_param_multi(&["PartialBalance1", "ClosePartialBalance"], "PartialBalance_Balanced");
-    let close_partial_balance_b = get_dir_string(&["PartialBalance1", "ClosePartialBalance"], "Buy");
-    let close_partial_balance_s = get_dir_string(&["PartialBalance1", "ClosePartialBalance"], "Sell");
-    let close_partial_trail_step_mode =
-        get_param_multi(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "TrailStepMode_Auto");
-    let close_partial_trail_step_mode_b = get_dir_string(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "Buy");
-    let close_partial_trail_step_mode_s = get_dir_string(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "Sell");
+    let close_partial_mode = decode_setfile_enum(&get_param_multi(&["PartialMode1", "ClosePartialMode"], "")).unwrap_or(defaults.close_partial_mode);
+    let close_partial_mode_b = get_dir_string(&["PartialMode1", "ClosePartialMode"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_mode_s = get_dir_string(&["PartialMode1", "ClosePartialMode"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance = decode_setfile_enum(&get_param_multi(&["PartialBalance1", "ClosePartialBalance"], "")).unwrap_or(defaults.close_partial_balance);
+    let close_partial_balance_b = get_dir_string(&["PartialBalance1", "ClosePartialBalance"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_s = get_dir_string(&["PartialBalance1", "ClosePartialBalance"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_trail_step_mode = decode_setfile_enum(&get_param_multi(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "")).unwrap_or(defaults.close_partial_trail_step_mode);
+    let close_partial_trail_step_mode_b = get_dir_string(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_trail_step_mode_s = get_dir_string(&["PartialTrailMode1", "ClosePartialTrailStepMode"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let close_partial_profit_threshold = get_param_f64_multi(
         &["PartialProfitThreshold1", "ClosePartialProfitThreshold"],
-        0.0,
+        defaults.close_partial_profit_threshold,
     );
     let close_partial_profit_threshold_b = get_dir_f64(&["PartialProfitThreshold1", "ClosePartialProfitThreshold"], "Buy");
     let close_partial_profit_threshold_s = get_dir_f64(&["PartialProfitThreshold1", "ClosePartialProfitThreshold"], "Sell");
@@ -6287,98 +5642,98 @@ fn build_logic_config(
     let trail_step_2 = get_param_f64_multi(&["TrailStep2", "Trail_Step_2"], 0.0);
     let trail_step_2_b = get_dir_f64(&["TrailStep2", "Trail_Step_2"], "Buy");
     let trail_step_2_s = get_dir_f64(&["TrailStep2", "Trail_Step_2"], "Sell");
-    let trail_step_method_2 = get_param_multi(&["TrailStepMethod2", "Trail_Step_Method_2"], "");
-    let trail_step_method_2_b = get_dir_string(&["TrailStepMethod2", "Trail_Step_Method_2"], "Buy");
-    let trail_step_method_2_s = get_dir_string(&["TrailStepMethod2", "Trail_Step_Method_2"], "Sell");
+    let trail_step_method_2 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod2", "Trail_Step_Method_2"], ""));
+    let trail_step_method_2_b = get_dir_string(&["TrailStepMethod2", "Trail_Step_Method_2"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_2_s = get_dir_string(&["TrailStepMethod2", "Trail_Step_Method_2"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_2 = get_param_i32_multi(&["TrailStepCycle2", "Trail_Step_Cycle_2"], 1);
     let trail_step_cycle_2_b = get_dir_i32(&["TrailStepCycle2", "Trail_Step_Cycle_2"], "Buy");
     let trail_step_cycle_2_s = get_dir_i32(&["TrailStepCycle2", "Trail_Step_Cycle_2"], "Sell");
     let trail_step_balance_2 = get_param_f64_multi(&["TrailStepBalance2", "Trail_Step_Balance_2"], 0.0);
     let trail_step_balance_2_b = get_dir_f64(&["TrailStepBalance2", "Trail_Step_Balance_2"], "Buy");
     let trail_step_balance_2_s = get_dir_f64(&["TrailStepBalance2", "Trail_Step_Balance_2"], "Sell");
-    let trail_step_mode_2 = get_param_multi(&["TrailStepMode2", "Trail_Step_Mode_2"], "TrailStepMode_Auto");
-    let trail_step_mode_2_b = get_dir_string(&["TrailStepMode2", "Trail_Step_Mode_2"], "Buy");
-    let trail_step_mode_2_s = get_dir_string(&["TrailStepMode2", "Trail_Step_Mode_2"], "Sell");
+    let trail_step_mode_2 = decode_setfile_enum(&get_param_multi(&["TrailStepMode2", "Trail_Step_Mode_2"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_2_b = get_dir_string(&["TrailStepMode2", "Trail_Step_Mode_2"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_2_s = get_dir_string(&["TrailStepMode2", "Trail_Step_Mode_2"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     let trail_step_3 = get_param_f64_multi(&["TrailStep3", "Trail_Step_3"], 0.0);
     let trail_step_3_b = get_dir_f64(&["TrailStep3", "Trail_Step_3"], "Buy");
     let trail_step_3_s = get_dir_f64(&["TrailStep3", "Trail_Step_3"], "Sell");
-    let trail_step_method_3 = get_param_multi(&["TrailStepMethod3", "Trail_Step_Method_3"], "");
-    let trail_step_method_3_b = get_dir_string(&["TrailStepMethod3", "Trail_Step_Method_3"], "Buy");
-    let trail_step_method_3_s = get_dir_string(&["TrailStepMethod3", "Trail_Step_Method_3"], "Sell");
+    let trail_step_method_3 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod3", "Trail_Step_Method_3"], ""));
+    let trail_step_method_3_b = get_dir_string(&["TrailStepMethod3", "Trail_Step_Method_3"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_3_s = get_dir_string(&["TrailStepMethod3", "Trail_Step_Method_3"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_3 = get_param_i32_multi(&["TrailStepCycle3", "Trail_Step_Cycle_3"], 1);
     let trail_step_cycle_3_b = get_dir_i32(&["TrailStepCycle3", "Trail_Step_Cycle_3"], "Buy");
     let trail_step_cycle_3_s = get_dir_i32(&["TrailStepCycle3", "Trail_Step_Cycle_3"], "Sell");
     let trail_step_balance_3 = get_param_f64_multi(&["TrailStepBalance3", "Trail_Step_Balance_3"], 0.0);
     let trail_step_balance_3_b = get_dir_f64(&["TrailStepBalance3", "Trail_Step_Balance_3"], "Buy");
     let trail_step_balance_3_s = get_dir_f64(&["TrailStepBalance3", "Trail_Step_Balance_3"], "Sell");
-    let trail_step_mode_3 = get_param_multi(&["TrailStepMode3", "Trail_Step_Mode_3"], "TrailStepMode_Auto");
-    let trail_step_mode_3_b = get_dir_string(&["TrailStepMode3", "Trail_Step_Mode_3"], "Buy");
-    let trail_step_mode_3_s = get_dir_string(&["TrailStepMode3", "Trail_Step_Mode_3"], "Sell");
+    let trail_step_mode_3 = decode_setfile_enum(&get_param_multi(&["TrailStepMode3", "Trail_Step_Mode_3"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_3_b = get_dir_string(&["TrailStepMode3", "Trail_Step_Mode_3"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_3_s = get_dir_string(&["TrailStepMode3", "Trail_Step_Mode_3"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     let trail_step_4 = get_param_f64_multi(&["TrailStep4", "Trail_Step_4"], 0.0);
     let trail_step_4_b = get_dir_f64(&["TrailStep4", "Trail_Step_4"], "Buy");
     let trail_step_4_s = get_dir_f64(&["TrailStep4", "Trail_Step_4"], "Sell");
-    let trail_step_method_4 = get_param_multi(&["TrailStepMethod4", "Trail_Step_Method_4"], "");
-    let trail_step_method_4_b = get_dir_string(&["TrailStepMethod4", "Trail_Step_Method_4"], "Buy");
-    let trail_step_method_4_s = get_dir_string(&["TrailStepMethod4", "Trail_Step_Method_4"], "Sell");
+    let trail_step_method_4 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod4", "Trail_Step_Method_4"], ""));
+    let trail_step_method_4_b = get_dir_string(&["TrailStepMethod4", "Trail_Step_Method_4"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_4_s = get_dir_string(&["TrailStepMethod4", "Trail_Step_Method_4"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_4 = get_param_i32_multi(&["TrailStepCycle4", "Trail_Step_Cycle_4"], 1);
     let trail_step_cycle_4_b = get_dir_i32(&["TrailStepCycle4", "Trail_Step_Cycle_4"], "Buy");
     let trail_step_cycle_4_s = get_dir_i32(&["TrailStepCycle4", "Trail_Step_Cycle_4"], "Sell");
     let trail_step_balance_4 = get_param_f64_multi(&["TrailStepBalance4", "Trail_Step_Balance_4"], 0.0);
     let trail_step_balance_4_b = get_dir_f64(&["TrailStepBalance4", "Trail_Step_Balance_4"], "Buy");
     let trail_step_balance_4_s = get_dir_f64(&["TrailStepBalance4", "Trail_Step_Balance_4"], "Sell");
-    let trail_step_mode_4 = get_param_multi(&["TrailStepMode4", "Trail_Step_Mode_4"], "TrailStepMode_Auto");
-    let trail_step_mode_4_b = get_dir_string(&["TrailStepMode4", "Trail_Step_Mode_4"], "Buy");
-    let trail_step_mode_4_s = get_dir_string(&["TrailStepMode4", "Trail_Step_Mode_4"], "Sell");
+    let trail_step_mode_4 = decode_setfile_enum(&get_param_multi(&["TrailStepMode4", "Trail_Step_Mode_4"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_4_b = get_dir_string(&["TrailStepMode4", "Trail_Step_Mode_4"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_4_s = get_dir_string(&["TrailStepMode4", "Trail_Step_Mode_4"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     let trail_step_5 = get_param_f64_multi(&["TrailStep5", "Trail_Step_5"], 0.0);
     let trail_step_5_b = get_dir_f64(&["TrailStep5", "Trail_Step_5"], "Buy");
     let trail_step_5_s = get_dir_f64(&["TrailStep5", "Trail_Step_5"], "Sell");
-    let trail_step_method_5 = get_param_multi(&["TrailStepMethod5", "Trail_Step_Method_5"], "");
-    let trail_step_method_5_b = get_dir_string(&["TrailStepMethod5", "Trail_Step_Method_5"], "Buy");
-    let trail_step_method_5_s = get_dir_string(&["TrailStepMethod5", "Trail_Step_Method_5"], "Sell");
+    let trail_step_method_5 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod5", "Trail_Step_Method_5"], ""));
+    let trail_step_method_5_b = get_dir_string(&["TrailStepMethod5", "Trail_Step_Method_5"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_5_s = get_dir_string(&["TrailStepMethod5", "Trail_Step_Method_5"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_5 = get_param_i32_multi(&["TrailStepCycle5", "Trail_Step_Cycle_5"], 1);
     let trail_step_cycle_5_b = get_dir_i32(&["TrailStepCycle5", "Trail_Step_Cycle_5"], "Buy");
     let trail_step_cycle_5_s = get_dir_i32(&["TrailStepCycle5", "Trail_Step_Cycle_5"], "Sell");
     let trail_step_balance_5 = get_param_f64_multi(&["TrailStepBalance5", "Trail_Step_Balance_5"], 0.0);
     let trail_step_balance_5_b = get_dir_f64(&["TrailStepBalance5", "Trail_Step_Balance_5"], "Buy");
     let trail_step_balance_5_s = get_dir_f64(&["TrailStepBalance5", "Trail_Step_Balance_5"], "Sell");
-    let trail_step_mode_5 = get_param_multi(&["TrailStepMode5", "Trail_Step_Mode_5"], "TrailStepMode_Auto");
-    let trail_step_mode_5_b = get_dir_string(&["TrailStepMode5", "Trail_Step_Mode_5"], "Buy");
-    let trail_step_mode_5_s = get_dir_string(&["TrailStepMode5", "Trail_Step_Mode_5"], "Sell");
+    let trail_step_mode_5 = decode_setfile_enum(&get_param_multi(&["TrailStepMode5", "Trail_Step_Mode_5"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_5_b = get_dir_string(&["TrailStepMode5", "Trail_Step_Mode_5"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_5_s = get_dir_string(&["TrailStepMode5", "Trail_Step_Mode_5"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     let trail_step_6 = get_param_f64_multi(&["TrailStep6", "Trail_Step_6"], 0.0);
     let trail_step_6_b = get_dir_f64(&["TrailStep6", "Trail_Step_6"], "Buy");
     let trail_step_6_s = get_dir_f64(&["TrailStep6", "Trail_Step_6"], "Sell");
-    let trail_step_method_6 = get_param_multi(&["TrailStepMethod6", "Trail_Step_Method_6"], "");
-    let trail_step_method_6_b = get_dir_string(&["TrailStepMethod6", "Trail_Step_Method_6"], "Buy");
-    let trail_step_method_6_s = get_dir_string(&["TrailStepMethod6", "Trail_Step_Method_6"], "Sell");
+    let trail_step_method_6 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod6", "Trail_Step_Method_6"], ""));
+    let trail_step_method_6_b = get_dir_string(&["TrailStepMethod6", "Trail_Step_Method_6"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_6_s = get_dir_string(&["TrailStepMethod6", "Trail_Step_Method_6"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_6 = get_param_i32_multi(&["TrailStepCycle6", "Trail_Step_Cycle_6"], 1);
     let trail_step_cycle_6_b = get_dir_i32(&["TrailStepCycle6", "Trail_Step_Cycle_6"], "Buy");
     let trail_step_cycle_6_s = get_dir_i32(&["TrailStepCycle6", "Trail_Step_Cycle_6"], "Sell");
     let trail_step_balance_6 = get_param_f64_multi(&["TrailStepBalance6", "Trail_Step_Balance_6"], 0.0);
     let trail_step_balance_6_b = get_dir_f64(&["TrailStepBalance6", "Trail_Step_Balance_6"], "Buy");
     let trail_step_balance_6_s = get_dir_f64(&["TrailStepBalance6", "Trail_Step_Balance_6"], "Sell");
-    let trail_step_mode_6 = get_param_multi(&["TrailStepMode6", "Trail_Step_Mode_6"], "TrailStepMode_Auto");
-    let trail_step_mode_6_b = get_dir_string(&["TrailStepMode6", "Trail_Step_Mode_6"], "Buy");
-    let trail_step_mode_6_s = get_dir_string(&["TrailStepMode6", "Trail_Step_Mode_6"], "Sell");
+    let trail_step_mode_6 = decode_setfile_enum(&get_param_multi(&["TrailStepMode6", "Trail_Step_Mode_6"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_6_b = get_dir_string(&["TrailStepMode6", "Trail_Step_Mode_6"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_6_s = get_dir_string(&["TrailStepMode6", "Trail_Step_Mode_6"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     let trail_step_7 = get_param_f64_multi(&["TrailStep7", "Trail_Step_7"], 0.0);
     let trail_step_7_b = get_dir_f64(&["TrailStep7", "Trail_Step_7"], "Buy");
     let trail_step_7_s = get_dir_f64(&["TrailStep7", "Trail_Step_7"], "Sell");
-    let trail_step_method_7 = get_param_multi(&["TrailStepMethod7", "Trail_Step_Method_7"], "");
-    let trail_step_method_7_b = get_dir_string(&["TrailStepMethod7", "Trail_Step_Method_7"], "Buy");
-    let trail_step_method_7_s = get_dir_string(&["TrailStepMethod7", "Trail_Step_Method_7"], "Sell");
+    let trail_step_method_7 = decode_setfile_enum(&get_param_multi(&["TrailStepMethod7", "Trail_Step_Method_7"], ""));
+    let trail_step_method_7_b = get_dir_string(&["TrailStepMethod7", "Trail_Step_Method_7"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_method_7_s = get_dir_string(&["TrailStepMethod7", "Trail_Step_Method_7"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trail_step_cycle_7 = get_param_i32_multi(&["TrailStepCycle7", "Trail_Step_Cycle_7"], 1);
     let trail_step_cycle_7_b = get_dir_i32(&["TrailStepCycle7", "Trail_Step_Cycle_7"], "Buy");
     let trail_step_cycle_7_s = get_dir_i32(&["TrailStepCycle7", "Trail_Step_Cycle_7"], "Sell");
     let trail_step_balance_7 = get_param_f64_multi(&["TrailStepBalance7", "Trail_Step_Balance_7"], 0.0);
     let trail_step_balance_7_b = get_dir_f64(&["TrailStepBalance7", "Trail_Step_Balance_7"], "Buy");
     let trail_step_balance_7_s = get_dir_f64(&["TrailStepBalance7", "Trail_Step_Balance_7"], "Sell");
-    let trail_step_mode_7 = get_param_multi(&["TrailStepMode7", "Trail_Step_Mode_7"], "TrailStepMode_Auto");
-    let trail_step_mode_7_b = get_dir_string(&["TrailStepMode7", "Trail_Step_Mode_7"], "Buy");
-    let trail_step_mode_7_s = get_dir_string(&["TrailStepMode7", "Trail_Step_Mode_7"], "Sell");
+    let trail_step_mode_7 = decode_setfile_enum(&get_param_multi(&["TrailStepMode7", "Trail_Step_Mode_7"], "")).or(Some(TrailStepMode::Auto));
+    let trail_step_mode_7_b = get_dir_string(&["TrailStepMode7", "Trail_Step_Mode_7"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trail_step_mode_7_s = get_dir_string(&["TrailStepMode7", "Trail_Step_Mode_7"], "Sell").and_then(|v| decode_setfile_enum(&v));
 
     // Parse Close Partial 2-4 parameters with Buy/Sell variants
     let close_partial_2_val = get_param_multi(&["PartialEnabled2", "ClosePartial2"], "");
@@ -6389,14 +5744,12 @@ fn build_logic_config(
     let close_partial_cycle_2 = if close_partial_cycle_2_val.is_empty() { None } else { close_partial_cycle_2_val.parse().ok() };
     let close_partial_cycle_2_b = get_dir_i32(&["PartialCycle2", "ClosePartialCycle2"], "Buy");
     let close_partial_cycle_2_s = get_dir_i32(&["PartialCycle2", "ClosePartialCycle2"], "Sell");
-    let close_partial_mode_2_val = get_param_multi(&["PartialMode2", "ClosePartialMode2"], "");
-    let close_partial_mode_2 = if close_partial_mode_2_val.is_empty() { None } else { Some(close_partial_mode_2_val) };
-    let close_partial_mode_2_b = get_dir_string(&["PartialMode2", "ClosePartialMode2"], "Buy");
-    let close_partial_mode_2_s = get_dir_string(&["PartialMode2", "ClosePartialMode2"], "Sell");
-    let close_partial_balance_2_val = get_param_multi(&["PartialBalance2", "ClosePartialBalance2"], "");
-    let close_partial_balance_2 = if close_partial_balance_2_val.is_empty() { None } else { Some(close_partial_balance_2_val) };
-    let close_partial_balance_2_b = get_dir_string(&["PartialBalance2", "ClosePartialBalance2"], "Buy");
-    let close_partial_balance_2_s = get_dir_string(&["PartialBalance2", "ClosePartialBalance2"], "Sell");
+    let close_partial_mode_2 = decode_setfile_enum(&get_param_multi(&["PartialMode2", "ClosePartialMode2"], ""));
+    let close_partial_mode_2_b = get_dir_string(&["PartialMode2", "ClosePartialMode2"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_mode_2_s = get_dir_string(&["PartialMode2", "ClosePartialMode2"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_2 = decode_setfile_enum(&get_param_multi(&["PartialBalance2", "ClosePartialBalance2"], ""));
+    let close_partial_balance_2_b = get_dir_string(&["PartialBalance2", "ClosePartialBalance2"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_2_s = get_dir_string(&["PartialBalance2", "ClosePartialBalance2"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let close_partial_profit_threshold_2_val = get_param_multi(&["PartialProfitThreshold2", "ClosePartialProfitThreshold2"], "");
     let close_partial_profit_threshold_2 = if close_partial_profit_threshold_2_val.is_empty() { None } else { close_partial_profit_threshold_2_val.parse().ok() };
     let close_partial_profit_threshold_2_b = get_dir_f64(&["PartialProfitThreshold2", "ClosePartialProfitThreshold2"], "Buy");
@@ -6410,14 +5763,12 @@ fn build_logic_config(
     let close_partial_cycle_3 = if close_partial_cycle_3_val.is_empty() { None } else { close_partial_cycle_3_val.parse().ok() };
     let close_partial_cycle_3_b = get_dir_i32(&["PartialCycle3", "ClosePartialCycle3"], "Buy");
     let close_partial_cycle_3_s = get_dir_i32(&["PartialCycle3", "ClosePartialCycle3"], "Sell");
-    let close_partial_mode_3_val = get_param_multi(&["PartialMode3", "ClosePartialMode3"], "");
-    let close_partial_mode_3 = if close_partial_mode_3_val.is_empty() { None } else { Some(close_partial_mode_3_val) };
-    let close_partial_mode_3_b = get_dir_string(&["PartialMode3", "ClosePartialMode3"], "Buy");
-    let close_partial_mode_3_s = get_dir_string(&["PartialMode3", "ClosePartialMode3"], "Sell");
-    let close_partial_balance_3_val = get_param_multi(&["PartialBalance3", "ClosePartialBalance3"], "");
-    let close_partial_balance_3 = if close_partial_balance_3_val.is_empty() { None } else { Some(close_partial_balance_3_val) };
-    let close_partial_balance_3_b = get_dir_string(&["PartialBalance3", "ClosePartialBalance3"], "Buy");
-    let close_partial_balance_3_s = get_dir_string(&["PartialBalance3", "ClosePartialBalance3"], "Sell");
+    let close_partial_mode_3 = decode_setfile_enum(&get_param_multi(&["PartialMode3", "ClosePartialMode3"], ""));
+    let close_partial_mode_3_b = get_dir_string(&["PartialMode3", "ClosePartialMode3"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_mode_3_s = get_dir_string(&["PartialMode3", "ClosePartialMode3"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_3 = decode_setfile_enum(&get_param_multi(&["PartialBalance3", "ClosePartialBalance3"], ""));
+    let close_partial_balance_3_b = get_dir_string(&["PartialBalance3", "ClosePartialBalance3"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_3_s = get_dir_string(&["PartialBalance3", "ClosePartialBalance3"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let close_partial_profit_threshold_3_val = get_param_multi(&["PartialProfitThreshold3", "ClosePartialProfitThreshold3"], "");
     let close_partial_profit_threshold_3 = if close_partial_profit_threshold_3_val.is_empty() { None } else { close_partial_profit_threshold_3_val.parse().ok() };
     let close_partial_profit_threshold_3_b = get_dir_f64(&["PartialProfitThreshold3", "ClosePartialProfitThreshold3"], "Buy");
@@ -6431,28 +5782,24 @@ fn build_logic_config(
     let close_partial_cycle_4 = if close_partial_cycle_4_val.is_empty() { None } else { close_partial_cycle_4_val.parse().ok() };
     let close_partial_cycle_4_b = get_dir_i32(&["PartialCycle4", "ClosePartialCycle4"], "Buy");
     let close_partial_cycle_4_s = get_dir_i32(&["PartialCycle4", "ClosePartialCycle4"], "Sell");
-    let close_partial_mode_4_val = get_param_multi(&["PartialMode4", "ClosePartialMode4"], "");
-    let close_partial_mode_4 = if close_partial_mode_4_val.is_empty() { None } else { Some(close_partial_mode_4_val) };
-    let close_partial_mode_4_b = get_dir_string(&["PartialMode4", "ClosePartialMode4"], "Buy");
-    let close_partial_mode_4_s = get_dir_string(&["PartialMode4", "ClosePartialMode4"], "Sell");
-    let close_partial_balance_4_val = get_param_multi(&["PartialBalance4", "ClosePartialBalance4"], "");
-    let close_partial_balance_4 = if close_partial_balance_4_val.is_empty() { None } else { Some(close_partial_balance_4_val) };
-    let close_partial_balance_4_b = get_dir_string(&["PartialBalance4", "ClosePartialBalance4"], "Buy");
-    let close_partial_balance_4_s = get_dir_string(&["PartialBalance4", "ClosePartialBalance4"], "Sell");
+    let close_partial_mode_4 = decode_setfile_enum(&get_param_multi(&["PartialMode4", "ClosePartialMode4"], ""));
+    let close_partial_mode_4_b = get_dir_string(&["PartialMode4", "ClosePartialMode4"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_mode_4_s = get_dir_string(&["PartialMode4", "ClosePartialMode4"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_4 = decode_setfile_enum(&get_param_multi(&["PartialBalance4", "ClosePartialBalance4"], ""));
+    let close_partial_balance_4_b = get_dir_string(&["PartialBalance4", "ClosePartialBalance4"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let close_partial_balance_4_s = get_dir_string(&["PartialBalance4", "ClosePartialBalance4"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let close_partial_profit_threshold_4_val = get_param_multi(&["PartialProfitThreshold4", "ClosePartialProfitThreshold4"], "");
     let close_partial_profit_threshold_4 = if close_partial_profit_threshold_4_val.is_empty() { None } else { close_partial_profit_threshold_4_val.parse().ok() };
     let close_partial_profit_threshold_4_b = get_dir_f64(&["PartialProfitThreshold4", "ClosePartialProfitThreshold4"], "Buy");
     let close_partial_profit_threshold_4_s = get_dir_f64(&["PartialProfitThreshold4", "ClosePartialProfitThreshold4"], "Sell");
 
     // Parse Trigger parameters with Buy/Sell variants
-    let trigger_type_val = get_param_multi(&["TriggerType"], "");
-    let trigger_type = if trigger_type_val.is_empty() { None } else { Some(trigger_type_val) };
-    let trigger_type_b = get_dir_string(&["TriggerType"], "Buy");
-    let trigger_type_s = get_dir_string(&["TriggerType"], "Sell");
-    let trigger_mode_val = get_param_multi(&["TriggerMode"], "");
-    let trigger_mode = if trigger_mode_val.is_empty() { None } else { Some(trigger_mode_val) };
-    let trigger_mode_b = get_dir_string(&["TriggerMode"], "Buy");
-    let trigger_mode_s = get_dir_string(&["TriggerMode"], "Sell");
+    let trigger_type = decode_setfile_enum(&get_param_multi(&["TriggerType"], ""));
+    let trigger_type_b = get_dir_string(&["TriggerType"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trigger_type_s = get_dir_string(&["TriggerType"], "Sell").and_then(|v| decode_setfile_enum(&v));
+    let trigger_mode = decode_setfile_enum(&get_param_multi(&["TriggerMode"], ""));
+    let trigger_mode_b = get_dir_string(&["TriggerMode"], "Buy").and_then(|v| decode_setfile_enum(&v));
+    let trigger_mode_s = get_dir_string(&["TriggerMode"], "Sell").and_then(|v| decode_setfile_enum(&v));
     let trigger_bars_val = get_param_multi(&["TriggerBars"], "");
     let trigger_bars = if trigger_bars_val.is_empty() { None } else { trigger_bars_val.parse().ok() };
     let trigger_bars_b = get_dir_i32(&["TriggerBars"], "Buy");
@@ -6471,18 +5818,20 @@ fn build_logic_config(
     let trigger_pips_s = get_dir_f64(&["TriggerPips"], "Sell");
 
     // Check if logic is enabled with multiple name variants
-    let enabled = get_param_bool_multi(&["Start", "Enabled"]);
+    let enabled = get_param_bool_multi(&["Start", "Enabled"], false);
 
-    // Debug logging for extracted values
-    if should_log {
-        println!("[SETFILE] Rust:   Extracted values:");
-        println!("[SETFILE] Rust:     - enabled: {} (from variants: {:?})", enabled, &["Enabled", &format!("Start_{}", logic_suffix)]);
-        println!("[SETFILE] Rust:     - initial_lot: {} (from variants: {:?})", initial_lot, &["InitialLot", "Initial_loT"]);
-        println!("[SETFILE] Rust:     - multiplier: {} (from variants: {:?})", multiplier, &["Multiplier", "Mult"]);
-        println!("[SETFILE] Rust:     - grid: {} (from variants: {:?})", grid, &["Grid"]);
-        println!("[SETFILE] Rust:     - start_level: {:?}", start_level);
-        println!("[SETFILE] Rust:     - order_count_reference: {} (from variants: {:?})", order_count_reference, &["OrderCountRef", "OrderCountReference"]);
-    }
+    tracing::debug!(
+        engine = engine_id,
+        group = group_num,
+        logic = logic_name,
+        enabled,
+        initial_lot,
+        multiplier,
+        grid,
+        start_level = ?start_level,
+        order_count_reference = %order_count_reference,
+        "Extracted logic values"
+    );
 
     Ok(LogicConfig {
         logic_name: logic_name.to_string(),
@@ -6497,7 +5846,7 @@ fn build_logic_config(
         grid,
         grid_b,
         grid_s,
-        trail_method: decode_trail_method_numeric(&trail_method),
+        trail_method,
         trail_value,
         trail_value_b,
         trail_value_s,
@@ -6507,7 +5856,7 @@ fn build_logic_config(
         trail_step,
         trail_step_b,
         trail_step_s,
-        trail_step_method: decode_trail_step_method_numeric(&trail_step_method),
+        trail_step_method,
         start_level,
         start_level_b,
         start_level_s,
@@ -6564,7 +5913,7 @@ fn build_logic_config(
         trail_step_2: Some(trail_step_2),
         trail_step_2_b,
         trail_step_2_s,
-        trail_step_method_2: if trail_step_method_2.is_empty() { None } else { Some(trail_step_method_2) },
+        trail_step_method_2,
         trail_step_method_2_b,
         trail_step_method_2_s,
         trail_step_cycle_2: Some(trail_step_cycle_2),
@@ -6573,13 +5922,13 @@ fn build_logic_config(
         trail_step_balance_2: Some(trail_step_balance_2),
         trail_step_balance_2_b,
         trail_step_balance_2_s,
-        trail_step_mode_2: if trail_step_mode_2.is_empty() { None } else { Some(trail_step_mode_2) },
+        trail_step_mode_2,
         trail_step_mode_2_b,
         trail_step_mode_2_s,
         trail_step_3: Some(trail_step_3),
         trail_step_3_b,
         trail_step_3_s,
-        trail_step_method_3: if trail_step_method_3.is_empty() { None } else { Some(trail_step_method_3) },
+        trail_step_method_3,
         trail_step_method_3_b,
         trail_step_method_3_s,
         trail_step_cycle_3: Some(trail_step_cycle_3),
@@ -6588,13 +5937,13 @@ fn build_logic_config(
         trail_step_balance_3: Some(trail_step_balance_3),
         trail_step_balance_3_b,
         trail_step_balance_3_s,
-        trail_step_mode_3: if trail_step_mode_3.is_empty() { None } else { Some(trail_step_mode_3) },
+        trail_step_mode_3,
         trail_step_mode_3_b,
         trail_step_mode_3_s,
         trail_step_4: Some(trail_step_4),
         trail_step_4_b,
         trail_step_4_s,
-        trail_step_method_4: if trail_step_method_4.is_empty() { None } else { Some(trail_step_method_4) },
+        trail_step_method_4,
         trail_step_method_4_b,
         trail_step_method_4_s,
         trail_step_cycle_4: Some(trail_step_cycle_4),
@@ -6603,13 +5952,13 @@ fn build_logic_config(
         trail_step_balance_4: Some(trail_step_balance_4),
         trail_step_balance_4_b,
         trail_step_balance_4_s,
-        trail_step_mode_4: if trail_step_mode_4.is_empty() { None } else { Some(trail_step_mode_4) },
+        trail_step_mode_4,
         trail_step_mode_4_b,
         trail_step_mode_4_s,
         trail_step_5: Some(trail_step_5),
         trail_step_5_b,
         trail_step_5_s,
-        trail_step_method_5: if trail_step_method_5.is_empty() { None } else { Some(trail_step_method_5) },
+        trail_step_method_5,
         trail_step_method_5_b,
         trail_step_method_5_s,
         trail_step_cycle_5: Some(trail_step_cycle_5),
@@ -6618,13 +5967,13 @@ fn build_logic_config(
         trail_step_balance_5: Some(trail_step_balance_5),
         trail_step_balance_5_b,
         trail_step_balance_5_s,
-        trail_step_mode_5: if trail_step_mode_5.is_empty() { None } else { Some(trail_step_mode_5) },
+        trail_step_mode_5,
         trail_step_mode_5_b,
         trail_step_mode_5_s,
         trail_step_6: Some(trail_step_6),
         trail_step_6_b,
         trail_step_6_s,
-        trail_step_method_6: if trail_step_method_6.is_empty() { None } else { Some(trail_step_method_6) },
+        trail_step_method_6,
         trail_step_method_6_b,
         trail_step_method_6_s,
         trail_step_cycle_6: Some(trail_step_cycle_6),
@@ -6633,13 +5982,13 @@ fn build_logic_config(
         trail_step_balance_6: Some(trail_step_balance_6),
         trail_step_balance_6_b,
         trail_step_balance_6_s,
-        trail_step_mode_6: if trail_step_mode_6.is_empty() { None } else { Some(trail_step_mode_6) },
+        trail_step_mode_6,
         trail_step_mode_6_b,
         trail_step_mode_6_s,
         trail_step_7: Some(trail_step_7),
         trail_step_7_b,
         trail_step_7_s,
-        trail_step_method_7: if trail_step_method_7.is_empty() { None } else { Some(trail_step_method_7) },
+        trail_step_method_7,
         trail_step_method_7_b,
         trail_step_method_7_s,
         trail_step_cycle_7: Some(trail_step_cycle_7),
@@ -6648,7 +5997,7 @@ fn build_logic_config(
         trail_step_balance_7: Some(trail_step_balance_7),
         trail_step_balance_7_b,
         trail_step_balance_7_s,
-        trail_step_mode_7: if trail_step_mode_7.is_empty() { None } else { Some(trail_step_mode_7) },
+        trail_step_mode_7,
         trail_step_mode_7_b,
         trail_step_mode_7_s,
         close_partial,
@@ -6762,83 +6111,84 @@ fn create_default_group(group_num: u8) -> GroupConfig {
 /// Create a default logic configuration
 fn create_default_logic(logic_name: &str) -> LogicConfig {
     let is_power = logic_name.eq_ignore_ascii_case("power");
+    let d = logic_defaults(logic_name);
 
     LogicConfig {
         logic_name: logic_name.to_string(),
         logic_id: format!("A_{}1", get_logic_code(logic_name)),
         enabled: is_power,
-        initial_lot: 0.02,
+        initial_lot: d.initial_lot,
         initial_lot_b: None,
         initial_lot_s: None,
-        multiplier: 1.2,
+        multiplier: d.multiplier,
         multiplier_b: None,
         multiplier_s: None,
-        grid: 300.0,
+        grid: d.grid,
         grid_b: None,
         grid_s: None,
-        trail_method: "Points".to_string(),
-        trail_value: 3000.0,
+        trail_method: d.trail_method,
+        trail_value: d.trail_value,
         trail_value_b: None,
         trail_value_s: None,
-        trail_start: 1.0,
+        trail_start: d.trail_start,
         trail_start_b: None,
         trail_start_s: None,
-        trail_step: 1500.0,
+        trail_step: d.trail_step,
         trail_step_b: None,
         trail_step_s: None,
-        trail_step_method: "Step_Points".to_string(),
+        trail_step_method: d.trail_step_method,
         // Preserve source-of-truth semantics: missing StartLevel stays missing.
         start_level: None,
         start_level_b: None,
         start_level_s: None,
-        last_lot: if is_power { Some(0.63) } else { Some(0.12) },
+        last_lot: Some(d.last_lot),
         last_lot_b: None,
         last_lot_s: None,
-        close_targets: String::new(), // NO default - use empty string
+        close_targets: d.close_targets.to_string(), // NO default - empty string
         close_targets_b: None,
         close_targets_s: None,
-        order_count_reference: "Logic_None".to_string(),
+        order_count_reference: d.order_count_reference.to_string(),
         order_count_reference_b: None,
         order_count_reference_s: None,
         group_order_count_reference: None,
         group_order_count_reference_b: None,
         group_order_count_reference_s: None,
-        reset_lot_on_restart: false,
+        reset_lot_on_restart: d.reset_lot_on_restart,
         reset_lot_on_restart_b: None,
         reset_lot_on_restart_s: None,
-        strategy_type: "Trail".to_string(),
+        strategy_type: d.strategy_type,
         strategy_type_b: None,
         strategy_type_s: None,
-        trading_mode: "Counter Trend".to_string(),
+        trading_mode: Some(d.trading_mode),
         trading_mode_b: None,
         trading_mode_s: None,
-        allow_buy: true,
-        allow_sell: true,
-        reverse_enabled: false,
+        allow_buy: d.allow_buy,
+        allow_sell: d.allow_sell,
+        reverse_enabled: d.reverse_enabled,
         reverse_enabled_b: None,
         reverse_enabled_s: None,
-        hedge_enabled: false,
+        hedge_enabled: d.hedge_enabled,
         hedge_enabled_b: None,
         hedge_enabled_s: None,
-        reverse_scale: 100.0,
+        reverse_scale: d.reverse_scale,
         reverse_scale_b: None,
         reverse_scale_s: None,
-        hedge_scale: 50.0,
+        hedge_scale: d.hedge_scale,
         hedge_scale_b: None,
         hedge_scale_s: None,
-        reverse_reference: "Logic_None".to_string(),
+        reverse_reference: d.reverse_reference.to_string(),
         reverse_reference_b: None,
         reverse_reference_s: None,
-        hedge_reference: "Logic_None".to_string(),
+        hedge_reference: d.hedge_reference.to_string(),
         hedge_reference_b: None,
         hedge_reference_s: None,
-        trail_step_mode: "TrailStepMode_Auto".to_string(),
+        trail_step_mode: d.trail_step_mode,
         trail_step_mode_b: None,
         trail_step_mode_s: None,
-        trail_step_cycle: 1,
+        trail_step_cycle: d.trail_step_cycle,
         trail_step_cycle_b: None,
         trail_step_cycle_s: None,
-        trail_step_balance: 0.0,
+        trail_step_balance: d.trail_step_balance,
         trail_step_balance_b: None,
         trail_step_balance_s: None,
         trail_step_2: None,
@@ -6931,22 +6281,22 @@ fn create_default_logic(logic_name: &str) -> LogicConfig {
         trail_step_mode_7: None,
         trail_step_mode_7_b: None,
         trail_step_mode_7_s: None,
-        close_partial: false,
+        close_partial: d.close_partial,
         close_partial_b: None,
         close_partial_s: None,
-        close_partial_cycle: 1,
+        close_partial_cycle: d.close_partial_cycle,
         close_partial_cycle_b: None,
         close_partial_cycle_s: None,
-        close_partial_mode: "PartialMode_Mid".to_string(),
+        close_partial_mode: d.close_partial_mode,
         close_partial_mode_b: None,
         close_partial_mode_s: None,
-        close_partial_balance: "PartialBalance_Balanced".to_string(),
+        close_partial_balance: d.close_partial_balance,
         close_partial_balance_b: None,
         close_partial_balance_s: None,
-        close_partial_trail_step_mode: "TrailStepMode_Auto".to_string(),
+        close_partial_trail_step_mode: d.close_partial_trail_step_mode,
         close_partial_trail_step_mode_b: None,
         close_partial_trail_step_mode_s: None,
-        close_partial_profit_threshold: 0.0,
+        close_partial_profit_threshold: d.close_partial_profit_threshold,
         close_partial_profit_threshold_b: None,
         close_partial_profit_threshold_s: None,
         close_partial_2: None,
@@ -7015,29 +6365,6 @@ fn create_default_logic(logic_name: &str) -> LogicConfig {
     }
 }
 
-/// Get logic code from name
-fn get_logic_code(logic_name: &str) -> &'static str {
-    match logic_name {
-        "Power" => "P",
-        "Repower" => "R",
-        "Scalp" | "Scalper" => "S",
-        "Stopper" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    }
-}
-
-/// Decode trail step method from numeric/string value
-fn decode_trail_step_method(val: &str) -> String {
-    match val {
-        "0" => "Step_Points".to_string(),
-        "1" => "Step_Percent".to_string(),
-        _ => val.to_string(),
-    }
-}
-
 // ============================================
 // MQL RUST COMPILER INTEGRATION
 // ============================================
@@ -7046,7 +6373,7 @@ fn decode_trail_step_method(val: &str) -> String {
 #[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn initialize_mql_compiler(state: State<'_, MTBridgeState>) -> Result<(), String> {
-    state.initialize_compiler()
+    state.initialize_compiler().await
 }
 
 /// Run real-time MQL validation
@@ -7056,50 +6383,51 @@ pub async fn validate_mql_code(
     _force_refresh: bool,
     state: State<'_, MTBridgeState>,
 ) -> Result<ValidationReport, String> {
-    let mut compiler_guard = state.mql_compiler.lock().unwrap();
-
-    if let Some(ref mut compiler) = *compiler_guard {
-        compiler
-            .analyze_with_context()
-            .map_err(|e| format!("Validation failed: {}", e))
-    } else {
-        Err("MQL Compiler not initialized. Please set MT4/MT5 paths first.".to_string())
-    }
+    state
+        .with_compiler(|compiler| {
+            compiler
+                .analyze_with_context()
+                .map_err(|e| format!("Validation failed: {}", e))
+        })
+        .await
 }
 
-/// Run complete pre-compilation pipeline
+/// Run complete pre-compilation pipeline (cancel with cancel_task(taskId))
 #[cfg(feature = "tauri-app")]
-#[tauri::command]
+#[tauri::command(rename_all = "camelCase")]
 pub async fn run_precompilation_pipeline(
+    task_id: Option<String>,
     state: State<'_, MTBridgeState>,
 ) -> Result<PrecompilationResult, String> {
-    let mut compiler_guard = state.mql_compiler.lock().unwrap();
-
-    if let Some(ref mut compiler) = *compiler_guard {
-        compiler
-            .run_precompilation_pipeline()
-            .map_err(|e| format!("Pipeline failed: {}", e))
-    } else {
-        Err("MQL Compiler not initialized. Please set MT4/MT5 paths first.".to_string())
-    }
+    let task = crate::task_manager().begin(task_id, "run_precompilation_pipeline");
+    let task_for_run = task.clone();
+    let result = state
+        .with_compiler(move |compiler| {
+            compiler
+                .run_precompilation_pipeline_with_task(Some(&task_for_run))
+                .map_err(|e| format!("Pipeline failed: {}", e))
+        })
+        .await;
+    crate::task_manager().finish(&task.id);
+    result
 }
 
 /// Apply automatic fixes generated by the compiler
 #[cfg(feature = "tauri-app")]
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(files = fixes.len()), err)]
 pub async fn apply_mql_fixes(
     fixes: std::collections::HashMap<String, String>,
     state: State<'_, MTBridgeState>,
 ) -> Result<(), String> {
-    let compiler_guard = state.mql_compiler.lock().unwrap();
-
-    if let Some(ref compiler) = *compiler_guard {
-        compiler
-            .apply_fixes(&fixes)
-            .map_err(|e| format!("Failed to apply fixes: {}", e))
-    } else {
-        Err("MQL Compiler not initialized.".to_string())
-    }
+    crate::safe_mode::ensure_writable("Applying MQL fixes")?;
+    state
+        .with_compiler(move |compiler| {
+            compiler
+                .apply_fixes(&fixes)
+                .map_err(|e| format!("Failed to apply fixes: {}", e))
+        })
+        .await
 }
 
 /// Get MQL compiler status and statistics
@@ -7108,7 +6436,7 @@ pub async fn apply_mql_fixes(
 pub async fn get_mql_compiler_status(
     state: State<'_, MTBridgeState>,
 ) -> Result<MQLCompilerStatus, String> {
-    let compiler_guard = state.mql_compiler.lock().unwrap();
+    let compiler_guard = state.mql_compiler.lock().await;
 
     if let Some(ref compiler) = *compiler_guard {
         Ok(MQLCompilerStatus {
@@ -7144,6 +6472,37 @@ pub async fn get_mql_compiler_status(
     }
 }
 
+/// List lint rules with their effective enabled/severity state
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn list_lint_rules(state: State<'_, MTBridgeState>) -> Result<Vec<LintRuleInfo>, String> {
+    let compiler_guard = state.mql_compiler.lock().await;
+
+    match *compiler_guard {
+        Some(ref compiler) => Ok(mql_lint::list_rules(compiler.lint_config())),
+        None => Ok(mql_lint::list_rules(&LintConfig::default())),
+    }
+}
+
+/// Apply a lint config to the compiler, optionally persisting it as .daavfxlint.toml
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn set_lint_config(
+    config: LintConfig,
+    persist: bool,
+    state: State<'_, MTBridgeState>,
+) -> Result<Vec<LintRuleInfo>, String> {
+    state
+        .with_compiler(move |compiler| {
+            compiler.set_lint_config(config.clone())?;
+            if persist {
+                config.save(&compiler.lint_config_dir())?;
+            }
+            Ok(mql_lint::list_rules(compiler.lint_config()))
+        })
+        .await
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MQLCompilerStatus {
     pub initialized: bool,
@@ -7154,6 +6513,129 @@ pub struct MQLCompilerStatus {
     pub watching_files: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetKeyMismatch {
+    pub key: String,
+    /// Case-insensitive match on the other side, if any (the usual cause of a silent mismatch)
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetSourceValidationReport {
+    pub source_files: Vec<String>,
+    pub exported_key_count: usize,
+    pub ea_input_count: usize,
+    /// Keys export_set_file emits that the EA does not declare
+    pub unknown_keys: Vec<SetKeyMismatch>,
+    /// EA inputs the dashboard never writes (the EA will run with its compiled default)
+    pub unset_inputs: Vec<MQLInputDeclaration>,
+    pub is_consistent: bool,
+}
+
+/// Keys export_set_file would write for this config, in file order (optimization hint rows excluded)
+fn collect_exported_set_keys(config: &MTConfig, platform: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    render_set_file_lines(config, "DAAVFX_Validation.set", platform, false, None, None, None)
+        .iter()
+        .filter(|line| !line.starts_with(';'))
+        .filter_map(|line| line.split_once('=').map(|(key, _)| key.trim().to_string()))
+        .filter(|key| !key.is_empty() && !key.contains(','))
+        .filter(|key| seen.insert(key.clone()))
+        .collect()
+}
+
+fn find_case_insensitive_match<'a, I>(key: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let lowered = key.to_lowercase();
+    candidates
+        .into_iter()
+        .find(|c| c.as_str() != key && c.to_lowercase() == lowered)
+        .cloned()
+}
+
+/// Cross-check the keys export_set_file emits against the input/extern declarations of an EA.
+/// `source_path` may be a single .mq4/.mq5 file or an EA folder (its Include tree is scanned too).
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn validate_set_against_source(
+    config: MTConfig,
+    source_path: String,
+    platform: String,
+) -> Result<SetSourceValidationReport, String> {
+    let source = sanitize_and_validate_path(&PathBuf::from(&source_path))?;
+    if !source.exists() {
+        return Err(format!("EA source not found: {}", source_path));
+    }
+
+    let compiler = if source.is_file() {
+        let root = source
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        let mut compiler = MQLRustCompiler::new(&root)
+            .map_err(|e| format!("Failed to read EA source: {}", e))?;
+        compiler.project.main_files = vec![source.clone()];
+        compiler
+    } else {
+        MQLRustCompiler::new(&source.to_string_lossy())
+            .map_err(|e| format!("Failed to read EA source: {}", e))?
+    };
+
+    if compiler.project.main_files.is_empty() {
+        return Err(format!("No .mq4/.mq5 files found in {}", source_path));
+    }
+
+    let inputs = compiler
+        .collect_input_declarations()
+        .map_err(|e| format!("Failed to parse EA inputs: {}", e))?;
+    let exported_keys = collect_exported_set_keys(&config, &platform);
+
+    let input_names: std::collections::HashSet<String> =
+        inputs.iter().map(|i| i.name.clone()).collect();
+    let exported_set: std::collections::HashSet<&String> = exported_keys.iter().collect();
+
+    let unknown_keys: Vec<SetKeyMismatch> = exported_keys
+        .iter()
+        .filter(|key| !input_names.contains(*key))
+        .map(|key| SetKeyMismatch {
+            key: key.clone(),
+            suggestion: find_case_insensitive_match(key, &input_names),
+        })
+        .collect();
+
+    // The same input can be declared under #ifdef in both MT4/MT5 branches; report it once
+    let mut reported = std::collections::HashSet::new();
+    let unset_inputs: Vec<MQLInputDeclaration> = inputs
+        .iter()
+        .filter(|input| !exported_set.contains(&input.name))
+        .filter(|input| reported.insert(input.name.clone()))
+        .cloned()
+        .collect();
+
+    tracing::info!(
+        exported_keys = exported_keys.len(),
+        ea_inputs = inputs.len(),
+        unknown = unknown_keys.len(),
+        unset = unset_inputs.len(),
+        "Source validation"
+    );
+
+    Ok(SetSourceValidationReport {
+        source_files: compiler
+            .project
+            .main_files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect(),
+        exported_key_count: exported_keys.len(),
+        ea_input_count: input_names.len(),
+        is_consistent: unknown_keys.is_empty() && unset_inputs.is_empty(),
+        unknown_keys,
+        unset_inputs,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7183,6 +6665,9 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_ok(), "Export should succeed: {:?}", result);
 
@@ -7207,6 +6692,96 @@ mod tests {
         std::fs::remove_file(&file_path).ok();
     }
 
+    #[test]
+    fn test_mirror_backs_up_the_active_set_before_overwriting_it() {
+        let common_dir =
+            std::env::temp_dir().join(format!("daavfx_mirror_backup_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&common_dir);
+        std::fs::create_dir_all(&common_dir).unwrap();
+        let active_path = common_dir.join(naming::current().active_set_name);
+        let source = std::env::temp_dir().join("daavfx_mirror_backup_source.set");
+
+        mirror_setfile_into(&common_dir, &source, "gInput_Grid=300", None);
+        assert!(!active_backup::backup_path(&active_path, 1).exists());
+        mirror_setfile_into(&common_dir, &source, "gInput_Grid=400", None);
+
+        assert_eq!(std::fs::read_to_string(&active_path).unwrap(), "gInput_Grid=400");
+        assert_eq!(
+            std::fs::read_to_string(active_backup::backup_path(&active_path, 1)).unwrap(),
+            "gInput_Grid=300"
+        );
+        // Mirroring the active set onto itself doesn't back up the file it just wrote
+        mirror_setfile_into(&common_dir, &active_path, "gInput_Grid=400", None);
+        assert!(!active_backup::backup_path(&active_path, 2).exists());
+
+        let _ = std::fs::remove_dir_all(&common_dir);
+    }
+
+    #[test]
+    fn test_validate_set_against_source_reports_both_directions() {
+        let temp_dir = std::env::temp_dir().join("daavfx_source_validation_test");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let ea_file = temp_dir.join("TestEA.mq4");
+        std::fs::write(
+            &ea_file,
+            "input int gInput_MagicNumber = 777;\n\
+             extern int gInput_magicNumberBuy = 1;\n\
+             input double gInput_NeverExported = 1.5; // not written by the dashboard\n\
+             int gNotAnInput = 0;\n",
+        )
+        .unwrap();
+
+        let report = validate_set_against_source(
+            MTConfig::default(),
+            ea_file.to_string_lossy().to_string(),
+            "MT4".to_string(),
+        )
+        .expect("Validation should succeed");
+
+        assert_eq!(report.ea_input_count, 3);
+        assert!(!report.is_consistent);
+        assert!(!report
+            .unknown_keys
+            .iter()
+            .any(|k| k.key == "gInput_MagicNumber"));
+        let buy = report
+            .unknown_keys
+            .iter()
+            .find(|k| k.key == "gInput_MagicNumberBuy")
+            .expect("Case-mismatched key should be reported");
+        assert_eq!(buy.suggestion.as_deref(), Some("gInput_magicNumberBuy"));
+        assert!(report
+            .unset_inputs
+            .iter()
+            .any(|i| i.name == "gInput_NeverExported"));
+        assert!(!report.unset_inputs.iter().any(|i| i.name == "gNotAnInput"));
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_import_json_file_served_from_parse_cache() {
+        let temp_file = std::env::temp_dir().join(format!(
+            "daavfx_parse_cache_{}.json",
+            std::process::id()
+        ));
+        let file_path = temp_file.to_string_lossy().to_string();
+        let mut config = MTConfig::default();
+        config.general.magic_number = 4242;
+        export_json_file(config, file_path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+
+        let first = import_json_file(file_path.clone()).await.unwrap();
+        let hits_before = get_parse_cache_stats().unwrap().hits;
+        let second = import_json_file(file_path.clone()).await.unwrap();
+        assert!(get_parse_cache_stats().unwrap().hits > hits_before);
+        assert_eq!(first.general.magic_number, second.general.magic_number);
+
+        assert_eq!(clear_parse_cache(Some(file_path.clone())).unwrap(), 1);
+        std::fs::remove_file(&temp_file).ok();
+    }
+
     #[test]
     fn test_build_config_from_values_includes_new_magic_number_fields() {
         use std::collections::HashMap;
@@ -7359,7 +6934,7 @@ pub struct MT4Settings {
     pub is_valid: bool,
 }
 
-fn find_mt4_common_files_path(terminal_path: &PathBuf) -> Option<PathBuf> {
+pub(crate) fn find_mt4_common_files_path(terminal_path: &Path) -> Option<PathBuf> {
     // Look for "Files" subdirectory in terminal folder
     let files_path = terminal_path.join("Files");
     if files_path.exists() && files_path.is_dir() {
@@ -7380,7 +6955,7 @@ fn find_mt4_common_files_path(terminal_path: &PathBuf) -> Option<PathBuf> {
     None
 }
 
-fn extract_broker_name(terminal_path: &PathBuf) -> String {
+pub(crate) fn extract_broker_name(terminal_path: &Path) -> String {
     // Broker name is usually in the terminal folder name
     if let Some(name) = terminal_path.file_name() {
         let name_str = name.to_string_lossy().to_string();
@@ -7394,101 +6969,44 @@ fn extract_broker_name(terminal_path: &PathBuf) -> String {
                 .replace("terminal64.exe - ", "")
                 .replace("terminal64 ", "");
         }
-        // Return folder name if it looks like a broker name
-        if !name_str.chars().next().unwrap_or_default().is_ascii_digit() {
-            return name_str;
-        }
-    }
-    "Unknown Broker".to_string()
-}
-
-#[cfg_attr(feature = "tauri-app", tauri::command)]
-pub async fn get_mt4_settings() -> Result<MT4Settings, String> {
-    let terminal_root = get_terminal_root_path()?;
-
-    if !terminal_root.exists() {
-        return Ok(MT4Settings {
-            terminal_path: "".to_string(),
-            common_files_path: "".to_string(),
-            profiles_path: "".to_string(),
-            broker_name: "MT4 Not Found".to_string(),
-            is_valid: false,
-        });
-    }
-
-    // Find the most recently used MT4 installation
-    let mut latest_terminal: Option<(std::time::SystemTime, PathBuf)> = None;
-
-    for entry in fs::read_dir(&terminal_root)
-        .map_err(|e| format!("Failed to read terminal folder: {}", e))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
-
-        if !path.is_dir() {
-            continue;
-        }
-
-        // Skip common non-broker folders
-        if let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
-            if name == "Common" || name == "MQL4" || name == "MQL5" || name.starts_with("tperm") {
-                continue;
-            }
-        }
-
-        // Check if this looks like an MT4 terminal folder (has Files, configs, etc.)
-        let has_files = path.join("Files").exists();
-        let has_mql4 = path.join("MQL4").exists();
-
-        if has_files || has_mql4 {
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    match &latest_terminal {
-                        None => {
-                            latest_terminal = Some((modified, path));
-                        }
-                        Some((best_time, _)) => {
-                            if modified > *best_time {
-                                latest_terminal = Some((modified, path));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        // Return folder name if it looks like a broker name
+        if !name_str.chars().next().unwrap_or_default().is_ascii_digit() {
+            return name_str;
+        }
     }
+    "Unknown Broker".to_string()
+}
 
-    match latest_terminal {
-        Some((_, terminal_path)) => {
-            let broker_name = extract_broker_name(&terminal_path);
-            let common_files = find_mt4_common_files_path(&terminal_path);
-
-            let common_files_path = match &common_files {
-                Some(p) => p.to_string_lossy().to_string(),
-                None => {
-                    // Fallback: try to construct from APPDATA
-                    let appdata = std::env::var("APPDATA").unwrap_or_default();
-                    format!(r"{}\MetaQuotes\Terminal\Common Files", appdata)
-                }
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn get_mt4_settings() -> Result<MT4Settings, String> {
+    // Most recently used terminal that isn't MT5, native or under Wine
+    let latest = terminal_discovery::discover_terminals()
+        .into_iter()
+        .find(|profile| profile.platform != "MT5");
+
+    match latest {
+        Some(profile) => Ok(MT4Settings {
+            terminal_path: profile.data_path,
+            common_files_path: profile.common_files_path,
+            profiles_path: profile.profiles_path,
+            broker_name: profile.broker_name,
+            is_valid: true,
+        }),
+        None => {
+            let terminal_root = get_terminal_root_path()?;
+            let terminal_path = if terminal_root.exists() {
+                terminal_root.to_string_lossy().to_string()
+            } else {
+                "".to_string()
             };
-
-            let profiles_path = terminal_path.join("profiles").to_string_lossy().to_string();
-
             Ok(MT4Settings {
-                terminal_path: terminal_path.to_string_lossy().to_string(),
-                common_files_path,
-                profiles_path,
-                broker_name,
-                is_valid: true,
+                terminal_path,
+                common_files_path: "".to_string(),
+                profiles_path: "".to_string(),
+                broker_name: "MT4 Not Found".to_string(),
+                is_valid: false,
             })
         }
-        None => Ok(MT4Settings {
-            terminal_path: terminal_root.to_string_lossy().to_string(),
-            common_files_path: "".to_string(),
-            profiles_path: "".to_string(),
-            broker_name: "MT4 Not Found".to_string(),
-            is_valid: false,
-        }),
     }
 }
 
@@ -7514,10 +7032,9 @@ pub async fn configure_mt4_path(path: String) -> Result<MT4Settings, String> {
 
     let common_files_path = match &common_files {
         Some(p) => p.to_string_lossy().to_string(),
-        None => {
-            let appdata = std::env::var("APPDATA").unwrap_or_default();
-            format!(r"{}\MetaQuotes\Terminal\Common Files", appdata)
-        }
+        None => get_mt_common_files_dir()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default(),
     };
 
     let profiles_path = path_buf.join("profiles").to_string_lossy().to_string();
@@ -7577,17 +7094,7 @@ pub async fn open_mt_folder(folder_type: String) -> Result<(), String> {
     }
     let path = sanitize_and_validate_path(&path)?;
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-        return Ok(());
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    Err("Open folder not supported on this OS".to_string())
+    os_paths::open_folder(&path)
 }
 
 // ============================================
@@ -7632,8 +7139,29 @@ pub struct MassiveSetfileParseResult {
 
 /// Parse massive v19 format setfile
 #[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
-pub async fn parse_massive_setfile(file_path: String) -> Result<MassiveSetfileParseResult, String> {
-    println!("[MASSIVE_SETFILE] Parsing: {}", file_path);
+pub async fn parse_massive_setfile(
+    file_path: String,
+    task_id: Option<String>,
+) -> Result<MassiveSetfileParseResult, String> {
+    let task = crate::task_manager().begin(task_id, "parse_massive_setfile");
+    let span = import_log::import_span("massive_set", &file_path);
+    let task_for_parse = task.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        span.in_scope(|| parse_massive_setfile_with_task(file_path, &task_for_parse))
+    })
+    .await
+    .map_err(|e| format!("Setfile parse task failed: {}", e))
+    .and_then(|r| r);
+    crate::task_manager().finish(&task.id);
+    result
+}
+
+fn parse_massive_setfile_with_task(
+    file_path: String,
+    task: &crate::TaskHandle,
+) -> Result<MassiveSetfileParseResult, String> {
+    tracing::info!("Parsing: {}", file_path);
+    task.report("reading", 0, 0);
 
     let path_buf = PathBuf::from(&file_path);
     let sanitized_path = sanitize_and_validate_path(&path_buf)?;
@@ -7666,65 +7194,63 @@ pub async fn parse_massive_setfile(file_path: String) -> Result<MassiveSetfilePa
     };
 
     // Parse directional gInput keys using the v19 parser.
-    let mut parsed_keys: Vec<ParsedV19Key> = Vec::new();
-    for (_line_num, line) in content.lines().enumerate() {
+    let total_lines = content.lines().count();
+    let mut found_groups: std::collections::BTreeSet<u8> = std::collections::BTreeSet::new();
+    let mut found_engines: std::collections::BTreeSet<char> = std::collections::BTreeSet::new();
+    let mut found_logics: std::collections::BTreeSet<char> = std::collections::BTreeSet::new();
+    let mut found_logic_directions: std::collections::HashSet<(usize, char, char, &'static str)> =
+        std::collections::HashSet::new();
+    for (line_num, line) in content.lines().enumerate() {
+        if line_num % 2000 == 0 {
+            task.check()?;
+            task.report("parsing_keys", line_num, total_lines);
+        }
         let line = line.trim();
         if line.is_empty() || line.starts_with(';') {
             continue;
         }
 
         if let Some(pos) = line.find('=') {
-            let key = line[..pos].trim().to_string();
-            let _value_str = line[pos + 1..].trim().to_string();
+            let key = line[..pos].trim();
 
             // Only process gInput keys
             if !key.starts_with("gInput_") {
                 continue;
             }
 
-            if let Some(parsed) = parse_v19_key(&key) {
-                parsed_keys.push(parsed);
+            // Record only the distinct components; the full key/value pairs are
+            // materialised once by build_config_from_v19_setfile below.
+            if let Some((group, engine, logic, direction, _)) = split_v19_key(key) {
+                found_groups.insert(group as u8);
+                found_engines.insert(engine);
+                found_logics.insert(logic);
+                found_logic_directions.insert((group, engine, logic, direction));
                 result.total_inputs_parsed += 1;
             }
         }
     }
 
-    // Track found items
-    let mut found_groups: Vec<u8> = Vec::new();
-    let mut found_engines: Vec<String> = Vec::new();
-    let mut found_logics: Vec<String> = Vec::new();
-    let mut found_logic_directions: std::collections::HashSet<(u8, String, String, String)> = std::collections::HashSet::new();
-
-    for parsed in &parsed_keys {
-        found_groups.push(parsed.group as u8);
-        found_engines.push(parsed.engine.to_string());
-        let logic_name = logic_code_to_name(parsed.logic)
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| parsed.logic.to_string());
-        found_logics.push(logic_name.clone());
-        found_logic_directions.insert((
-            parsed.group as u8,
-            parsed.engine.to_string(),
-            logic_name,
-            parsed.direction.clone(),
-        ));
-    }
-
     // Build config from the authoritative v19 parser.
     // This preserves independent Buy/Sell rows and avoids collapsing values.
+    task.check()?;
+    task.report("building_config", total_lines, total_lines);
     let mut config = build_config_from_v19_setfile(&content)?;
+    task.check()?;
+
+    let mut logic_names: Vec<String> = found_logics
+        .iter()
+        .map(|code| {
+            logic_code_to_name(*code)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| code.to_string())
+        })
+        .collect();
+    logic_names.sort();
+    logic_names.dedup();
 
-    // Deduplicate and sort
-    found_groups.sort();
-    found_groups.dedup();
-    found_engines.sort();
-    found_engines.dedup();
-    found_logics.sort();
-    found_logics.dedup();
-
-    result.groups_found = found_groups;
-    result.engines_found = found_engines;
-    result.logics_found = found_logics;
+    result.groups_found = found_groups.into_iter().collect();
+    result.engines_found = found_engines.iter().map(|e| e.to_string()).collect();
+    result.logics_found = logic_names;
     result.logic_directions_found = found_logic_directions.len();
 
     // Validate expected count
@@ -7753,7 +7279,7 @@ pub async fn parse_massive_setfile(file_path: String) -> Result<MassiveSetfilePa
     result.config = Some(config);
     result.success = result.errors.is_empty();
 
-    println!("[MASSIVE_SETFILE] Parsed {} inputs, {} logic-directions across {} groups",
+    tracing::info!("Parsed {} inputs, {} logic-directions across {} groups",
         result.total_inputs_parsed, result.logic_directions_found, result.groups_found.len());
 
     Ok(result)
@@ -7837,16 +7363,19 @@ fn create_default_mt_config() -> MTConfig {
         current_set_name: None,
         tags: None,
         comments: None,
+        annotations: None,
         general: GeneralConfig {
             license_key: "".to_string(),
             license_server_url: "https://license.daavfx.com".to_string(),
             require_license: true,
             license_check_interval: 3600,
-            config_file_name: "DAAVFX_Config.json".to_string(),
+            config_file_name: naming::current().config_name,
             config_file_is_common: true,
             allow_buy: true,
             allow_sell: true,
             enable_logs: true,
+            use_direct_price_grid: false,
+            group_mode: None,
             grid_unit: Some(10),
             pip_factor: Some(1),
             compounding_enabled: false,
@@ -7896,6 +7425,7 @@ fn create_default_mt_config() -> MTConfig {
                 impact_level: 3,
                 minutes_before: 30,
                 minutes_after: 30,
+                action: String::new(),
                 stop_ea: true,
                 close_trades: false,
                 auto_restart: true,
@@ -7911,275 +7441,21 @@ fn create_default_mt_config() -> MTConfig {
                 include_reports: true,
                 visual_indicator: true,
                 alert_before_news: false,
-                calendar_file: Some("DAAVFX_NEWS.csv".to_string()),
+                calendar_file: Some(naming::current().prefixed("NEWS.csv")),
             },
         },
         engines: Vec::new(),
     }
 }
 
-/// Create default LogicConfig for a logic type
+/// Create default LogicConfig for a logic type, seeded before v19 values are applied
 fn create_default_logic_config(logic_name: &str) -> LogicConfig {
     LogicConfig {
-        logic_name: logic_name.to_string(),
-        logic_id: logic_name.to_uppercase(),
-        enabled: true,
-        initial_lot: 0.01,
-        initial_lot_b: None,
-        initial_lot_s: None,
-        multiplier: 1.5,
-        multiplier_b: None,
-        multiplier_s: None,
-        grid: 100.0,
-        grid_b: None,
-        grid_s: None,
-        trail_method: "Points".to_string(),
-        trail_value: 50.0,
-        trail_value_b: None,
-        trail_value_s: None,
-        trail_start: 0.0,
-        trail_start_b: None,
-        trail_start_s: None,
-        trail_step: 50.0,
-        trail_step_b: None,
-        trail_step_s: None,
-        trail_step_method: "Step_Points".to_string(),
-        // Preserve source values as-is. Missing StartLevel stays missing.
-        start_level: None,
-        start_level_b: None,
-        start_level_s: None,
-        last_lot: None,
-        last_lot_b: None,
-        last_lot_s: None,
-        close_targets: "CloseTargets_ProfitOnly".to_string(),
-        close_targets_b: None,
-        close_targets_s: None,
-        order_count_reference: "OrderCount_Direct".to_string(),
-        order_count_reference_b: None,
-        order_count_reference_s: None,
-        group_order_count_reference: None,
-        group_order_count_reference_b: None,
-        group_order_count_reference_s: None,
-        reset_lot_on_restart: false,
-        reset_lot_on_restart_b: None,
-        reset_lot_on_restart_s: None,
-        strategy_type: "Trail".to_string(),
-        strategy_type_b: None,
-        strategy_type_s: None,
-        trading_mode: "Counter Trend".to_string(),
-        trading_mode_b: None,
-        trading_mode_s: None,
-        allow_buy: true,
-        allow_sell: true,
-        reverse_enabled: false,
-        reverse_enabled_b: None,
-        reverse_enabled_s: None,
-        hedge_enabled: false,
-        hedge_enabled_b: None,
-        hedge_enabled_s: None,
-        reverse_scale: 100.0,
-        reverse_scale_b: None,
-        reverse_scale_s: None,
-        hedge_scale: 50.0,
-        hedge_scale_b: None,
-        hedge_scale_s: None,
-        reverse_reference: "Logic_None".to_string(),
-        reverse_reference_b: None,
-        reverse_reference_s: None,
-        hedge_reference: "Logic_None".to_string(),
-        hedge_reference_b: None,
-        hedge_reference_s: None,
-        trail_step_mode: "TrailStepMode_Auto".to_string(),
-        trail_step_mode_b: None,
-        trail_step_mode_s: None,
-        trail_step_cycle: 1,
-        trail_step_cycle_b: None,
-        trail_step_cycle_s: None,
-        trail_step_balance: 0.0,
-        trail_step_balance_b: None,
-        trail_step_balance_s: None,
-        // Trail Step 2
-        trail_step_2: None,
-        trail_step_2_b: None,
-        trail_step_2_s: None,
-        trail_step_method_2: None,
-        trail_step_method_2_b: None,
-        trail_step_method_2_s: None,
-        trail_step_cycle_2: None,
-        trail_step_cycle_2_b: None,
-        trail_step_cycle_2_s: None,
-        trail_step_balance_2: None,
-        trail_step_balance_2_b: None,
-        trail_step_balance_2_s: None,
-        trail_step_mode_2: None,
-        trail_step_mode_2_b: None,
-        trail_step_mode_2_s: None,
-        // Trail Step 3
-        trail_step_3: None,
-        trail_step_3_b: None,
-        trail_step_3_s: None,
-        trail_step_method_3: None,
-        trail_step_method_3_b: None,
-        trail_step_method_3_s: None,
-        trail_step_cycle_3: None,
-        trail_step_cycle_3_b: None,
-        trail_step_cycle_3_s: None,
-        trail_step_balance_3: None,
-        trail_step_balance_3_b: None,
-        trail_step_balance_3_s: None,
-        trail_step_mode_3: None,
-        trail_step_mode_3_b: None,
-        trail_step_mode_3_s: None,
-        // Trail Step 4
-        trail_step_4: None,
-        trail_step_4_b: None,
-        trail_step_4_s: None,
-        trail_step_method_4: None,
-        trail_step_method_4_b: None,
-        trail_step_method_4_s: None,
-        trail_step_cycle_4: None,
-        trail_step_cycle_4_b: None,
-        trail_step_cycle_4_s: None,
-        trail_step_balance_4: None,
-        trail_step_balance_4_b: None,
-        trail_step_balance_4_s: None,
-        trail_step_mode_4: None,
-        trail_step_mode_4_b: None,
-        trail_step_mode_4_s: None,
-        // Trail Step 5
-        trail_step_5: None,
-        trail_step_5_b: None,
-        trail_step_5_s: None,
-        trail_step_method_5: None,
-        trail_step_method_5_b: None,
-        trail_step_method_5_s: None,
-        trail_step_cycle_5: None,
-        trail_step_cycle_5_b: None,
-        trail_step_cycle_5_s: None,
-        trail_step_balance_5: None,
-        trail_step_balance_5_b: None,
-        trail_step_balance_5_s: None,
-        trail_step_mode_5: None,
-        trail_step_mode_5_b: None,
-        trail_step_mode_5_s: None,
-        // Trail Step 6
-        trail_step_6: None,
-        trail_step_6_b: None,
-        trail_step_6_s: None,
-        trail_step_method_6: None,
-        trail_step_method_6_b: None,
-        trail_step_method_6_s: None,
-        trail_step_cycle_6: None,
-        trail_step_cycle_6_b: None,
-        trail_step_cycle_6_s: None,
-        trail_step_balance_6: None,
-        trail_step_balance_6_b: None,
-        trail_step_balance_6_s: None,
-        trail_step_mode_6: None,
-        trail_step_mode_6_b: None,
-        trail_step_mode_6_s: None,
-        // Trail Step 7
-        trail_step_7: None,
-        trail_step_7_b: None,
-        trail_step_7_s: None,
-        trail_step_method_7: None,
-        trail_step_method_7_b: None,
-        trail_step_method_7_s: None,
-        trail_step_cycle_7: None,
-        trail_step_cycle_7_b: None,
-        trail_step_cycle_7_s: None,
-        trail_step_balance_7: None,
-        trail_step_balance_7_b: None,
-        trail_step_balance_7_s: None,
-        trail_step_mode_7: None,
-        trail_step_mode_7_b: None,
-        trail_step_mode_7_s: None,
-        // Close Partial 1
-        close_partial: false,
-        close_partial_b: None,
-        close_partial_s: None,
-        close_partial_cycle: 1,
-        close_partial_cycle_b: None,
-        close_partial_cycle_s: None,
-        close_partial_mode: "PartialMode_Mid".to_string(),
-        close_partial_mode_b: None,
-        close_partial_mode_s: None,
-        close_partial_balance: "PartialBalance_Balanced".to_string(),
-        close_partial_balance_b: None,
-        close_partial_balance_s: None,
-        close_partial_trail_step_mode: "TrailStepMode_Auto".to_string(),
-        close_partial_trail_step_mode_b: None,
-        close_partial_trail_step_mode_s: None,
-        close_partial_profit_threshold: 0.0,
-        close_partial_profit_threshold_b: None,
-        close_partial_profit_threshold_s: None,
-        // Close Partial 2
-        close_partial_2: None,
-        close_partial_2_b: None,
-        close_partial_2_s: None,
-        close_partial_cycle_2: None,
-        close_partial_cycle_2_b: None,
-        close_partial_cycle_2_s: None,
-        close_partial_mode_2: None,
-        close_partial_mode_2_b: None,
-        close_partial_mode_2_s: None,
-        close_partial_balance_2: None,
-        close_partial_balance_2_b: None,
-        close_partial_balance_2_s: None,
-        close_partial_profit_threshold_2: None,
-        close_partial_profit_threshold_2_b: None,
-        close_partial_profit_threshold_2_s: None,
-        // Close Partial 3
-        close_partial_3: None,
-        close_partial_3_b: None,
-        close_partial_3_s: None,
-        close_partial_cycle_3: None,
-        close_partial_cycle_3_b: None,
-        close_partial_cycle_3_s: None,
-        close_partial_mode_3: None,
-        close_partial_mode_3_b: None,
-        close_partial_mode_3_s: None,
-        close_partial_balance_3: None,
-        close_partial_balance_3_b: None,
-        close_partial_balance_3_s: None,
-        close_partial_profit_threshold_3: None,
-        close_partial_profit_threshold_3_b: None,
-        close_partial_profit_threshold_3_s: None,
-        // Close Partial 4
-        close_partial_4: None,
-        close_partial_4_b: None,
-        close_partial_4_s: None,
-        close_partial_cycle_4: None,
-        close_partial_cycle_4_b: None,
-        close_partial_cycle_4_s: None,
-        close_partial_mode_4: None,
-        close_partial_mode_4_b: None,
-        close_partial_mode_4_s: None,
-        close_partial_balance_4: None,
-        close_partial_balance_4_b: None,
-        close_partial_balance_4_s: None,
-        close_partial_profit_threshold_4: None,
-        close_partial_profit_threshold_4_b: None,
-        close_partial_profit_threshold_4_s: None,
-        // Triggers
-        trigger_type: None,
-        trigger_type_b: None,
-        trigger_type_s: None,
-        trigger_mode: None,
-        trigger_mode_b: None,
-        trigger_mode_s: None,
-        trigger_bars: None,
-        trigger_bars_b: None,
-        trigger_bars_s: None,
-        trigger_minutes: None,
-        trigger_minutes_b: None,
-        trigger_minutes_s: None,
-        trigger_seconds: None,
-        trigger_seconds_b: None,
-        trigger_seconds_s: None,
-        trigger_pips: None,
-        trigger_pips_b: None,
-        trigger_pips_s: None,
+        logic_id: logic_name.to_uppercase(),
+        enabled: true,
+        // Missing LastLot stays missing, like StartLevel.
+        last_lot: None,
+        ..create_default_logic(logic_name)
     }
 }
 
@@ -8214,8 +7490,8 @@ fn apply_param_to_config(
                     "Grid_B" => logic.grid_b = Some(value),
                     "Grid_S" => logic.grid_s = Some(value),
                     "Trail" => logic.trail_method = match value as i32 {
-                        1 => "AVG_Percent".to_string(),
-                        _ => "Points".to_string(),
+                        1 => TrailMethod::AvgPercent,
+                        _ => TrailMethod::Points,
                     },
                     "TrailValue" => logic.trail_value = value,
                     "TrailValue_B" => logic.trail_value_b = Some(value),
@@ -8232,22 +7508,17 @@ fn apply_param_to_config(
                     "ClosePartial" => logic.close_partial = value > 0.5,
                     "ClosePartialCycle" => logic.close_partial_cycle = value as i32,
                     "ClosePartialMode" => {
-                        logic.close_partial_mode = match value as i32 {
-                            0 => "PartialMode_Low".to_string(),
-                            1 => "PartialMode_Mid".to_string(),
-                            2 => "PartialMode_Aggressive".to_string(),
-                            _ => "PartialMode_Mid".to_string(),
+                        if let Some(mode) = decode_setfile_enum(&(value as i32).to_string()) {
+                            logic.close_partial_mode = mode;
                         }
                     }
                     "LastLot" => logic.last_lot = Some(value),
                     "StartLevel" => logic.start_level = Some(value as i32),
                     "TriggerType" => {
-                        logic.trigger_type =
-                            Some(decode_trigger_type_numeric(&format!("{}", value as i32)))
+                        logic.trigger_type = decode_setfile_enum(&(value as i32).to_string())
                     }
                     "TriggerMode" => {
-                        logic.trigger_mode =
-                            Some(decode_trigger_mode_numeric(&format!("{}", value as i32)))
+                        logic.trigger_mode = decode_setfile_enum(&(value as i32).to_string())
                     }
                     "TriggerBars" => logic.trigger_bars = Some(value as i32),
                     "TriggerMinutes" | "TriggerSeconds" => logic.trigger_seconds = Some(value as i32),
@@ -8311,13 +7582,62 @@ const V19_TOTAL_LOGIC_INPUTS: usize = V19_GROUP1_LOGIC_DIRECTIONS * V19_FIELDS_P
 const V19_MIN_TOTAL_INPUTS: usize = V19_GROUP1_LOGIC_DIRECTIONS * V19_FIELDS_PER_LOGIC_GROUP1_LEGACY
     + V19_NON_GROUP1_LOGIC_DIRECTIONS * V19_FIELDS_PER_LOGIC_OTHER_GROUPS_LEGACY; // 53,046
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ParsedV19Key {
     pub group: usize,
     pub engine: char,
     pub logic: char,
-    pub direction: String,
-    pub param: String,
+    /// Always "Buy" or "Sell".
+    pub direction: &'static str,
+    /// Shared with every other key carrying the same parameter name when parsed
+    /// through a `V19KeyInterner`.
+    pub param: Arc<str>,
+}
+
+/// Deduplicates v19 parameter names and values during a bulk parse.
+///
+/// A massive setfile repeats the same ~90 parameter names across 630
+/// logic-directions, and most values are one of a handful of literals ("0",
+/// "1", "0.01"), so interning keeps one allocation per distinct string
+/// instead of two per key.
+#[derive(Debug, Default)]
+pub struct V19KeyInterner {
+    params: std::collections::HashSet<Arc<str>>,
+}
+
+impl V19KeyInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.params.get(value) {
+            return Arc::clone(existing);
+        }
+        let interned: Arc<str> = Arc::from(value);
+        self.params.insert(Arc::clone(&interned));
+        interned
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Parse a v19 key, sharing the parameter name with previously seen keys.
+    pub fn parse_key(&mut self, key: &str) -> Option<ParsedV19Key> {
+        let (group, engine, logic, direction, param) = split_v19_key(key)?;
+        Some(ParsedV19Key {
+            group,
+            engine,
+            logic,
+            direction,
+            param: self.intern(param),
+        })
+    }
 }
 
 /// Parse v19 format key: gInput_{Group}_{Engine}{Logic}_{Direction}_{Param}
@@ -8327,35 +7647,39 @@ pub struct ParsedV19Key {
 ///   gInput_2_BP_Buy_TrailValue=500
 ///   gInput_15_CX_Sell_InitialLot=0.02
 pub fn parse_v19_key(key: &str) -> Option<ParsedV19Key> {
+    let (group, engine, logic, direction, param) = split_v19_key(key)?;
+    Some(ParsedV19Key {
+        group,
+        engine,
+        logic,
+        direction,
+        param: Arc::from(param),
+    })
+}
+
+/// Split a v19 key into its components without allocating. The parameter is
+/// returned as a slice of `key`.
+fn split_v19_key(key: &str) -> Option<(usize, char, char, &'static str, &str)> {
     // Remove gInput_ prefix
-    let remainder = if key.starts_with("ginput_") {
-        &key[7..]
-    } else if key.starts_with("gInput_") {
+    let remainder = if key.starts_with("ginput_") || key.starts_with("gInput_") {
         &key[7..]
     } else {
         return None;
     };
 
-    let parts: Vec<&str> = remainder.split('_').collect();
-
-    if parts.len() < 4 {
-        return None;
-    }
+    let mut parts = remainder.splitn(4, '_');
 
     // Parse group (first part)
-    let group: usize = parts[0].parse().ok()?;
+    let group: usize = parts.next()?.parse().ok()?;
     if group < 1 || group > V19_MAX_GROUPS {
         return None;
     }
 
     // Parse engine+logic code (second part): "AP", "AR", "BP", "CS", etc.
-    let engine_logic = parts[1];
-    if engine_logic.len() < 2 {
-        return None;
-    }
-
-    let engine_char = engine_logic.chars().next().unwrap();
-    let logic_char = engine_logic.chars().nth(1).unwrap();
+    let engine_logic = parts.next()?;
+    let mut codes = engine_logic.chars();
+    let engine_char = codes.next()?;
+    let logic_char = codes.next()?;
 
     // Validate engine
     if engine_char != 'A' && engine_char != 'B' && engine_char != 'C' {
@@ -8369,21 +7693,16 @@ pub fn parse_v19_key(key: &str) -> Option<ParsedV19Key> {
     }
 
     // Parse direction (third part)
-    let direction = parts[2].to_string();
-    if direction != "Buy" && direction != "Sell" {
-        return None;
-    }
+    let direction = match parts.next()? {
+        "Buy" => "Buy",
+        "Sell" => "Sell",
+        _ => return None,
+    };
 
     // Parse parameter (remaining parts)
-    let param = parts[3..].join("_");
+    let param = parts.next()?;
 
-    Some(ParsedV19Key {
-        group,
-        engine: engine_char,
-        logic: logic_char,
-        direction,
-        param,
-    })
+    Some((group, engine_char, logic_char, direction, param))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8399,18 +7718,24 @@ pub struct V19SetfileValidation {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct V19ParsedSetfile {
     pub version: String,
+    /// Global gInput keys (everything that isn't a logic-direction key)
     pub inputs: HashMap<String, String>,
+    /// Logic-direction keys with interned parameter names and values
+    pub logic_inputs: HashMap<ParsedV19Key, Arc<str>>,
     pub validation: V19SetfileValidation,
 }
 
 /// Parse v19 massive setfile format
 pub fn parse_v19_setfile(content: &str) -> V19ParsedSetfile {
     let mut inputs = HashMap::new();
+    let mut logic_inputs = HashMap::new();
+    let mut interner = V19KeyInterner::new();
     let mut errors = Vec::new();
-    let mut logic_counts: HashMap<String, usize> = HashMap::new();
+    // Keyed by (group, engine, logic, direction) so counting never allocates.
+    let mut logic_counts: HashMap<(usize, char, char, &'static str), usize> = HashMap::new();
 
     for line in content.lines() {
         let line = line.trim();
@@ -8422,19 +7747,18 @@ pub fn parse_v19_setfile(content: &str) -> V19ParsedSetfile {
 
         // Parse key=value
         if let Some(eq_pos) = line.find('=') {
-            let key = line[..eq_pos].trim().to_string();
-            let value = line[eq_pos + 1..].trim().to_string();
+            let key = line[..eq_pos].trim();
+            let value = line[eq_pos + 1..].trim();
             // Try v19 format first
-            if let Some(parsed) = parse_v19_key(&key) {
-                inputs.insert(key.clone(), value.clone());
-
+            if let Some(parsed) = interner.parse_key(key) {
                 // Count logic-directions
-                let logic_dir_key =
-                    format!("{}_{}_{}_{}", parsed.group, parsed.engine, parsed.logic, parsed.direction);
-                *logic_counts.entry(logic_dir_key).or_insert(0) += 1;
+                *logic_counts
+                    .entry((parsed.group, parsed.engine, parsed.logic, parsed.direction))
+                    .or_insert(0) += 1;
+                logic_inputs.insert(parsed, interner.intern(value));
             } else if key.starts_with("ginput_") || key.starts_with("gInput_") {
                 // Add non-v19 format global inputs
-                inputs.insert(key, value);
+                inputs.insert(key.to_string(), value.to_string());
             }
         }
     }
@@ -8450,12 +7774,8 @@ pub fn parse_v19_setfile(content: &str) -> V19ParsedSetfile {
         ));
     }
 
-    for (k, count) in &logic_counts {
-        let group = k
-            .split('_')
-            .next()
-            .and_then(|v| v.parse::<usize>().ok())
-            .unwrap_or(0);
+    for (&(group, engine, logic, direction), count) in &logic_counts {
+        let k = format!("{}_{}_{}_{}", group, engine, logic, direction);
         let (expected_fields, expected_fields_legacy) = if group == 1 {
             (V19_FIELDS_PER_LOGIC_GROUP1, V19_FIELDS_PER_LOGIC_GROUP1_LEGACY)
         } else {
@@ -8487,7 +7807,7 @@ pub fn parse_v19_setfile(content: &str) -> V19ParsedSetfile {
         }
     }
 
-    let total_inputs = inputs.len();
+    let total_inputs = inputs.len() + logic_inputs.len();
     if total_inputs < V19_MIN_TOTAL_INPUTS {
         errors.push(format!(
             "Expected at least {} inputs, found {}. Setfile may be incomplete.",
@@ -8510,6 +7830,7 @@ pub fn parse_v19_setfile(content: &str) -> V19ParsedSetfile {
     V19ParsedSetfile {
         version: "19.0".to_string(),
         inputs,
+        logic_inputs,
         validation,
     }
 }
@@ -8583,7 +7904,7 @@ pub fn audit_v19_setfile_contract(file_path: String) -> Result<V19ContractAuditR
         group_power_start.insert(format!("CP{}", g), value_cp);
     }
 
-    for (key, value) in &parsed.inputs {
+    for key in parsed.inputs.keys() {
         let key_lower = key.to_ascii_lowercase();
         if key_lower.starts_with("ginput_grouppowerstart_bp") {
             banned_bp_keys += 1;
@@ -8591,28 +7912,28 @@ pub fn audit_v19_setfile_contract(file_path: String) -> Result<V19ContractAuditR
         if key_lower.starts_with("ginput_grouppowerstart_cp") {
             banned_cp_keys += 1;
         }
+    }
 
-        if let Some(pk) = parse_v19_key(key) {
-            if pk.param.eq_ignore_ascii_case("Enabled") {
-                if value.trim() == "1" || value.trim().eq_ignore_ascii_case("true") {
-                    enabled_rows += 1;
-                }
-            } else if pk.param.eq_ignore_ascii_case("TriggerType") {
-                *trigger_type_distribution
-                    .entry(value.trim().to_string())
-                    .or_insert(0) += 1;
-            } else if pk.param.eq_ignore_ascii_case("TriggerMode") {
-                *trigger_mode_distribution
-                    .entry(value.trim().to_string())
-                    .or_insert(0) += 1;
-            } else if pk.group == 1 && pk.param.eq_ignore_ascii_case("StartLevel") {
-                start_levels_group1.push(V19StartLevelAuditRow {
-                    engine: pk.engine.to_string(),
-                    logic: logic_code_to_name(pk.logic).unwrap_or("UNKNOWN").to_string(),
-                    direction: pk.direction,
-                    start_level: value.trim().to_string(),
-                });
+    for (pk, value) in &parsed.logic_inputs {
+        if pk.param.eq_ignore_ascii_case("Enabled") {
+            if value.trim() == "1" || value.trim().eq_ignore_ascii_case("true") {
+                enabled_rows += 1;
             }
+        } else if pk.param.eq_ignore_ascii_case("TriggerType") {
+            *trigger_type_distribution
+                .entry(value.trim().to_string())
+                .or_insert(0) += 1;
+        } else if pk.param.eq_ignore_ascii_case("TriggerMode") {
+            *trigger_mode_distribution
+                .entry(value.trim().to_string())
+                .or_insert(0) += 1;
+        } else if pk.group == 1 && pk.param.eq_ignore_ascii_case("StartLevel") {
+            start_levels_group1.push(V19StartLevelAuditRow {
+                engine: pk.engine.to_string(),
+                logic: logic_code_to_name(pk.logic).unwrap_or("UNKNOWN").to_string(),
+                direction: pk.direction.to_string(),
+                start_level: value.trim().to_string(),
+            });
         }
     }
 
@@ -8625,7 +7946,7 @@ pub fn audit_v19_setfile_contract(file_path: String) -> Result<V19ContractAuditR
 
     Ok(V19ContractAuditReport {
         file_path: sanitized_path.to_string_lossy().to_string(),
-        total_inputs: parsed.inputs.len(),
+        total_inputs: parsed.validation.total_inputs,
         enabled_rows,
         group_power_start,
         start_levels_group1,
@@ -8637,7 +7958,7 @@ pub fn audit_v19_setfile_contract(file_path: String) -> Result<V19ContractAuditR
     })
 }
 
-fn create_full_v19_config() -> MTConfig {
+pub(crate) fn create_full_v19_config() -> MTConfig {
     let mut config = create_default_mt_config();
 
     let engines = ["A", "B", "C"];
@@ -8689,7 +8010,7 @@ fn create_full_v19_config() -> MTConfig {
     config
 }
 
-fn logic_code_to_name(code: char) -> Option<&'static str> {
+pub(crate) fn logic_code_to_name(code: char) -> Option<&'static str> {
     match code {
         'P' => Some("POWER"),
         'R' => Some("REPOWER"),
@@ -8710,101 +8031,26 @@ fn parse_bool_val(raw: &str) -> bool {
     matches!(v.to_ascii_lowercase().as_str(), "true" | "yes" | "on")
 }
 
-fn decode_trail_step_mode_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "TrailStepMode_Auto".to_string(),
-        "1" => "TrailStepMode_Fixed".to_string(),
-        "3" => "TrailStepMode_PerOrder".to_string(),
-        "4" => "TrailStepMode_Auto".to_string(),
-        other => other.to_string(),
-    }
-}
-
-fn decode_trail_step_method_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "Step_Points".to_string(),
-        "1" => "Step_Percent".to_string(),
-        "2" => "Step_Points".to_string(),
-        "Step_Pips" | "step_pips" => "Step_Points".to_string(),
-        "Step_Points" | "step_points" => "Step_Points".to_string(),
-        "Step_Percent" | "step_percent" => "Step_Percent".to_string(),
-        other => other.to_string(),
+/// Selector value read from a setfile: canonical spelling, legacy spelling or
+/// EA code. Blank gives None; an unknown value is reported as an import
+/// warning and also gives None, so the caller keeps its default instead of
+/// importing a config the backend will refuse to load again.
+fn decode_setfile_enum<E: ConfigEnum>(raw: &str) -> Option<E> {
+    let value = raw.trim();
+    if value.is_empty() {
+        return None;
     }
-}
-
-fn decode_partial_mode_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "PartialMode_Low".to_string(),
-        "1" => "PartialMode_Mid".to_string(),
-        "2" => "PartialMode_Aggressive".to_string(),
-        // Legacy aliases -> canonical
-        "3" => "PartialMode_Aggressive".to_string(),
-        "4" => "PartialMode_Mid".to_string(),
-        "PartialMode_Low" | "partialmode_low" => "PartialMode_Low".to_string(),
-        "PartialMode_Mid" | "partialmode_mid" => "PartialMode_Mid".to_string(),
-        "PartialMode_Aggressive" | "partialmode_aggressive" => {
-            "PartialMode_Aggressive".to_string()
+    match E::parse(value) {
+        Ok(parsed) => Some(parsed),
+        Err(err) => {
+            tracing::warn!(
+                value = %value,
+                error = %err,
+                "Unknown {} in setfile, keeping default",
+                E::NAME
+            );
+            None
         }
-        "PartialMode_High" | "partialmode_high" => "PartialMode_Aggressive".to_string(),
-        "PartialMode_Balanced" | "partialmode_balanced" => "PartialMode_Mid".to_string(),
-        _ => "PartialMode_Mid".to_string(),
-    }
-}
-
-fn decode_partial_balance_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "PartialBalance_Aggressive".to_string(),
-        "1" => "PartialBalance_Balanced".to_string(),
-        "2" => "PartialBalance_Conservative".to_string(),
-        other => other.to_string(),
-    }
-}
-
-fn decode_trail_method_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "Points".to_string(),
-        "1" => "AVG_Percent".to_string(),
-        "2" => "Points".to_string(),
-        "Trail" | "trail" => "Points".to_string(),
-        "Trail_Points" | "Points" => "Points".to_string(),
-        "Trail_AVG_Percent" | "AVG_Percent" => "AVG_Percent".to_string(),
-        "Trail_AVG_Points" | "AVG_Points" => "Points".to_string(),
-        "Trail_Profit_Percent" | "Percent" => "Points".to_string(),
-        other => other.to_string(),
-    }
-}
-
-fn decode_trigger_type_numeric(raw: &str) -> String {
-    match raw.trim() {
-        "0" => "0 Trigger_Immediate".to_string(),
-        "1" => "1 Trigger_AfterBars".to_string(),
-        "2" => "2 Trigger_AfterSeconds".to_string(),
-        "3" => "3 Trigger_AfterPips".to_string(),
-        "4" => "4 Trigger_TimeFilter".to_string(),
-        "5" => "5 Trigger_NewsFilter".to_string(),
-        "6" => "6 Trigger_PowerAOppositeCount".to_string(),
-        other => other.to_string(),
-    }
-}
-
-fn decode_trigger_mode_numeric(raw: &str) -> String {
-    normalize_trigger_mode(raw)
-}
-
-fn normalize_trading_mode(raw: &str) -> String {
-    let mode = raw.trim().to_ascii_lowercase();
-    match mode.as_str() {
-        "hedge" => "Hedge".to_string(),
-        "reverse" => "Reverse".to_string(),
-        "counter trend"
-        | "countertrend"
-        | "counter_trend"
-        | "counter-trend"
-        | "trend following"
-        | "trend_following"
-        | "trending"
-        | "" => "Counter Trend".to_string(),
-        _ => "Counter Trend".to_string(),
     }
 }
 
@@ -8818,43 +8064,13 @@ fn is_missing_logic_reference(raw: &str) -> bool {
 }
 
 fn normalize_logic_mode_and_flags(logic: &mut LogicConfig, engine_id: &str) {
-    logic.trail_method = decode_trail_method_numeric(&logic.trail_method);
-    logic.trail_step_method = decode_trail_step_method_numeric(&logic.trail_step_method);
-    logic.trail_step_mode = decode_trail_step_mode_numeric(&logic.trail_step_mode);
     if logic.trigger_seconds.is_none() && logic.trigger_minutes.is_some() {
         logic.trigger_seconds = logic.trigger_minutes;
     }
     logic.trigger_minutes = None;
 
-    let normalize_opt_step =
-        |slot: &mut Option<String>, decoder: fn(&str) -> String| {
-            if let Some(curr) = slot.clone() {
-                let next = decoder(&curr);
-                if next.trim().is_empty() {
-                    *slot = None;
-                } else {
-                    *slot = Some(next);
-                }
-            }
-        };
-
-    normalize_opt_step(&mut logic.trail_step_method_2, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_method_3, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_method_4, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_method_5, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_method_6, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_method_7, decode_trail_step_method_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_2, decode_trail_step_mode_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_3, decode_trail_step_mode_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_4, decode_trail_step_mode_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_5, decode_trail_step_mode_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_6, decode_trail_step_mode_numeric);
-    normalize_opt_step(&mut logic.trail_step_mode_7, decode_trail_step_mode_numeric);
-
-    if let Some(tt) = logic.trigger_type.clone() {
-        let normalized = decode_trigger_type_numeric(&tt);
-        logic.trigger_type = Some(normalized.clone());
-        let code = trigger_type_code(&normalized);
+    if let Some(trigger) = logic.trigger_type {
+        let code = trigger.ea_code();
         if code != 1 {
             logic.trigger_bars = Some(0);
         }
@@ -8865,11 +8081,7 @@ fn normalize_logic_mode_and_flags(logic: &mut LogicConfig, engine_id: &str) {
             logic.trigger_pips = Some(0.0);
         }
         if code == 0 {
-            let mode_raw = logic
-                .trigger_mode
-                .clone()
-                .unwrap_or_else(|| "TriggerMode_OnTick".to_string());
-            logic.trigger_mode = Some(normalize_trigger_mode(&mode_raw));
+            logic.trigger_mode = Some(logic.trigger_mode.unwrap_or_default());
         } else {
             logic.trigger_mode = None;
         }
@@ -8877,36 +8089,32 @@ fn normalize_logic_mode_and_flags(logic: &mut LogicConfig, engine_id: &str) {
         logic.trigger_mode = None;
     }
 
-    let explicit_mode = if logic.trading_mode.trim().is_empty() {
-        None
-    } else {
-        Some(normalize_trading_mode(&logic.trading_mode))
-    };
+    let explicit_mode = logic.trading_mode;
 
-    let mut mode = if let Some(m) = explicit_mode.clone() {
+    let mut mode = if let Some(m) = explicit_mode {
         m
     } else if logic.hedge_enabled && !logic.reverse_enabled {
-        "Hedge".to_string()
+        TradingMode::Hedge
     } else if logic.reverse_enabled && !logic.hedge_enabled {
-        "Reverse".to_string()
+        TradingMode::Reverse
     } else {
-        "Counter Trend".to_string()
+        TradingMode::CounterTrend
     };
 
     // Invalid legacy conflict: no explicit mode and both flags enabled.
     if explicit_mode.is_none() && logic.hedge_enabled && logic.reverse_enabled {
-        mode = "Counter Trend".to_string();
+        mode = TradingMode::CounterTrend;
     }
 
     // Engine A POWER is always Counter Trend.
     if is_engine_a_power(engine_id, &logic.logic_name) {
-        mode = "Counter Trend".to_string();
+        mode = TradingMode::CounterTrend;
     }
 
-    logic.trading_mode = mode.clone();
+    logic.trading_mode = Some(mode);
 
-    match mode.as_str() {
-        "Hedge" => {
+    match mode {
+        TradingMode::Hedge => {
             logic.hedge_enabled = true;
             logic.reverse_enabled = false;
             if logic.hedge_reference.trim().is_empty() {
@@ -8915,7 +8123,7 @@ fn normalize_logic_mode_and_flags(logic: &mut LogicConfig, engine_id: &str) {
             logic.reverse_reference = "Logic_None".to_string();
             logic.reverse_scale = 100.0;
         }
-        "Reverse" => {
+        TradingMode::Reverse => {
             logic.reverse_enabled = true;
             logic.hedge_enabled = false;
             if logic.reverse_reference.trim().is_empty() {
@@ -8924,19 +8132,18 @@ fn normalize_logic_mode_and_flags(logic: &mut LogicConfig, engine_id: &str) {
             logic.hedge_reference = "Logic_None".to_string();
             logic.hedge_scale = 50.0;
         }
-        _ => {
+        TradingMode::CounterTrend => {
             logic.reverse_enabled = false;
             logic.hedge_enabled = false;
             logic.reverse_reference = "Logic_None".to_string();
             logic.hedge_reference = "Logic_None".to_string();
             logic.reverse_scale = 100.0;
             logic.hedge_scale = 50.0;
-            logic.trading_mode = "Counter Trend".to_string();
         }
     }
 }
 
-fn normalize_config_mode_contract(config: &mut MTConfig) {
+pub(crate) fn normalize_config_mode_contract(config: &mut MTConfig) {
     for engine in &mut config.engines {
         for group in &mut engine.groups {
             for logic in &mut group.logics {
@@ -8954,7 +8161,7 @@ fn set_dir_opt_f64(is_buy: bool, field_b: &mut Option<f64>, field_s: &mut Option
     }
 }
 
-fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str, raw: &str) {
+pub(crate) fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str, raw: &str) {
     let p = param.to_ascii_lowercase();
     let raw_trimmed = raw.trim();
     match p.as_str() {
@@ -8962,7 +8169,7 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         "allowbuy" => logic.allow_buy = parse_bool_val(raw),
         "allowsell" => logic.allow_sell = parse_bool_val(raw),
         "tradingmode" => {
-            logic.trading_mode = normalize_trading_mode(raw);
+            logic.trading_mode = decode_setfile_enum(raw);
         }
         "initiallot" => {
             if let Ok(v) = raw_trimmed.parse::<f64>() {
@@ -8992,8 +8199,8 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         "trail" => {
             if let Ok(n) = raw_trimmed.parse::<i32>() {
                 logic.trail_method = match n {
-                    1 => "AVG_Percent".to_string(),
-                    _ => "Points".to_string(),
+                    1 => TrailMethod::AvgPercent,
+                    _ => TrailMethod::Points,
                 };
             }
         }
@@ -9014,8 +8221,16 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
                 set_dir_opt_f64(is_buy, &mut logic.trail_step_b, &mut logic.trail_step_s, v);
             }
         }
-        "trailstepmethod" => logic.trail_step_method = decode_trail_step_method_numeric(raw),
-        "trailstepmode" => logic.trail_step_mode = decode_trail_step_mode_numeric(raw),
+        "trailstepmethod" => {
+            if let Some(v) = decode_setfile_enum(raw) {
+                logic.trail_step_method = v;
+            }
+        }
+        "trailstepmode" => {
+            if let Some(v) = decode_setfile_enum(raw) {
+                logic.trail_step_mode = v;
+            }
+        }
         "trailstepcycle" => {
             if let Ok(v) = raw_trimmed.parse::<i32>() {
                 logic.trail_step_cycle = v;
@@ -9026,20 +8241,8 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
                 logic.trail_step_balance = v;
             }
         }
-        "triggertype" => {
-            if raw_trimmed.is_empty() {
-                logic.trigger_type = None;
-            } else {
-                logic.trigger_type = Some(decode_trigger_type_numeric(raw));
-            }
-        }
-        "triggermode" => {
-            if raw_trimmed.is_empty() {
-                logic.trigger_mode = None;
-            } else {
-                logic.trigger_mode = Some(decode_trigger_mode_numeric(raw));
-            }
-        }
+        "triggertype" => logic.trigger_type = decode_setfile_enum(raw),
+        "triggermode" => logic.trigger_mode = decode_setfile_enum(raw),
         "triggerbars" => {
             if let Ok(v) = raw_trimmed.parse::<i32>() {
                 logic.trigger_bars = Some(v);
@@ -9080,7 +8283,11 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         "closepartial" => logic.close_partial = parse_bool_val(raw),
         // Deprecated in active contract: ignored on import.
         "closepartialcycle" => {}
-        "closepartialmode" => logic.close_partial_mode = decode_partial_mode_numeric(raw),
+        "closepartialmode" => {
+            if let Some(v) = decode_setfile_enum(raw) {
+                logic.close_partial_mode = v;
+            }
+        }
         // Deprecated in active contract: ignored on import.
         "closepartialbalance" => {}
         "closepartialtrailmode" => {}
@@ -9098,13 +8305,7 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         }
         // Deprecated in active contract: ignored on import.
         "closepartialcycle2" => {}
-        "closepartialmode2" => {
-            if raw_trimmed.is_empty() {
-                logic.close_partial_mode_2 = None;
-            } else {
-                logic.close_partial_mode_2 = Some(decode_partial_mode_numeric(raw));
-            }
-        }
+        "closepartialmode2" => logic.close_partial_mode_2 = decode_setfile_enum(raw),
         // Deprecated in active contract: ignored on import.
         "closepartialbalance2" => {}
         "closepartialtrailmode2" => {}
@@ -9124,13 +8325,7 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         }
         // Deprecated in active contract: ignored on import.
         "closepartialcycle3" => {}
-        "closepartialmode3" => {
-            if raw_trimmed.is_empty() {
-                logic.close_partial_mode_3 = None;
-            } else {
-                logic.close_partial_mode_3 = Some(decode_partial_mode_numeric(raw));
-            }
-        }
+        "closepartialmode3" => logic.close_partial_mode_3 = decode_setfile_enum(raw),
         // Deprecated in active contract: ignored on import.
         "closepartialbalance3" => {}
         "closepartialtrailmode3" => {}
@@ -9150,13 +8345,7 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
         }
         // Deprecated in active contract: ignored on import.
         "closepartialcycle4" => {}
-        "closepartialmode4" => {
-            if raw_trimmed.is_empty() {
-                logic.close_partial_mode_4 = None;
-            } else {
-                logic.close_partial_mode_4 = Some(decode_partial_mode_numeric(raw));
-            }
-        }
+        "closepartialmode4" => logic.close_partial_mode_4 = decode_setfile_enum(raw),
         // Deprecated in active contract: ignored on import.
         "closepartialbalance4" => {}
         "closepartialtrailmode4" => {}
@@ -9214,26 +8403,14 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
             }
             if let Some(suffix) = p.strip_prefix("trailstepmethod") {
                 if let Ok(n) = suffix.parse::<i32>() {
-                    if raw_trimmed.is_empty() {
-                        match n {
-                            2 => logic.trail_step_method_2 = None,
-                            3 => logic.trail_step_method_3 = None,
-                            4 => logic.trail_step_method_4 = None,
-                            5 => logic.trail_step_method_5 = None,
-                            6 => logic.trail_step_method_6 = None,
-                            7 => logic.trail_step_method_7 = None,
-                            _ => {}
-                        }
-                        return;
-                    }
-                    let v = decode_trail_step_method_numeric(raw);
+                    let v = decode_setfile_enum(raw);
                     match n {
-                        2 => logic.trail_step_method_2 = Some(v),
-                        3 => logic.trail_step_method_3 = Some(v),
-                        4 => logic.trail_step_method_4 = Some(v),
-                        5 => logic.trail_step_method_5 = Some(v),
-                        6 => logic.trail_step_method_6 = Some(v),
-                        7 => logic.trail_step_method_7 = Some(v),
+                        2 => logic.trail_step_method_2 = v,
+                        3 => logic.trail_step_method_3 = v,
+                        4 => logic.trail_step_method_4 = v,
+                        5 => logic.trail_step_method_5 = v,
+                        6 => logic.trail_step_method_6 = v,
+                        7 => logic.trail_step_method_7 = v,
                         _ => {}
                     }
                     return;
@@ -9241,26 +8418,14 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
             }
             if let Some(suffix) = p.strip_prefix("trailstepmode") {
                 if let Ok(n) = suffix.parse::<i32>() {
-                    if raw_trimmed.is_empty() {
-                        match n {
-                            2 => logic.trail_step_mode_2 = None,
-                            3 => logic.trail_step_mode_3 = None,
-                            4 => logic.trail_step_mode_4 = None,
-                            5 => logic.trail_step_mode_5 = None,
-                            6 => logic.trail_step_mode_6 = None,
-                            7 => logic.trail_step_mode_7 = None,
-                            _ => {}
-                        }
-                        return;
-                    }
-                    let v = decode_trail_step_mode_numeric(raw);
+                    let v = decode_setfile_enum(raw);
                     match n {
-                        2 => logic.trail_step_mode_2 = Some(v),
-                        3 => logic.trail_step_mode_3 = Some(v),
-                        4 => logic.trail_step_mode_4 = Some(v),
-                        5 => logic.trail_step_mode_5 = Some(v),
-                        6 => logic.trail_step_mode_6 = Some(v),
-                        7 => logic.trail_step_mode_7 = Some(v),
+                        2 => logic.trail_step_mode_2 = v,
+                        3 => logic.trail_step_mode_3 = v,
+                        4 => logic.trail_step_mode_4 = v,
+                        5 => logic.trail_step_mode_5 = v,
+                        6 => logic.trail_step_mode_6 = v,
+                        7 => logic.trail_step_mode_7 = v,
                         _ => {}
                     }
                     return;
@@ -9328,7 +8493,7 @@ fn apply_v19_param_to_logic(logic: &mut LogicConfig, is_buy: bool, param: &str,
     }
 }
 
-fn apply_v19_global_keys(config: &mut MTConfig, inputs: &HashMap<String, String>) {
+pub(crate) fn apply_v19_global_keys(config: &mut MTConfig, inputs: &HashMap<String, String>) {
     if let Some(v) = inputs.get("gInput_MagicNumber") {
         config.general.magic_number = v.parse::<i32>().unwrap_or(config.general.magic_number);
     }
@@ -9430,59 +8595,86 @@ fn build_config_from_v19_setfile(content: &str) -> Result<MTConfig, String> {
     }
 
     let legacy_trigger_minute_keys = parsed
-        .inputs
+        .logic_inputs
         .keys()
-        .filter(|k| k.contains("TriggerMinutes"))
-        .count();
+        .filter(|k| k.param.contains("TriggerMinutes"))
+        .count()
+        + parsed
+            .inputs
+            .keys()
+            .filter(|k| k.contains("TriggerMinutes"))
+            .count();
     if legacy_trigger_minute_keys > 0 {
-        println!(
-            "[SETFILE] WARN: Detected {} legacy TriggerMinutes key(s) in v19 import; mapping to trigger_seconds.",
+        tracing::warn!(
+            "Detected {} legacy TriggerMinutes key(s) in v19 import; mapping to trigger_seconds.",
             legacy_trigger_minute_keys
         );
     }
 
     let mut config = create_full_v19_config();
     apply_v19_global_keys(&mut config, &parsed.inputs);
-
-    for (key, raw_val) in &parsed.inputs {
-        if let Some(parsed_key) = parse_v19_key(key) {
-            // StartLevel is Group 1-only. Ignore legacy repeated keys from Groups 2-15.
-            if parsed_key.group != 1 && parsed_key.param.eq_ignore_ascii_case("StartLevel") {
-                continue;
-            }
-            let engine_id = parsed_key.engine.to_string();
-            let logic_name = logic_code_to_name(parsed_key.logic)
-                .ok_or_else(|| format!("Unknown logic code: {}", parsed_key.logic))?;
-            let is_buy = parsed_key.direction == "Buy";
-            let group_u8 = parsed_key.group as u8;
-
-            if let Some(engine) = config.engines.iter_mut().find(|e| e.engine_id == engine_id) {
-                if let Some(group) = engine.groups.iter_mut().find(|g| g.group_number == group_u8) {
-                    let dir_token = if is_buy { "_B_" } else { "_S_" };
-                    let mut maybe_logic = group.logics.iter_mut().find(|l| {
-                        l.logic_name.to_uppercase() == logic_name.to_uppercase()
-                            && l.logic_id.to_uppercase().contains(dir_token)
-                    });
-                    if maybe_logic.is_none() {
-                        maybe_logic = group
-                            .logics
-                            .iter_mut()
-                            .find(|l| l.logic_name.to_uppercase() == logic_name.to_uppercase());
-                    }
-                    if let Some(logic) = maybe_logic {
-                        apply_v19_param_to_logic(logic, is_buy, &parsed_key.param, raw_val);
-                    }
-                }
-            }
-        }
+    for (key, raw_val) in &parsed.logic_inputs {
+        apply_v19_logic_input(&mut config, key, raw_val)?;
     }
 
     normalize_config_mode_contract(&mut config);
-    config.total_inputs = parsed.inputs.len();
+    config.total_inputs = parsed.validation.total_inputs;
     config.version = "v19.0".to_string();
     Ok(config)
 }
 
+/// Apply directional gInput_{Group}_{Engine}{Logic}_{Direction}_{Param} keys onto
+/// the matching logic rows of `config`
+pub(crate) fn apply_v19_logic_inputs(
+    config: &mut MTConfig,
+    inputs: &HashMap<String, String>,
+) -> Result<(), String> {
+    let mut interner = V19KeyInterner::new();
+    for (key, raw_val) in inputs {
+        if let Some(parsed_key) = interner.parse_key(key) {
+            apply_v19_logic_input(config, &parsed_key, raw_val)?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply one parsed directional key onto its logic row
+fn apply_v19_logic_input(
+    config: &mut MTConfig,
+    parsed_key: &ParsedV19Key,
+    raw_val: &str,
+) -> Result<(), String> {
+    // StartLevel is Group 1-only. Ignore legacy repeated keys from Groups 2-15.
+    if parsed_key.group != 1 && parsed_key.param.eq_ignore_ascii_case("StartLevel") {
+        return Ok(());
+    }
+    let engine_id = parsed_key.engine.to_string();
+    let logic_name = logic_code_to_name(parsed_key.logic)
+        .ok_or_else(|| format!("Unknown logic code: {}", parsed_key.logic))?;
+    let is_buy = parsed_key.direction == "Buy";
+    let group_u8 = parsed_key.group as u8;
+
+    if let Some(engine) = config.engines.iter_mut().find(|e| e.engine_id == engine_id) {
+        if let Some(group) = engine.groups.iter_mut().find(|g| g.group_number == group_u8) {
+            let dir_token = if is_buy { "_B_" } else { "_S_" };
+            let mut maybe_logic = group.logics.iter_mut().find(|l| {
+                l.logic_name.to_uppercase() == logic_name.to_uppercase()
+                    && l.logic_id.to_uppercase().contains(dir_token)
+            });
+            if maybe_logic.is_none() {
+                maybe_logic = group
+                    .logics
+                    .iter_mut()
+                    .find(|l| l.logic_name.to_uppercase() == logic_name.to_uppercase());
+            }
+            if let Some(logic) = maybe_logic {
+                apply_v19_param_to_logic(logic, is_buy, &parsed_key.param, raw_val);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Get magic number for a logic-direction (v19 scheme)
 #[allow(dead_code)]
 pub fn get_v19_magic_number(base: i32, engine_idx: usize, logic_idx: usize, direction_idx: usize) -> i32 {
@@ -9504,7 +8696,21 @@ mod v19_tests {
         assert_eq!(parsed.engine, 'A');
         assert_eq!(parsed.logic, 'P');
         assert_eq!(parsed.direction, "Buy");
-        assert_eq!(parsed.param, "InitialLot");
+        assert_eq!(&*parsed.param, "InitialLot");
+    }
+
+    #[test]
+    fn test_v19_key_interner_shares_param_names() {
+        let mut interner = V19KeyInterner::new();
+        let buy = interner.parse_key("gInput_1_AP_Buy_TrailStep_2").unwrap();
+        let sell = interner.parse_key("gInput_15_CX_Sell_TrailStep_2").unwrap();
+        assert_eq!(&*buy.param, "TrailStep_2");
+        assert_eq!(sell.direction, "Sell");
+        assert!(Arc::ptr_eq(&buy.param, &sell.param));
+        assert_eq!(interner.len(), 1);
+
+        assert!(interner.parse_key("gInput_1_AP_Both_Start").is_none());
+        assert_eq!(interner.len(), 1);
     }
 
     #[test]
@@ -9579,8 +8785,56 @@ gInput_Global_MagicNumber=777
 "#;
 
         let setfile = parse_v19_setfile(content);
-        assert!(setfile.inputs.len() >= 5);
+        assert_eq!(setfile.logic_inputs.len(), 5);
+        assert_eq!(setfile.inputs.len(), 1);
+        assert_eq!(setfile.validation.total_inputs, 6);
         assert_eq!(setfile.validation.logic_directions, 5);
+
+        let grid = parse_v19_key("gInput_1_AR_Buy_Grid").unwrap();
+        assert_eq!(setfile.logic_inputs.get(&grid).map(|v| &**v), Some("300"));
+        let start_buy = parse_v19_key("gInput_1_AP_Buy_Start").unwrap();
+        let start_sell = parse_v19_key("gInput_1_AP_Sell_Start").unwrap();
+        // Repeated parameter names and values share one allocation
+        let (buy_key, buy_value) = setfile.logic_inputs.get_key_value(&start_buy).unwrap();
+        let (sell_key, sell_value) = setfile.logic_inputs.get_key_value(&start_sell).unwrap();
+        assert!(Arc::ptr_eq(&buy_key.param, &sell_key.param));
+        assert!(Arc::ptr_eq(buy_value, sell_value));
+    }
+
+    #[test]
+    fn test_export_massive_v19_setfile_applies_redaction() {
+        let mut config = create_full_v19_config();
+        config.general.news_filter.api_key = "news-secret".to_string();
+        let tmp_path = std::env::temp_dir().join(format!(
+            "daavfx_v19_redaction_{}_{}.set",
+            std::process::id(),
+            chrono::Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let tmp_str = tmp_path.to_string_lossy().to_string();
+
+        let client = ExportRedaction {
+            audience: ExportAudience::Client,
+            policy: None,
+        };
+        export_massive_v19_setfile(
+            config.clone(),
+            tmp_str.clone(),
+            "MT5".to_string(),
+            Some(false),
+            None,
+            Some(client),
+            None,
+        )
+        .expect("export should succeed");
+        let content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
+        assert!(content.lines().any(|l| l == "gInput_NewsAPIKey="));
+        assert!(!content.contains("news-secret"));
+
+        export_massive_v19_setfile(config, tmp_str, "MT5".to_string(), Some(false), None, None, None)
+            .expect("export should succeed");
+        let content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
+        assert!(content.lines().any(|l| l == "gInput_NewsAPIKey=news-secret"));
+        let _ = fs::remove_file(&tmp_path);
     }
 
     #[test]
@@ -9594,7 +8848,7 @@ gInput_Global_MagicNumber=777
         let tmp_str = tmp_path.to_string_lossy().to_string();
         let _ = fs::remove_file(&tmp_path);
 
-        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false))
+        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false), None, None, None)
             .expect("export should succeed");
 
         let mut content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
@@ -9623,6 +8877,25 @@ gInput_Global_MagicNumber=777
         let _ = fs::remove_file(&tmp_path);
     }
 
+    #[test]
+    fn test_unknown_selector_code_keeps_default_on_import() {
+        let mut logic = create_default_logic("Power");
+        apply_v19_param_to_logic(&mut logic, true, "TrailStepMode", "7");
+        apply_v19_param_to_logic(&mut logic, true, "TrailStepMode3", "7");
+        apply_v19_param_to_logic(&mut logic, true, "TriggerType", "42");
+        assert_eq!(logic.trail_step_mode, BASE_LOGIC.trail_step_mode);
+        assert_eq!(logic.trail_step_mode_3, None);
+        assert_eq!(logic.trigger_type, None);
+
+        apply_v19_param_to_logic(&mut logic, true, "TrailStepMode", "3");
+        assert_eq!(logic.trail_step_mode, TrailStepMode::PerOrder);
+
+        // The imported config must load again on its next trip to the backend
+        let json = serde_json::to_string(&logic).unwrap();
+        let reloaded: LogicConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.trail_step_mode, TrailStepMode::PerOrder);
+    }
+
     #[test]
     fn test_create_full_v19_config_has_directional_rows() {
         let config = create_full_v19_config();
@@ -9669,7 +8942,7 @@ gInput_Global_MagicNumber=777
         let tmp_str = tmp_path.to_string_lossy().to_string();
         let _ = fs::remove_file(&tmp_path);
 
-        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false))
+        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false), None, None, None)
             .expect("export should succeed");
 
         let content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
@@ -9711,7 +8984,7 @@ gInput_Global_MagicNumber=777
         let tmp_str = tmp_path.to_string_lossy().to_string();
         let _ = fs::remove_file(&tmp_path);
 
-        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false))
+        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false), None, None, None)
             .expect("export should succeed");
 
         let content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
@@ -9762,6 +9035,256 @@ gInput_Global_MagicNumber=777
         let _ = fs::remove_file(&tmp_path);
     }
 
+    #[test]
+    fn test_parse_massive_setfile_stops_when_cancelled() {
+        let tmp_path = std::env::temp_dir().join(format!(
+            "daavfx_massive_parse_cancel_{}.set",
+            std::process::id()
+        ));
+        export_massive_v19_setfile(
+            create_full_v19_config(),
+            tmp_path.to_string_lossy().to_string(),
+            "MT5".to_string(),
+            Some(false),
+            None,
+            None,
+            None,
+        )
+        .expect("export should succeed");
+
+        let task = crate::task_manager().begin(Some("cancel-test".to_string()), "test");
+        assert!(crate::task_manager().cancel("cancel-test"));
+
+        let result = parse_massive_setfile_with_task(tmp_path.to_string_lossy().to_string(), &task);
+        crate::task_manager().finish(&task.id);
+        assert!(result.unwrap_err().contains("cancelled"));
+        assert!(!crate::task_manager().cancel("cancel-test"));
+
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    #[test]
+    fn test_export_massive_v19_setfile_emits_progress_events() {
+        static EVENTS: std::sync::Mutex<Vec<crate::ProgressEvent>> = std::sync::Mutex::new(Vec::new());
+        crate::set_progress_sink(|event| {
+            if let Ok(mut events) = EVENTS.lock() {
+                events.push(event.clone());
+            }
+        });
+
+        let tmp_path = std::env::temp_dir().join(format!(
+            "daavfx_massive_export_progress_{}.set",
+            std::process::id()
+        ));
+        export_massive_v19_setfile(
+            create_full_v19_config(),
+            tmp_path.to_string_lossy().to_string(),
+            "MT5".to_string(),
+            Some(false),
+            Some("export-progress-test".to_string()),
+            None,
+            None,
+        )
+        .expect("export should succeed");
+
+        let events: Vec<crate::ProgressEvent> = EVENTS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.task_id == "export-progress-test")
+            .cloned()
+            .collect();
+        assert!(events.iter().any(|e| e.section == "engine_B_group_7"));
+        assert!(events.windows(2).all(|w| w[0].percent <= w[1].percent));
+        let last = events.last().expect("progress events emitted");
+        assert!(last.done);
+        assert_eq!(last.percent, 100.0);
+
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    #[test]
+    fn test_render_set_preview_matches_massive_export() {
+        let tmp_path = std::env::temp_dir().join(format!(
+            "daavfx_massive_preview_{}.set",
+            std::process::id()
+        ));
+        export_massive_v19_setfile(
+            create_full_v19_config(),
+            tmp_path.to_string_lossy().to_string(),
+            "MT5".to_string(),
+            Some(false),
+            None,
+            None,
+            None,
+        )
+        .expect("export should succeed");
+        let written = fs::read_to_string(&tmp_path).expect("should read exported setfile");
+        let _ = fs::remove_file(&tmp_path);
+
+        let options = SetPreviewOptions {
+            platform: Some("MT5".to_string()),
+            ..Default::default()
+        };
+        let preview = render_set_preview(
+            create_full_v19_config(),
+            "massive_v19".to_string(),
+            Some(options.clone()),
+        )
+        .expect("preview should render");
+        // The timestamp may tick between the two renders
+        let without_timestamp = |text: &str| -> Vec<String> {
+            text.lines()
+                .filter(|line| !line.starts_with("; Generated:"))
+                .map(str::to_string)
+                .collect()
+        };
+        assert!(!preview.truncated);
+        assert_eq!(without_timestamp(&preview.content), without_timestamp(&written));
+        assert!(preview.sections.iter().any(|s| s.title == "GENERAL SETTINGS"));
+
+        let cut = render_set_preview(
+            create_full_v19_config(),
+            "massive_v19".to_string(),
+            Some(SetPreviewOptions {
+                max_lines: Some(3),
+                ..options
+            }),
+        )
+        .unwrap();
+        assert!(cut.truncated);
+        assert_eq!(cut.total_lines, preview.total_lines);
+        assert!(cut.content.contains("; ... sections not shown: GENERAL SETTINGS"));
+        assert!(render_set_preview(create_full_v19_config(), "xml".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_setfile_formats_round_trip() {
+        let mut config = create_full_v19_config();
+        config.general.magic_number = 4242;
+        config.engines[0].groups[0].reverse_mode = true;
+        let options = setfile_format::RenderOptions {
+            platform: "MT5".to_string(),
+            file_path: "DAAVFX_Config.set".to_string(),
+            ..Default::default()
+        };
+
+        let task = crate::task_manager().begin(None, "test_setfile_formats_round_trip");
+        for id in ["legacy", "v17", "massive_v19", "tester_ini"] {
+            let format = setfile_format::find_format(id).unwrap();
+            let content = format
+                .render(config.clone(), &options, &task)
+                .unwrap()
+                .join("\n");
+            assert_eq!(setfile_format::detect_format(&content).id(), id);
+
+            let imported = format.parse(&content, &task).unwrap();
+            assert_eq!(imported.general.magic_number, 4242, "{}", id);
+            match id {
+                "v17" => assert!(imported.engines[0].groups[0].reverse_mode),
+                "legacy" => assert!(!imported.engines[0].groups[0].reverse_mode),
+                _ => {}
+            }
+        }
+        crate::task_manager().finish(&task.id);
+    }
+
+    #[test]
+    fn test_default_logic_sources_agree() {
+        use crate::logic_defaults::LOGIC_DEFAULTS;
+
+        let no_directions = HashMap::new();
+        let no_values = HashMap::new();
+        for row in &LOGIC_DEFAULTS {
+            let created = create_default_logic(row.logic_name);
+            let seeded = create_default_logic_config(row.logic_name);
+            let parsed = build_logic_config("A", 1, row.logic_name, &no_directions, &no_values).unwrap();
+            for logic in [&created, &seeded, &parsed] {
+                assert_eq!(logic.initial_lot, row.initial_lot, "{}", row.logic_name);
+                assert_eq!(logic.multiplier, row.multiplier, "{}", row.logic_name);
+                assert_eq!(logic.grid, row.grid, "{}", row.logic_name);
+                assert_eq!(logic.trail_method, row.trail_method, "{}", row.logic_name);
+                assert_eq!(logic.trail_value, row.trail_value, "{}", row.logic_name);
+                assert_eq!(logic.trail_start, row.trail_start, "{}", row.logic_name);
+                assert_eq!(logic.trail_step, row.trail_step, "{}", row.logic_name);
+                assert_eq!(logic.trail_step_method, row.trail_step_method, "{}", row.logic_name);
+                assert_eq!(logic.close_targets, row.close_targets, "{}", row.logic_name);
+                assert_eq!(logic.order_count_reference, row.order_count_reference);
+                assert_eq!(logic.strategy_type, row.strategy_type, "{}", row.logic_name);
+                assert_eq!(logic.trading_mode, Some(row.trading_mode), "{}", row.logic_name);
+                assert_eq!(logic.allow_buy, row.allow_buy, "{}", row.logic_name);
+                assert_eq!(logic.allow_sell, row.allow_sell, "{}", row.logic_name);
+                assert_eq!(logic.reverse_scale, row.reverse_scale, "{}", row.logic_name);
+                assert_eq!(logic.hedge_scale, row.hedge_scale, "{}", row.logic_name);
+                assert_eq!(logic.trail_step_mode, row.trail_step_mode, "{}", row.logic_name);
+                assert_eq!(logic.trail_step_cycle, row.trail_step_cycle, "{}", row.logic_name);
+                assert_eq!(logic.close_partial_cycle, row.close_partial_cycle);
+                assert_eq!(logic.close_partial_mode, row.close_partial_mode);
+                assert_eq!(logic.close_partial_balance, row.close_partial_balance);
+            }
+            assert_eq!(created.last_lot, Some(row.last_lot), "{}", row.logic_name);
+            assert_eq!(parsed.last_lot, Some(row.last_lot), "{}", row.logic_name);
+            assert_eq!(parsed.start_level, row.start_level, "{}", row.logic_name);
+        }
+
+        assert_eq!(default_scale(), BASE_LOGIC.reverse_scale);
+        assert_eq!(default_half_scale(), BASE_LOGIC.hedge_scale);
+        assert_eq!(default_one(), BASE_LOGIC.trail_step_cycle);
+        assert_eq!(default_trail_step_mode(), BASE_LOGIC.trail_step_mode);
+        assert_eq!(default_strategy_trail(), BASE_LOGIC.strategy_type);
+        assert_eq!(default_mode_counter_trend(), Some(BASE_LOGIC.trading_mode));
+        assert_eq!(default_close_partial_cycle(), BASE_LOGIC.close_partial_cycle);
+        assert_eq!(default_partial_mode(), BASE_LOGIC.close_partial_mode);
+        assert_eq!(default_partial_balance(), BASE_LOGIC.close_partial_balance);
+        assert_eq!(default_logic_none(), BASE_LOGIC.reverse_reference);
+    }
+
+    #[test]
+    fn test_deterministic_export_ignores_order_and_clock() {
+        let options = SetPreviewOptions {
+            file_path: Some("DAAVFX_Config.set".to_string()),
+            platform: Some("MT4".to_string()),
+            deterministic: Some(DeterministicExport {
+                timestamp: Some("2024-01-01 00:00:00".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        let config = create_full_v19_config();
+        let mut shuffled = config.clone();
+        shuffled.engines.reverse();
+        for engine in &mut shuffled.engines {
+            engine.groups.reverse();
+            for group in &mut engine.groups {
+                group.logics.reverse();
+            }
+        }
+
+        let first =
+            render_set_preview(config, "legacy".to_string(), Some(options.clone())).unwrap();
+        let second = render_set_preview(shuffled, "legacy".to_string(), Some(options)).unwrap();
+        assert_eq!(first.content, second.content);
+        assert!(first.content.contains("; Generated: 2024-01-01 00:00:00"));
+
+        let omitted = render_set_preview(
+            create_full_v19_config(),
+            "massive_v19".to_string(),
+            Some(SetPreviewOptions {
+                deterministic: Some(DeterministicExport::default()),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        assert!(!omitted.content.contains("; Generated"));
+
+        assert_eq!(normalize_float_value("0.30000000000000004").as_deref(), Some("0.3"));
+        assert_eq!(normalize_float_value("1.50").as_deref(), Some("1.5"));
+        assert_eq!(normalize_float_value("111.0").as_deref(), Some("111.0"));
+        assert_eq!(normalize_float_value("-0.0").as_deref(), Some("0.0"));
+        assert_eq!(normalize_float_value("15"), None);
+        assert_eq!(normalize_float_value("1.0||0.5||2.0"), None);
+    }
+
     #[tokio::test]
     async fn test_parse_massive_setfile_keeps_directional_values_independent() {
         let mut config = create_full_v19_config();
@@ -9792,10 +9315,10 @@ gInput_Global_MagicNumber=777
         let tmp_str = tmp_path.to_string_lossy().to_string();
 
         let _ = fs::remove_file(&tmp_path);
-        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false))
+        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false), None, None, None)
             .expect("export should succeed");
 
-        let parsed = parse_massive_setfile(tmp_str.clone())
+        let parsed = parse_massive_setfile(tmp_str.clone(), None)
             .await
             .expect("parse_massive_setfile should succeed");
         assert!(parsed.success, "parser returned errors: {:?}", parsed.errors);
@@ -9856,7 +9379,7 @@ gInput_Global_MagicNumber=777
         let tmp_str = tmp_path.to_string_lossy().to_string();
         let _ = fs::remove_file(&tmp_path);
 
-        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false))
+        export_massive_v19_setfile(config, tmp_str.clone(), "MT5".to_string(), Some(false), None, None, None)
             .expect("export should succeed");
 
         let content = fs::read_to_string(&tmp_path).expect("should read exported setfile");
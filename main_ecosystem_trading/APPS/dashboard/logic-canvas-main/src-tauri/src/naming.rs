@@ -0,0 +1,148 @@
+// ============================================
+// NAMING POLICY
+// ============================================
+//
+// File names the dashboard writes for the EA and the terminal (config JSON,
+// ACTIVE.set, DAAVFX_-prefixed exports). White-label builds change them in
+// settings; every export, import and watcher command reads them from here.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::atomic_write;
+
+const NAMING_FILE: &str = "naming_policy.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NamingPolicy {
+    /// Prepended to generated file names, e.g. "DAAVFX_" in DAAVFX_MT5_Config.set
+    pub prefix: String,
+    /// JSON config the EA reads and the file watcher follows
+    pub config_name: String,
+    /// Set file the EA and the strategy tester load
+    pub active_set_name: String,
+    /// Platform tag used in per-platform file names ("MT4" -> "MT4");
+    /// platforms without an entry use their own name
+    pub platform_suffixes: BTreeMap<String, String>,
+}
+
+impl Default for NamingPolicy {
+    fn default() -> Self {
+        NamingPolicy {
+            prefix: "DAAVFX_".to_string(),
+            config_name: "DAAVFX_Config.json".to_string(),
+            active_set_name: "ACTIVE.set".to_string(),
+            platform_suffixes: BTreeMap::new(),
+        }
+    }
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\', ':']) && !name.contains("..")
+}
+
+impl NamingPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.config_name.trim().is_empty() || self.active_set_name.trim().is_empty() {
+            return Err("Config and active set names are required".to_string());
+        }
+        let names = [&self.prefix, &self.config_name, &self.active_set_name]
+            .into_iter()
+            .chain(self.platform_suffixes.values());
+        for name in names {
+            if !is_plain_file_name(name) {
+                return Err(format!(
+                    "Invalid name '{}': only plain file names are allowed",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn platform_suffix(&self, platform: &str) -> String {
+        self.platform_suffixes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(platform))
+            .map(|(_, suffix)| suffix.clone())
+            .unwrap_or_else(|| platform.to_string())
+    }
+
+    /// "<prefix><name>", e.g. DAAVFX_NEWS.csv
+    pub fn prefixed(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// Per-platform set file in Common Files, e.g. DAAVFX_MT5_Config.set
+    pub fn platform_set_name(&self, platform: &str) -> String {
+        self.prefixed(&format!("{}_Config.set", self.platform_suffix(platform)))
+    }
+}
+
+fn naming_store_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(NAMING_FILE)
+}
+
+/// The policy from settings, or the DAAVFX defaults when none is stored
+pub(crate) fn current() -> NamingPolicy {
+    std::fs::read_to_string(naming_store_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<NamingPolicy>(&content).ok())
+        .filter(|policy| policy.validate().is_ok())
+        .unwrap_or_default()
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_naming_policy() -> Result<NamingPolicy, String> {
+    Ok(current())
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_naming_policy(policy: NamingPolicy) -> Result<NamingPolicy, String> {
+    policy.validate()?;
+    let path = naming_store_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&policy)
+        .map_err(|e| format!("Failed to serialize naming policy: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_keeps_daavfx_names() {
+        let policy = NamingPolicy::default();
+        assert_eq!(policy.platform_set_name("MT5"), "DAAVFX_MT5_Config.set");
+        assert_eq!(policy.prefixed("NEWS.csv"), "DAAVFX_NEWS.csv");
+        assert!(policy.validate().is_ok());
+    }
+
+    #[test]
+    fn test_white_label_policy() {
+        let mut policy = NamingPolicy {
+            prefix: "ACME_".to_string(),
+            config_name: "ACME_Config.json".to_string(),
+            active_set_name: "LIVE.set".to_string(),
+            ..Default::default()
+        };
+        policy
+            .platform_suffixes
+            .insert("MT4".to_string(), "Classic".to_string());
+        assert_eq!(policy.platform_set_name("mt4"), "ACME_Classic_Config.set");
+        assert_eq!(policy.platform_set_name("MT5"), "ACME_MT5_Config.set");
+
+        policy.active_set_name = "../LIVE.set".to_string();
+        assert!(policy.validate().is_err());
+    }
+}
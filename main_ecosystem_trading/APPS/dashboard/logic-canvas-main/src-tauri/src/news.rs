@@ -0,0 +1,699 @@
+// ============================================
+// NEWS CALENDAR
+// ============================================
+//
+// Fetches the calendar API configured in NewsFilterConfig from the dashboard
+// itself (so news filtering keeps working when the EA's WebRequest is blocked),
+// normalizes events to UTC time / currency / impact, caches them on disk and
+// writes the DAAVFX_NEWS.csv file the EA reads from Common Files.
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::backtest::decode_text;
+use crate::mt_bridge::{
+    atomic_write, deobfuscate_string, get_mt_common_files_dir, sanitize_and_validate_path,
+    NewsFilterConfig,
+};
+use crate::naming;
+
+/// Canonical EA calendar layout; Date/Time are GMT, Impact is 1 (low) to 3 (high)
+pub const NEWS_CSV_HEADER: &str = "Date,Time,Currency,Impact,Event";
+const NEWS_CACHE_FILE: &str = "news_cache.json";
+const FETCH_TIMEOUT_SECS: u64 = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsEvent {
+    /// RFC 3339, UTC
+    pub time: String,
+    pub currency: String,
+    /// 0 = holiday/none, 1 = low, 2 = medium, 3 = high
+    pub impact: u8,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsCache {
+    pub fetched_at: String,
+    pub source: String,
+    pub events: Vec<NewsEvent>,
+    /// True when a refresh failed and these are older cached events
+    #[serde(default)]
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsFilters {
+    /// Currency codes (USD, EUR...); defaults to the config's countries
+    #[serde(default)]
+    pub currencies: Option<Vec<String>>,
+    #[serde(default)]
+    pub min_impact: Option<u8>,
+    #[serde(default)]
+    pub hours_ahead: Option<i64>,
+    /// Also return events that started up to this many minutes ago
+    #[serde(default)]
+    pub include_past_minutes: Option<i64>,
+}
+
+/// Country codes used by `countries` mapped to the currency they move
+pub fn country_to_currency(code: &str) -> String {
+    match code.trim().to_uppercase().as_str() {
+        "US" => "USD",
+        "GB" | "UK" => "GBP",
+        "EU" | "EZ" | "DE" | "FR" | "IT" | "ES" => "EUR",
+        "JP" => "JPY",
+        "CH" => "CHF",
+        "CA" => "CAD",
+        "AU" => "AUD",
+        "NZ" => "NZD",
+        "CN" => "CNY",
+        other => return other.to_string(),
+    }
+    .to_string()
+}
+
+pub fn normalize_impact(value: &str) -> Option<u8> {
+    match value.trim().to_lowercase().as_str() {
+        "3" | "high" | "high impact expected" | "red" => Some(3),
+        "2" | "medium" | "moderate" | "medium impact expected" | "orange" => Some(2),
+        "1" | "low" | "low impact expected" | "yellow" => Some(1),
+        "0" | "holiday" | "none" | "non-economic" | "gray" | "grey" => Some(0),
+        _ => None,
+    }
+}
+
+/// Parse the timestamp shapes calendar feeds use; naive times are taken as UTC
+pub fn parse_event_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    [
+        "%Y.%m.%d %H:%M:%S",
+        "%Y.%m.%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+    .map(|naive| naive.and_utc())
+}
+
+fn field<'a>(item: &'a serde_json::Value, names: &[&str]) -> Option<&'a serde_json::Value> {
+    names.iter().find_map(|name| item.get(*name))
+}
+
+fn field_text(item: &serde_json::Value, names: &[&str]) -> Option<String> {
+    field(item, names).and_then(|v| match v {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// Normalize a calendar API response (a bare array or one wrapped in
+/// `data`/`events`/`result`) into events sorted by time
+pub fn normalize_events(json: &serde_json::Value) -> Vec<NewsEvent> {
+    let items = json
+        .as_array()
+        .or_else(|| field(json, &["data", "events", "result"]).and_then(|v| v.as_array()));
+    let mut events: Vec<NewsEvent> = items
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let time = field_text(item, &["Date", "date", "datetime", "time", "Time"])
+                        .and_then(|t| parse_event_time(&t))?;
+                    let currency =
+                        field_text(item, &["Currency", "currency", "country", "Country"])?
+                            .trim()
+                            .to_uppercase();
+                    let impact = field_text(item, &["Impact", "impact", "Strength", "importance"])
+                        .and_then(|i| normalize_impact(&i))
+                        .unwrap_or(0);
+                    let title = field_text(item, &["Name", "name", "title", "event", "Event"])
+                        .unwrap_or_default();
+                    Some(NewsEvent {
+                        time: time.to_rfc3339(),
+                        currency: if currency.len() == 2 {
+                            country_to_currency(&currency)
+                        } else {
+                            currency
+                        },
+                        impact,
+                        title: title.trim().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    events.sort_by(|a, b| a.time.cmp(&b.time));
+    events
+}
+
+/// Canonical DAAVFX_NEWS.csv content (CRLF, one event per row)
+pub fn render_news_csv(events: &[NewsEvent]) -> String {
+    let mut lines = vec![NEWS_CSV_HEADER.to_string()];
+    for event in events {
+        let time = match parse_event_time(&event.time) {
+            Some(t) => t,
+            None => continue,
+        };
+        // The EA splits on commas and does not understand quoting
+        let title = event.title.replace([',', '"', '\r', '\n'], " ");
+        lines.push(format!(
+            "{},{},{},{},{}",
+            time.format("%Y.%m.%d"),
+            time.format("%H:%M"),
+            event.currency,
+            event.impact,
+            title.trim()
+        ));
+    }
+    lines.join("\r\n") + "\r\n"
+}
+
+fn news_cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(NEWS_CACHE_FILE)
+}
+
+pub(crate) fn load_news_cache() -> Option<NewsCache> {
+    let content = std::fs::read_to_string(news_cache_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub(crate) fn save_news_cache(cache: &NewsCache) -> Result<(), String> {
+    let path = news_cache_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create news cache folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Failed to serialize news cache: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+fn cache_is_fresh(cache: &NewsCache, max_age_secs: i64) -> bool {
+    DateTime::parse_from_rfc3339(&cache.fetched_at)
+        .map(|t| Utc::now() - t.with_timezone(&Utc) < Duration::seconds(max_age_secs))
+        .unwrap_or(false)
+}
+
+async fn fetch_news_events(news: &NewsFilterConfig) -> Result<Vec<NewsEvent>, String> {
+    let url = deobfuscate_string(&news.api_url);
+    if url.trim().is_empty() {
+        return Err("No news API URL configured".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut request = client.get(url.trim());
+    let api_key = deobfuscate_string(&news.api_key);
+    if !api_key.trim().is_empty() {
+        request = request.header("Authorization", format!("Api-Key {}", api_key.trim()));
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch news calendar: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("News API returned HTTP {}", response.status()));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse news calendar: {}", e))?;
+    Ok(normalize_events(&json))
+}
+
+/// Return cached events when they are younger than `cache_duration`, otherwise
+/// fetch, cache and return fresh ones. A failed fetch falls back to the stale
+/// cache when one exists.
+pub(crate) async fn refresh_news_cache(
+    news: &NewsFilterConfig,
+    force: bool,
+) -> Result<NewsCache, String> {
+    let cached = load_news_cache();
+    if !force && news.use_local_cache {
+        if let Some(cache) = &cached {
+            if cache_is_fresh(cache, news.cache_duration.max(60) as i64) {
+                return Ok(cache.clone());
+            }
+        }
+    }
+
+    match fetch_news_events(news).await {
+        Ok(events) => {
+            let cache = NewsCache {
+                fetched_at: Utc::now().to_rfc3339(),
+                source: deobfuscate_string(&news.api_url),
+                events,
+                stale: false,
+            };
+            save_news_cache(&cache)?;
+            Ok(cache)
+        }
+        Err(e) => match cached {
+            Some(mut cache) => {
+                tracing::warn!(error = %e, "news fetch failed, using cached calendar");
+                cache.stale = true;
+                Ok(cache)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+pub fn filter_events(
+    events: &[NewsEvent],
+    news: Option<&NewsFilterConfig>,
+    filters: &NewsFilters,
+    now: DateTime<Utc>,
+) -> Vec<NewsEvent> {
+    let currencies: Vec<String> = filters.currencies.clone().unwrap_or_else(|| {
+        news.map(|n| {
+            let source = if n.filter_currencies.trim().is_empty() {
+                &n.countries
+            } else {
+                &n.filter_currencies
+            };
+            source
+                .split(',')
+                .filter(|c| !c.trim().is_empty())
+                .map(|c| {
+                    let c = c.trim();
+                    if c.len() == 2 {
+                        country_to_currency(c)
+                    } else {
+                        c.to_uppercase()
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    });
+    let min_impact = filters
+        .min_impact
+        .or_else(|| {
+            news.map(|n| {
+                if n.filter_high_only {
+                    3
+                } else {
+                    n.impact_level.clamp(0, 3) as u8
+                }
+            })
+        })
+        .unwrap_or(0);
+    let from = now - Duration::minutes(filters.include_past_minutes.unwrap_or(0).max(0));
+    let until = now + Duration::hours(filters.hours_ahead.unwrap_or(24 * 7).max(0));
+
+    events
+        .iter()
+        .filter(|e| e.impact >= min_impact)
+        .filter(|e| {
+            currencies.is_empty()
+                || currencies
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(&e.currency))
+        })
+        .filter(|e| {
+            parse_event_time(&e.time)
+                .map(|t| t >= from && t <= until)
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Fetch (or reuse cached) calendar events
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn refresh_news_calendar(
+    news_filter: NewsFilterConfig,
+    force: Option<bool>,
+) -> Result<NewsCache, String> {
+    refresh_news_cache(&news_filter, force.unwrap_or(false)).await
+}
+
+/// Upcoming events matching `filters`; refreshes the cache first when a news
+/// config is supplied, otherwise works from the cache alone
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn get_upcoming_news(
+    filters: Option<NewsFilters>,
+    news_filter: Option<NewsFilterConfig>,
+) -> Result<Vec<NewsEvent>, String> {
+    let cache = match &news_filter {
+        Some(news) => refresh_news_cache(news, false).await?,
+        None => load_news_cache()
+            .ok_or_else(|| "No cached news calendar; refresh it first".to_string())?,
+    };
+    Ok(filter_events(
+        &cache.events,
+        news_filter.as_ref(),
+        &filters.unwrap_or_default(),
+        Utc::now(),
+    ))
+}
+
+/// Write the cached calendar as the EA's news CSV into MT Common Files
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn generate_news_csv(news_filter: NewsFilterConfig) -> Result<String, String> {
+    let cache = refresh_news_cache(&news_filter, false).await?;
+    write_news_csv_to_common_files(&cache.events, news_filter.calendar_file.clone())
+}
+
+pub(crate) fn write_news_csv_to_common_files(
+    events: &[NewsEvent],
+    file_name: Option<String>,
+) -> Result<String, String> {
+    crate::safe_mode::ensure_writable("Writing the news calendar")?;
+    let file_name = file_name
+        .filter(|f| !f.trim().is_empty())
+        .unwrap_or_else(|| naming::current().prefixed("NEWS.csv"));
+    if file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("Invalid calendar file name '{}'", file_name));
+    }
+    let common_dir = get_mt_common_files_dir()?;
+    std::fs::create_dir_all(&common_dir)
+        .map_err(|e| format!("Failed to create Common Files folder: {}", e))?;
+    let path = common_dir.join(file_name);
+    atomic_write(&path, &render_news_csv(events))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// ============================================
+// OFFLINE CSV IMPORT
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsCsvIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsCsvImport {
+    pub source_path: String,
+    /// "canonical", "forexfactory" or "investing"
+    pub detected_format: String,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub issues: Vec<NewsCsvIssue>,
+    pub output_path: Option<String>,
+}
+
+/// Split one CSV line, honouring double-quoted fields
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+struct CsvColumns {
+    format: &'static str,
+    date: usize,
+    time: usize,
+    currency: usize,
+    impact: usize,
+    title: usize,
+}
+
+fn detect_columns(header: &[String]) -> Result<CsvColumns, String> {
+    let find = |names: &[&str]| {
+        header.iter().position(|h| {
+            names
+                .iter()
+                .any(|n| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(n))
+        })
+    };
+    let canonical: Vec<&str> = NEWS_CSV_HEADER.split(',').collect();
+    let format = if header.len() >= canonical.len()
+        && header
+            .iter()
+            .zip(&canonical)
+            .all(|(h, c)| h.eq_ignore_ascii_case(c))
+    {
+        "canonical"
+    } else if find(&["Country"]).is_some() && find(&["Title"]).is_some() {
+        "forexfactory"
+    } else {
+        "investing"
+    };
+    let missing = |name: &str| format!("Missing '{}' column in header", name);
+    Ok(CsvColumns {
+        format,
+        date: find(&["Date"]).ok_or_else(|| missing("Date"))?,
+        time: find(&["Time"]).ok_or_else(|| missing("Time"))?,
+        currency: find(&["Currency", "Country", "Cur."]).ok_or_else(|| missing("Currency"))?,
+        impact: find(&["Impact", "Importance", "Volatility"]).ok_or_else(|| missing("Impact"))?,
+        title: find(&["Event", "Title", "Name"]).ok_or_else(|| missing("Event"))?,
+    })
+}
+
+fn parse_csv_date(value: &str) -> Option<chrono::NaiveDate> {
+    ["%Y.%m.%d", "%Y-%m-%d", "%m-%d-%Y", "%m/%d/%Y", "%d.%m.%Y"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDate::parse_from_str(value.trim(), fmt).ok())
+}
+
+fn parse_csv_time(value: &str) -> Option<chrono::NaiveTime> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("all day") {
+        return chrono::NaiveTime::from_hms_opt(0, 0, 0);
+    }
+    ["%H:%M", "%H:%M:%S", "%I:%M%p", "%I:%M %p"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveTime::parse_from_str(value, fmt).ok())
+}
+
+/// Validate a downloaded calendar CSV and normalize it to events. Source
+/// times are shifted by `utc_offset_minutes` (the export's time zone) to UTC.
+pub fn parse_news_csv(
+    content: &str,
+    utc_offset_minutes: i32,
+) -> Result<(String, usize, Vec<NewsEvent>, Vec<NewsCsvIssue>), String> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| "CSV file is empty".to_string())?;
+    let columns = detect_columns(&split_csv_line(header))?;
+    let width = [
+        columns.date,
+        columns.time,
+        columns.currency,
+        columns.impact,
+        columns.title,
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(0)
+        + 1;
+
+    let mut total_rows = 0;
+    let mut events = Vec::new();
+    let mut issues = Vec::new();
+    for (index, line) in lines {
+        total_rows += 1;
+        let line_no = index + 1;
+        let fields = split_csv_line(line);
+        if fields.len() < width {
+            issues.push(NewsCsvIssue {
+                line: line_no,
+                message: format!(
+                    "Expected at least {} columns, found {}",
+                    width,
+                    fields.len()
+                ),
+            });
+            continue;
+        }
+        let date = match parse_csv_date(&fields[columns.date]) {
+            Some(d) => d,
+            None => {
+                issues.push(NewsCsvIssue {
+                    line: line_no,
+                    message: format!("Unrecognized date '{}'", fields[columns.date]),
+                });
+                continue;
+            }
+        };
+        let time = match parse_csv_time(&fields[columns.time]) {
+            Some(t) => t,
+            None => {
+                issues.push(NewsCsvIssue {
+                    line: line_no,
+                    message: format!("Unrecognized time '{}'", fields[columns.time]),
+                });
+                continue;
+            }
+        };
+        let impact = match normalize_impact(&fields[columns.impact]) {
+            Some(i) => i,
+            None => {
+                issues.push(NewsCsvIssue {
+                    line: line_no,
+                    message: format!("Unknown impact '{}'", fields[columns.impact]),
+                });
+                continue;
+            }
+        };
+        let currency = fields[columns.currency].to_uppercase();
+        if currency.is_empty() {
+            issues.push(NewsCsvIssue {
+                line: line_no,
+                message: "Missing currency".to_string(),
+            });
+            continue;
+        }
+        let utc = date.and_time(time).and_utc() - Duration::minutes(utc_offset_minutes as i64);
+        events.push(NewsEvent {
+            time: utc.to_rfc3339(),
+            currency: if currency.len() == 2 {
+                country_to_currency(&currency)
+            } else {
+                currency
+            },
+            impact,
+            title: fields[columns.title].clone(),
+        });
+    }
+    events.sort_by(|a, b| a.time.cmp(&b.time));
+    Ok((columns.format.to_string(), total_rows, events, issues))
+}
+
+/// Import a hand-downloaded ForexFactory/Investing CSV, report malformed rows
+/// and write the canonical calendar into Common Files (also refreshing the
+/// local news cache). Nothing is written when no row is valid.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn import_news_csv(
+    path: String,
+    utc_offset_minutes: Option<i32>,
+    file_name: Option<String>,
+) -> Result<NewsCsvImport, String> {
+    let source = sanitize_and_validate_path(&PathBuf::from(&path))?;
+    let bytes = std::fs::read(&source).map_err(|e| format!("Failed to read news CSV: {}", e))?;
+    let content = decode_text(&bytes);
+    let (detected_format, total_rows, events, issues) =
+        parse_news_csv(&content, utc_offset_minutes.unwrap_or(0))?;
+
+    let output_path = if events.is_empty() {
+        None
+    } else {
+        save_news_cache(&NewsCache {
+            fetched_at: Utc::now().to_rfc3339(),
+            source: format!("csv:{}", source.to_string_lossy()),
+            events: events.clone(),
+            stale: false,
+        })?;
+        Some(write_news_csv_to_common_files(&events, file_name)?)
+    };
+
+    Ok(NewsCsvImport {
+        source_path: source.to_string_lossy().to_string(),
+        detected_format,
+        total_rows,
+        imported: events.len(),
+        issues,
+        output_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_events_and_render_csv() {
+        let json = serde_json::json!([
+            {"Name": "Non-Farm Payrolls, m/m", "Currency": "USD", "Date": "2026.03.06 13:30:00", "Impact": "High"},
+            {"Name": "CPI y/y", "Currency": "eur", "Date": "2026-03-05T10:00:00Z", "Impact": "Medium"},
+            {"Name": "Broken", "Currency": "USD", "Date": "not a date", "Impact": "High"}
+        ]);
+        let events = normalize_events(&json);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].currency, "EUR");
+        assert_eq!(events[0].impact, 2);
+        assert_eq!(events[1].impact, 3);
+
+        let csv = render_news_csv(&events);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], NEWS_CSV_HEADER);
+        assert_eq!(lines[1], "2026.03.05,10:00,EUR,2,CPI y/y");
+        assert_eq!(lines[2], "2026.03.06,13:30,USD,3,Non-Farm Payrolls  m/m");
+    }
+
+    #[test]
+    fn test_filter_events_by_currency_impact_and_window() {
+        let now = parse_event_time("2026.03.05 09:00").unwrap();
+        let events = normalize_events(&serde_json::json!({"data": [
+            {"name": "A", "currency": "USD", "date": "2026.03.05 10:00", "impact": "3"},
+            {"name": "B", "currency": "JPY", "date": "2026.03.05 11:00", "impact": "3"},
+            {"name": "C", "currency": "USD", "date": "2026.03.05 12:00", "impact": "1"},
+            {"name": "D", "currency": "USD", "date": "2026.03.20 12:00", "impact": "3"}
+        ]}));
+        let filters = NewsFilters {
+            currencies: Some(vec!["usd".to_string()]),
+            min_impact: Some(2),
+            hours_ahead: Some(48),
+            include_past_minutes: None,
+        };
+        let upcoming = filter_events(&events, None, &filters, now);
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].title, "A");
+    }
+
+    #[test]
+    fn test_parse_news_csv_forexfactory_with_issues() {
+        let csv = "Title,Country,Date,Time,Impact,Forecast,Previous\r\n\
+\"Non-Farm Employment Change, Prelim\",USD,03-06-2026,8:30am,High,150K,143K\r\n\
+Bank Holiday,JPY,03-05-2026,All Day,Holiday,,\r\n\
+German CPI,EUR,03-05-2026,Tentative,Medium,,\r\n\
+Retail Sales,GBP,03-05-2026,7:00am,Severe,,\r\n";
+        // Export in New York time (UTC-5)
+        let (format, total, events, issues) = parse_news_csv(csv, -300).unwrap();
+        assert_eq!(format, "forexfactory");
+        assert_eq!(total, 4);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].currency, "JPY");
+        assert_eq!(events[0].impact, 0);
+        assert_eq!(events[1].time, "2026-03-06T13:30:00+00:00");
+        assert_eq!(events[1].title, "Non-Farm Employment Change, Prelim");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].line, 4);
+        assert!(issues[1].message.contains("Severe"));
+
+        let canonical = render_news_csv(&events);
+        let (format, _, reparsed, issues) = parse_news_csv(&canonical, 0).unwrap();
+        assert_eq!(format, "canonical");
+        assert!(issues.is_empty());
+        assert_eq!(reparsed[1].time, events[1].time);
+    }
+}
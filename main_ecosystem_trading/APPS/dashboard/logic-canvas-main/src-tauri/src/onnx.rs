@@ -0,0 +1,696 @@
+//! Minimal ONNX reader/writer for the chat models
+//!
+//! Only the parts of the ONNX protobuf schema the dashboard needs are
+//! encoded: a single graph with nodes, float/int64 initializers, typed
+//! inputs/outputs and model metadata. Reading skips everything else, so
+//! files written by the Python `onnx` package load as long as they use the
+//! initializer names documented on each export function.
+//!
+//! The chat intent network is exported as a complete graph (token ids in,
+//! intent probabilities out). The transformer exports every weight as an
+//! initializer but only its classification head as nodes.
+
+use std::collections::BTreeMap;
+
+use crate::chat_neural::{Intent, ModelSnapshot, TinyNeural};
+use crate::subword_tokenizer::SubwordTokenizer;
+use crate::trading_transformer::{TradingTransformer, TransformerIntent};
+
+const IR_VERSION: i64 = 8;
+const OPSET_VERSION: i64 = 13;
+const PRODUCER: &str = "daavfx";
+
+// TensorProto.DataType
+const FLOAT: i32 = 1;
+const INT64: i32 = 7;
+// AttributeProto.AttributeType
+const ATTR_INT: i64 = 2;
+
+/// Metadata keys
+pub const META_MODEL: &str = "daavfx.model";
+pub const META_INTENTS: &str = "daavfx.intents";
+pub const META_VOCAB: &str = "daavfx.vocab";
+pub const META_CORRECTIONS: &str = "daavfx.corrections";
+pub const META_GRAPH: &str = "daavfx.graph";
+pub const CHAT_MODEL_KIND: &str = "chat_intent";
+pub const TRANSFORMER_MODEL_KIND: &str = "transformer";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TensorData {
+    Float(Vec<f32>),
+    Int64(Vec<i64>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor {
+    pub name: String,
+    pub dims: Vec<i64>,
+    pub data: TensorData,
+}
+
+impl Tensor {
+    pub fn float(name: &str, dims: &[usize], data: Vec<f32>) -> Self {
+        Tensor {
+            name: name.to_string(),
+            dims: dims.iter().map(|&d| d as i64).collect(),
+            data: TensorData::Float(data),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.dims.iter().product::<i64>().max(0) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub op_type: String,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    /// Integer attributes only; that's all the exported ops need
+    pub attributes: Vec<(String, i64)>,
+}
+
+fn node(op_type: &str, inputs: &[&str], output: &str) -> Node {
+    Node {
+        op_type: op_type.to_string(),
+        inputs: inputs.iter().map(|s| s.to_string()).collect(),
+        outputs: vec![output.to_string()],
+        attributes: Vec::new(),
+    }
+}
+
+/// Graph input or output; `None` dims are symbolic ("batch")
+#[derive(Debug, Clone)]
+pub struct ValueInfo {
+    pub name: String,
+    pub elem_type: i32,
+    pub dims: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OnnxModel {
+    pub producer: String,
+    pub graph_name: String,
+    pub nodes: Vec<Node>,
+    pub initializers: Vec<Tensor>,
+    pub inputs: Vec<ValueInfo>,
+    pub outputs: Vec<ValueInfo>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+// ---------------------------------------------------------------------------
+// Protobuf wire format
+// ---------------------------------------------------------------------------
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    put_varint(buf, ((field as u64) << 3) | wire_type as u64);
+}
+
+fn put_int(buf: &mut Vec<u8>, field: u32, value: i64) {
+    put_key(buf, field, 0);
+    put_varint(buf, value as u64);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    put_key(buf, field, 2);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn put_str(buf: &mut Vec<u8>, field: u32, value: &str) {
+    put_bytes(buf, field, value.as_bytes());
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed32([u8; 4]),
+    Fixed64,
+}
+
+/// Iterate the (field number, value) pairs of one message
+fn fields(mut buf: &[u8]) -> impl Iterator<Item = Result<(u32, Field<'_>), String>> {
+    fn varint(buf: &mut &[u8]) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = buf
+                .split_first()
+                .ok_or_else(|| "Truncated ONNX file".to_string())?;
+            *buf = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Malformed varint in ONNX file".to_string())
+    }
+    fn take<'a>(buf: &mut &'a [u8], n: usize) -> Result<&'a [u8], String> {
+        if buf.len() < n {
+            return Err("Truncated ONNX file".to_string());
+        }
+        let (head, rest) = buf.split_at(n);
+        *buf = rest;
+        Ok(head)
+    }
+
+    std::iter::from_fn(move || {
+        if buf.is_empty() {
+            return None;
+        }
+        let next = (|| {
+            let key = varint(&mut buf)?;
+            let field = (key >> 3) as u32;
+            let value = match key & 7 {
+                0 => Field::Varint(varint(&mut buf)?),
+                1 => {
+                    take(&mut buf, 8)?;
+                    Field::Fixed64
+                }
+                2 => {
+                    let len = varint(&mut buf)? as usize;
+                    Field::Bytes(take(&mut buf, len)?)
+                }
+                5 => Field::Fixed32(take(&mut buf, 4)?.try_into().unwrap()),
+                other => return Err(format!("Unsupported protobuf wire type {}", other)),
+            };
+            Ok((field, value))
+        })();
+        if next.is_err() {
+            buf = &[];
+        }
+        Some(next)
+    })
+}
+
+fn as_string(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| "Invalid UTF-8 in ONNX file".to_string())
+}
+
+/// Values of a repeated scalar field, packed or not
+fn push_varints(out: &mut Vec<i64>, value: Field<'_>) -> Result<(), String> {
+    match value {
+        Field::Varint(v) => out.push(v as i64),
+        Field::Bytes(packed) => {
+            let mut rest = packed;
+            while !rest.is_empty() {
+                let mut shift = 0;
+                let mut v = 0u64;
+                loop {
+                    let (&byte, tail) = rest
+                        .split_first()
+                        .ok_or_else(|| "Truncated packed field".to_string())?;
+                    rest = tail;
+                    v |= ((byte & 0x7f) as u64) << shift;
+                    shift += 7;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                out.push(v as i64);
+            }
+        }
+        _ => return Err("Unexpected wire type for integer field".to_string()),
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ONNX messages
+// ---------------------------------------------------------------------------
+
+fn encode_tensor(tensor: &Tensor) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for &dim in &tensor.dims {
+        put_int(&mut buf, 1, dim);
+    }
+    let (data_type, raw): (i32, Vec<u8>) = match &tensor.data {
+        TensorData::Float(values) => (FLOAT, values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+        TensorData::Int64(values) => (INT64, values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+    };
+    put_int(&mut buf, 2, data_type as i64);
+    put_str(&mut buf, 8, &tensor.name);
+    put_bytes(&mut buf, 9, &raw);
+    buf
+}
+
+fn decode_tensor(bytes: &[u8]) -> Result<Tensor, String> {
+    let mut dims = Vec::new();
+    let mut data_type = 0;
+    let mut name = String::new();
+    let mut raw: Option<&[u8]> = None;
+    let mut floats = Vec::new();
+    let mut ints = Vec::new();
+    for field in fields(bytes) {
+        match field? {
+            (1, value) => push_varints(&mut dims, value)?,
+            (2, Field::Varint(v)) => data_type = v as i32,
+            (4, Field::Fixed32(b)) => floats.push(f32::from_le_bytes(b)),
+            (4, Field::Bytes(packed)) => floats.extend(
+                packed
+                    .chunks_exact(4)
+                    .map(|c| f32::from_le_bytes(c.try_into().unwrap())),
+            ),
+            (7, value) => push_varints(&mut ints, value)?,
+            (8, Field::Bytes(b)) => name = as_string(b)?,
+            (9, Field::Bytes(b)) => raw = Some(b),
+            _ => {}
+        }
+    }
+    let data = match (data_type, raw) {
+        (FLOAT, Some(raw)) => TensorData::Float(
+            raw.chunks_exact(4)
+                .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+                .collect(),
+        ),
+        (FLOAT, None) => TensorData::Float(floats),
+        (INT64, Some(raw)) => TensorData::Int64(
+            raw.chunks_exact(8)
+                .map(|c| i64::from_le_bytes(c.try_into().unwrap()))
+                .collect(),
+        ),
+        (INT64, None) => TensorData::Int64(ints),
+        (other, _) => {
+            return Err(format!(
+                "Tensor '{}' has unsupported data type {}",
+                name, other
+            ))
+        }
+    };
+    let tensor = Tensor { name, dims, data };
+    let len = match &tensor.data {
+        TensorData::Float(v) => v.len(),
+        TensorData::Int64(v) => v.len(),
+    };
+    if len != tensor.len() {
+        return Err(format!(
+            "Tensor '{}' has {} values but shape {:?}",
+            tensor.name, len, tensor.dims
+        ));
+    }
+    Ok(tensor)
+}
+
+fn encode_node(node: &Node) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for input in &node.inputs {
+        put_str(&mut buf, 1, input);
+    }
+    for output in &node.outputs {
+        put_str(&mut buf, 2, output);
+    }
+    put_str(&mut buf, 3, &node.outputs[0]);
+    put_str(&mut buf, 4, &node.op_type);
+    for (name, value) in &node.attributes {
+        let mut attr = Vec::new();
+        put_str(&mut attr, 1, name);
+        put_int(&mut attr, 3, *value);
+        put_int(&mut attr, 20, ATTR_INT);
+        put_bytes(&mut buf, 5, &attr);
+    }
+    buf
+}
+
+fn encode_value_info(info: &ValueInfo) -> Vec<u8> {
+    let mut shape = Vec::new();
+    for dim in &info.dims {
+        let mut d = Vec::new();
+        match dim {
+            Some(value) => put_int(&mut d, 1, *value),
+            None => put_str(&mut d, 2, "batch"),
+        }
+        put_bytes(&mut shape, 1, &d);
+    }
+    let mut tensor_type = Vec::new();
+    put_int(&mut tensor_type, 1, info.elem_type as i64);
+    put_bytes(&mut tensor_type, 2, &shape);
+    let mut type_proto = Vec::new();
+    put_bytes(&mut type_proto, 1, &tensor_type);
+
+    let mut buf = Vec::new();
+    put_str(&mut buf, 1, &info.name);
+    put_bytes(&mut buf, 2, &type_proto);
+    buf
+}
+
+impl OnnxModel {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut graph = Vec::new();
+        for node in &self.nodes {
+            put_bytes(&mut graph, 1, &encode_node(node));
+        }
+        put_str(&mut graph, 2, &self.graph_name);
+        for tensor in &self.initializers {
+            put_bytes(&mut graph, 5, &encode_tensor(tensor));
+        }
+        for input in &self.inputs {
+            put_bytes(&mut graph, 11, &encode_value_info(input));
+        }
+        for output in &self.outputs {
+            put_bytes(&mut graph, 12, &encode_value_info(output));
+        }
+
+        let mut opset = Vec::new();
+        put_str(&mut opset, 1, "");
+        put_int(&mut opset, 2, OPSET_VERSION);
+
+        let mut model = Vec::new();
+        put_int(&mut model, 1, IR_VERSION);
+        put_str(&mut model, 2, &self.producer);
+        put_str(&mut model, 3, env!("CARGO_PKG_VERSION"));
+        put_bytes(&mut model, 7, &graph);
+        put_bytes(&mut model, 8, &opset);
+        for (key, value) in &self.metadata {
+            let mut entry = Vec::new();
+            put_str(&mut entry, 1, key);
+            put_str(&mut entry, 2, value);
+            put_bytes(&mut model, 14, &entry);
+        }
+        model
+    }
+
+    /// Parse the producer, graph name, nodes' op types, initializers and
+    /// metadata; inputs/outputs are not needed for import and are skipped
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut model = OnnxModel::default();
+        for field in fields(bytes) {
+            match field? {
+                (2, Field::Bytes(b)) => model.producer = as_string(b)?,
+                (7, Field::Bytes(graph)) => {
+                    for field in fields(graph) {
+                        match field? {
+                            (1, Field::Bytes(n)) => {
+                                let mut parsed = Node {
+                                    op_type: String::new(),
+                                    inputs: Vec::new(),
+                                    outputs: Vec::new(),
+                                    attributes: Vec::new(),
+                                };
+                                for field in fields(n) {
+                                    match field? {
+                                        (1, Field::Bytes(s)) => parsed.inputs.push(as_string(s)?),
+                                        (2, Field::Bytes(s)) => parsed.outputs.push(as_string(s)?),
+                                        (4, Field::Bytes(s)) => parsed.op_type = as_string(s)?,
+                                        _ => {}
+                                    }
+                                }
+                                model.nodes.push(parsed);
+                            }
+                            (2, Field::Bytes(s)) => model.graph_name = as_string(s)?,
+                            (5, Field::Bytes(t)) => model.initializers.push(decode_tensor(t)?),
+                            _ => {}
+                        }
+                    }
+                }
+                (14, Field::Bytes(entry)) => {
+                    let (mut key, mut value) = (String::new(), String::new());
+                    for field in fields(entry) {
+                        match field? {
+                            (1, Field::Bytes(s)) => key = as_string(s)?,
+                            (2, Field::Bytes(s)) => value = as_string(s)?,
+                            _ => {}
+                        }
+                    }
+                    model.metadata.insert(key, value);
+                }
+                _ => {}
+            }
+        }
+        if model.initializers.is_empty() {
+            return Err("ONNX file has no initializers (weights)".to_string());
+        }
+        Ok(model)
+    }
+
+    /// Float values of an initializer, checked against the expected shape
+    pub fn weights(&self, name: &str, dims: &[usize]) -> Result<Vec<f32>, String> {
+        let tensor = self
+            .initializers
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| format!("ONNX model has no initializer '{}'", name))?;
+        let expected: Vec<i64> = dims.iter().map(|&d| d as i64).collect();
+        if tensor.dims != expected {
+            return Err(format!(
+                "Initializer '{}' has shape {:?}, expected {:?}",
+                name, tensor.dims, expected
+            ));
+        }
+        match &tensor.data {
+            TensorData::Float(values) => Ok(values.clone()),
+            TensorData::Int64(_) => Err(format!("Initializer '{}' is not float", name)),
+        }
+    }
+
+    fn check_kind(&self, kind: &str) -> Result<(), String> {
+        match self.metadata.get(META_MODEL) {
+            Some(found) if found != kind => Err(format!(
+                "ONNX file holds a '{}' model, not '{}'",
+                found, kind
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Chat intent network
+// ---------------------------------------------------------------------------
+
+/// Export the chat network. Initializers: `embeddings` [vocab, embed],
+/// `fc1.weight` [embed, hidden], `fc1.bias`, `fc2.weight` [hidden, hidden],
+/// `fc2.bias`, `output.weight` [hidden, intents], `output.bias`. Input
+/// `tokens` is int64 [batch, seq] character ids (see `daavfx.vocab`),
+/// output `probabilities` is [batch, intents].
+pub fn export_chat_model(net: &TinyNeural, trained: bool) -> OnnxModel {
+    let s = net.to_snapshot(trained);
+    let (v, e, h, n) = (s.vocab_size, s.embed_dim, s.hidden_dim, s.num_intents);
+    let intents: Vec<&str> = (0..n).map(|i| Intent::from_index(i).as_str()).collect();
+    let mut initializers = vec![
+        Tensor::float("embeddings", &[v, e], s.embeddings.clone()),
+        // The forward pass only reads the first embed_dim rows of fc1
+        Tensor::float("fc1.weight", &[e, h], s.fc1_weights[..e * h].to_vec()),
+        Tensor::float("fc1.bias", &[h], s.fc1_bias.clone()),
+        Tensor::float("fc2.weight", &[h, h], s.fc2_weights.clone()),
+        Tensor::float("fc2.bias", &[h], s.fc2_bias.clone()),
+        Tensor::float("output.weight", &[h, n], s.output_weights.clone()),
+        Tensor::float("output.bias", &[n], s.output_bias.clone()),
+    ];
+    initializers.push(Tensor {
+        name: "sum_axes".to_string(),
+        dims: vec![1],
+        data: TensorData::Int64(vec![1]),
+    });
+
+    let mut reduce = node("ReduceSum", &["embedded", "sum_axes"], "pooled");
+    reduce.attributes.push(("keepdims".to_string(), 0));
+    let mut gather = node("Gather", &["embeddings", "tokens"], "embedded");
+    gather.attributes.push(("axis".to_string(), 0));
+    let mut softmax = node("Softmax", &["logits"], "probabilities");
+    softmax.attributes.push(("axis".to_string(), -1));
+    let nodes = vec![
+        gather,
+        reduce,
+        node("MatMul", &["pooled", "fc1.weight"], "fc1_mm"),
+        node("Add", &["fc1_mm", "fc1.bias"], "fc1_out"),
+        node("Relu", &["fc1_out"], "hidden1"),
+        node("MatMul", &["hidden1", "fc2.weight"], "fc2_mm"),
+        node("Add", &["fc2_mm", "fc2.bias"], "fc2_out"),
+        node("Relu", &["fc2_out"], "hidden2"),
+        node("MatMul", &["hidden2", "output.weight"], "out_mm"),
+        node("Add", &["out_mm", "output.bias"], "logits"),
+        softmax,
+    ];
+
+    let mut metadata = BTreeMap::new();
+    metadata.insert(META_MODEL.to_string(), CHAT_MODEL_KIND.to_string());
+    metadata.insert(META_INTENTS.to_string(), json(&intents));
+    metadata.insert(META_VOCAB.to_string(), net.vocab_chars());
+    metadata.insert(META_CORRECTIONS.to_string(), json(&s.corrections));
+    metadata.insert("daavfx.trained".to_string(), trained.to_string());
+
+    OnnxModel {
+        producer: PRODUCER.to_string(),
+        graph_name: "daavfx_chat_intent".to_string(),
+        nodes,
+        initializers,
+        inputs: vec![ValueInfo {
+            name: "tokens".to_string(),
+            elem_type: INT64,
+            dims: vec![None, Some(s.max_seq_len as i64)],
+        }],
+        outputs: vec![ValueInfo {
+            name: "probabilities".to_string(),
+            elem_type: FLOAT,
+            dims: vec![None, Some(n as i64)],
+        }],
+        metadata,
+    }
+}
+
+/// Rebuild the chat network from an ONNX file with the export's layout;
+/// `template` supplies the parts ONNX doesn't carry (unused fc1 rows)
+pub fn import_chat_model(model: &OnnxModel, template: &TinyNeural) -> Result<TinyNeural, String> {
+    model.check_kind(CHAT_MODEL_KIND)?;
+    let mut s: ModelSnapshot = template.to_snapshot(true);
+    let (v, e, h, n) = (s.vocab_size, s.embed_dim, s.hidden_dim, s.num_intents);
+    s.embeddings = model.weights("embeddings", &[v, e])?;
+    let fc1 = model.weights("fc1.weight", &[e, h])?;
+    s.fc1_weights[..e * h].copy_from_slice(&fc1);
+    s.fc1_bias = model.weights("fc1.bias", &[h])?;
+    s.fc2_weights = model.weights("fc2.weight", &[h, h])?;
+    s.fc2_bias = model.weights("fc2.bias", &[h])?;
+    s.output_weights = model.weights("output.weight", &[h, n])?;
+    s.output_bias = model.weights("output.bias", &[n])?;
+    s.corrections = model
+        .metadata
+        .get(META_CORRECTIONS)
+        .and_then(|c| serde_json::from_str(c).ok())
+        .unwrap_or_default();
+    TinyNeural::from_snapshot(s)
+}
+
+// ---------------------------------------------------------------------------
+// Transformer
+// ---------------------------------------------------------------------------
+
+/// Export the transformer. Every parameter is an initializer named as in
+/// `TradingTransformer::parameters`; the nodes compute only the
+/// classification head (`pooled` [batch, embed] -> `probabilities`).
+pub fn export_transformer(tf: &TradingTransformer) -> OnnxModel {
+    let params = tf.parameters();
+    let embed = params
+        .iter()
+        .find(|(name, _, _)| name == "output_proj")
+        .map_or(0, |(_, dims, _)| dims[0]);
+    let classes = params
+        .iter()
+        .find(|(name, _, _)| name == "output_bias")
+        .map_or(0, |(_, dims, _)| dims[0]);
+    let intents: Vec<&str> = (0..classes)
+        .map(|i| TransformerIntent::from_index(i).as_str())
+        .collect();
+    let initializers = params
+        .into_iter()
+        .map(|(name, dims, data)| Tensor::float(&name, &dims, data))
+        .collect();
+
+    let mut softmax = node("Softmax", &["logits"], "probabilities");
+    softmax.attributes.push(("axis".to_string(), -1));
+    let nodes = vec![
+        node("MatMul", &["pooled", "output_proj"], "head_mm"),
+        node("Add", &["head_mm", "output_bias"], "logits"),
+        softmax,
+    ];
+
+    let mut metadata = BTreeMap::new();
+    metadata.insert(META_MODEL.to_string(), TRANSFORMER_MODEL_KIND.to_string());
+    metadata.insert(META_INTENTS.to_string(), json(&intents));
+    metadata.insert(META_VOCAB.to_string(), json(&tf.tokenizer().vocab()));
+    metadata.insert(
+        META_GRAPH.to_string(),
+        "classification head only; encoder weights are stored as initializers".to_string(),
+    );
+
+    OnnxModel {
+        producer: PRODUCER.to_string(),
+        graph_name: "daavfx_trading_transformer".to_string(),
+        nodes,
+        initializers,
+        inputs: vec![ValueInfo {
+            name: "pooled".to_string(),
+            elem_type: FLOAT,
+            dims: vec![None, Some(embed as i64)],
+        }],
+        outputs: vec![ValueInfo {
+            name: "probabilities".to_string(),
+            elem_type: FLOAT,
+            dims: vec![None, Some(classes as i64)],
+        }],
+        metadata,
+    }
+}
+
+/// Load transformer weights (and its tokenizer vocabulary, when present)
+pub fn import_transformer(model: &OnnxModel, tf: &mut TradingTransformer) -> Result<(), String> {
+    model.check_kind(TRANSFORMER_MODEL_KIND)?;
+    let tokenizer = match model.metadata.get(META_VOCAB) {
+        Some(vocab) => {
+            let tokens: Vec<String> = serde_json::from_str(vocab)
+                .map_err(|e| format!("Invalid tokenizer vocabulary in ONNX file: {}", e))?;
+            Some(SubwordTokenizer::from_vocab(tokens)?)
+        }
+        None => None,
+    };
+    let mut weights = Vec::new();
+    for (name, dims, _) in tf.parameters() {
+        weights.push((name.clone(), model.weights(&name, &dims)?));
+    }
+    if let Some(tokenizer) = tokenizer {
+        tf.set_tokenizer(tokenizer)?;
+    }
+    tf.set_parameters(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protobuf_round_trip() {
+        let mut model = OnnxModel {
+            producer: PRODUCER.to_string(),
+            graph_name: "g".to_string(),
+            ..OnnxModel::default()
+        };
+        model
+            .initializers
+            .push(Tensor::float("w", &[2, 2], vec![1.0, -2.5, 3.0, 0.0]));
+        model.initializers.push(Tensor {
+            name: "axes".to_string(),
+            dims: vec![1],
+            data: TensorData::Int64(vec![-1]),
+        });
+        model.nodes.push(node("Relu", &["x"], "y"));
+        model
+            .metadata
+            .insert(META_MODEL.to_string(), "test".to_string());
+
+        let parsed = OnnxModel::from_bytes(&model.to_bytes()).unwrap();
+        assert_eq!(parsed.producer, PRODUCER);
+        assert_eq!(parsed.initializers, model.initializers);
+        assert_eq!(parsed.nodes[0].op_type, "Relu");
+        assert_eq!(
+            parsed.metadata.get(META_MODEL).map(String::as_str),
+            Some("test")
+        );
+        assert_eq!(parsed.weights("w", &[2, 2]).unwrap()[1], -2.5);
+        assert!(parsed.weights("w", &[4]).is_err());
+        assert!(parsed.check_kind(CHAT_MODEL_KIND).is_err());
+        assert!(OnnxModel::from_bytes(&[0x0a, 0x05, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_chat_model_round_trip() {
+        let net = TinyNeural::new();
+        let exported = export_chat_model(&net, true);
+        let parsed = OnnxModel::from_bytes(&exported.to_bytes()).unwrap();
+        let imported = import_chat_model(&parsed, &TinyNeural::new()).unwrap();
+        assert_eq!(
+            imported.forward("set grid to 500"),
+            net.forward("set grid to 500")
+        );
+    }
+}
@@ -0,0 +1,428 @@
+// ============================================
+// MT5 OPTIMIZATION RESULTS
+// ============================================
+//
+// Reads the Strategy Tester optimizer export (Excel 2003 XML, which is what
+// "Export to XML" produces) into ranked passes. Input columns use the
+// exporter's gInput_* key names, so every pass is mapped back onto MTConfig
+// field paths and can be applied to a config to load it as a preset.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{
+    apply_v19_global_keys, apply_v19_logic_inputs, apply_v19_param_to_logic,
+    create_full_v19_config, logic_code_to_name, normalize_config_mode_contract, parse_v19_key,
+    sanitize_and_validate_path, LogicConfig, MTConfig,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationFieldMapping {
+    pub key: String,
+    /// MTConfig path, e.g. engines[A].groups[1].logics[POWER].initial_lot_b
+    pub field: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationPass {
+    pub rank: usize,
+    pub pass: u32,
+    pub result: f64,
+    pub profit: f64,
+    pub expected_payoff: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub recovery_factor: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub custom: Option<f64>,
+    pub drawdown_percent: Option<f64>,
+    pub trades: Option<u32>,
+    pub parameters: BTreeMap<String, String>,
+    pub mapped_fields: Vec<OptimizationFieldMapping>,
+    pub unmapped_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationResults {
+    pub source_path: String,
+    pub sort_by: String,
+    pub passes: Vec<OptimizationPass>,
+}
+
+/// Optimizer columns that are metrics rather than EA inputs
+const METRIC_COLUMNS: [&str; 13] = [
+    "pass",
+    "result",
+    "profit",
+    "expected payoff",
+    "profit factor",
+    "recovery factor",
+    "sharpe ratio",
+    "custom",
+    "equity dd %",
+    "trades",
+    "back result",
+    "forward result",
+    "balance dd %",
+];
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Rows of cell text from a SpreadsheetML document
+fn parse_spreadsheet_rows(xml: &str) -> Vec<Vec<String>> {
+    let row_regex = Regex::new(r"(?s)<Row[^>]*>(.*?)</Row>").unwrap();
+    let cell_regex = Regex::new(r"(?s)<Cell[^>]*?(?:/>|>(.*?)</Cell>)").unwrap();
+    let data_regex = Regex::new(r"(?s)<Data[^>]*>(.*?)</Data>").unwrap();
+
+    row_regex
+        .captures_iter(xml)
+        .map(|row| {
+            cell_regex
+                .captures_iter(&row[1])
+                .map(|cell| {
+                    cell.get(1)
+                        .and_then(|inner| data_regex.captures(inner.as_str()))
+                        .map(|data| unescape_xml(data[1].trim()))
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .filter(|cells: &Vec<String>| !cells.is_empty())
+        .collect()
+}
+
+fn parse_metric(value: Option<&String>) -> Option<f64> {
+    value.and_then(|v| v.trim().replace(',', "").parse::<f64>().ok())
+}
+
+/// Finds which MTConfig field a v19 parameter writes by applying two probe values
+/// through the importer and diffing the serialized logic. Cached per parameter.
+struct FieldResolver {
+    logic_template: LogicConfig,
+    config_template: MTConfig,
+    cache: HashMap<(String, bool), Option<String>>,
+}
+
+impl FieldResolver {
+    fn new() -> Self {
+        let template = create_full_v19_config();
+        Self {
+            logic_template: template.engines[0].groups[0].logics[0].clone(),
+            config_template: template,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn changed_fields(before: &serde_json::Value, after: &serde_json::Value) -> Vec<String> {
+        match (before.as_object(), after.as_object()) {
+            (Some(a), Some(b)) => b
+                .iter()
+                .filter(|(k, v)| a.get(*k) != Some(*v))
+                .map(|(k, _)| k.clone())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn logic_field(&mut self, param: &str, is_buy: bool) -> Option<String> {
+        let cache_key = (param.to_ascii_lowercase(), is_buy);
+        if let Some(cached) = self.cache.get(&cache_key) {
+            return cached.clone();
+        }
+        let mut resolved = None;
+        for (first, second) in [("1", "2"), ("0", "1")] {
+            let mut a = self.logic_template.clone();
+            let mut b = self.logic_template.clone();
+            apply_v19_param_to_logic(&mut a, is_buy, param, first);
+            apply_v19_param_to_logic(&mut b, is_buy, param, second);
+            let a = serde_json::to_value(&a).unwrap_or_default();
+            let b = serde_json::to_value(&b).unwrap_or_default();
+            // Directional params may also touch the shared field; prefer the _b/_s one
+            let suffix = if is_buy { "_b" } else { "_s" };
+            let changed = Self::changed_fields(&a, &b);
+            if let Some(field) = changed
+                .iter()
+                .find(|f| f.ends_with(suffix))
+                .or_else(|| changed.first())
+            {
+                resolved = Some(field.clone());
+                break;
+            }
+        }
+        self.cache.insert(cache_key, resolved.clone());
+        resolved
+    }
+
+    fn general_field(&mut self, key: &str) -> Option<String> {
+        let cache_key = (key.to_string(), false);
+        if let Some(cached) = self.cache.get(&cache_key) {
+            return cached.clone();
+        }
+        let mut resolved = None;
+        for (first, second) in [("1", "2"), ("0", "1")] {
+            let mut config_a = self.config_template.clone();
+            let mut config_b = self.config_template.clone();
+            apply_v19_global_keys(
+                &mut config_a,
+                &HashMap::from([(key.to_string(), first.to_string())]),
+            );
+            apply_v19_global_keys(
+                &mut config_b,
+                &HashMap::from([(key.to_string(), second.to_string())]),
+            );
+            let a = serde_json::to_value(&config_a.general).unwrap_or_default();
+            let b = serde_json::to_value(&config_b.general).unwrap_or_default();
+            if let Some(field) = Self::changed_fields(&a, &b).into_iter().next() {
+                resolved = Some(format!("general.{}", field));
+                break;
+            }
+        }
+        self.cache.insert(cache_key, resolved.clone());
+        resolved
+    }
+
+    fn resolve(&mut self, key: &str) -> Option<String> {
+        if let Some(parsed) = parse_v19_key(key) {
+            let logic = logic_code_to_name(parsed.logic)?;
+            let field = self.logic_field(&parsed.param, parsed.direction == "Buy")?;
+            return Some(format!(
+                "engines[{}].groups[{}].logics[{}].{}",
+                parsed.engine, parsed.group, logic, field
+            ));
+        }
+        self.general_field(key)
+    }
+}
+
+fn sort_passes(passes: &mut [OptimizationPass], sort_by: &str) -> Result<(), String> {
+    let key = |p: &OptimizationPass| -> f64 {
+        match sort_by {
+            "profit" => p.profit,
+            "sharpe" => p.sharpe_ratio.unwrap_or(f64::MIN),
+            "custom" => p.custom.unwrap_or(f64::MIN),
+            "recovery" => p.recovery_factor.unwrap_or(f64::MIN),
+            // Lower drawdown ranks higher
+            "drawdown" => -p.drawdown_percent.unwrap_or(f64::MAX),
+            _ => p.result,
+        }
+    };
+    if !matches!(
+        sort_by,
+        "result" | "profit" | "sharpe" | "custom" | "recovery" | "drawdown"
+    ) {
+        return Err(format!("Unknown sort criterion '{}'", sort_by));
+    }
+    passes.sort_by(|a, b| {
+        key(b)
+            .partial_cmp(&key(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    for (index, pass) in passes.iter_mut().enumerate() {
+        pass.rank = index + 1;
+    }
+    Ok(())
+}
+
+/// Parse optimizer XML content into ranked passes
+pub fn parse_optimization_xml(xml: &str, sort_by: &str) -> Result<Vec<OptimizationPass>, String> {
+    let rows = parse_spreadsheet_rows(xml);
+    let header = rows
+        .first()
+        .ok_or_else(|| "Optimization export contains no rows".to_string())?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let pass_col = column("Pass").ok_or_else(|| "Missing 'Pass' column".to_string())?;
+    let result_col = column("Result").or_else(|| column("Back Result"));
+    let profit_col = column("Profit");
+    let input_cols: Vec<usize> = (0..header.len())
+        .filter(|i| !METRIC_COLUMNS.contains(&header[*i].trim().to_lowercase().as_str()))
+        .collect();
+
+    let mut resolver = FieldResolver::new();
+    let mut passes = Vec::new();
+    for row in rows.iter().skip(1) {
+        let pass = match row.get(pass_col).and_then(|v| v.trim().parse::<u32>().ok()) {
+            Some(pass) => pass,
+            None => continue,
+        };
+        let metric = |col: Option<usize>| parse_metric(col.and_then(|c| row.get(c)));
+
+        let mut parameters = BTreeMap::new();
+        let mut mapped_fields = Vec::new();
+        let mut unmapped_keys = Vec::new();
+        for &col in &input_cols {
+            let key = header[col].trim().to_string();
+            let value = row.get(col).cloned().unwrap_or_default();
+            match resolver.resolve(&key) {
+                Some(field) => mapped_fields.push(OptimizationFieldMapping {
+                    key: key.clone(),
+                    field,
+                    value: value.clone(),
+                }),
+                None => unmapped_keys.push(key.clone()),
+            }
+            parameters.insert(key, value);
+        }
+
+        passes.push(OptimizationPass {
+            rank: 0,
+            pass,
+            result: metric(result_col).unwrap_or(0.0),
+            profit: metric(profit_col).unwrap_or(0.0),
+            expected_payoff: metric(column("Expected Payoff")),
+            profit_factor: metric(column("Profit Factor")),
+            recovery_factor: metric(column("Recovery Factor")),
+            sharpe_ratio: metric(column("Sharpe Ratio")),
+            custom: metric(column("Custom")),
+            drawdown_percent: metric(column("Equity DD %")),
+            trades: metric(column("Trades")).map(|t| t as u32),
+            parameters,
+            mapped_fields,
+            unmapped_keys,
+        });
+    }
+
+    sort_passes(&mut passes, sort_by)?;
+    Ok(passes)
+}
+
+fn read_optimization_export(path: &str) -> Result<String, String> {
+    let sanitized_path = sanitize_and_validate_path(&PathBuf::from(path))?;
+    let ext = sanitized_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if ext == "opt" {
+        return Err(
+            "Binary .opt cache files are not supported; use \"Export to XML\" in the optimizer results"
+                .to_string(),
+        );
+    }
+    let bytes = fs::read(&sanitized_path)
+        .map_err(|e| format!("Failed to read optimization file: {}", e))?;
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        let u16_vec: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16(&u16_vec).map_err(|e| format!("Failed to parse UTF-16: {}", e))
+    } else {
+        String::from_utf8(bytes).map_err(|e| format!("Failed to parse UTF-8: {}", e))
+    }
+}
+
+/// Read an MT5 optimizer XML export and return passes ranked by `sort_by`
+/// (result, profit, sharpe, custom, recovery, drawdown; default result)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn parse_optimization_results(
+    path: String,
+    sort_by: Option<String>,
+) -> Result<OptimizationResults, String> {
+    let sort_by = sort_by.unwrap_or_else(|| "result".to_string());
+    let xml = read_optimization_export(&path)?;
+    let passes = parse_optimization_xml(&xml, &sort_by)?;
+    Ok(OptimizationResults {
+        source_path: path,
+        sort_by,
+        passes,
+    })
+}
+
+/// Apply one optimizer pass onto `base_config` (or the full v19 defaults)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn load_optimization_pass(
+    path: String,
+    pass: u32,
+    base_config: Option<MTConfig>,
+) -> Result<MTConfig, String> {
+    let xml = read_optimization_export(&path)?;
+    let passes = parse_optimization_xml(&xml, "result")?;
+    let selected = passes
+        .into_iter()
+        .find(|p| p.pass == pass)
+        .ok_or_else(|| format!("Pass {} not found in {}", pass, path))?;
+
+    let inputs: HashMap<String, String> = selected.parameters.into_iter().collect();
+    let mut config = base_config.unwrap_or_else(create_full_v19_config);
+    apply_v19_global_keys(&mut config, &inputs);
+    apply_v19_logic_inputs(&mut config, &inputs)?;
+    normalize_config_mode_contract(&mut config);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(value: &str, kind: &str) -> String {
+        format!("<Cell><Data ss:Type=\"{}\">{}</Data></Cell>", kind, value)
+    }
+
+    fn sample_xml() -> String {
+        let header = [
+            "Pass",
+            "Result",
+            "Profit",
+            "Sharpe Ratio",
+            "Equity DD %",
+            "Trades",
+        ]
+        .iter()
+        .chain(
+            [
+                "gInput_1_AP_Buy_InitialLot",
+                "gInput_MagicNumber",
+                "SomeOtherInput",
+            ]
+            .iter(),
+        )
+        .map(|h| cell(h, "String"))
+        .collect::<String>();
+        let row = |values: [&str; 9]| values.iter().map(|v| cell(v, "Number")).collect::<String>();
+        format!(
+            "<?xml version=\"1.0\"?><Workbook><Worksheet><Table><Row>{}</Row><Row>{}</Row><Row>{}</Row></Table></Worksheet></Workbook>",
+            header,
+            row(["3", "1200.5", "1200.5", "1.1", "12.5", "40", "0.05", "900", "7"]),
+            row(["8", "2500", "2500", "0.8", "30.1", "55", "0.08", "900", "7"]),
+        )
+    }
+
+    #[test]
+    fn test_parse_optimization_xml_ranks_and_maps() {
+        let passes = parse_optimization_xml(&sample_xml(), "result").unwrap();
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[0].pass, 8);
+        assert_eq!(passes[0].rank, 1);
+        assert_eq!(passes[0].drawdown_percent, Some(30.1));
+        assert_eq!(passes[0].trades, Some(55));
+
+        let lot = passes[0]
+            .mapped_fields
+            .iter()
+            .find(|m| m.key == "gInput_1_AP_Buy_InitialLot")
+            .expect("v19 key mapped");
+        assert!(lot.field.starts_with("engines[A].groups[1].logics[POWER]."));
+        assert!(lot.field.contains("initial_lot"));
+        assert_eq!(lot.value, "0.08");
+        assert!(passes[0]
+            .unmapped_keys
+            .contains(&"SomeOtherInput".to_string()));
+
+        let by_drawdown = parse_optimization_xml(&sample_xml(), "drawdown").unwrap();
+        assert_eq!(by_drawdown[0].pass, 3);
+        assert!(parse_optimization_xml(&sample_xml(), "bogus").is_err());
+    }
+}
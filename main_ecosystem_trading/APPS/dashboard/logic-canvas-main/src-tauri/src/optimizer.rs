@@ -0,0 +1,529 @@
+// ============================================
+// GENETIC OPTIMIZER
+// ============================================
+//
+// Evolutionary search over the same axes the parameter sweep uses. Each
+// generation is written as .set files into the vault; the caller backtests
+// them and feeds the tester reports back, which become the fitness values used
+// to breed the next generation. The whole run (population, history, RNG seed)
+// is persisted as run.json so it can be resumed or inspected later.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::backtest::{parse_tester_report, TesterReportSummary};
+use crate::mt_bridge::{
+    atomic_write, resolve_vault_path, sanitize_and_validate_path, write_massive_v19_setfile,
+    MTConfig,
+};
+use crate::sweep::{apply_axis_value, SweepAxis};
+
+pub const OPTIMIZER_DIR: &str = "Optimizer";
+pub const OPTIMIZER_RUN_FILE: &str = "run.json";
+pub const FITNESS_METRICS: [&str; 5] = ["profit", "profitFactor", "sharpe", "recovery", "custom"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizerOptions {
+    #[serde(default)]
+    pub population_size: Option<usize>,
+    #[serde(default)]
+    pub elite_count: Option<usize>,
+    #[serde(default)]
+    pub tournament_size: Option<usize>,
+    #[serde(default)]
+    pub mutation_rate: Option<f64>,
+    /// One of FITNESS_METRICS (default "recovery")
+    #[serde(default)]
+    pub fitness_metric: Option<String>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub vault_path_override: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Individual {
+    pub id: String,
+    pub genes: Vec<f64>,
+    pub set_path: Option<String>,
+    pub fitness: Option<f64>,
+    pub report: Option<TesterReportSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    pub best_individual_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizerRun {
+    pub run_id: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub run_dir: String,
+    pub base_config: MTConfig,
+    pub axes: Vec<SweepAxis>,
+    pub population_size: usize,
+    pub elite_count: usize,
+    pub tournament_size: usize,
+    pub mutation_rate: f64,
+    pub fitness_metric: String,
+    pub seed: u64,
+    pub generation: usize,
+    pub population: Vec<Individual>,
+    pub history: Vec<GenerationStats>,
+    pub best: Option<Individual>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndividualReport {
+    pub individual_id: String,
+    pub report_path: String,
+}
+
+/// Score a tester report; higher is better
+pub fn fitness_from_report(summary: &TesterReportSummary, metric: &str) -> Result<f64, String> {
+    let drawdown_ratio = || match summary.max_drawdown {
+        Some(dd) if dd > 0.0 => summary.net_profit / dd,
+        _ => summary.net_profit,
+    };
+    let score = match metric {
+        "profit" => summary.net_profit,
+        "profitFactor" => summary.profit_factor.unwrap_or(0.0),
+        "sharpe" => summary.sharpe_ratio.unwrap_or(0.0),
+        "recovery" => summary.recovery_factor.unwrap_or_else(drawdown_ratio),
+        // Profit penalised by relative drawdown
+        "custom" => summary.net_profit / (1.0 + summary.max_drawdown_percent.unwrap_or(0.0) / 10.0),
+        other => {
+            return Err(format!(
+                "Unknown fitness metric '{}' (expected one of {})",
+                other,
+                FITNESS_METRICS.join(", ")
+            ))
+        }
+    };
+    Ok(if score.is_finite() { score } else { 0.0 })
+}
+
+fn is_valid_run_id(run_id: &str) -> bool {
+    !run_id.is_empty()
+        && run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn run_dir(vault_path_override: Option<String>, run_id: &str) -> Result<PathBuf, String> {
+    if !is_valid_run_id(run_id) {
+        return Err(format!("Invalid optimization run id '{}'", run_id));
+    }
+    let vault_root = resolve_vault_path(vault_path_override)?;
+    sanitize_and_validate_path(&vault_root.join(OPTIMIZER_DIR).join(run_id))
+}
+
+fn individual_id(generation: usize, index: usize) -> String {
+    format!("g{:03}_i{:02}", generation, index + 1)
+}
+
+fn nearest_index(values: &[f64], value: f64) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            (*a - value)
+                .abs()
+                .partial_cmp(&(*b - value).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn random_genes(gene_values: &[Vec<f64>], rng: &mut StdRng) -> Vec<f64> {
+    gene_values
+        .iter()
+        .map(|values| values[rng.gen_range(0..values.len())])
+        .collect()
+}
+
+fn tournament<'a>(scored: &'a [Individual], size: usize, rng: &mut StdRng) -> &'a Individual {
+    let mut best = &scored[rng.gen_range(0..scored.len())];
+    for _ in 1..size.max(1) {
+        let candidate = &scored[rng.gen_range(0..scored.len())];
+        if candidate.fitness.unwrap_or(f64::MIN) > best.fitness.unwrap_or(f64::MIN) {
+            best = candidate;
+        }
+    }
+    best
+}
+
+/// Mutation moves one step along the axis grid most of the time and jumps to a
+/// random value otherwise, so offspring always stay on exportable values.
+fn mutate(genes: &mut [f64], gene_values: &[Vec<f64>], rate: f64, rng: &mut StdRng) {
+    for (gene, values) in genes.iter_mut().zip(gene_values) {
+        if values.len() < 2 || rng.gen::<f64>() >= rate {
+            continue;
+        }
+        let index = nearest_index(values, *gene);
+        let next = if rng.gen::<f64>() < 0.75 {
+            if index == 0 {
+                1
+            } else if index + 1 == values.len() || rng.gen::<bool>() {
+                index - 1
+            } else {
+                index + 1
+            }
+        } else {
+            rng.gen_range(0..values.len())
+        };
+        *gene = values[next];
+    }
+}
+
+/// Breed the next population from a fully scored one: elites carry over, the
+/// rest come from tournament selection, uniform crossover and mutation.
+pub fn breed_generation(run: &OptimizerRun, rng: &mut StdRng) -> Result<Vec<Vec<f64>>, String> {
+    let gene_values: Vec<Vec<f64>> = run
+        .axes
+        .iter()
+        .map(|a| a.expand())
+        .collect::<Result<_, _>>()?;
+    let mut scored: Vec<Individual> = run
+        .population
+        .iter()
+        .filter(|i| i.fitness.is_some())
+        .cloned()
+        .collect();
+    if scored.is_empty() {
+        return Err("No scored individuals to breed from".to_string());
+    }
+    scored.sort_by(|a, b| {
+        b.fitness
+            .partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut next: Vec<Vec<f64>> = scored
+        .iter()
+        .take(run.elite_count.min(run.population_size))
+        .map(|i| i.genes.clone())
+        .collect();
+    while next.len() < run.population_size {
+        let mother = tournament(&scored, run.tournament_size, rng);
+        let father = tournament(&scored, run.tournament_size, rng);
+        let mut child: Vec<f64> = mother
+            .genes
+            .iter()
+            .zip(&father.genes)
+            .map(|(m, f)| if rng.gen::<bool>() { *m } else { *f })
+            .collect();
+        mutate(&mut child, &gene_values, run.mutation_rate, rng);
+        next.push(child);
+    }
+    Ok(next)
+}
+
+fn generation_rng(seed: u64, generation: usize) -> StdRng {
+    StdRng::seed_from_u64(seed ^ (generation as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+fn write_generation(run: &mut OptimizerRun, genomes: Vec<Vec<f64>>) -> Result<(), String> {
+    let gen_dir = Path::new(&run.run_dir).join(format!("gen_{:03}", run.generation));
+    std::fs::create_dir_all(&gen_dir)
+        .map_err(|e| format!("Failed to create generation folder: {}", e))?;
+
+    let mut population = Vec::with_capacity(genomes.len());
+    for (index, genes) in genomes.into_iter().enumerate() {
+        let id = individual_id(run.generation, index);
+        let mut config = run.base_config.clone();
+        for (axis, value) in run.axes.iter().zip(&genes) {
+            if apply_axis_value(&mut config, axis, *value) == 0 {
+                return Err(format!(
+                    "Axis {} matches no logic in the config",
+                    axis.label()
+                ));
+            }
+        }
+        let set_path = gen_dir.join(format!("{}.set", id));
+        write_massive_v19_setfile(config, &set_path, "MT5")?;
+        population.push(Individual {
+            id,
+            genes,
+            set_path: Some(set_path.to_string_lossy().to_string()),
+            fitness: None,
+            report: None,
+        });
+    }
+    run.population = population;
+    Ok(())
+}
+
+fn save_run(run: &mut OptimizerRun) -> Result<(), String> {
+    run.updated_at = chrono::Local::now().to_rfc3339();
+    let json = serde_json::to_string_pretty(run)
+        .map_err(|e| format!("Failed to serialize optimization run: {}", e))?;
+    atomic_write(&Path::new(&run.run_dir).join(OPTIMIZER_RUN_FILE), &json)
+}
+
+fn load_run(vault_path_override: Option<String>, run_id: &str) -> Result<OptimizerRun, String> {
+    let path = run_dir(vault_path_override, run_id)?.join(OPTIMIZER_RUN_FILE);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read optimization run {}: {}", run_id, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse optimization run: {}", e))
+}
+
+/// Record the scores of the current generation and close it out in the history
+fn record_generation_stats(run: &mut OptimizerRun) {
+    let scored: Vec<&Individual> = run
+        .population
+        .iter()
+        .filter(|i| i.fitness.is_some())
+        .collect();
+    let best = match scored.iter().max_by(|a, b| {
+        a.fitness
+            .partial_cmp(&b.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }) {
+        Some(best) => (*best).clone(),
+        None => return,
+    };
+    let mean = scored.iter().filter_map(|i| i.fitness).sum::<f64>() / scored.len() as f64;
+    run.history.push(GenerationStats {
+        generation: run.generation,
+        best_fitness: best.fitness.unwrap_or(0.0),
+        mean_fitness: mean,
+        best_individual_id: best.id.clone(),
+    });
+    let improved = run
+        .best
+        .as_ref()
+        .map_or(true, |current| best.fitness > current.fitness);
+    if improved {
+        run.best = Some(best);
+    }
+}
+
+/// Create a run, write generation 0 as .set files into the vault and persist it
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn start_optimization_run(
+    config: MTConfig,
+    axes: Vec<SweepAxis>,
+    options: Option<OptimizerOptions>,
+) -> Result<OptimizerRun, String> {
+    let options = options.unwrap_or(OptimizerOptions {
+        population_size: None,
+        elite_count: None,
+        tournament_size: None,
+        mutation_rate: None,
+        fitness_metric: None,
+        seed: None,
+        vault_path_override: None,
+    });
+    if axes.is_empty() {
+        return Err("At least one optimization axis is required".to_string());
+    }
+    let gene_values: Vec<Vec<f64>> = axes.iter().map(|a| a.expand()).collect::<Result<_, _>>()?;
+
+    let fitness_metric = options
+        .fitness_metric
+        .unwrap_or_else(|| "recovery".to_string());
+    if !FITNESS_METRICS.contains(&fitness_metric.as_str()) {
+        return Err(format!("Unknown fitness metric '{}'", fitness_metric));
+    }
+    let population_size = options.population_size.unwrap_or(20).clamp(2, 500);
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let run_id = format!("opt_{}", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+    let dir = run_dir(options.vault_path_override, &run_id)?;
+    if dir.exists() {
+        return Err(format!("Optimization run {} already exists", run_id));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create run folder: {}", e))?;
+
+    let now = chrono::Local::now().to_rfc3339();
+    let mut run = OptimizerRun {
+        run_id,
+        created_at: now.clone(),
+        updated_at: now,
+        run_dir: dir.to_string_lossy().to_string(),
+        base_config: config,
+        axes,
+        population_size,
+        elite_count: options.elite_count.unwrap_or(2).min(population_size - 1),
+        tournament_size: options.tournament_size.unwrap_or(3).max(1),
+        mutation_rate: options.mutation_rate.unwrap_or(0.2).clamp(0.0, 1.0),
+        fitness_metric,
+        seed,
+        generation: 0,
+        population: Vec::new(),
+        history: Vec::new(),
+        best: None,
+    };
+
+    let mut rng = generation_rng(seed, 0);
+    let genomes = (0..population_size)
+        .map(|_| random_genes(&gene_values, &mut rng))
+        .collect();
+    write_generation(&mut run, genomes)?;
+    save_run(&mut run)?;
+    Ok(run)
+}
+
+/// Attach tester reports to individuals of the current generation. Once every
+/// individual has a fitness, the next generation is bred and written.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn advance_optimization_run(
+    run_id: String,
+    reports: Vec<IndividualReport>,
+    vault_path_override: Option<String>,
+) -> Result<OptimizerRun, String> {
+    let mut run = load_run(vault_path_override, &run_id)?;
+
+    for entry in reports {
+        let individual = run
+            .population
+            .iter_mut()
+            .find(|i| i.id == entry.individual_id)
+            .ok_or_else(|| {
+                format!(
+                    "Individual {} is not part of generation {}",
+                    entry.individual_id, run.generation
+                )
+            })?;
+        let report_path = sanitize_and_validate_path(&PathBuf::from(&entry.report_path))?;
+        let summary = parse_tester_report(&report_path)?;
+        individual.fitness = Some(fitness_from_report(&summary, &run.fitness_metric)?);
+        individual.report = Some(summary);
+    }
+
+    if run.population.iter().all(|i| i.fitness.is_some()) {
+        record_generation_stats(&mut run);
+        let mut rng = generation_rng(run.seed, run.generation + 1);
+        let genomes = breed_generation(&run, &mut rng)?;
+        run.generation += 1;
+        write_generation(&mut run, genomes)?;
+    }
+
+    save_run(&mut run)?;
+    Ok(run)
+}
+
+/// Load a persisted run (population, history and best individual so far)
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn inspect_optimization_run(
+    run_id: String,
+    vault_path_override: Option<String>,
+) -> Result<OptimizerRun, String> {
+    load_run(vault_path_override, &run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(net_profit: f64, max_drawdown: Option<f64>) -> TesterReportSummary {
+        TesterReportSummary {
+            report_path: String::new(),
+            net_profit,
+            gross_profit: None,
+            gross_loss: None,
+            profit_factor: Some(1.4),
+            expected_payoff: None,
+            recovery_factor: None,
+            sharpe_ratio: None,
+            max_drawdown,
+            max_drawdown_percent: Some(10.0),
+            total_trades: Some(120),
+        }
+    }
+
+    fn test_run(axes: Vec<SweepAxis>, fitness: &[f64]) -> OptimizerRun {
+        OptimizerRun {
+            run_id: "opt_test".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            run_dir: String::new(),
+            base_config: crate::mt_bridge::create_full_v19_config(),
+            axes,
+            population_size: fitness.len(),
+            elite_count: 1,
+            tournament_size: 2,
+            mutation_rate: 0.5,
+            fitness_metric: "profit".to_string(),
+            seed: 7,
+            generation: 0,
+            population: fitness
+                .iter()
+                .enumerate()
+                .map(|(i, f)| Individual {
+                    id: individual_id(0, i),
+                    genes: vec![0.01 * (i + 1) as f64, 100.0 * (i + 1) as f64],
+                    set_path: None,
+                    fitness: Some(*f),
+                    report: None,
+                })
+                .collect(),
+            history: Vec::new(),
+            best: None,
+        }
+    }
+
+    #[test]
+    fn test_fitness_from_report_metrics() {
+        let s = summary(500.0, Some(250.0));
+        assert_eq!(fitness_from_report(&s, "profit").unwrap(), 500.0);
+        assert_eq!(fitness_from_report(&s, "profitFactor").unwrap(), 1.4);
+        assert_eq!(fitness_from_report(&s, "recovery").unwrap(), 2.0);
+        assert_eq!(fitness_from_report(&s, "custom").unwrap(), 250.0);
+        assert!(fitness_from_report(&s, "winrate").is_err());
+    }
+
+    #[test]
+    fn test_breed_generation_keeps_elite_and_axis_values() {
+        let lot = SweepAxis {
+            field: "initial_lot".to_string(),
+            start: None,
+            end: None,
+            step: None,
+            values: Some(vec![0.01, 0.02, 0.03, 0.04]),
+            engine: None,
+            group: None,
+            logic: None,
+            direction: None,
+        };
+        let grid = SweepAxis {
+            field: "grid".to_string(),
+            start: Some(100.0),
+            end: Some(400.0),
+            step: Some(100.0),
+            values: None,
+            ..lot.clone()
+        };
+        let mut run = test_run(vec![lot, grid], &[10.0, 40.0, 20.0, 30.0]);
+
+        let mut rng = generation_rng(run.seed, 1);
+        let next = breed_generation(&run, &mut rng).unwrap();
+        assert_eq!(next.len(), 4);
+        assert_eq!(next[0], vec![0.02, 200.0]);
+        for genes in &next {
+            assert!([0.01, 0.02, 0.03, 0.04].contains(&genes[0]));
+            assert!([100.0, 200.0, 300.0, 400.0].contains(&genes[1]));
+        }
+
+        let again = breed_generation(&run, &mut generation_rng(run.seed, 1)).unwrap();
+        assert_eq!(next, again);
+
+        record_generation_stats(&mut run);
+        assert_eq!(run.history[0].best_individual_id, "g000_i02");
+        assert_eq!(run.history[0].mean_fitness, 25.0);
+        assert_eq!(run.best.as_ref().and_then(|b| b.fitness), Some(40.0));
+    }
+}
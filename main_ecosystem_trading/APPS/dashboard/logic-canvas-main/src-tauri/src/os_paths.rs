@@ -0,0 +1,83 @@
+// ============================================
+// OS PATHS
+// ============================================
+//
+// Where MetaTrader keeps its data on the current OS, and how to show a folder
+// to the user. Everything is built with PathBuf::join so the same code serves
+// the Windows desktop app and the headless/CLI tools on Linux and macOS.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// %APPDATA%\MetaQuotes, falling back to <home>/AppData/Roaming/MetaQuotes
+pub(crate) fn metaquotes_dir() -> Result<PathBuf, String> {
+    if let Some(appdata) = std::env::var_os("APPDATA").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(appdata).join("MetaQuotes"));
+    }
+    dirs::home_dir()
+        .map(|home| home.join("AppData").join("Roaming").join("MetaQuotes"))
+        .ok_or_else(|| "Home directory not found".to_string())
+}
+
+/// MetaQuotes/Terminal: one data folder per installed terminal
+pub(crate) fn terminal_data_root() -> Result<PathBuf, String> {
+    Ok(metaquotes_dir()?.join("Terminal"))
+}
+
+/// MetaQuotes/Terminal/Common/Files, shared by every terminal of the user
+pub(crate) fn common_files_dir() -> Result<PathBuf, String> {
+    Ok(terminal_data_root()?.join("Common").join("Files"))
+}
+
+/// Program Files folders terminals are usually installed into
+pub(crate) fn program_files_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ["ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    if cfg!(target_os = "windows") && dirs.is_empty() {
+        dirs.push(PathBuf::from(r"C:\Program Files"));
+        dirs.push(PathBuf::from(r"C:\Program Files (x86)"));
+    }
+    dirs
+}
+
+/// The command that opens a folder in the OS file manager
+fn open_folder_command() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Show `path` in Explorer, Finder or the desktop's file manager (xdg-open)
+pub(crate) fn open_folder(path: &Path) -> Result<(), String> {
+    Command::new(open_folder_command())
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open folder: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_files_is_joined_per_component() {
+        let common = common_files_dir().unwrap();
+        let tail: Vec<String> = common
+            .components()
+            .rev()
+            .take(4)
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(tail, vec!["Files", "Common", "Terminal", "MetaQuotes"]);
+        assert!(!open_folder_command().is_empty());
+    }
+}
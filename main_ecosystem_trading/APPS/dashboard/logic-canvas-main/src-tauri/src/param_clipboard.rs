@@ -0,0 +1,372 @@
+// ============================================
+// PARAMETER CLIPBOARD
+// ============================================
+//
+// Copy/paste of a tuned engine, group or logic between slots, configs or
+// machines. The copied subtree is serialized to a compact string (version
+// tag, checksum and base64 JSON) that survives being pasted through chat;
+// stray whitespace and line breaks are ignored on the way back in. Pasting
+// overwrites the target's parameters but keeps its identity (engine id,
+// group number, logic name/id and the buy/sell direction of the row), and
+// goes through the open-config undo stack like any other edit.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::field_locks::LockViolation;
+use crate::mt_bridge::{EngineConfig, GroupConfig, LogicConfig, MTConfig};
+
+#[cfg(feature = "tauri-app")]
+use crate::field_locks::enforce_field_locks;
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+const CLIPBOARD_PREFIX: &str = "DAAVFX1";
+const CHECKSUM_LEN: usize = 8;
+
+/// An engine (no group), a group (no logic) or a single logic row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAddress {
+    pub engine_id: String,
+    #[serde(default)]
+    pub group_number: Option<u8>,
+    /// logic_id of the row; a logic_name works when only one row has it
+    #[serde(default)]
+    pub logic: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "block", rename_all = "lowercase")]
+pub enum ParamBlock {
+    Engine(EngineConfig),
+    Group(GroupConfig),
+    Logic(LogicConfig),
+}
+
+impl ParamBlock {
+    fn kind(&self) -> &'static str {
+        match self {
+            ParamBlock::Engine(_) => "engine",
+            ParamBlock::Group(_) => "group",
+            ParamBlock::Logic(_) => "logic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardBlock {
+    pub source: BlockAddress,
+    pub platform: String,
+    pub block: ParamBlock,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamPasteResult {
+    pub handle: String,
+    /// "engine", "group" or "logic"
+    pub kind: String,
+    /// Where the block was copied from
+    pub source: BlockAddress,
+    pub violations: Vec<LockViolation>,
+}
+
+impl BlockAddress {
+    fn kind(&self) -> &'static str {
+        match (self.group_number, &self.logic) {
+            (None, _) => "engine",
+            (Some(_), None) => "group",
+            (Some(_), Some(_)) => "logic",
+        }
+    }
+}
+
+fn find_engine<'a>(
+    config: &'a mut MTConfig,
+    engine_id: &str,
+) -> Result<&'a mut EngineConfig, String> {
+    config
+        .engines
+        .iter_mut()
+        .find(|e| e.engine_id.eq_ignore_ascii_case(engine_id))
+        .ok_or_else(|| format!("Config has no engine {}", engine_id))
+}
+
+fn find_group(engine: &mut EngineConfig, group_number: u8) -> Result<&mut GroupConfig, String> {
+    let engine_id = engine.engine_id.clone();
+    engine
+        .groups
+        .iter_mut()
+        .find(|g| g.group_number == group_number)
+        .ok_or_else(|| format!("Engine {} has no group {}", engine_id, group_number))
+}
+
+fn find_logic<'a>(group: &'a mut GroupConfig, logic: &str) -> Result<&'a mut LogicConfig, String> {
+    let by_name: Vec<usize> = group
+        .logics
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.logic_name.eq_ignore_ascii_case(logic))
+        .map(|(i, _)| i)
+        .collect();
+    let index = match group.logics.iter().position(|l| l.logic_id == logic) {
+        Some(index) => index,
+        None if by_name.len() == 1 => by_name[0],
+        None if by_name.len() > 1 => {
+            return Err(format!(
+                "Group {} has several {} rows; address one by its logic id",
+                group.group_number, logic
+            ))
+        }
+        None => {
+            return Err(format!(
+                "Group {} has no logic {}",
+                group.group_number, logic
+            ))
+        }
+    };
+    Ok(&mut group.logics[index])
+}
+
+/// The subtree at `address`, with its own address as the source
+pub fn copy_block(config: &MTConfig, address: &BlockAddress) -> Result<ClipboardBlock, String> {
+    let mut config = config.clone();
+    let engine = find_engine(&mut config, &address.engine_id)?;
+    let block = match (address.group_number, &address.logic) {
+        (None, _) => ParamBlock::Engine(engine.clone()),
+        (Some(group), None) => ParamBlock::Group(find_group(engine, group)?.clone()),
+        (Some(group), Some(logic)) => {
+            ParamBlock::Logic(find_logic(find_group(engine, group)?, logic)?.clone())
+        }
+    };
+    Ok(ClipboardBlock {
+        source: address.clone(),
+        platform: config.platform.clone(),
+        block,
+    })
+}
+
+fn checksum(payload: &str) -> String {
+    hex::encode(Sha256::digest(payload.as_bytes()))[..CHECKSUM_LEN].to_string()
+}
+
+/// "DAAVFX1.<checksum>.<base64 JSON>"
+pub fn encode_block(block: &ClipboardBlock) -> Result<String, String> {
+    let json = serde_json::to_string(block)
+        .map_err(|e| format!("Failed to serialize parameter block: {}", e))?;
+    Ok(format!(
+        "{}.{}.{}",
+        CLIPBOARD_PREFIX,
+        checksum(&json),
+        BASE64.encode(json.as_bytes())
+    ))
+}
+
+pub fn decode_block(text: &str) -> Result<ClipboardBlock, String> {
+    // Chat clients wrap long lines; whitespace is never part of the encoding
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut parts = text.splitn(3, '.');
+    let (Some(prefix), Some(expected), Some(encoded)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("Not a DAAVFX parameter block".to_string());
+    };
+    if prefix != CLIPBOARD_PREFIX {
+        return Err("Not a DAAVFX parameter block".to_string());
+    }
+    let json = BASE64
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| "Parameter block is damaged (not valid base64)".to_string())?;
+    if checksum(&json) != expected.to_ascii_lowercase() {
+        return Err("Parameter block is damaged or incomplete (checksum mismatch)".to_string());
+    }
+    serde_json::from_str(&json).map_err(|e| format!("Invalid parameter block: {}", e))
+}
+
+/// Overwrite `target` with `source`, keeping the row's identity and direction
+fn paste_logic(target: &mut LogicConfig, source: &LogicConfig) {
+    let mut pasted = source.clone();
+    pasted.logic_name = target.logic_name.clone();
+    pasted.logic_id = target.logic_id.clone();
+    pasted.allow_buy = target.allow_buy;
+    pasted.allow_sell = target.allow_sell;
+    *target = pasted;
+}
+
+/// Rows are matched by position; both groups must hold the same logics in the same order
+fn paste_group(target: &mut GroupConfig, source: &GroupConfig) -> Result<(), String> {
+    let layout = |g: &GroupConfig| {
+        g.logics
+            .iter()
+            .map(|l| l.logic_name.to_ascii_uppercase())
+            .collect::<Vec<_>>()
+    };
+    if layout(target) != layout(source) {
+        return Err(format!(
+            "Group {} and the copied group {} hold different logics",
+            target.group_number, source.group_number
+        ));
+    }
+    let mut pasted = source.clone();
+    pasted.group_number = target.group_number;
+    for (logic, original) in pasted.logics.iter_mut().zip(&target.logics) {
+        let copied = logic.clone();
+        *logic = original.clone();
+        paste_logic(logic, &copied);
+    }
+    *target = pasted;
+    Ok(())
+}
+
+/// Groups are matched by number; target groups the copy doesn't have are left alone
+fn paste_engine(target: &mut EngineConfig, source: &EngineConfig) -> Result<(), String> {
+    target.max_power_orders = source.max_power_orders;
+    for group in target.groups.iter_mut() {
+        if let Some(copied) = source
+            .groups
+            .iter()
+            .find(|g| g.group_number == group.group_number)
+        {
+            paste_group(group, copied)?;
+        }
+    }
+    Ok(())
+}
+
+/// `config` with `block` pasted at `target`; the block kind must match the target
+pub fn paste_block(
+    config: &MTConfig,
+    target: &BlockAddress,
+    block: &ParamBlock,
+) -> Result<MTConfig, String> {
+    if block.kind() != target.kind() {
+        return Err(format!(
+            "The clipboard holds a copied {}, which can't be pasted onto a {}",
+            block.kind(),
+            target.kind()
+        ));
+    }
+    let mut config = config.clone();
+    let engine = find_engine(&mut config, &target.engine_id)?;
+    match (block, target.group_number, &target.logic) {
+        (ParamBlock::Engine(source), _, _) => paste_engine(engine, source)?,
+        (ParamBlock::Group(source), Some(group), _) => {
+            paste_group(find_group(engine, group)?, source)?
+        }
+        (ParamBlock::Logic(source), Some(group), Some(logic)) => {
+            paste_logic(find_logic(find_group(engine, group)?, logic)?, source)
+        }
+        _ => unreachable!("block kind matches the target"),
+    }
+    Ok(config)
+}
+
+/// Copy the engine/group/logic at `source` of the config behind `handle` (default: the
+/// active one) as a clipboard string
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn copy_param_block(
+    handle: Option<String>,
+    source: BlockAddress,
+    state: State<'_, MTBridgeState>,
+) -> Result<String, String> {
+    let configs = state.configs.read().await;
+    let config = &configs.get(handle.as_deref())?.config;
+    encode_block(&copy_block(config, &source)?)
+}
+
+/// Paste a clipboard string from copy_param_block into `target` of the config behind `handle`
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn paste_param_block(
+    handle: Option<String>,
+    target: BlockAddress,
+    clipboard: String,
+    state: State<'_, MTBridgeState>,
+) -> Result<ParamPasteResult, String> {
+    let copied = decode_block(&clipboard)?;
+    let (current, handle) = {
+        let configs = state.configs.read().await;
+        let handle = configs.resolve(handle.as_deref())?;
+        (configs.get(Some(&handle))?.config.clone(), handle)
+    };
+    let pasted = paste_block(&current, &target, &copied.block)?;
+    let violations = enforce_field_locks(&state, &pasted).await?;
+    state.configs.write().await.commit(Some(&handle), pasted)?;
+    Ok(ParamPasteResult {
+        handle,
+        kind: copied.block.kind().to_string(),
+        source: copied.source,
+        violations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn address(engine: &str, group: Option<u8>, logic: Option<&str>) -> BlockAddress {
+        BlockAddress {
+            engine_id: engine.to_string(),
+            group_number: group,
+            logic: logic.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_logic_roundtrip_between_engines() {
+        let mut config = create_full_v19_config();
+        config.engines[0].groups[0].logics[0].grid = 321.0;
+        let source = address("A", Some(1), Some("A_POWER_B_G1"));
+        let text = encode_block(&copy_block(&config, &source).unwrap()).unwrap();
+        assert!(text.starts_with("DAAVFX1."));
+
+        // Line breaks from chat are ignored
+        let wrapped = format!("{}\n  {}", &text[..20], &text[20..]);
+        let copied = decode_block(&wrapped).unwrap();
+        assert_eq!(copied.source, source);
+
+        let target = address("B", Some(3), Some("B_POWER_S_G3"));
+        let pasted = paste_block(&config, &target, &copied.block).unwrap();
+        let row = pasted.engines[1].groups[2]
+            .logics
+            .iter()
+            .find(|l| l.logic_id == "B_POWER_S_G3")
+            .unwrap();
+        assert_eq!(row.grid, 321.0);
+        assert!(row.allow_sell && !row.allow_buy);
+
+        // Kind mismatch, damage and ambiguous names are rejected
+        assert!(paste_block(&config, &address("B", Some(3), None), &copied.block).is_err());
+        let damaged = text.replacen("DAAVFX1.", "DAAVFX1.0", 1);
+        assert!(decode_block(&damaged).is_err());
+        assert!(decode_block(&text[..text.len() - 4]).is_err());
+        assert!(copy_block(&config, &address("A", Some(1), Some("POWER"))).is_err());
+    }
+
+    #[test]
+    fn test_group_paste_keeps_target_identity() {
+        let mut config = create_full_v19_config();
+        config.engines[0].groups[1].entry_delay_bars = 7;
+        config.engines[0].groups[1].logics[3].multiplier = 1.9;
+        let copied = copy_block(&config, &address("A", Some(2), None)).unwrap();
+
+        let pasted = paste_block(&config, &address("C", Some(5), None), &copied.block).unwrap();
+        let group = &pasted.engines[2].groups[4];
+        assert_eq!(group.group_number, 5);
+        assert_eq!(group.entry_delay_bars, 7);
+        assert_eq!(group.logics[3].multiplier, 1.9);
+        assert_eq!(
+            group.logics[3].logic_id,
+            config.engines[2].groups[4].logics[3].logic_id
+        );
+    }
+}
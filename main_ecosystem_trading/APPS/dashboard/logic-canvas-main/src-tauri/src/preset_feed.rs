@@ -0,0 +1,496 @@
+// ============================================
+// PRESET PUBLISHING FEED
+// ============================================
+//
+// Pushes tuned presets to customers. publish_preset signs a .set file with
+// the publisher's Ed25519 key (see preset_signing) and uploads it with its
+// metadata to a channel on the configured HTTPS feed server. Client
+// dashboards poll check_for_preset_updates, which fetches what was published
+// since their last check, verifies every preset against the configured public
+// key and installs the verified ones into the vault, in a folder named after
+// the channel. Presets that fail verification are reported, never written.
+//
+// Feed server contract, relative to the endpoint:
+//   POST channels/<channel>/presets          body: PublishedPreset
+//   GET  channels/<channel>/presets?since=   -> {"presets": [PublishedPreset]}
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cloud_sync::sync_aware_write;
+use crate::mt_bridge::{atomic_write, deobfuscate_string, obfuscate_string, resolve_vault_path};
+use crate::preset_signing::{
+    key_id, normalized_parameters, parse_signing_key, read_preset, sign_preset_content,
+    verification_key, verify_preset_content,
+};
+use crate::url_import::{is_secure_url, secure_redirect_policy};
+
+const PRESET_FEED_FILE: &str = "preset_feed.json";
+const FEED_TIMEOUT_SECS: u64 = 20;
+const MAX_CHANNEL_LEN: usize = 64;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PresetFeedSettings {
+    /// Feed server base URL, e.g. https://presets.example.com/api
+    pub endpoint: Option<String>,
+    /// Bearer token sent with every request (publish rights on the desk,
+    /// read access on client machines), obfuscated
+    pub token: Option<String>,
+    /// Base64 Ed25519 key publish_preset signs with, obfuscated; publisher only
+    pub private_key: Option<String>,
+    /// Channels check_for_preset_updates polls
+    pub channels: Vec<String>,
+    /// Version installed per channel and preset name
+    pub installed: BTreeMap<String, BTreeMap<String, String>>,
+    /// publishedAt of the newest preset seen per channel, sent as `since`
+    pub last_seen: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetFeedInfo {
+    pub endpoint: Option<String>,
+    pub channels: Vec<String>,
+    pub has_token: bool,
+    /// Key id of the configured signing key; None on client machines
+    pub publisher_key_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedPreset {
+    pub channel: String,
+    pub name: String,
+    /// First 16 hex digits of the SHA-256 of the normalized parameters
+    pub version: String,
+    pub key_id: String,
+    pub published_at: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub parameter_count: usize,
+    /// Signed .set content
+    pub content: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FeedPage {
+    #[serde(default)]
+    presets: Vec<PublishedPreset>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetUpdate {
+    pub channel: String,
+    pub name: String,
+    pub version: String,
+    pub published_at: String,
+    pub notes: Option<String>,
+    /// Where the preset was installed; None when it was rejected
+    pub installed_path: Option<String>,
+    pub verified: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetUpdateReport {
+    pub updates: Vec<PresetUpdate>,
+    /// Channels that couldn't be checked, "<channel>: <error>"
+    pub errors: Vec<String>,
+    pub checked_at: String,
+}
+
+fn feed_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(PRESET_FEED_FILE)
+}
+
+fn load_feed_settings() -> PresetFeedSettings {
+    fs::read_to_string(feed_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_feed_settings(settings: &PresetFeedSettings) -> Result<(), String> {
+    let path = feed_settings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize preset feed settings: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+/// HTTPS only; plain HTTP is accepted for a feed server on this machine
+fn validate_endpoint(endpoint: &str) -> Result<String, String> {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(endpoint)
+        .map_err(|e| format!("Invalid preset feed endpoint '{}': {}", endpoint, e))?;
+    if !is_secure_url(&parsed) {
+        return Err(format!(
+            "Preset feed endpoint must use https://: {}",
+            endpoint
+        ));
+    }
+    Ok(endpoint.to_string())
+}
+
+/// Channel names end up in URLs and vault folder names
+fn validate_channel(channel: &str) -> Result<String, String> {
+    let channel = channel.trim();
+    if channel.is_empty()
+        || channel.len() > MAX_CHANNEL_LEN
+        || !channel
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid channel '{}': use letters, digits, '-' and '_'",
+            channel
+        ));
+    }
+    Ok(channel.to_string())
+}
+
+fn preset_version(content: &str) -> String {
+    let (normalized, _) = normalized_parameters(content);
+    hex::encode(Sha256::digest(normalized.as_bytes()))[..16].to_string()
+}
+
+/// Sign `content` and wrap it with the metadata the feed carries
+pub(crate) fn build_published_preset(
+    content: &str,
+    name: &str,
+    channel: &str,
+    notes: Option<String>,
+    signing_key: &SigningKey,
+    published_at: &str,
+) -> Result<PublishedPreset, String> {
+    let (_, parameter_count) = normalized_parameters(content);
+    if parameter_count == 0 {
+        return Err("Preset has no parameters to publish".to_string());
+    }
+    let signed = sign_preset_content(content, signing_key, published_at);
+    Ok(PublishedPreset {
+        channel: validate_channel(channel)?,
+        name: name.to_string(),
+        version: preset_version(&signed),
+        key_id: key_id(&signing_key.verifying_key()),
+        published_at: published_at.to_string(),
+        notes: notes.filter(|n| !n.trim().is_empty()),
+        parameter_count,
+        content: signed,
+    })
+}
+
+/// Verify a feed preset and write it to <vault>/<channel>/<name>.set if the signature holds
+fn install_update(preset: &PublishedPreset, key: &VerifyingKey, vault_root: &Path) -> PresetUpdate {
+    let mut update = PresetUpdate {
+        channel: preset.channel.clone(),
+        name: preset.name.clone(),
+        version: preset_version(&preset.content),
+        published_at: preset.published_at.clone(),
+        notes: preset.notes.clone(),
+        installed_path: None,
+        verified: false,
+        message: String::new(),
+    };
+    let check = verify_preset_content(&preset.content, key);
+    if !check.valid {
+        update.message = check.message;
+        return update;
+    }
+    update.verified = true;
+
+    let channel = match validate_channel(&preset.channel) {
+        Ok(channel) => channel,
+        Err(e) => {
+            update.message = e;
+            return update;
+        }
+    };
+    let safe_name = preset.name.replace(
+        |c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != ' ',
+        "_",
+    );
+    let folder = vault_root.join(channel);
+    let path = folder.join(format!("{}.set", safe_name));
+    let written = fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))
+        .and_then(|_| sync_aware_write(&path, &preset.content));
+    match written {
+        Ok(()) => {
+            crate::vault_git::auto_commit_preset(vault_root, &path, "Save");
+            update.installed_path = Some(path.to_string_lossy().to_string());
+            update.message = check.message;
+        }
+        Err(e) => update.message = e,
+    }
+    update
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(FEED_TIMEOUT_SECS))
+        .redirect(secure_redirect_policy())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn with_token(
+    request: reqwest::RequestBuilder,
+    settings: &PresetFeedSettings,
+) -> reqwest::RequestBuilder {
+    match settings.token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => request.bearer_auth(deobfuscate_string(token)),
+        None => request,
+    }
+}
+
+fn channel_url(settings: &PresetFeedSettings, channel: &str) -> Result<String, String> {
+    let endpoint = settings
+        .endpoint
+        .as_deref()
+        .ok_or_else(|| "No preset feed endpoint configured".to_string())?;
+    Ok(format!(
+        "{}/channels/{}/presets",
+        validate_endpoint(endpoint)?,
+        channel
+    ))
+}
+
+fn feed_info(settings: &PresetFeedSettings) -> PresetFeedInfo {
+    PresetFeedInfo {
+        endpoint: settings.endpoint.clone(),
+        channels: settings.channels.clone(),
+        has_token: settings.token.as_deref().is_some_and(|t| !t.is_empty()),
+        publisher_key_id: settings
+            .private_key
+            .as_deref()
+            .and_then(|k| parse_signing_key(&deobfuscate_string(k)).ok())
+            .map(|k| key_id(&k.verifying_key())),
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_preset_feed() -> Result<PresetFeedInfo, String> {
+    Ok(feed_info(&load_feed_settings()))
+}
+
+/// Configure the feed; omitted fields stay as they are, empty strings clear them
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn set_preset_feed(
+    endpoint: Option<String>,
+    token: Option<String>,
+    private_key: Option<String>,
+    channels: Option<Vec<String>>,
+) -> Result<PresetFeedInfo, String> {
+    let mut settings = load_feed_settings();
+    if let Some(endpoint) = endpoint {
+        settings.endpoint = if endpoint.trim().is_empty() {
+            None
+        } else {
+            Some(validate_endpoint(&endpoint)?)
+        };
+    }
+    if let Some(token) = token {
+        settings.token = Some(obfuscate_string(token.trim())).filter(|t| !t.is_empty());
+    }
+    if let Some(private_key) = private_key {
+        if !private_key.trim().is_empty() {
+            parse_signing_key(&private_key)?;
+        }
+        settings.private_key = Some(obfuscate_string(private_key.trim())).filter(|k| !k.is_empty());
+    }
+    if let Some(channels) = channels {
+        settings.channels = channels
+            .iter()
+            .map(|c| validate_channel(c))
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    save_feed_settings(&settings)?;
+    Ok(feed_info(&settings))
+}
+
+/// Sign a .set preset and upload it to `channel` on the feed server
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn publish_preset(
+    preset: String,
+    channel: String,
+    notes: Option<String>,
+) -> Result<PublishedPreset, String> {
+    let settings = load_feed_settings();
+    let signing_key = settings
+        .private_key
+        .as_deref()
+        .map(|k| parse_signing_key(&deobfuscate_string(k)))
+        .ok_or_else(|| "No signing key configured for publishing".to_string())??;
+    let (path, content) = read_preset(&preset)?;
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let published = build_published_preset(
+        &content,
+        &name,
+        &channel,
+        notes,
+        &signing_key,
+        &chrono::Utc::now().to_rfc3339(),
+    )?;
+
+    let url = channel_url(&settings, &published.channel)?;
+    let response = with_token(http_client()?.post(url), &settings)
+        .json(&published)
+        .send()
+        .await
+        .map_err(|e| format!("Preset feed request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Preset feed returned HTTP {}", response.status()));
+    }
+    tracing::info!(channel = %published.channel, name = %published.name, version = %published.version, "Preset published");
+    Ok(published)
+}
+
+/// Poll the subscribed channels (or `channels`), installing newly published presets
+/// whose signature checks out against the configured public key
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn check_for_preset_updates(
+    channels: Option<Vec<String>>,
+) -> Result<PresetUpdateReport, String> {
+    let mut settings = load_feed_settings();
+    let key = verification_key(None)?;
+    let vault_root = resolve_vault_path(None)?;
+    let channels = channels.unwrap_or_else(|| settings.channels.clone());
+    if channels.is_empty() {
+        return Err("Subscribe to at least one preset channel".to_string());
+    }
+    let client = http_client()?;
+    let mut report = PresetUpdateReport {
+        updates: Vec::new(),
+        errors: Vec::new(),
+        checked_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    for channel in channels {
+        let fetched = async {
+            let channel = validate_channel(&channel)?;
+            let mut request = with_token(client.get(channel_url(&settings, &channel)?), &settings);
+            if let Some(since) = settings.last_seen.get(&channel) {
+                request = request.query(&[("since", since)]);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("Preset feed request failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Preset feed returned HTTP {}", response.status()));
+            }
+            let page: FeedPage = response
+                .json()
+                .await
+                .map_err(|e| format!("Invalid preset feed response: {}", e))?;
+            Ok::<_, String>((channel, page))
+        }
+        .await;
+        let (channel, page) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                report.errors.push(format!("{}: {}", channel, e));
+                continue;
+            }
+        };
+
+        for preset in page.presets.iter().filter(|p| p.channel == channel) {
+            let installed = settings.installed.entry(channel.clone()).or_default();
+            if installed.get(&preset.name) == Some(&preset_version(&preset.content)) {
+                continue;
+            }
+            let update = install_update(preset, &key, &vault_root);
+            if update.installed_path.is_some() {
+                installed.insert(update.name.clone(), update.version.clone());
+                if let Some(path) = &update.installed_path {
+                    if let Err(e) =
+                        crate::vault_backend::upload_preset(&vault_root, Path::new(path)).await
+                    {
+                        tracing::warn!(error = %e, "Failed to upload feed preset to remote vault");
+                    }
+                }
+            }
+            report.updates.push(update);
+        }
+        if let Some(newest) = page.presets.iter().map(|p| &p.published_at).max() {
+            settings.last_seen.insert(channel, newest.clone());
+        }
+    }
+
+    save_feed_settings(&settings)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_presets_install_only_when_verified() {
+        let signing_key = SigningKey::from_bytes(&[3u8; 32]);
+        let public = signing_key.verifying_key();
+        let content = "gInput_1_AP_Buy_Grid=300\r\ngInput_1_AP_Buy_InitialLot=0.02\r\n";
+        let published = build_published_preset(
+            content,
+            "Gold Scalper",
+            "gold-vip",
+            Some("Tighter grid".to_string()),
+            &signing_key,
+            "2026-10-01T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(published.parameter_count, 2);
+        assert_eq!(published.version, preset_version(content));
+        assert!(build_published_preset(content, "x", "../vault", None, &signing_key, "t").is_err());
+
+        let vault = std::env::temp_dir().join(format!("daavfx_feed_{}", std::process::id()));
+        let update = install_update(&published, &public, &vault);
+        assert!(update.verified, "{}", update.message);
+        let installed = vault.join("gold-vip").join("Gold Scalper.set");
+        assert_eq!(
+            update.installed_path,
+            Some(installed.to_string_lossy().to_string())
+        );
+        assert_eq!(fs::read_to_string(&installed).unwrap(), published.content);
+
+        let tampered = PublishedPreset {
+            name: "Tampered".to_string(),
+            content: published.content.replace("Grid=300", "Grid=900"),
+            ..published.clone()
+        };
+        let update = install_update(&tampered, &public, &vault);
+        assert!(!update.verified && update.installed_path.is_none());
+        assert!(!vault.join("gold-vip").join("Tampered.set").exists());
+
+        let other = SigningKey::from_bytes(&[4u8; 32]).verifying_key();
+        assert!(install_update(&published, &other, &vault)
+            .installed_path
+            .is_none());
+
+        assert!(validate_endpoint("http://presets.example.com").is_err());
+        assert!(validate_endpoint("http://localhost.evil.com/api").is_err());
+        assert!(validate_endpoint("http://127.0.0.1:8080/api").is_ok());
+        assert_eq!(
+            validate_endpoint("https://presets.example.com/api/").unwrap(),
+            "https://presets.example.com/api"
+        );
+        let _ = fs::remove_dir_all(&vault);
+    }
+}
@@ -0,0 +1,305 @@
+// ============================================
+// SIGNED PRESETS
+// ============================================
+//
+// Presets handed to clients carry an Ed25519 signature of their normalized
+// parameter set in the .set header comments. Verification re-normalizes the
+// parameters, so reordering lines or re-saving the file in MT5 keeps the
+// signature valid while any changed value breaks it.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use crate::backtest::decode_text;
+use crate::mt_bridge::{atomic_write, sanitize_and_validate_path};
+
+const SIGNATURE_PREFIX: &str = "; DAAVFX-Signature: ed25519:";
+const KEY_ID_PREFIX: &str = "; DAAVFX-Key-Id: ";
+const SIGNED_AT_PREFIX: &str = "; DAAVFX-Signed-At: ";
+const SIGNING_SETTINGS_FILE: &str = "preset_signing.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningKeyPair {
+    /// Base64 Ed25519 public key, safe to distribute
+    pub public_key: String,
+    /// Base64 Ed25519 secret key; keep this private
+    pub private_key: String,
+    pub key_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetVerification {
+    pub valid: bool,
+    pub signed: bool,
+    pub key_id: Option<String>,
+    pub signed_at: Option<String>,
+    pub parameter_count: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SigningSettings {
+    #[serde(default)]
+    public_key: Option<String>,
+}
+
+fn is_signature_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(SIGNATURE_PREFIX.trim())
+        || line.starts_with(KEY_ID_PREFIX.trim())
+        || line.starts_with(SIGNED_AT_PREFIX.trim())
+}
+
+/// Whether the content carries a DAAVFX signature header, valid or not
+pub(crate) fn has_signature(content: &str) -> bool {
+    content
+        .lines()
+        .any(|l| l.trim().starts_with(SIGNATURE_PREFIX.trim()))
+}
+
+/// Sorted `key=value` lines with comments, blank lines and whitespace removed
+pub fn normalized_parameters(content: &str) -> (String, usize) {
+    let mut params: Vec<(String, String)> = content
+        .lines()
+        .map(|l| l.trim().trim_start_matches('\u{feff}'))
+        .filter(|l| !l.is_empty() && !l.starts_with(';') && !l.starts_with('#'))
+        .filter_map(|l| {
+            let (key, value) = l.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    params.sort();
+    let count = params.len();
+    let text = params
+        .into_iter()
+        .map(|(k, v)| format!("{}={}\n", k, v))
+        .collect::<String>();
+    (text, count)
+}
+
+fn digest(normalized: &str) -> [u8; 32] {
+    Sha256::digest(normalized.as_bytes()).into()
+}
+
+pub fn key_id(public_key: &VerifyingKey) -> String {
+    hex::encode(Sha256::digest(public_key.as_bytes()))[..16].to_string()
+}
+
+fn decode_key<const N: usize>(value: &str, what: &str) -> Result<[u8; N], String> {
+    let bytes = BASE64
+        .decode(value.trim())
+        .map_err(|e| format!("Invalid {}: {}", what, e))?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("Invalid {}: expected {} bytes", what, N))
+}
+
+fn parse_public_key(value: &str) -> Result<VerifyingKey, String> {
+    VerifyingKey::from_bytes(&decode_key::<32>(value, "public key")?)
+        .map_err(|e| format!("Invalid public key: {}", e))
+}
+
+pub(crate) fn parse_signing_key(value: &str) -> Result<SigningKey, String> {
+    Ok(SigningKey::from_bytes(&decode_key::<32>(
+        value,
+        "private key",
+    )?))
+}
+
+/// Content with any previous signature replaced by one over its parameters
+pub fn sign_preset_content(content: &str, signing_key: &SigningKey, signed_at: &str) -> String {
+    let body: Vec<&str> = content.lines().filter(|l| !is_signature_line(l)).collect();
+    let (normalized, _) = normalized_parameters(content);
+    let signature = signing_key.sign(&digest(&normalized));
+    let mut lines = vec![
+        format!(
+            "{}{}",
+            SIGNATURE_PREFIX,
+            BASE64.encode(signature.to_bytes())
+        ),
+        format!("{}{}", KEY_ID_PREFIX, key_id(&signing_key.verifying_key())),
+        format!("{}{}", SIGNED_AT_PREFIX, signed_at),
+    ];
+    lines.extend(body.into_iter().map(|l| l.to_string()));
+    lines.join("\r\n") + "\r\n"
+}
+
+pub fn verify_preset_content(content: &str, public_key: &VerifyingKey) -> PresetVerification {
+    let header = |prefix: &str| {
+        content
+            .lines()
+            .map(str::trim)
+            .find_map(|l| l.strip_prefix(prefix.trim()).map(|v| v.trim().to_string()))
+    };
+    let (normalized, parameter_count) = normalized_parameters(content);
+    let mut result = PresetVerification {
+        valid: false,
+        signed: false,
+        key_id: header(KEY_ID_PREFIX),
+        signed_at: header(SIGNED_AT_PREFIX),
+        parameter_count,
+        message: String::new(),
+    };
+
+    let encoded = match header(SIGNATURE_PREFIX) {
+        Some(sig) => sig,
+        None => {
+            result.message = "Preset is not signed".to_string();
+            return result;
+        }
+    };
+    result.signed = true;
+    let signature = match decode_key::<64>(&encoded, "signature") {
+        Ok(bytes) => Signature::from_bytes(&bytes),
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    };
+    if result
+        .key_id
+        .as_deref()
+        .is_some_and(|id| id != key_id(public_key))
+    {
+        result.message = "Preset was signed with a different key".to_string();
+        return result;
+    }
+    match public_key.verify(&digest(&normalized), &signature) {
+        Ok(()) => {
+            result.valid = true;
+            result.message = "Signature valid; parameters unmodified".to_string();
+        }
+        Err(_) => {
+            result.message = "Signature does not match; parameters were modified".to_string();
+        }
+    }
+    result
+}
+
+fn signing_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(SIGNING_SETTINGS_FILE)
+}
+
+fn load_signing_settings() -> SigningSettings {
+    std::fs::read_to_string(signing_settings_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// `public_key` if given, else the key configured with set_preset_public_key
+pub(crate) fn verification_key(public_key: Option<String>) -> Result<VerifyingKey, String> {
+    let public_key = public_key
+        .filter(|k| !k.trim().is_empty())
+        .or_else(|| load_signing_settings().public_key)
+        .ok_or_else(|| "No public key configured for preset verification".to_string())?;
+    parse_public_key(&public_key)
+}
+
+pub(crate) fn read_preset(file_path: &str) -> Result<(PathBuf, String), String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(file_path))?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read preset: {}", e))?;
+    Ok((path, decode_text(&bytes)))
+}
+
+/// Create a new Ed25519 key pair for signing presets
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn generate_preset_signing_key() -> Result<SigningKeyPair, String> {
+    let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let public = signing_key.verifying_key();
+    Ok(SigningKeyPair {
+        public_key: BASE64.encode(public.as_bytes()),
+        private_key: BASE64.encode(signing_key.to_bytes()),
+        key_id: key_id(&public),
+    })
+}
+
+/// Configure the public key `verify_preset` checks against by default
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn set_preset_public_key(public_key: String) -> Result<String, String> {
+    let key = parse_public_key(&public_key)?;
+    let path = signing_settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let settings = SigningSettings {
+        public_key: Some(public_key.trim().to_string()),
+    };
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize signing settings: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(key_id(&key))
+}
+
+/// Embed a signature of the preset's parameters into its header comments
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn sign_preset(file_path: String, private_key: String) -> Result<String, String> {
+    let signing_key = parse_signing_key(&private_key)?;
+    let (path, content) = read_preset(&file_path)?;
+    if normalized_parameters(&content).1 == 0 {
+        return Err("Preset has no parameters to sign".to_string());
+    }
+    let signed = sign_preset_content(&content, &signing_key, &chrono::Utc::now().to_rfc3339());
+    atomic_write(&path, &signed)?;
+    Ok(key_id(&signing_key.verifying_key()))
+}
+
+/// Check a preset's signature against `public_key` or the configured key
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn verify_preset(
+    file_path: String,
+    public_key: Option<String>,
+) -> Result<PresetVerification, String> {
+    let key = verification_key(public_key)?;
+    let (_, content) = read_preset(&file_path)?;
+    Ok(verify_preset_content(&content, &key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_preset_content() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public = signing_key.verifying_key();
+        let content =
+            "; DAAVFX preset\r\ngInput_1_AP_Buy_Grid=300\r\ngInput_1_AP_Buy_InitialLot=0.02\r\n";
+
+        let signed = sign_preset_content(content, &signing_key, "2026-01-01T00:00:00Z");
+        assert!(signed.starts_with(SIGNATURE_PREFIX));
+        let check = verify_preset_content(&signed, &public);
+        assert!(check.valid, "{}", check.message);
+        assert_eq!(check.parameter_count, 2);
+
+        // Reordering lines keeps the signature valid
+        let reordered = signed.replace(
+            "gInput_1_AP_Buy_Grid=300\r\ngInput_1_AP_Buy_InitialLot=0.02",
+            "gInput_1_AP_Buy_InitialLot=0.02\r\ngInput_1_AP_Buy_Grid=300",
+        );
+        assert!(verify_preset_content(&reordered, &public).valid);
+
+        let tampered = signed.replace("Grid=300", "Grid=250");
+        let check = verify_preset_content(&tampered, &public);
+        assert!(check.signed && !check.valid);
+
+        // Re-signing replaces the old header instead of stacking another
+        let resigned = sign_preset_content(&signed, &signing_key, "2026-02-01T00:00:00Z");
+        assert_eq!(resigned.matches("DAAVFX-Signature").count(), 1);
+
+        let other = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        assert!(!verify_preset_content(&signed, &other).valid);
+        assert!(!verify_preset_content(content, &public).signed);
+    }
+}
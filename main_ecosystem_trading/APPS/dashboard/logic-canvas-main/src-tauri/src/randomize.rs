@@ -0,0 +1,296 @@
+// ============================================
+// RANDOMIZED ROBUSTNESS VARIANTS
+// ============================================
+//
+// Perturbs selected logic fields within +/- bounds to produce stress-test
+// copies of a preset. Each variant draws one change per constraint and
+// applies it to every logic the constraint selects, so a backtest of the
+// variants shows how sensitive the preset is to small parameter changes.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{sanitize_and_validate_path, write_massive_v19_setfile, MTConfig};
+use crate::sweep::{set_directional, SweepAxis};
+
+/// Hard ceiling on variants per request
+const MAX_VARIANTS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomizeConstraint {
+    /// One of SWEEP_FIELDS
+    pub field: String,
+    /// Relative bound: the value moves by up to +/- this percent
+    #[serde(default)]
+    pub percent: Option<f64>,
+    /// Absolute bound: the value moves by up to +/- this amount
+    #[serde(default)]
+    pub delta: Option<f64>,
+    /// Clamp for the perturbed value
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub engine: Option<String>,
+    #[serde(default)]
+    pub group: Option<u8>,
+    #[serde(default)]
+    pub logic: Option<String>,
+    /// "Buy" or "Sell" perturbs only that side
+    #[serde(default)]
+    pub direction: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomizeOptions {
+    pub output_dir: String,
+    #[serde(default)]
+    pub name_prefix: Option<String>,
+    /// Same seed, same variants
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// "MT4" or "MT5" (default) for the exported .set files
+    #[serde(default)]
+    pub platform: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomizedVariant {
+    pub index: usize,
+    pub name: String,
+    pub path: String,
+    /// Change drawn per constraint: percent for relative bounds, amount for absolute ones
+    pub changes: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomizeResult {
+    pub seed: u64,
+    pub variants: Vec<RandomizedVariant>,
+}
+
+impl RandomizeConstraint {
+    /// The sweep axis with the same field and scope, for validation and matching
+    fn axis(&self) -> SweepAxis {
+        SweepAxis {
+            field: self.field.clone(),
+            start: None,
+            end: None,
+            step: None,
+            values: None,
+            engine: self.engine.clone(),
+            group: self.group,
+            logic: self.logic.clone(),
+            direction: self.direction.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let axis = self.axis();
+        axis.validate()?;
+        match (self.percent, self.delta) {
+            (Some(bound), None) | (None, Some(bound)) if bound.is_finite() && bound > 0.0 => {}
+            _ => {
+                return Err(format!(
+                    "Constraint {} needs exactly one positive percent or delta bound",
+                    axis.label()
+                ))
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(format!("Constraint {} has min above max", axis.label()));
+            }
+        }
+        Ok(())
+    }
+
+    fn perturb(&self, current: f64, change: f64) -> f64 {
+        let mut value = if self.percent.is_some() {
+            current * (1.0 + change / 100.0)
+        } else {
+            current + change
+        };
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+        // Lots and multipliers keep two decimals; distances are whole points
+        match self.field.as_str() {
+            "initial_lot" | "multiplier" => (value.max(0.0) * 100.0).round() / 100.0,
+            _ => value.max(0.0).round(),
+        }
+    }
+}
+
+/// Apply one drawn change of `constraint` to every logic it selects; returns how many changed
+pub fn apply_constraint(
+    config: &mut MTConfig,
+    constraint: &RandomizeConstraint,
+    change: f64,
+) -> usize {
+    let axis = constraint.axis();
+    let direction = constraint.direction.as_deref();
+    let mut touched = 0;
+    for engine in &mut config.engines {
+        for group in &mut engine.groups {
+            for logic in &mut group.logics {
+                if !axis.matches(&engine.engine_id, group.group_number, logic) {
+                    continue;
+                }
+                let l = &mut *logic;
+                let (base, buy, sell) = match constraint.field.as_str() {
+                    "initial_lot" => (
+                        &mut l.initial_lot,
+                        &mut l.initial_lot_b,
+                        &mut l.initial_lot_s,
+                    ),
+                    "grid" => (&mut l.grid, &mut l.grid_b, &mut l.grid_s),
+                    "multiplier" => (&mut l.multiplier, &mut l.multiplier_b, &mut l.multiplier_s),
+                    "trail_value" => (
+                        &mut l.trail_value,
+                        &mut l.trail_value_b,
+                        &mut l.trail_value_s,
+                    ),
+                    _ => continue,
+                };
+                let current = match direction {
+                    Some("Buy") => buy.unwrap_or(*base),
+                    Some("Sell") => sell.unwrap_or(*base),
+                    _ => *base,
+                };
+                let value = constraint.perturb(current, change);
+                set_directional(base, buy, sell, direction, value);
+                touched += 1;
+            }
+        }
+    }
+    touched
+}
+
+/// Write `count` randomly perturbed copies of `config` as .set files for robustness backtests
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn generate_randomized_variants(
+    config: MTConfig,
+    constraints: Vec<RandomizeConstraint>,
+    count: usize,
+    options: RandomizeOptions,
+) -> Result<RandomizeResult, String> {
+    if constraints.is_empty() {
+        return Err("At least one constraint is required".to_string());
+    }
+    if count == 0 || count > MAX_VARIANTS {
+        return Err(format!(
+            "Variant count must be between 1 and {}",
+            MAX_VARIANTS
+        ));
+    }
+    for constraint in &constraints {
+        constraint.validate()?;
+    }
+
+    let output_dir = sanitize_and_validate_path(&PathBuf::from(&options.output_dir))?;
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create variants folder: {}", e))?;
+    let prefix = options
+        .name_prefix
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "robust".to_string());
+    let platform = options
+        .platform
+        .clone()
+        .unwrap_or_else(|| "MT5".to_string());
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut variants = Vec::with_capacity(count);
+    for index in 1..=count {
+        let mut variant = config.clone();
+        let mut changes = BTreeMap::new();
+        for constraint in &constraints {
+            let bound = constraint.percent.or(constraint.delta).unwrap_or(0.0);
+            let change = (rng.gen_range(-bound..=bound) * 100.0).round() / 100.0;
+            if apply_constraint(&mut variant, constraint, change) == 0 {
+                return Err(format!(
+                    "Constraint {} matches no logic in the config",
+                    constraint.axis().label()
+                ));
+            }
+            changes.insert(constraint.axis().label(), change);
+        }
+
+        let name = format!("{}_{:03}", prefix, index);
+        let file_path = output_dir.join(format!("{}.set", name));
+        write_massive_v19_setfile(variant, &file_path, &platform)?;
+        variants.push(RandomizedVariant {
+            index,
+            name,
+            path: file_path.to_string_lossy().to_string(),
+            changes,
+        });
+    }
+
+    Ok(RandomizeResult { seed, variants })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    fn constraint(field: &str, percent: Option<f64>, delta: Option<f64>) -> RandomizeConstraint {
+        RandomizeConstraint {
+            field: field.to_string(),
+            percent,
+            delta,
+            min: None,
+            max: None,
+            engine: Some("A".to_string()),
+            group: Some(1),
+            logic: Some("POWER".to_string()),
+            direction: None,
+        }
+    }
+
+    #[test]
+    fn test_constraint_bounds_and_clamping() {
+        assert!(constraint("grid", Some(10.0), None).validate().is_ok());
+        assert!(constraint("grid", Some(10.0), Some(5.0))
+            .validate()
+            .is_err());
+        assert!(constraint("grid", None, None).validate().is_err());
+        assert!(constraint("magic", Some(10.0), None).validate().is_err());
+
+        let grid = constraint("grid", Some(10.0), None);
+        assert_eq!(grid.perturb(300.0, 10.0), 330.0);
+        assert_eq!(grid.perturb(300.0, -10.0), 270.0);
+
+        let mut lot = constraint("initial_lot", None, Some(0.05));
+        lot.min = Some(0.01);
+        assert_eq!(lot.perturb(0.02, -0.05), 0.01);
+    }
+
+    #[test]
+    fn test_apply_constraint_respects_scope() {
+        let mut config = create_full_v19_config();
+        let before = config.engines[1].groups[0].logics[0].grid;
+        let grid = constraint("grid", None, Some(50.0));
+        assert!(apply_constraint(&mut config, &grid, 50.0) >= 1);
+
+        let power = &config.engines[0].groups[0].logics[0];
+        assert_eq!(power.logic_name, "POWER");
+        assert_eq!(power.grid, (before + 50.0).round());
+        assert_eq!(config.engines[1].groups[0].logics[0].grid, before);
+    }
+}
@@ -0,0 +1,227 @@
+// ============================================
+// EXPORT REDACTION
+// ============================================
+//
+// Exports are written for an audience. Internal copies keep everything (with
+// credentials obfuscated at rest), client copies drop our keys but keep what
+// the EA needs to run, and public copies strip anything that identifies the
+// account, servers or the person who tuned the preset.
+
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{obfuscate_string, MTConfig, NewsFilterConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ExportAudience {
+    #[default]
+    Internal,
+    Client,
+    Public,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RedactAction {
+    Keep,
+    /// Reversible XOR encoding, restored on import
+    Obfuscate,
+    /// Replaced with asterisks except the last four characters
+    Mask,
+    Strip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionPolicy {
+    pub license_key: RedactAction,
+    pub license_server_url: RedactAction,
+    pub news_api_key: RedactAction,
+    pub news_api_url: RedactAction,
+    pub comments: RedactAction,
+}
+
+/// Export option: the audience picks a default policy, `policy` overrides it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRedaction {
+    #[serde(default)]
+    pub audience: ExportAudience,
+    #[serde(default)]
+    pub policy: Option<RedactionPolicy>,
+}
+
+impl RedactionPolicy {
+    pub fn for_audience(audience: ExportAudience) -> Self {
+        use RedactAction::*;
+        match audience {
+            ExportAudience::Internal => RedactionPolicy {
+                license_key: Obfuscate,
+                license_server_url: Obfuscate,
+                news_api_key: Obfuscate,
+                news_api_url: Obfuscate,
+                comments: Keep,
+            },
+            ExportAudience::Client => RedactionPolicy {
+                license_key: Mask,
+                license_server_url: Keep,
+                news_api_key: Strip,
+                news_api_url: Keep,
+                comments: Keep,
+            },
+            ExportAudience::Public => RedactionPolicy {
+                license_key: Strip,
+                license_server_url: Strip,
+                news_api_key: Strip,
+                news_api_url: Strip,
+                comments: Strip,
+            },
+        }
+    }
+}
+
+impl ExportRedaction {
+    pub fn resolved_policy(&self) -> RedactionPolicy {
+        self.policy
+            .clone()
+            .unwrap_or_else(|| RedactionPolicy::for_audience(self.audience))
+    }
+}
+
+pub fn redact_value(value: &str, action: RedactAction) -> String {
+    match action {
+        RedactAction::Keep => value.to_string(),
+        RedactAction::Obfuscate => obfuscate_string(value),
+        RedactAction::Mask => {
+            let chars: Vec<char> = value.chars().collect();
+            let hidden = chars.len().saturating_sub(4).max(chars.len() / 2);
+            chars
+                .iter()
+                .enumerate()
+                .map(|(i, c)| if i < hidden { '*' } else { *c })
+                .collect()
+        }
+        RedactAction::Strip => String::new(),
+    }
+}
+
+fn redact_news_filter(filter: &mut NewsFilterConfig, policy: &RedactionPolicy) {
+    filter.api_key = redact_value(&filter.api_key, policy.news_api_key);
+    filter.api_url = redact_value(&filter.api_url, policy.news_api_url);
+}
+
+/// Apply `policy` to the config's sensitive fields in place
+pub fn apply_redaction(config: &mut MTConfig, policy: &RedactionPolicy) {
+    let general = &mut config.general;
+    general.license_key = redact_value(&general.license_key, policy.license_key);
+    general.license_server_url =
+        redact_value(&general.license_server_url, policy.license_server_url);
+    redact_news_filter(&mut general.news_filter, policy);
+    for filter in general
+        .news_filter_b
+        .iter_mut()
+        .chain(general.news_filter_s.iter_mut())
+    {
+        redact_news_filter(filter, policy);
+    }
+    config.comments = redact_comments(config.comments.take(), policy);
+    // Field notes are free text too
+    if matches!(policy.comments, RedactAction::Strip) {
+        config.annotations = None;
+    }
+}
+
+/// Free-text comments are either kept verbatim or dropped
+pub fn redact_comments(comments: Option<String>, policy: &RedactionPolicy) -> Option<String> {
+    match policy.comments {
+        RedactAction::Strip => None,
+        _ => comments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::deobfuscate_string;
+
+    #[test]
+    fn test_audience_policies() {
+        let mut config = MTConfig::default();
+        config.general.license_key = "DAAV-1234-5678".to_string();
+        config.general.license_server_url = "https://license.daavfx.com".to_string();
+        config.general.news_filter.api_key = "secret".to_string();
+        config.comments = Some("tuned on live account 51234".to_string());
+        config.annotations = Some(
+            [("/general/magic_number".to_string(), "client 51234".to_string())].into(),
+        );
+
+        let mut internal = config.clone();
+        apply_redaction(
+            &mut internal,
+            &RedactionPolicy::for_audience(ExportAudience::Internal),
+        );
+        assert!(internal.general.license_key.starts_with("ENC:"));
+        assert_eq!(
+            deobfuscate_string(&internal.general.license_key),
+            "DAAV-1234-5678"
+        );
+        assert!(internal.comments.is_some());
+        assert!(internal.annotations.is_some());
+
+        let mut client = config.clone();
+        apply_redaction(
+            &mut client,
+            &RedactionPolicy::for_audience(ExportAudience::Client),
+        );
+        assert_eq!(client.general.license_key, "**********5678");
+        assert_eq!(
+            client.general.license_server_url,
+            "https://license.daavfx.com"
+        );
+        assert!(client.general.news_filter.api_key.is_empty());
+
+        let mut public = config;
+        apply_redaction(
+            &mut public,
+            &RedactionPolicy::for_audience(ExportAudience::Public),
+        );
+        assert!(public.general.license_key.is_empty());
+        assert!(public.general.license_server_url.is_empty());
+        assert!(public.comments.is_none());
+        assert!(public.annotations.is_none());
+    }
+
+    #[test]
+    fn test_internal_policy_round_trips_directional_news_filters() {
+        let mut config = MTConfig::default();
+        config.general.news_filter_b = Some(NewsFilterConfig {
+            api_key: "buy-key".to_string(),
+            api_url: "https://news.example/buy".to_string(),
+            ..NewsFilterConfig::default()
+        });
+        config.general.news_filter_s = Some(NewsFilterConfig {
+            api_key: "sell-key".to_string(),
+            ..NewsFilterConfig::default()
+        });
+
+        let mut stored = config.clone();
+        apply_redaction(
+            &mut stored,
+            &RedactionPolicy::for_audience(ExportAudience::Internal),
+        );
+        let buy = stored.general.news_filter_b.as_ref().unwrap();
+        assert!(buy.api_key.starts_with("ENC:"));
+        assert!(buy.api_url.starts_with("ENC:"));
+
+        stored.deobfuscate_sensitive_fields();
+        let buy = stored.general.news_filter_b.unwrap();
+        assert_eq!(buy.api_key, "buy-key");
+        assert_eq!(buy.api_url, "https://news.example/buy");
+        assert_eq!(stored.general.news_filter_s.unwrap().api_key, "sell-key");
+    }
+
+    #[test]
+    fn test_mask_hides_short_values() {
+        assert_eq!(redact_value("abcd", RedactAction::Mask), "**cd");
+        assert_eq!(redact_value("", RedactAction::Mask), "");
+    }
+}
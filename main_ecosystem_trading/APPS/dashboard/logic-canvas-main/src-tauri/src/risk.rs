@@ -0,0 +1,1174 @@
+// ============================================
+// MONTE CARLO RISK SIMULATOR
+// ============================================
+//
+// Replays each enabled logic's grid/martingale ladder (grid spacing,
+// multiplier, max orders, basket take-profit) against random-walk or
+// bootstrapped historical price paths, and reports the distribution of max
+// drawdown and margin usage plus the share of paths that hit stop-out.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::symbols::{self, SymbolSpec};
+use crate::units::{self, DistanceUnit};
+
+const MAX_PATHS: usize = 20_000;
+const DEFAULT_STEPS: usize = 2_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolParams {
+    pub symbol: String,
+    /// Starting price of every path
+    pub price: f64,
+    /// Price change of one point (e.g. 0.00001 for a 5-digit FX pair)
+    pub point: f64,
+    /// Account currency value of a one-point move for 1.0 lot
+    pub point_value_per_lot: f64,
+    /// Margin for 1.0 lot; derived from contract size and leverage when absent
+    #[serde(default)]
+    pub margin_per_lot: Option<f64>,
+    #[serde(default)]
+    pub contract_size: Option<f64>,
+    #[serde(default)]
+    pub leverage: Option<f64>,
+    pub balance: f64,
+    /// Std-dev of one price step in points for random-walk paths
+    #[serde(default)]
+    pub volatility_points: Option<f64>,
+    /// Close prices to bootstrap step changes from instead of a random walk
+    #[serde(default)]
+    pub historical_prices: Option<Vec<f64>>,
+    #[serde(default)]
+    pub steps: Option<usize>,
+    /// Margin level (%) at which the broker closes everything (default 50)
+    #[serde(default)]
+    pub stop_out_percent: Option<f64>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicRiskResult {
+    pub engine_id: String,
+    pub group_number: u8,
+    pub logic_name: String,
+    pub direction: String,
+    pub initial_lot: f64,
+    pub multiplier: f64,
+    pub grid_points: f64,
+    pub max_orders: usize,
+    /// Lot size of the last order the ladder can open
+    pub max_ladder_lot: f64,
+    pub blow_up_probability: f64,
+    pub max_drawdown_percent: Distribution,
+    pub peak_margin_usage_percent: Distribution,
+    pub mean_max_orders_reached: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskReport {
+    pub symbol: String,
+    pub n_paths: usize,
+    pub steps: usize,
+    pub path_source: String,
+    pub logics: Vec<LogicRiskResult>,
+}
+
+/// Ladder settings for one logic row and direction
+#[derive(Debug, Clone)]
+pub struct LadderParams {
+    pub is_buy: bool,
+    pub initial_lot: f64,
+    pub multiplier: f64,
+    pub grid_points: f64,
+    pub take_profit_points: f64,
+    pub max_orders: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathOutcome {
+    pub blown_up: bool,
+    pub max_drawdown_percent: f64,
+    pub peak_margin_usage_percent: f64,
+    pub max_orders_reached: usize,
+}
+
+fn pick(base: f64, b: Option<f64>, s: Option<f64>, is_buy: bool) -> f64 {
+    if is_buy {
+        b.unwrap_or(base)
+    } else {
+        s.unwrap_or(base)
+    }
+}
+
+pub(crate) fn ladder_params(logic: &LogicConfig, is_buy: bool, max_orders: usize) -> LadderParams {
+    let trail_value = pick(
+        logic.trail_value,
+        logic.trail_value_b,
+        logic.trail_value_s,
+        is_buy,
+    );
+    let grid_points = pick(logic.grid, logic.grid_b, logic.grid_s, is_buy);
+    LadderParams {
+        is_buy,
+        initial_lot: pick(
+            logic.initial_lot,
+            logic.initial_lot_b,
+            logic.initial_lot_s,
+            is_buy,
+        ),
+        multiplier: pick(
+            logic.multiplier,
+            logic.multiplier_b,
+            logic.multiplier_s,
+            is_buy,
+        ),
+        grid_points,
+        // Basket closes once the average price is recovered by the trail distance
+        take_profit_points: if trail_value > 0.0 {
+            trail_value
+        } else {
+            grid_points
+        },
+        max_orders,
+    }
+}
+
+impl SymbolParams {
+    fn margin_per_lot(&self) -> f64 {
+        self.margin_per_lot.unwrap_or_else(|| {
+            let contract = self.contract_size.unwrap_or(100_000.0);
+            let leverage = self.leverage.filter(|l| *l > 0.0).unwrap_or(100.0);
+            self.price * contract / leverage
+        })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.price > 0.0 && self.point > 0.0 && self.balance > 0.0) {
+            return Err("Symbol price, point and balance must be positive".to_string());
+        }
+        if self.point_value_per_lot.is_nan() || self.point_value_per_lot <= 0.0 {
+            return Err("pointValuePerLot must be positive".to_string());
+        }
+        if let Some(prices) = &self.historical_prices {
+            if prices.len() < 2 {
+                return Err("historicalPrices needs at least two prices".to_string());
+            }
+        } else if self
+            .volatility_points
+            .map_or(true, |v| v.is_nan() || v <= 0.0)
+        {
+            return Err("Either historicalPrices or volatilityPoints is required".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Standard normal sample (Box-Muller)
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Per-step price changes in points: bootstrapped from history or Gaussian
+pub fn generate_path(
+    symbol: &SymbolParams,
+    history_steps: &[f64],
+    steps: usize,
+    rng: &mut StdRng,
+) -> Vec<f64> {
+    (0..steps)
+        .map(|_| {
+            if history_steps.is_empty() {
+                standard_normal(rng) * symbol.volatility_points.unwrap_or(0.0)
+            } else {
+                history_steps[rng.gen_range(0..history_steps.len())]
+            }
+        })
+        .collect()
+}
+
+/// Run one ladder over one path of point moves
+pub fn simulate_ladder(ladder: &LadderParams, symbol: &SymbolParams, moves: &[f64]) -> PathOutcome {
+    let margin_per_lot = symbol.margin_per_lot();
+    let stop_out = symbol.stop_out_percent.unwrap_or(50.0);
+    let sign = if ladder.is_buy { 1.0 } else { -1.0 };
+
+    let mut outcome = PathOutcome::default();
+    let mut balance = symbol.balance;
+    let mut peak_equity = balance;
+    let mut price = 0.0_f64; // points relative to path start
+    let mut orders: Vec<(f64, f64)> = Vec::new(); // (open price, lots)
+
+    for step in std::iter::once(0.0).chain(moves.iter().copied()) {
+        price += step;
+
+        if orders.is_empty() {
+            orders.push((price, ladder.initial_lot));
+        } else if orders.len() < ladder.max_orders && ladder.grid_points > 0.0 {
+            let (last_open, last_lot) = orders[orders.len() - 1];
+            if sign * (last_open - price) >= ladder.grid_points {
+                orders.push((price, last_lot * ladder.multiplier));
+            }
+        }
+        outcome.max_orders_reached = outcome.max_orders_reached.max(orders.len());
+
+        let lots: f64 = orders.iter().map(|(_, l)| l).sum();
+        let floating: f64 = orders
+            .iter()
+            .map(|(open, l)| sign * (price - open) * l * symbol.point_value_per_lot)
+            .sum();
+        let equity = balance + floating;
+        let margin = lots * margin_per_lot;
+
+        if equity > peak_equity {
+            peak_equity = equity;
+        }
+        if peak_equity > 0.0 {
+            let dd = (peak_equity - equity) / peak_equity * 100.0;
+            outcome.max_drawdown_percent = outcome.max_drawdown_percent.max(dd);
+        }
+        if equity > 0.0 {
+            outcome.peak_margin_usage_percent = outcome
+                .peak_margin_usage_percent
+                .max(margin / equity * 100.0);
+        }
+        if equity <= 0.0 || (margin > 0.0 && equity / margin * 100.0 <= stop_out) {
+            outcome.blown_up = true;
+            break;
+        }
+
+        let average = orders.iter().map(|(open, l)| open * l).sum::<f64>() / lots;
+        if sign * (price - average) >= ladder.take_profit_points {
+            balance = equity;
+            orders.clear();
+        }
+    }
+    outcome
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+pub fn distribution(mut values: Vec<f64>) -> Distribution {
+    if values.is_empty() {
+        return Distribution::default();
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Distribution {
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        p50: percentile(&values, 0.50),
+        p95: percentile(&values, 0.95),
+        p99: percentile(&values, 0.99),
+        max: values[values.len() - 1],
+    }
+}
+
+pub fn simulate_risk_report(
+    config: &MTConfig,
+    symbol: &SymbolParams,
+    n_paths: usize,
+) -> Result<RiskReport, String> {
+    symbol.validate()?;
+    let n_paths = n_paths.clamp(1, MAX_PATHS);
+    let steps = symbol.steps.unwrap_or(DEFAULT_STEPS).max(1);
+    let history_steps: Vec<f64> = symbol
+        .historical_prices
+        .as_ref()
+        .map(|prices| {
+            prices
+                .windows(2)
+                .map(|w| (w[1] - w[0]) / symbol.point)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Every logic sees the same set of paths so results are comparable
+    let mut rng = StdRng::seed_from_u64(symbol.seed.unwrap_or(42));
+    let paths: Vec<Vec<f64>> = (0..n_paths)
+        .map(|_| generate_path(symbol, &history_steps, steps, &mut rng))
+        .collect();
+
+    let mut logics = Vec::new();
+    for engine in &config.engines {
+        let max_orders = engine.max_power_orders.max(1) as usize;
+        for group in engine.groups.iter().filter(|g| g.enabled) {
+            for logic in group.logics.iter().filter(|l| l.enabled) {
+                for (is_buy, allowed) in [(true, logic.allow_buy), (false, logic.allow_sell)] {
+                    if !allowed {
+                        continue;
+                    }
+                    let ladder = ladder_params(logic, is_buy, max_orders);
+                    if ladder.initial_lot.is_nan() || ladder.initial_lot <= 0.0 {
+                        continue;
+                    }
+                    let outcomes: Vec<PathOutcome> = paths
+                        .iter()
+                        .map(|moves| simulate_ladder(&ladder, symbol, moves))
+                        .collect();
+                    let blown = outcomes.iter().filter(|o| o.blown_up).count();
+                    logics.push(LogicRiskResult {
+                        engine_id: engine.engine_id.clone(),
+                        group_number: group.group_number,
+                        logic_name: logic.logic_name.clone(),
+                        direction: if is_buy { "Buy" } else { "Sell" }.to_string(),
+                        initial_lot: ladder.initial_lot,
+                        multiplier: ladder.multiplier,
+                        grid_points: ladder.grid_points,
+                        max_orders,
+                        max_ladder_lot: ladder.initial_lot
+                            * ladder.multiplier.powi(max_orders as i32 - 1),
+                        blow_up_probability: blown as f64 / n_paths as f64,
+                        max_drawdown_percent: distribution(
+                            outcomes.iter().map(|o| o.max_drawdown_percent).collect(),
+                        ),
+                        peak_margin_usage_percent: distribution(
+                            outcomes
+                                .iter()
+                                .map(|o| o.peak_margin_usage_percent)
+                                .collect(),
+                        ),
+                        mean_max_orders_reached: outcomes
+                            .iter()
+                            .map(|o| o.max_orders_reached as f64)
+                            .sum::<f64>()
+                            / n_paths as f64,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(RiskReport {
+        symbol: symbol.symbol.clone(),
+        n_paths,
+        steps,
+        path_source: if history_steps.is_empty() {
+            "randomWalk".to_string()
+        } else {
+            "historicalBootstrap".to_string()
+        },
+        logics,
+    })
+}
+
+/// Monte Carlo drawdown / margin / blow-up estimates for every enabled logic
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn simulate_risk(
+    config: MTConfig,
+    symbol_params: SymbolParams,
+    n_paths: usize,
+) -> Result<RiskReport, String> {
+    tokio::task::spawn_blocking(move || simulate_risk_report(&config, &symbol_params, n_paths))
+        .await
+        .map_err(|e| format!("Risk simulation failed: {}", e))?
+}
+
+// ============================================
+// MARGIN & EXPOSURE CALCULATOR
+// ============================================
+//
+// Deterministic counterpart to the simulator: assumes price moves straight
+// against every ladder until all configured levels are open.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureAccount {
+    pub balance: f64,
+    pub leverage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureSymbol {
+    /// Looked up in the symbol catalog for a missing contract size or tick value
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub contract_size: f64,
+    /// Account currency value of a one-point move for 1.0 lot
+    #[serde(default)]
+    pub tick_value: f64,
+    /// Quote price used for margin (default 1.0, i.e. margin in base units)
+    #[serde(default)]
+    pub price: Option<f64>,
+}
+
+impl ExposureSymbol {
+    /// Fill a missing contract size or tick value from the symbol's spec
+    fn with_symbol_spec(mut self) -> Result<Self, String> {
+        if self.contract_size > 0.0 && self.tick_value > 0.0 {
+            return Ok(self);
+        }
+        let Some(name) = self.symbol.clone() else {
+            return Ok(self);
+        };
+        if let Some(spec) = symbols::lookup(&name, None)? {
+            if self.contract_size <= 0.0 {
+                self.contract_size = spec.contract_size;
+            }
+            if self.tick_value <= 0.0 {
+                self.tick_value = spec.tick_value.unwrap_or_default();
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureLevel {
+    pub level: usize,
+    pub lot: f64,
+    pub cumulative_lots: f64,
+    /// Adverse distance from the first entry, in points
+    pub distance_points: f64,
+    pub floating_loss: f64,
+    pub required_margin: f64,
+    pub equity: f64,
+    pub margin_level_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicExposure {
+    pub logic_name: String,
+    pub direction: String,
+    pub levels: Vec<ExposureLevel>,
+    pub worst_case_lots: f64,
+    pub required_margin: f64,
+    /// Adverse distance at which this ladder alone reaches the equity stop
+    pub equity_stop_distance_points: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupExposure {
+    pub group_number: u8,
+    pub worst_case_lots: f64,
+    pub required_margin: f64,
+    /// Adverse distance at which all same-direction ladders of the group
+    /// together reach the equity stop (worst direction)
+    pub equity_stop_distance_points: Option<f64>,
+    pub logics: Vec<LogicExposure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineExposure {
+    pub engine_id: String,
+    pub max_orders: usize,
+    pub worst_case_lots: f64,
+    pub required_margin: f64,
+    pub groups: Vec<GroupExposure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureReport {
+    pub balance: f64,
+    pub leverage: f64,
+    /// Loss that triggers the equity stop (None when the stop is disabled)
+    pub equity_stop_amount: Option<f64>,
+    pub worst_case_lots: f64,
+    pub required_margin: f64,
+    pub engines: Vec<EngineExposure>,
+}
+
+/// (distance, lot) for every level of a fully opened ladder
+pub(crate) fn ladder_levels(ladder: &LadderParams) -> Vec<(f64, f64)> {
+    let mut lot = ladder.initial_lot;
+    (0..ladder.max_orders)
+        .map(|i| {
+            let level = (ladder.grid_points * i as f64, lot);
+            lot *= ladder.multiplier;
+            level
+        })
+        .collect()
+}
+
+fn floating_loss_at(ladders: &[Vec<(f64, f64)>], distance: f64, tick_value: f64) -> f64 {
+    ladders
+        .iter()
+        .flatten()
+        .filter(|(open, _)| *open <= distance)
+        .map(|(open, lot)| (distance - open) * lot * tick_value)
+        .sum()
+}
+
+/// Smallest adverse distance whose floating loss reaches `target`
+fn equity_stop_distance(ladders: &[Vec<(f64, f64)>], target: f64, tick_value: f64) -> Option<f64> {
+    if target <= 0.0 || ladders.iter().all(|l| l.is_empty()) {
+        return None;
+    }
+    let mut hi = 1.0;
+    while floating_loss_at(ladders, hi, tick_value) < target {
+        hi *= 2.0;
+        if hi > 1e9 {
+            return None;
+        }
+    }
+    let mut lo = 0.0;
+    for _ in 0..60 {
+        let mid = (lo + hi) / 2.0;
+        if floating_loss_at(ladders, mid, tick_value) < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some((hi * 10.0).round() / 10.0)
+}
+
+pub fn calculate_exposure_report(
+    config: &MTConfig,
+    account: &ExposureAccount,
+    symbol: &ExposureSymbol,
+) -> Result<ExposureReport, String> {
+    if account.balance <= 0.0 || account.leverage <= 0.0 {
+        return Err("Account balance and leverage must be positive".to_string());
+    }
+    if symbol.contract_size <= 0.0 || symbol.tick_value <= 0.0 {
+        return Err("Symbol contract size and tick value must be positive".to_string());
+    }
+    let margin_per_lot = symbol.contract_size * symbol.price.unwrap_or(1.0) / account.leverage;
+    let rm = &config.general.risk_management;
+    let equity_stop_amount = if rm.equity_stop_enabled {
+        Some(account.balance * rm.equity_stop_value / 100.0)
+    } else if rm.drawdown_stop_enabled {
+        Some(account.balance * rm.max_drawdown_percent / 100.0)
+    } else {
+        None
+    };
+
+    let mut engines = Vec::new();
+    for engine in &config.engines {
+        let max_orders = engine.max_power_orders.max(1) as usize;
+        let mut groups = Vec::new();
+        for group in engine.groups.iter().filter(|g| g.enabled) {
+            let mut logics = Vec::new();
+            let mut buy_ladders = Vec::new();
+            let mut sell_ladders = Vec::new();
+            for logic in group.logics.iter().filter(|l| l.enabled) {
+                for (is_buy, allowed) in [(true, logic.allow_buy), (false, logic.allow_sell)] {
+                    let ladder = ladder_params(logic, is_buy, max_orders);
+                    if !allowed || ladder.initial_lot <= 0.0 {
+                        continue;
+                    }
+                    let points = ladder_levels(&ladder);
+                    let mut cumulative = 0.0;
+                    let levels: Vec<ExposureLevel> = points
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (distance, lot))| {
+                            cumulative += lot;
+                            let loss = floating_loss_at(
+                                std::slice::from_ref(&points),
+                                *distance,
+                                symbol.tick_value,
+                            );
+                            let margin = cumulative * margin_per_lot;
+                            let equity = account.balance - loss;
+                            ExposureLevel {
+                                level: i + 1,
+                                lot: *lot,
+                                cumulative_lots: cumulative,
+                                distance_points: *distance,
+                                floating_loss: loss,
+                                required_margin: margin,
+                                equity,
+                                margin_level_percent: (margin > 0.0)
+                                    .then(|| equity / margin * 100.0),
+                            }
+                        })
+                        .collect();
+                    logics.push(LogicExposure {
+                        logic_name: logic.logic_name.clone(),
+                        direction: if is_buy { "Buy" } else { "Sell" }.to_string(),
+                        worst_case_lots: cumulative,
+                        required_margin: cumulative * margin_per_lot,
+                        equity_stop_distance_points: equity_stop_amount.and_then(|target| {
+                            equity_stop_distance(
+                                std::slice::from_ref(&points),
+                                target,
+                                symbol.tick_value,
+                            )
+                        }),
+                        levels,
+                    });
+                    if is_buy {
+                        buy_ladders.push(points);
+                    } else {
+                        sell_ladders.push(points);
+                    }
+                }
+            }
+            let worst_case_lots: f64 = logics.iter().map(|l| l.worst_case_lots).sum();
+            let equity_stop_distance_points = equity_stop_amount.and_then(|target| {
+                [&buy_ladders, &sell_ladders]
+                    .iter()
+                    .filter_map(|ladders| equity_stop_distance(ladders, target, symbol.tick_value))
+                    .reduce(f64::min)
+            });
+            groups.push(GroupExposure {
+                group_number: group.group_number,
+                worst_case_lots,
+                required_margin: worst_case_lots * margin_per_lot,
+                equity_stop_distance_points,
+                logics,
+            });
+        }
+        let worst_case_lots: f64 = groups.iter().map(|g| g.worst_case_lots).sum();
+        engines.push(EngineExposure {
+            engine_id: engine.engine_id.clone(),
+            max_orders,
+            worst_case_lots,
+            required_margin: worst_case_lots * margin_per_lot,
+            groups,
+        });
+    }
+
+    let worst_case_lots: f64 = engines.iter().map(|e| e.worst_case_lots).sum();
+    Ok(ExposureReport {
+        balance: account.balance,
+        leverage: account.leverage,
+        equity_stop_amount,
+        worst_case_lots,
+        required_margin: worst_case_lots * margin_per_lot,
+        engines,
+    })
+}
+
+/// Worst-case lots, margin and equity-stop distance per engine/group with
+/// per-level tables for the risk panel
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn calculate_exposure(
+    config: MTConfig,
+    account: ExposureAccount,
+    symbol: ExposureSymbol,
+) -> Result<ExposureReport, String> {
+    calculate_exposure_report(&config, &account, &symbol.with_symbol_spec()?)
+}
+
+// ============================================
+// POSITION-SIZE ADVISOR
+// ============================================
+//
+// Derives initial lots from the compounding settings so the two stop drifting
+// apart. The current lots are taken as tuned for `reference_balance`; every
+// `compounding_target` percent of growth over it adds `compounding_increase`
+// percent to each lot (and the same amount is removed per step of shrinkage).
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotAdvice {
+    pub engine_id: String,
+    pub group_number: u8,
+    pub logic_name: String,
+    pub direction: String,
+    pub current_lot: f64,
+    pub recommended_lot: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSizeAdvice {
+    /// "balance" or "equity", from compounding_type
+    pub basis: String,
+    pub basis_amount: f64,
+    pub compounding_steps: i64,
+    pub scale_factor: f64,
+    pub rows: Vec<LotAdvice>,
+    /// Updated config when `apply` was requested
+    pub config: Option<MTConfig>,
+}
+
+fn round_to_step(lot: f64, lot_step: f64) -> f64 {
+    let steps = (lot / lot_step).round().max(1.0);
+    ((steps * lot_step) * 1e8).round() / 1e8
+}
+
+pub fn compounding_scale(
+    config: &MTConfig,
+    basis_amount: f64,
+    reference_balance: f64,
+) -> (i64, f64) {
+    let general = &config.general;
+    if !general.compounding_enabled || general.compounding_target <= 0.0 {
+        return (0, 1.0);
+    }
+    let growth_percent = (basis_amount / reference_balance - 1.0) * 100.0;
+    let steps = (growth_percent / general.compounding_target).floor() as i64;
+    let factor = (1.0 + steps as f64 * general.compounding_increase / 100.0).max(0.0);
+    (steps, factor)
+}
+
+pub fn advise_position_size_for(
+    mut config: MTConfig,
+    balance: f64,
+    equity: Option<f64>,
+    reference_balance: f64,
+    lot_step: Option<f64>,
+    apply: bool,
+) -> Result<PositionSizeAdvice, String> {
+    if balance <= 0.0 || reference_balance <= 0.0 {
+        return Err("Balance and reference balance must be positive".to_string());
+    }
+    let lot_step = lot_step.filter(|s| *s > 0.0).unwrap_or(0.01);
+    let (basis, basis_amount) = if config.general.compounding_type == "Compound_Equity" {
+        ("equity", equity.unwrap_or(balance))
+    } else {
+        ("balance", balance)
+    };
+    let (compounding_steps, scale_factor) =
+        compounding_scale(&config, basis_amount, reference_balance);
+    let scale = |lot: f64| round_to_step(lot * scale_factor, lot_step);
+
+    let mut rows = Vec::new();
+    for engine in &mut config.engines {
+        for group in &mut engine.groups {
+            for logic in &mut group.logics {
+                for (is_buy, allowed) in [(true, logic.allow_buy), (false, logic.allow_sell)] {
+                    if !allowed {
+                        continue;
+                    }
+                    let current = pick(
+                        logic.initial_lot,
+                        logic.initial_lot_b,
+                        logic.initial_lot_s,
+                        is_buy,
+                    );
+                    rows.push(LotAdvice {
+                        engine_id: engine.engine_id.clone(),
+                        group_number: group.group_number,
+                        logic_name: logic.logic_name.clone(),
+                        direction: if is_buy { "Buy" } else { "Sell" }.to_string(),
+                        current_lot: current,
+                        recommended_lot: scale(current),
+                    });
+                }
+                if apply {
+                    logic.initial_lot = scale(logic.initial_lot);
+                    logic.initial_lot_b = logic.initial_lot_b.map(scale);
+                    logic.initial_lot_s = logic.initial_lot_s.map(scale);
+                }
+            }
+        }
+    }
+
+    Ok(PositionSizeAdvice {
+        basis: basis.to_string(),
+        basis_amount,
+        compounding_steps,
+        scale_factor,
+        rows,
+        config: if apply { Some(config) } else { None },
+    })
+}
+
+/// Recommended initial lots for the current balance/equity under the
+/// configured compounding rules; `apply` also writes them into the config
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn advise_position_size(
+    config: MTConfig,
+    balance: f64,
+    equity: Option<f64>,
+    reference_balance: f64,
+    lot_step: Option<f64>,
+    apply: Option<bool>,
+) -> Result<PositionSizeAdvice, String> {
+    advise_position_size_for(
+        config,
+        balance,
+        equity,
+        reference_balance,
+        lot_step,
+        apply.unwrap_or(false),
+    )
+}
+
+// ============================================
+// GRID LADDER CALCULATOR
+// ============================================
+//
+// What-if view of one logic's progression on a concrete symbol: the entry
+// price of every level, the lot the broker will actually take after the
+// multiplier (capped by last_lot and snapped to the lot step), the running
+// total, the basket's break-even price and the floating loss as each level
+// opens, so "multiplier 1.5, grid 300" turns into numbers before it trades.
+
+const MAX_LADDER_LEVELS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridLadderLevel {
+    pub level: usize,
+    pub entry_price: f64,
+    /// Adverse distance from the first entry, in points
+    pub distance_points: f64,
+    /// initial_lot * multiplier^(level - 1), before broker rounding
+    pub raw_lot: f64,
+    pub lot: f64,
+    pub cumulative_lots: f64,
+    /// Volume-weighted average entry of the open basket
+    pub break_even_price: f64,
+    /// Distance price has to recover from this level to break even, in points
+    pub break_even_distance_points: f64,
+    /// Account currency loss when this level opens; None without a tick value
+    pub floating_loss: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridLadder {
+    pub logic_name: String,
+    pub direction: String,
+    pub grid_points: f64,
+    pub multiplier: f64,
+    /// Cap on a single order's lot, when last_lot is set
+    pub last_lot: Option<f64>,
+    pub levels: Vec<GridLadderLevel>,
+    pub total_lots: f64,
+    pub max_floating_loss: Option<f64>,
+}
+
+fn normalize_lot(lot: f64, spec: &SymbolSpec) -> f64 {
+    let stepped = if spec.lot_step > 0.0 {
+        (lot / spec.lot_step).round() * spec.lot_step
+    } else {
+        lot
+    };
+    (stepped.clamp(spec.min_lot, spec.max_lot.max(spec.min_lot)) * 1e8).round() / 1e8
+}
+
+fn round_price(price: f64, digits: u32) -> f64 {
+    let scale = 10f64.powi(digits as i32);
+    (price * scale).round() / scale
+}
+
+pub fn grid_ladder(
+    logic: &LogicConfig,
+    spec: &SymbolSpec,
+    levels: usize,
+    entry_price: f64,
+    is_buy: bool,
+    grid_unit: DistanceUnit,
+) -> Result<GridLadder, String> {
+    if levels == 0 || levels > MAX_LADDER_LEVELS {
+        return Err(format!(
+            "Levels must be between 1 and {}",
+            MAX_LADDER_LEVELS
+        ));
+    }
+    if entry_price.is_nan() || entry_price <= 0.0 {
+        return Err("Entry price must be positive".to_string());
+    }
+    let mut ladder = ladder_params(logic, is_buy, levels);
+    if ladder.initial_lot <= 0.0 {
+        return Err(format!("{} has no initial lot", logic.logic_name));
+    }
+    if levels > 1 && ladder.grid_points <= 0.0 {
+        return Err(format!("{} has no grid distance", logic.logic_name));
+    }
+    let point = spec.point();
+    ladder.grid_points *= match grid_unit {
+        DistanceUnit::Points => 1.0,
+        DistanceUnit::Pips => units::symbol_points_per_pip(spec),
+        DistanceUnit::Price => 1.0 / point,
+    };
+    let last_lot = if is_buy {
+        logic.last_lot_b.or(logic.last_lot)
+    } else {
+        logic.last_lot_s.or(logic.last_lot)
+    }
+    .filter(|lot| *lot > 0.0);
+    let sign = if is_buy { 1.0 } else { -1.0 };
+
+    let mut rows: Vec<GridLadderLevel> = Vec::new();
+    let mut opened: Vec<(f64, f64)> = Vec::new(); // (distance, lot)
+    for (i, (distance, raw_lot)) in ladder_levels(&ladder).into_iter().enumerate() {
+        let lot = normalize_lot(last_lot.map_or(raw_lot, |cap| raw_lot.min(cap)), spec);
+        opened.push((distance, lot));
+        let cumulative_lots: f64 = opened.iter().map(|(_, l)| l).sum();
+        let average_distance = opened.iter().map(|(d, l)| d * l).sum::<f64>() / cumulative_lots;
+        let loss_points: f64 = opened.iter().map(|(d, l)| (distance - d) * l).sum();
+        rows.push(GridLadderLevel {
+            level: i + 1,
+            entry_price: round_price(entry_price - sign * distance * point, spec.digits),
+            distance_points: distance,
+            raw_lot: (raw_lot * 1e8).round() / 1e8,
+            lot,
+            cumulative_lots: (cumulative_lots * 1e8).round() / 1e8,
+            break_even_price: round_price(
+                entry_price - sign * average_distance * point,
+                spec.digits,
+            ),
+            break_even_distance_points: ((distance - average_distance) * 10.0).round() / 10.0,
+            floating_loss: spec
+                .tick_value
+                .map(|tick| (loss_points * tick * 100.0).round() / 100.0),
+        });
+    }
+
+    let total_lots = rows.last().map(|l| l.cumulative_lots).unwrap_or_default();
+    let max_floating_loss = rows.last().and_then(|l| l.floating_loss);
+    Ok(GridLadder {
+        logic_name: logic.logic_name.clone(),
+        direction: if is_buy { "Buy" } else { "Sell" }.to_string(),
+        grid_points: ladder.grid_points,
+        multiplier: ladder.multiplier,
+        last_lot,
+        levels: rows,
+        total_lots,
+        max_floating_loss,
+    })
+}
+
+/// The concrete ladder `logic` builds on `symbol_spec` from `entry_price`: prices,
+/// lots, break-even and floating loss for the first `levels` orders. `direction`
+/// defaults to the row's own; `grid_unit` is the config's distance unit (points).
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn compute_grid_ladder(
+    logic: LogicConfig,
+    symbol_spec: SymbolSpec,
+    levels: usize,
+    entry_price: f64,
+    direction: Option<String>,
+    grid_unit: Option<DistanceUnit>,
+) -> Result<GridLadder, String> {
+    let is_buy = match direction.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        Some("buy") => true,
+        Some("sell") => false,
+        Some(other) => return Err(format!("Unknown direction '{}'", other)),
+        None => logic.allow_buy || !logic.allow_sell,
+    };
+    grid_ladder(
+        &logic,
+        &symbol_spec,
+        levels,
+        entry_price,
+        is_buy,
+        grid_unit.unwrap_or(DistanceUnit::Points),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol() -> SymbolParams {
+        SymbolParams {
+            symbol: "EURUSD".to_string(),
+            price: 1.1,
+            point: 0.00001,
+            point_value_per_lot: 1.0,
+            margin_per_lot: Some(1100.0),
+            contract_size: None,
+            leverage: None,
+            balance: 10_000.0,
+            volatility_points: Some(50.0),
+            historical_prices: None,
+            steps: Some(500),
+            stop_out_percent: None,
+            seed: Some(1),
+        }
+    }
+
+    fn ladder(multiplier: f64) -> LadderParams {
+        LadderParams {
+            is_buy: true,
+            initial_lot: 0.1,
+            multiplier,
+            grid_points: 300.0,
+            take_profit_points: 100.0,
+            max_orders: 12,
+        }
+    }
+
+    #[test]
+    fn test_simulate_ladder_blows_up_on_one_way_move() {
+        let crash = vec![-100.0; 5_000];
+        let outcome = simulate_ladder(&ladder(2.0), &symbol(), &crash);
+        assert!(outcome.blown_up);
+        // Margin stop-out hits long before the 12-order ladder is filled
+        assert!(outcome.max_orders_reached < 12);
+        assert!(outcome.max_drawdown_percent > 30.0);
+
+        let rally = vec![50.0; 10];
+        let outcome = simulate_ladder(&ladder(2.0), &symbol(), &rally);
+        assert!(!outcome.blown_up);
+        assert_eq!(outcome.max_orders_reached, 1);
+    }
+
+    #[test]
+    fn test_higher_multiplier_raises_drawdown() {
+        let sym = symbol();
+        let mut rng = StdRng::seed_from_u64(3);
+        let paths: Vec<Vec<f64>> = (0..200)
+            .map(|_| generate_path(&sym, &[], 500, &mut rng))
+            .collect();
+        let mean_dd = |m: f64| {
+            paths
+                .iter()
+                .map(|p| simulate_ladder(&ladder(m), &sym, p).max_drawdown_percent)
+                .sum::<f64>()
+                / paths.len() as f64
+        };
+        assert!(mean_dd(2.0) > mean_dd(1.0));
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let d = distribution((1..=100).map(|v| v as f64).collect());
+        assert_eq!(d.max, 100.0);
+        assert_eq!(d.p50, 51.0);
+        assert_eq!(d.mean, 50.5);
+    }
+
+    #[test]
+    fn test_calculate_exposure_levels_and_equity_stop() {
+        let mut config = crate::mt_bridge::create_full_v19_config();
+        config.engines.truncate(1);
+        config.engines[0].max_power_orders = 3;
+        config.engines[0].groups.truncate(1);
+        config.engines[0].groups[0].logics.truncate(1);
+        {
+            let logic = &mut config.engines[0].groups[0].logics[0];
+            logic.initial_lot = 0.1;
+            logic.initial_lot_b = None;
+            logic.multiplier = 2.0;
+            logic.multiplier_b = None;
+            logic.grid = 100.0;
+            logic.grid_b = None;
+        }
+        config.general.risk_management.equity_stop_enabled = true;
+        config.general.risk_management.equity_stop_value = 10.0;
+
+        let report = calculate_exposure_report(
+            &config,
+            &ExposureAccount {
+                balance: 1000.0,
+                leverage: 100.0,
+            },
+            &ExposureSymbol {
+                symbol: None,
+                contract_size: 100_000.0,
+                tick_value: 1.0,
+                price: None,
+            },
+        )
+        .unwrap();
+
+        let group = &report.engines[0].groups[0];
+        let ladder = &group.logics[0];
+        assert_eq!(ladder.direction, "Buy");
+        assert_eq!(ladder.levels.len(), 3);
+        assert!((ladder.worst_case_lots - 0.7).abs() < 1e-9);
+        assert!((ladder.required_margin - 700.0).abs() < 1e-6);
+        // Level 3 opens 200 points away: 0.1 * 200 + 0.2 * 100 = 40
+        assert!((ladder.levels[2].floating_loss - 40.0).abs() < 1e-9);
+        // Equity stop at 10% of 1000: 0.7 * d - 100 = 100 -> d = 285.7
+        let distance = ladder.equity_stop_distance_points.unwrap();
+        assert!((distance - 285.7).abs() < 0.1);
+        assert_eq!(report.equity_stop_amount, Some(100.0));
+    }
+
+    #[test]
+    fn test_advise_position_size_scales_with_compounding() {
+        let mut config = crate::mt_bridge::create_full_v19_config();
+        config.general.compounding_enabled = true;
+        config.general.compounding_type = "Compound_Balance".to_string();
+        config.general.compounding_target = 50.0;
+        config.general.compounding_increase = 100.0;
+        for engine in &mut config.engines {
+            for group in &mut engine.groups {
+                for logic in &mut group.logics {
+                    logic.initial_lot = 0.02;
+                    logic.initial_lot_b = None;
+                    logic.initial_lot_s = None;
+                }
+            }
+        }
+
+        // +120% growth -> two 50% steps -> lots x3
+        let advice =
+            advise_position_size_for(config.clone(), 2200.0, None, 1000.0, None, true).unwrap();
+        assert_eq!(advice.compounding_steps, 2);
+        assert_eq!(advice.scale_factor, 3.0);
+        assert!(advice
+            .rows
+            .iter()
+            .all(|r| (r.recommended_lot - 0.06).abs() < 1e-9));
+        let updated = advice.config.unwrap();
+        assert!((updated.engines[0].groups[0].logics[0].initial_lot - 0.06).abs() < 1e-9);
+
+        config.general.compounding_enabled = false;
+        let advice = advise_position_size_for(config, 2200.0, None, 1000.0, None, false).unwrap();
+        assert_eq!(advice.scale_factor, 1.0);
+        assert!(advice.config.is_none());
+    }
+
+    #[test]
+    fn test_grid_ladder_prices_lots_and_break_even() {
+        let mut logic =
+            crate::mt_bridge::create_full_v19_config().engines[0].groups[0].logics[0].clone();
+        logic.initial_lot = 0.1;
+        logic.initial_lot_b = None;
+        logic.multiplier = 1.3;
+        logic.multiplier_b = None;
+        logic.grid = 300.0;
+        logic.grid_b = None;
+        logic.last_lot = None;
+        logic.last_lot_b = None;
+        let spec = symbols::catalog_spec("EURUSD").unwrap();
+
+        let ladder = grid_ladder(&logic, &spec, 4, 1.1, true, DistanceUnit::Points).unwrap();
+        let prices: Vec<f64> = ladder.levels.iter().map(|l| l.entry_price).collect();
+        assert_eq!(prices, vec![1.1, 1.097, 1.094, 1.091]);
+        // 0.169 and 0.2197 snap to the 0.01 lot step
+        let lots: Vec<f64> = ladder.levels.iter().map(|l| l.lot).collect();
+        assert_eq!(lots, vec![0.1, 0.13, 0.17, 0.22]);
+        assert!((ladder.total_lots - 0.62).abs() < 1e-9);
+        // (1.1 * 0.1 + 1.097 * 0.13) / 0.23
+        assert_eq!(ladder.levels[1].break_even_price, 1.0983);
+        // 0.1 * 900 + 0.13 * 600 + 0.17 * 300 points at $1 per point and lot
+        assert_eq!(ladder.max_floating_loss, Some(219.0));
+
+        logic.last_lot = Some(0.15);
+        let capped = compute_grid_ladder(
+            logic,
+            spec,
+            4,
+            1.1,
+            Some("Sell".to_string()),
+            Some(DistanceUnit::Pips),
+        )
+        .unwrap();
+        assert_eq!(capped.direction, "Sell");
+        assert_eq!(capped.grid_points, 3000.0);
+        assert_eq!(capped.levels[1].entry_price, 1.13);
+        assert!(capped.levels.iter().all(|l| l.lot <= 0.15));
+    }
+}
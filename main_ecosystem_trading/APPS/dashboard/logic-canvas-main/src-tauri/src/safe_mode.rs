@@ -0,0 +1,32 @@
+// ============================================
+// SAFE MODE
+// ============================================
+//
+// Commands for the read-only switch in daavfx_core::safe_mode. While it is
+// on, anything that writes into terminal folders (Common Files exports,
+// ACTIVE.set, the news calendar CSV, MQL fixes, A/B deployments, tester
+// files, terminal launches) fails with a SafeModeBlocked error; vault and
+// analysis commands keep working. MTBridgeState holds the same Arc as the
+// core flag.
+
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use std::sync::atomic::Ordering;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+pub(crate) use daavfx_core::safe_mode::{ensure_writable, flag, is_enabled};
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn get_safe_mode(state: State<'_, MTBridgeState>) -> Result<bool, String> {
+    Ok(state.safe_mode.load(Ordering::SeqCst))
+}
+
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn set_safe_mode(enabled: bool, state: State<'_, MTBridgeState>) -> Result<bool, String> {
+    state.safe_mode.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
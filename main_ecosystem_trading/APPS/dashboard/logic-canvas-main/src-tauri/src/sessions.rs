@@ -0,0 +1,482 @@
+// ============================================
+// SESSION SCHEDULE PREVIEW
+// ============================================
+//
+// Expands the session filters and news windows into a concrete weekly
+// timeline for the calendar view and flags configurations that contradict
+// themselves or can never take effect.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{MTConfig, NewsFilterConfig, SessionConfig, TimeFiltersConfig};
+use crate::news::{filter_events, load_news_cache, parse_event_time, NewsEvent, NewsFilters};
+use crate::timezone::server_offset_for;
+
+const TIMELINE_FORMAT: &str = "%Y-%m-%dT%H:%M";
+const DAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    /// "session" or "news"
+    pub kind: String,
+    pub label: String,
+    /// "Both", "Buy" or "Sell"
+    pub direction: String,
+    pub start: String,
+    pub end: String,
+    pub action: String,
+    #[serde(default)]
+    pub session_number: Option<i32>,
+    /// Set when the priority flags mean this window never takes effect
+    #[serde(default)]
+    pub suppressed_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConflict {
+    /// "error" or "warning"
+    pub severity: String,
+    pub kind: String,
+    pub message: String,
+    pub direction: String,
+    #[serde(default)]
+    pub session_numbers: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSchedule {
+    pub week_start: String,
+    pub week_end: String,
+    pub entries: Vec<ScheduleEntry>,
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+struct Window {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl Window {
+    fn overlaps(&self, other: &Window) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    fn covered_by(&self, others: &[Window]) -> bool {
+        // Walk forward from the start, jumping to the end of any window that covers the cursor
+        let mut cursor = self.start;
+        loop {
+            if cursor >= self.end {
+                return true;
+            }
+            match others
+                .iter()
+                .filter(|w| w.start <= cursor && w.end > cursor)
+                .map(|w| w.end)
+                .max()
+            {
+                Some(end) => cursor = end,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// What a trigger action does to the EA, used to spot contradicting overlaps
+fn action_effect(action: &str) -> &'static str {
+    match action {
+        "TriggerAction_None" => "none",
+        "TriggerAction_StopEA" | "TriggerAction_StopEA_KeepTrades" => "stop",
+        "TriggerAction_StopEA_CloseTrades" | "TriggerAction_CloseAll" => "stop_close",
+        "TriggerAction_PauseEA_KeepTrades" => "pause",
+        "TriggerAction_PauseEA_CloseTrades" => "pause_close",
+        "TriggerAction_KeepEA_CloseTrades" => "close",
+        _ => "default",
+    }
+}
+
+fn session_window(session: &SessionConfig, week_start: NaiveDate) -> Option<Window> {
+    let offset = (session.day - week_start.weekday().num_days_from_sunday() as i32).rem_euclid(7);
+    let date = week_start + Duration::days(offset as i64);
+    let start = date.and_hms_opt(session.start_hour as u32, session.start_minute as u32, 0)?;
+    let mut end = date.and_hms_opt(session.end_hour as u32, session.end_minute as u32, 0)?;
+    if end < start {
+        // Overnight session: ends on the following day
+        end += Duration::days(1);
+    }
+    Some(Window { start, end })
+}
+
+fn validate_session(session: &SessionConfig) -> Option<String> {
+    if !(0..=6).contains(&session.day) {
+        return Some(format!(
+            "day {} is outside 0 (Sunday) to 6 (Saturday)",
+            session.day
+        ));
+    }
+    let hours_ok = (0..=23).contains(&session.start_hour) && (0..=23).contains(&session.end_hour);
+    let minutes_ok =
+        (0..=59).contains(&session.start_minute) && (0..=59).contains(&session.end_minute);
+    if !hours_ok || !minutes_ok {
+        return Some(format!(
+            "invalid time {:02}:{:02}-{:02}:{:02}",
+            session.start_hour, session.start_minute, session.end_hour, session.end_minute
+        ));
+    }
+    None
+}
+
+fn conflict(
+    severity: &str,
+    kind: &str,
+    direction: &str,
+    message: String,
+    sessions: Vec<i32>,
+) -> ScheduleConflict {
+    ScheduleConflict {
+        severity: severity.to_string(),
+        kind: kind.to_string(),
+        message,
+        direction: direction.to_string(),
+        session_numbers: sessions,
+    }
+}
+
+/// News blackout windows ([event - before, event + after]) inside the week
+fn news_windows(
+    news: &NewsFilterConfig,
+    events: &[NewsEvent],
+    week_start: NaiveDate,
+    server_offset_minutes: i32,
+) -> Vec<(Window, NewsEvent)> {
+    let week_from = week_start.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let week_to = week_from + Duration::days(7);
+    filter_events(
+        events,
+        Some(news),
+        &NewsFilters {
+            hours_ahead: Some(24 * 8),
+            include_past_minutes: Some(0),
+            ..NewsFilters::default()
+        },
+        week_from.and_utc(),
+    )
+    .into_iter()
+    .filter_map(|event| {
+        // Event times are UTC; sessions are in broker server time
+        let time = parse_event_time(&event.time)?.naive_utc()
+            + Duration::minutes(server_offset_minutes as i64);
+        let window = Window {
+            start: time - Duration::minutes(news.minutes_before.max(0) as i64),
+            end: time + Duration::minutes(news.minutes_after.max(0) as i64),
+        };
+        (window.end > week_from && window.start < week_to).then_some((window, event))
+    })
+    .collect()
+}
+
+pub fn build_session_schedule(
+    config: &MTConfig,
+    news_events: &[NewsEvent],
+    week_start: NaiveDate,
+    server_offset_minutes: i32,
+) -> SessionSchedule {
+    let general = &config.general;
+    let mut entries = Vec::new();
+    let mut conflicts = Vec::new();
+
+    let scopes: Vec<(&str, &TimeFiltersConfig, &NewsFilterConfig)> = [
+        (
+            "Both",
+            Some(&general.time_filters),
+            Some(&general.news_filter),
+        ),
+        (
+            "Buy",
+            general.time_filters_b.as_ref(),
+            general.news_filter_b.as_ref(),
+        ),
+        (
+            "Sell",
+            general.time_filters_s.as_ref(),
+            general.news_filter_s.as_ref(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(direction, filters, news)| {
+        filters.map(|f| (direction, f, news.unwrap_or(&general.news_filter)))
+    })
+    .collect();
+
+    for (direction, filters, news) in scopes {
+        let priority = &filters.priority_settings;
+        let news_wins = priority.news_filter_overrides_session;
+        let session_wins = priority.session_filter_overrides_news;
+        if news_wins && session_wins {
+            conflicts.push(conflict(
+                "error",
+                "priority",
+                direction,
+                "Both 'news overrides session' and 'session overrides news' are set".to_string(),
+                Vec::new(),
+            ));
+        }
+
+        let mut sessions: Vec<(&SessionConfig, Window)> = Vec::new();
+        for session in filters.sessions.iter().filter(|s| s.enabled) {
+            if let Some(problem) = validate_session(session) {
+                conflicts.push(conflict(
+                    "error",
+                    "invalid",
+                    direction,
+                    format!("Session {}: {}", session.session_number, problem),
+                    vec![session.session_number],
+                ));
+                continue;
+            }
+            if !filters.enabled {
+                conflicts.push(conflict(
+                    "warning",
+                    "never_fires",
+                    direction,
+                    format!(
+                        "Session {} is enabled but time filters are switched off",
+                        session.session_number
+                    ),
+                    vec![session.session_number],
+                ));
+            }
+            if action_effect(&session.action) == "none" {
+                conflicts.push(conflict(
+                    "warning",
+                    "never_fires",
+                    direction,
+                    format!("Session {} has no trigger action", session.session_number),
+                    vec![session.session_number],
+                ));
+            }
+            match session_window(session, week_start) {
+                Some(window) if window.start == window.end => conflicts.push(conflict(
+                    "warning",
+                    "never_fires",
+                    direction,
+                    format!(
+                        "Session {} starts and ends at the same time",
+                        session.session_number
+                    ),
+                    vec![session.session_number],
+                )),
+                Some(window) => sessions.push((session, window)),
+                None => {}
+            }
+        }
+
+        for (i, (a, wa)) in sessions.iter().enumerate() {
+            for (b, wb) in sessions.iter().skip(i + 1) {
+                if wa.overlaps(wb) && action_effect(&a.action) != action_effect(&b.action) {
+                    conflicts.push(conflict(
+                        "warning",
+                        "overlap",
+                        direction,
+                        format!(
+                            "Sessions {} ({}) and {} ({}) overlap on {} with different actions",
+                            a.session_number,
+                            a.action,
+                            b.session_number,
+                            b.action,
+                            DAY_NAMES[a.day as usize]
+                        ),
+                        vec![a.session_number, b.session_number],
+                    ));
+                }
+            }
+        }
+
+        let blackouts = if news.enabled {
+            news_windows(news, news_events, week_start, server_offset_minutes)
+        } else {
+            Vec::new()
+        };
+        let news_only: Vec<Window> = blackouts
+            .iter()
+            .map(|(w, _)| Window {
+                start: w.start,
+                end: w.end,
+            })
+            .collect();
+        let session_only: Vec<Window> = sessions
+            .iter()
+            .map(|(_, w)| Window {
+                start: w.start,
+                end: w.end,
+            })
+            .collect();
+
+        for (session, window) in &sessions {
+            let suppressed = news_wins && !session_wins && window.covered_by(&news_only);
+            if suppressed {
+                conflicts.push(conflict(
+                    "warning",
+                    "never_fires",
+                    direction,
+                    format!(
+                        "Session {} is entirely inside news windows, which take priority",
+                        session.session_number
+                    ),
+                    vec![session.session_number],
+                ));
+            }
+            entries.push(ScheduleEntry {
+                kind: "session".to_string(),
+                label: format!("Session {}", session.session_number),
+                direction: direction.to_string(),
+                start: window.start.format(TIMELINE_FORMAT).to_string(),
+                end: window.end.format(TIMELINE_FORMAT).to_string(),
+                action: session.action.clone(),
+                session_number: Some(session.session_number),
+                suppressed_by: suppressed.then(|| "news".to_string()),
+            });
+        }
+
+        for (window, event) in &blackouts {
+            let suppressed =
+                session_wins && !news_wins && session_only.iter().any(|s| s.overlaps(window));
+            entries.push(ScheduleEntry {
+                kind: "news".to_string(),
+                label: format!("{} {}", event.currency, event.title),
+                direction: direction.to_string(),
+                start: window.start.format(TIMELINE_FORMAT).to_string(),
+                end: window.end.format(TIMELINE_FORMAT).to_string(),
+                action: if news.close_trades {
+                    "CloseTrades".to_string()
+                } else if news.stop_ea {
+                    "StopEA".to_string()
+                } else {
+                    "Alert".to_string()
+                },
+                session_number: None,
+                suppressed_by: suppressed.then(|| "session".to_string()),
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| a.start.cmp(&b.start));
+    SessionSchedule {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: (week_start + Duration::days(6))
+            .format("%Y-%m-%d")
+            .to_string(),
+        entries,
+        conflicts,
+    }
+}
+
+/// Weekly timeline of session filters and cached news windows with conflicts.
+/// News is shifted into server time using the stored timezone of `profile`.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn preview_session_schedule(
+    config: MTConfig,
+    week_start: String,
+    profile: Option<String>,
+) -> Result<SessionSchedule, String> {
+    let week_start = NaiveDate::parse_from_str(week_start.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid week start '{}': {}", week_start, e))?;
+    let events = load_news_cache().map(|c| c.events).unwrap_or_default();
+    let server_offset = server_offset_for(profile.as_deref(), week_start);
+    Ok(build_session_schedule(
+        &config,
+        &events,
+        week_start,
+        server_offset,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(
+        number: i32,
+        day: i32,
+        start: (i32, i32),
+        end: (i32, i32),
+        action: &str,
+    ) -> SessionConfig {
+        SessionConfig {
+            session_number: number,
+            enabled: true,
+            day,
+            start_hour: start.0,
+            start_minute: start.1,
+            end_hour: end.0,
+            end_minute: end.1,
+            action: action.to_string(),
+            ..SessionConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_flags_overlaps_and_news_priority() {
+        let mut config = MTConfig::default();
+        config.general.time_filters.enabled = true;
+        config
+            .general
+            .time_filters
+            .priority_settings
+            .news_filter_overrides_session = true;
+        config.general.time_filters.sessions = vec![
+            session(1, 1, (8, 0), (10, 0), "TriggerAction_StopEA"),
+            session(2, 1, (9, 0), (11, 0), "TriggerAction_KeepEA_CloseTrades"),
+            session(3, 3, (13, 0), (13, 30), "TriggerAction_StopEA"),
+            session(4, 5, (22, 0), (2, 0), "TriggerAction_StopEA"),
+        ];
+        config.general.news_filter.enabled = true;
+        config.general.news_filter.minutes_before = 30;
+        config.general.news_filter.minutes_after = 30;
+        config.general.news_filter.countries = "US".to_string();
+        let events = vec![NewsEvent {
+            time: "2026-03-04T13:15:00+00:00".to_string(),
+            currency: "USD".to_string(),
+            impact: 3,
+            title: "CPI".to_string(),
+        }];
+
+        // Sunday 2026-03-01
+        let week = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let schedule = build_session_schedule(&config, &events, week, 0);
+
+        let overnight = schedule
+            .entries
+            .iter()
+            .find(|e| e.session_number == Some(4))
+            .unwrap();
+        assert_eq!(overnight.start, "2026-03-06T22:00");
+        assert_eq!(overnight.end, "2026-03-07T02:00");
+
+        assert!(schedule
+            .conflicts
+            .iter()
+            .any(|c| c.kind == "overlap" && c.session_numbers == vec![1, 2]));
+        let wednesday = schedule
+            .entries
+            .iter()
+            .find(|e| e.session_number == Some(3))
+            .unwrap();
+        assert_eq!(wednesday.suppressed_by.as_deref(), Some("news"));
+        assert!(schedule
+            .entries
+            .iter()
+            .any(|e| e.kind == "news" && e.start == "2026-03-04T12:45"));
+    }
+}
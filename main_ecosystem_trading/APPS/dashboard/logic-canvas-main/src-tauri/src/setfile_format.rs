@@ -0,0 +1,432 @@
+// ============================================
+// SETFILE FORMAT REGISTRY
+// ============================================
+//
+// Every .set layout the dashboard reads or writes sits behind SetfileFormat:
+// the flat pre-V17.04 and V17 layouts, the massive v19 layout and the
+// [TesterInputs] section of an MT5 tester .ini. Import detects the format from
+// the content unless one is named; export writes V17 unless told otherwise.
+// A new layout is one more implementation added to FORMATS.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use daavfx_core::fs::decode_setfile_bytes;
+
+use crate::mt_bridge::{
+    build_config_from_flat_values, build_config_from_v19_values, parse_v19_key,
+    read_setfile_values, render_massive_v19_setfile_lines, render_set_file_lines,
+    sanitize_and_validate_path, MTConfig,
+};
+use crate::TaskHandle;
+
+/// Format export_set_file writes when none is named
+pub const DEFAULT_EXPORT_FORMAT: &str = "v17";
+
+/// Everything a renderer may need besides the config. Redaction and deterministic
+/// output are applied by the caller, around the render.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub platform: String,
+    /// Target path; the flat formats embed it as gInput_ConfigFileName
+    pub file_path: String,
+    pub include_optimization_hints: bool,
+    /// "BUY", "SELL" or "BOTH" (default)
+    pub trade_direction: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub comments: Option<String>,
+}
+
+pub trait SetfileFormat: Sync {
+    /// Stable id used by commands and the frontend
+    fn id(&self) -> &'static str;
+
+    /// Other names accepted for this format
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn label(&self) -> &'static str;
+
+    fn extension(&self) -> &'static str {
+        "set"
+    }
+
+    /// Whether `content` is written in this format. Formats are tried in registry
+    /// order, so a detector only has to rule out the formats listed after it.
+    fn detect(&self, content: &str) -> bool;
+
+    fn render(
+        &self,
+        config: MTConfig,
+        options: &RenderOptions,
+        task: &TaskHandle,
+    ) -> Result<Vec<String>, String>;
+
+    fn parse(&self, content: &str, task: &TaskHandle) -> Result<MTConfig, String>;
+}
+
+/// Most specific first: detection stops at the first match and Legacy takes the rest
+static FORMATS: &[&dyn SetfileFormat] = &[&TesterIni, &V19Massive, &V17, &Legacy];
+
+/// Look a format up by id or alias, case-insensitively
+pub fn find_format(name: &str) -> Result<&'static dyn SetfileFormat, String> {
+    let name = name.trim();
+    FORMATS
+        .iter()
+        .copied()
+        .find(|format| {
+            format.id().eq_ignore_ascii_case(name)
+                || format
+                    .aliases()
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| format!("Unknown set file format: {}", name))
+}
+
+pub fn detect_format(content: &str) -> &'static dyn SetfileFormat {
+    FORMATS
+        .iter()
+        .copied()
+        .find(|format| format.detect(content))
+        .unwrap_or(&Legacy)
+}
+
+/// The named format, or the detected one when `name` is unset or blank
+pub fn resolve_format(
+    name: Option<&str>,
+    content: &str,
+) -> Result<&'static dyn SetfileFormat, String> {
+    match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => find_format(name),
+        None => Ok(detect_format(content)),
+    }
+}
+
+/// Keys of the key=value lines, comments skipped
+fn setfile_keys(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(';'))
+        .filter_map(|line| line.split_once('=').map(|(key, _)| key.trim()))
+}
+
+/// Inputs the EA gained in V17.04: group reverse/hedge controls, trail step
+/// mode/cycle/balance and the per-logic reverse/hedge block
+fn is_v17_04_key(key: &str) -> bool {
+    let Some(rest) = key.strip_prefix("gInput_") else {
+        return false;
+    };
+    if ["TrailStepMode", "TrailStepCycle", "TrailStepBalance"]
+        .iter()
+        .any(|prefix| rest.starts_with(prefix))
+    {
+        return true;
+    }
+
+    // gInput_Group3_HedgeMode, gInput_G3_BP_ReverseEnabled, gInput_G3_Scale_BP_Hedge
+    let Some(rest) = rest.strip_prefix('G') else {
+        return false;
+    };
+    let rest = rest.strip_prefix("roup").unwrap_or(rest);
+    rest.starts_with(|c: char| c.is_ascii_digit())
+        && [
+            "_ReverseMode",
+            "_HedgeMode",
+            "_HedgeReference",
+            "_EntryDelayBars",
+            "_ReverseEnabled",
+            "_HedgeEnabled",
+            "_ReverseReference",
+            "_Reverse",
+            "_Hedge",
+        ]
+        .iter()
+        .any(|suffix| rest.ends_with(suffix))
+}
+
+/// Flat gInput_ layout from before V17.04. Read like V17 (absent inputs take their
+/// defaults); written as the V17 layout without the V17.04 inputs.
+pub struct Legacy;
+
+impl SetfileFormat for Legacy {
+    fn id(&self) -> &'static str {
+        "legacy"
+    }
+
+    fn label(&self) -> &'static str {
+        "Legacy (pre-V17.04)"
+    }
+
+    fn detect(&self, _content: &str) -> bool {
+        true
+    }
+
+    fn render(
+        &self,
+        config: MTConfig,
+        options: &RenderOptions,
+        task: &TaskHandle,
+    ) -> Result<Vec<String>, String> {
+        Ok(V17
+            .render(config, options, task)?
+            .into_iter()
+            .filter(|line| {
+                !line
+                    .split_once('=')
+                    .is_some_and(|(key, _)| is_v17_04_key(key.trim()))
+            })
+            .collect())
+    }
+
+    fn parse(&self, content: &str, task: &TaskHandle) -> Result<MTConfig, String> {
+        build_config_from_flat_values(read_setfile_values(content, task)?)
+    }
+}
+
+/// Flat gInput_ layout of the V17.04+ EA
+pub struct V17;
+
+impl SetfileFormat for V17 {
+    fn id(&self) -> &'static str {
+        "v17"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["set", "flat"]
+    }
+
+    fn label(&self) -> &'static str {
+        "V17"
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        setfile_keys(content).any(is_v17_04_key)
+    }
+
+    fn render(
+        &self,
+        config: MTConfig,
+        options: &RenderOptions,
+        _task: &TaskHandle,
+    ) -> Result<Vec<String>, String> {
+        Ok(render_set_file_lines(
+            &config,
+            &options.file_path,
+            &options.platform,
+            options.include_optimization_hints,
+            options.trade_direction.as_deref(),
+            options.tags.clone(),
+            options.comments.clone(),
+        ))
+    }
+
+    fn parse(&self, content: &str, task: &TaskHandle) -> Result<MTConfig, String> {
+        build_config_from_flat_values(read_setfile_values(content, task)?)
+    }
+}
+
+/// gInput_{Group}_{Engine}{Logic}_{Direction}_{Param}
+pub struct V19Massive;
+
+impl SetfileFormat for V19Massive {
+    fn id(&self) -> &'static str {
+        "massive_v19"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["massive", "v19"]
+    }
+
+    fn label(&self) -> &'static str {
+        "Massive v19"
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        setfile_keys(content).any(|key| parse_v19_key(key).is_some())
+    }
+
+    fn render(
+        &self,
+        config: MTConfig,
+        options: &RenderOptions,
+        task: &TaskHandle,
+    ) -> Result<Vec<String>, String> {
+        render_massive_v19_setfile_lines(config, &options.platform, task)
+    }
+
+    fn parse(&self, content: &str, task: &TaskHandle) -> Result<MTConfig, String> {
+        build_config_from_v19_values(content, read_setfile_values(content, task)?)
+    }
+}
+
+/// The [TesterInputs] section of an MT5 tester .ini, carrying the massive v19 inputs.
+/// The [Tester] section (symbol, dates, model) comes from backtest::render_tester_ini.
+pub struct TesterIni;
+
+const TESTER_INPUTS_HEADER: &str = "[TesterInputs]";
+
+impl TesterIni {
+    /// The inputs as plain .set content: "key=value||start||step||stop||Y" -> "key=value"
+    fn inputs_as_setfile(content: &str) -> String {
+        content
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| !line.eq_ignore_ascii_case(TESTER_INPUTS_HEADER))
+            .skip(1)
+            .take_while(|line| !line.starts_with('['))
+            .map(|line| match line.split_once("||") {
+                Some((input, _)) if !line.starts_with(';') => input.trim(),
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl SetfileFormat for TesterIni {
+    fn id(&self) -> &'static str {
+        "tester_ini"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ini", "tester"]
+    }
+
+    fn label(&self) -> &'static str {
+        "MT5 tester inputs (.ini)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "ini"
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        content
+            .lines()
+            .any(|line| line.trim().eq_ignore_ascii_case(TESTER_INPUTS_HEADER))
+    }
+
+    fn render(
+        &self,
+        config: MTConfig,
+        options: &RenderOptions,
+        task: &TaskHandle,
+    ) -> Result<Vec<String>, String> {
+        let mut lines = vec![TESTER_INPUTS_HEADER.to_string()];
+        for line in V19Massive.render(config, options, task)? {
+            if line.starts_with(';') || line.trim().is_empty() {
+                continue;
+            }
+            // Fixed value, optimization off: value||start||step||stop||N
+            if let Some((key, value)) = line.split_once('=') {
+                lines.push(format!("{}={}||{}||0||{}||N", key, value, value, value));
+            }
+        }
+        Ok(lines)
+    }
+
+    fn parse(&self, content: &str, task: &TaskHandle) -> Result<MTConfig, String> {
+        let inputs = Self::inputs_as_setfile(content);
+        if inputs.trim().is_empty() {
+            return Err("Tester .ini has no [TesterInputs] entries".to_string());
+        }
+        detect_format(&inputs).parse(&inputs, task)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetfileFormatInfo {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub label: String,
+    pub extension: String,
+}
+
+/// Registered setfile formats, for the import/export format pickers
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_setfile_formats() -> Result<Vec<SetfileFormatInfo>, String> {
+    Ok(FORMATS
+        .iter()
+        .map(|format| SetfileFormatInfo {
+            id: format.id().to_string(),
+            aliases: format.aliases().iter().map(|a| a.to_string()).collect(),
+            label: format.label().to_string(),
+            extension: format.extension().to_string(),
+        })
+        .collect())
+}
+
+/// Id of the format import_set_file would read `file_path` as
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn detect_setfile_format(file_path: String) -> Result<String, String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(&file_path))?;
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let content = decode_setfile_bytes(bytes)?;
+    Ok(detect_format(&content).id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let legacy = "; DAAVFX Configuration Export\ngInput_MagicNumber=777\ngInput_Grid_AP1=300";
+        let v17 = format!("{}\ngInput_Group1_ReverseMode=0", legacy);
+        let v19 = "gInput_MagicNumber=777\ngInput_1_AP_Buy_InitialLot=0.01";
+        let ini = "[Tester]\nExpert=DAAVFX.ex5\n[TesterInputs]\ngInput_1_AP_Buy_InitialLot=0.01||0.01||0||0.01||N";
+
+        assert_eq!(detect_format(legacy).id(), "legacy");
+        assert_eq!(detect_format(&v17).id(), "v17");
+        assert_eq!(detect_format(v19).id(), "massive_v19");
+        assert_eq!(detect_format(ini).id(), "tester_ini");
+        assert_eq!(detect_format("").id(), "legacy");
+
+        assert_eq!(
+            TesterIni::inputs_as_setfile(ini),
+            "gInput_1_AP_Buy_InitialLot=0.01"
+        );
+    }
+
+    #[test]
+    fn test_find_format() {
+        assert_eq!(find_format("V19").unwrap().id(), "massive_v19");
+        assert_eq!(find_format(" set ").unwrap().id(), "v17");
+        assert_eq!(resolve_format(Some(""), "").unwrap().id(), "legacy");
+        assert!(find_format("xml").is_err());
+
+        // No id or alias may be shadowed by an earlier format
+        for format in FORMATS {
+            for name in format.aliases().iter().chain([&format.id()]) {
+                assert_eq!(find_format(name).unwrap().id(), format.id(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_v17_04_keys() {
+        for key in [
+            "gInput_Group3_HedgeMode",
+            "gInput_Group12_EntryDelayBars",
+            "gInput_G3_BP_ReverseEnabled",
+            "gInput_G3_Scale_BP_Hedge",
+            "gInput_TrailStepMode_AP1",
+            "gInput_TrailStepCycle2_AP1",
+        ] {
+            assert!(is_v17_04_key(key), "{}", key);
+        }
+        for key in [
+            "gInput_MagicNumberHedgeBase",
+            "gInput_GroupPowerStart_P2_Buy",
+            "gInput_TrailStepMethod_AP1",
+            "gInput_1_AP_Buy_InitialLot",
+        ] {
+            assert!(!is_v17_04_key(key), "{}", key);
+        }
+    }
+}
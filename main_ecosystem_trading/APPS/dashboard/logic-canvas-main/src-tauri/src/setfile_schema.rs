@@ -0,0 +1,559 @@
+// ============================================
+// SETFILE KEY SCHEMA
+// ============================================
+//
+// Declarative descriptors for the per-logic keys of the flat (v17) .set file.
+// LogicWriter takes key names and number formatting from here, and
+// build_logic_config takes the names it accepts and its fallbacks from the same
+// descriptor, so a key spelled one way on export cannot be looked up another
+// way on import. The table also drives the key reference served to the UI.
+
+use daavfx_core::config_enums::{self, TrailStepMethod, TrailStepMode};
+use serde::{Deserialize, Serialize};
+
+/// How a descriptor's value is written and read back
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ValueKind {
+    /// Fixed-point number with this many decimals
+    Float(usize),
+    Int,
+    /// Written as 1/0, read back from 1/0 or true/false
+    Bool,
+    /// UI label written as its numeric config_enums code
+    Code(fn(&str) -> i32),
+    /// Written verbatim
+    Text,
+}
+
+impl ValueKind {
+    fn type_name(self) -> &'static str {
+        match self {
+            ValueKind::Float(_) => "float",
+            ValueKind::Int => "int",
+            ValueKind::Bool => "bool",
+            ValueKind::Code(_) => "enum",
+            ValueKind::Text => "text",
+        }
+    }
+}
+
+/// Where the group, logic suffix and direction go in the key name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyPattern {
+    /// `gInput_{name}_{suffix}`, e.g. `gInput_TrailStepCycle_AP1`
+    Logic,
+    /// `gInput_{name}_{suffix}` plus optional `_B`/`_S` overrides; `positive` skips
+    /// zero overrides as well as negative ones
+    LogicSided { positive: bool },
+    /// `gInput_G{group}_{short}_{name}`, e.g. `gInput_G1_AP_StrategyType`
+    GroupLogic,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KeySpec {
+    pub section: &'static str,
+    /// LogicConfig field the key carries; `_b`/`_s` fields map to the side keys
+    pub field: &'static str,
+    /// Name as written by the exporter
+    pub name: &'static str,
+    /// Names the parser accepts, in lookup order; always includes `name`
+    pub lookup: &'static [&'static str],
+    pub pattern: KeyPattern,
+    pub kind: ValueKind,
+    /// Value the parser falls back to when no lookup name is present
+    pub default: &'static str,
+    /// Only written for group 1 (Initial/Last lot)
+    pub group1_only: bool,
+    pub description: &'static str,
+}
+
+impl KeySpec {
+    pub(crate) fn key(&self, group: u8, suffix: &str, short: &str) -> String {
+        match self.pattern {
+            KeyPattern::GroupLogic => format!("gInput_G{}_{}_{}", group, short, self.name),
+            KeyPattern::Logic | KeyPattern::LogicSided { .. } => {
+                format!("gInput_{}_{}", self.name, suffix)
+            }
+        }
+    }
+
+    /// Key name with `{group}`, `{suffix}` and `{short}` placeholders
+    fn key_template(&self) -> String {
+        match self.pattern {
+            KeyPattern::GroupLogic => format!("gInput_G{{group}}_{{short}}_{}", self.name),
+            KeyPattern::Logic | KeyPattern::LogicSided { .. } => {
+                format!("gInput_{}_{{suffix}}", self.name)
+            }
+        }
+    }
+
+    pub(crate) fn encode_f64(&self, value: f64) -> String {
+        match self.kind {
+            ValueKind::Float(decimals) => format!("{:.*}", decimals, value),
+            ValueKind::Int => format!("{}", value as i64),
+            ValueKind::Bool => encode_bool(value != 0.0).to_string(),
+            ValueKind::Code(_) | ValueKind::Text => value.to_string(),
+        }
+    }
+
+    pub(crate) fn encode_str(&self, value: &str) -> String {
+        match self.kind {
+            ValueKind::Code(encode) => encode(value).to_string(),
+            _ => value.to_string(),
+        }
+    }
+
+    pub(crate) fn line_f64(&self, group: u8, suffix: &str, short: &str, value: f64) -> String {
+        format!(
+            "{}={}",
+            self.key(group, suffix, short),
+            self.encode_f64(value)
+        )
+    }
+
+    pub(crate) fn line_str(&self, group: u8, suffix: &str, short: &str, value: &str) -> String {
+        format!(
+            "{}={}",
+            self.key(group, suffix, short),
+            self.encode_str(value)
+        )
+    }
+
+    pub(crate) fn line_bool(&self, group: u8, suffix: &str, short: &str, value: bool) -> String {
+        format!("{}={}", self.key(group, suffix, short), encode_bool(value))
+    }
+
+    /// `_B`/`_S` override lines for a LogicSided key, skipping unset or out-of-range values
+    pub(crate) fn side_lines(
+        &self,
+        suffix: &str,
+        buy: Option<f64>,
+        sell: Option<f64>,
+    ) -> Vec<String> {
+        let KeyPattern::LogicSided { positive } = self.pattern else {
+            return Vec::new();
+        };
+        [("B", buy), ("S", sell)]
+            .into_iter()
+            .filter_map(|(side, value)| {
+                let v = value?;
+                let keep = if positive { v > 0.0 } else { v >= 0.0 };
+                keep.then(|| {
+                    format!(
+                        "gInput_{}_{}_{}={}",
+                        self.name,
+                        suffix,
+                        side,
+                        self.encode_f64(v)
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn decode_bool(raw: &str) -> bool {
+    raw == "1" || raw.eq_ignore_ascii_case("true")
+}
+
+fn encode_bool(value: bool) -> i32 {
+    if value {
+        1
+    } else {
+        0
+    }
+}
+
+const SECTION_BASE: &str = "Base";
+const SECTION_STRATEGY: &str = "Strategy";
+const SECTION_TRAIL: &str = "Trail";
+const SECTION_TRAIL_STEP: &str = "Trail step (V17.04+)";
+
+pub(crate) const INITIAL_LOT: KeySpec = KeySpec {
+    section: SECTION_BASE,
+    field: "initial_lot",
+    name: "Initial_loT",
+    lookup: &["InitialLot", "Initial_loT"],
+    pattern: KeyPattern::LogicSided { positive: true },
+    kind: ValueKind::Float(2),
+    default: "0.02",
+    group1_only: true,
+    description: "Lot size of the first order",
+};
+
+pub(crate) const MULTIPLIER: KeySpec = KeySpec {
+    section: SECTION_BASE,
+    field: "multiplier",
+    name: "Mult",
+    lookup: &["Multiplier", "Mult"],
+    pattern: KeyPattern::LogicSided { positive: true },
+    kind: ValueKind::Float(2),
+    default: "1.2",
+    group1_only: false,
+    description: "Lot multiplier applied to each grid level",
+};
+
+pub(crate) const GRID: KeySpec = KeySpec {
+    section: SECTION_BASE,
+    field: "grid",
+    name: "Grid",
+    lookup: &["Grid"],
+    pattern: KeyPattern::LogicSided { positive: false },
+    kind: ValueKind::Float(1),
+    default: "300",
+    group1_only: false,
+    description: "Distance between grid orders, in points",
+};
+
+pub(crate) const STRATEGY_TYPE: KeySpec = KeySpec {
+    section: SECTION_STRATEGY,
+    field: "strategy_type",
+    name: "StrategyType",
+    lookup: &["StrategyType"],
+    pattern: KeyPattern::GroupLogic,
+    kind: ValueKind::Text,
+    default: "Trail",
+    group1_only: false,
+    description: "Exit strategy of the logic",
+};
+
+pub(crate) const TRADING_MODE: KeySpec = KeySpec {
+    section: SECTION_STRATEGY,
+    field: "trading_mode",
+    name: "TradingMode",
+    lookup: &["TradingMode"],
+    pattern: KeyPattern::GroupLogic,
+    kind: ValueKind::Text,
+    default: "Counter Trend",
+    group1_only: false,
+    description: "Entry direction relative to the trend",
+};
+
+pub(crate) const ALLOW_BUY: KeySpec = KeySpec {
+    section: SECTION_STRATEGY,
+    field: "allow_buy",
+    name: "AllowBuy",
+    lookup: &["AllowBuy"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Bool,
+    default: "1",
+    group1_only: false,
+    description: "Open buy orders; overridden by the export trade direction",
+};
+
+pub(crate) const ALLOW_SELL: KeySpec = KeySpec {
+    section: SECTION_STRATEGY,
+    field: "allow_sell",
+    name: "AllowSell",
+    lookup: &["AllowSell"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Bool,
+    default: "1",
+    group1_only: false,
+    description: "Open sell orders; overridden by the export trade direction",
+};
+
+pub(crate) const TRAIL_METHOD: KeySpec = KeySpec {
+    section: SECTION_TRAIL,
+    field: "trail_method",
+    name: "Trail",
+    lookup: &["TrailMethod", "Trail"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Text,
+    default: "Points",
+    group1_only: false,
+    description: "Trailing method",
+};
+
+pub(crate) const TRAIL_VALUE: KeySpec = KeySpec {
+    section: SECTION_TRAIL,
+    field: "trail_value",
+    name: "TrailValue",
+    lookup: &["TrailValue"],
+    pattern: KeyPattern::LogicSided { positive: false },
+    kind: ValueKind::Float(1),
+    default: "3000",
+    group1_only: false,
+    description: "Trailing distance",
+};
+
+pub(crate) const TRAIL_START: KeySpec = KeySpec {
+    section: SECTION_TRAIL,
+    field: "trail_start",
+    name: "Trail_Start",
+    lookup: &["TrailStart", "Trail_Start"],
+    pattern: KeyPattern::LogicSided { positive: false },
+    kind: ValueKind::Float(1),
+    default: "1",
+    group1_only: false,
+    description: "Profit at which trailing starts",
+};
+
+pub(crate) const TRAIL_STEP: KeySpec = KeySpec {
+    section: SECTION_TRAIL,
+    field: "trail_step",
+    name: "TrailStep",
+    lookup: &["TrailStep"],
+    pattern: KeyPattern::LogicSided { positive: false },
+    kind: ValueKind::Float(1),
+    default: "1500",
+    group1_only: false,
+    description: "Step by which the trailing stop moves",
+};
+
+pub(crate) const TRAIL_STEP_METHOD: KeySpec = KeySpec {
+    section: SECTION_TRAIL,
+    field: "trail_step_method",
+    name: "TrailStepMethod",
+    lookup: &["TrailStepMethod"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Code(config_enums::encode::<TrailStepMethod>),
+    default: "Step_Points",
+    group1_only: false,
+    description: "How the trail step is measured",
+};
+
+pub(crate) const TRAIL_STEP_MODE: KeySpec = KeySpec {
+    section: SECTION_TRAIL_STEP,
+    field: "trail_step_mode",
+    name: "TrailStepMode",
+    lookup: &["TrailStepMode"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Code(config_enums::encode::<TrailStepMode>),
+    default: "TrailStepMode_Auto",
+    group1_only: false,
+    description: "When the trail step advances",
+};
+
+pub(crate) const TRAIL_STEP_CYCLE: KeySpec = KeySpec {
+    section: SECTION_TRAIL_STEP,
+    field: "trail_step_cycle",
+    name: "TrailStepCycle",
+    lookup: &["TrailStepCycle"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Int,
+    default: "1",
+    group1_only: false,
+    description: "Cycles between trail step advances",
+};
+
+pub(crate) const TRAIL_STEP_BALANCE: KeySpec = KeySpec {
+    section: SECTION_TRAIL_STEP,
+    field: "trail_step_balance",
+    name: "TrailStepBalance",
+    lookup: &["TrailStepBalance"],
+    pattern: KeyPattern::Logic,
+    kind: ValueKind::Float(2),
+    default: "0",
+    group1_only: false,
+    description: "Balance threshold for the trail step",
+};
+
+/// Every described key, in export order
+pub(crate) const LOGIC_KEYS: &[&KeySpec] = &[
+    &INITIAL_LOT,
+    &STRATEGY_TYPE,
+    &TRADING_MODE,
+    &ALLOW_BUY,
+    &ALLOW_SELL,
+    &MULTIPLIER,
+    &GRID,
+    &TRAIL_METHOD,
+    &TRAIL_VALUE,
+    &TRAIL_START,
+    &TRAIL_STEP,
+    &TRAIL_STEP_METHOD,
+    &TRAIL_STEP_MODE,
+    &TRAIL_STEP_CYCLE,
+    &TRAIL_STEP_BALANCE,
+];
+
+/// Key written for a LogicConfig field, e.g. `grid_b` -> `gInput_Grid_AP1_B`
+pub(crate) fn logic_field_key(field: &str, group: u8, suffix: &str, short: &str) -> Option<String> {
+    if let Some(spec) = LOGIC_KEYS.iter().find(|spec| spec.field == field) {
+        return Some(spec.key(group, suffix, short));
+    }
+    let (base, side) = field
+        .strip_suffix("_b")
+        .map(|base| (base, "B"))
+        .or_else(|| field.strip_suffix("_s").map(|base| (base, "S")))?;
+    LOGIC_KEYS
+        .iter()
+        .find(|spec| spec.field == base && matches!(spec.pattern, KeyPattern::LogicSided { .. }))
+        .map(|spec| format!("{}_{}", spec.key(group, suffix, short), side))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetfileKeyInfo {
+    pub section: String,
+    pub field: String,
+    pub key: String,
+    pub side_keys: Vec<String>,
+    pub accepted_names: Vec<String>,
+    pub value_type: String,
+    pub default: String,
+    pub group1_only: bool,
+    pub description: String,
+}
+
+fn key_info(spec: &KeySpec) -> SetfileKeyInfo {
+    let side_keys = match spec.pattern {
+        KeyPattern::LogicSided { .. } => vec![
+            format!("gInput_{}_{{suffix}}_B", spec.name),
+            format!("gInput_{}_{{suffix}}_S", spec.name),
+        ],
+        _ => Vec::new(),
+    };
+    SetfileKeyInfo {
+        section: spec.section.to_string(),
+        field: spec.field.to_string(),
+        key: spec.key_template(),
+        side_keys,
+        accepted_names: spec.lookup.iter().map(|n| n.to_string()).collect(),
+        value_type: spec.kind.type_name().to_string(),
+        default: spec.default.to_string(),
+        group1_only: spec.group1_only,
+        description: spec.description.to_string(),
+    }
+}
+
+/// Markdown reference of every described key, grouped by section
+pub(crate) fn render_key_reference() -> String {
+    let mut out = vec![
+        "# Setfile key reference".to_string(),
+        String::new(),
+        "`{suffix}` is the logic suffix (e.g. `AP1`), `{short}` the short logic name (e.g. `AP`)."
+            .to_string(),
+    ];
+    let mut section = "";
+    for spec in LOGIC_KEYS {
+        if spec.section != section {
+            section = spec.section;
+            out.push(String::new());
+            out.push(format!("## {}", section));
+            out.push(String::new());
+            out.push("| Key | Type | Default | Accepted names | Description |".to_string());
+            out.push("|---|---|---|---|---|".to_string());
+        }
+        let info = key_info(spec);
+        let mut key = format!("`{}`", info.key);
+        for side in &info.side_keys {
+            key.push_str(&format!("<br>`{}`", side));
+        }
+        let mut description = info.description;
+        if info.group1_only {
+            description.push_str(" (group 1 only)");
+        }
+        out.push(format!(
+            "| {} | {} | `{}` | {} | {} |",
+            key,
+            info.value_type,
+            info.default,
+            info.accepted_names.join(", "),
+            description
+        ));
+    }
+    out.join("\n")
+}
+
+/// Described setfile keys, for the key reference panel
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn list_setfile_keys() -> Result<Vec<SetfileKeyInfo>, String> {
+    Ok(LOGIC_KEYS.iter().map(|spec| key_info(spec)).collect())
+}
+
+/// The key reference as markdown, for saving next to exported setfiles
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_setfile_key_reference() -> Result<String, String> {
+    Ok(render_key_reference())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_lookup_includes_exported_name() {
+        let mut names = HashSet::new();
+        for spec in LOGIC_KEYS {
+            assert!(
+                spec.lookup.contains(&spec.name),
+                "{} not accepted on import",
+                spec.name
+            );
+            assert!(names.insert(spec.name), "{} described twice", spec.name);
+        }
+    }
+
+    #[test]
+    fn test_defaults_decode_as_their_kind() {
+        for spec in LOGIC_KEYS {
+            match spec.kind {
+                ValueKind::Float(_) => {
+                    assert!(spec.default.parse::<f64>().is_ok(), "{}", spec.name)
+                }
+                ValueKind::Int | ValueKind::Bool => {
+                    assert!(spec.default.parse::<i32>().is_ok(), "{}", spec.name)
+                }
+                ValueKind::Code(_) | ValueKind::Text => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_patterns() {
+        assert_eq!(
+            MULTIPLIER.line_f64(2, "AP2", "AP", 1.5),
+            "gInput_Mult_AP2=1.50"
+        );
+        assert_eq!(
+            GRID.line_f64(1, "AP1", "AP", 300.0),
+            "gInput_Grid_AP1=300.0"
+        );
+        assert_eq!(
+            STRATEGY_TYPE.line_str(3, "AP3", "AP", "Trail"),
+            "gInput_G3_AP_StrategyType=Trail"
+        );
+        assert_eq!(
+            ALLOW_BUY.line_bool(1, "AP1", "AP", true),
+            "gInput_AllowBuy_AP1=1"
+        );
+        assert_eq!(
+            INITIAL_LOT.side_lines("AP1", Some(0.0), Some(0.05)),
+            vec!["gInput_Initial_loT_AP1_S=0.05".to_string()]
+        );
+        assert_eq!(
+            GRID.side_lines("AP1", Some(0.0), None),
+            vec!["gInput_Grid_AP1_B=0.0".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_logic_field_key() {
+        assert_eq!(
+            logic_field_key("grid", 1, "AP1", "AP").as_deref(),
+            Some("gInput_Grid_AP1")
+        );
+        assert_eq!(
+            logic_field_key("trail_value_s", 2, "AP2", "AP").as_deref(),
+            Some("gInput_TrailValue_AP2_S")
+        );
+        assert_eq!(logic_field_key("allow_buy_b", 1, "AP1", "AP"), None);
+        assert_eq!(logic_field_key("close_targets", 1, "AP1", "AP"), None);
+    }
+
+    #[test]
+    fn test_key_reference_lists_every_key() {
+        let reference = render_key_reference();
+        for spec in LOGIC_KEYS {
+            assert!(
+                reference.contains(spec.name),
+                "{} missing from reference",
+                spec.name
+            );
+        }
+        assert!(reference.contains("`gInput_G{group}_{short}_StrategyType`"));
+    }
+}
@@ -0,0 +1,1088 @@
+// ============================================
+// SETFILE SECTION WRITERS
+// ============================================
+//
+// The flat (v17) .set file is assembled from one writer per section: general
+// settings and compounding, risk management, clean math, news filter, time
+// filters, then an EngineWriter per engine that hands each logic to a
+// LogicWriter. Writers only append "key=value" and "; ..." lines, so the
+// massive v19 exporter, render_set_preview and line-based dumps such as CSV
+// can pick the sections they need, and each one can be tested on its own.
+
+use daavfx_core::config_enums::ConfigEnum;
+use daavfx_core::setfile_keys::{
+    get_logic_global_key, get_logic_short, get_logic_start_key, get_logic_suffix,
+    trigger_action_to_int,
+};
+
+use crate::mt_bridge::{
+    EngineConfig, GeneralConfig, GroupConfig, LogicConfig, NewsFilterConfig, RiskManagementConfig,
+    TimeFiltersConfig,
+};
+use crate::setfile_schema as schema;
+
+/// EA code of a trigger action; names this EA build doesn't know mean
+/// "stop EA, keep trades"
+pub(crate) fn trigger_action_code(action: &str) -> i32 {
+    trigger_action_to_int(action).unwrap_or(2)
+}
+
+pub(crate) trait SectionWriter {
+    /// Append this section's lines, header included, without a trailing blank line
+    fn write(&self, lines: &mut Vec<String>);
+
+    fn render(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.write(&mut lines);
+        lines
+    }
+}
+
+/// Which exporter a shared section is written for; the massive v19 file spells a few
+/// keys differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetfileLayout {
+    Flat,
+    Massive,
+}
+
+/// Account-wide section or one of the massive v19 per-direction copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    Both,
+    Buy,
+    Sell,
+}
+
+impl Side {
+    fn key_suffix(self) -> &'static str {
+        match self {
+            Side::Both => "",
+            Side::Buy => "_Buy",
+            Side::Sell => "_Sell",
+        }
+    }
+
+    fn title_suffix(self) -> &'static str {
+        match self {
+            Side::Both => "",
+            Side::Buy => " BUY",
+            Side::Sell => " SELL",
+        }
+    }
+}
+
+/// GENERAL SETTINGS and COMPOUNDING. `file_path` is the export target: absolute
+/// paths are written as gInput_ConfigFileName so the EA can reload the file itself.
+pub(crate) struct GeneralWriter<'a> {
+    pub general: &'a GeneralConfig,
+    pub file_path: &'a str,
+}
+
+impl SectionWriter for GeneralWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let GeneralWriter { general, file_path } = *self;
+
+        lines.push("; === GENERAL SETTINGS ===".to_string());
+        lines.push(format!("gInput_MagicNumber={}", general.magic_number));
+        lines.push(format!(
+            "gInput_MagicNumberBuy={}",
+            general.magic_number_buy
+        ));
+        lines.push(format!(
+            "gInput_MagicNumberSell={}",
+            general.magic_number_sell
+        ));
+        lines.push(format!(
+            "gInput_MagicNumberPowerBuy={}",
+            general.magic_number_buy
+        ));
+        lines.push(format!(
+            "gInput_MagicNumberPowerSell={}",
+            general.magic_number_sell
+        ));
+        lines.push(format!(
+            "gInput_MagicNumberReverseBase={}",
+            general.reverse_magic_base
+        ));
+        lines.push(format!(
+            "gInput_MagicNumberHedgeBase={}",
+            general.hedge_magic_base
+        ));
+        lines.push(format!(
+            "gInput_HedgeMagicIndependent={}",
+            if general.hedge_magic_independent {
+                1
+            } else {
+                0
+            }
+        ));
+        lines.push(format!(
+            "gInput_MaxSlippage={}",
+            (general.max_slippage_points.round() as i32)
+        ));
+        lines.push(format!(
+            "gInput_MaxSlippagePoints={:.1}",
+            general.max_slippage_points
+        ));
+        lines.push(format!(
+            "gInput_allowBuy={}",
+            if general.allow_buy { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_allowSell={}",
+            if general.allow_sell { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_EnableLogs={}",
+            if general.enable_logs { 1 } else { 0 }
+        ));
+        // Lazy Fix: Auto-point to self for absolute paths to support >1100 inputs via EA loader
+        // This allows the EA to re-read the .set file from disk to bypass MT5 input limits
+        let is_absolute =
+            file_path.contains(":") || file_path.starts_with("\\\\") || file_path.starts_with("/");
+        if is_absolute {
+            lines.push(format!("gInput_ConfigFileName={}", file_path));
+            lines.push(format!("gInput_ConfigFileIsCommon=0"));
+        } else {
+            lines.push(format!(
+                "gInput_ConfigFileName={}",
+                general.config_file_name
+            ));
+            lines.push(format!(
+                "gInput_ConfigFileIsCommon={}",
+                if general.config_file_is_common { 1 } else { 0 }
+            ));
+        }
+
+        lines.push(String::new());
+
+        // Compounding
+        lines.push("; === COMPOUNDING ===".to_string());
+        lines.push(format!(
+            "gInput_Input_Compounding={}",
+            if general.compounding_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_Input_CompoundingType={}",
+            general.compounding_type
+        ));
+        lines.push(format!(
+            "gInput_Input_CompoundingTarget={:.1}",
+            general.compounding_target
+        ));
+        lines.push(format!(
+            "gInput_Input_CompoundIncrease={:.1}",
+            general.compounding_increase
+        ));
+    }
+}
+
+/// RISK MANAGEMENT, or RISK MANAGEMENT BUY/SELL in the massive v19 file
+pub(crate) struct RiskWriter<'a> {
+    pub risk: &'a RiskManagementConfig,
+    pub layout: SetfileLayout,
+    pub side: Side,
+}
+
+impl SectionWriter for RiskWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let rm = self.risk;
+        let side = self.side.key_suffix();
+
+        lines.push(format!(
+            "; === RISK MANAGEMENT{} ===",
+            self.side.title_suffix()
+        ));
+        if self.layout == SetfileLayout::Massive {
+            lines.push(format!(
+                "gInput_RiskManagementEnabled{}={}",
+                side,
+                if rm.enabled { 1 } else { 0 }
+            ));
+        }
+        lines.push(format!(
+            "gInput_UseSpreadFilter{}={}",
+            side,
+            if rm.spread_filter_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_MaxSpreadPoints{}={:.1}",
+            side, rm.max_spread_points
+        ));
+        lines.push(format!(
+            "gInput_UseEquityStop{}={}",
+            side,
+            if rm.equity_stop_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_EquityStopValue{}={:.1}",
+            side, rm.equity_stop_value
+        ));
+        lines.push(format!(
+            "gInput_UseDrawdownStop{}={}",
+            side,
+            if rm.drawdown_stop_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_MaxDrawdownPercent{}={:.1}",
+            side, rm.max_drawdown_percent
+        ));
+
+        // The action is account-wide; the flat file leaves it out when unset
+        if self.side == Side::Both {
+            match rm.risk_action.as_deref() {
+                Some(risk_action) => lines.push(format!(
+                    "gInput_RiskAction={}",
+                    trigger_action_code(risk_action)
+                )),
+                None if self.layout == SetfileLayout::Massive => {
+                    lines.push("gInput_RiskAction=".to_string())
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// CLEAN MATH: grid unit and pip factor overrides, empty when unset
+pub(crate) struct CleanMathWriter<'a> {
+    pub general: &'a GeneralConfig,
+}
+
+impl SectionWriter for CleanMathWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        lines.push("; === CLEAN MATH ===".to_string());
+        lines.push(format!(
+            "gInput_GridUnit={}",
+            self.general
+                .grid_unit
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        ));
+        lines.push(format!(
+            "gInput_PipFactor={}",
+            self.general
+                .pip_factor
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        ));
+    }
+}
+
+/// NEWS FILTER (account-wide settings)
+pub(crate) struct NewsFilterWriter<'a> {
+    pub news: &'a NewsFilterConfig,
+    pub layout: SetfileLayout,
+}
+
+impl SectionWriter for NewsFilterWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let nf = self.news;
+
+        lines.push("; === NEWS FILTER ===".to_string());
+        lines.push(format!(
+            "gInput_EnableNewsFilter={}",
+            if nf.enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_NewsFilterEnabled={}",
+            if nf.enabled { 1 } else { 0 }
+        ));
+        lines.push(format!("gInput_NewsAPIKey={}", nf.api_key));
+        lines.push(format!("gInput_NewsAPIURL={}", nf.api_url));
+        lines.push(format!("gInput_NewsFilterCountries={}", nf.countries));
+        lines.push(format!("gInput_NewsImpactLevel={}", nf.impact_level));
+        lines.push(format!("gInput_MinutesBeforeNews={}", nf.minutes_before));
+        lines.push(format!("gInput_MinutesAfterNews={}", nf.minutes_after));
+        // Convert 3 boolean fields to news action enum
+        let news_action = if !nf.stop_ea {
+            0
+        } else if nf.close_trades && nf.auto_restart {
+            6
+        } else if nf.close_trades && !nf.auto_restart {
+            5
+        } else if !nf.close_trades && nf.auto_restart {
+            7
+        } else {
+            2
+        };
+        lines.push(format!("gInput_NewsAction={}", news_action));
+        lines.push(format!(
+            "gInput_NewsStopEA={}",
+            if nf.stop_ea { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_NewsCloseTrades={}",
+            if nf.close_trades { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_NewsAutoRestart={}",
+            if nf.auto_restart { 1 } else { 0 }
+        ));
+        lines.push(format!("gInput_NewsCheckInterval={}", nf.check_interval));
+        lines.push(format!(
+            "gInput_FilterHighImpactOnly={}",
+            if nf.filter_high_only { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_FilterWeekendNews={}",
+            if nf.filter_weekends { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_UseLocalNewsCache={}",
+            if nf.use_local_cache { 1 } else { 0 }
+        ));
+        lines.push(format!("gInput_NewsCacheDuration={}", nf.cache_duration));
+        lines.push(format!(
+            "gInput_NewsFallbackOnError={}",
+            nf.fallback_on_error
+        ));
+        lines.push(format!("gInput_FilterCurrencies={}", nf.filter_currencies));
+        lines.push(format!(
+            "gInput_IncludeSpeeches={}",
+            if nf.include_speeches { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_IncludeReports={}",
+            if nf.include_reports { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_NewsVisualIndicator={}",
+            if nf.visual_indicator { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_AlertBeforeNews={}",
+            if nf.alert_before_news { 1 } else { 0 }
+        ));
+        lines.push(format!("gInput_AlertMinutesBefore={}", nf.alert_minutes));
+        // The massive v19 file always carries the key, empty when unset
+        match nf.calendar_file.as_deref() {
+            Some(cf) => lines.push(format!("gInput_NewsCalendarFile={}", cf)),
+            None if self.layout == SetfileLayout::Massive => {
+                lines.push("gInput_NewsCalendarFile=".to_string())
+            }
+            None => {}
+        }
+    }
+}
+
+/// TIME FILTERS: override priorities and the seven sessions
+pub(crate) struct TimeFilterWriter<'a> {
+    pub time_filters: &'a TimeFiltersConfig,
+}
+
+impl SectionWriter for TimeFilterWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let time_filters = self.time_filters;
+
+        lines.push("; === TIME FILTERS ===".to_string());
+        lines.push(format!(
+            "gInput_NewsFilterOverridesSession={}",
+            if time_filters.priority_settings.news_filter_overrides_session {
+                1
+            } else {
+                0
+            }
+        ));
+        lines.push(format!(
+            "gInput_SessionFilterOverridesNews={}",
+            if time_filters.priority_settings.session_filter_overrides_news {
+                1
+            } else {
+                0
+            }
+        ));
+        lines.push(format!(
+            "gInput_NewsOverridesSession={}",
+            if time_filters.priority_settings.news_filter_overrides_session {
+                1
+            } else {
+                0
+            }
+        ));
+        lines.push(format!(
+            "gInput_SessionOverridesNews={}",
+            if time_filters.priority_settings.session_filter_overrides_news {
+                1
+            } else {
+                0
+            }
+        ));
+        let any_session_enabled = time_filters.sessions.iter().any(|s| s.enabled);
+        lines.push(format!(
+            "gInput_SessionFilterEnabled={}",
+            if any_session_enabled { 1 } else { 0 }
+        ));
+
+        for (i, session) in time_filters.sessions.iter().enumerate() {
+            let session_num = i + 1;
+            let n = session.enabled as i32; // 0 or 1
+            lines.push(format!("gInput_Session{}Enabled={}", session_num, n));
+            lines.push(format!("gInput_Session{}Day={}", session_num, session.day));
+            lines.push(format!(
+                "gInput_Session{}StartHour={}",
+                session_num, session.start_hour
+            ));
+            lines.push(format!(
+                "gInput_Session{}StartMinute={}",
+                session_num, session.start_minute
+            ));
+            lines.push(format!(
+                "gInput_Session{}EndHour={}",
+                session_num, session.end_hour
+            ));
+            lines.push(format!(
+                "gInput_Session{}EndMinute={}",
+                session_num, session.end_minute
+            ));
+            lines.push(format!(
+                "gInput_Session{}Action={}",
+                session_num,
+                trigger_action_code(&session.action)
+            ));
+        }
+    }
+}
+
+/// Per-logic export switches shared by every EngineWriter and LogicWriter
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LogicWriteOptions<'a> {
+    /// Append ",F=", ",1=", ",2=", ",3=" optimization rows for lot, multiplier and grid
+    pub include_optimization_hints: bool,
+    /// "BUY" or "SELL" overrides every logic's allow flags; "BOTH" or unset keeps them
+    pub trade_direction: Option<&'a str>,
+}
+
+/// ENGINE header, then per group its thresholds, reverse/hedge controls and logics
+pub(crate) struct EngineWriter<'a> {
+    pub engine: &'a EngineConfig,
+    pub options: LogicWriteOptions<'a>,
+}
+
+impl SectionWriter for EngineWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let engine = self.engine;
+
+        lines.push(format!(";"));
+        lines.push(format!("; === ENGINE {} ===", engine.engine_id));
+        tracing::trace!(engine = %engine.engine_id, groups = engine.groups.len(), "Processing engine");
+
+        for group in &engine.groups {
+            tracing::trace!(group = group.group_number, "Processing group");
+            lines.push(format!("; --- Group {} ---", group.group_number));
+
+            // GroupPowerStart thresholds (groups 2-20): ONLY Engine A Power controls group progression (V3 behavior).
+            // B/C engines always mirror A's group at EA runtime, so no BP/CP thresholds are needed.
+            if group.group_number > 1 && engine.engine_id == "A" {
+                let base_key = format!("P{}", group.group_number);
+
+                // Use new Buy/Sell specific fields, fallback to legacy group_power_start
+                let gps_b = group.group_power_start_b.or(group.group_power_start);
+                let gps_s = group.group_power_start_s.or(group.group_power_start);
+
+                if let Some(v) = gps_b {
+                    lines.push(format!("gInput_GroupPowerStart_{}_Buy={}", base_key, v));
+                }
+                if let Some(v) = gps_s {
+                    lines.push(format!("gInput_GroupPowerStart_{}_Sell={}", base_key, v));
+                }
+            }
+
+            // Group-level Reverse/Hedge controls (V17.04+)
+            lines.push(format!(
+                "gInput_Group{}_ReverseMode={}",
+                group.group_number,
+                if group.reverse_mode { 1 } else { 0 }
+            ));
+            lines.push(format!(
+                "gInput_Group{}_HedgeMode={}",
+                group.group_number,
+                if group.hedge_mode { 1 } else { 0 }
+            ));
+            lines.push(format!(
+                "gInput_Group{}_HedgeReference={}",
+                group.group_number, group.hedge_reference
+            ));
+            lines.push(format!(
+                "gInput_Group{}_EntryDelayBars={}",
+                group.group_number, group.entry_delay_bars
+            ));
+
+            for logic in &group.logics {
+                LogicWriter {
+                    engine,
+                    group,
+                    logic,
+                    options: self.options,
+                }
+                .write(lines);
+                lines.push(String::new());
+            }
+        }
+    }
+}
+
+/// Every input of one logic in one group
+pub(crate) struct LogicWriter<'a> {
+    pub engine: &'a EngineConfig,
+    pub group: &'a GroupConfig,
+    pub logic: &'a LogicConfig,
+    pub options: LogicWriteOptions<'a>,
+}
+
+impl SectionWriter for LogicWriter<'_> {
+    fn write(&self, lines: &mut Vec<String>) {
+        let LogicWriter {
+            engine,
+            group,
+            logic,
+            options,
+        } = *self;
+        let LogicWriteOptions {
+            include_optimization_hints,
+            trade_direction,
+        } = options;
+
+        let g = group.group_number;
+        let suffix = get_logic_suffix(&engine.engine_id, g, &logic.logic_name);
+        let short = get_logic_short(&engine.engine_id, &logic.logic_name);
+
+        // No _Enabled/_Start key — all logics always enabled.
+        // Trading is controlled by StartLevel, trigger type, risk management, etc.
+
+        // Base params: Initial/Last lot are Group 1-only by contract.
+        if g == 1 {
+            lines.push(schema::INITIAL_LOT.line_f64(g, &suffix, &short, logic.initial_lot));
+            lines.extend(schema::INITIAL_LOT.side_lines(
+                &suffix,
+                logic.initial_lot_b,
+                logic.initial_lot_s,
+            ));
+
+            if let Some(ll) = logic.last_lot {
+                let upper = logic.logic_name.to_uppercase();
+                if upper == "POWER" {
+                    lines.push(format!("gInput_LastLotPower_{}={:.2}", suffix, ll));
+                } else if upper == "REPOWER" {
+                    lines.push(format!("gInput_LastLotRepower_{}={:.2}", suffix, ll));
+                } else {
+                    lines.push(format!("gInput_LastLot_{}={:.2}", suffix, ll));
+                }
+            }
+        }
+
+        // Strategy Type & Trading Mode
+        lines.push(schema::STRATEGY_TYPE.line_str(
+            g,
+            &suffix,
+            &short,
+            logic.strategy_type.as_str(),
+        ));
+        lines.push(schema::TRADING_MODE.line_str(
+            g,
+            &suffix,
+            &short,
+            logic.trading_mode.map_or("", |mode| mode.as_str()),
+        ));
+
+        // Apply trade direction override if specified
+        let (allow_buy, allow_sell) = match trade_direction {
+            Some("BUY") => (true, false),
+            Some("SELL") => (false, true),
+            Some("BOTH") | None => (logic.allow_buy, logic.allow_sell),
+            _ => (logic.allow_buy, logic.allow_sell),
+        };
+        lines.push(schema::ALLOW_BUY.line_bool(g, &suffix, &short, allow_buy));
+        lines.push(schema::ALLOW_SELL.line_bool(g, &suffix, &short, allow_sell));
+
+        if include_optimization_hints && g == 1 {
+            let initial_key = schema::INITIAL_LOT.key(g, &suffix, &short);
+            let (f, start, step, stop) = get_optimization_values("initial_lot", logic.initial_lot);
+            lines.push(format!("{},F={}", initial_key, f));
+            lines.push(format!("{},1={:.2}", initial_key, start));
+            lines.push(format!("{},2={:.2}", initial_key, step));
+            lines.push(format!("{},3={:.2}", initial_key, stop));
+        }
+
+        let mult_key = schema::MULTIPLIER.key(g, &suffix, &short);
+        lines.push(schema::MULTIPLIER.line_f64(g, &suffix, &short, logic.multiplier));
+        lines.extend(schema::MULTIPLIER.side_lines(
+            &suffix,
+            logic.multiplier_b,
+            logic.multiplier_s,
+        ));
+        if include_optimization_hints {
+            let (f, start, step, stop) = get_optimization_values("multiplier", logic.multiplier);
+            lines.push(format!("{},F={}", mult_key, f));
+            lines.push(format!("{},1={:.2}", mult_key, start));
+            lines.push(format!("{},2={:.2}", mult_key, step));
+            lines.push(format!("{},3={:.2}", mult_key, stop));
+        }
+
+        let grid_key = schema::GRID.key(g, &suffix, &short);
+        lines.push(schema::GRID.line_f64(g, &suffix, &short, logic.grid));
+        lines.extend(schema::GRID.side_lines(&suffix, logic.grid_b, logic.grid_s));
+        if include_optimization_hints {
+            let (f, start, step, stop) = get_optimization_values("grid", logic.grid);
+            lines.push(format!("{},F={}", grid_key, f));
+            lines.push(format!("{},1={:.1}", grid_key, start));
+            lines.push(format!("{},2={:.1}", grid_key, step));
+            lines.push(format!("{},3={:.1}", grid_key, stop));
+        }
+
+        // Trail params - use correct MT4/MT5 variable names
+        lines.push(schema::TRAIL_METHOD.line_str(g, &suffix, &short, logic.trail_method.as_str()));
+        lines.push(schema::TRAIL_VALUE.line_f64(g, &suffix, &short, logic.trail_value));
+        lines.extend(schema::TRAIL_VALUE.side_lines(
+            &suffix,
+            logic.trail_value_b,
+            logic.trail_value_s,
+        ));
+        lines.push(schema::TRAIL_START.line_f64(g, &suffix, &short, logic.trail_start));
+        lines.extend(schema::TRAIL_START.side_lines(
+            &suffix,
+            logic.trail_start_b,
+            logic.trail_start_s,
+        ));
+        lines.push(schema::TRAIL_STEP.line_f64(g, &suffix, &short, logic.trail_step));
+        lines.extend(schema::TRAIL_STEP.side_lines(
+            &suffix,
+            logic.trail_step_b,
+            logic.trail_step_s,
+        ));
+        lines.push(schema::TRAIL_STEP_METHOD.line_str(
+            g,
+            &suffix,
+            &short,
+            logic.trail_step_method.as_str(),
+        ));
+
+        // Trail Step Advanced (V17.04+)
+        lines.push(schema::TRAIL_STEP_MODE.line_str(
+            g,
+            &suffix,
+            &short,
+            logic.trail_step_mode.as_str(),
+        ));
+        lines.push(schema::TRAIL_STEP_CYCLE.line_f64(
+            g,
+            &suffix,
+            &short,
+            logic.trail_step_cycle as f64,
+        ));
+        lines.push(schema::TRAIL_STEP_BALANCE.line_f64(
+            g,
+            &suffix,
+            &short,
+            logic.trail_step_balance,
+        ));
+
+        // Trail Step Extended (Levels 2-7)
+        // Level 2
+        if let Some(v) = logic.trail_step_2 {
+            lines.push(format!("gInput_TrailStep2_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_2 {
+            lines.push(format!("gInput_TrailStepMethod2_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_2 {
+            lines.push(format!("gInput_TrailStepCycle2_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_2 {
+            lines.push(format!("gInput_TrailStepBalance2_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_2 {
+            lines.push(format!("gInput_TrailStepMode2_{}={}", suffix, v));
+        }
+
+        // Level 3
+        if let Some(v) = logic.trail_step_3 {
+            lines.push(format!("gInput_TrailStep3_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_3 {
+            lines.push(format!("gInput_TrailStepMethod3_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_3 {
+            lines.push(format!("gInput_TrailStepCycle3_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_3 {
+            lines.push(format!("gInput_TrailStepBalance3_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_3 {
+            lines.push(format!("gInput_TrailStepMode3_{}={}", suffix, v));
+        }
+
+        // Level 4
+        if let Some(v) = logic.trail_step_4 {
+            lines.push(format!("gInput_TrailStep4_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_4 {
+            lines.push(format!("gInput_TrailStepMethod4_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_4 {
+            lines.push(format!("gInput_TrailStepCycle4_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_4 {
+            lines.push(format!("gInput_TrailStepBalance4_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_4 {
+            lines.push(format!("gInput_TrailStepMode4_{}={}", suffix, v));
+        }
+
+        // Level 5
+        if let Some(v) = logic.trail_step_5 {
+            lines.push(format!("gInput_TrailStep5_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_5 {
+            lines.push(format!("gInput_TrailStepMethod5_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_5 {
+            lines.push(format!("gInput_TrailStepCycle5_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_5 {
+            lines.push(format!("gInput_TrailStepBalance5_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_5 {
+            lines.push(format!("gInput_TrailStepMode5_{}={}", suffix, v));
+        }
+
+        // Level 6
+        if let Some(v) = logic.trail_step_6 {
+            lines.push(format!("gInput_TrailStep6_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_6 {
+            lines.push(format!("gInput_TrailStepMethod6_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_6 {
+            lines.push(format!("gInput_TrailStepCycle6_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_6 {
+            lines.push(format!("gInput_TrailStepBalance6_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_6 {
+            lines.push(format!("gInput_TrailStepMode6_{}={}", suffix, v));
+        }
+
+        // Level 7
+        if let Some(v) = logic.trail_step_7 {
+            lines.push(format!("gInput_TrailStep7_{}={:.1}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_method_7 {
+            lines.push(format!("gInput_TrailStepMethod7_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_cycle_7 {
+            lines.push(format!("gInput_TrailStepCycle7_{}={}", suffix, v));
+        }
+        if let Some(v) = logic.trail_step_balance_7 {
+            lines.push(format!("gInput_TrailStepBalance7_{}={:.2}", suffix, v));
+        }
+        if let Some(ref v) = logic.trail_step_mode_7 {
+            lines.push(format!("gInput_TrailStepMode7_{}={}", suffix, v));
+        }
+
+        // Logic specific
+        if group.group_number == 1 {
+            if let Some(sl) = logic.start_level {
+                if let Some(start_key) = get_logic_start_key(&engine.engine_id, &logic.logic_name) {
+                    lines.push(format!("gInput_{}={}", start_key, sl));
+                }
+            }
+        }
+
+        lines.push(format!(
+            "gInput_CloseTargets_{}={}",
+            suffix, logic.close_targets
+        ));
+
+        if group.group_number == 1 {
+            let logic_key = get_logic_global_key(&engine.engine_id, &logic.logic_name);
+            // Export EXACTLY what the frontend provides - NO defaults
+            lines.push(format!(
+                "gInput_CloseTargets_{}={}",
+                logic_key, logic.close_targets
+            ));
+        }
+        if group.group_number == 1 {
+            let logic_key = get_logic_global_key(&engine.engine_id, &logic.logic_name);
+            lines.push(format!(
+                "gInput_{}_OrderCountReference={}",
+                logic_key, logic.order_count_reference
+            ));
+        }
+        lines.push(format!(
+            "gInput_MaxPowerOrders_{}={}",
+            suffix, engine.max_power_orders
+        ));
+        lines.push(format!(
+            "gInput_OrderCountReference_{}={}",
+            suffix, logic.order_count_reference
+        ));
+        lines.push(format!(
+            "gInput_ResetLotOnRestart_{}={}",
+            suffix,
+            if logic.reset_lot_on_restart { 1 } else { 0 }
+        ));
+
+        // Reverse/Hedge per-logic (V17.04+ full structure)
+        lines.push(format!(
+            "gInput_G{}_{}_ReverseEnabled={}",
+            group.group_number,
+            short,
+            if logic.reverse_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_G{}_{}_HedgeEnabled={}",
+            group.group_number,
+            short,
+            if logic.hedge_enabled { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_G{}_Scale_{}_Reverse={:.1}",
+            group.group_number, short, logic.reverse_scale
+        ));
+        lines.push(format!(
+            "gInput_G{}_Scale_{}_Hedge={:.1}",
+            group.group_number, short, logic.hedge_scale
+        ));
+        lines.push(format!(
+            "gInput_G{}_{}_ReverseReference={}",
+            group.group_number, short, logic.reverse_reference
+        ));
+        lines.push(format!(
+            "gInput_G{}_{}_HedgeReference={}",
+            group.group_number, short, logic.hedge_reference
+        ));
+
+        // Close Partial (active simplified contract)
+        lines.push(format!(
+            "gInput_ClosePartial_{}={}",
+            suffix,
+            if logic.close_partial { 1 } else { 0 }
+        ));
+        lines.push(format!(
+            "gInput_ClosePartialMode_{}={}",
+            suffix,
+            logic.close_partial_mode.ea_code()
+        ));
+        lines.push(format!(
+            "gInput_ClosePartialProfitThreshold_{}={:.2}",
+            suffix, logic.close_partial_profit_threshold
+        ));
+
+        // Close Partial Extended (Levels 2-4)
+        if let Some(v) = logic.close_partial_2 {
+            lines.push(format!(
+                "gInput_ClosePartial2_{}={}",
+                suffix,
+                if v { 1 } else { 0 }
+            ));
+        }
+        if let Some(ref v) = logic.close_partial_mode_2 {
+            lines.push(format!(
+                "gInput_ClosePartialMode2_{}={}",
+                suffix,
+                v.ea_code()
+            ));
+        }
+        if let Some(v) = logic.close_partial_profit_threshold_2 {
+            lines.push(format!(
+                "gInput_ClosePartialProfitThreshold2_{}={:.2}",
+                suffix, v
+            ));
+        }
+
+        if let Some(v) = logic.close_partial_3 {
+            lines.push(format!(
+                "gInput_ClosePartial3_{}={}",
+                suffix,
+                if v { 1 } else { 0 }
+            ));
+        }
+        if let Some(ref v) = logic.close_partial_mode_3 {
+            lines.push(format!(
+                "gInput_ClosePartialMode3_{}={}",
+                suffix,
+                v.ea_code()
+            ));
+        }
+        if let Some(v) = logic.close_partial_profit_threshold_3 {
+            lines.push(format!(
+                "gInput_ClosePartialProfitThreshold3_{}={:.2}",
+                suffix, v
+            ));
+        }
+
+        if let Some(v) = logic.close_partial_4 {
+            lines.push(format!(
+                "gInput_ClosePartial4_{}={}",
+                suffix,
+                if v { 1 } else { 0 }
+            ));
+        }
+        if let Some(ref v) = logic.close_partial_mode_4 {
+            lines.push(format!(
+                "gInput_ClosePartialMode4_{}={}",
+                suffix,
+                v.ea_code()
+            ));
+        }
+        if let Some(v) = logic.close_partial_profit_threshold_4 {
+            lines.push(format!(
+                "gInput_ClosePartialProfitThreshold4_{}={:.2}",
+                suffix, v
+            ));
+        }
+
+        if let Some(tt) = logic.trigger_type {
+            lines.push(format!("gInput_TriggerType_{}={}", suffix, tt.ea_code()));
+        }
+        lines.push(format!(
+            "gInput_TriggerMode_{}={}",
+            suffix,
+            logic.trigger_mode.unwrap_or_default().ea_code()
+        ));
+        if let Some(tb) = logic.trigger_bars {
+            lines.push(format!("gInput_TriggerBars_{}={}", suffix, tb));
+        }
+        if let Some(tm) = logic.trigger_seconds {
+            lines.push(format!("gInput_TriggerSeconds_{}={}", suffix, tm));
+        }
+        if let Some(tp) = logic.trigger_pips {
+            lines.push(format!("gInput_TriggerPips_{}={:.1}", suffix, tp));
+        }
+    }
+}
+
+/// (flag, start, step, stop) of the optimization rows written next to lot, multiplier and grid
+fn get_optimization_values(field: &str, value: f64) -> (i32, f64, f64, f64) {
+    if !value.is_finite() {
+        return (0, 0.0, 0.0, 0.0);
+    }
+
+    match field {
+        "initial_lot" => {
+            let base = if value > 0.0 { value } else { 0.01 };
+            let step = 0.01_f64;
+            let min = 0.01_f64;
+            let max = (base * 3.0).max(0.03);
+            (1, min, step, max)
+        }
+        "multiplier" => {
+            let base = if value > 1.0 { value } else { 1.5 };
+            let step = 0.1_f64;
+            let min = 1.0_f64;
+            let max = (base * 2.0).max(3.0);
+            (1, min, step, max)
+        }
+        "grid" => {
+            let base = if value > 0.0 { value } else { 10.0 };
+            let step = 5.0_f64;
+            let min = 5.0_f64;
+            let max = (base * 3.0).max(50.0);
+            (1, min, step, max)
+        }
+        _ => (0, 0.0, 0.0, 0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_risk_writer_layouts() {
+        let risk = RiskManagementConfig {
+            max_spread_points: 25.0,
+            ..Default::default()
+        };
+        let writer = |layout, side| RiskWriter {
+            risk: &risk,
+            layout,
+            side,
+        };
+
+        let flat = writer(SetfileLayout::Flat, Side::Both).render();
+        assert_eq!(flat[0], "; === RISK MANAGEMENT ===");
+        assert!(flat.contains(&"gInput_MaxSpreadPoints=25.0".to_string()));
+        assert!(!flat.iter().any(|l| l.starts_with("gInput_RiskAction")));
+        assert!(!flat
+            .iter()
+            .any(|l| l.starts_with("gInput_RiskManagementEnabled")));
+
+        let massive = writer(SetfileLayout::Massive, Side::Both).render();
+        assert_eq!(massive[1], "gInput_RiskManagementEnabled=0");
+        assert_eq!(massive.last().unwrap(), "gInput_RiskAction=");
+
+        let buy = writer(SetfileLayout::Massive, Side::Buy).render();
+        assert_eq!(buy[0], "; === RISK MANAGEMENT BUY ===");
+        assert!(buy.contains(&"gInput_MaxSpreadPoints_Buy=25.0".to_string()));
+        assert!(!buy.iter().any(|l| l.starts_with("gInput_RiskAction")));
+
+        // Unknown names keep trades open
+        let unknown = RiskManagementConfig {
+            risk_action: Some("TriggerAction_Sometimes".to_string()),
+            ..Default::default()
+        };
+        let flat = RiskWriter {
+            risk: &unknown,
+            layout: SetfileLayout::Flat,
+            side: Side::Both,
+        }
+        .render();
+        assert_eq!(flat.last().unwrap(), "gInput_RiskAction=2");
+    }
+
+    #[test]
+    fn test_logic_writer_options() {
+        let config = create_full_v19_config();
+        let engine = &config.engines[0];
+        let group = &engine.groups[0];
+        let logic = &group.logics[0];
+        let suffix = get_logic_suffix(&engine.engine_id, group.group_number, &logic.logic_name);
+        let render = |options| {
+            LogicWriter {
+                engine,
+                group,
+                logic,
+                options,
+            }
+            .render()
+        };
+
+        let sell_only = render(LogicWriteOptions {
+            include_optimization_hints: false,
+            trade_direction: Some("SELL"),
+        });
+        assert!(sell_only.contains(&format!("gInput_AllowBuy_{}=0", suffix)));
+        assert!(sell_only.contains(&format!("gInput_AllowSell_{}=1", suffix)));
+        assert!(!sell_only.iter().any(|l| l.contains(",F=")));
+
+        let hinted = render(LogicWriteOptions {
+            include_optimization_hints: true,
+            trade_direction: None,
+        });
+        assert!(hinted.contains(&format!("gInput_Grid_{},F=1", suffix)));
+    }
+
+    #[test]
+    fn test_engine_writer_separates_logics() {
+        let config = create_full_v19_config();
+        let engine = &config.engines[0];
+        let lines = EngineWriter {
+            engine,
+            options: LogicWriteOptions::default(),
+        }
+        .render();
+
+        assert_eq!(lines[1], format!("; === ENGINE {} ===", engine.engine_id));
+        let group_headers = lines
+            .iter()
+            .filter(|l| l.starts_with("; --- Group"))
+            .count();
+        assert_eq!(group_headers, engine.groups.len());
+        let logics: usize = engine.groups.iter().map(|g| g.logics.len()).sum();
+        assert_eq!(lines.iter().filter(|l| l.is_empty()).count(), logics);
+    }
+}
@@ -0,0 +1,300 @@
+//! WordPiece tokenizer for the trading transformer
+//!
+//! Parameter names like `gInput_1_AP_Buy_TrailStep3` are first split on
+//! underscores, case changes and digit boundaries, then each piece is
+//! covered greedily by the longest known subwords ("trail", "##step").
+//! The vocabulary is learned with BPE-style merges over a corpus, so it
+//! stays within a fixed size instead of growing with every new name.
+//! Words that can't be covered become `[UNK]`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+pub const PAD: &str = "[PAD]";
+pub const UNK: &str = "[UNK]";
+pub const SEP: &str = "[SEP]";
+const SPECIAL_TOKENS: [&str; 3] = [PAD, UNK, SEP];
+/// Continuation prefix for pieces inside a word
+const CONTINUATION: &str = "##";
+const TOKENIZER_FORMAT_VERSION: u32 = 1;
+/// Longer words are not worth splitting and map straight to [UNK]
+const MAX_WORD_CHARS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct SubwordTokenizer {
+    vocab: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenizerFile {
+    version: u32,
+    vocab: Vec<String>,
+}
+
+/// Split text into lowercase words, breaking identifiers apart:
+/// "gInput_1_AP_Buy_TrailStep3" -> g input 1 ap buy trail step 3
+pub fn pre_tokenize(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for chunk in text.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            let boundary = (prev.is_lowercase() && cur.is_uppercase())
+                || (prev.is_uppercase() && cur.is_uppercase() && next_lower)
+                || (prev.is_ascii_digit() != cur.is_ascii_digit());
+            if boundary {
+                words.push(chars[start..i].iter().collect::<String>().to_lowercase());
+                start = i;
+            }
+        }
+        if start < chars.len() {
+            words.push(chars[start..].iter().collect::<String>().to_lowercase());
+        }
+    }
+    words
+}
+
+/// A word as its initial symbols: first char bare, the rest "##"-prefixed
+fn word_symbols(word: &str) -> Vec<String> {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i == 0 {
+                c.to_string()
+            } else {
+                format!("{}{}", CONTINUATION, c)
+            }
+        })
+        .collect()
+}
+
+impl SubwordTokenizer {
+    /// Tokenizer over an existing vocabulary (special tokens first)
+    pub fn from_vocab(vocab: Vec<String>) -> Result<Self, String> {
+        if vocab.len() < SPECIAL_TOKENS.len()
+            || vocab[..SPECIAL_TOKENS.len()]
+                .iter()
+                .zip(SPECIAL_TOKENS)
+                .any(|(token, special)| token != special)
+        {
+            return Err("Tokenizer vocabulary must start with [PAD], [UNK], [SEP]".to_string());
+        }
+        let index = vocab
+            .iter()
+            .enumerate()
+            .map(|(i, token)| (token.clone(), i))
+            .collect();
+        Ok(SubwordTokenizer { vocab, index })
+    }
+
+    /// Learn a vocabulary of at most `max_size` tokens from `corpus`
+    pub fn train<S: AsRef<str>>(corpus: &[S], max_size: usize) -> Self {
+        let mut word_counts: BTreeMap<String, usize> = BTreeMap::new();
+        for text in corpus {
+            for word in pre_tokenize(text.as_ref()) {
+                if word.chars().count() <= MAX_WORD_CHARS {
+                    *word_counts.entry(word).or_insert(0) += 1;
+                }
+            }
+        }
+        let mut words: Vec<(Vec<String>, usize)> = word_counts
+            .into_iter()
+            .map(|(word, count)| (word_symbols(&word), count))
+            .collect();
+
+        // Base alphabet, most frequent first so a tiny max_size keeps the useful ones
+        let mut symbol_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (symbols, count) in &words {
+            for symbol in symbols {
+                *symbol_counts.entry(symbol.as_str()).or_insert(0) += count;
+            }
+        }
+        let mut alphabet: Vec<(&str, usize)> = symbol_counts.into_iter().collect();
+        alphabet.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let mut vocab: Vec<String> = SPECIAL_TOKENS.iter().map(|s| s.to_string()).collect();
+        vocab.extend(alphabet.iter().map(|(symbol, _)| symbol.to_string()));
+        vocab.truncate(max_size.max(SPECIAL_TOKENS.len()));
+
+        while vocab.len() < max_size {
+            let mut pairs: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+            for (symbols, count) in &words {
+                for pair in symbols.windows(2) {
+                    *pairs.entry((&pair[0], &pair[1])).or_insert(0) += count;
+                }
+            }
+            // Ties go to the lexicographically first pair so training is deterministic
+            let best = pairs
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                .filter(|(_, count)| *count >= 2);
+            let Some(((left, right), _)) = best else {
+                break;
+            };
+            let merged = format!("{}{}", left, right.trim_start_matches(CONTINUATION));
+            let (left, right) = (left.to_string(), right.to_string());
+            for (symbols, _) in &mut words {
+                let mut i = 0;
+                while i + 1 < symbols.len() {
+                    if symbols[i] == left && symbols[i + 1] == right {
+                        symbols[i] = merged.clone();
+                        symbols.remove(i + 1);
+                    }
+                    i += 1;
+                }
+            }
+            if !vocab.contains(&merged) {
+                vocab.push(merged);
+            }
+        }
+
+        Self::from_vocab(vocab).expect("trained vocabulary starts with the special tokens")
+    }
+
+    pub fn vocab(&self) -> &[String] {
+        &self.vocab
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+
+    pub fn token_id(&self, token: &str) -> Option<usize> {
+        self.index.get(token).copied()
+    }
+
+    pub fn unk_id(&self) -> usize {
+        self.index[UNK]
+    }
+
+    /// Greedy longest-match pieces for one pre-tokenized word, or None if
+    /// some part of it isn't covered by the vocabulary
+    fn word_pieces(&self, word: &str) -> Option<Vec<usize>> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.is_empty() || chars.len() > MAX_WORD_CHARS {
+            return None;
+        }
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let prefix = if start == 0 { "" } else { CONTINUATION };
+            let id = (start + 1..=chars.len()).rev().find_map(|end| {
+                let piece: String = chars[start..end].iter().collect();
+                self.token_id(&format!("{}{}", prefix, piece))
+                    .map(|id| (id, end))
+            });
+            let (id, end) = id?;
+            pieces.push(id);
+            start = end;
+        }
+        Some(pieces)
+    }
+
+    pub fn encode(&self, text: &str) -> Vec<usize> {
+        pre_tokenize(text)
+            .iter()
+            .flat_map(|word| {
+                self.word_pieces(word)
+                    .unwrap_or_else(|| vec![self.unk_id()])
+            })
+            .collect()
+    }
+
+    /// Encode to exactly `len` ids: truncated, closed with [SEP], padded with [PAD]
+    pub fn encode_padded(&self, text: &str, len: usize) -> Vec<usize> {
+        let mut ids = self.encode(text);
+        ids.truncate(len.saturating_sub(1));
+        ids.push(self.index[SEP]);
+        ids.resize(len, self.index[PAD]);
+        ids
+    }
+
+    /// Token strings for ids, e.g. for showing how an input was split
+    pub fn tokens(&self, ids: &[usize]) -> Vec<String> {
+        ids.iter()
+            .map(|&id| {
+                self.vocab
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| UNK.to_string())
+            })
+            .collect()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create models folder: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(&TokenizerFile {
+            version: TOKENIZER_FORMAT_VERSION,
+            vocab: self.vocab.clone(),
+        })
+        .map_err(|e| format!("Failed to serialize tokenizer: {}", e))?;
+        crate::mt_bridge::atomic_write(&path.to_path_buf(), &json)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read tokenizer: {}", e))?;
+        let file: TokenizerFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse tokenizer: {}", e))?;
+        if file.version != TOKENIZER_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported tokenizer format version {} (expected {})",
+                file.version, TOKENIZER_FORMAT_VERSION
+            ));
+        }
+        Self::from_vocab(file.vocab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pre_tokenize_splits_parameter_names() {
+        assert_eq!(
+            pre_tokenize("gInput_1_AP_Buy_TrailStep3"),
+            vec!["g", "input", "1", "ap", "buy", "trail", "step", "3"]
+        );
+        assert_eq!(
+            pre_tokenize("set grid to 0.01"),
+            vec!["set", "grid", "to", "0", "01"]
+        );
+    }
+
+    #[test]
+    fn test_train_encode_and_unknowns() {
+        let corpus = [
+            "set trail step to 50",
+            "gInput_1_AP_Buy_TrailStep3",
+            "show trail start for power",
+            "set trailing stop on group 2",
+        ];
+        let tokenizer = SubwordTokenizer::train(&corpus, 80);
+        assert!(tokenizer.vocab_size() <= 80);
+        assert!(tokenizer.token_id("trail").is_some());
+
+        // Unseen but coverable words split into known pieces, no [UNK]
+        let ids = tokenizer.encode("stepper");
+        assert!(!ids.contains(&tokenizer.unk_id()));
+        // Characters never seen in training make the word unknown
+        assert_eq!(tokenizer.encode("zzz"), vec![tokenizer.unk_id()]);
+
+        let padded = tokenizer.encode_padded("set trail step", 8);
+        assert_eq!(padded.len(), 8);
+        assert_eq!(tokenizer.tokens(&padded)[7], PAD);
+
+        let reloaded = SubwordTokenizer::from_vocab(tokenizer.vocab.clone()).unwrap();
+        assert_eq!(
+            reloaded.encode("trail step"),
+            tokenizer.encode("trail step")
+        );
+        assert!(SubwordTokenizer::from_vocab(vec!["a".to_string()]).is_err());
+    }
+}
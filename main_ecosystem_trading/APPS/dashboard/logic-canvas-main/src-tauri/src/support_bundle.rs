@@ -0,0 +1,603 @@
+// ============================================
+// SUPPORT BUNDLE
+// ============================================
+//
+// One zip a user can attach to a support ticket instead of answering ten
+// follow-up questions: the open config (client redaction: license masked,
+// news API key stripped), copies of the settings files under daavfx/, the
+// recent-log ring and the tail of the rolling log files, the tail of the
+// terminal command audit log, the last import log and crash report
+// summaries, a health check and version info. Secrets are removed before
+// anything is written: settings keys that name a token, password or key are
+// blanked, obfuscated "ENC:" values too, and credential patterns in log text
+// are masked.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::app_log;
+use crate::crash_report::{list_crash_reports, CrashReportSummary};
+use crate::import_log::last_import_log;
+use crate::mt_bridge::{atomic_write, sanitize_and_validate_path, MTConfig};
+use crate::redaction::{apply_redaction, ExportAudience, RedactionPolicy};
+use crate::vault_location;
+
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::MTBridgeState;
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+const REDACTED: &str = "[redacted]";
+/// Key name fragments whose values never leave the machine
+const SECRET_KEYS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passphrase",
+    "private_key",
+    "privatekey",
+    "api_key",
+    "apikey",
+    "access_key",
+    "license_key",
+    "licensekey",
+];
+/// Settings files bigger than this (caches) are listed in the manifest but not copied
+const MAX_SETTINGS_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const RECENT_LOG_LINES: usize = 1000;
+const LOG_FILES_INCLUDED: usize = 2;
+/// Only the end of each rolling log file is included
+const LOG_TAIL_BYTES: u64 = 1024 * 1024;
+const AUDIT_LOG_FILE: &str = "audit_log.jsonl";
+const AUDIT_TAIL_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub name: String,
+    /// "ok", "warn" or "error"
+    pub status: String,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, status: &str, detail: impl Into<String>) -> Self {
+        HealthCheck {
+            name: name.to_string(),
+            status: status.to_string(),
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub debug_build: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub path: String,
+    pub size: u64,
+    /// Files inside the zip
+    pub files: Vec<String>,
+    /// Settings files left out (too large, or not JSON that could be sanitized)
+    pub skipped: Vec<String>,
+    pub health: Vec<HealthCheck>,
+}
+
+/// What the bundle needs from the app state, gathered up front so the zip
+/// is built without holding any lock
+#[derive(Debug, Clone, Default)]
+pub struct StateSnapshot {
+    pub config: Option<MTConfig>,
+    pub open_configs: usize,
+    pub constraint_violations: usize,
+    pub field_locks: usize,
+    pub terminal_paths: Vec<(String, Option<PathBuf>)>,
+    pub safe_mode: bool,
+}
+
+fn daavfx_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+}
+
+/// Command audit log the root dashboard appends to (src-tauri/src/audit_log.rs)
+fn audit_log_path() -> Option<PathBuf> {
+    dirs::data_dir()
+        .or_else(dirs::home_dir)
+        .map(|base| base.join("DAAVFX").join(AUDIT_LOG_FILE))
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_KEYS.iter().any(|secret| key.contains(secret))
+}
+
+/// Blank every value stored under a secret-looking key, and any obfuscated value
+pub fn sanitize_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let stored =
+                    !matches!(value, Value::Null) && value.as_str().map_or(true, |s| !s.is_empty());
+                if is_secret_key(key) && stored && !value.is_object() && !value.is_array() {
+                    *value = Value::from(REDACTED);
+                } else {
+                    sanitize_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sanitize_json),
+        Value::String(text) if text.starts_with("ENC:") => *text = REDACTED.to_string(),
+        _ => {}
+    }
+}
+
+fn credential_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(bearer\s+|(?:token|secret|password|passphrase|api[_-]?key|access[_-]?key|license[_-]?key|signature)["']?\s*[:=]\s*["']?)[^\s"',;&]+"#,
+        )
+        .expect("valid credential pattern")
+    })
+}
+
+/// Mask credentials in free text (log lines, URLs with query tokens)
+pub fn redact_text(text: &str) -> String {
+    credential_pattern()
+        .replace_all(text, format!("${{1}}{}", REDACTED))
+        .to_string()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize bundle entry: {}", e))
+}
+
+/// Last `max_bytes` of a file, starting at a line boundary
+fn file_tail(path: &Path, max_bytes: u64) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let text = String::from_utf8_lossy(&bytes).to_string();
+    Ok(match (start > 0, text.find('\n')) {
+        (true, Some(newline)) => text[newline + 1..].to_string(),
+        _ => text,
+    })
+}
+
+/// Redacted tail of the audit log at `path`; None when there is no log yet
+fn audit_tail_entry(path: &Path) -> Option<(String, Vec<u8>)> {
+    let tail = file_tail(path, AUDIT_TAIL_BYTES).ok()?;
+    Some((
+        format!("audit/{}", AUDIT_LOG_FILE),
+        redact_text(&tail).into_bytes(),
+    ))
+}
+
+/// Sanitized copies of the top-level settings files; returns (entries, skipped names)
+fn settings_entries(dir: &Path) -> (Vec<(String, Vec<u8>)>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|read| {
+            read.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    for path in files {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let too_large = std::fs::metadata(&path)
+            .map(|m| m.len() > MAX_SETTINGS_FILE_BYTES)
+            .unwrap_or(true);
+        let parsed = (!too_large)
+            .then(|| std::fs::read_to_string(&path).ok())
+            .flatten()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+        match parsed {
+            Some(mut value) => {
+                sanitize_json(&mut value);
+                if let Ok(bytes) = to_json(&value) {
+                    entries.push((format!("settings/{}", name), bytes));
+                }
+            }
+            // Unreadable files can't be sanitized, so they stay out too
+            None => skipped.push(name),
+        }
+    }
+    (entries, skipped)
+}
+
+fn health_checks(snapshot: &StateSnapshot, settings_dir: &Path) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+    checks.push(match std::fs::metadata(settings_dir) {
+        Ok(meta) if meta.permissions().readonly() => HealthCheck::new(
+            "settings_folder",
+            "error",
+            format!("{} is read-only", settings_dir.display()),
+        ),
+        Ok(_) => HealthCheck::new("settings_folder", "ok", settings_dir.display().to_string()),
+        Err(_) => HealthCheck::new(
+            "settings_folder",
+            "warn",
+            format!("{} does not exist yet", settings_dir.display()),
+        ),
+    });
+    for (platform, path) in &snapshot.terminal_paths {
+        let name = format!("{}_path", platform.to_ascii_lowercase());
+        checks.push(match path {
+            Some(path) if path.exists() => {
+                HealthCheck::new(&name, "ok", path.display().to_string())
+            }
+            Some(path) => HealthCheck::new(
+                &name,
+                "error",
+                format!("{} no longer exists", path.display()),
+            ),
+            None => HealthCheck::new(&name, "warn", "Not configured"),
+        });
+    }
+    checks.push(if snapshot.safe_mode {
+        HealthCheck::new("safe_mode", "warn", "Safe mode is on; writes are blocked")
+    } else {
+        HealthCheck::new("safe_mode", "ok", "Off")
+    });
+    checks.push(match (&snapshot.config, snapshot.constraint_violations) {
+        (None, _) => HealthCheck::new("open_config", "warn", "No config is open"),
+        (Some(_), 0) => HealthCheck::new(
+            "open_config",
+            "ok",
+            format!("{} open, no constraint violations", snapshot.open_configs),
+        ),
+        (Some(_), violations) => HealthCheck::new(
+            "open_config",
+            "warn",
+            format!(
+                "{} open, {} constraint violations in the active config",
+                snapshot.open_configs, violations
+            ),
+        ),
+    });
+    checks.push(HealthCheck::new(
+        "field_locks",
+        "ok",
+        format!("{} locked fields", snapshot.field_locks),
+    ));
+    let vault = vault_location::active_vault_root();
+    checks.push(if vault.is_dir() {
+        HealthCheck::new("vault", "ok", vault.display().to_string())
+    } else {
+        HealthCheck::new(
+            "vault",
+            "warn",
+            format!("{} does not exist", vault.display()),
+        )
+    });
+    let crashes = list_crash_reports().unwrap_or_default().len();
+    checks.push(HealthCheck::new(
+        "crash_reports",
+        if crashes == 0 { "ok" } else { "warn" },
+        format!("{} crash reports on disk", crashes),
+    ));
+    checks
+}
+
+fn version_info() -> VersionInfo {
+    VersionInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        debug_build: cfg!(debug_assertions),
+        created_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// Zip `entries` (stored, not compressed) into memory
+pub fn write_zip(entries: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let fail = |e: zip::result::ZipError| format!("Failed to write support bundle: {}", e);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options).map_err(fail)?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write support bundle: {}", e))?;
+    }
+    Ok(zip.finish().map_err(fail)?.into_inner())
+}
+
+/// Every file of the bundle plus the health checks; skipped settings files are named
+pub fn bundle_entries(
+    snapshot: &StateSnapshot,
+) -> Result<(Vec<(String, Vec<u8>)>, Vec<String>, Vec<HealthCheck>), String> {
+    let settings_dir = daavfx_dir();
+    let mut entries = Vec::new();
+
+    entries.push(("version.json".to_string(), to_json(&version_info())?));
+    let health = health_checks(snapshot, &settings_dir);
+    entries.push(("health.json".to_string(), to_json(&health)?));
+
+    if let Some(config) = &snapshot.config {
+        let mut config = config.clone();
+        apply_redaction(
+            &mut config,
+            &RedactionPolicy::for_audience(ExportAudience::Client),
+        );
+        entries.push(("config.json".to_string(), to_json(&config)?));
+    }
+
+    let (settings, skipped) = settings_entries(&settings_dir);
+    entries.extend(settings);
+
+    let recent =
+        String::from_utf8_lossy(&to_json(&app_log::last_entries(RECENT_LOG_LINES))?).to_string();
+    entries.push((
+        "logs/recent.json".to_string(),
+        redact_text(&recent).into_bytes(),
+    ));
+    for path in app_log::log_files().into_iter().take(LOG_FILES_INCLUDED) {
+        if let Ok(tail) = file_tail(&path, LOG_TAIL_BYTES) {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            entries.push((format!("logs/{}", name), redact_text(&tail).into_bytes()));
+        }
+    }
+
+    if let Some(entry) = audit_log_path().and_then(|path| audit_tail_entry(&path)) {
+        entries.push(entry);
+    }
+    if let Some(import_log) = last_import_log() {
+        let json = String::from_utf8_lossy(&to_json(&import_log)?).to_string();
+        entries.push((
+            "audit/last_import.json".to_string(),
+            redact_text(&json).into_bytes(),
+        ));
+    }
+    let crashes: Vec<CrashReportSummary> = list_crash_reports().unwrap_or_default();
+    entries.push(("audit/crash_reports.json".to_string(), to_json(&crashes)?));
+
+    Ok((entries, skipped, health))
+}
+
+/// `output_path` (a folder gets a timestamped file name), else the Downloads
+/// folder. An existing file is only replaced when it is a zip.
+fn bundle_path(output_path: Option<String>) -> Result<PathBuf, String> {
+    let file_name = format!(
+        "daavfx-support-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let path = match output_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => sanitize_and_validate_path(&PathBuf::from(path.trim()))?,
+        None => dirs::download_dir()
+            .or_else(dirs::document_dir)
+            .unwrap_or_else(std::env::temp_dir),
+    };
+    if path.is_dir() {
+        return Ok(path.join(file_name));
+    }
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if path.exists() && !is_zip {
+        return Err(format!(
+            "{} already exists and is not a zip; choose another file",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Write the support bundle for `snapshot` to `output_path`
+pub fn write_support_bundle(
+    snapshot: &StateSnapshot,
+    output_path: Option<String>,
+) -> Result<SupportBundle, String> {
+    let (entries, skipped, health) = bundle_entries(snapshot)?;
+    let mut files: Vec<String> = entries.iter().map(|(name, _)| name.clone()).collect();
+    let manifest = serde_json::json!({
+        "files": files,
+        "skippedSettings": skipped,
+    });
+    let mut entries = entries;
+    entries.push(("manifest.json".to_string(), to_json(&manifest)?));
+    files.push("manifest.json".to_string());
+
+    let zip = write_zip(&entries)?;
+    let path = bundle_path(output_path)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create bundle folder: {}", e))?;
+    }
+    atomic_write(&path, &zip)?;
+    tracing::info!(file_path = %path.display(), size = zip.len(), "Support bundle written");
+    Ok(SupportBundle {
+        path: path.to_string_lossy().to_string(),
+        size: zip.len() as u64,
+        files,
+        skipped,
+        health,
+    })
+}
+
+/// Zip a sanitized snapshot of the dashboard (config behind `handle`, settings,
+/// logs, audit tail, health and version) for a support ticket
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_support_bundle(
+    handle: Option<String>,
+    output_path: Option<String>,
+    state: State<'_, MTBridgeState>,
+) -> Result<SupportBundle, String> {
+    let field_locks = state
+        .field_locks
+        .read()
+        .await
+        .locks
+        .values()
+        .map(|paths| paths.len())
+        .sum();
+    let snapshot = {
+        let configs = state.configs.read().await;
+        let active = configs.get(handle.as_deref()).ok();
+        StateSnapshot {
+            config: active.map(|open| open.config.clone()),
+            open_configs: configs.list().len(),
+            constraint_violations: active.map_or(0, |open| open.diagnostics.len()),
+            field_locks,
+            terminal_paths: vec![
+                ("MT4".to_string(), state.mt4_path.read().await.clone()),
+                ("MT5".to_string(), state.mt5_path.read().await.clone()),
+            ],
+            safe_mode: crate::safe_mode::is_enabled(),
+        }
+    };
+    tokio::task::spawn_blocking(move || write_support_bundle(&snapshot, output_path))
+        .await
+        .map_err(|e| format!("Failed to write support bundle: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_settings_and_log_text() {
+        let mut settings = serde_json::json!({
+            "endpoint": "https://feed.example.com",
+            "token": "abc123",
+            "privateKey": "ENC:deadbeef",
+            "backends": {"s3": {"kind": "s3", "secret_access_key": "ENC:0011", "access_key_id": "AKIA", "bucket": "presets"}},
+            "channels": ["gold", "ENC:xyz"],
+            "password": "",
+            "licenseKey": null,
+        });
+        sanitize_json(&mut settings);
+        assert_eq!(settings["endpoint"], "https://feed.example.com");
+        assert_eq!(settings["token"], REDACTED);
+        assert_eq!(settings["privateKey"], REDACTED);
+        assert_eq!(settings["backends"]["s3"]["secret_access_key"], REDACTED);
+        assert_eq!(settings["backends"]["s3"]["access_key_id"], REDACTED);
+        assert_eq!(settings["backends"]["s3"]["bucket"], "presets");
+        assert_eq!(settings["channels"][1], REDACTED);
+        // Nothing stored, nothing to hide
+        assert_eq!(settings["password"], "");
+        assert!(settings["licenseKey"].is_null());
+
+        let line = "GET https://x.io/a?token=s3cr3t&x=1 Authorization: Bearer eyJhbGci \"api_key\": \"k-42\"";
+        let redacted = redact_text(line);
+        assert!(!redacted.contains("s3cr3t"));
+        assert!(!redacted.contains("eyJhbGci"));
+        assert!(!redacted.contains("k-42"));
+        assert!(redacted.contains("x=1"));
+    }
+
+    #[test]
+    fn test_bundle_zip_round_trip() {
+        let entries = vec![
+            ("version.json".to_string(), b"{}".to_vec()),
+            ("logs/recent.json".to_string(), b"[]".to_vec()),
+        ];
+        let bytes = write_zip(&entries).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut content = String::new();
+        archive
+            .by_name("logs/recent.json")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "[]");
+    }
+
+    #[test]
+    fn test_audit_tail_is_redacted() {
+        let dir = std::env::temp_dir().join(format!("daavfx_bundle_audit_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join(AUDIT_LOG_FILE);
+        assert!(audit_tail_entry(&log).is_none());
+
+        std::fs::write(
+            &log,
+            "{\"action\":\"close_all\",\"outcome\":\"sent\",\"detail\":\"token=s3cr3t\"}\n",
+        )
+        .unwrap();
+        let (name, bytes) = audit_tail_entry(&log).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert_eq!(name, "audit/audit_log.jsonl");
+        assert!(text.contains("close_all"));
+        assert!(!text.contains("s3cr3t"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bundle_path_refuses_existing_non_zip() {
+        let dir = std::env::temp_dir().join(format!("daavfx_bundle_path_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let notes = dir.join("notes.txt");
+        let old_bundle = dir.join("old.zip");
+        std::fs::write(&notes, "keep me").unwrap();
+        std::fs::write(&old_bundle, "zip").unwrap();
+
+        let as_string = |path: &Path| Some(path.to_string_lossy().to_string());
+        assert!(bundle_path(as_string(&notes)).is_err());
+        assert!(bundle_path(as_string(&old_bundle))
+            .unwrap()
+            .ends_with("old.zip"));
+        let in_folder = bundle_path(as_string(&dir)).unwrap();
+        assert_eq!(
+            in_folder.parent(),
+            Some(dir.canonicalize().unwrap().as_path())
+        );
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "keep me");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_redacted_config_in_bundle() {
+        let mut config = crate::mt_bridge::create_full_v19_config();
+        config.general.license_key = "DAAV-1234-5678".to_string();
+        config.general.news_filter.api_key = "news-secret".to_string();
+        let snapshot = StateSnapshot {
+            config: Some(config),
+            open_configs: 1,
+            ..StateSnapshot::default()
+        };
+        let (entries, _, health) = bundle_entries(&snapshot).unwrap();
+        let config = &entries.iter().find(|(n, _)| n == "config.json").unwrap().1;
+        let config = String::from_utf8_lossy(config);
+        assert!(!config.contains("DAAV-1234"));
+        assert!(!config.contains("news-secret"));
+        assert!(health
+            .iter()
+            .any(|c| c.name == "open_config" && c.status == "ok"));
+    }
+}
@@ -0,0 +1,445 @@
+// ============================================
+// PARAMETER SWEEP (GRID SEARCH) PRESETS
+// ============================================
+//
+// Expands ranges over selected logic fields into the cartesian product of
+// configs and writes each one as a numbered .set file or vault preset.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{
+    sanitize_and_validate_path, save_to_vault, write_massive_v19_setfile, LogicConfig, MTConfig,
+};
+
+pub const SWEEP_FIELDS: [&str; 4] = ["initial_lot", "grid", "multiplier", "trail_value"];
+const DEFAULT_MAX_PRESETS: usize = 256;
+/// Hard ceiling regardless of the requested cap
+const ABSOLUTE_MAX_PRESETS: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepAxis {
+    /// One of SWEEP_FIELDS
+    pub field: String,
+    #[serde(default)]
+    pub start: Option<f64>,
+    #[serde(default)]
+    pub end: Option<f64>,
+    #[serde(default)]
+    pub step: Option<f64>,
+    /// Explicit values instead of start/end/step
+    #[serde(default)]
+    pub values: Option<Vec<f64>>,
+    /// Scope filters; None applies the value to every matching logic
+    #[serde(default)]
+    pub engine: Option<String>,
+    #[serde(default)]
+    pub group: Option<u8>,
+    #[serde(default)]
+    pub logic: Option<String>,
+    /// "Buy" or "Sell" writes only that side's override
+    #[serde(default)]
+    pub direction: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepOptions {
+    /// "files" (default) or "vault"
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Folder for "files"
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// Vault category for "vault" (default "Sweeps")
+    #[serde(default)]
+    pub vault_category: Option<String>,
+    #[serde(default)]
+    pub vault_path_override: Option<String>,
+    #[serde(default)]
+    pub name_prefix: Option<String>,
+    #[serde(default)]
+    pub max_presets: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepPreset {
+    pub index: usize,
+    pub name: String,
+    pub path: Option<String>,
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepResult {
+    pub total: usize,
+    pub presets: Vec<SweepPreset>,
+}
+
+impl SweepAxis {
+    /// Label used in preset names and value maps, e.g. "grid@A.G1.POWER.Buy"
+    pub fn label(&self) -> String {
+        let mut scope = Vec::new();
+        if let Some(engine) = &self.engine {
+            scope.push(engine.to_uppercase());
+        }
+        if let Some(group) = self.group {
+            scope.push(format!("G{}", group));
+        }
+        if let Some(logic) = &self.logic {
+            scope.push(logic.to_uppercase());
+        }
+        if let Some(direction) = &self.direction {
+            scope.push(direction.clone());
+        }
+        if scope.is_empty() {
+            self.field.clone()
+        } else {
+            format!("{}@{}", self.field, scope.join("."))
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !SWEEP_FIELDS.contains(&self.field.as_str()) {
+            return Err(format!(
+                "Unsupported sweep field '{}' (expected one of {})",
+                self.field,
+                SWEEP_FIELDS.join(", ")
+            ));
+        }
+        if let Some(direction) = &self.direction {
+            if direction != "Buy" && direction != "Sell" {
+                return Err(format!("Invalid direction '{}'", direction));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn expand(&self) -> Result<Vec<f64>, String> {
+        self.validate()?;
+        if let Some(values) = &self.values {
+            if values.is_empty() || values.iter().any(|v| !v.is_finite()) {
+                return Err(format!("Axis {} has no valid values", self.label()));
+            }
+            return Ok(values.clone());
+        }
+        let (start, end, step) = match (self.start, self.end, self.step) {
+            (Some(start), Some(end), Some(step)) => (start, end, step),
+            _ => {
+                return Err(format!(
+                    "Axis {} needs either values or start/end/step",
+                    self.label()
+                ))
+            }
+        };
+        if step.is_nan() || step <= 0.0 || !start.is_finite() || !end.is_finite() || end < start {
+            return Err(format!("Axis {} has an invalid range", self.label()));
+        }
+        let count = ((end - start) / step + 1e-9).floor() as usize + 1;
+        if count > ABSOLUTE_MAX_PRESETS {
+            return Err(format!("Axis {} expands to {} values", self.label(), count));
+        }
+        Ok((0..count)
+            .map(|i| ((start + step * i as f64) * 1e8).round() / 1e8)
+            .collect())
+    }
+
+    pub(crate) fn matches(&self, engine_id: &str, group_number: u8, logic: &LogicConfig) -> bool {
+        self.engine
+            .as_ref()
+            .map_or(true, |e| e.eq_ignore_ascii_case(engine_id))
+            && self.group.map_or(true, |g| g == group_number)
+            && self
+                .logic
+                .as_ref()
+                .map_or(true, |l| l.eq_ignore_ascii_case(&logic.logic_name))
+    }
+}
+
+pub(crate) fn set_directional(
+    base: &mut f64,
+    buy: &mut Option<f64>,
+    sell: &mut Option<f64>,
+    direction: Option<&str>,
+    value: f64,
+) {
+    match direction {
+        Some("Buy") => *buy = Some(value),
+        Some("Sell") => *sell = Some(value),
+        _ => {
+            *base = value;
+            // Existing per-side overrides would shadow the base value on export
+            if buy.is_some() {
+                *buy = Some(value);
+            }
+            if sell.is_some() {
+                *sell = Some(value);
+            }
+        }
+    }
+}
+
+/// Write `value` into every logic the axis selects; returns how many logics changed
+pub fn apply_axis_value(config: &mut MTConfig, axis: &SweepAxis, value: f64) -> usize {
+    let direction = axis.direction.as_deref();
+    let mut touched = 0;
+    for engine in &mut config.engines {
+        for group in &mut engine.groups {
+            for logic in &mut group.logics {
+                if !axis.matches(&engine.engine_id, group.group_number, logic) {
+                    continue;
+                }
+                let l = &mut *logic;
+                match axis.field.as_str() {
+                    "initial_lot" => set_directional(
+                        &mut l.initial_lot,
+                        &mut l.initial_lot_b,
+                        &mut l.initial_lot_s,
+                        direction,
+                        value,
+                    ),
+                    "grid" => {
+                        set_directional(&mut l.grid, &mut l.grid_b, &mut l.grid_s, direction, value)
+                    }
+                    "multiplier" => set_directional(
+                        &mut l.multiplier,
+                        &mut l.multiplier_b,
+                        &mut l.multiplier_s,
+                        direction,
+                        value,
+                    ),
+                    "trail_value" => set_directional(
+                        &mut l.trail_value,
+                        &mut l.trail_value_b,
+                        &mut l.trail_value_s,
+                        direction,
+                        value,
+                    ),
+                    _ => continue,
+                }
+                touched += 1;
+            }
+        }
+    }
+    touched
+}
+
+/// Cartesian product of all axis values, in row-major order (last axis varies fastest)
+pub fn sweep_combinations(axes: &[SweepAxis], cap: usize) -> Result<Vec<Vec<f64>>, String> {
+    if axes.is_empty() {
+        return Err("At least one sweep axis is required".to_string());
+    }
+    let expanded: Vec<Vec<f64>> = axes.iter().map(|a| a.expand()).collect::<Result<_, _>>()?;
+    let total = expanded
+        .iter()
+        .try_fold(1usize, |acc, values| acc.checked_mul(values.len()))
+        .unwrap_or(usize::MAX);
+    if total > cap {
+        return Err(format!(
+            "Sweep expands to {} presets, above the cap of {}",
+            total, cap
+        ));
+    }
+
+    let mut combinations: Vec<Vec<f64>> = vec![Vec::new()];
+    for values in &expanded {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|prefix| {
+                values.iter().map(move |v| {
+                    let mut next = prefix.clone();
+                    next.push(*v);
+                    next
+                })
+            })
+            .collect();
+    }
+    Ok(combinations)
+}
+
+fn format_value(value: f64) -> String {
+    let text = format!("{}", value);
+    text.replace('.', "p").replace('-', "m")
+}
+
+/// `{prefix}_{NNN}_{field}-{value}_...` using only characters the vault accepts
+pub fn sweep_preset_name(prefix: &str, index: usize, axes: &[SweepAxis], values: &[f64]) -> String {
+    let parts: Vec<String> = axes
+        .iter()
+        .zip(values)
+        .map(|(axis, value)| {
+            let scope = axis.label().replace('@', "_").replace('.', "_");
+            format!("{}-{}", scope, format_value(*value))
+        })
+        .collect();
+    format!("{}_{:03}_{}", prefix, index, parts.join("_"))
+}
+
+/// Expand `axes` over `config` and write every combination as a .set file or vault preset
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn generate_parameter_sweep(
+    config: MTConfig,
+    axes: Vec<SweepAxis>,
+    options: Option<SweepOptions>,
+) -> Result<SweepResult, String> {
+    let options = options.unwrap_or(SweepOptions {
+        target: None,
+        output_dir: None,
+        vault_category: None,
+        vault_path_override: None,
+        name_prefix: None,
+        max_presets: None,
+    });
+    let cap = options
+        .max_presets
+        .unwrap_or(DEFAULT_MAX_PRESETS)
+        .min(ABSOLUTE_MAX_PRESETS);
+    let combinations = sweep_combinations(&axes, cap)?;
+    let prefix = options
+        .name_prefix
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "sweep".to_string());
+    let to_vault = options.target.as_deref() == Some("vault");
+
+    let output_dir = if to_vault {
+        None
+    } else {
+        let dir = options
+            .output_dir
+            .clone()
+            .ok_or_else(|| "outputDir is required when writing .set files".to_string())?;
+        let sanitized = sanitize_and_validate_path(&PathBuf::from(dir))?;
+        std::fs::create_dir_all(&sanitized)
+            .map_err(|e| format!("Failed to create sweep folder: {}", e))?;
+        Some(sanitized)
+    };
+
+    let mut presets = Vec::with_capacity(combinations.len());
+    for (i, values) in combinations.iter().enumerate() {
+        let index = i + 1;
+        let mut preset_config = config.clone();
+        let mut value_map = BTreeMap::new();
+        for (axis, value) in axes.iter().zip(values) {
+            if apply_axis_value(&mut preset_config, axis, *value) == 0 {
+                return Err(format!(
+                    "Axis {} matches no logic in the config",
+                    axis.label()
+                ));
+            }
+            value_map.insert(axis.label(), *value);
+        }
+        let name = sweep_preset_name(&prefix, index, &axes, values);
+
+        let path = match &output_dir {
+            Some(dir) => {
+                let file_path = dir.join(format!("{}.set", name));
+                write_massive_v19_setfile(preset_config, &file_path, "MT5")?;
+                Some(file_path.to_string_lossy().to_string())
+            }
+            None => {
+                let comments = value_map
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                save_to_vault(
+                    preset_config,
+                    name.clone(),
+                    Some(
+                        options
+                            .vault_category
+                            .clone()
+                            .unwrap_or_else(|| "Sweeps".to_string()),
+                    ),
+                    Some(vec!["sweep".to_string(), prefix.clone()]),
+                    Some(comments),
+                    Some("set".to_string()),
+                    options.vault_path_override.clone(),
+                )
+                .await?;
+                None
+            }
+        };
+
+        presets.push(SweepPreset {
+            index,
+            name,
+            path,
+            values: value_map,
+        });
+    }
+
+    Ok(SweepResult {
+        total: presets.len(),
+        presets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis(field: &str, start: f64, end: f64, step: f64) -> SweepAxis {
+        SweepAxis {
+            field: field.to_string(),
+            start: Some(start),
+            end: Some(end),
+            step: Some(step),
+            values: None,
+            engine: None,
+            group: None,
+            logic: None,
+            direction: None,
+        }
+    }
+
+    #[test]
+    fn test_sweep_combinations_cartesian_product() {
+        let axes = vec![
+            axis("initial_lot", 0.01, 0.03, 0.01),
+            axis("grid", 200.0, 300.0, 100.0),
+        ];
+        let combos = sweep_combinations(&axes, 100).unwrap();
+        assert_eq!(combos.len(), 6);
+        assert_eq!(combos[0], vec![0.01, 200.0]);
+        assert_eq!(combos[1], vec![0.01, 300.0]);
+        assert_eq!(combos[5], vec![0.03, 300.0]);
+
+        assert!(sweep_combinations(&axes, 5).unwrap_err().contains("cap"));
+        assert!(sweep_combinations(&[axis("magic", 1.0, 2.0, 1.0)], 10).is_err());
+    }
+
+    #[test]
+    fn test_apply_axis_value_respects_scope() {
+        let mut config = crate::mt_bridge::create_full_v19_config();
+        let mut scoped = axis("multiplier", 1.5, 1.5, 0.1);
+        scoped.engine = Some("A".to_string());
+        scoped.group = Some(1);
+        scoped.logic = Some("POWER".to_string());
+        scoped.direction = Some("Sell".to_string());
+        assert!(apply_axis_value(&mut config, &scoped, 1.5) >= 1);
+
+        let engine_a = config.engines.iter().find(|e| e.engine_id == "A").unwrap();
+        let group_1 = engine_a
+            .groups
+            .iter()
+            .find(|g| g.group_number == 1)
+            .unwrap();
+        let power = group_1
+            .logics
+            .iter()
+            .find(|l| l.logic_name == "POWER")
+            .unwrap();
+        assert_eq!(power.multiplier_s, Some(1.5));
+
+        assert_eq!(
+            sweep_preset_name("ab", 7, &[scoped], &[1.5]),
+            "ab_007_multiplier_A_G1_POWER_Sell-1p5"
+        );
+    }
+}
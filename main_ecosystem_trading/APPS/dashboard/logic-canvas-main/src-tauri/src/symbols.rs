@@ -0,0 +1,291 @@
+// ============================================
+// BROKER SYMBOL SPECS
+// ============================================
+//
+// Digits, point, contract size and volume limits per symbol. The EA can
+// export the broker's real values to <prefix>SYMBOLS.csv in Common Files;
+// symbols missing there fall back to a bundled catalog of typical specs.
+// Unit conversion, exposure and broker-limit checks all look symbols up here.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::mt_bridge::sanitize_and_validate_path;
+use crate::{naming, os_paths};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolSource {
+    /// Exported by the EA from the broker's symbol properties
+    Broker,
+    /// Bundled typical values
+    Catalog,
+    /// Entered by the user
+    #[default]
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolSpec {
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Price digits, e.g. 5 for EURUSD on most brokers, 3 for USDJPY
+    pub digits: u32,
+    /// Price of one point; 10^-digits when omitted
+    #[serde(default)]
+    pub point: Option<f64>,
+    #[serde(default = "default_contract_size")]
+    pub contract_size: f64,
+    #[serde(default = "default_lot_step")]
+    pub min_lot: f64,
+    #[serde(default = "default_max_lot")]
+    pub max_lot: f64,
+    #[serde(default = "default_lot_step")]
+    pub lot_step: f64,
+    /// Minimum distance of stops from the price, in points
+    #[serde(default)]
+    pub stop_level: u32,
+    /// Distance from the price inside which pending orders can't be changed, in points
+    #[serde(default)]
+    pub freeze_level: u32,
+    /// Account currency value of a one-point move for 1.0 lot, when known
+    #[serde(default)]
+    pub tick_value: Option<f64>,
+    #[serde(default)]
+    pub source: SymbolSource,
+}
+
+fn default_contract_size() -> f64 {
+    100_000.0
+}
+fn default_lot_step() -> f64 {
+    0.01
+}
+fn default_max_lot() -> f64 {
+    100.0
+}
+
+impl SymbolSpec {
+    pub fn point(&self) -> f64 {
+        self.point
+            .filter(|p| *p > 0.0)
+            .unwrap_or_else(|| 10f64.powi(-(self.digits as i32)))
+    }
+}
+
+/// symbol, digits, contract size, min lot, lot step, tick value (USD account)
+const CATALOG: [(&str, u32, f64, f64, f64, Option<f64>); 14] = [
+    ("EURUSD", 5, 100_000.0, 0.01, 0.01, Some(1.0)),
+    ("GBPUSD", 5, 100_000.0, 0.01, 0.01, Some(1.0)),
+    ("AUDUSD", 5, 100_000.0, 0.01, 0.01, Some(1.0)),
+    ("NZDUSD", 5, 100_000.0, 0.01, 0.01, Some(1.0)),
+    ("USDJPY", 3, 100_000.0, 0.01, 0.01, None),
+    ("USDCHF", 5, 100_000.0, 0.01, 0.01, None),
+    ("USDCAD", 5, 100_000.0, 0.01, 0.01, None),
+    ("EURJPY", 3, 100_000.0, 0.01, 0.01, None),
+    ("GBPJPY", 3, 100_000.0, 0.01, 0.01, None),
+    ("EURGBP", 5, 100_000.0, 0.01, 0.01, None),
+    ("XAUUSD", 2, 100.0, 0.01, 0.01, Some(1.0)),
+    ("XAGUSD", 3, 5_000.0, 0.01, 0.01, Some(5.0)),
+    ("BTCUSD", 2, 1.0, 0.01, 0.01, Some(0.01)),
+    ("ETHUSD", 2, 1.0, 0.01, 0.01, Some(0.01)),
+];
+
+fn catalog() -> Vec<SymbolSpec> {
+    CATALOG
+        .iter()
+        .map(
+            |(symbol, digits, contract_size, min_lot, lot_step, tick_value)| SymbolSpec {
+                symbol: Some(symbol.to_string()),
+                digits: *digits,
+                point: None,
+                contract_size: *contract_size,
+                min_lot: *min_lot,
+                max_lot: default_max_lot(),
+                lot_step: *lot_step,
+                stop_level: 0,
+                freeze_level: 0,
+                tick_value: *tick_value,
+                source: SymbolSource::Catalog,
+            },
+        )
+        .collect()
+}
+
+/// "#EURUSD.m", "eurusd-ecn" -> "EURUSD"
+fn base_symbol(symbol: &str) -> String {
+    symbol
+        .trim()
+        .trim_start_matches(|c: char| !c.is_ascii_alphanumeric())
+        .split(['.', '-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase()
+}
+
+/// Exact name first, then the broker suffix stripped ("EURUSDm" matches EURUSD)
+fn find_spec<'a>(specs: &'a [SymbolSpec], symbol: &str) -> Option<&'a SymbolSpec> {
+    let name = |spec: &SymbolSpec| spec.symbol.clone().unwrap_or_default();
+    if let Some(spec) = specs
+        .iter()
+        .find(|s| name(s).eq_ignore_ascii_case(symbol.trim()))
+    {
+        return Some(spec);
+    }
+    let base = base_symbol(symbol);
+    specs
+        .iter()
+        .filter(|s| !name(s).is_empty() && base.starts_with(&name(s).to_ascii_uppercase()))
+        .max_by_key(|s| name(s).len())
+}
+
+/// Parse the EA's symbol export: a header row naming the columns, then one row per symbol.
+/// Accepts MQL property names (SYMBOL_VOLUME_MIN) as well as the short ones.
+pub fn parse_symbol_csv(content: &str) -> Result<Vec<SymbolSpec>, String> {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}').trim())
+        .filter(|l| !l.is_empty());
+    let header = lines
+        .next()
+        .ok_or_else(|| "Symbol CSV is empty".to_string())?;
+    let separator = if header.contains(';') { ';' } else { ',' };
+    let columns: Vec<String> = header
+        .split(separator)
+        .map(|c| {
+            c.trim()
+                .to_ascii_lowercase()
+                .trim_start_matches("symbol_")
+                .replace('_', "")
+        })
+        .collect();
+    let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+
+    let symbol_col =
+        column(&["symbol", "name"]).ok_or_else(|| "Symbol CSV has no symbol column".to_string())?;
+    let digits_col =
+        column(&["digits"]).ok_or_else(|| "Symbol CSV has no digits column".to_string())?;
+    let point_col = column(&["point"]);
+    let contract_col = column(&["contractsize", "tradecontractsize"]);
+    let min_lot_col = column(&["minlot", "volumemin"]);
+    let max_lot_col = column(&["maxlot", "volumemax"]);
+    let step_col = column(&["lotstep", "volumestep"]);
+    let stop_col = column(&["stoplevel", "tradestopslevel", "stopslevel"]);
+    let freeze_col = column(&["freezelevel", "tradefreezelevel"]);
+    let tick_col = column(&["tickvalue", "tradetickvalue"]);
+
+    let mut specs = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let fields: Vec<&str> = line.split(separator).map(str::trim).collect();
+        let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).filter(|v| !v.is_empty());
+        let number = |col: Option<usize>| field(col).and_then(|v| v.parse::<f64>().ok());
+
+        let symbol = field(Some(symbol_col))
+            .ok_or_else(|| format!("Symbol CSV row {}: missing symbol", i + 2))?;
+        let digits = field(Some(digits_col))
+            .and_then(|v| v.parse::<u32>().ok())
+            .ok_or_else(|| format!("Symbol CSV row {}: invalid digits", i + 2))?;
+        specs.push(SymbolSpec {
+            symbol: Some(symbol.to_string()),
+            digits,
+            point: number(point_col),
+            contract_size: number(contract_col).unwrap_or_else(default_contract_size),
+            min_lot: number(min_lot_col).unwrap_or_else(default_lot_step),
+            max_lot: number(max_lot_col).unwrap_or_else(default_max_lot),
+            lot_step: number(step_col).unwrap_or_else(default_lot_step),
+            stop_level: number(stop_col).map(|v| v.max(0.0) as u32).unwrap_or(0),
+            freeze_level: number(freeze_col).map(|v| v.max(0.0) as u32).unwrap_or(0),
+            tick_value: number(tick_col),
+            source: SymbolSource::Broker,
+        });
+    }
+    Ok(specs)
+}
+
+fn symbol_csv_path(csv_path: Option<String>) -> Result<PathBuf, String> {
+    match csv_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => sanitize_and_validate_path(&PathBuf::from(path)),
+        None => Ok(os_paths::common_files_dir()?.join(naming::current().prefixed("SYMBOLS.csv"))),
+    }
+}
+
+fn load_broker_specs(path: &Path) -> Result<Vec<SymbolSpec>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read symbol CSV: {}", e))?;
+    parse_symbol_csv(&content)
+}
+
+/// Bundled spec for `symbol`, ignoring any broker export
+pub(crate) fn catalog_spec(symbol: &str) -> Option<SymbolSpec> {
+    find_spec(&catalog(), symbol).cloned()
+}
+
+/// Broker spec from the EA's CSV when present, otherwise the catalog entry
+pub(crate) fn lookup(symbol: &str, csv_path: Option<String>) -> Result<Option<SymbolSpec>, String> {
+    let broker = load_broker_specs(&symbol_csv_path(csv_path)?)?;
+    if let Some(spec) = find_spec(&broker, symbol) {
+        return Ok(Some(spec.clone()));
+    }
+    Ok(catalog_spec(symbol))
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_symbol_spec(symbol: String, csv_path: Option<String>) -> Result<SymbolSpec, String> {
+    lookup(&symbol, csv_path)?.ok_or_else(|| format!("No spec known for symbol '{}'", symbol))
+}
+
+/// Every known symbol: broker specs first, then catalog entries the broker file doesn't cover
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn list_symbol_specs(csv_path: Option<String>) -> Result<Vec<SymbolSpec>, String> {
+    let mut specs = load_broker_specs(&symbol_csv_path(csv_path)?)?;
+    for spec in catalog() {
+        let name = spec.symbol.clone().unwrap_or_default();
+        if find_spec(&specs, &name).is_none() {
+            specs.push(spec);
+        }
+    }
+    Ok(specs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_matches_broker_suffixes() {
+        let specs = catalog();
+        assert_eq!(find_spec(&specs, "EURUSD").unwrap().digits, 5);
+        assert_eq!(
+            find_spec(&specs, "#eurusd.m").unwrap().symbol.as_deref(),
+            Some("EURUSD")
+        );
+        assert_eq!(find_spec(&specs, "XAUUSDpro").unwrap().contract_size, 100.0);
+        assert!(find_spec(&specs, "US30").is_none());
+        assert!((find_spec(&specs, "USDJPY").unwrap().point() - 0.001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_ea_symbol_csv() {
+        let csv = concat!(
+            "Symbol;Digits;Point;SYMBOL_TRADE_CONTRACT_SIZE;SYMBOL_VOLUME_MIN;",
+            "SYMBOL_VOLUME_STEP;SYMBOL_TRADE_STOPS_LEVEL\r\n",
+            "EURUSD.r;5;0.00001;100000;0.01;0.01;10\r\n",
+            "GER40;1;0.1;1;0.1;0.1;0\r\n",
+        );
+        let specs = parse_symbol_csv(csv).unwrap();
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].stop_level, 10);
+        assert_eq!(specs[0].source, SymbolSource::Broker);
+        assert_eq!(specs[1].min_lot, 0.1);
+        assert_eq!(specs[1].contract_size, 1.0);
+        // Broker rows win over the catalog for the exact broker name
+        assert_eq!(find_spec(&specs, "EURUSD.r").unwrap().stop_level, 10);
+
+        assert!(parse_symbol_csv("Name,Point\nEURUSD,0.00001").is_err());
+        assert!(parse_symbol_csv("Symbol,Digits\nEURUSD,five").is_err());
+    }
+}
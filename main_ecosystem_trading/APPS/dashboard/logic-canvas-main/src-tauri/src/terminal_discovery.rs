@@ -0,0 +1,235 @@
+// ============================================
+// TERMINAL DISCOVERY
+// ============================================
+//
+// Finds MetaTrader data folders (MetaQuotes/Terminal/<hash>) and describes
+// each one as a TerminalProfile. Besides the native %APPDATA% location this
+// also looks inside Wine prefixes, where MT4/5 run headless on Linux:
+// <prefix>/drive_c/users/*/AppData/Roaming/MetaQuotes.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mt_bridge::{extract_broker_name, find_mt4_common_files_path, get_mt_common_files_dir};
+use crate::os_paths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalSource {
+    Native,
+    Wine,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalProfile {
+    /// Data folder name (the hash MetaTrader generates per install)
+    pub id: String,
+    /// "MT4", "MT5" or "unknown"
+    pub platform: String,
+    pub broker_name: String,
+    pub data_path: String,
+    pub common_files_path: String,
+    pub profiles_path: String,
+    pub source: TerminalSource,
+    pub wine_prefix: Option<String>,
+    /// Unix seconds of the data folder's last modification
+    pub last_modified: Option<u64>,
+}
+
+/// Wine prefixes to search: $WINEPREFIX and ~/.wine
+fn wine_prefixes() -> Vec<PathBuf> {
+    let mut prefixes = Vec::new();
+    if let Some(prefix) = std::env::var_os("WINEPREFIX").filter(|v| !v.is_empty()) {
+        prefixes.push(PathBuf::from(prefix));
+    }
+    if let Some(home) = dirs::home_dir() {
+        let default_prefix = home.join(".wine");
+        if !prefixes.contains(&default_prefix) {
+            prefixes.push(default_prefix);
+        }
+    }
+    prefixes
+}
+
+/// Every <prefix>/drive_c/users/*/AppData/Roaming/MetaQuotes that exists
+fn wine_metaquotes_dirs(prefix: &Path) -> Vec<PathBuf> {
+    let users = prefix.join("drive_c").join("users");
+    let Ok(entries) = fs::read_dir(&users) else {
+        return Vec::new();
+    };
+
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| {
+            entry
+                .path()
+                .join("AppData")
+                .join("Roaming")
+                .join("MetaQuotes")
+        })
+        .filter(|dir| dir.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn detect_platform(data_path: &Path) -> &'static str {
+    if data_path.join("MQL5").is_dir() {
+        "MT5"
+    } else if data_path.join("MQL4").is_dir() {
+        "MT4"
+    } else {
+        "unknown"
+    }
+}
+
+/// Profiles for every terminal data folder under `<metaquotes>/Terminal`
+fn scan_metaquotes_dir(
+    metaquotes: &Path,
+    source: TerminalSource,
+    wine_prefix: Option<&Path>,
+) -> Vec<TerminalProfile> {
+    let terminal_root = metaquotes.join("Terminal");
+    let Ok(entries) = fs::read_dir(&terminal_root) else {
+        return Vec::new();
+    };
+    let shared_common = metaquotes.join("Terminal").join("Common").join("Files");
+
+    let mut profiles = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        // Skip common non-broker folders
+        let id = entry.file_name().to_string_lossy().to_string();
+        if id == "Common" || id == "MQL4" || id == "MQL5" || id.starts_with("tperm") {
+            continue;
+        }
+
+        let platform = detect_platform(&path);
+        if platform == "unknown" && !path.join("Files").exists() {
+            continue;
+        }
+
+        let common_files = find_mt4_common_files_path(&path).unwrap_or_else(|| {
+            if source == TerminalSource::Native {
+                get_mt_common_files_dir().unwrap_or_else(|_| shared_common.clone())
+            } else {
+                shared_common.clone()
+            }
+        });
+        let last_modified = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+
+        profiles.push(TerminalProfile {
+            id,
+            platform: platform.to_string(),
+            broker_name: extract_broker_name(&path),
+            data_path: path.to_string_lossy().to_string(),
+            common_files_path: common_files.to_string_lossy().to_string(),
+            profiles_path: path.join("profiles").to_string_lossy().to_string(),
+            source,
+            wine_prefix: wine_prefix.map(|p| p.to_string_lossy().to_string()),
+            last_modified,
+        });
+    }
+    profiles
+}
+
+/// All terminals on this machine, native and Wine, most recently used first
+pub(crate) fn discover_terminals() -> Vec<TerminalProfile> {
+    let mut profiles = Vec::new();
+
+    if let Ok(metaquotes) = os_paths::metaquotes_dir() {
+        profiles.extend(scan_metaquotes_dir(
+            &metaquotes,
+            TerminalSource::Native,
+            None,
+        ));
+    }
+    for prefix in wine_prefixes() {
+        for metaquotes in wine_metaquotes_dirs(&prefix) {
+            profiles.extend(scan_metaquotes_dir(
+                &metaquotes,
+                TerminalSource::Wine,
+                Some(&prefix),
+            ));
+        }
+    }
+
+    profiles.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    profiles
+}
+
+/// Discovered terminal by id (the data folder name) or data folder path
+pub(crate) fn find_terminal(profile: &str) -> Option<TerminalProfile> {
+    discover_terminals()
+        .into_iter()
+        .find(|t| t.id.eq_ignore_ascii_case(profile) || t.data_path == profile)
+}
+
+/// Data folder for a terminal id from discovery, or a data folder path
+pub(crate) fn resolve_terminal_data_path(profile: &str) -> Result<PathBuf, String> {
+    if let Some(terminal) = find_terminal(profile) {
+        return Ok(PathBuf::from(terminal.data_path));
+    }
+    let path = PathBuf::from(profile);
+    if path.is_dir() {
+        Ok(path)
+    } else {
+        Err(format!("Terminal '{}' not found", profile))
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn list_terminal_profiles() -> Result<Vec<TerminalProfile>, String> {
+    Ok(discover_terminals())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wine_prefix_terminals_are_discovered() {
+        let prefix = std::env::temp_dir().join(format!("daavfx_wine_{}", std::process::id()));
+        let metaquotes = prefix
+            .join("drive_c")
+            .join("users")
+            .join("trader")
+            .join("AppData")
+            .join("Roaming")
+            .join("MetaQuotes");
+        let terminal = metaquotes.join("Terminal").join("0A1B2C3D4E5F");
+        fs::create_dir_all(terminal.join("MQL4")).unwrap();
+        fs::create_dir_all(metaquotes.join("Terminal").join("Common").join("Files")).unwrap();
+
+        let found = wine_metaquotes_dirs(&prefix);
+        assert_eq!(found, vec![metaquotes.clone()]);
+
+        let profiles = scan_metaquotes_dir(&metaquotes, TerminalSource::Wine, Some(&prefix));
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, "0A1B2C3D4E5F");
+        assert_eq!(profiles[0].platform, "MT4");
+        assert_eq!(profiles[0].source, TerminalSource::Wine);
+        assert_eq!(
+            profiles[0].common_files_path,
+            metaquotes
+                .join("Terminal")
+                .join("Common")
+                .join("Files")
+                .to_string_lossy()
+        );
+        assert!(wine_metaquotes_dirs(&prefix.join("missing")).is_empty());
+
+        let _ = fs::remove_dir_all(&prefix);
+    }
+}
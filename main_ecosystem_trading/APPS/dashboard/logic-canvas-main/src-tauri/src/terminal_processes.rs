@@ -0,0 +1,398 @@
+// ============================================
+// TERMINAL PROCESSES
+// ============================================
+//
+// Finds running terminal.exe / terminal64.exe processes and starts or closes
+// them per terminal, so backtests and EA deployments can restart a terminal
+// without the user switching windows. A process belongs to a terminal when
+// its executable sits in the install folder named by the data folder's
+// origin.txt (portable installs are their own data folder) or when its
+// command line names the data folder. Processes are listed with `ps` on
+// Linux/macOS (which covers Wine) and PowerShell CIM queries on Windows.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::backtest::decode_text;
+use crate::terminal_discovery::{discover_terminals, find_terminal, resolve_terminal_data_path};
+
+const TERMINAL_EXES: [&str; 2] = ["terminal64.exe", "terminal.exe"];
+const DEFAULT_CLOSE_TIMEOUT_SECS: u64 = 15;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalProcess {
+    pub pid: u32,
+    pub executable: String,
+    pub command_line: String,
+    pub portable: bool,
+    /// Id of the discovered terminal this process runs, when one matches
+    pub terminal_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalCloseResult {
+    pub closed: Vec<u32>,
+    /// Processes still alive when the timeout ran out
+    pub still_running: Vec<u32>,
+}
+
+/// Lowercase, forward slashes, no trailing slash, so Windows and Wine paths compare
+fn normalize_path(path: &str) -> String {
+    path.trim()
+        .trim_matches('"')
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+/// The terminal executable at the start of a command line, quoted or not
+fn executable_from_command_line(command_line: &str) -> Option<String> {
+    let lower = command_line.to_ascii_lowercase();
+    let end = TERMINAL_EXES
+        .iter()
+        .filter_map(|exe| lower.find(exe).map(|i| i + exe.len()))
+        .min()?;
+    Some(
+        command_line[..end]
+            .trim()
+            .trim_start_matches('"')
+            .to_string(),
+    )
+}
+
+fn process_from_command_line(pid: u32, command_line: &str) -> Option<TerminalProcess> {
+    let executable = executable_from_command_line(command_line)?;
+    Some(TerminalProcess {
+        pid,
+        executable,
+        command_line: command_line.to_string(),
+        portable: command_line.to_lowercase().contains("/portable"),
+        terminal_id: None,
+    })
+}
+
+/// `ps -eo pid=,args=` lines: "<pid> <command line>"
+fn parse_ps_output(output: &str) -> Vec<TerminalProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (pid, args) = line.trim().split_once(char::is_whitespace)?;
+            process_from_command_line(pid.parse().ok()?, args.trim())
+        })
+        .collect()
+}
+
+/// ConvertTo-Json prints one object for a single match and an array otherwise
+fn parse_cim_json(output: &str) -> Vec<TerminalProcess> {
+    let value: Value = serde_json::from_str(output.trim()).unwrap_or(Value::Null);
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(_) => vec![value],
+        _ => Vec::new(),
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let pid = item.get("ProcessId")?.as_u64()? as u32;
+            let command_line = item
+                .get("CommandLine")
+                .and_then(Value::as_str)
+                .or_else(|| item.get("ExecutablePath").and_then(Value::as_str))?;
+            let mut process = process_from_command_line(pid, command_line)?;
+            if let Some(path) = item.get("ExecutablePath").and_then(Value::as_str) {
+                process.executable = path.to_string();
+            }
+            Some(process)
+        })
+        .collect()
+}
+
+fn running_terminal_processes() -> Result<Vec<TerminalProcess>, String> {
+    if cfg!(target_os = "windows") {
+        let query = "Get-CimInstance Win32_Process -Filter \"Name='terminal.exe' or Name='terminal64.exe'\" \
+             | Select-Object ProcessId,ExecutablePath,CommandLine | ConvertTo-Json -Compress";
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", query])
+            .output()
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+        Ok(parse_cim_json(&String::from_utf8_lossy(&output.stdout)))
+    } else {
+        let output = Command::new("ps")
+            .args(["-eo", "pid=,args="])
+            .output()
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+        Ok(parse_ps_output(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Install folder for a data folder: origin.txt, or the data folder itself (portable)
+pub(crate) fn terminal_install_dir(data_path: &Path) -> String {
+    fs::read(data_path.join("origin.txt"))
+        .ok()
+        .map(|bytes| decode_text(&bytes).trim().to_string())
+        .filter(|origin| !origin.is_empty())
+        .unwrap_or_else(|| data_path.to_string_lossy().to_string())
+}
+
+fn process_runs_terminal(process: &TerminalProcess, data_path: &Path) -> bool {
+    let install_dir = normalize_path(&terminal_install_dir(data_path));
+    let exe_dir = normalize_path(&process.executable);
+    let exe_dir = exe_dir.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    exe_dir == install_dir
+        || normalize_path(&process.command_line)
+            .contains(&normalize_path(&data_path.to_string_lossy()))
+}
+
+/// Running processes of the terminal whose data folder is `data_path`
+pub(crate) fn processes_for_terminal(data_path: &Path) -> Result<Vec<TerminalProcess>, String> {
+    Ok(running_terminal_processes()?
+        .into_iter()
+        .filter(|process| process_runs_terminal(process, data_path))
+        .collect())
+}
+
+/// Ask the processes to exit (or kill them with `force`) and wait up to `timeout`
+pub(crate) fn close_terminal_processes(
+    data_path: &Path,
+    force: bool,
+    timeout: Duration,
+) -> Result<TerminalCloseResult, String> {
+    let pids: Vec<u32> = processes_for_terminal(data_path)?
+        .iter()
+        .map(|p| p.pid)
+        .collect();
+    for pid in &pids {
+        let pid = pid.to_string();
+        let mut cmd = if cfg!(target_os = "windows") {
+            // Without /F taskkill sends WM_CLOSE, so the terminal saves its profile first
+            let mut cmd = Command::new("taskkill");
+            cmd.args(["/PID", pid.as_str()]);
+            if force {
+                cmd.arg("/F");
+            }
+            cmd
+        } else {
+            let mut cmd = Command::new("kill");
+            cmd.args([if force { "-KILL" } else { "-TERM" }, pid.as_str()]);
+            cmd
+        };
+        cmd.output()
+            .map_err(|e| format!("Failed to close terminal process {}: {}", pid, e))?;
+    }
+
+    let started = Instant::now();
+    let mut still_running = pids.clone();
+    while !still_running.is_empty() && started.elapsed() < timeout {
+        std::thread::sleep(POLL_INTERVAL);
+        let alive: Vec<u32> = processes_for_terminal(data_path)?
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        still_running.retain(|pid| alive.contains(pid));
+    }
+    Ok(TerminalCloseResult {
+        closed: pids
+            .into_iter()
+            .filter(|pid| !still_running.contains(pid))
+            .collect(),
+        still_running,
+    })
+}
+
+/// terminal64.exe when the install has one, terminal.exe otherwise
+fn terminal_executable(install_dir: &str) -> Result<String, String> {
+    TERMINAL_EXES
+        .iter()
+        .map(|exe| PathBuf::from(install_dir).join(exe))
+        .find(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| format!("No terminal executable found in {}", install_dir))
+}
+
+/// Start the terminal for `data_path`, passing /config:<config_path> when given
+pub(crate) fn launch_terminal_process(
+    data_path: &Path,
+    config_path: Option<&str>,
+    wine_prefix: Option<&str>,
+) -> Result<u32, String> {
+    if let Some(running) = processes_for_terminal(data_path)?.first() {
+        return Err(format!(
+            "Terminal is already running (pid {}); close it first",
+            running.pid
+        ));
+    }
+    let install_dir = terminal_install_dir(data_path);
+    let portable = normalize_path(&install_dir) == normalize_path(&data_path.to_string_lossy());
+    let wine_prefix = wine_prefix.filter(|_| !cfg!(target_os = "windows"));
+
+    let mut cmd = match wine_prefix {
+        Some(prefix) => {
+            // origin.txt holds a Windows path, which wine resolves inside the prefix
+            let exe = if data_path.join("MQL5").is_dir() {
+                "terminal64.exe"
+            } else {
+                "terminal.exe"
+            };
+            let mut cmd = Command::new("wine");
+            cmd.env("WINEPREFIX", prefix).arg(format!(
+                "{}\\{}",
+                install_dir.trim_end_matches('\\'),
+                exe
+            ));
+            cmd
+        }
+        None => Command::new(terminal_executable(&install_dir)?),
+    };
+    if portable {
+        cmd.arg("/portable");
+    }
+    if let Some(config) = config_path.filter(|c| !c.trim().is_empty()) {
+        cmd.arg(format!("/config:{}", config));
+    }
+    let child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to launch terminal: {}", e))?;
+    Ok(child.id())
+}
+
+/// Close the terminal gracefully and start it again, so it reloads its EAs.
+/// Returns the new pid, or None when the terminal was not running.
+pub(crate) fn restart_terminal_process(
+    data_path: &Path,
+    wine_prefix: Option<&str>,
+) -> Result<Option<u32>, String> {
+    let closed = close_terminal_processes(
+        data_path,
+        false,
+        Duration::from_secs(DEFAULT_CLOSE_TIMEOUT_SECS),
+    )?;
+    if !closed.still_running.is_empty() {
+        return Err(format!(
+            "Terminal did not close in time (pids {:?})",
+            closed.still_running
+        ));
+    }
+    if closed.closed.is_empty() {
+        return Ok(None);
+    }
+    launch_terminal_process(data_path, None, wine_prefix).map(Some)
+}
+
+/// Running terminal processes, tagged with the discovered terminal each one runs.
+/// With `profile` (terminal id or data folder) only that terminal's processes are returned.
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub async fn list_terminal_processes(
+    profile: Option<String>,
+) -> Result<Vec<TerminalProcess>, String> {
+    tokio::task::spawn_blocking(move || {
+        let data_path = profile
+            .as_deref()
+            .map(resolve_terminal_data_path)
+            .transpose()?;
+        let terminals = discover_terminals();
+        let mut processes = running_terminal_processes()?;
+        for process in &mut processes {
+            process.terminal_id = terminals
+                .iter()
+                .find(|t| process_runs_terminal(process, Path::new(&t.data_path)))
+                .map(|t| t.id.clone());
+        }
+        if let Some(data_path) = data_path {
+            processes.retain(|process| process_runs_terminal(process, &data_path));
+        }
+        Ok(processes)
+    })
+    .await
+    .map_err(|e| format!("Process listing failed: {}", e))?
+}
+
+/// Start a terminal (terminal id or data folder), optionally with a /config ini; returns the pid
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn launch_terminal(profile: String, config_path: Option<String>) -> Result<u32, String> {
+    // A /config ini can auto-start an EA on a live account
+    crate::safe_mode::ensure_writable("Launching a terminal")?;
+    let data_path = resolve_terminal_data_path(&profile)?;
+    tokio::task::spawn_blocking(move || {
+        let wine_prefix = find_terminal(&profile).and_then(|t| t.wine_prefix);
+        launch_terminal_process(&data_path, config_path.as_deref(), wine_prefix.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Launching terminal failed: {}", e))?
+}
+
+/// Close a terminal's processes, gracefully unless `force`, waiting up to `timeout_secs`
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn close_terminal(
+    profile: String,
+    force: Option<bool>,
+    timeout_secs: Option<u64>,
+) -> Result<TerminalCloseResult, String> {
+    crate::safe_mode::ensure_writable("Closing a terminal")?;
+    let data_path = resolve_terminal_data_path(&profile)?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_CLOSE_TIMEOUT_SECS));
+    tokio::task::spawn_blocking(move || {
+        close_terminal_processes(&data_path, force.unwrap_or(false), timeout)
+    })
+    .await
+    .map_err(|e| format!("Closing terminal failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_process_listings() {
+        let ps = "  101 /usr/bin/bash\n\
+                  2040 C:\\Program Files\\MetaTrader 5\\terminal64.exe /portable\n\
+                  2077 \"C:\\MT4 Broker\\terminal.exe\" /config:C:\\t.ini\n";
+        let processes = parse_ps_output(ps);
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[0].pid, 2040);
+        assert_eq!(
+            processes[0].executable,
+            "C:\\Program Files\\MetaTrader 5\\terminal64.exe"
+        );
+        assert!(processes[0].portable);
+        assert_eq!(processes[1].executable, "C:\\MT4 Broker\\terminal.exe");
+        assert!(!processes[1].portable);
+
+        let single =
+            r#"{"ProcessId":7,"ExecutablePath":"C:\\MT5\\terminal64.exe","CommandLine":null}"#;
+        assert_eq!(
+            parse_cim_json(single)[0].executable,
+            "C:\\MT5\\terminal64.exe"
+        );
+        assert_eq!(parse_cim_json("").len(), 0);
+    }
+
+    #[test]
+    fn test_processes_match_by_install_folder() {
+        let data = std::env::temp_dir().join(format!("daavfx_proc_{}", std::process::id()));
+        fs::create_dir_all(&data).unwrap();
+        let origin: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(
+                "C:\\Program Files\\MetaTrader 5"
+                    .encode_utf16()
+                    .flat_map(|u| u.to_le_bytes()),
+            )
+            .collect();
+        fs::write(data.join("origin.txt"), origin).unwrap();
+
+        let ours = process_from_command_line(1, "C:\\Program Files\\MetaTrader 5\\terminal64.exe")
+            .unwrap();
+        let other = process_from_command_line(2, "C:\\Other MT5\\terminal64.exe").unwrap();
+        assert!(process_runs_terminal(&ours, &data));
+        assert!(!process_runs_terminal(&other, &data));
+
+        let _ = fs::remove_dir_all(&data);
+    }
+}
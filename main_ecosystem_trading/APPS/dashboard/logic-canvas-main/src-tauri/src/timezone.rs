@@ -0,0 +1,261 @@
+// ============================================
+// BROKER TIMEZONES
+// ============================================
+//
+// Sessions are entered in broker server time, which differs per broker and
+// usually follows US or EU daylight saving. Each terminal profile stores its
+// broker's standard GMT offset and DST rule so session hours can be converted
+// between local, GMT and server time.
+
+use chrono::{Datelike, Local, NaiveDate, Offset, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::mt_bridge::{atomic_write, MTConfig, SessionConfig};
+
+const TIMEZONE_FILE: &str = "broker_timezones.json";
+const MINUTES_PER_WEEK: i32 = 7 * 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerTimezone {
+    /// Offset from GMT outside daylight saving (e.g. 120 for GMT+2)
+    pub gmt_offset_minutes: i32,
+    /// "none", "us" or "eu"; adds one hour while that region observes DST
+    #[serde(default = "default_dst_rule")]
+    pub dst_rule: String,
+}
+
+fn default_dst_rule() -> String {
+    "none".to_string()
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+fn last_sunday(year: i32, month: u32) -> Option<NaiveDate> {
+    nth_weekday(year, month, Weekday::Sun, 5).or_else(|| nth_weekday(year, month, Weekday::Sun, 4))
+}
+
+/// Whether `date` falls inside the rule's daylight saving period (day precision)
+pub fn is_dst(rule: &str, date: NaiveDate) -> bool {
+    let year = date.year();
+    let (start, end) = match rule {
+        "us" => (
+            nth_weekday(year, 3, Weekday::Sun, 2),
+            nth_weekday(year, 11, Weekday::Sun, 1),
+        ),
+        "eu" => (last_sunday(year, 3), last_sunday(year, 10)),
+        _ => return false,
+    };
+    match (start, end) {
+        (Some(start), Some(end)) => date >= start && date < end,
+        _ => false,
+    }
+}
+
+impl BrokerTimezone {
+    pub fn offset_minutes_at(&self, date: NaiveDate) -> i32 {
+        self.gmt_offset_minutes + if is_dst(&self.dst_rule, date) { 60 } else { 0 }
+    }
+}
+
+fn timezone_store_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join(TIMEZONE_FILE)
+}
+
+fn load_timezones() -> BTreeMap<String, BrokerTimezone> {
+    std::fs::read_to_string(timezone_store_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_timezones(zones: &BTreeMap<String, BrokerTimezone>) -> Result<(), String> {
+    let path = timezone_store_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(zones)
+        .map_err(|e| format!("Failed to serialize broker timezones: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+/// Stored timezone for a terminal profile (terminal path or profile name)
+pub(crate) fn broker_timezone(profile: &str) -> Option<BrokerTimezone> {
+    load_timezones().remove(profile)
+}
+
+/// GMT offset in minutes of `zone` ("local", "gmt" or "server") on `date`
+pub fn zone_offset_minutes(
+    zone: &str,
+    date: NaiveDate,
+    server: Option<&BrokerTimezone>,
+) -> Result<i32, String> {
+    match zone {
+        "gmt" | "utc" => Ok(0),
+        "local" => {
+            let noon = date.and_hms_opt(12, 0, 0).unwrap_or_default();
+            Local
+                .from_local_datetime(&noon)
+                .earliest()
+                .map(|dt| dt.offset().fix().local_minus_utc() / 60)
+                .ok_or_else(|| "Failed to resolve local time offset".to_string())
+        }
+        "server" => server
+            .map(|tz| tz.offset_minutes_at(date))
+            .ok_or_else(|| "No broker timezone stored for this terminal profile".to_string()),
+        other => Err(format!(
+            "Unknown timezone '{}' (expected local, gmt or server)",
+            other
+        )),
+    }
+}
+
+/// Shift a session by `delta_minutes`, moving it to another weekday when it
+/// crosses midnight. The session length is preserved.
+pub fn shift_session(session: &mut SessionConfig, delta_minutes: i32) {
+    let start = session.day * 1440 + session.start_hour * 60 + session.start_minute;
+    let mut length = (session.end_hour * 60 + session.end_minute)
+        - (session.start_hour * 60 + session.start_minute);
+    if length < 0 {
+        length += 1440;
+    }
+    let shifted = (start + delta_minutes).rem_euclid(MINUTES_PER_WEEK);
+    let end = (shifted + length) % 1440;
+    session.day = shifted / 1440;
+    session.start_hour = (shifted % 1440) / 60;
+    session.start_minute = shifted % 60;
+    session.end_hour = end / 60;
+    session.end_minute = end % 60;
+}
+
+pub fn convert_config_sessions(config: &mut MTConfig, delta_minutes: i32) {
+    if delta_minutes == 0 {
+        return;
+    }
+    let general = &mut config.general;
+    let filters = std::iter::once(&mut general.time_filters)
+        .chain(general.time_filters_b.as_mut())
+        .chain(general.time_filters_s.as_mut());
+    for filter in filters {
+        for session in &mut filter.sessions {
+            shift_session(session, delta_minutes);
+        }
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_broker_timezones() -> Result<BTreeMap<String, BrokerTimezone>, String> {
+    Ok(load_timezones())
+}
+
+/// Store the broker timezone for a terminal profile
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn set_broker_timezone(
+    profile: String,
+    gmt_offset_minutes: i32,
+    dst_rule: Option<String>,
+) -> Result<BrokerTimezone, String> {
+    if profile.trim().is_empty() {
+        return Err("Terminal profile is required".to_string());
+    }
+    if !(-12 * 60..=14 * 60).contains(&gmt_offset_minutes) {
+        return Err(format!(
+            "GMT offset {} minutes is out of range",
+            gmt_offset_minutes
+        ));
+    }
+    let dst_rule = dst_rule.unwrap_or_else(default_dst_rule);
+    if !["none", "us", "eu"].contains(&dst_rule.as_str()) {
+        return Err(format!("Unknown DST rule '{}'", dst_rule));
+    }
+    let zone = BrokerTimezone {
+        gmt_offset_minutes,
+        dst_rule,
+    };
+    let mut zones = load_timezones();
+    zones.insert(profile.trim().to_string(), zone.clone());
+    save_timezones(&zones)?;
+    Ok(zone)
+}
+
+/// Convert every session in the config from one clock to another ("local",
+/// "gmt" or "server"). Offsets are evaluated on `reference_date` (default
+/// today) so DST is applied as it will be on that day.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn convert_sessions_timezone(
+    mut config: MTConfig,
+    from: String,
+    to: String,
+    profile: Option<String>,
+    reference_date: Option<String>,
+) -> Result<MTConfig, String> {
+    let date = match reference_date {
+        Some(value) => NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|e| format!("Invalid reference date '{}': {}", value, e))?,
+        None => Local::now().date_naive(),
+    };
+    let server = profile.as_deref().and_then(broker_timezone);
+    let from_offset = zone_offset_minutes(&from, date, server.as_ref())?;
+    let to_offset = zone_offset_minutes(&to, date, server.as_ref())?;
+    convert_config_sessions(&mut config, to_offset - from_offset);
+    Ok(config)
+}
+
+/// Minutes to add to a GMT time to get server time for `profile` on `date`
+pub(crate) fn server_offset_for(profile: Option<&str>, date: NaiveDate) -> i32 {
+    profile
+        .and_then(broker_timezone)
+        .map(|tz| tz.offset_minutes_at(date))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dst_rules() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        // US DST 2026: Mar 8 - Nov 1; EU: Mar 29 - Oct 25
+        assert!(is_dst("us", date(3, 10)));
+        assert!(!is_dst("eu", date(3, 10)));
+        assert!(is_dst("eu", date(7, 1)));
+        assert!(!is_dst("us", date(11, 2)));
+        let tz = BrokerTimezone {
+            gmt_offset_minutes: 120,
+            dst_rule: "us".to_string(),
+        };
+        assert_eq!(tz.offset_minutes_at(date(1, 15)), 120);
+        assert_eq!(tz.offset_minutes_at(date(6, 15)), 180);
+    }
+
+    #[test]
+    fn test_shift_session_crosses_midnight_and_week() {
+        let mut session = SessionConfig {
+            day: 0,
+            start_hour: 1,
+            start_minute: 30,
+            end_hour: 3,
+            end_minute: 0,
+            ..SessionConfig::default()
+        };
+        // GMT+3 server -> GMT
+        shift_session(&mut session, -180);
+        assert_eq!(session.day, 6);
+        assert_eq!((session.start_hour, session.start_minute), (22, 30));
+        assert_eq!((session.end_hour, session.end_minute), (0, 0));
+
+        shift_session(&mut session, 180);
+        assert_eq!(session.day, 0);
+        assert_eq!((session.start_hour, session.start_minute), (1, 30));
+        assert_eq!((session.end_hour, session.end_minute), (3, 0));
+    }
+}
@@ -0,0 +1,515 @@
+// ============================================
+// TRADE HISTORY
+// ============================================
+//
+// Closed trades from a terminal history export (CSV, semicolon or tab
+// separated; UTF-8 or UTF-16 as MT writes them). Columns are located by
+// header name, so account-history exports and EA/script CSVs with a Magic
+// column both load. Rows that aren't trades (balance operations, totals)
+// are skipped and counted. Times stay in server time, the same clock the
+// session filters use.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::backtest::decode_text;
+use crate::mt_bridge::{sanitize_and_validate_path, MTConfig};
+
+const TIME_FORMATS: [&str; 6] = [
+    "%Y.%m.%d %H:%M:%S",
+    "%Y.%m.%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+];
+
+/// Normalized header names per column, first match wins
+const CLOSE_TIME_HEADERS: [&str; 3] = ["closetime", "timeclose", "time"];
+const OPEN_TIME_HEADERS: [&str; 2] = ["opentime", "timeopen"];
+const MAGIC_HEADERS: [&str; 3] = ["magic", "magicnumber", "expert"];
+const PROFIT_HEADERS: [&str; 1] = ["profit"];
+const SYMBOL_HEADERS: [&str; 2] = ["symbol", "item"];
+const VOLUME_HEADERS: [&str; 3] = ["volume", "lots", "size"];
+const TICKET_HEADERS: [&str; 4] = ["ticket", "deal", "order", "position"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryTrade {
+    #[serde(default)]
+    pub ticket: Option<i64>,
+    pub magic: i32,
+    pub symbol: String,
+    #[serde(default)]
+    pub open_time: Option<NaiveDateTime>,
+    pub close_time: NaiveDateTime,
+    pub volume: f64,
+    /// Profit including commission and swap
+    pub profit: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TradeHistory {
+    /// Sorted by close time
+    pub trades: Vec<HistoryTrade>,
+    /// Data rows that weren't closed trades
+    pub skipped_rows: usize,
+}
+
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+/// "1 234.56" and "1\u{a0}234.56" as exported with thousands separators
+fn parse_amount(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}')
+        .collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    cleaned.parse().ok()
+}
+
+/// Parse an exported history table; needs close time, magic and profit columns
+pub fn parse_history(text: &str) -> Result<TradeHistory, String> {
+    let mut lines = text
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .filter(|l| !l.trim().is_empty());
+    let header_line = lines
+        .next()
+        .ok_or_else(|| "History file is empty".to_string())?;
+    let delimiter = ['\t', ';', ',']
+        .into_iter()
+        .max_by_key(|d| header_line.matches(*d).count())
+        .unwrap_or(',');
+    let headers: Vec<String> = header_line.split(delimiter).map(normalize_header).collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.iter().position(|h| h == name))
+    };
+    let required = |names: &[&str], label: &str| {
+        column(names).ok_or_else(|| format!("History has no {} column", label))
+    };
+    let close_col = required(&CLOSE_TIME_HEADERS, "close time")?;
+    let magic_col = required(&MAGIC_HEADERS, "magic number")?;
+    let profit_col = required(&PROFIT_HEADERS, "profit")?;
+    let open_col = column(&OPEN_TIME_HEADERS);
+    let commission_col = column(&["commission"]);
+    let swap_col = column(&["swap"]);
+    let symbol_col = column(&SYMBOL_HEADERS);
+    let volume_col = column(&VOLUME_HEADERS);
+    let ticket_col = column(&TICKET_HEADERS);
+
+    let mut history = TradeHistory::default();
+    for line in lines {
+        let cells: Vec<&str> = line.split(delimiter).map(str::trim).collect();
+        let cell = |col: Option<usize>| col.and_then(|c| cells.get(c).copied());
+        let parsed = (|| {
+            let close_time = parse_time(cell(Some(close_col))?)?;
+            let magic = cell(Some(magic_col))?.parse::<i32>().ok()?;
+            let extra = |col| cell(col).and_then(parse_amount).unwrap_or(0.0);
+            let profit =
+                parse_amount(cell(Some(profit_col))?)? + extra(commission_col) + extra(swap_col);
+            Some(HistoryTrade {
+                ticket: cell(ticket_col).and_then(|t| t.parse().ok()),
+                magic,
+                symbol: cell(symbol_col).unwrap_or_default().to_string(),
+                open_time: cell(open_col).and_then(parse_time),
+                close_time,
+                volume: cell(volume_col).and_then(parse_amount).unwrap_or(0.0),
+                profit,
+            })
+        })();
+        match parsed {
+            Some(trade) => history.trades.push(trade),
+            None => history.skipped_rows += 1,
+        }
+    }
+    history.trades.sort_by_key(|t| t.close_time);
+    Ok(history)
+}
+
+pub fn load_history(path: &Path) -> Result<TradeHistory, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read history: {}", e))?;
+    parse_history(&decode_text(&bytes))
+}
+
+pub(crate) fn load_history_file(history_path: &str) -> Result<TradeHistory, String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(history_path))?;
+    load_history(&path)
+}
+
+// ============================================
+// TRADING-HOURS HEATMAP
+// ============================================
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapCell {
+    pub trades: u32,
+    pub wins: u32,
+    pub net_profit: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MagicHeatmap {
+    pub magic: i32,
+    pub trades: u32,
+    pub net_profit: f64,
+    /// [day][hour], day 0 = Sunday as in SessionConfig
+    pub cells: Vec<Vec<HeatmapCell>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradingHoursHeatmap {
+    /// "open" or "close": which trade time picked the cell
+    pub bucketed_by: String,
+    pub magics: Vec<MagicHeatmap>,
+    pub skipped_rows: usize,
+}
+
+/// Bucket trades per magic by day of week and hour. Trades go by open time
+/// (when the logic decided to trade) unless `use_close_time` is set or the
+/// export has no open time.
+pub fn build_heatmap(
+    trades: &[HistoryTrade],
+    magic_numbers: &[i32],
+    use_close_time: bool,
+) -> Vec<MagicHeatmap> {
+    let mut by_magic: BTreeMap<i32, MagicHeatmap> = BTreeMap::new();
+    for trade in trades {
+        if !magic_numbers.is_empty() && !magic_numbers.contains(&trade.magic) {
+            continue;
+        }
+        let time = if use_close_time {
+            trade.close_time
+        } else {
+            trade.open_time.unwrap_or(trade.close_time)
+        };
+        let heatmap = by_magic.entry(trade.magic).or_insert_with(|| MagicHeatmap {
+            magic: trade.magic,
+            trades: 0,
+            net_profit: 0.0,
+            cells: vec![vec![HeatmapCell::default(); 24]; 7],
+        });
+        heatmap.trades += 1;
+        heatmap.net_profit += trade.profit;
+        let cell = &mut heatmap.cells[time.weekday().num_days_from_sunday() as usize]
+            [time.hour() as usize];
+        cell.trades += 1;
+        cell.net_profit += trade.profit;
+        if trade.profit > 0.0 {
+            cell.wins += 1;
+        }
+    }
+    by_magic.into_values().collect()
+}
+
+/// Profit by hour-of-day and day-of-week per magic number, from an exported
+/// terminal history, for tuning session windows
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_trading_hours_heatmap(
+    history_path: String,
+    magic_numbers: Option<Vec<i32>>,
+    use_close_time: Option<bool>,
+) -> Result<TradingHoursHeatmap, String> {
+    let history = load_history_file(&history_path)?;
+    let use_close_time = use_close_time.unwrap_or(false);
+    Ok(TradingHoursHeatmap {
+        bucketed_by: if use_close_time { "close" } else { "open" }.to_string(),
+        magics: build_heatmap(
+            &history.trades,
+            &magic_numbers.unwrap_or_default(),
+            use_close_time,
+        ),
+        skipped_rows: history.skipped_rows,
+    })
+}
+
+// ============================================
+// EQUITY CURVE
+// ============================================
+
+/// Curves longer than this are downsampled unless the caller asks otherwise
+const DEFAULT_CURVE_POINTS: usize = 500;
+
+/// Inclusive date range, YYYY.MM.DD or YYYY-MM-DD; open ends are unbounded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRange {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityPoint {
+    pub time: NaiveDateTime,
+    /// Cumulative closed P/L
+    pub balance: f64,
+    /// Balance plus the open trades' P/L, interpolated over their lifetime
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityCurve {
+    pub magic_numbers: Vec<i32>,
+    pub trades: usize,
+    pub net_profit: f64,
+    /// Largest peak-to-trough drop of the equity approximation
+    pub max_drawdown: f64,
+    /// Points before downsampling
+    pub total_points: usize,
+    pub points: Vec<EquityPoint>,
+    pub skipped_rows: usize,
+}
+
+/// Magic numbers a preset trades under (main, buy and sell)
+pub fn preset_magic_numbers(config: &MTConfig) -> Vec<i32> {
+    let general = &config.general;
+    let mut magics = Vec::new();
+    for magic in [
+        general.magic_number,
+        general.magic_number_buy,
+        general.magic_number_sell,
+    ] {
+        if magic != 0 && !magics.contains(&magic) {
+            magics.push(magic);
+        }
+    }
+    magics
+}
+
+fn parse_range_date(value: &Option<String>) -> Result<Option<NaiveDate>, String> {
+    match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => NaiveDate::parse_from_str(v, "%Y.%m.%d")
+            .or_else(|_| NaiveDate::parse_from_str(v, "%Y-%m-%d"))
+            .map(Some)
+            .map_err(|_| format!("Invalid date '{}': expected YYYY.MM.DD", v)),
+    }
+}
+
+/// Trades of the given magics (all when empty) closed inside `range`
+pub fn select_trades<'a>(
+    trades: &'a [HistoryTrade],
+    magic_numbers: &[i32],
+    range: &HistoryRange,
+) -> Result<Vec<&'a HistoryTrade>, String> {
+    let from = parse_range_date(&range.from)?.map(|d| d.and_time(NaiveTime::MIN));
+    let to = parse_range_date(&range.to)?
+        .and_then(|d| d.succ_opt())
+        .map(|d| d.and_time(NaiveTime::MIN));
+    Ok(trades
+        .iter()
+        .filter(|t| magic_numbers.is_empty() || magic_numbers.contains(&t.magic))
+        .filter(|t| from.map_or(true, |from| t.close_time >= from))
+        .filter(|t| to.map_or(true, |to| t.close_time < to))
+        .collect())
+}
+
+/// Balance/equity after every trade open and close, starting from zero.
+/// Without tick data each open trade's floating P/L is taken as moving
+/// linearly from zero at open to its final profit at close, which is enough
+/// to show drawdown depth.
+pub fn build_equity_curve(selected: &[&HistoryTrade]) -> Vec<EquityPoint> {
+    let mut times: Vec<NaiveDateTime> = selected
+        .iter()
+        .flat_map(|t| t.open_time.into_iter().chain(Some(t.close_time)))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+
+    let mut points = Vec::with_capacity(times.len());
+    for time in times {
+        let mut balance = 0.0;
+        let mut floating = 0.0;
+        for trade in selected {
+            if trade.close_time <= time {
+                balance += trade.profit;
+            } else if let Some(open) = trade.open_time.filter(|open| *open < time) {
+                let elapsed = (time - open).num_seconds() as f64;
+                let lifetime = (trade.close_time - open).num_seconds() as f64;
+                floating += trade.profit * elapsed / lifetime;
+            }
+        }
+        points.push(EquityPoint {
+            time,
+            balance,
+            equity: balance + floating,
+        });
+    }
+    points
+}
+
+/// Keep at most `max_points`: the first and last points, plus the lowest
+/// equity point of each bucket in between so drawdowns survive
+pub fn downsample(points: &[EquityPoint], max_points: usize) -> Vec<EquityPoint> {
+    let max_points = max_points.max(2);
+    if points.len() <= max_points {
+        return points.to_vec();
+    }
+    let inner = &points[1..points.len() - 1];
+    let buckets = max_points - 2;
+    let mut out = Vec::with_capacity(max_points);
+    out.push(points[0].clone());
+    for b in 0..buckets {
+        let start = b * inner.len() / buckets;
+        let end = (b + 1) * inner.len() / buckets;
+        if let Some(low) = inner[start..end]
+            .iter()
+            .min_by(|a, b| a.equity.total_cmp(&b.equity))
+        {
+            out.push(low.clone());
+        }
+    }
+    out.push(points[points.len() - 1].clone());
+    out
+}
+
+pub(crate) fn max_drawdown(points: &[EquityPoint]) -> f64 {
+    let mut peak = 0.0f64;
+    let mut drawdown = 0.0f64;
+    for point in points {
+        peak = peak.max(point.equity);
+        drawdown = drawdown.max(peak - point.equity);
+    }
+    drawdown
+}
+
+/// Cumulative P/L for a preset's trades from an exported terminal history.
+/// Magic numbers come from `magic_numbers`, else from `config`; with neither
+/// every trade in the history counts.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_equity_curve(
+    history_path: String,
+    magic_numbers: Option<Vec<i32>>,
+    config: Option<MTConfig>,
+    range: Option<HistoryRange>,
+    max_points: Option<usize>,
+) -> Result<EquityCurve, String> {
+    let history = load_history_file(&history_path)?;
+    let magic_numbers = match magic_numbers.filter(|m| !m.is_empty()) {
+        Some(magics) => magics,
+        None => config
+            .as_ref()
+            .map(preset_magic_numbers)
+            .unwrap_or_default(),
+    };
+    let selected = select_trades(&history.trades, &magic_numbers, &range.unwrap_or_default())?;
+    let points = build_equity_curve(&selected);
+    Ok(EquityCurve {
+        magic_numbers,
+        trades: selected.len(),
+        net_profit: points.last().map_or(0.0, |p| p.balance),
+        max_drawdown: max_drawdown(&points),
+        total_points: points.len(),
+        points: downsample(&points, max_points.unwrap_or(DEFAULT_CURVE_POINTS)),
+        skipped_rows: history.skipped_rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HISTORY: &str = "\
+Ticket;Open Time;Type;Size;Symbol;Close Time;Commission;Swap;Profit;Magic
+101;2026.03.02 08:15:00;buy;0.10;XAUUSD;2026.03.02 09:40:00;-0.70;0.00;25.50;1001
+102;2026.03.02 08:50:00;sell;0.10;XAUUSD;2026.03.02 10:05:00;-0.70;-0.10;-12.00;1001
+103;2026.03.04 14:00:00;buy;0.20;EURUSD;2026.03.05 01:00:00;0.00;0.00;1 200.00;2002
+;;balance;;;2026.03.01 00:00:00;;;5000.00;
+";
+
+    #[test]
+    fn test_parse_history() {
+        let history = parse_history(HISTORY).unwrap();
+        assert_eq!(history.trades.len(), 3);
+        assert_eq!(history.skipped_rows, 1);
+        let first = &history.trades[0];
+        assert_eq!(first.ticket, Some(101));
+        assert_eq!(first.magic, 1001);
+        assert!((first.profit - 24.8).abs() < 1e-9);
+        assert_eq!(history.trades[2].profit, 1200.0);
+
+        let err = parse_history("Time,Profit\n2026.03.02 08:15,1.0").unwrap_err();
+        assert_eq!(err, "History has no magic number column");
+    }
+
+    #[test]
+    fn test_build_heatmap() {
+        let history = parse_history(HISTORY).unwrap();
+        let maps = build_heatmap(&history.trades, &[], false);
+        assert_eq!(maps.len(), 2);
+        let gold = &maps[0];
+        assert_eq!(gold.magic, 1001);
+        // Both trades opened Monday 08:xx
+        let cell = &gold.cells[1][8];
+        assert_eq!((cell.trades, cell.wins), (2, 1));
+        assert!((cell.net_profit - 12.0).abs() < 1e-9);
+
+        // By close time they land in separate hours
+        let by_close = build_heatmap(&history.trades, &[1001], true);
+        assert_eq!(by_close.len(), 1);
+        assert_eq!(by_close[0].cells[1][9].trades, 1);
+        assert_eq!(by_close[0].cells[1][10].trades, 1);
+    }
+
+    #[test]
+    fn test_equity_curve() {
+        let history = parse_history(HISTORY).unwrap();
+        let selected = select_trades(&history.trades, &[1001], &HistoryRange::default()).unwrap();
+        assert_eq!(selected.len(), 2);
+        let points = build_equity_curve(&selected);
+        // Opens at 08:15 and 08:50, closes at 09:40 and 10:05
+        assert_eq!(points.len(), 4);
+        let last = points.last().unwrap();
+        assert!((last.balance - 12.0).abs() < 1e-9);
+        assert_eq!(last.balance, last.equity);
+        // At the first close the losing trade is partly underwater
+        let first_close = &points[2];
+        assert!((first_close.balance - 24.8).abs() < 1e-9);
+        assert!(first_close.equity < first_close.balance);
+
+        let range = HistoryRange {
+            from: Some("2026-03-03".to_string()),
+            to: None,
+        };
+        let later = select_trades(&history.trades, &[], &range).unwrap();
+        assert_eq!(later.len(), 1);
+        let bad = HistoryRange {
+            from: Some("March".to_string()),
+            to: None,
+        };
+        assert!(select_trades(&history.trades, &[], &bad).is_err());
+
+        let sampled = downsample(&points, 3);
+        assert_eq!(sampled.len(), 3);
+        assert_eq!(sampled[0], points[0]);
+        assert_eq!(sampled[2], points[3]);
+        assert!(sampled[1].equity <= points[1].equity.min(points[2].equity));
+        assert!(max_drawdown(&points) > 0.0);
+    }
+}
@@ -4,6 +4,7 @@
 //! trading config commands. This is the core of your assistant.
 //!
 //! Architecture:
+//! - WordPiece subword tokens (see subword_tokenizer)
 //! - Token embeddings (learned)
 //! - Positional encoding (sinusoidal)
 //! - Multi-head self-attention (4 heads)
@@ -11,7 +12,10 @@
 //! - Layer norm
 //! - Output: intent classification
 
-use rand::Rng;
+use crate::quantize::{Precision, QuantizedLinear};
+use crate::subword_tokenizer::SubwordTokenizer;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -26,8 +30,8 @@ const NUM_HEADS: usize = 4;
 const FF_DIM: usize = 512;
 /// Number of transformer layers
 const NUM_LAYERS: usize = 2;
-/// Vocabulary size
-const VOCAB_SIZE: usize = 256;
+/// Vocabulary size (upper bound for the trained tokenizer)
+pub const VOCAB_SIZE: usize = 256;
 /// Number of intent classes
 const NUM_INTENTS: usize = 16;
 
@@ -77,43 +81,6 @@ impl TransformerIntent {
     }
 }
 
-/// Token vocabulary
-pub struct Vocabulary {
-    char_to_idx: HashMap<char, usize>,
-    idx_to_char: Vec<char>,
-}
-
-impl Vocabulary {
-    pub fn new() -> Self {
-        let chars: Vec<char> = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_. -"
-            .chars()
-            .collect();
-        let mut char_to_idx = HashMap::new();
-        for (i, &c) in chars.iter().enumerate() {
-            char_to_idx.insert(c, i);
-        }
-        char_to_idx.insert('<', chars.len());
-        char_to_idx.insert('>', chars.len() + 1);
-        let idx_to_char: Vec<char> = char_to_idx.keys().cloned().collect();
-        Vocabulary {
-            char_to_idx,
-            idx_to_char,
-        }
-    }
-
-    pub fn encode(&self, text: &str) -> Vec<usize> {
-        let mut result = Vec::with_capacity(MAX_SEQ_LEN);
-        for c in text.chars().take(MAX_SEQ_LEN - 1) {
-            result.push(*self.char_to_idx.get(&c).unwrap_or(&0));
-        }
-        result.push(*self.char_to_idx.get(&'>').unwrap_or(&1));
-        while result.len() < MAX_SEQ_LEN {
-            result.push(*self.char_to_idx.get(&'<').unwrap_or(&0));
-        }
-        result
-    }
-}
-
 /// Scaled dot-product attention
 fn attention(q: &[f32], k: &[f32], v: &[f32], mask: &[f32]) -> Vec<f32> {
     let dim = q.len();
@@ -238,6 +205,30 @@ impl FeedForward {
     }
 }
 
+impl FeedForward {
+    /// Same as `forward` with int8 copies of w1/w2
+    fn forward_int8(&self, w1: &QuantizedLinear, w2: &QuantizedLinear, x: &[f32]) -> Vec<f32> {
+        let mut hidden = w1.forward(x);
+        for (h, b) in hidden.iter_mut().zip(&self.b1) {
+            *h += b;
+            *h = *h * 0.5 * (1.0 + (*h * 1.702).tanh());
+        }
+        let mut output = w2.forward(&hidden);
+        for (o, b) in output.iter_mut().zip(&self.b2) {
+            *o += b;
+        }
+        output
+    }
+}
+
+/// Int8 copies of the encoder and head weights
+struct QuantizedWeights {
+    attn_out: Vec<QuantizedLinear>,
+    ffn_w1: Vec<QuantizedLinear>,
+    ffn_w2: Vec<QuantizedLinear>,
+    head: QuantizedLinear,
+}
+
 /// Layer normalization
 fn layer_norm(x: &[f32], gamma: &[f32], beta: &[f32]) -> Vec<f32> {
     let mean = x.iter().sum::<f32>() / x.len() as f32;
@@ -259,6 +250,149 @@ fn positional_encoding(pos: usize, i: usize) -> f32 {
     }
 }
 
+/// How a prediction is picked from the intent distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecodeStrategy {
+    /// Most likely intent
+    #[default]
+    Greedy,
+    /// Keep the `beam_width` best hypotheses; the model emits a single intent
+    /// step, so the beam is the ranked top of the distribution
+    Beam,
+    /// Draw from the (temperature, top-k, top-p) filtered distribution
+    Sample,
+}
+
+/// Decoding options for predict_decoded
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DecodeOptions {
+    pub strategy: DecodeStrategy,
+    /// Number of candidates kept and returned as alternatives
+    pub beam_width: usize,
+    pub top_k: Option<usize>,
+    /// Nucleus sampling: keep the most likely intents up to this total probability
+    pub top_p: Option<f32>,
+    /// Below 1 sharpens the distribution, above 1 flattens it
+    pub temperature: f32,
+    /// Fixed seed for reproducible sampling
+    pub seed: Option<u64>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            strategy: DecodeStrategy::Greedy,
+            beam_width: 3,
+            top_k: None,
+            top_p: None,
+            temperature: 1.0,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct IntentCandidate {
+    pub intent: String,
+    pub score: f32,
+}
+
+/// A decoded prediction: the chosen intent plus ranked alternatives
+#[derive(Debug, Clone)]
+pub struct DecodedPrediction {
+    pub intent: TransformerIntent,
+    pub confidence: f32,
+    /// Chosen intent first, then the next best up to `beam_width`
+    pub candidates: Vec<IntentCandidate>,
+}
+
+/// Apply temperature, top-k and top-p to the model output and pick an intent
+pub fn decode_distribution<R: Rng>(
+    probs: &[f32],
+    options: &DecodeOptions,
+    rng: &mut R,
+) -> DecodedPrediction {
+    // Unused output slots all map to Unknown; fold them into one entry
+    let mut dist: Vec<(TransformerIntent, f32)> = Vec::new();
+    for (idx, &p) in probs.iter().enumerate() {
+        let intent = TransformerIntent::from_index(idx);
+        match dist.iter_mut().find(|(i, _)| *i == intent) {
+            Some(entry) => entry.1 += p,
+            None => dist.push((intent, p)),
+        }
+    }
+
+    let temperature = options.temperature.max(0.05);
+    for entry in &mut dist {
+        entry.1 = entry.1.max(0.0).powf(1.0 / temperature);
+    }
+    dist.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    normalize(&mut dist);
+
+    if let Some(k) = options.top_k {
+        dist.truncate(k.max(1));
+        normalize(&mut dist);
+    }
+    if let Some(top_p) = options.top_p {
+        let mut total = 0.0;
+        let keep = dist
+            .iter()
+            .position(|(_, p)| {
+                total += p;
+                total >= top_p
+            })
+            .map_or(dist.len(), |i| i + 1);
+        dist.truncate(keep);
+        normalize(&mut dist);
+    }
+
+    let chosen = match options.strategy {
+        DecodeStrategy::Greedy | DecodeStrategy::Beam => 0,
+        DecodeStrategy::Sample => {
+            let draw: f32 = rng.gen();
+            let mut total = 0.0;
+            dist.iter()
+                .position(|(_, p)| {
+                    total += p;
+                    draw < total
+                })
+                .unwrap_or(dist.len() - 1)
+        }
+    };
+    let (intent, confidence) = dist[chosen];
+    let mut candidates = vec![dist[chosen]];
+    candidates.extend(
+        dist.iter()
+            .enumerate()
+            .filter(|(i, _)| *i != chosen)
+            .map(|(_, c)| *c),
+    );
+    candidates.truncate(options.beam_width.max(1));
+
+    DecodedPrediction {
+        intent,
+        confidence,
+        candidates: candidates
+            .into_iter()
+            .map(|(intent, score)| IntentCandidate {
+                intent: intent.as_str().to_string(),
+                score,
+            })
+            .collect(),
+    }
+}
+
+fn normalize(dist: &mut [(TransformerIntent, f32)]) {
+    let total: f32 = dist.iter().map(|(_, p)| p).sum();
+    if total > 0.0 {
+        for entry in dist.iter_mut() {
+            entry.1 /= total;
+        }
+    }
+}
+
 /// Training example
 #[derive(Debug, Clone)]
 pub struct TrainingExample {
@@ -268,7 +402,7 @@ pub struct TrainingExample {
 
 /// The Transformer Model
 pub struct TradingTransformer {
-    vocab: Vocabulary,
+    tokenizer: SubwordTokenizer,
     /// Token embeddings: [vocab_size][embed_dim]
     token_embeddings: Vec<f32>,
     /// Positional embeddings: [max_seq_len][embed_dim]
@@ -287,11 +421,13 @@ pub struct TradingTransformer {
     output_bias: Vec<f32>,
     /// Learnable corrections
     corrections: HashMap<String, String>,
+    /// Set when inference runs on int8 weights
+    quantized: Option<QuantizedWeights>,
 }
 
 impl TradingTransformer {
     pub fn new() -> Self {
-        let vocab = Vocabulary::new();
+        let tokenizer = Self::train_tokenizer(&generate_training_data());
         let mut rng = rand::thread_rng();
 
         let token_embeddings: Vec<f32> = (0..VOCAB_SIZE * EMBED_DIM)
@@ -318,7 +454,7 @@ impl TradingTransformer {
         let output_bias = vec![0.0f32; NUM_INTENTS];
 
         TradingTransformer {
-            vocab,
+            tokenizer,
             token_embeddings,
             pos_embeddings,
             attention,
@@ -330,9 +466,89 @@ impl TradingTransformer {
             output_proj,
             output_bias,
             corrections: HashMap::new(),
+            quantized: None,
         }
     }
 
+    pub fn precision(&self) -> Precision {
+        if self.quantized.is_some() {
+            Precision::Int8
+        } else {
+            Precision::F32
+        }
+    }
+
+    /// Switch inference between f32 and int8 weights
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.quantized = match precision {
+            Precision::F32 => None,
+            Precision::Int8 => Some(self.quantize()),
+        };
+    }
+
+    fn quantize(&self) -> QuantizedWeights {
+        QuantizedWeights {
+            attn_out: self
+                .attention
+                .iter()
+                .map(|a| QuantizedLinear::from_out_in(&a.w_o, EMBED_DIM, EMBED_DIM))
+                .collect(),
+            ffn_w1: self
+                .ffn
+                .iter()
+                .map(|f| QuantizedLinear::from_in_out(&f.w1, EMBED_DIM, FF_DIM))
+                .collect(),
+            ffn_w2: self
+                .ffn
+                .iter()
+                .map(|f| QuantizedLinear::from_in_out(&f.w2, FF_DIM, EMBED_DIM))
+                .collect(),
+            head: QuantizedLinear::from_in_out(&self.output_proj, EMBED_DIM, NUM_INTENTS),
+        }
+    }
+
+    /// Bytes of weights read by inference at the current precision
+    pub fn inference_weight_bytes(&self) -> usize {
+        match &self.quantized {
+            Some(q) => q
+                .attn_out
+                .iter()
+                .chain(&q.ffn_w1)
+                .chain(&q.ffn_w2)
+                .chain(std::iter::once(&q.head))
+                .map(QuantizedLinear::size_bytes)
+                .sum(),
+            None => {
+                (NUM_LAYERS * (EMBED_DIM * EMBED_DIM + 2 * EMBED_DIM * FF_DIM)
+                    + EMBED_DIM * NUM_INTENTS)
+                    * std::mem::size_of::<f32>()
+            }
+        }
+    }
+
+    /// Learn a subword vocabulary that fits the embedding table
+    pub fn train_tokenizer(examples: &[TrainingExample]) -> SubwordTokenizer {
+        let corpus: Vec<&str> = examples.iter().map(|ex| ex.text.as_str()).collect();
+        SubwordTokenizer::train(&corpus, VOCAB_SIZE)
+    }
+
+    pub fn tokenizer(&self) -> &SubwordTokenizer {
+        &self.tokenizer
+    }
+
+    /// Swap the tokenizer; it must fit the embedding table
+    pub fn set_tokenizer(&mut self, tokenizer: SubwordTokenizer) -> Result<(), String> {
+        if tokenizer.vocab_size() > VOCAB_SIZE {
+            return Err(format!(
+                "Tokenizer has {} tokens; the transformer supports at most {}",
+                tokenizer.vocab_size(),
+                VOCAB_SIZE
+            ));
+        }
+        self.tokenizer = tokenizer;
+        Ok(())
+    }
+
     /// Get token embedding
     fn get_token_embedding(&self, token_idx: usize) -> &[f32] {
         let idx = token_idx.min(VOCAB_SIZE - 1) * EMBED_DIM;
@@ -349,7 +565,7 @@ impl TradingTransformer {
     pub fn forward(&self, input: &str) -> Vec<f32> {
         // Preprocess input (apply corrections)
         let processed = self.apply_corrections(input);
-        let tokens = self.vocab.encode(&processed);
+        let tokens = self.tokenizer.encode_padded(&processed, MAX_SEQ_LEN);
 
         // Build input embeddings (token + position)
         let mut embeddings = vec![0.0f32; MAX_SEQ_LEN * EMBED_DIM];
@@ -361,22 +577,10 @@ impl TradingTransformer {
             }
         }
 
-        // Transformer layers
+        // Transformer layers, applied to each position's embedding row
         let mut hidden = embeddings;
-        for layer in 0..NUM_LAYERS {
-            // Self-attention + residual
-            let attn_out = self.attention[layer].forward(&hidden);
-            for i in 0..hidden.len() {
-                hidden[i] += attn_out[i];
-            }
-            hidden = layer_norm(&hidden, &self.norm1_gamma, &self.norm1_beta);
-
-            // FFN + residual
-            let ffn_out = self.ffn[layer].forward(&hidden);
-            for i in 0..hidden.len() {
-                hidden[i] += ffn_out[i];
-            }
-            hidden = layer_norm(&hidden, &self.norm2_gamma, &self.norm2_beta);
+        for row in hidden.chunks_exact_mut(EMBED_DIM) {
+            self.encode_row(row);
         }
 
         // Average pooling over sequence
@@ -392,10 +596,22 @@ impl TradingTransformer {
 
         // Output projection + softmax
         let mut logits = vec![0.0f32; NUM_INTENTS];
-        for i in 0..NUM_INTENTS {
-            logits[i] = self.output_bias[i];
-            for j in 0..EMBED_DIM {
-                logits[i] += pooled[j] * self.output_proj[j * NUM_INTENTS + i];
+        match &self.quantized {
+            Some(q) => {
+                for (l, (p, b)) in logits
+                    .iter_mut()
+                    .zip(q.head.forward(&pooled).iter().zip(&self.output_bias))
+                {
+                    *l = p + b;
+                }
+            }
+            None => {
+                for i in 0..NUM_INTENTS {
+                    logits[i] = self.output_bias[i];
+                    for j in 0..EMBED_DIM {
+                        logits[i] += pooled[j] * self.output_proj[j * NUM_INTENTS + i];
+                    }
+                }
             }
         }
 
@@ -413,6 +629,30 @@ impl TradingTransformer {
         logits
     }
 
+    /// Encoder layers (attention + FFN, each with residual and layer norm) on
+    /// one position's row
+    fn encode_row(&self, row: &mut [f32]) {
+        for layer in 0..NUM_LAYERS {
+            let attn_out = match &self.quantized {
+                Some(q) => q.attn_out[layer].forward(row),
+                None => self.attention[layer].forward(row),
+            };
+            for (h, a) in row.iter_mut().zip(&attn_out) {
+                *h += a;
+            }
+            row.copy_from_slice(&layer_norm(row, &self.norm1_gamma, &self.norm1_beta));
+
+            let ffn_out = match &self.quantized {
+                Some(q) => self.ffn[layer].forward_int8(&q.ffn_w1[layer], &q.ffn_w2[layer], row),
+                None => self.ffn[layer].forward(row),
+            };
+            for (h, f) in row.iter_mut().zip(&ffn_out) {
+                *h += f;
+            }
+            row.copy_from_slice(&layer_norm(row, &self.norm2_gamma, &self.norm2_beta));
+        }
+    }
+
     /// Apply corrections
     fn apply_corrections(&self, input: &str) -> String {
         let mut result = input.to_string();
@@ -433,6 +673,15 @@ impl TradingTransformer {
         (TransformerIntent::from_index(idx), *prob)
     }
 
+    /// Predict with a decoding strategy, returning ranked alternatives too
+    pub fn predict_decoded(&self, input: &str, options: &DecodeOptions) -> DecodedPrediction {
+        let probs = self.forward(input);
+        match options.seed {
+            Some(seed) => decode_distribution(&probs, options, &mut StdRng::seed_from_u64(seed)),
+            None => decode_distribution(&probs, options, &mut rand::thread_rng()),
+        }
+    }
+
     /// Learn from correction
     pub fn learn_correction(&mut self, wrong: &str, correct: &str) {
         self.corrections
@@ -462,10 +711,11 @@ impl TradingTransformer {
                 // Simplified backprop (in real impl, would compute gradients properly)
                 // Update output projection
                 for i in 0..NUM_INTENTS {
+                    // d(loss)/d(logit) = p - y
                     let grad = if i == target {
-                        1.0 - probs[i]
+                        probs[i] - 1.0
                     } else {
-                        -probs[i]
+                        probs[i]
                     };
                     self.output_bias[i] -= lr * grad * 0.1;
                 }
@@ -479,6 +729,122 @@ impl TradingTransformer {
                 );
             }
         }
+        if self.quantized.is_some() {
+            self.quantized = Some(self.quantize());
+        }
+    }
+
+    /// Every weight tensor as (name, shape, values), e.g. for ONNX export
+    pub fn parameters(&self) -> Vec<(String, Vec<usize>, Vec<f32>)> {
+        let square = vec![EMBED_DIM, EMBED_DIM];
+        let mut params = vec![
+            (
+                "token_embeddings".to_string(),
+                vec![VOCAB_SIZE, EMBED_DIM],
+                self.token_embeddings.clone(),
+            ),
+            (
+                "pos_embeddings".to_string(),
+                vec![MAX_SEQ_LEN, EMBED_DIM],
+                self.pos_embeddings.clone(),
+            ),
+        ];
+        for (l, (attn, ffn)) in self.attention.iter().zip(&self.ffn).enumerate() {
+            let name = |part: &str| format!("layers.{}.{}", l, part);
+            params.push((name("attn.w_q"), square.clone(), attn.w_q.clone()));
+            params.push((name("attn.w_k"), square.clone(), attn.w_k.clone()));
+            params.push((name("attn.w_v"), square.clone(), attn.w_v.clone()));
+            params.push((name("attn.w_o"), square.clone(), attn.w_o.clone()));
+            params.push((name("ffn.w1"), vec![EMBED_DIM, FF_DIM], ffn.w1.clone()));
+            params.push((name("ffn.b1"), vec![FF_DIM], ffn.b1.clone()));
+            params.push((name("ffn.w2"), vec![FF_DIM, EMBED_DIM], ffn.w2.clone()));
+            params.push((name("ffn.b2"), vec![EMBED_DIM], ffn.b2.clone()));
+        }
+        params.extend([
+            (
+                "norm1.gamma".to_string(),
+                vec![EMBED_DIM],
+                self.norm1_gamma.clone(),
+            ),
+            (
+                "norm1.beta".to_string(),
+                vec![EMBED_DIM],
+                self.norm1_beta.clone(),
+            ),
+            (
+                "norm2.gamma".to_string(),
+                vec![EMBED_DIM],
+                self.norm2_gamma.clone(),
+            ),
+            (
+                "norm2.beta".to_string(),
+                vec![EMBED_DIM],
+                self.norm2_beta.clone(),
+            ),
+            (
+                "output_proj".to_string(),
+                vec![EMBED_DIM, NUM_INTENTS],
+                self.output_proj.clone(),
+            ),
+            (
+                "output_bias".to_string(),
+                vec![NUM_INTENTS],
+                self.output_bias.clone(),
+            ),
+        ]);
+        params
+    }
+
+    /// Replace weights by name (names as in `parameters`); sizes must match
+    pub fn set_parameters(&mut self, params: Vec<(String, Vec<f32>)>) -> Result<(), String> {
+        for (name, values) in params {
+            let target = match name.as_str() {
+                "token_embeddings" => &mut self.token_embeddings,
+                "pos_embeddings" => &mut self.pos_embeddings,
+                "norm1.gamma" => &mut self.norm1_gamma,
+                "norm1.beta" => &mut self.norm1_beta,
+                "norm2.gamma" => &mut self.norm2_gamma,
+                "norm2.beta" => &mut self.norm2_beta,
+                "output_proj" => &mut self.output_proj,
+                "output_bias" => &mut self.output_bias,
+                layered => {
+                    let (layer, part) = layered
+                        .strip_prefix("layers.")
+                        .and_then(|rest| rest.split_once('.'))
+                        .and_then(|(l, part)| Some((l.parse::<usize>().ok()?, part)))
+                        .ok_or_else(|| format!("Unknown transformer parameter '{}'", name))?;
+                    let (attn, ffn) = self
+                        .attention
+                        .get_mut(layer)
+                        .zip(self.ffn.get_mut(layer))
+                        .ok_or_else(|| format!("Transformer has no layer {}", layer))?;
+                    match part {
+                        "attn.w_q" => &mut attn.w_q,
+                        "attn.w_k" => &mut attn.w_k,
+                        "attn.w_v" => &mut attn.w_v,
+                        "attn.w_o" => &mut attn.w_o,
+                        "ffn.w1" => &mut ffn.w1,
+                        "ffn.b1" => &mut ffn.b1,
+                        "ffn.w2" => &mut ffn.w2,
+                        "ffn.b2" => &mut ffn.b2,
+                        _ => return Err(format!("Unknown transformer parameter '{}'", name)),
+                    }
+                }
+            };
+            if target.len() != values.len() {
+                return Err(format!(
+                    "Parameter '{}' has {} values, expected {}",
+                    name,
+                    values.len(),
+                    target.len()
+                ));
+            }
+            *target = values;
+        }
+        if self.quantized.is_some() {
+            self.set_precision(Precision::Int8);
+        }
+        Ok(())
     }
 
     /// Save model
@@ -698,4 +1064,71 @@ mod tests {
         println!("Predicted: {:?} ({:.2})", intent, prob);
         assert!(matches!(intent, TransformerIntent::Set));
     }
+
+    #[test]
+    fn test_int8_inference_matches_f32() {
+        let mut transformer = TradingTransformer::new();
+        let exact = transformer.forward("set grid to 1000 on power buy");
+        let f32_bytes = transformer.inference_weight_bytes();
+
+        transformer.set_precision(Precision::Int8);
+        assert_eq!(transformer.precision(), Precision::Int8);
+        assert!(transformer.inference_weight_bytes() * 3 < f32_bytes);
+        let quantized = transformer.forward("set grid to 1000 on power buy");
+        for (q, e) in quantized.iter().zip(&exact) {
+            assert!((q - e).abs() < 0.02, "{} vs {}", q, e);
+        }
+
+        transformer.set_precision(Precision::F32);
+        assert_eq!(transformer.forward("set grid to 1000 on power buy"), exact);
+    }
+
+    #[test]
+    fn test_decode_strategies() {
+        let mut probs = vec![0.0f32; NUM_INTENTS];
+        probs[0] = 0.6; // SET
+        probs[1] = 0.3; // QUERY
+        probs[3] = 0.1; // COPY
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let beam = DecodeOptions {
+            strategy: DecodeStrategy::Beam,
+            beam_width: 2,
+            ..DecodeOptions::default()
+        };
+        let decoded = decode_distribution(&probs, &beam, &mut rng);
+        assert_eq!(decoded.intent, TransformerIntent::Set);
+        let names: Vec<&str> = decoded
+            .candidates
+            .iter()
+            .map(|c| c.intent.as_str())
+            .collect();
+        assert_eq!(names, vec!["SET", "QUERY"]);
+
+        // Nucleus of 0.8 keeps SET and QUERY, renormalized
+        let nucleus = DecodeOptions {
+            top_p: Some(0.8),
+            beam_width: 5,
+            ..DecodeOptions::default()
+        };
+        let decoded = decode_distribution(&probs, &nucleus, &mut rng);
+        assert_eq!(decoded.candidates.len(), 2);
+        assert!((decoded.confidence - 2.0 / 3.0).abs() < 1e-5);
+
+        // Sampling from top-1 is deterministic; low temperature sharpens
+        let sample = DecodeOptions {
+            strategy: DecodeStrategy::Sample,
+            top_k: Some(1),
+            ..DecodeOptions::default()
+        };
+        assert_eq!(
+            decode_distribution(&probs, &sample, &mut rng).intent,
+            TransformerIntent::Set
+        );
+        let sharp = DecodeOptions {
+            temperature: 0.1,
+            ..DecodeOptions::default()
+        };
+        assert!(decode_distribution(&probs, &sharp, &mut rng).confidence > 0.99);
+    }
 }
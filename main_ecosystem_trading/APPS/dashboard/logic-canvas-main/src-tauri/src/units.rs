@@ -0,0 +1,304 @@
+// ============================================
+// DISTANCE UNITS
+// ============================================
+//
+// Distance fields (grid, trail value, trail steps) are stored in whatever
+// unit general.grid_unit says: 0 = points, 1 = pips. How many points make
+// a pip depends on pip_factor (0 = auto from the symbol's digits), and a
+// point is a price step that depends on the symbol. This module converts
+// between those so "grid 30" can't silently mean 3 pips on a 5-digit broker.
+
+use daavfx_core::config_enums::{TrailMethod, TrailStepMethod};
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::symbols::SymbolSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceUnit {
+    Points,
+    Pips,
+    /// Price difference, e.g. 0.0003 on EURUSD
+    Price,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistanceValues {
+    pub points: f64,
+    pub pips: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitConversion {
+    pub config: MTConfig,
+    pub from_unit: DistanceUnit,
+    pub to_unit: DistanceUnit,
+    pub points_per_pip: f64,
+    /// Number of distance values rewritten
+    pub converted_fields: usize,
+}
+
+/// Unit the config's distance fields are stored in
+pub fn config_unit(config: &MTConfig) -> DistanceUnit {
+    if config.general.grid_unit == Some(1) {
+        DistanceUnit::Pips
+    } else {
+        DistanceUnit::Points
+    }
+}
+
+/// Points per pip: pip_factor when set, otherwise 10 on 3/5-digit symbols and 1 elsewhere
+pub fn points_per_pip(config: &MTConfig, spec: &SymbolSpec) -> f64 {
+    match config.general.pip_factor {
+        Some(factor) if factor > 0 => factor as f64,
+        _ => symbol_points_per_pip(spec),
+    }
+}
+
+/// Points per pip from the symbol alone, when there's no config pip_factor to go by
+pub(crate) fn symbol_points_per_pip(spec: &SymbolSpec) -> f64 {
+    if spec.digits == 3 || spec.digits == 5 {
+        10.0
+    } else {
+        1.0
+    }
+}
+
+fn points_per_unit(unit: DistanceUnit, points_per_pip: f64, point: f64) -> f64 {
+    match unit {
+        DistanceUnit::Points => 1.0,
+        DistanceUnit::Pips => points_per_pip,
+        DistanceUnit::Price => 1.0 / point,
+    }
+}
+
+/// One distance expressed in points, pips and price
+pub fn distance_values(
+    value: f64,
+    unit: DistanceUnit,
+    config: &MTConfig,
+    spec: &SymbolSpec,
+) -> DistanceValues {
+    let per_pip = points_per_pip(config, spec);
+    let point = spec.point();
+    let points = value * points_per_unit(unit, per_pip, point);
+    DistanceValues {
+        points: round_distance(points),
+        pips: round_distance(points / per_pip),
+        price: points * point,
+    }
+}
+
+/// Drops float noise from unit factors (0.1 * 30 = 3.0000000000000004)
+fn round_distance(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
+
+fn is_points_method(method: Option<TrailStepMethod>) -> bool {
+    method != Some(TrailStepMethod::Percent)
+}
+
+/// Scale every point-based distance of `logic` by `factor`; returns how many values changed
+fn scale_logic_distances(logic: &mut LogicConfig, factor: f64) -> usize {
+    let mut scaled = 0;
+    let mut scale = |value: &mut f64| {
+        *value = round_distance(*value * factor);
+        scaled += 1;
+    };
+
+    scale(&mut logic.grid);
+    logic
+        .grid_b
+        .iter_mut()
+        .chain(logic.grid_s.iter_mut())
+        .for_each(&mut scale);
+
+    // Percent-based trails aren't distances
+    if logic.trail_method != TrailMethod::AvgPercent {
+        scale(&mut logic.trail_value);
+        logic
+            .trail_value_b
+            .iter_mut()
+            .chain(logic.trail_value_s.iter_mut())
+            .for_each(&mut scale);
+    }
+    if is_points_method(Some(logic.trail_step_method)) {
+        scale(&mut logic.trail_step);
+        logic
+            .trail_step_b
+            .iter_mut()
+            .chain(logic.trail_step_s.iter_mut())
+            .for_each(&mut scale);
+    }
+
+    let levels = [
+        (
+            &mut logic.trail_step_2,
+            &mut logic.trail_step_2_b,
+            &mut logic.trail_step_2_s,
+            logic.trail_step_method_2,
+        ),
+        (
+            &mut logic.trail_step_3,
+            &mut logic.trail_step_3_b,
+            &mut logic.trail_step_3_s,
+            logic.trail_step_method_3,
+        ),
+        (
+            &mut logic.trail_step_4,
+            &mut logic.trail_step_4_b,
+            &mut logic.trail_step_4_s,
+            logic.trail_step_method_4,
+        ),
+        (
+            &mut logic.trail_step_5,
+            &mut logic.trail_step_5_b,
+            &mut logic.trail_step_5_s,
+            logic.trail_step_method_5,
+        ),
+        (
+            &mut logic.trail_step_6,
+            &mut logic.trail_step_6_b,
+            &mut logic.trail_step_6_s,
+            logic.trail_step_method_6,
+        ),
+        (
+            &mut logic.trail_step_7,
+            &mut logic.trail_step_7_b,
+            &mut logic.trail_step_7_s,
+            logic.trail_step_method_7,
+        ),
+    ];
+    for (base, buy, sell, method) in levels {
+        if is_points_method(method) {
+            base.iter_mut()
+                .chain(buy.iter_mut())
+                .chain(sell.iter_mut())
+                .for_each(&mut scale);
+        }
+    }
+    scaled
+}
+
+/// Rewrite every distance field of `config` from its stored unit into `target`
+/// (points or pips) and update grid_unit to match
+pub fn convert_units(
+    config: &MTConfig,
+    spec: &SymbolSpec,
+    target: DistanceUnit,
+) -> Result<UnitConversion, String> {
+    if target == DistanceUnit::Price {
+        return Err("The EA reads distances in points or pips; price is display-only".to_string());
+    }
+    if spec.digits > 10 {
+        return Err(format!("Invalid symbol digits: {}", spec.digits));
+    }
+
+    let from = config_unit(config);
+    let per_pip = points_per_pip(config, spec);
+    let point = spec.point();
+    let factor = points_per_unit(from, per_pip, point) / points_per_unit(target, per_pip, point);
+
+    let mut converted = config.clone();
+    let mut converted_fields = 0;
+    if from != target {
+        for engine in &mut converted.engines {
+            for group in &mut engine.groups {
+                for logic in &mut group.logics {
+                    converted_fields += scale_logic_distances(logic, factor);
+                }
+            }
+        }
+        converted.general.grid_unit = Some(if target == DistanceUnit::Pips { 1 } else { 0 });
+    }
+    // Pin the factor used so the EA doesn't auto-detect a different one
+    converted.general.pip_factor = Some(per_pip as i32);
+
+    Ok(UnitConversion {
+        config: converted,
+        from_unit: from,
+        to_unit: target,
+        points_per_pip: per_pip,
+        converted_fields,
+    })
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn convert_config_units(
+    config: MTConfig,
+    symbol_spec: SymbolSpec,
+    target_unit: DistanceUnit,
+) -> Result<UnitConversion, String> {
+    convert_units(&config, &symbol_spec, target_unit)
+}
+
+/// A distance in the config's unit expressed in points, pips and price, for field hints
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn describe_distance(
+    config: MTConfig,
+    symbol_spec: SymbolSpec,
+    value: f64,
+) -> Result<DistanceValues, String> {
+    Ok(distance_values(
+        value,
+        config_unit(&config),
+        &config,
+        &symbol_spec,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+    use crate::symbols::catalog_spec;
+
+    fn eurusd() -> SymbolSpec {
+        catalog_spec("EURUSD").unwrap()
+    }
+
+    #[test]
+    fn test_points_per_pip_auto_and_explicit() {
+        let mut config = create_full_v19_config();
+        config.general.pip_factor = Some(0);
+        assert_eq!(points_per_pip(&config, &eurusd()), 10.0);
+        let gold = catalog_spec("XAUUSD").unwrap();
+        assert_eq!(points_per_pip(&config, &gold), 1.0);
+        config.general.pip_factor = Some(100);
+        assert_eq!(points_per_pip(&config, &gold), 100.0);
+    }
+
+    #[test]
+    fn test_convert_points_to_pips_and_back() {
+        let mut config = create_full_v19_config();
+        config.general.grid_unit = Some(0);
+        config.general.pip_factor = Some(0);
+        let logic = &mut config.engines[0].groups[0].logics[0];
+        logic.grid = 300.0;
+        logic.grid_b = Some(150.0);
+        logic.trail_method = TrailMethod::AvgPercent;
+        logic.trail_value = 5.0;
+
+        let pips = convert_units(&config, &eurusd(), DistanceUnit::Pips).unwrap();
+        assert_eq!(pips.from_unit, DistanceUnit::Points);
+        assert_eq!(pips.config.general.grid_unit, Some(1));
+        assert_eq!(pips.config.general.pip_factor, Some(10));
+        let logic = &pips.config.engines[0].groups[0].logics[0];
+        assert_eq!(logic.grid, 30.0);
+        assert_eq!(logic.grid_b, Some(15.0));
+        // Percent trail left alone
+        assert_eq!(logic.trail_value, 5.0);
+
+        let back = convert_units(&pips.config, &eurusd(), DistanceUnit::Points).unwrap();
+        assert_eq!(back.config.engines[0].groups[0].logics[0].grid, 300.0);
+
+        assert!(convert_units(&config, &eurusd(), DistanceUnit::Price).is_err());
+        let values = distance_values(30.0, DistanceUnit::Pips, &pips.config, &eurusd());
+        assert_eq!(values.points, 300.0);
+        assert!((values.price - 0.003).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,349 @@
+// ============================================
+// IMPORT FROM URL
+// ============================================
+//
+// Lets support send clients a download link instead of walking them through
+// file dialogs. The preset is downloaded over HTTPS with a size cap and a
+// content-type check (a login or share page comes back as HTML), saved to a
+// temp folder and handed to import_set_file / import_json_file, so it goes
+// through the same parsing, caching and import log as a local file. Signed
+// .set files are verified against the configured public key first; a
+// signature that doesn't match rejects the import.
+
+use serde::Serialize;
+
+use crate::backtest::decode_text;
+use crate::mt_bridge::{atomic_write, import_json_file, import_set_file, MTConfig};
+use crate::preset_signing::{
+    has_signature, normalized_parameters, verification_key, verify_preset_content,
+    PresetVerification,
+};
+
+/// Same cap import_set_file puts on local files
+const MAX_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT_SECS: u64 = 60;
+/// Content types presets are served with; S3 and most file hosts use octet-stream
+const ALLOWED_CONTENT_TYPES: &[&str] = &[
+    "text/plain",
+    "application/json",
+    "text/json",
+    "application/octet-stream",
+    "binary/octet-stream",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlImport {
+    pub config: MTConfig,
+    pub source_url: String,
+    /// Downloaded copy the import ran on
+    pub file_path: String,
+    /// "set" or "json"
+    pub format: String,
+    pub size: u64,
+    /// None when the preset isn't signed
+    pub signature: Option<PresetVerification>,
+}
+
+/// Hosts plain http:// is accepted for: a server on this machine
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1"];
+const MAX_REDIRECTS: usize = 10;
+
+/// https://, or http:// to this machine. The host is compared whole, so
+/// http://localhost.example.com is still plain HTTP to someone else.
+pub(crate) fn is_secure_url(url: &reqwest::Url) -> bool {
+    match url.scheme() {
+        "https" => true,
+        "http" => url
+            .host_str()
+            .is_some_and(|host| LOCAL_HOSTS.contains(&host)),
+        _ => false,
+    }
+}
+
+/// Follows redirects only to URLs `is_secure_url` accepts, and never from
+/// https:// down to plain http://
+pub(crate) fn secure_redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        let downgrade = attempt.url().scheme() == "http"
+            && attempt.previous().iter().any(|url| url.scheme() == "https");
+        if downgrade || !is_secure_url(attempt.url()) {
+            let message = format!("Refused redirect to {}", attempt.url());
+            attempt.error(message)
+        } else if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("Too many redirects")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+fn check_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid link: {}", e))?;
+    if !is_secure_url(&parsed) {
+        return Err("Only https:// links can be imported".to_string());
+    }
+    Ok(url.to_string())
+}
+
+fn check_content_type(content_type: Option<&str>) -> Result<(), String> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if mime == "text/html" {
+        return Err(
+            "The link opened a web page, not a preset; use a direct download link".to_string(),
+        );
+    }
+    if !ALLOWED_CONTENT_TYPES.contains(&mime.as_str()) {
+        return Err(format!("Unsupported content type for a preset: {}", mime));
+    }
+    Ok(())
+}
+
+/// Last path segment of the URL, or the Content-Disposition filename when given
+fn file_name_for(url: &str, content_disposition: Option<&str>) -> String {
+    let from_header = content_disposition.and_then(|value| {
+        value.split(';').find_map(|part| {
+            let (key, name) = part.trim().split_once('=')?;
+            (key.trim().eq_ignore_ascii_case("filename"))
+                .then(|| name.trim().trim_matches('"').to_string())
+        })
+    });
+    let from_url = || {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let path = path.split_once("://").map(|(_, rest)| rest).unwrap_or(path);
+        path.split_once('/')
+            .and_then(|(_, path)| path.rsplit('/').next())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let name = from_header
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(from_url);
+    let safe = name.replace(
+        |c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != ' ' && c != '.',
+        "_",
+    );
+    let safe = safe.trim_matches('.').to_string();
+    if safe.is_empty() {
+        "preset".to_string()
+    } else {
+        safe
+    }
+}
+
+/// "json" from the file extension or content type, else sniffed from the content
+fn detect_format(file_name: &str, content_type: Option<&str>, content: &str) -> &'static str {
+    let lower = file_name.to_ascii_lowercase();
+    if lower.ends_with(".json") {
+        return "json";
+    }
+    if lower.ends_with(".set") || lower.ends_with(".ini") {
+        return "set";
+    }
+    if content_type.is_some_and(|t| t.to_ascii_lowercase().contains("json"))
+        || content.trim_start().starts_with('{')
+    {
+        "json"
+    } else {
+        "set"
+    }
+}
+
+/// Verify a signed preset; Err when the signature doesn't match
+fn check_signature(content: &str) -> Result<Option<PresetVerification>, String> {
+    if !has_signature(content) {
+        return Ok(None);
+    }
+    match verification_key(None) {
+        Ok(key) => {
+            let check = verify_preset_content(content, &key);
+            if !check.valid {
+                return Err(format!("Preset signature check failed: {}", check.message));
+            }
+            Ok(Some(check))
+        }
+        Err(_) => Ok(Some(PresetVerification {
+            valid: false,
+            signed: true,
+            key_id: None,
+            signed_at: None,
+            parameter_count: normalized_parameters(content).1,
+            message: "Preset is signed, but no public key is configured to verify it".to_string(),
+        })),
+    }
+}
+
+/// Download with the size cap enforced while streaming, in case Content-Length is missing
+async fn download(url: &str) -> Result<(Vec<u8>, Option<String>, Option<String>), String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .redirect(secure_redirect_policy())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download returned HTTP {}", response.status()));
+    }
+    let header = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let content_type = header(reqwest::header::CONTENT_TYPE);
+    let disposition = header(reqwest::header::CONTENT_DISPOSITION);
+    check_content_type(content_type.as_deref())?;
+
+    let too_large = || "File too large (max 50MB)".to_string();
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_DOWNLOAD_BYTES)
+    {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+            return Err(too_large());
+        }
+    }
+    Ok((bytes, content_type, disposition))
+}
+
+/// Download a .set or JSON preset from a link and import it like a local file
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_from_url(
+    url: String,
+    task_id: Option<String>,
+    format: Option<String>,
+) -> Result<UrlImport, String> {
+    let url = check_url(&url)?;
+    tracing::info!(url = %url, "Importing preset from URL");
+    let (bytes, content_type, disposition) = download(&url).await?;
+    if bytes.is_empty() {
+        return Err("The link returned an empty file".to_string());
+    }
+    let content = decode_text(&bytes);
+    let mut file_name = file_name_for(&url, disposition.as_deref());
+    let kind = detect_format(&file_name, content_type.as_deref(), &content);
+    let signature = if kind == "set" {
+        check_signature(&content)?
+    } else {
+        None
+    };
+    let lower = file_name.to_ascii_lowercase();
+    if ![".set", ".json", ".ini"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+    {
+        file_name = format!("{}.{}", file_name, kind);
+    }
+
+    let folder = std::env::temp_dir().join("daavfx_url_imports");
+    std::fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create download folder: {}", e))?;
+    let path = folder.join(&file_name);
+    atomic_write(&path, &bytes)?;
+    let file_path = path.to_string_lossy().to_string();
+
+    let config = if kind == "json" {
+        import_json_file(file_path.clone()).await?
+    } else {
+        import_set_file(file_path.clone(), task_id, format).await?
+    };
+    Ok(UrlImport {
+        config,
+        source_url: url,
+        file_path,
+        format: kind.to_string(),
+        size: bytes.len() as u64,
+        signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_import_checks() {
+        assert!(check_url("http://example.com/a.set").is_err());
+        assert!(check_url(" https://example.com/a.set ").is_ok());
+        assert!(check_url("http://localhost:8080/a.set").is_ok());
+        assert!(check_url("http://127.0.0.1/a.set").is_ok());
+        assert!(check_url("http://localhost.attacker.com/a.set").is_err());
+        assert!(check_url("http://127.0.0.1.nip.io/a.set").is_err());
+        assert!(check_url("http://localhost@attacker.com/a.set").is_err());
+        assert!(check_url("not a link").is_err());
+
+        assert!(check_content_type(None).is_ok());
+        assert!(check_content_type(Some("text/plain; charset=utf-16le")).is_ok());
+        assert!(check_content_type(Some("application/octet-stream")).is_ok());
+        assert!(check_content_type(Some("text/html; charset=utf-8"))
+            .unwrap_err()
+            .contains("direct download"));
+        assert!(check_content_type(Some("image/png")).is_err());
+
+        assert_eq!(
+            file_name_for("https://files.example.com/presets/Gold%20v2.set?dl=1", None),
+            "Gold_20v2.set"
+        );
+        assert_eq!(
+            file_name_for(
+                "https://files.example.com/download/123",
+                Some("attachment; filename=\"client.json\"")
+            ),
+            "client.json"
+        );
+        assert_eq!(file_name_for("https://example.com/", None), "preset");
+        assert_eq!(file_name_for("https://example.com/../..", None), "preset");
+
+        assert_eq!(detect_format("client.json", None, ""), "json");
+        assert_eq!(detect_format("123", Some("application/json"), ""), "json");
+        assert_eq!(detect_format("123", None, "  {\"config\": {}}"), "json");
+        assert_eq!(detect_format("123", None, "gInput_Grid=300"), "set");
+
+        assert!(check_signature("gInput_Grid=300").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_refuses_redirect_off_this_machine() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(
+                b"HTTP/1.1 302 Found\r\nLocation: http://localhost.attacker.com/a.set\r\nContent-Length: 0\r\n\r\n",
+            );
+        });
+
+        let err = download(&format!("http://127.0.0.1:{}/a.set", port))
+            .await
+            .unwrap_err();
+        assert!(err.contains("Refused redirect"), "{}", err);
+    }
+}
@@ -0,0 +1,329 @@
+// ============================================
+// SEMANTIC CONFIG VALIDATION
+// ============================================
+//
+// Checks a config that deserializes fine but would still misbehave once it
+// runs against a broker. Issues are reported per field, addressed by the
+// same JSON pointer apply_config_patch takes, so the editor can mark the
+// input and offer the suggested value as a one-click fix.
+//
+// Broker limits: lots below min_lot, above max_lot or off lot_step; trail
+// distances inside the stop level; grids inside the freeze level.
+
+use daavfx_core::config_enums::{TrailMethod, TrailStepMethod};
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{LogicConfig, MTConfig};
+use crate::symbols::{self, SymbolSpec};
+use crate::units::{config_unit, distance_values};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// JSON pointer to the field, e.g. /engines/0/groups/2/logics/1/initial_lot_b
+    pub path: String,
+    pub engine_id: String,
+    pub group_number: u8,
+    pub logic_name: String,
+    pub message: String,
+    /// Closest value the broker accepts, in the field's own unit
+    pub suggestion: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub symbol: Option<SymbolSpec>,
+    pub issues: Vec<ValidationIssue>,
+    pub is_valid: bool,
+}
+
+struct Location<'a> {
+    prefix: String,
+    engine_id: &'a str,
+    group_number: u8,
+    logic_name: &'a str,
+}
+
+impl Location<'_> {
+    fn issue(&self, field: &str, message: String, suggestion: Option<f64>) -> ValidationIssue {
+        ValidationIssue {
+            path: format!("{}/{}", self.prefix, field),
+            engine_id: self.engine_id.to_string(),
+            group_number: self.group_number,
+            logic_name: self.logic_name.to_string(),
+            message,
+            suggestion,
+        }
+    }
+}
+
+fn lot_fields(logic: &LogicConfig) -> Vec<(&'static str, f64)> {
+    let mut fields = vec![("initial_lot", logic.initial_lot)];
+    let optional = [
+        ("initial_lot_b", logic.initial_lot_b),
+        ("initial_lot_s", logic.initial_lot_s),
+        ("last_lot", logic.last_lot),
+        ("last_lot_b", logic.last_lot_b),
+        ("last_lot_s", logic.last_lot_s),
+    ];
+    fields.extend(optional.into_iter().filter_map(|(f, v)| v.map(|v| (f, v))));
+    // 0 leaves the lot unset (EA default / no cap)
+    fields.retain(|(_, v)| *v > 0.0);
+    fields
+}
+
+fn with_overrides(
+    base: (&'static str, f64),
+    buy: (&'static str, Option<f64>),
+    sell: (&'static str, Option<f64>),
+) -> Vec<(&'static str, f64)> {
+    let mut fields = vec![base];
+    fields.extend(
+        [buy, sell]
+            .into_iter()
+            .filter_map(|(f, v)| v.map(|v| (f, v))),
+    );
+    fields.retain(|(_, v)| *v > 0.0);
+    fields
+}
+
+fn round_lot(value: f64) -> f64 {
+    (value * 1e8).round() / 1e8
+}
+
+fn check_lots(
+    logic: &LogicConfig,
+    spec: &SymbolSpec,
+    at: &Location,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for (field, lot) in lot_fields(logic) {
+        if lot < spec.min_lot {
+            issues.push(at.issue(
+                field,
+                format!(
+                    "Lot {} is below the broker minimum of {}",
+                    lot, spec.min_lot
+                ),
+                Some(spec.min_lot),
+            ));
+        } else if lot > spec.max_lot {
+            issues.push(at.issue(
+                field,
+                format!(
+                    "Lot {} is above the broker maximum of {}",
+                    lot, spec.max_lot
+                ),
+                Some(spec.max_lot),
+            ));
+        } else if spec.lot_step > 0.0 {
+            let steps = lot / spec.lot_step;
+            if (steps - steps.round()).abs() > 1e-6 {
+                let snapped = round_lot(steps.round() * spec.lot_step).max(spec.min_lot);
+                issues.push(at.issue(
+                    field,
+                    format!(
+                        "Lot {} is not a multiple of the lot step {}",
+                        lot, spec.lot_step
+                    ),
+                    Some(snapped),
+                ));
+            }
+        }
+    }
+}
+
+/// Flag distances (in the config's unit) that are inside `limit_points`
+fn check_distances(
+    fields: Vec<(&'static str, f64)>,
+    limit_points: u32,
+    what: &str,
+    config: &MTConfig,
+    spec: &SymbolSpec,
+    at: &Location,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if limit_points == 0 {
+        return;
+    }
+    let unit = config_unit(config);
+    // Config-unit value of the limit, for the suggestion
+    let per_point = distance_values(1.0, unit, config, spec).points;
+    for (field, value) in fields {
+        let points = distance_values(value, unit, config, spec).points;
+        if points < limit_points as f64 {
+            issues.push(at.issue(
+                field,
+                format!(
+                    "{} of {} points is inside the broker {} of {} points",
+                    field, points, what, limit_points
+                ),
+                Some((limit_points as f64 / per_point * 100.0).ceil() / 100.0),
+            ));
+        }
+    }
+}
+
+/// Per-field issues for every enabled logic against the broker's symbol limits
+pub fn broker_limit_issues(config: &MTConfig, spec: &SymbolSpec) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (e, engine) in config.engines.iter().enumerate() {
+        for (g, group) in engine.groups.iter().enumerate().filter(|(_, g)| g.enabled) {
+            for (l, logic) in group.logics.iter().enumerate().filter(|(_, l)| l.enabled) {
+                let at = Location {
+                    prefix: format!("/engines/{}/groups/{}/logics/{}", e, g, l),
+                    engine_id: &engine.engine_id,
+                    group_number: group.group_number,
+                    logic_name: &logic.logic_name,
+                };
+                check_lots(logic, spec, &at, &mut issues);
+
+                // Trailing stops are modified at these distances from the price
+                let mut stops = Vec::new();
+                if logic.trail_method != TrailMethod::AvgPercent {
+                    stops.extend(with_overrides(
+                        ("trail_value", logic.trail_value),
+                        ("trail_value_b", logic.trail_value_b),
+                        ("trail_value_s", logic.trail_value_s),
+                    ));
+                }
+                if logic.trail_step_method != TrailStepMethod::Percent {
+                    stops.extend(with_overrides(
+                        ("trail_step", logic.trail_step),
+                        ("trail_step_b", logic.trail_step_b),
+                        ("trail_step_s", logic.trail_step_s),
+                    ));
+                }
+                check_distances(
+                    stops,
+                    spec.stop_level,
+                    "stop level",
+                    config,
+                    spec,
+                    &at,
+                    &mut issues,
+                );
+
+                let grids = with_overrides(
+                    ("grid", logic.grid),
+                    ("grid_b", logic.grid_b),
+                    ("grid_s", logic.grid_s),
+                );
+                check_distances(
+                    grids,
+                    spec.freeze_level,
+                    "freeze level",
+                    config,
+                    spec,
+                    &at,
+                    &mut issues,
+                );
+            }
+        }
+    }
+    issues
+}
+
+/// Semantic checks for a config; broker limits run when a symbol is given
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn validate_config(
+    config: MTConfig,
+    symbol: Option<String>,
+    csv_path: Option<String>,
+) -> Result<ValidationReport, String> {
+    let spec = match symbol.filter(|s| !s.trim().is_empty()) {
+        Some(symbol) => Some(
+            symbols::lookup(&symbol, csv_path)?
+                .ok_or_else(|| format!("No spec known for symbol '{}'", symbol))?,
+        ),
+        None => None,
+    };
+    let issues = spec
+        .as_ref()
+        .map(|spec| broker_limit_issues(&config, spec))
+        .unwrap_or_default();
+    Ok(ValidationReport {
+        symbol: spec,
+        is_valid: issues.is_empty(),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+    use crate::symbols::catalog_spec;
+
+    fn single_logic_config() -> MTConfig {
+        let mut config = create_full_v19_config();
+        config.general.grid_unit = Some(0);
+        for engine in &mut config.engines {
+            for group in &mut engine.groups {
+                group.enabled = group.group_number == 1;
+                for logic in &mut group.logics {
+                    logic.enabled = false;
+                }
+            }
+        }
+        let logic = &mut config.engines[0].groups[0].logics[0];
+        logic.enabled = true;
+        logic.initial_lot = 0.02;
+        logic.initial_lot_b = None;
+        logic.initial_lot_s = None;
+        logic.last_lot = None;
+        logic.grid = 300.0;
+        logic.trail_method = TrailMethod::Points;
+        logic.trail_value = 3000.0;
+        logic.trail_step_method = TrailStepMethod::Points;
+        logic.trail_step = 1500.0;
+        config
+    }
+
+    #[test]
+    fn test_lots_checked_against_min_and_step() {
+        let mut spec = catalog_spec("EURUSD").unwrap();
+        spec.min_lot = 0.1;
+        spec.lot_step = 0.1;
+        let mut config = single_logic_config();
+        config.engines[0].groups[0].logics[0].initial_lot_b = Some(0.25);
+
+        let issues = broker_limit_issues(&config, &spec);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].path, "/engines/0/groups/0/logics/0/initial_lot");
+        assert_eq!(issues[0].suggestion, Some(0.1));
+        assert_eq!(issues[1].path, "/engines/0/groups/0/logics/0/initial_lot_b");
+        assert_eq!(issues[1].suggestion, Some(0.3));
+    }
+
+    #[test]
+    fn test_stop_and_freeze_levels_in_config_units() {
+        let mut spec = catalog_spec("EURUSD").unwrap();
+        spec.stop_level = 2000;
+        spec.freeze_level = 400;
+        let config = single_logic_config();
+
+        let issues = broker_limit_issues(&config, &spec);
+        let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/engines/0/groups/0/logics/0/trail_step",
+                "/engines/0/groups/0/logics/0/grid",
+            ]
+        );
+        assert_eq!(issues[1].suggestion, Some(400.0));
+
+        // Same grid stored in pips (30 pips = 300 points) is still too tight
+        let mut in_pips = config.clone();
+        in_pips.general.grid_unit = Some(1);
+        in_pips.general.pip_factor = Some(10);
+        in_pips.engines[0].groups[0].logics[0].grid = 30.0;
+        let grid = broker_limit_issues(&in_pips, &spec)
+            .into_iter()
+            .find(|i| i.path.ends_with("/grid"))
+            .unwrap();
+        assert_eq!(grid.suggestion, Some(40.0));
+    }
+}
@@ -0,0 +1,499 @@
+// ============================================
+// GIT-BACKED VAULT
+// ============================================
+//
+// Optional sharing mode for teams: the vault folder is a git repository
+// (initialised here or cloned from a shared remote), every vault save is
+// committed with the preset name in the message, and push/pull sync it with
+// the remote. When a pull conflicts, each conflicted preset comes back as a
+// field-by-field diff of our and their version (the shape the preset compare
+// view shows) and is resolved by keeping one side. Runs the system `git`, so
+// credentials come from the user's git setup.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::backtest::decode_text;
+use crate::mt_bridge::resolve_vault_path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultGitStatus {
+    /// Whether the vault folder is a git repository
+    pub enabled: bool,
+    pub branch: Option<String>,
+    pub remote: Option<String>,
+    /// Commits not yet pushed / not yet pulled (0 without an upstream)
+    pub ahead: usize,
+    pub behind: usize,
+    /// Files with uncommitted changes
+    pub changed_files: Vec<String>,
+    /// Presets left conflicted by the last pull
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetFieldDiff {
+    /// Setfile key, or JSON pointer for .json presets
+    pub field: String,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultConflict {
+    /// Path relative to the vault root
+    pub path: String,
+    pub fields: Vec<PresetFieldDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSyncResult {
+    pub ok: bool,
+    pub message: String,
+    pub conflicts: Vec<VaultConflict>,
+    pub status: VaultGitStatus,
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        // Fail instead of waiting on a credential prompt nobody can answer
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git (is it installed?): {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Err(format!(
+            "git {} failed: {}",
+            args.iter()
+                .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+                .copied()
+                .unwrap_or(""),
+            if stderr.is_empty() { stdout } else { stderr }
+        ))
+    }
+}
+
+/// Only the vault folder's own repository counts, never a repo it happens to sit in
+pub(crate) fn is_vault_repo(vault: &Path) -> bool {
+    vault.join(".git").exists()
+}
+
+/// git commands that create commits (commit, merging pull) run with the user's
+/// git identity, or the OS user name when none is configured
+fn git_as_user(vault: &Path, args: &[&str]) -> Result<String, String> {
+    let mut full: Vec<String> = Vec::new();
+    if git(vault, &["config", "user.name"]).is_err() {
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "daavfx".to_string());
+        full.extend([
+            "-c".to_string(),
+            format!("user.name={}", user),
+            "-c".to_string(),
+            format!("user.email={}@localhost", user),
+        ]);
+    }
+    full.extend(args.iter().map(|arg| arg.to_string()));
+    let full: Vec<&str> = full.iter().map(String::as_str).collect();
+    git(vault, &full)
+}
+
+fn commit(vault: &Path, message: &str) -> Result<(), String> {
+    git_as_user(vault, &["commit", "--quiet", "-m", message]).map(|_| ())
+}
+
+/// `file` relative to the vault; the file may already be deleted, so its folder is canonicalized
+fn relative_path(vault: &Path, file: &Path) -> Result<String, String> {
+    let canonical = || -> Option<(PathBuf, PathBuf)> {
+        let dir = file.parent()?.canonicalize().ok()?;
+        Some((vault.canonicalize().ok()?, dir.join(file.file_name()?)))
+    };
+    let relative = match file.strip_prefix(vault) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => canonical()
+            .and_then(|(vault, file)| file.strip_prefix(vault).ok().map(Path::to_path_buf))
+            .ok_or_else(|| format!("{} is not inside the vault", file.display()))?,
+    };
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Commit one preset after a vault save or delete; a no-op outside git-backed vaults
+pub(crate) fn commit_preset(vault: &Path, file: &Path, action: &str) -> Result<(), String> {
+    if !is_vault_repo(vault) {
+        return Ok(());
+    }
+    let relative = relative_path(vault, file)?;
+    git(vault, &["add", "--all", "--", &relative])?;
+    if git(vault, &["diff", "--cached", "--quiet", "--", &relative]).is_ok() {
+        return Ok(());
+    }
+    let name = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or(relative);
+    commit(vault, &format!("{} preset {}", action, name))
+}
+
+/// commit_preset for vault saves: the file is already written, so a failed commit is only logged
+pub(crate) fn auto_commit_preset(vault: &Path, file: &Path, action: &str) {
+    if let Err(e) = commit_preset(vault, file, action) {
+        tracing::warn!(path = ?file, error = %e, "Vault auto-commit failed");
+    }
+}
+
+fn lines(output: Result<String, String>) -> Vec<String> {
+    output
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn conflicted_files(vault: &Path) -> Vec<String> {
+    lines(git(vault, &["diff", "--name-only", "--diff-filter=U"]))
+}
+
+pub(crate) fn vault_status(vault: &Path) -> VaultGitStatus {
+    if !is_vault_repo(vault) {
+        return VaultGitStatus::default();
+    }
+    let (ahead, behind) = git(
+        vault,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )
+    .ok()
+    .and_then(|counts| {
+        let mut parts = counts.split_whitespace().map(|n| n.parse::<usize>().ok());
+        Some((parts.next()??, parts.next()??))
+    })
+    .unwrap_or((0, 0));
+    VaultGitStatus {
+        enabled: true,
+        branch: git(vault, &["symbolic-ref", "--short", "HEAD"]).ok(),
+        remote: git(vault, &["remote", "get-url", "origin"]).ok(),
+        ahead,
+        behind,
+        changed_files: lines(git(vault, &["status", "--porcelain"]))
+            .iter()
+            .map(|line| line.get(3..).unwrap_or(line).to_string())
+            .collect(),
+        conflicts: conflicted_files(vault),
+    }
+}
+
+fn flatten_json(value: &Value, path: String, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                let token = key.replace('~', "~0").replace('/', "~1");
+                flatten_json(v, format!("{}/{}", path, token), out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten_json(v, format!("{}/{}", path, i), out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(path, s.clone());
+        }
+        other => {
+            out.insert(path, other.to_string());
+        }
+    }
+}
+
+/// Preset content as field -> value: setfile keys, or JSON pointers for .json
+pub(crate) fn preset_fields(path: &str, content: &str) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    if path.to_lowercase().ends_with(".json") {
+        if let Ok(value) = serde_json::from_str::<Value>(content) {
+            flatten_json(&value, String::new(), &mut fields);
+        }
+    } else {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                fields.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    fields
+}
+
+pub(crate) fn diff_preset_fields(
+    ours: &BTreeMap<String, String>,
+    theirs: &BTreeMap<String, String>,
+) -> Vec<PresetFieldDiff> {
+    let mut keys: Vec<&String> = ours.keys().chain(theirs.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| ours.get(*key) != theirs.get(*key))
+        .map(|key| PresetFieldDiff {
+            field: key.clone(),
+            ours: ours.get(key).cloned(),
+            theirs: theirs.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// Stage 2 (ours) or 3 (theirs) of a conflicted file; None when that side deleted it
+fn conflict_side(vault: &Path, stage: u8, path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(vault)
+        .args(["show", &format!(":{}:{}", stage, path)])
+        .output()
+        .ok()?;
+    output.status.success().then(|| decode_text(&output.stdout))
+}
+
+fn vault_conflicts(vault: &Path) -> Vec<VaultConflict> {
+    conflicted_files(vault)
+        .into_iter()
+        .map(|path| {
+            let ours = conflict_side(vault, 2, &path)
+                .map(|content| preset_fields(&path, &content))
+                .unwrap_or_default();
+            let theirs = conflict_side(vault, 3, &path)
+                .map(|content| preset_fields(&path, &content))
+                .unwrap_or_default();
+            VaultConflict {
+                fields: diff_preset_fields(&ours, &theirs),
+                path,
+            }
+        })
+        .collect()
+}
+
+fn require_repo(vault: &Path) -> Result<(), String> {
+    if is_vault_repo(vault) {
+        Ok(())
+    } else {
+        Err("The vault is not git-backed; enable sharing first".to_string())
+    }
+}
+
+fn sync_result(vault: &Path, ok: bool, message: String) -> VaultSyncResult {
+    VaultSyncResult {
+        ok,
+        message,
+        conflicts: vault_conflicts(vault),
+        status: vault_status(vault),
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn get_vault_git_status(vault_path_override: Option<String>) -> Result<VaultGitStatus, String> {
+    Ok(vault_status(&resolve_vault_path(vault_path_override)?))
+}
+
+/// Turn the vault into a git repository. With `remote_url`, an empty vault is
+/// cloned from it; otherwise the existing presets become the first commit and
+/// the remote is added as origin.
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn init_vault_git(
+    remote_url: Option<String>,
+    vault_path_override: Option<String>,
+) -> Result<VaultGitStatus, String> {
+    let vault = resolve_vault_path(vault_path_override)?;
+    run_blocking(move || init_vault_repo(&vault, remote_url)).await
+}
+
+/// Network git calls can take a while; keep them off the command thread
+async fn run_blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Vault git task failed: {}", e))?
+}
+
+fn init_vault_repo(vault: &Path, remote_url: Option<String>) -> Result<VaultGitStatus, String> {
+    if is_vault_repo(vault) {
+        return Err("The vault is already git-backed".to_string());
+    }
+    std::fs::create_dir_all(vault).map_err(|e| format!("Failed to create vault: {}", e))?;
+    let remote_url = remote_url.filter(|url| !url.trim().is_empty());
+    let empty = std::fs::read_dir(vault)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true);
+
+    match remote_url.as_deref() {
+        Some(url) if empty => {
+            git(vault, &["clone", "--quiet", "--", url, "."])?;
+        }
+        _ => {
+            git(vault, &["init", "--quiet"])?;
+            if let Some(url) = remote_url.as_deref() {
+                git(vault, &["remote", "add", "--", "origin", url])?;
+            }
+            git(vault, &["add", "--all"])?;
+            if git(vault, &["diff", "--cached", "--quiet"]).is_err() {
+                commit(vault, "Share vault presets")?;
+            }
+        }
+    }
+    Ok(vault_status(vault))
+}
+
+/// Pull the remote into the vault; conflicting presets come back as field diffs
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn pull_vault(vault_path_override: Option<String>) -> Result<VaultSyncResult, String> {
+    let vault = resolve_vault_path(vault_path_override)?;
+    run_blocking(move || pull_vault_repo(&vault)).await
+}
+
+fn pull_vault_repo(vault: &Path) -> Result<VaultSyncResult, String> {
+    require_repo(vault)?;
+    if !conflicted_files(vault).is_empty() {
+        return Ok(sync_result(
+            vault,
+            false,
+            "Resolve the open conflicts before pulling again".to_string(),
+        ));
+    }
+    Ok(
+        match git_as_user(vault, &["pull", "--no-rebase", "--no-edit", "--quiet"]) {
+            Ok(_) => sync_result(vault, true, "Vault is up to date".to_string()),
+            Err(e) => {
+                let conflicts = conflicted_files(vault).len();
+                let message = if conflicts > 0 {
+                    format!(
+                        "{} preset(s) were changed on both sides; choose which version to keep",
+                        conflicts
+                    )
+                } else {
+                    e
+                };
+                sync_result(vault, false, message)
+            }
+        },
+    )
+}
+
+/// Push committed presets; rejected pushes ask for a pull first
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn push_vault(vault_path_override: Option<String>) -> Result<VaultSyncResult, String> {
+    let vault = resolve_vault_path(vault_path_override)?;
+    run_blocking(move || push_vault_repo(&vault)).await
+}
+
+fn push_vault_repo(vault: &Path) -> Result<VaultSyncResult, String> {
+    require_repo(vault)?;
+    let has_upstream = git(vault, &["rev-parse", "--abbrev-ref", "@{upstream}"]).is_ok();
+    let pushed = if has_upstream {
+        git(vault, &["push", "--quiet"])
+    } else {
+        git(
+            vault,
+            &["push", "--quiet", "--set-upstream", "origin", "HEAD"],
+        )
+    };
+    Ok(match pushed {
+        Ok(_) => sync_result(vault, true, "Vault pushed".to_string()),
+        Err(e) if e.contains("rejected") || e.contains("fetch first") => sync_result(
+            vault,
+            false,
+            "The remote has presets you don't have yet; pull first".to_string(),
+        ),
+        Err(e) => sync_result(vault, false, e),
+    })
+}
+
+/// Settle a conflicted preset by keeping "ours" or "theirs"; the merge is
+/// committed once no conflicts remain
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn resolve_vault_conflict(
+    path: String,
+    keep: String,
+    vault_path_override: Option<String>,
+) -> Result<VaultSyncResult, String> {
+    let vault = resolve_vault_path(vault_path_override)?;
+    require_repo(&vault)?;
+    if !conflicted_files(&vault).contains(&path) {
+        return Err(format!("{} has no open conflict", path));
+    }
+    let side = match keep.as_str() {
+        "ours" => "--ours",
+        "theirs" => "--theirs",
+        other => {
+            return Err(format!(
+                "Unknown side '{}' (expected ours or theirs)",
+                other
+            ))
+        }
+    };
+    // A side that deleted the preset has nothing to check out; the deletion wins
+    if git(&vault, &["checkout", side, "--", &path]).is_ok() {
+        git(&vault, &["add", "--", &path])?;
+    } else {
+        git(&vault, &["rm", "--quiet", "--", &path])?;
+    }
+    if conflicted_files(&vault).is_empty() {
+        commit(&vault, "Merge shared vault presets")?;
+    }
+    Ok(sync_result(
+        &vault,
+        true,
+        format!("Kept {} for {}", keep, path),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_field_diff() {
+        let ours = preset_fields(
+            "gold.set",
+            "; Tags: gold\ngInput_Grid=300\ngInput_MagicNumber=1\n",
+        );
+        let theirs = preset_fields(
+            "gold.set",
+            "; Tags: gold, team\ngInput_Grid=450\ngInput_MagicNumber=1\ngInput_Extra=1\n",
+        );
+        assert_eq!(
+            diff_preset_fields(&ours, &theirs),
+            vec![
+                PresetFieldDiff {
+                    field: "gInput_Extra".to_string(),
+                    ours: None,
+                    theirs: Some("1".to_string()),
+                },
+                PresetFieldDiff {
+                    field: "gInput_Grid".to_string(),
+                    ours: Some("300".to_string()),
+                    theirs: Some("450".to_string()),
+                },
+            ]
+        );
+
+        let json = preset_fields("gold.json", r#"{"config":{"general":{"magic_number":7}}}"#);
+        assert_eq!(
+            json.get("/config/general/magic_number").map(String::as_str),
+            Some("7")
+        );
+    }
+}
@@ -0,0 +1,395 @@
+// ============================================
+// VAULT LOCATIONS
+// ============================================
+//
+// Where vault presets live. Users register one or more named vault roots
+// (e.g. "Personal", "Client A" on a shared drive) and pick the active one;
+// with none set the vault is Documents/DAAVFX_Vault. Portable installs keep
+// vault_locations.json next to the executable: the settings are then read
+// from there and relative roots resolve against the app folder, so the app
+// and its vault can be moved together. A root can also be backed by an S3
+// bucket or WebDAV folder (see vault_backend), its path then being the local
+// cache the remote presets are mirrored into.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cloud_sync::{sync_provider, sync_warning};
+use crate::mt_bridge::atomic_write;
+use crate::vault_backend::VaultBackendConfig;
+
+const VAULT_LOCATIONS_FILE: &str = "vault_locations.json";
+const DEFAULT_ROOT_NAME: &str = "Default";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VaultLocations {
+    /// Name of the root presets are listed from and saved to
+    pub active: Option<String>,
+    /// Named roots; relative paths are relative to the app folder
+    pub roots: BTreeMap<String, String>,
+    /// Remote storage for roots that aren't plain folders, secrets obfuscated
+    pub backends: BTreeMap<String, VaultBackendConfig>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRootInfo {
+    pub name: String,
+    /// As stored (may be relative)
+    pub path: String,
+    pub resolved_path: String,
+    pub exists: bool,
+    /// "OneDrive", "Dropbox", ... when the root is inside a synced folder
+    pub sync_provider: Option<String>,
+    /// Remote storage with secrets blanked; Local for plain folders
+    pub backend: VaultBackendConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultLocationInfo {
+    /// Active root name; None when the default Documents vault is in use
+    pub active: Option<String>,
+    pub path: String,
+    pub portable: bool,
+    /// Shown when the active vault is inside a synced folder
+    pub sync_warning: Option<String>,
+    pub roots: Vec<VaultRootInfo>,
+}
+
+fn app_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn portable_store_path() -> Option<PathBuf> {
+    Some(app_dir().join(VAULT_LOCATIONS_FILE)).filter(|path| path.is_file())
+}
+
+fn vault_store_path() -> PathBuf {
+    portable_store_path().unwrap_or_else(|| {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("daavfx")
+            .join(VAULT_LOCATIONS_FILE)
+    })
+}
+
+fn load_locations() -> VaultLocations {
+    fs::read_to_string(vault_store_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_locations(locations: &VaultLocations) -> Result<(), String> {
+    let path = vault_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(locations)
+        .map_err(|e| format!("Failed to serialize vault locations: {}", e))?;
+    atomic_write(&path, &json)
+}
+
+fn resolve_root(path: &str, base: &Path) -> PathBuf {
+    let path = PathBuf::from(path.trim());
+    if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    }
+}
+
+/// Vault used when no root is active: Documents/DAAVFX_Vault, or next to a portable app
+fn default_vault_root() -> PathBuf {
+    if portable_store_path().is_some() {
+        return app_dir().join("Vault_Presets");
+    }
+    dirs::document_dir()
+        .map(|docs| docs.join("DAAVFX_Vault"))
+        .unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("daavfx")
+                .join("Vault")
+        })
+}
+
+impl VaultLocations {
+    /// Path of the active root, relative roots resolved against `base`
+    fn active_root(&self, base: &Path) -> Option<PathBuf> {
+        let name = self.active.as_ref()?;
+        self.roots.get(name).map(|path| resolve_root(path, base))
+    }
+
+    fn add_root(&mut self, name: &str, path: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Vault name is required".to_string());
+        }
+        if path.trim().is_empty() {
+            return Err("Vault path is required".to_string());
+        }
+        self.roots.insert(name.to_string(), path.trim().to_string());
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    fn select_root(&mut self, name: &str) -> Result<(), String> {
+        if !self.roots.contains_key(name) {
+            return Err(format!("No vault named '{}'", name));
+        }
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    fn remove_root(&mut self, name: &str) -> Result<(), String> {
+        if self.roots.remove(name).is_none() {
+            return Err(format!("No vault named '{}'", name));
+        }
+        self.backends.remove(name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        Ok(())
+    }
+
+    /// Attach remote storage to a root, registering it with a cache folder if
+    /// it doesn't exist yet; secrets left empty keep the stored ones
+    fn set_backend(
+        &mut self,
+        name: &str,
+        backend: VaultBackendConfig,
+        cache_dir: &Path,
+    ) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Vault name is required".to_string());
+        }
+        if !backend.is_remote() {
+            if !self.roots.contains_key(name) {
+                return Err(format!("No vault named '{}'", name));
+            }
+            self.backends.remove(name);
+            return Ok(());
+        }
+        backend.validate()?;
+        let backend = backend.keep_secrets_from(self.backends.get(name));
+        if !self.roots.contains_key(name) {
+            self.add_root(name, &cache_dir.to_string_lossy())?;
+        }
+        self.backends.insert(name.to_string(), backend.obfuscated());
+        Ok(())
+    }
+}
+
+/// Local cache folder for a remote root registered through set_vault_backend
+fn default_cache_dir(name: &str) -> PathBuf {
+    let safe_name = name.trim().replace(
+        |c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != ' ',
+        "_",
+    );
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("daavfx")
+        .join("vault_cache")
+        .join(safe_name)
+}
+
+/// Every root backed by remote storage: (config with secrets readable, cache folder)
+pub(crate) fn remote_roots() -> Vec<(VaultBackendConfig, PathBuf)> {
+    let locations = load_locations();
+    let base = app_dir();
+    locations
+        .backends
+        .iter()
+        .filter(|(_, backend)| backend.is_remote())
+        .filter_map(|(name, backend)| {
+            let path = locations.roots.get(name)?;
+            Some((backend.revealed(), resolve_root(path, &base)))
+        })
+        .collect()
+}
+
+/// Remote storage of the active root, if it has any
+pub(crate) fn active_remote_root() -> Option<(VaultBackendConfig, PathBuf)> {
+    let locations = load_locations();
+    let name = locations.active.as_ref()?;
+    let backend = locations.backends.get(name).filter(|b| b.is_remote())?;
+    let root = locations.active_root(&app_dir())?;
+    Some((backend.revealed(), root))
+}
+
+/// The active vault folder, created if it doesn't exist yet
+pub(crate) fn active_vault_root() -> PathBuf {
+    let root = load_locations()
+        .active_root(&app_dir())
+        .unwrap_or_else(default_vault_root);
+    if !root.exists() {
+        if let Err(e) = fs::create_dir_all(&root) {
+            tracing::warn!(path = ?root, error = %e, "Failed to create vault folder");
+        }
+    }
+    root
+}
+
+fn location_info(locations: &VaultLocations) -> VaultLocationInfo {
+    let base = app_dir();
+    let path = active_vault_root();
+    VaultLocationInfo {
+        active: locations
+            .active
+            .clone()
+            .filter(|name| locations.roots.contains_key(name)),
+        sync_warning: sync_warning(&path),
+        path: path.to_string_lossy().to_string(),
+        portable: portable_store_path().is_some(),
+        roots: locations
+            .roots
+            .iter()
+            .map(|(name, path)| {
+                let resolved = resolve_root(path, &base);
+                VaultRootInfo {
+                    name: name.clone(),
+                    path: path.clone(),
+                    exists: resolved.is_dir(),
+                    sync_provider: sync_provider(&resolved).map(str::to_string),
+                    resolved_path: resolved.to_string_lossy().to_string(),
+                    backend: locations
+                        .backends
+                        .get(name)
+                        .map(VaultBackendConfig::redacted)
+                        .unwrap_or_default(),
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn get_vault_location() -> Result<VaultLocationInfo, String> {
+    Ok(location_info(&load_locations()))
+}
+
+/// Register (or re-point) a named vault root and make it active; `name` defaults to "Default"
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_vault_location(path: String, name: Option<String>) -> Result<VaultLocationInfo, String> {
+    let mut locations = load_locations();
+    let name = name.unwrap_or_else(|| DEFAULT_ROOT_NAME.to_string());
+    locations.add_root(&name, &path)?;
+    let root = resolve_root(&path, &app_dir());
+    fs::create_dir_all(&root)
+        .map_err(|e| format!("Failed to create vault folder {}: {}", root.display(), e))?;
+    save_locations(&locations)?;
+    Ok(location_info(&locations))
+}
+
+/// Switch the active vault to a registered root
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn select_vault_location(name: String) -> Result<VaultLocationInfo, String> {
+    let mut locations = load_locations();
+    locations.select_root(&name)?;
+    save_locations(&locations)?;
+    Ok(location_info(&locations))
+}
+
+/// Forget a vault root (its files stay on disk); removing the active one falls back to the default
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn remove_vault_location(name: String) -> Result<VaultLocationInfo, String> {
+    let mut locations = load_locations();
+    locations.remove_root(&name)?;
+    save_locations(&locations)?;
+    Ok(location_info(&locations))
+}
+
+/// Back a vault root with an S3 bucket or WebDAV folder (Local detaches it);
+/// a new name is registered with a cache folder under the settings folder
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn set_vault_backend(
+    name: String,
+    backend: VaultBackendConfig,
+) -> Result<VaultLocationInfo, String> {
+    let mut locations = load_locations();
+    locations.set_backend(&name, backend, &default_cache_dir(&name))?;
+    if let Some(path) = locations.roots.get(name.trim()) {
+        let root = resolve_root(path, &app_dir());
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create vault folder {}: {}", root.display(), e))?;
+    }
+    save_locations(&locations)?;
+    Ok(location_info(&locations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_roots() {
+        let base = std::env::temp_dir().join("daavfx_app");
+        let mut locations = VaultLocations::default();
+        assert_eq!(locations.active_root(&base), None);
+        assert!(locations.add_root(" ", "/vaults/a").is_err());
+
+        locations.add_root("Client A", "vaults/client_a").unwrap();
+        assert_eq!(
+            locations.active_root(&base),
+            Some(base.join("vaults/client_a"))
+        );
+
+        let absolute = std::env::temp_dir().join("daavfx_personal_vault");
+        locations
+            .add_root("Personal", &absolute.to_string_lossy())
+            .unwrap();
+        assert_eq!(locations.active_root(&base), Some(absolute));
+
+        locations.select_root("Client A").unwrap();
+        assert!(locations.select_root("Missing").is_err());
+        locations.remove_root("Client A").unwrap();
+        assert_eq!(locations.active, None);
+        assert_eq!(locations.roots.len(), 1);
+    }
+
+    #[test]
+    fn test_remote_backend_keeps_secrets() {
+        let cache = std::env::temp_dir().join("daavfx_vault_cache_central");
+        let mut locations = VaultLocations::default();
+        let s3 = |secret: &str| VaultBackendConfig::S3 {
+            bucket: "presets".to_string(),
+            prefix: "team".to_string(),
+            region: "eu-west-1".to_string(),
+            endpoint: None,
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: secret.to_string(),
+        };
+
+        locations
+            .set_backend("Central", s3("s3cret"), &cache)
+            .unwrap();
+        assert_eq!(locations.active.as_deref(), Some("Central"));
+        assert_eq!(locations.active_root(Path::new("/")), Some(cache.clone()));
+        let stored = &locations.backends["Central"];
+        assert_ne!(stored, &s3("s3cret"));
+        assert_eq!(stored.revealed(), s3("s3cret"));
+        assert_eq!(stored.redacted(), s3(""));
+
+        // An empty secret from the UI means "unchanged"
+        locations.set_backend("Central", s3(""), &cache).unwrap();
+        assert_eq!(locations.backends["Central"].revealed(), s3("s3cret"));
+
+        locations
+            .set_backend("Central", VaultBackendConfig::Local, &cache)
+            .unwrap();
+        assert!(locations.backends.is_empty());
+        assert!(locations
+            .set_backend("Missing", VaultBackendConfig::Local, &cache)
+            .is_err());
+    }
+}
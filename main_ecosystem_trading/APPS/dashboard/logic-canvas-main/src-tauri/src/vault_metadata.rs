@@ -0,0 +1,336 @@
+// ============================================
+// VAULT METADATA EDITING
+// ============================================
+//
+// Bulk tag/comment edits over vault presets without an import/export cycle.
+// .set files only have their "; Tags:" / "; Comments:" header lines
+// rewritten, in the file's own encoding and line endings, so every
+// parameter line keeps its bytes. JSON presets get the same VaultJson
+// wrapper export_json_file writes. The modification time is put back
+// afterwards so the vault listing doesn't reorder itself.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cloud_sync::sync_aware_write;
+use crate::mt_bridge::{sanitize_and_validate_path, MTConfig, VaultJson, VaultMetadata};
+
+const TAGS_PREFIX: &str = "; Tags: ";
+const COMMENTS_PREFIX: &str = "; Comments: ";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataEdit {
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    /// Replaces the comment; an empty string clears it, None leaves it alone
+    #[serde(default)]
+    pub set_comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMetadataResult {
+    pub path: String,
+    pub tags: Vec<String>,
+    pub comments: Option<String>,
+    /// False when the edit was a no-op for this file
+    pub changed: bool,
+    /// Set when this file was skipped; the other files are still edited
+    pub error: Option<String>,
+}
+
+impl MetadataEdit {
+    /// Tags after the edit: removals first, then additions, case-insensitively unique
+    fn apply_tags(&self, current: &[String]) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in current.iter().chain(self.add_tags.iter()) {
+            let tag = tag.trim();
+            let removed = self
+                .remove_tags
+                .iter()
+                .any(|r| r.trim().eq_ignore_ascii_case(tag));
+            let duplicate = tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+            if !tag.is_empty() && !removed && !duplicate {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+
+    fn apply_comment(&self, current: Option<String>) -> Option<String> {
+        match &self.set_comment {
+            Some(comment) if comment.trim().is_empty() => None,
+            // The .set header is one line per field
+            Some(comment) => Some(comment.replace("\r\n", " ").replace('\n', " ")),
+            None => current,
+        }
+    }
+}
+
+/// Number of leading comment lines: the header the exporters write metadata into
+fn header_len(lines: &[&str]) -> usize {
+    lines
+        .iter()
+        .position(|line| !line.starts_with(';'))
+        .unwrap_or(lines.len())
+}
+
+/// Tags and comment from the header of .set text
+pub(crate) fn read_set_metadata(content: &str) -> (Vec<String>, Option<String>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let header = &lines[..header_len(&lines)];
+    let tags = header
+        .iter()
+        .find_map(|line| line.strip_prefix(TAGS_PREFIX))
+        .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default();
+    let comment = header
+        .iter()
+        .find_map(|line| line.strip_prefix(COMMENTS_PREFIX))
+        .map(|c| c.to_string());
+    (tags, comment)
+}
+
+/// Rewrite the metadata header of .set text; returns None when nothing changed
+pub(crate) fn edit_set_metadata(
+    content: &str,
+    edit: &MetadataEdit,
+) -> Option<(String, Vec<String>, Option<String>)> {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = content.split(newline).collect();
+    let header_len = header_len(&lines);
+    let (current_tags, current_comment) = read_set_metadata(content);
+
+    let tags = edit.apply_tags(&current_tags);
+    let comments = edit.apply_comment(current_comment.clone());
+    if tags == current_tags && comments == current_comment {
+        return None;
+    }
+
+    let mut header: Vec<String> = lines[..header_len]
+        .iter()
+        .filter(|line| !line.starts_with(TAGS_PREFIX) && !line.starts_with(COMMENTS_PREFIX))
+        .map(|line| line.to_string())
+        .collect();
+    if !tags.is_empty() {
+        header.push(format!("{}{}", TAGS_PREFIX, tags.join(", ")));
+    }
+    if let Some(comment) = &comments {
+        header.push(format!("{}{}", COMMENTS_PREFIX, comment));
+    }
+
+    let rewritten = header
+        .into_iter()
+        .chain(lines[header_len..].iter().map(|line| line.to_string()))
+        .collect::<Vec<_>>()
+        .join(newline);
+    Some((rewritten, tags, comments))
+}
+
+fn decode_set(bytes: &[u8]) -> Result<(String, bool), String> {
+    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        let text = String::from_utf16(&units)
+            .map_err(|e| format!("Failed to parse UTF-16 .set file: {}", e))?;
+        Ok((text, true))
+    } else {
+        let text = String::from_utf8(bytes.to_vec())
+            .map_err(|e| format!("Failed to parse .set file (not UTF-8 or UTF-16 LE): {}", e))?;
+        Ok((text, false))
+    }
+}
+
+fn encode_set(text: &str, utf16: bool) -> Vec<u8> {
+    if !utf16 {
+        return text.as_bytes().to_vec();
+    }
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    bytes
+}
+
+/// New file content (None when the edit doesn't change it) and the resulting metadata
+fn edit_file(
+    path: &Path,
+    edit: &MetadataEdit,
+) -> Result<(Option<Vec<u8>>, Vec<String>, Option<String>), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let is_json = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    if !is_json {
+        let (text, utf16) = decode_set(&bytes)?;
+        return Ok(match edit_set_metadata(&text, edit) {
+            Some((rewritten, tags, comments)) => {
+                (Some(encode_set(&rewritten, utf16)), tags, comments)
+            }
+            None => {
+                let (tags, comments) = read_set_metadata(&text);
+                (None, tags, comments)
+            }
+        });
+    }
+
+    let text = String::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8 JSON: {}", e))?;
+    let wrapper = match serde_json::from_str::<VaultJson>(&text) {
+        Ok(wrapper) => wrapper,
+        Err(_) => {
+            let mut config: MTConfig =
+                serde_json::from_str(&text).map_err(|e| format!("Invalid preset JSON: {}", e))?;
+            VaultJson {
+                metadata: VaultMetadata {
+                    tags: config.tags.clone(),
+                    comments: config.comments.clone(),
+                    annotations: config.annotations.take(),
+                },
+                config,
+            }
+        }
+    };
+    let current_tags = wrapper.metadata.tags.clone().unwrap_or_default();
+    let current_comment = wrapper.metadata.comments.clone();
+    let tags = edit.apply_tags(&current_tags);
+    let comments = edit.apply_comment(current_comment.clone());
+    if tags == current_tags && comments == current_comment {
+        return Ok((None, tags, comments));
+    }
+
+    let wrapper = VaultJson {
+        metadata: VaultMetadata {
+            tags: Some(tags.clone()).filter(|t| !t.is_empty()),
+            comments: comments.clone(),
+            annotations: wrapper.metadata.annotations,
+        },
+        config: wrapper.config,
+    };
+    let json = serde_json::to_string_pretty(&wrapper)
+        .map_err(|e| format!("Failed to serialize config with metadata: {}", e))?;
+    Ok((Some(json.into_bytes()), tags, comments))
+}
+
+fn edit_vault_file(path: &str, edit: &MetadataEdit) -> Result<VaultMetadataResult, String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(path))?;
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+    let (content, tags, comments) = edit_file(&path, edit)?;
+    let changed = content.is_some();
+    if let Some(content) = content {
+        sync_aware_write(&path, content)?;
+        // Best effort: some filesystems don't allow setting mtime
+        if let Some(modified) = modified {
+            let _ = fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(modified));
+        }
+    }
+    Ok(VaultMetadataResult {
+        path: path.to_string_lossy().to_string(),
+        tags,
+        comments,
+        changed,
+        error: None,
+    })
+}
+
+/// Add/remove tags and set the comment on many vault presets at once
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn edit_vault_metadata(
+    paths: Vec<String>,
+    add_tags: Option<Vec<String>>,
+    remove_tags: Option<Vec<String>>,
+    set_comment: Option<String>,
+) -> Result<Vec<VaultMetadataResult>, String> {
+    let edit = MetadataEdit {
+        add_tags: add_tags.unwrap_or_default(),
+        remove_tags: remove_tags.unwrap_or_default(),
+        set_comment,
+    };
+    Ok(paths
+        .iter()
+        .map(|path| {
+            edit_vault_file(path, &edit).unwrap_or_else(|error| VaultMetadataResult {
+                path: path.clone(),
+                tags: Vec::new(),
+                comments: None,
+                changed: false,
+                error: Some(error),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SET: &str = "; DAAVFX Configuration Export\r\n\
+        ; Tags: XAUUSD, Safe\r\n\
+        \r\n\
+        gInput_MagicNumber=777\r\n\
+        ; Tags: not-a-header\r\n";
+
+    fn edit(add: &[&str], remove: &[&str], comment: Option<&str>) -> MetadataEdit {
+        MetadataEdit {
+            add_tags: add.iter().map(|t| t.to_string()).collect(),
+            remove_tags: remove.iter().map(|t| t.to_string()).collect(),
+            set_comment: comment.map(|c| c.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_edit_set_metadata_rewrites_header_only() {
+        let (rewritten, tags, comments) = edit_set_metadata(
+            SET,
+            &edit(&["live", "SAFE"], &["safe"], Some("client A\nv2")),
+        )
+        .unwrap();
+
+        assert_eq!(tags, vec!["XAUUSD", "live"]);
+        assert_eq!(comments.as_deref(), Some("client A v2"));
+        assert_eq!(
+            rewritten,
+            "; DAAVFX Configuration Export\r\n\
+             ; Tags: XAUUSD, live\r\n\
+             ; Comments: client A v2\r\n\
+             \r\n\
+             gInput_MagicNumber=777\r\n\
+             ; Tags: not-a-header\r\n"
+        );
+        // Parameter bytes are untouched
+        assert!(rewritten.ends_with(SET.split_once("\r\n\r\n").unwrap().1));
+    }
+
+    #[test]
+    fn test_edit_set_metadata_noop_and_clear() {
+        assert!(edit_set_metadata(SET, &edit(&["xauusd"], &[], None)).is_none());
+
+        let (rewritten, tags, comments) =
+            edit_set_metadata(SET, &edit(&[], &["XAUUSD", "Safe"], Some(""))).unwrap();
+        assert!(tags.is_empty());
+        assert_eq!(comments, None);
+        assert!(!rewritten.contains("; Tags: XAUUSD"));
+    }
+
+    #[test]
+    fn test_utf16_round_trip() {
+        let bytes = encode_set(SET, true);
+        let (text, utf16) = decode_set(&bytes).unwrap();
+        assert!(utf16);
+        assert_eq!(text, SET);
+    }
+}
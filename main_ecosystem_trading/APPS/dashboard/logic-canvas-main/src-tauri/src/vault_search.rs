@@ -0,0 +1,493 @@
+// ============================================
+// VAULT SEARCH
+// ============================================
+//
+// Structured filters over the vault listing, plus the query builder that
+// turns a chat request ("find my XAUUSD presets with drawdown stop under
+// 20%") into those filters. Metadata filters (symbol, category, tags,
+// magic) only need the listing; value filters load each candidate config
+// through the regular importers, which cache parsed files.
+
+use serde::{Deserialize, Serialize};
+
+use crate::mt_bridge::{import_json_file, import_set_file, list_vault_files, MTConfig, VaultFile};
+
+/// Symbols that aren't two currency codes
+const OTHER_SYMBOLS: [&str; 8] = [
+    "us30", "us500", "nas100", "ger40", "uk100", "jp225", "btcusd", "ethusd",
+];
+const CURRENCY_CODES: [&str; 10] = [
+    "usd", "eur", "gbp", "jpy", "chf", "aud", "nzd", "cad", "xau", "xag",
+];
+
+/// Phrases naming a filterable value, most specific first
+static FIELD_ALIASES: &[(&str, VaultField)] = &[
+    ("drawdown stop", VaultField::DrawdownStop),
+    ("max drawdown", VaultField::DrawdownStop),
+    ("drawdown", VaultField::DrawdownStop),
+    ("dd", VaultField::DrawdownStop),
+    ("equity stop", VaultField::EquityStop),
+    ("max spread", VaultField::MaxSpread),
+    ("spread", VaultField::MaxSpread),
+    ("initial lot", VaultField::InitialLot),
+    ("lot size", VaultField::InitialLot),
+    ("lots", VaultField::InitialLot),
+    ("lot", VaultField::InitialLot),
+    ("multiplier", VaultField::Multiplier),
+    ("mult", VaultField::Multiplier),
+    ("grid", VaultField::Grid),
+];
+
+static COMPARISONS: &[(&str, Comparison)] = &[
+    ("less than", Comparison::Lt),
+    ("lower than", Comparison::Lt),
+    ("under", Comparison::Lt),
+    ("below", Comparison::Lt),
+    ("<", Comparison::Lt),
+    ("at most", Comparison::Le),
+    ("<=", Comparison::Le),
+    ("more than", Comparison::Gt),
+    ("greater than", Comparison::Gt),
+    ("higher than", Comparison::Gt),
+    ("over", Comparison::Gt),
+    ("above", Comparison::Gt),
+    (">", Comparison::Gt),
+    ("at least", Comparison::Ge),
+    (">=", Comparison::Ge),
+];
+
+/// How far past a field name to look for its number
+const VALUE_WINDOW: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultField {
+    /// Max drawdown percent, when the drawdown stop is on
+    DrawdownStop,
+    /// Equity stop value, when the equity stop is on
+    EquityStop,
+    /// Max spread points, when the spread filter is on
+    MaxSpread,
+    InitialLot,
+    Multiplier,
+    Grid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Comparison {
+    fn holds(self, actual: f64, wanted: f64) -> bool {
+        match self {
+            Comparison::Lt => actual < wanted,
+            Comparison::Le => actual <= wanted,
+            Comparison::Gt => actual > wanted,
+            Comparison::Ge => actual >= wanted,
+            Comparison::Eq => (actual - wanted).abs() < 1e-9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueFilter {
+    pub field: VaultField,
+    pub op: Comparison,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSearchFilters {
+    /// Instrument, e.g. "XAUUSD", matched against name, tags and comments
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Every tag must be present
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub magic_number: Option<i32>,
+    /// Every filter must hold; logic fields must hold for every enabled logic
+    #[serde(default)]
+    pub values: Vec<ValueFilter>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSearchResult {
+    pub filters: VaultSearchFilters,
+    pub matches: Vec<VaultFile>,
+    /// Vault files looked at
+    pub scanned: usize,
+    /// Files skipped because their config couldn't be loaded
+    pub unreadable: usize,
+}
+
+fn is_symbol(word: &str) -> bool {
+    if OTHER_SYMBOLS.contains(&word) {
+        return true;
+    }
+    word.len() == 6
+        && word.is_ascii()
+        && CURRENCY_CODES.contains(&&word[..3])
+        && CURRENCY_CODES.contains(&&word[3..])
+}
+
+/// Parse "20%", "300pt", "1.5x" and plain numbers
+fn parse_number(word: &str) -> Option<f64> {
+    let trimmed = word.trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%');
+    if trimmed.is_empty() {
+        return None;
+    }
+    trimmed.parse::<f64>().ok()
+}
+
+/// Index just past `phrase` if the words starting at `at` spell it
+fn phrase_at(words: &[&str], at: usize, phrase: &str) -> Option<usize> {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    let end = at + parts.len();
+    (end <= words.len() && words[at..end] == parts[..]).then_some(end)
+}
+
+/// Build vault filters from a retrieval command; None for anything else
+pub fn query_from_command(command: &str) -> Option<VaultSearchFilters> {
+    if !crate::chat_preprocessor::is_retrieval_command(command) {
+        return None;
+    }
+    let lower = command.to_lowercase();
+    let words: Vec<&str> = lower
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| matches!(c, ',' | '?' | '!' | '"' | '\'')))
+        .map(|w| w.trim_end_matches("'s"))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut filters = VaultSearchFilters::default();
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        if filters.symbol.is_none() && is_symbol(word) {
+            filters.symbol = Some(word.to_uppercase());
+        } else if word == "magic" {
+            let next = words[i + 1..].iter().take(2).find_map(|w| w.parse().ok());
+            if let Some(magic) = next {
+                filters.magic_number = Some(magic);
+            }
+        } else if (word == "tagged" || word == "tag") && i + 1 < words.len() {
+            filters.tags.push(words[i + 1].to_string());
+            i += 1;
+        } else if let Some(category) = category_at(&words, i) {
+            filters.category = Some(category);
+        } else if let Some((field, end)) = FIELD_ALIASES
+            .iter()
+            .find_map(|(alias, field)| phrase_at(&words, i, alias).map(|end| (*field, end)))
+        {
+            if let Some((filter, next)) = value_filter_after(&words, end, field) {
+                filters.values.push(filter);
+                i = next;
+                continue;
+            }
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    Some(filters)
+}
+
+/// "in (the) scalping category/folder" -> "scalping"
+fn category_at(words: &[&str], at: usize) -> Option<String> {
+    if words[at] != "in" {
+        return None;
+    }
+    let mut name = at + 1;
+    if words.get(name) == Some(&"the") || words.get(name) == Some(&"my") {
+        name += 1;
+    }
+    match words.get(name + 1) {
+        Some(&"category") | Some(&"folder") => words.get(name).map(|w| w.to_string()),
+        _ => None,
+    }
+}
+
+/// Comparison and number following a field name, and the index after them
+fn value_filter_after(
+    words: &[&str],
+    start: usize,
+    field: VaultField,
+) -> Option<(ValueFilter, usize)> {
+    let mut op = Comparison::Eq;
+    let mut i = start;
+    while i < words.len() && i < start + VALUE_WINDOW {
+        if let Some(value) = parse_number(words[i]) {
+            return Some((ValueFilter { field, op, value }, i + 1));
+        }
+        if let Some((found, end)) = COMPARISONS
+            .iter()
+            .find_map(|(phrase, cmp)| phrase_at(words, i, phrase).map(|end| (*cmp, end)))
+        {
+            op = found;
+            i = end;
+            continue;
+        }
+        if FIELD_ALIASES
+            .iter()
+            .any(|(alias, _)| phrase_at(words, i, alias).is_some())
+        {
+            return None;
+        }
+        i += 1;
+    }
+    None
+}
+
+fn matches_metadata(file: &VaultFile, filters: &VaultSearchFilters) -> bool {
+    let tags: Vec<String> = file
+        .tags
+        .iter()
+        .flatten()
+        .map(|t| t.to_lowercase())
+        .collect();
+    if let Some(symbol) = &filters.symbol {
+        let haystack = format!(
+            "{} {} {}",
+            file.name,
+            tags.join(" "),
+            file.comments.as_deref().unwrap_or("")
+        )
+        .to_lowercase();
+        if !haystack.contains(&symbol.to_lowercase()) {
+            return false;
+        }
+    }
+    if let Some(category) = &filters.category {
+        if !file
+            .category
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case(category))
+        {
+            return false;
+        }
+    }
+    if !filters
+        .tags
+        .iter()
+        .all(|wanted| tags.contains(&wanted.to_lowercase()))
+    {
+        return false;
+    }
+    match filters.magic_number {
+        Some(magic) => file.magic_number == Some(magic),
+        None => true,
+    }
+}
+
+/// Values a filter is checked against; empty when the setting is off
+fn field_values(config: &MTConfig, field: VaultField) -> Vec<f64> {
+    let risk = &config.general.risk_management;
+    let logic_values = |value: fn(&crate::mt_bridge::LogicConfig) -> f64| -> Vec<f64> {
+        config
+            .engines
+            .iter()
+            .flat_map(|e| &e.groups)
+            .filter(|g| g.enabled)
+            .flat_map(|g| &g.logics)
+            .filter(|l| l.enabled)
+            .map(value)
+            .collect()
+    };
+    match field {
+        VaultField::DrawdownStop => risk
+            .drawdown_stop_enabled
+            .then_some(risk.max_drawdown_percent)
+            .into_iter()
+            .collect(),
+        VaultField::EquityStop => risk
+            .equity_stop_enabled
+            .then_some(risk.equity_stop_value)
+            .into_iter()
+            .collect(),
+        VaultField::MaxSpread => risk
+            .spread_filter_enabled
+            .then_some(risk.max_spread_points)
+            .into_iter()
+            .collect(),
+        VaultField::InitialLot => logic_values(|l| l.initial_lot),
+        VaultField::Multiplier => logic_values(|l| l.multiplier),
+        VaultField::Grid => logic_values(|l| l.grid),
+    }
+}
+
+pub fn matches_values(config: &MTConfig, filters: &[ValueFilter]) -> bool {
+    filters.iter().all(|filter| {
+        let values = field_values(config, filter.field);
+        !values.is_empty() && values.iter().all(|v| filter.op.holds(*v, filter.value))
+    })
+}
+
+async fn load_vault_config(file: &VaultFile) -> Result<MTConfig, String> {
+    if file.path.to_lowercase().ends_with(".json") {
+        import_json_file(file.path.clone()).await
+    } else {
+        import_set_file(file.path.clone(), None, None).await
+    }
+}
+
+/// Vault files matching every filter, newest first
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn search_vault(
+    filters: VaultSearchFilters,
+    vault_path_override: Option<String>,
+    task_id: Option<String>,
+) -> Result<VaultSearchResult, String> {
+    let listing = list_vault_files(vault_path_override, task_id).await?;
+    let scanned = listing.files.len();
+    let mut matches = Vec::new();
+    let mut unreadable = 0;
+    for file in listing.files {
+        if !matches_metadata(&file, &filters) {
+            continue;
+        }
+        if !filters.values.is_empty() {
+            match load_vault_config(&file).await {
+                Ok(config) if matches_values(&config, &filters.values) => {}
+                Ok(_) => continue,
+                Err(_) => {
+                    unreadable += 1;
+                    continue;
+                }
+            }
+        }
+        matches.push(file);
+    }
+    Ok(VaultSearchResult {
+        filters,
+        matches,
+        scanned,
+        unreadable,
+    })
+}
+
+/// Filters a chat message asks for, if it is a vault lookup
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn build_vault_query(input: String) -> Result<Option<VaultSearchFilters>, String> {
+    Ok(query_from_command(&crate::chat_preprocessor::preprocess(
+        &input,
+    )))
+}
+
+/// Run the vault search a chat message asks for; None when the message
+/// isn't a vault lookup
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub async fn chat_search_vault(
+    input: String,
+    vault_path_override: Option<String>,
+) -> Result<Option<VaultSearchResult>, String> {
+    match query_from_command(&crate::chat_preprocessor::preprocess(&input)) {
+        Some(filters) => Ok(Some(
+            search_vault(filters, vault_path_override, None).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_bridge::create_full_v19_config;
+
+    #[test]
+    fn test_query_from_command() {
+        let filters = query_from_command("find my XAUUSD presets with drawdown stop under 20%")
+            .expect("retrieval command");
+        assert_eq!(filters.symbol.as_deref(), Some("XAUUSD"));
+        assert_eq!(
+            filters.values,
+            vec![ValueFilter {
+                field: VaultField::DrawdownStop,
+                op: Comparison::Lt,
+                value: 20.0,
+            }]
+        );
+
+        let filters = query_from_command(
+            "show presets in the scalping folder tagged safe with grid at least 300 and lot 0.01",
+        )
+        .unwrap();
+        assert_eq!(filters.category.as_deref(), Some("scalping"));
+        assert_eq!(filters.tags, vec!["safe"]);
+        assert_eq!(filters.values.len(), 2);
+        assert_eq!(filters.values[0].op, Comparison::Ge);
+        assert_eq!(filters.values[1].field, VaultField::InitialLot);
+        assert_eq!(filters.values[1].op, Comparison::Eq);
+
+        assert_eq!(
+            query_from_command("list presets for magic 777").and_then(|f| f.magic_number),
+            Some(777)
+        );
+        assert!(query_from_command("set grid to 300").is_none());
+        assert!(query_from_command("show grid on A1 power").is_none());
+    }
+
+    #[test]
+    fn test_matches_values() {
+        let mut config = create_full_v19_config();
+        config.general.risk_management.drawdown_stop_enabled = true;
+        config.general.risk_management.max_drawdown_percent = 15.0;
+        let under = |field, value| ValueFilter {
+            field,
+            op: Comparison::Lt,
+            value,
+        };
+        assert!(matches_values(
+            &config,
+            &[under(VaultField::DrawdownStop, 20.0)]
+        ));
+        assert!(!matches_values(
+            &config,
+            &[under(VaultField::DrawdownStop, 10.0)]
+        ));
+        assert!(matches_values(&config, &[under(VaultField::Grid, 1000.0)]));
+
+        config.engines[0].groups[0].logics[0].grid = 2000.0;
+        assert!(!matches_values(&config, &[under(VaultField::Grid, 1000.0)]));
+        config.general.risk_management.drawdown_stop_enabled = false;
+        assert!(!matches_values(
+            &config,
+            &[under(VaultField::DrawdownStop, 20.0)]
+        ));
+    }
+
+    #[test]
+    fn test_matches_metadata() {
+        let file = VaultFile {
+            name: "gold_scalp.set".to_string(),
+            path: "gold_scalp.set".to_string(),
+            last_modified: String::new(),
+            size: 0,
+            category: Some("Scalping".to_string()),
+            tags: Some(vec!["XAUUSD".to_string(), "Safe".to_string()]),
+            comments: None,
+            magic_number: Some(777),
+        };
+        let mut filters = VaultSearchFilters {
+            symbol: Some("XAUUSD".to_string()),
+            category: Some("scalping".to_string()),
+            tags: vec!["safe".to_string()],
+            magic_number: Some(777),
+            values: Vec::new(),
+        };
+        assert!(matches_metadata(&file, &filters));
+        filters.symbol = Some("EURUSD".to_string());
+        assert!(!matches_metadata(&file, &filters));
+    }
+}
@@ -0,0 +1,355 @@
+// ============================================
+// WALK-FORWARD TEST PLANS
+// ============================================
+//
+// Splits a date range into rolling (or anchored) in-sample / out-of-sample
+// windows, writes one tester .ini per segment plus a manifest, and collects the
+// per-window reports into a walk-forward summary.
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::backtest::{
+    normalize_tester_date, parse_tester_report, render_tester_ini, resolve_data_dir,
+    resolve_terminal_exe, write_tester_set, BacktestRequest, TesterReportSummary,
+};
+use crate::mt_bridge::{atomic_write, sanitize_and_validate_path};
+
+pub const WALK_FORWARD_MANIFEST: &str = "walk_forward_manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardRequest {
+    /// Template for every segment; its from/to dates are the overall range
+    pub backtest: BacktestRequest,
+    pub in_sample_days: u32,
+    pub out_of_sample_days: u32,
+    /// Days to advance between windows (defaults to out_of_sample_days)
+    #[serde(default)]
+    pub step_days: Option<u32>,
+    /// Keep every in-sample segment starting at the range start
+    #[serde(default)]
+    pub anchored: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardWindow {
+    pub index: usize,
+    pub in_sample_from: String,
+    pub in_sample_to: String,
+    pub out_of_sample_from: String,
+    pub out_of_sample_to: String,
+    pub in_sample_ini: String,
+    pub out_of_sample_ini: String,
+    /// Report names relative to the terminal data folder (no extension)
+    pub in_sample_report: String,
+    pub out_of_sample_report: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardManifest {
+    pub created_at: String,
+    pub data_dir: String,
+    pub set_path: String,
+    pub symbol: String,
+    pub timeframe: String,
+    pub anchored: bool,
+    pub windows: Vec<WalkForwardWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardWindowResult {
+    pub index: usize,
+    pub in_sample: Option<TesterReportSummary>,
+    pub out_of_sample: Option<TesterReportSummary>,
+    /// Out-of-sample profit per day over in-sample profit per day
+    pub efficiency: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkForwardSummary {
+    pub windows: Vec<WalkForwardWindowResult>,
+    pub completed_windows: usize,
+    pub missing_reports: Vec<String>,
+    pub total_out_of_sample_profit: f64,
+    pub profitable_out_of_sample_windows: usize,
+    pub worst_out_of_sample_drawdown_percent: Option<f64>,
+    pub average_efficiency: Option<f64>,
+}
+
+/// (in-sample from, in-sample to, out-of-sample from, out-of-sample to), inclusive
+pub fn split_walk_forward_windows(
+    from: NaiveDate,
+    to: NaiveDate,
+    in_sample_days: u32,
+    out_of_sample_days: u32,
+    step_days: u32,
+    anchored: bool,
+) -> Result<Vec<(NaiveDate, NaiveDate, NaiveDate, NaiveDate)>, String> {
+    if in_sample_days == 0 || out_of_sample_days == 0 || step_days == 0 {
+        return Err("Window and step lengths must be at least one day".to_string());
+    }
+    let mut windows = Vec::new();
+    let mut is_start = from;
+    loop {
+        let offset = windows.len() as i64 * step_days as i64;
+        let is_end = if anchored {
+            from + Duration::days(in_sample_days as i64 + offset - 1)
+        } else {
+            is_start + Duration::days(in_sample_days as i64 - 1)
+        };
+        let oos_start = is_end + Duration::days(1);
+        let oos_end = oos_start + Duration::days(out_of_sample_days as i64 - 1);
+        if oos_end > to {
+            break;
+        }
+        windows.push((is_start, is_end, oos_start, oos_end));
+        if !anchored {
+            is_start += Duration::days(step_days as i64);
+        }
+    }
+    if windows.is_empty() {
+        return Err(format!(
+            "Range {} - {} is too short for {} in-sample + {} out-of-sample days",
+            from, to, in_sample_days, out_of_sample_days
+        ));
+    }
+    Ok(windows)
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    let normalized = normalize_tester_date(value)?;
+    NaiveDate::parse_from_str(&normalized, "%Y.%m.%d")
+        .map_err(|e| format!("Invalid date '{}': {}", value, e))
+}
+
+fn segment_ini(
+    template: &BacktestRequest,
+    from: NaiveDate,
+    to: NaiveDate,
+    report: &str,
+) -> Result<String, String> {
+    let mut request = template.clone();
+    request.from_date = from.format("%Y.%m.%d").to_string();
+    request.to_date = to.format("%Y.%m.%d").to_string();
+    render_tester_ini(&request, report)
+}
+
+/// Write the ACTIVE.set, one tester .ini per window segment and the manifest
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn generate_walk_forward_plan(
+    request: WalkForwardRequest,
+) -> Result<WalkForwardManifest, String> {
+    let template = &request.backtest;
+    let terminal_exe = resolve_terminal_exe(&template.terminal_path)?;
+    let data_dir = resolve_data_dir(&terminal_exe, template.data_dir.as_deref())?;
+
+    let windows = split_walk_forward_windows(
+        parse_date(&template.from_date)?,
+        parse_date(&template.to_date)?,
+        request.in_sample_days,
+        request.out_of_sample_days,
+        request.step_days.unwrap_or(request.out_of_sample_days),
+        request.anchored,
+    )?;
+
+    let set_path = write_tester_set(&data_dir, &template.config)?;
+    let plan_dir = data_dir.join(format!(
+        "daavfx_wf_{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::create_dir_all(&plan_dir)
+        .map_err(|e| format!("Failed to create walk-forward folder: {}", e))?;
+    let plan_name = plan_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut manifest_windows = Vec::with_capacity(windows.len());
+    for (index, (is_from, is_to, oos_from, oos_to)) in windows.into_iter().enumerate() {
+        let is_report = format!("{}\\window_{:02}_is", plan_name, index + 1);
+        let oos_report = format!("{}\\window_{:02}_oos", plan_name, index + 1);
+        let is_ini = plan_dir.join(format!("window_{:02}_is.ini", index + 1));
+        let oos_ini = plan_dir.join(format!("window_{:02}_oos.ini", index + 1));
+        atomic_write(&is_ini, &segment_ini(template, is_from, is_to, &is_report)?)?;
+        atomic_write(
+            &oos_ini,
+            &segment_ini(template, oos_from, oos_to, &oos_report)?,
+        )?;
+
+        manifest_windows.push(WalkForwardWindow {
+            index: index + 1,
+            in_sample_from: is_from.format("%Y.%m.%d").to_string(),
+            in_sample_to: is_to.format("%Y.%m.%d").to_string(),
+            out_of_sample_from: oos_from.format("%Y.%m.%d").to_string(),
+            out_of_sample_to: oos_to.format("%Y.%m.%d").to_string(),
+            in_sample_ini: is_ini.to_string_lossy().to_string(),
+            out_of_sample_ini: oos_ini.to_string_lossy().to_string(),
+            in_sample_report: is_report,
+            out_of_sample_report: oos_report,
+        });
+    }
+
+    let manifest = WalkForwardManifest {
+        created_at: chrono::Local::now().to_rfc3339(),
+        data_dir: data_dir.to_string_lossy().to_string(),
+        set_path: set_path.to_string_lossy().to_string(),
+        symbol: template.symbol.clone(),
+        timeframe: template.timeframe.trim().to_uppercase(),
+        anchored: request.anchored,
+        windows: manifest_windows,
+    };
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    atomic_write(&plan_dir.join(WALK_FORWARD_MANIFEST), &json)?;
+    Ok(manifest)
+}
+
+fn find_segment_report(data_dir: &Path, report: &str) -> Option<PathBuf> {
+    ["htm", "html"]
+        .iter()
+        .map(|ext| data_dir.join(format!("{}.{}", report, ext)))
+        .find(|p| p.is_file())
+}
+
+fn days_between(from: &str, to: &str) -> Option<f64> {
+    let from = NaiveDate::parse_from_str(from, "%Y.%m.%d").ok()?;
+    let to = NaiveDate::parse_from_str(to, "%Y.%m.%d").ok()?;
+    Some(((to - from).num_days() + 1) as f64)
+}
+
+fn window_efficiency(
+    window: &WalkForwardWindow,
+    in_sample: Option<&TesterReportSummary>,
+    out_of_sample: Option<&TesterReportSummary>,
+) -> Option<f64> {
+    let (is, oos) = (in_sample?, out_of_sample?);
+    if is.net_profit <= 0.0 {
+        return None;
+    }
+    let is_days = days_between(&window.in_sample_from, &window.in_sample_to)?;
+    let oos_days = days_between(&window.out_of_sample_from, &window.out_of_sample_to)?;
+    Some((oos.net_profit / oos_days) / (is.net_profit / is_days))
+}
+
+/// Aggregate per-window reports; windows whose reports are missing are listed, not fatal
+pub fn summarize_walk_forward(manifest: &WalkForwardManifest) -> WalkForwardSummary {
+    let data_dir = PathBuf::from(&manifest.data_dir);
+    let mut missing_reports = Vec::new();
+    let mut load = |report: &str| match find_segment_report(&data_dir, report) {
+        Some(path) => parse_tester_report(&path).ok(),
+        None => {
+            missing_reports.push(report.to_string());
+            None
+        }
+    };
+
+    let windows: Vec<WalkForwardWindowResult> = manifest
+        .windows
+        .iter()
+        .map(|w| {
+            let in_sample = load(&w.in_sample_report);
+            let out_of_sample = load(&w.out_of_sample_report);
+            WalkForwardWindowResult {
+                index: w.index,
+                efficiency: window_efficiency(w, in_sample.as_ref(), out_of_sample.as_ref()),
+                in_sample,
+                out_of_sample,
+            }
+        })
+        .collect();
+
+    let completed: Vec<&TesterReportSummary> = windows
+        .iter()
+        .filter_map(|w| w.out_of_sample.as_ref())
+        .collect();
+    let efficiencies: Vec<f64> = windows.iter().filter_map(|w| w.efficiency).collect();
+
+    WalkForwardSummary {
+        completed_windows: completed.len(),
+        missing_reports,
+        total_out_of_sample_profit: completed.iter().map(|r| r.net_profit).sum(),
+        profitable_out_of_sample_windows: completed.iter().filter(|r| r.net_profit > 0.0).count(),
+        worst_out_of_sample_drawdown_percent: completed
+            .iter()
+            .filter_map(|r| r.max_drawdown_percent)
+            .fold(None, |worst: Option<f64>, dd| {
+                Some(worst.map_or(dd, |w| w.max(dd)))
+            }),
+        average_efficiency: if efficiencies.is_empty() {
+            None
+        } else {
+            Some(efficiencies.iter().sum::<f64>() / efficiencies.len() as f64)
+        },
+        windows,
+    }
+}
+
+/// Read a manifest written by generate_walk_forward_plan and summarize its reports
+#[cfg_attr(feature = "tauri-app", tauri::command(rename_all = "camelCase"))]
+pub fn collect_walk_forward_results(manifest_path: String) -> Result<WalkForwardSummary, String> {
+    let sanitized_path = sanitize_and_validate_path(&PathBuf::from(manifest_path))?;
+    let json = std::fs::read_to_string(&sanitized_path)
+        .map_err(|e| format!("Failed to read manifest: {}", e))?;
+    let manifest: WalkForwardManifest =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse manifest: {}", e))?;
+    Ok(summarize_walk_forward(&manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_split_rolling_windows() {
+        let windows =
+            split_walk_forward_windows(date("2024-01-01"), date("2024-12-31"), 120, 30, 30, false)
+                .unwrap();
+        assert_eq!(windows.len(), 8);
+        assert_eq!(
+            windows[0],
+            (
+                date("2024-01-01"),
+                date("2024-04-29"),
+                date("2024-04-30"),
+                date("2024-05-29")
+            )
+        );
+        // Rolling: every in-sample segment has the same length
+        assert_eq!(windows[1].0, date("2024-01-31"));
+        assert_eq!(windows[1].2, date("2024-05-30"));
+        assert!(windows.last().unwrap().3 <= date("2024-12-31"));
+    }
+
+    #[test]
+    fn test_split_anchored_windows() {
+        let windows =
+            split_walk_forward_windows(date("2024-01-01"), date("2024-06-30"), 90, 30, 30, true)
+                .unwrap();
+        assert_eq!(windows.len(), 3);
+        assert!(windows.iter().all(|w| w.0 == date("2024-01-01")));
+        assert_eq!(windows[2].1, date("2024-05-29"));
+    }
+
+    #[test]
+    fn test_split_rejects_short_range() {
+        assert!(split_walk_forward_windows(
+            date("2024-01-01"),
+            date("2024-01-20"),
+            30,
+            10,
+            10,
+            false
+        )
+        .is_err());
+    }
+}
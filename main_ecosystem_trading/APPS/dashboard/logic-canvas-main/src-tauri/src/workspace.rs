@@ -0,0 +1,214 @@
+// ============================================
+// WORKSPACES
+// ============================================
+//
+// A workspace (.daavfxproj) bundles everything that differs between client
+// accounts: the terminal profile, the vault preset being edited, the naming
+// policy and which terminal config the file watcher follows. Opening one
+// restores that whole context in a single step.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[cfg(feature = "tauri-app")]
+use tauri::State;
+
+use crate::mt_bridge::{atomic_write, sanitize_and_validate_path, MTConfig};
+#[cfg(feature = "tauri-app")]
+use crate::mt_bridge::{import_json_file, import_set_file, start_file_watcher, MTBridgeState};
+use crate::naming::NamingPolicy;
+use crate::terminal_discovery::TerminalProfile;
+
+pub const WORKSPACE_EXTENSION: &str = "daavfxproj";
+const WORKSPACE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherSettings {
+    pub mt4_path: Option<String>,
+    pub mt5_path: Option<String>,
+    /// "MT4" or "MT5": whose config the file watcher follows once opened
+    pub watch_platform: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    #[serde(default = "default_workspace_version")]
+    pub version: u32,
+    pub name: String,
+    pub terminal_profile: Option<TerminalProfile>,
+    /// Vault file (.json or .set) loaded as the active config
+    pub active_preset: Option<String>,
+    #[serde(default)]
+    pub naming_policy: NamingPolicy,
+    #[serde(default)]
+    pub watcher: WatcherSettings,
+}
+
+fn default_workspace_version() -> u32 {
+    WORKSPACE_VERSION
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedWorkspace {
+    pub workspace: Workspace,
+    /// The active preset, when it could be loaded
+    pub config: Option<MTConfig>,
+    /// Handle the preset was opened under
+    pub handle: Option<String>,
+    /// Parts of the workspace that couldn't be restored (missing folders, presets)
+    pub warnings: Vec<String>,
+}
+
+fn workspace_path(path: &str) -> Result<PathBuf, String> {
+    let path = sanitize_and_validate_path(&PathBuf::from(path))?;
+    let has_extension = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case(WORKSPACE_EXTENSION))
+        .unwrap_or(false);
+    if !has_extension {
+        return Err(format!(
+            "Workspace files must end in .{}",
+            WORKSPACE_EXTENSION
+        ));
+    }
+    Ok(path)
+}
+
+pub(crate) fn read_workspace(path: &str) -> Result<Workspace, String> {
+    let path = workspace_path(path)?;
+    let json =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read workspace: {}", e))?;
+    let workspace: Workspace =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse workspace: {}", e))?;
+    if workspace.version > WORKSPACE_VERSION {
+        return Err(format!(
+            "Workspace version {} is newer than this dashboard supports ({})",
+            workspace.version, WORKSPACE_VERSION
+        ));
+    }
+    Ok(workspace)
+}
+
+pub(crate) fn write_workspace(path: &str, workspace: &Workspace) -> Result<PathBuf, String> {
+    let path = workspace_path(path)?;
+    workspace.naming_policy.validate()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create workspace folder: {}", e))?;
+    }
+    let workspace = Workspace {
+        version: WORKSPACE_VERSION,
+        ..workspace.clone()
+    };
+    let json = serde_json::to_string_pretty(&workspace)
+        .map_err(|e| format!("Failed to serialize workspace: {}", e))?;
+    atomic_write(&path, &json)?;
+    Ok(path)
+}
+
+#[cfg_attr(feature = "tauri-app", tauri::command)]
+pub fn save_workspace(path: String, workspace: Workspace) -> Result<String, String> {
+    Ok(write_workspace(&path, &workspace)?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// Load a workspace and apply it: naming policy, terminal paths, active preset and the
+/// file watcher. Parts that fail are reported as warnings so the rest still switches over.
+#[cfg(feature = "tauri-app")]
+#[tauri::command]
+pub async fn open_workspace(
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, MTBridgeState>,
+) -> Result<OpenedWorkspace, String> {
+    let workspace = read_workspace(&path)?;
+    let mut warnings = Vec::new();
+
+    crate::naming::set_naming_policy(workspace.naming_policy.clone())?;
+
+    let terminal_paths = [
+        (&state.mt4_path, &workspace.watcher.mt4_path),
+        (&state.mt5_path, &workspace.watcher.mt5_path),
+    ];
+    for (slot, terminal_path) in terminal_paths {
+        let Some(terminal_path) = terminal_path else {
+            continue;
+        };
+        match sanitize_and_validate_path(&PathBuf::from(terminal_path)) {
+            Ok(sanitized) if sanitized.exists() => *slot.write().await = Some(sanitized),
+            Ok(_) => warnings.push(format!("Terminal path not found: {}", terminal_path)),
+            Err(e) => warnings.push(e),
+        }
+    }
+
+    let mut config = None;
+    let mut handle = None;
+    if let Some(preset) = &workspace.active_preset {
+        let loaded = if preset.to_ascii_lowercase().ends_with(".json") {
+            import_json_file(preset.clone()).await
+        } else {
+            import_set_file(preset.clone(), None, None).await
+        };
+        match loaded {
+            Ok(loaded) => {
+                let label = Some(workspace.name.clone());
+                handle = Some(state.configs.write().await.open(loaded.clone(), label));
+                config = Some(loaded);
+            }
+            Err(e) => warnings.push(format!("Failed to load preset {}: {}", preset, e)),
+        }
+    }
+
+    if let Some(platform) = &workspace.watcher.watch_platform {
+        if let Err(e) = start_file_watcher(platform.clone(), app_handle, state).await {
+            warnings.push(format!("File watcher not started: {}", e));
+        }
+    }
+
+    Ok(OpenedWorkspace {
+        workspace,
+        config,
+        handle,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "daavfx_workspace_{}.{}",
+            std::process::id(),
+            WORKSPACE_EXTENSION
+        ));
+        let path = path.to_string_lossy().to_string();
+        let workspace = Workspace {
+            version: 0,
+            name: "Client A".to_string(),
+            terminal_profile: None,
+            active_preset: Some("Vault_Presets/client_a.json".to_string()),
+            naming_policy: NamingPolicy::default(),
+            watcher: WatcherSettings {
+                watch_platform: Some("MT5".to_string()),
+                ..Default::default()
+            },
+        };
+
+        write_workspace(&path, &workspace).unwrap();
+        let loaded = read_workspace(&path).unwrap();
+        assert_eq!(loaded.version, WORKSPACE_VERSION);
+        assert_eq!(loaded.name, "Client A");
+        assert_eq!(loaded.active_preset, workspace.active_preset);
+        assert_eq!(loaded.watcher, workspace.watcher);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(save_workspace("client.json".to_string(), workspace).is_err());
+    }
+}
@@ -14,6 +14,15 @@ use app_lib::{
   GroupConfig,
   LogicConfig,
 };
+use daavfx_core::config_enums::{
+  PartialBalance,
+  PartialMode,
+  StrategyType,
+  TradingMode,
+  TrailMethod,
+  TrailStepMethod,
+  TrailStepMode,
+};
 
 fn base_config() -> MTConfig {
   MTConfig {
@@ -26,6 +35,7 @@ fn base_config() -> MTConfig {
     current_set_name: None,
     tags: None,
     comments: None,
+    annotations: None,
     general: GeneralConfig {
       license_key: "".into(),
       license_server_url: "https://license.daavfx.com".into(),
@@ -122,7 +132,7 @@ fn base_config() -> MTConfig {
           grid: 10.0,
           grid_b: None,
           grid_s: None,
-          trail_method: "Trail".into(),
+          trail_method: TrailMethod::Points,
           trail_value: 20.0,
           trail_value_b: None,
           trail_value_s: None,
@@ -132,14 +142,14 @@ fn base_config() -> MTConfig {
           trail_step: 1.0,
           trail_step_b: None,
           trail_step_s: None,
-          trail_step_method: "Step".into(),
+          trail_step_method: TrailStepMethod::Points,
           start_level: None,
           last_lot: Some(0.55),
           close_targets: "Targets_Default".into(),
           order_count_reference: "Orders_Default".into(),
           reset_lot_on_restart: false,
-          strategy_type: "Trail".into(),
-          trading_mode: "Trending".into(),
+          strategy_type: StrategyType::Trail,
+          trading_mode: Some(TradingMode::CounterTrend),
           allow_buy: true,
           allow_sell: true,
           use_tp: false,
@@ -154,7 +164,7 @@ fn base_config() -> MTConfig {
           hedge_scale: 50.0,
           reverse_reference: "Logic_None".into(),
           hedge_reference: "Logic_None".into(),
-          trail_step_mode: "TrailStepMode_Auto".into(),
+          trail_step_mode: TrailStepMode::Auto,
           trail_step_cycle: 1,
           trail_step_balance: 0.0,
           trail_step_2: None,
@@ -189,10 +199,10 @@ fn base_config() -> MTConfig {
           trail_step_mode_7: None,
           close_partial: false,
           close_partial_cycle: 1,
-          close_partial_mode: "None".into(),
+          close_partial_mode: PartialMode::Mid,
           close_partial_profit_threshold: 0.0,
-          close_partial_balance: "None".into(),
-          close_partial_trail_step_mode: "TrailStepMode_Auto".into(),
+          close_partial_balance: PartialBalance::Balanced,
+          close_partial_trail_step_mode: TrailStepMode::Auto,
           close_partial_2: None,
           close_partial_cycle_2: None,
           close_partial_mode_2: None,
@@ -223,7 +233,7 @@ fn export_contains_edited_values() {
   let cfg = base_config();
   let tmp = std::env::temp_dir().join("daavfx_roundtrip.set");
   let path_str = tmp.to_string_lossy().to_string();
-  let r = export_set_file(cfg.clone(), path_str.clone(), "MT4".into(), true, Some("BOTH".into()), Some(vec!["test".into()]), Some("roundtrip".into()));
+  let r = export_set_file(cfg.clone(), path_str.clone(), "MT4".into(), true, Some("BOTH".into()), Some(vec!["test".into()]), Some("roundtrip".into()), None, None, None);
   assert!(r.is_ok());
   let content = std::fs::read_to_string(&tmp).unwrap();
   assert!(content.contains("gInput_Initial_loT_"));
@@ -238,7 +248,7 @@ fn massive_v19_export_has_expected_structure() {
   let tmp = std::env::temp_dir().join("daavfx_massive_v19.set");
   let path_str = tmp.to_string_lossy().to_string();
 
-  export_massive_v19_setfile(cfg.clone(), path_str.clone(), "MT5".into(), Some(false)).expect("export failed");
+  export_massive_v19_setfile(cfg.clone(), path_str.clone(), "MT5".into(), Some(false), None, None, None).expect("export failed");
 
   let file_content = std::fs::read_to_string(&tmp).expect("read failed");
   let validation = validate_v19_setfile(&file_content);
@@ -254,9 +264,9 @@ async fn import_restores_values() {
   let cfg = base_config();
   let tmp = std::env::temp_dir().join("daavfx_roundtrip_import.set");
   let path_str = tmp.to_string_lossy().to_string();
-  let r = export_set_file(cfg.clone(), path_str.clone(), "MT4".into(), false, Some("BOTH".into()), None, None);
+  let r = export_set_file(cfg.clone(), path_str.clone(), "MT4".into(), false, Some("BOTH".into()), None, None, None, None, None);
   assert!(r.is_ok());
-  let imported = import_set_file(path_str.clone()).await.unwrap();
+  let imported = import_set_file(path_str.clone(), None, None).await.unwrap();
   let logic = &imported.engines[0].groups[0].logics[0];
   assert!((logic.initial_lot - 0.23).abs() < 1e-6);
   assert!((logic.multiplier - 1.55).abs() < 1e-6);
@@ -273,9 +283,9 @@ async fn massive_v19_export_then_import_overrides_values() {
   let tmp = std::env::temp_dir().join("daavfx_massive_v19_roundtrip.set");
   let path_str = tmp.to_string_lossy().to_string();
 
-  export_massive_v19_setfile(cfg.clone(), path_str.clone(), "MT5".into(), Some(false)).expect("export failed");
+  export_massive_v19_setfile(cfg.clone(), path_str.clone(), "MT5".into(), Some(false), None, None, None).expect("export failed");
 
-  let imported = import_set_file(path_str.clone()).await.expect("import failed");
+  let imported = import_set_file(path_str.clone(), None, None).await.expect("import failed");
 
   assert_eq!(imported.general.magic_number_buy, 111);
   assert_eq!(imported.general.magic_number_sell, 222);
@@ -1,10 +1,26 @@
 import { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
+interface IntentCandidate {
+  intent: string;
+  score: number;
+}
+
 interface IntentPrediction {
   intent: string;
   confidence: number;
   input: string;
+  best_guess?: string;
+  candidates?: IntentCandidate[];
+}
+
+export interface DecodeOptions {
+  strategy?: 'greedy' | 'beam' | 'sample';
+  beamWidth?: number;
+  topK?: number;
+  topP?: number;
+  temperature?: number;
+  seed?: number;
 }
 
 interface ExtractedParameter {
@@ -14,11 +30,22 @@ interface ExtractedParameter {
   is_denoised: boolean;
 }
 
+type PredictionStage = 'diffusion' | 'transformer' | 'rule_based';
+
+interface StageAttempt {
+  stage: PredictionStage;
+  intent: string;
+  confidence: number;
+  accepted: boolean;
+}
+
 interface DiffusionPrediction {
   intent: string;
   confidence: number;
   parameters: ExtractedParameter[];
   input: string;
+  stage: PredictionStage;
+  attempts: StageAttempt[];
 }
 
 interface CommandExtraction {
@@ -120,8 +147,8 @@ export function useChatNeural() {
     }
   }, []);
 
-  const predictWithTransformer = useCallback(async (input: string): Promise<IntentPrediction> => {
-    const prediction = await invoke<IntentPrediction>('predict_transformer', { input });
+  const predictWithTransformer = useCallback(async (input: string, options?: DecodeOptions): Promise<IntentPrediction> => {
+    const prediction = await invoke<IntentPrediction>('predict_transformer', { input, options });
     console.log('Transformer prediction:', prediction);
     return prediction;
   }, []);
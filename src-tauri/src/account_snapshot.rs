@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::tactical_bridge::{sync_route, unix_now};
+
+/// Snapshot layout written by current EA builds
+const ACCOUNT_SNAPSHOT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotPosition {
+  pub ticket: i64,
+  pub symbol: String,
+  /// "buy" or "sell"
+  pub side: String,
+  pub volume: f64,
+  pub open_price: f64,
+  #[serde(default)]
+  pub current_price: f64,
+  pub profit: f64,
+  #[serde(default)]
+  pub swap: f64,
+  #[serde(default)]
+  pub commission: f64,
+  pub magic: i32,
+  #[serde(default)]
+  pub comment: String,
+}
+
+/// Account figures the EA writes on its timer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+  #[serde(default = "default_schema_version")]
+  pub schema_version: u32,
+  /// Unix seconds when the EA wrote the snapshot
+  pub timestamp: i64,
+  #[serde(default)]
+  pub login: Option<i64>,
+  #[serde(default)]
+  pub server: String,
+  pub currency: String,
+  pub balance: f64,
+  pub equity: f64,
+  #[serde(default)]
+  pub margin: f64,
+  #[serde(default)]
+  pub free_margin: f64,
+  #[serde(default)]
+  pub leverage: Option<i32>,
+  #[serde(default)]
+  pub positions: Vec<SnapshotPosition>,
+}
+
+fn default_schema_version() -> u32 {
+  ACCOUNT_SNAPSHOT_SCHEMA_VERSION
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MagicExposure {
+  pub magic: i32,
+  pub positions: usize,
+  pub buy_volume: f64,
+  pub sell_volume: f64,
+  /// Profit including swap and commission
+  pub profit: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSnapshotReport {
+  pub profile: String,
+  pub path: String,
+  pub snapshot: AccountSnapshot,
+  pub age_secs: u64,
+  pub floating_profit: f64,
+  /// Equity below balance as a percentage of balance (0 when in profit)
+  pub drawdown_percent: f64,
+  /// Equity / margin * 100, None without open margin
+  pub margin_level: Option<f64>,
+  pub exposure: Vec<MagicExposure>,
+}
+
+pub fn parse_snapshot(content: &str) -> Result<AccountSnapshot, String> {
+  let snapshot: AccountSnapshot = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+    .map_err(|e| format!("Failed to parse account snapshot JSON: {}", e))?;
+  if snapshot.schema_version == 0 || snapshot.schema_version > ACCOUNT_SNAPSHOT_SCHEMA_VERSION {
+    return Err(format!(
+      "Unsupported account snapshot schema_version {}",
+      snapshot.schema_version
+    ));
+  }
+  if !snapshot.balance.is_finite() || !snapshot.equity.is_finite() || !snapshot.margin.is_finite() {
+    return Err("Account snapshot has non-numeric balance, equity or margin".to_string());
+  }
+  Ok(snapshot)
+}
+
+/// Per-magic totals, in ascending magic order
+pub fn exposure_by_magic(positions: &[SnapshotPosition]) -> Vec<MagicExposure> {
+  let mut exposure: Vec<MagicExposure> = Vec::new();
+  for position in positions {
+    let index = match exposure.iter().position(|e| e.magic == position.magic) {
+      Some(index) => index,
+      None => {
+        exposure.push(MagicExposure {
+          magic: position.magic,
+          positions: 0,
+          buy_volume: 0.0,
+          sell_volume: 0.0,
+          profit: 0.0,
+        });
+        exposure.len() - 1
+      }
+    };
+    let entry = &mut exposure[index];
+    entry.positions += 1;
+    if position.side.eq_ignore_ascii_case("sell") {
+      entry.sell_volume += position.volume;
+    } else {
+      entry.buy_volume += position.volume;
+    }
+    entry.profit += position.profit + position.swap + position.commission;
+  }
+  exposure.sort_by_key(|e| e.magic);
+  exposure
+}
+
+pub fn build_report(
+  profile: &str,
+  path: &Path,
+  snapshot: AccountSnapshot,
+) -> AccountSnapshotReport {
+  let drawdown_percent = if snapshot.balance > 0.0 {
+    ((snapshot.balance - snapshot.equity) / snapshot.balance * 100.0).max(0.0)
+  } else {
+    0.0
+  };
+  let margin_level = if snapshot.margin > 0.0 {
+    Some(snapshot.equity / snapshot.margin * 100.0)
+  } else {
+    None
+  };
+  AccountSnapshotReport {
+    profile: profile.to_string(),
+    path: path.to_string_lossy().to_string(),
+    age_secs: (unix_now() - snapshot.timestamp).max(0) as u64,
+    floating_profit: snapshot.equity - snapshot.balance,
+    drawdown_percent,
+    margin_level,
+    exposure: exposure_by_magic(&snapshot.positions),
+    snapshot,
+  }
+}
+
+/// Latest account snapshot for an EA profile (a registered instance id, or
+/// the single-EA snapshot without one); None until the EA has written one
+#[tauri::command]
+pub fn get_account_snapshot(
+  platform: String,
+  profile: Option<String>,
+) -> Result<Option<AccountSnapshotReport>, String> {
+  let route = sync_route(&platform, profile.as_deref())?;
+  if !route.account_path.exists() {
+    return Ok(None);
+  }
+  let content = fs::read_to_string(&route.account_path)
+    .map_err(|e| format!("Failed to read account snapshot: {}", e))?;
+  let snapshot = parse_snapshot(&content)?;
+  Ok(Some(build_report(&route.instance_id, &route.account_path, snapshot)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SNAPSHOT: &str = r#"{
+    "timestamp": 1772445600,
+    "currency": "USD",
+    "balance": 10000.0,
+    "equity": 9400.0,
+    "margin": 800.0,
+    "free_margin": 8600.0,
+    "positions": [
+      {"ticket": 1, "symbol": "XAUUSD", "side": "buy", "volume": 0.2, "open_price": 2900.0,
+       "profit": -450.0, "swap": -5.0, "magic": 777},
+      {"ticket": 2, "symbol": "XAUUSD", "side": "sell", "volume": 0.1, "open_price": 2910.0,
+       "profit": -145.0, "magic": 777},
+      {"ticket": 3, "symbol": "EURUSD", "side": "buy", "volume": 1.0, "open_price": 1.08,
+       "profit": 0.0, "commission": -7.0, "magic": 101}
+    ]
+  }"#;
+
+  #[test]
+  fn test_snapshot_report() {
+    let snapshot = parse_snapshot(SNAPSHOT).unwrap();
+    assert_eq!(snapshot.schema_version, 1);
+    let report = build_report("default", Path::new("DAAVFX_Account.json"), snapshot);
+    assert!((report.drawdown_percent - 6.0).abs() < 1e-9);
+    assert!((report.margin_level.unwrap() - 1175.0).abs() < 1e-9);
+    assert!((report.floating_profit + 600.0).abs() < 1e-9);
+
+    assert_eq!(report.exposure.len(), 2);
+    assert_eq!(report.exposure[0].magic, 101);
+    assert!((report.exposure[0].profit + 7.0).abs() < 1e-9);
+    let gold = &report.exposure[1];
+    assert_eq!(gold.positions, 2);
+    assert!((gold.buy_volume - 0.2).abs() < 1e-9 && (gold.sell_volume - 0.1).abs() < 1e-9);
+    assert!((gold.profit + 600.0).abs() < 1e-9);
+
+    let future = SNAPSHOT.replacen("\"timestamp\"", "\"schema_version\": 9, \"timestamp\"", 1);
+    assert!(parse_snapshot(&future).is_err());
+  }
+}
@@ -0,0 +1,261 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::mt_bridge::get_mt_common_files_dir;
+use crate::tactical_bridge::{last_heartbeat, stale_for_secs, sync_route, SyncState};
+
+const ACTIVE_SET_FILE: &str = "ACTIVE.set";
+const DEFAULT_WATCH_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_WATCH_INTERVAL_MS: u64 = 1000;
+const DEFAULT_STALE_AFTER_SECS: u64 = 30;
+
+/// Bumped on every start/stop so only the latest watchdog keeps running
+static WATCHDOG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActiveSetReadiness {
+  /// The EA hasn't picked up the exported file yet
+  Waiting,
+  /// The EA reports the exported file's hash
+  Ready,
+  /// No heartbeat from the EA, or it never reported the file in time
+  Stale,
+  /// ACTIVE.set or the EA's loaded file differs from what was exported
+  Mismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSetWatchEvent {
+  pub platform: String,
+  pub instance_id: String,
+  pub status: ActiveSetReadiness,
+  pub expected_hash: String,
+  /// Hash of ACTIVE.set as it is on disk now
+  pub file_hash: Option<String>,
+  /// Hash the EA reports for the file it loaded
+  pub ea_hash: Option<String>,
+  pub message: String,
+  pub elapsed_ms: u64,
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+  Sha256::digest(bytes)
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect()
+}
+
+fn file_hash(path: &Path) -> Option<String> {
+  fs::read(path).ok().map(|bytes| sha256_hex(&bytes))
+}
+
+/// Where the EA stands on the exported file. `timed_out` turns a wait into
+/// a verdict: stale when the EA never reported, mismatch when it reports
+/// another file.
+pub(crate) fn assess_readiness(
+  expected_hash: &str,
+  file_hash: Option<&str>,
+  state: Option<&SyncState>,
+  heartbeat_stale: bool,
+  timed_out: bool,
+) -> (ActiveSetReadiness, String) {
+  match file_hash {
+    None => {
+      return (
+        ActiveSetReadiness::Mismatch,
+        "ACTIVE.set was removed after the export".to_string(),
+      )
+    }
+    Some(hash) if !hash.eq_ignore_ascii_case(expected_hash) => {
+      return (
+        ActiveSetReadiness::Mismatch,
+        "ACTIVE.set was overwritten after the export".to_string(),
+      )
+    }
+    Some(_) => {}
+  }
+
+  let Some(state) = state.filter(|_| !heartbeat_stale) else {
+    return (
+      ActiveSetReadiness::Stale,
+      "No recent heartbeat from the EA".to_string(),
+    );
+  };
+  let ea_hash = state.active_set_hash.trim();
+  if ea_hash.eq_ignore_ascii_case(expected_hash) {
+    return (
+      ActiveSetReadiness::Ready,
+      "EA is running the exported set".to_string(),
+    );
+  }
+  match (timed_out, ea_hash.is_empty()) {
+    (false, _) => (
+      ActiveSetReadiness::Waiting,
+      "Waiting for the EA to reload".to_string(),
+    ),
+    (true, true) => (
+      ActiveSetReadiness::Stale,
+      "The EA never reported which set it loaded".to_string(),
+    ),
+    (true, false) => (
+      ActiveSetReadiness::Mismatch,
+      "The EA is running a different set file".to_string(),
+    ),
+  }
+}
+
+fn read_state(path: &Path) -> Option<SyncState> {
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Re-check ACTIVE.set after an export until the EA's sync state reports the
+/// same hash, emitting "active-set-ready", "active-set-stale" or
+/// "active-set-mismatch" whenever the verdict changes. Without
+/// `expected_hash` the current ACTIVE.set is taken as the exported file, so
+/// call this right after export_active_set_file_to_mt_common_files.
+/// Starting again replaces the running watchdog.
+#[tauri::command]
+pub fn start_active_set_watchdog(
+  platform: String,
+  instance_id: Option<String>,
+  expected_hash: Option<String>,
+  timeout_secs: Option<u64>,
+  interval_ms: Option<u64>,
+  stale_after_secs: Option<u64>,
+  app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+  let active_path = get_mt_common_files_dir()?.join(ACTIVE_SET_FILE);
+  let expected_hash = match expected_hash.filter(|h| !h.trim().is_empty()) {
+    Some(hash) => hash.trim().to_lowercase(),
+    None => {
+      file_hash(&active_path).ok_or_else(|| "ACTIVE.set has not been exported".to_string())?
+    }
+  };
+  let route = sync_route(&platform, instance_id.as_deref())?;
+  let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_WATCH_TIMEOUT_SECS).max(1));
+  let interval = Duration::from_millis(interval_ms.unwrap_or(DEFAULT_WATCH_INTERVAL_MS).max(250));
+  let stale_after = stale_after_secs.unwrap_or(DEFAULT_STALE_AFTER_SECS).max(1);
+  let generation = WATCHDOG_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  let watched_hash = expected_hash.clone();
+
+  std::thread::spawn(move || {
+    let started = Instant::now();
+    let mut last_status = ActiveSetReadiness::Waiting;
+    while WATCHDOG_GENERATION.load(Ordering::SeqCst) == generation {
+      let timed_out = started.elapsed() >= timeout;
+      let current_hash = file_hash(&active_path);
+      let state = read_state(&route.state_path);
+      let heartbeat_stale = stale_for_secs(last_heartbeat(&route.state_path)) >= stale_after;
+      let (status, message) = assess_readiness(
+        &watched_hash,
+        current_hash.as_deref(),
+        state.as_ref(),
+        heartbeat_stale,
+        timed_out,
+      );
+
+      if status != last_status {
+        last_status = status;
+        let event = ActiveSetWatchEvent {
+          platform: platform.clone(),
+          instance_id: route.instance_id.clone(),
+          status,
+          expected_hash: watched_hash.clone(),
+          file_hash: current_hash,
+          ea_hash: state
+            .map(|s| s.active_set_hash)
+            .filter(|hash| !hash.trim().is_empty()),
+          message,
+          elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        let name = match status {
+          ActiveSetReadiness::Ready => "active-set-ready",
+          ActiveSetReadiness::Stale => "active-set-stale",
+          ActiveSetReadiness::Mismatch => "active-set-mismatch",
+          ActiveSetReadiness::Waiting => "active-set-waiting",
+        };
+        let _ = app_handle.emit(name, &event);
+      }
+      if status == ActiveSetReadiness::Ready || timed_out {
+        break;
+      }
+      std::thread::sleep(interval);
+    }
+  });
+  Ok(expected_hash)
+}
+
+#[tauri::command]
+pub fn stop_active_set_watchdog() -> Result<(), String> {
+  WATCHDOG_GENERATION.fetch_add(1, Ordering::SeqCst);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn state_with_hash(hash: &str) -> SyncState {
+    serde_json::from_str(&format!(
+      r#"{{
+        "schema_version": 2, "version": "19.0", "timestamp": "2026.03.02 10:00",
+        "symbol": "EURUSD", "magic_number": 777,
+        "global_buy_sell": {{ "allow_buy": true, "allow_sell": true }},
+        "logic_states": [],
+        "account": {{ "balance": 10000.0, "equity": 10000.0, "currency": "USD" }},
+        "active_set_hash": "{}"
+      }}"#,
+      hash
+    ))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_readiness_verdicts() {
+    let exported = sha256_hex(b"gInput_MagicNumber=777\r\n");
+    let file = Some(exported.as_str());
+    let loaded = state_with_hash(&exported.to_uppercase());
+    let old = state_with_hash("abc123");
+    let silent = state_with_hash("");
+
+    let verdict =
+      |file, state, stale, timed_out| assess_readiness(&exported, file, state, stale, timed_out).0;
+    assert_eq!(
+      verdict(file, Some(&loaded), false, false),
+      ActiveSetReadiness::Ready
+    );
+    assert_eq!(
+      verdict(file, Some(&old), false, false),
+      ActiveSetReadiness::Waiting
+    );
+    assert_eq!(
+      verdict(file, Some(&old), false, true),
+      ActiveSetReadiness::Mismatch
+    );
+    assert_eq!(
+      verdict(file, Some(&silent), false, true),
+      ActiveSetReadiness::Stale
+    );
+    assert_eq!(
+      verdict(file, Some(&loaded), true, false),
+      ActiveSetReadiness::Stale
+    );
+    assert_eq!(verdict(file, None, false, false), ActiveSetReadiness::Stale);
+    assert_eq!(
+      verdict(Some("0000"), Some(&loaded), false, false),
+      ActiveSetReadiness::Mismatch
+    );
+    assert_eq!(
+      verdict(None, Some(&loaded), false, false),
+      ActiveSetReadiness::Mismatch
+    );
+  }
+}
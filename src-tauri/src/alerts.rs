@@ -0,0 +1,511 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+use uuid::Uuid;
+
+use crate::account_snapshot::{parse_snapshot, AccountSnapshot};
+use crate::audit_log::{self, AuditEntry};
+use crate::mt_bridge::{find_latest_terminal_log, get_terminal_root_path, read_tail_lines};
+use crate::notifications::{self, Notification, Severity};
+use crate::tactical_bridge::{
+  assign_command_ids, common_files_dir_for_platform, last_heartbeat, sync_route, unix_now,
+  write_commands_file, SyncCommandPayload,
+};
+
+const ALERT_RULES_FILE: &str = "alert_rules.json";
+/// Upcoming events the EA exports from the terminal's economic calendar
+const NEWS_CALENDAR_FILE: &str = "DAAVFX_NewsCalendar.json";
+const DEFAULT_ALERT_INTERVAL_MS: u64 = 5000;
+const TERMINAL_LOG_LINES: usize = 200;
+
+/// Bumped on every start/stop so only the latest engine thread keeps running
+static ALERT_ENGINE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertCondition {
+  /// Equity below balance by more than `percent`
+  Drawdown { percent: f64 },
+  /// A calendar event for a traded currency within `minutes` while
+  /// positions are open; `min_impact` is "low", "medium" or "high"
+  NewsWithOpenPositions {
+    minutes: u32,
+    #[serde(default)]
+    min_impact: Option<String>,
+  },
+  /// No EA heartbeat for `seconds`
+  HeartbeatLost { seconds: u64 },
+  /// A recent terminal log line contains `pattern` (case-insensitive)
+  TerminalLogMatch { pattern: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  pub platform: String,
+  /// EA instance id; None for the single-EA sync files
+  #[serde(default)]
+  pub profile: Option<String>,
+  pub condition: AlertCondition,
+  /// Also tell the EA to stop opening trades when the alert fires
+  #[serde(default)]
+  pub stop_on_trigger: bool,
+  /// Picks the notification channels the alert goes out on
+  #[serde(default = "default_severity")]
+  pub severity: Severity,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+fn default_severity() -> Severity {
+  Severity::Critical
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsEvent {
+  /// Unix seconds, server time as exported by the EA
+  pub time: i64,
+  pub currency: String,
+  #[serde(default)]
+  pub impact: String,
+  #[serde(default)]
+  pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+  pub rule_id: String,
+  pub rule_name: String,
+  pub platform: String,
+  pub profile: Option<String>,
+  pub message: String,
+  pub stop_sent: bool,
+  pub timestamp: i64,
+}
+
+/// Everything a rule can look at in one evaluation round
+#[derive(Debug, Clone, Default)]
+pub struct AlertContext {
+  pub now: i64,
+  pub snapshot: Option<AccountSnapshot>,
+  pub heartbeat: Option<i64>,
+  pub news: Vec<NewsEvent>,
+  pub log_lines: Vec<String>,
+}
+
+fn impact_rank(impact: &str) -> u8 {
+  match impact.trim().to_lowercase().as_str() {
+    "high" => 3,
+    "medium" | "moderate" => 2,
+    "low" => 1,
+    _ => 0,
+  }
+}
+
+/// Why the rule fires, or None when it doesn't
+pub fn evaluate(condition: &AlertCondition, ctx: &AlertContext) -> Option<String> {
+  match condition {
+    AlertCondition::Drawdown { percent } => {
+      let snapshot = ctx.snapshot.as_ref().filter(|s| s.balance > 0.0)?;
+      let drawdown = (snapshot.balance - snapshot.equity) / snapshot.balance * 100.0;
+      (drawdown > *percent).then(|| format!("Drawdown {:.1}% exceeds {}%", drawdown, percent))
+    }
+    AlertCondition::NewsWithOpenPositions {
+      minutes,
+      min_impact,
+    } => {
+      let positions = &ctx.snapshot.as_ref()?.positions;
+      if positions.is_empty() {
+        return None;
+      }
+      let min_rank = min_impact.as_deref().map(impact_rank).unwrap_or(0);
+      let horizon = ctx.now + *minutes as i64 * 60;
+      ctx
+        .news
+        .iter()
+        .filter(|event| event.time >= ctx.now && event.time <= horizon)
+        .filter(|event| impact_rank(&event.impact) >= min_rank)
+        .find(|event| {
+          let currency = event.currency.trim().to_uppercase();
+          !currency.is_empty()
+            && positions
+              .iter()
+              .any(|p| p.symbol.to_uppercase().contains(&currency))
+        })
+        .map(|event| {
+          format!(
+            "{} {} in {} min with {} open position(s)",
+            event.currency,
+            if event.title.is_empty() {
+              "news"
+            } else {
+              event.title.as_str()
+            },
+            (event.time - ctx.now) / 60,
+            positions.len()
+          )
+        })
+    }
+    AlertCondition::HeartbeatLost { seconds } => match ctx.heartbeat {
+      None => Some("No EA sync state found".to_string()),
+      Some(heartbeat) => {
+        let silent = (ctx.now - heartbeat).max(0) as u64;
+        (silent >= *seconds).then(|| format!("No EA heartbeat for {}s", silent))
+      }
+    },
+    AlertCondition::TerminalLogMatch { pattern } => {
+      let needle = pattern.trim().to_lowercase();
+      if needle.is_empty() {
+        return None;
+      }
+      ctx
+        .log_lines
+        .iter()
+        .rev()
+        .find(|line| line.to_lowercase().contains(&needle))
+        .map(|line| format!("Terminal log: {}", line.trim()))
+    }
+  }
+}
+
+fn alert_rules_path() -> Result<PathBuf, String> {
+  Ok(audit_log::app_data_dir()?.join(ALERT_RULES_FILE))
+}
+
+fn load_rules() -> Result<Vec<AlertRule>, String> {
+  let path = alert_rules_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content =
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read alert rules: {}", e))?;
+  serde_json::from_str(&content).map_err(|e| format!("Failed to parse alert rules: {}", e))
+}
+
+fn save_rules(rules: &[AlertRule]) -> Result<(), String> {
+  let path = alert_rules_path()?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+  }
+  let json = serde_json::to_string_pretty(rules)
+    .map_err(|e| format!("Failed to serialize alert rules: {}", e))?;
+  fs::write(&path, json).map_err(|e| format!("Failed to write alert rules: {}", e))
+}
+
+fn validate_rule(rule: &AlertRule) -> Result<(), String> {
+  if rule.name.trim().is_empty() {
+    return Err("Alert rule needs a name".to_string());
+  }
+  match &rule.condition {
+    AlertCondition::Drawdown { percent } if !(0.0..100.0).contains(percent) => Err(format!(
+      "Drawdown threshold must be 0-100%, got {}",
+      percent
+    )),
+    AlertCondition::NewsWithOpenPositions { minutes: 0, .. } => {
+      Err("News window must be at least 1 minute".to_string())
+    }
+    AlertCondition::HeartbeatLost { seconds: 0 } => {
+      Err("Heartbeat timeout must be at least 1 second".to_string())
+    }
+    AlertCondition::TerminalLogMatch { pattern } if pattern.trim().is_empty() => {
+      Err("Log pattern is empty".to_string())
+    }
+    _ => Ok(()),
+  }
+}
+
+#[tauri::command]
+pub fn list_alert_rules() -> Result<Vec<AlertRule>, String> {
+  load_rules()
+}
+
+/// Create (empty id) or replace a rule; returns the stored rule
+#[tauri::command]
+pub fn save_alert_rule(mut rule: AlertRule) -> Result<AlertRule, String> {
+  validate_rule(&rule)?;
+  let mut rules = load_rules()?;
+  if rule.id.trim().is_empty() {
+    rule.id = Uuid::new_v4().to_string();
+  }
+  match rules.iter_mut().find(|r| r.id == rule.id) {
+    Some(existing) => *existing = rule.clone(),
+    None => rules.push(rule.clone()),
+  }
+  save_rules(&rules)?;
+  Ok(rule)
+}
+
+#[tauri::command]
+pub fn delete_alert_rule(id: String) -> Result<(), String> {
+  let mut rules = load_rules()?;
+  let before = rules.len();
+  rules.retain(|r| r.id != id);
+  if rules.len() == before {
+    return Err(format!("Alert rule '{}' not found", id));
+  }
+  save_rules(&rules)
+}
+
+fn read_news(platform: &str) -> Vec<NewsEvent> {
+  common_files_dir_for_platform(platform)
+    .ok()
+    .and_then(|dir| fs::read_to_string(dir.join(NEWS_CALENDAR_FILE)).ok())
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default()
+}
+
+fn read_terminal_log() -> Vec<String> {
+  get_terminal_root_path()
+    .ok()
+    .and_then(|root| find_latest_terminal_log(&root))
+    .and_then(|log| read_tail_lines(&log, TERMINAL_LOG_LINES).ok())
+    .unwrap_or_default()
+}
+
+/// Gather what `rules` need for one platform/profile; the terminal log and
+/// calendar are only read when some rule looks at them
+fn build_context(platform: &str, profile: Option<&str>, rules: &[&AlertRule]) -> AlertContext {
+  let mut ctx = AlertContext {
+    now: unix_now(),
+    ..AlertContext::default()
+  };
+  if let Ok(route) = sync_route(platform, profile) {
+    ctx.heartbeat = last_heartbeat(&route.state_path);
+    ctx.snapshot = fs::read_to_string(&route.account_path)
+      .ok()
+      .and_then(|content| parse_snapshot(&content).ok());
+  }
+  let wants = |f: fn(&AlertCondition) -> bool| rules.iter().any(|r| f(&r.condition));
+  if wants(|c| matches!(c, AlertCondition::NewsWithOpenPositions { .. })) {
+    ctx.news = read_news(platform);
+  }
+  if wants(|c| matches!(c, AlertCondition::TerminalLogMatch { .. })) {
+    ctx.log_lines = read_terminal_log();
+  }
+  ctx
+}
+
+/// Turn off new buys and sells through the same override commands the
+/// tactical view sends
+fn send_stop(rule: &AlertRule, message: &str) -> Result<(), String> {
+  let route = sync_route(&rule.platform, rule.profile.as_deref())?;
+  let command = |command: &str, param: Option<(&str, &str)>| SyncCommandPayload {
+    command: command.to_string(),
+    group: None,
+    logic: None,
+    allow_buy: None,
+    allow_sell: None,
+    param_name: param.map(|(name, _)| name.to_string()),
+    param_value: param.map(|(_, value)| value.to_string()),
+    command_id: None,
+  };
+  let mut commands = vec![
+    command("set_config_value", Some(("gInput_allowBuy", "false"))),
+    command("set_config_value", Some(("gInput_allowSell", "false"))),
+    command("apply_override", None),
+  ];
+  assign_command_ids(&mut commands);
+  let written = write_commands_file(&route.commands_path, &commands);
+  let entry = AuditEntry {
+    timestamp: audit_log::now_timestamp(),
+    action: "alert_stop".to_string(),
+    platform: rule.platform.clone(),
+    instance_id: rule.profile.clone(),
+    scope: "buy and sell disabled".to_string(),
+    command_id: commands.last().and_then(|c| c.command_id.clone()),
+    outcome: if written.is_ok() { "sent" } else { "failed" }.to_string(),
+    detail: format!("{}: {}", rule.name, message),
+  };
+  if let Err(e) = audit_log::record(&entry) {
+    log::error!("{}", e);
+  }
+  written
+}
+
+/// Evaluate the stored rules every `interval_ms` and emit "alert-triggered"
+/// when a rule starts firing (it re-arms once its condition clears). Rule
+/// edits are picked up on the next round. Starting again replaces the
+/// running engine.
+#[tauri::command]
+pub fn start_alert_engine(
+  interval_ms: Option<u64>,
+  app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+  load_rules()?;
+  let interval = Duration::from_millis(interval_ms.unwrap_or(DEFAULT_ALERT_INTERVAL_MS).max(500));
+  let generation = ALERT_ENGINE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+  std::thread::spawn(move || {
+    let mut firing: HashMap<String, bool> = HashMap::new();
+    while ALERT_ENGINE_GENERATION.load(Ordering::SeqCst) == generation {
+      let rules = load_rules().unwrap_or_default();
+      let mut targets: Vec<(String, Option<String>)> = Vec::new();
+      for rule in rules.iter().filter(|r| r.enabled) {
+        let target = (rule.platform.clone(), rule.profile.clone());
+        if !targets.contains(&target) {
+          targets.push(target);
+        }
+      }
+      for (platform, profile) in targets {
+        let group: Vec<&AlertRule> = rules
+          .iter()
+          .filter(|r| r.enabled && r.platform == platform && r.profile == profile)
+          .collect();
+        let ctx = build_context(&platform, profile.as_deref(), &group);
+        for rule in group {
+          let message = evaluate(&rule.condition, &ctx);
+          let was_firing = firing
+            .insert(rule.id.clone(), message.is_some())
+            .unwrap_or(false);
+          let Some(message) = message else { continue };
+          if was_firing {
+            continue;
+          }
+          let stop_sent = rule.stop_on_trigger && send_stop(rule, &message).is_ok();
+          let event = AlertEvent {
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            platform: rule.platform.clone(),
+            profile: rule.profile.clone(),
+            message,
+            stop_sent,
+            timestamp: ctx.now,
+          };
+          let _ = app_handle.emit("alert-triggered", &event);
+          notifications::dispatch_in_background(Notification {
+            severity: rule.severity,
+            title: format!("Alert: {}", event.rule_name),
+            message: if event.stop_sent {
+              format!("{} (EA told to stop trading)", event.message)
+            } else {
+              event.message.clone()
+            },
+            source: "alert".to_string(),
+          });
+        }
+      }
+      std::thread::sleep(interval);
+    }
+  });
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_alert_engine() -> Result<(), String> {
+  ALERT_ENGINE_GENERATION.fetch_add(1, Ordering::SeqCst);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::account_snapshot::SnapshotPosition;
+
+  fn snapshot(balance: f64, equity: f64, symbols: &[&str]) -> AccountSnapshot {
+    AccountSnapshot {
+      schema_version: 1,
+      timestamp: 0,
+      login: None,
+      server: String::new(),
+      currency: "USD".to_string(),
+      balance,
+      equity,
+      margin: 0.0,
+      free_margin: 0.0,
+      leverage: None,
+      positions: symbols
+        .iter()
+        .enumerate()
+        .map(|(i, symbol)| SnapshotPosition {
+          ticket: i as i64 + 1,
+          symbol: symbol.to_string(),
+          side: "buy".to_string(),
+          volume: 0.1,
+          open_price: 1.0,
+          current_price: 1.0,
+          profit: 0.0,
+          swap: 0.0,
+          commission: 0.0,
+          magic: 777,
+          comment: String::new(),
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn test_evaluate_conditions() {
+    let now = 1_772_445_600;
+    let mut ctx = AlertContext {
+      now,
+      snapshot: Some(snapshot(10_000.0, 9_200.0, &["XAUUSD"])),
+      heartbeat: Some(now - 10),
+      news: vec![NewsEvent {
+        time: now + 600,
+        currency: "USD".to_string(),
+        impact: "High".to_string(),
+        title: "Non-Farm Payrolls".to_string(),
+      }],
+      log_lines: vec!["2026.03.02 10:00 DAAVFX: order failed, not enough money".to_string()],
+    };
+
+    let drawdown = |percent| AlertCondition::Drawdown { percent };
+    assert_eq!(
+      evaluate(&drawdown(5.0), &ctx).unwrap(),
+      "Drawdown 8.0% exceeds 5%"
+    );
+    assert!(evaluate(&drawdown(10.0), &ctx).is_none());
+
+    let news = |minutes, impact: &str| AlertCondition::NewsWithOpenPositions {
+      minutes,
+      min_impact: Some(impact.to_string()),
+    };
+    let message = evaluate(&news(15, "high"), &ctx).unwrap();
+    assert!(
+      message.contains("Non-Farm Payrolls in 10 min"),
+      "{}",
+      message
+    );
+    assert!(evaluate(&news(5, "high"), &ctx).is_none());
+
+    let lost = AlertCondition::HeartbeatLost { seconds: 30 };
+    assert!(evaluate(&lost, &ctx).is_none());
+    ctx.heartbeat = Some(now - 45);
+    assert_eq!(evaluate(&lost, &ctx).unwrap(), "No EA heartbeat for 45s");
+
+    let log = AlertCondition::TerminalLogMatch {
+      pattern: "Not Enough Money".to_string(),
+    };
+    assert!(evaluate(&log, &ctx).is_some());
+
+    // No open positions in a news currency: nothing to warn about
+    ctx.snapshot = Some(snapshot(10_000.0, 10_000.0, &["EURGBP"]));
+    assert!(evaluate(&news(15, "high"), &ctx).is_none());
+  }
+
+  #[test]
+  fn test_rule_json_and_validation() {
+    let rule: AlertRule = serde_json::from_str(
+      r#"{"name": "DD guard", "platform": "MT5",
+          "condition": {"kind": "drawdown", "percent": 12.5}, "stop_on_trigger": true}"#,
+    )
+    .unwrap();
+    assert!(rule.enabled && rule.id.is_empty());
+    assert_eq!(rule.condition, AlertCondition::Drawdown { percent: 12.5 });
+    assert!(validate_rule(&rule).is_ok());
+
+    let mut bad = rule.clone();
+    bad.condition = AlertCondition::HeartbeatLost { seconds: 0 };
+    assert!(validate_rule(&bad).is_err());
+  }
+}
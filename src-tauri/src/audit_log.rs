@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+const AUDIT_LOG_FILE: &str = "audit_log.jsonl";
+const DEFAULT_AUDIT_ENTRIES: usize = 200;
+
+/// One action taken against a live terminal, appended as a JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+  pub timestamp: String,
+  pub action: String,
+  pub platform: String,
+  #[serde(default)]
+  pub instance_id: Option<String>,
+  /// Human-readable target, e.g. "engine A, magic 777"
+  #[serde(default)]
+  pub scope: String,
+  #[serde(default)]
+  pub command_id: Option<String>,
+  /// "sent", "rejected", ...
+  pub outcome: String,
+  #[serde(default)]
+  pub detail: String,
+}
+
+/// Per-user app data folder shared by the audit log and alert rules
+pub(crate) fn app_data_dir() -> Result<PathBuf, String> {
+  let base = dirs::data_dir()
+    .or_else(dirs::home_dir)
+    .ok_or_else(|| "Data directory not found".to_string())?;
+  Ok(base.join("DAAVFX"))
+}
+
+fn audit_log_path() -> Result<PathBuf, String> {
+  Ok(app_data_dir()?.join(AUDIT_LOG_FILE))
+}
+
+pub fn now_timestamp() -> String {
+  chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Append an entry; the log is append-only and never rewritten
+pub fn record(entry: &AuditEntry) -> Result<(), String> {
+  let path = audit_log_path()?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create audit log directory: {}", e))?;
+  }
+  let line = serde_json::to_string(entry)
+    .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .map_err(|e| format!("Failed to open audit log: {}", e))?;
+  writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+fn parse_entries(content: &str, limit: usize) -> Vec<AuditEntry> {
+  let mut entries: Vec<AuditEntry> = content
+    .lines()
+    .filter_map(|line| serde_json::from_str(line).ok())
+    .collect();
+  let skip = entries.len().saturating_sub(limit);
+  entries.drain(..skip);
+  entries.reverse();
+  entries
+}
+
+/// Most recent entries first
+#[tauri::command]
+pub fn read_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+  let path = audit_log_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content =
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read audit log: {}", e))?;
+  Ok(parse_entries(&content, limit.unwrap_or(DEFAULT_AUDIT_ENTRIES)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_entries_newest_first() {
+    let entry = |action: &str| AuditEntry {
+      timestamp: now_timestamp(),
+      action: action.to_string(),
+      platform: "MT4".to_string(),
+      instance_id: None,
+      scope: "all".to_string(),
+      command_id: None,
+      outcome: "sent".to_string(),
+      detail: String::new(),
+    };
+    let content = ["first", "second", "third"]
+      .iter()
+      .map(|a| serde_json::to_string(&entry(a)).unwrap())
+      .collect::<Vec<_>>()
+      .join("\n")
+      + "\nnot json\n";
+    let entries = parse_entries(&content, 2);
+    let actions: Vec<&str> = entries.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, vec!["third", "second"]);
+  }
+}
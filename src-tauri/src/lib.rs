@@ -1,5 +1,23 @@
 mod mt_bridge;
+// Command-only modules; mt_bridge keeps its config and setfile code buildable without Tauri
+#[cfg(feature = "tauri-app")]
+mod audit_log;
+#[cfg(feature = "tauri-app")]
 mod tactical_bridge;
+#[cfg(feature = "tauri-app")]
+mod active_set_watchdog;
+#[cfg(feature = "tauri-app")]
+mod account_snapshot;
+#[cfg(feature = "tauri-app")]
+mod alerts;
+#[cfg(feature = "tauri-app")]
+mod rotation;
+#[cfg(feature = "tauri-app")]
+mod notifications;
+#[cfg(feature = "tauri-app")]
+mod safe_mode;
+#[cfg(feature = "tauri-app")]
+mod remote_auth;
 pub mod mql_rust_compiler;
 mod mql_compiler;
 pub mod headless;
@@ -26,7 +44,7 @@ pub fn run() {
       }
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![
+    .invoke_handler(remote_auth::guard_invoke(tauri::generate_handler![
       mt_bridge::load_mt_config,
       mt_bridge::save_mt_config,
       mt_bridge::set_mt_path,
@@ -62,7 +80,39 @@ pub fn run() {
       tactical_bridge::get_sync_paths,
       tactical_bridge::read_sync_state,
       tactical_bridge::write_sync_commands,
-    ])
+      tactical_bridge::broadcast_sync_commands,
+      tactical_bridge::list_ea_instances,
+      tactical_bridge::start_sync_poller,
+      tactical_bridge::stop_sync_poller,
+      tactical_bridge::push_config_to_ea,
+      tactical_bridge::request_kill_switch_token,
+      tactical_bridge::close_all_positions,
+      active_set_watchdog::start_active_set_watchdog,
+      active_set_watchdog::stop_active_set_watchdog,
+      audit_log::read_audit_log,
+      account_snapshot::get_account_snapshot,
+      alerts::list_alert_rules,
+      alerts::save_alert_rule,
+      alerts::delete_alert_rule,
+      alerts::start_alert_engine,
+      alerts::stop_alert_engine,
+      rotation::list_rotation_schedule,
+      rotation::save_rotation_entry,
+      rotation::delete_rotation_entry,
+      rotation::get_next_rotation,
+      rotation::start_rotation_scheduler,
+      rotation::stop_rotation_scheduler,
+      notifications::list_notification_channels,
+      notifications::save_notification_channel,
+      notifications::delete_notification_channel,
+      notifications::test_notification_channel,
+      notifications::send_notification,
+      remote_auth::list_api_tokens,
+      remote_auth::create_api_token,
+      remote_auth::revoke_api_token,
+      safe_mode::get_safe_mode,
+      safe_mode::set_safe_mode,
+    ]))
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
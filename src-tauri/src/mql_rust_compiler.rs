@@ -5,8 +5,9 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use notify::{Watcher, RecursiveMode, Event};
@@ -97,6 +98,61 @@ pub struct PrecompilationResult {
     pub recommendations: Vec<String>,
 }
 
+/// Watch mode options - by default a save only re-validates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchOptions {
+    /// Run the full pre-compilation pipeline on save instead of validation only
+    pub auto_pipeline: bool,
+    /// If set, also compile with MetaEditor (metaeditor.exe / metaeditor64.exe) after the pipeline
+    pub metaeditor_path: Option<String>,
+    pub debounce_ms: u64,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            auto_pipeline: false,
+            metaeditor_path: None,
+            debounce_ms: 500,
+        }
+    }
+}
+
+/// Emitted for each pipeline stage so the UI can drive a live build status bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStageEvent {
+    pub run_id: u64,
+    /// "validation" | "dependencies" | "performance" | "fixes" | "metaeditor" | "pipeline"
+    pub stage: String,
+    /// "started" | "finished" | "failed" | "skipped"
+    pub status: String,
+    pub message: Option<String>,
+    pub timestamp: u64,
+    /// Only set on the final "pipeline" event of a successful run
+    pub result: Option<PrecompilationResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaEditorCompileResult {
+    pub file: String,
+    pub success: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub log: Vec<String>,
+}
+
+static PIPELINE_RUN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Clears the running flag however the pipeline exits
+struct PipelineRunGuard(Arc<AtomicBool>);
+
+impl Drop for PipelineRunGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
 #[derive(Debug)]
 pub struct MQLRustCompiler {
     pub project: MQLProject,
@@ -106,6 +162,7 @@ pub struct MQLRustCompiler {
     file_watchers: HashMap<String, Arc<Mutex<Option<notify::RecommendedWatcher>>>>,
     last_validation: Arc<Mutex<Option<SystemTime>>>,
     validation_cache: Arc<Mutex<HashMap<String, Vec<CompilationError>>>>,
+    pipeline_running: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Clone)]
@@ -126,6 +183,7 @@ impl MQLRustCompiler {
             file_watchers: HashMap::new(),
             last_validation: Arc::new(Mutex::new(None)),
             validation_cache: Arc::new(Mutex::new(HashMap::new())),
+            pipeline_running: Arc::new(AtomicBool::new(false)),
         };
         
         compiler.initialize_error_patterns();
@@ -173,6 +231,7 @@ impl MQLRustCompiler {
             file_watchers: HashMap::new(),
             last_validation: Arc::new(Mutex::new(None)),
             validation_cache: Arc::new(Mutex::new(HashMap::new())),
+            pipeline_running: Arc::new(AtomicBool::new(false)),
         };
         
         compiler.initialize_error_patterns();
@@ -558,6 +617,22 @@ impl MQLRustCompiler {
     pub fn start_file_watching<F>(&mut self, callback: F) -> Result<(), Box<dyn std::error::Error>>
     where
         F: Fn(Vec<CompilationError>) + Send + 'static + Clone,
+    {
+        self.start_file_watching_with_options(WatchOptions::default(), callback, |_| {})
+    }
+
+    /// Start file watching; with `auto_pipeline` each (debounced) save runs the full pipeline
+    /// and reports every stage through `on_stage`. Saves that land while a run is in progress
+    /// are coalesced into a single follow-up run.
+    pub fn start_file_watching_with_options<F, G>(
+        &mut self,
+        options: WatchOptions,
+        callback: F,
+        on_stage: G,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Fn(Vec<CompilationError>) + Send + 'static + Clone,
+        G: Fn(PipelineStageEvent) + Send + 'static + Clone,
     {
         for main_file in &self.project.main_files.clone() {
             let file_path = main_file.to_string_lossy().to_string();
@@ -567,6 +642,8 @@ impl MQLRustCompiler {
             }
 
             let callback_clone = callback.clone();
+            let on_stage_clone = on_stage.clone();
+            let options_clone = options.clone();
             let compiler_clone = self.clone_for_watching();
             
             let (tx, rx) = std::sync::mpsc::channel();
@@ -584,12 +661,81 @@ impl MQLRustCompiler {
 
             // Spawn validation thread
             std::thread::spawn(move || {
+                let debounce = Duration::from_millis(options_clone.debounce_ms.max(50));
                 while rx.recv().is_ok() {
-                    std::thread::sleep(std::time::Duration::from_millis(500)); // Debounce
-                    
-                    if let Ok(errors) = compiler_clone.lock().unwrap().validate_with_cache(true) {
-                        callback_clone(errors);
+                    // Debounce: wait until saves stop arriving for a full window
+                    loop {
+                        match rx.recv_timeout(debounce) {
+                            Ok(()) => continue,
+                            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => break,
+                            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+
+                    let mut compiler = compiler_clone.lock().unwrap();
+                    if !options_clone.auto_pipeline {
+                        if let Ok(errors) = compiler.validate_with_cache(true) {
+                            callback_clone(errors);
+                        }
+                        continue;
                     }
+
+                    // The file just changed - never serve the 30s validation cache here
+                    compiler.invalidate_validation_cache();
+                    let run_id = PIPELINE_RUN_ID.fetch_add(1, Ordering::SeqCst);
+                    let emit = |stage: &str, status: &str, message: Option<String>| {
+                        on_stage_clone(PipelineStageEvent {
+                            run_id,
+                            stage: stage.to_string(),
+                            status: status.to_string(),
+                            message,
+                            timestamp: unix_millis(),
+                            result: None,
+                        });
+                    };
+
+                    let outcome = compiler.run_precompilation_pipeline_with_stages(&mut |stage, status| {
+                        emit(stage, status, None)
+                    });
+                    let result = match outcome {
+                        Ok(result) => result,
+                        Err(e) if compiler.is_pipeline_running() => {
+                            // Another run (e.g. a manual one) owns the pipeline - the next save retries
+                            emit("pipeline", "skipped", Some(e.to_string()));
+                            continue;
+                        }
+                        Err(e) => {
+                            emit("pipeline", "failed", Some(e.to_string()));
+                            continue;
+                        }
+                    };
+                    callback_clone(result.validation_report.errors.clone());
+
+                    if let Some(ref metaeditor) = options_clone.metaeditor_path {
+                        emit("metaeditor", "started", None);
+                        for file in &compiler.project.main_files {
+                            match compile_with_metaeditor(metaeditor, file) {
+                                Ok(compiled) => emit(
+                                    "metaeditor",
+                                    if compiled.success { "finished" } else { "failed" },
+                                    Some(format!(
+                                        "{}: {} errors, {} warnings",
+                                        compiled.file, compiled.errors, compiled.warnings
+                                    )),
+                                ),
+                                Err(e) => emit("metaeditor", "failed", Some(e)),
+                            }
+                        }
+                    }
+
+                    on_stage_clone(PipelineStageEvent {
+                        run_id,
+                        stage: "pipeline".to_string(),
+                        status: "finished".to_string(),
+                        message: None,
+                        timestamp: unix_millis(),
+                        result: Some(result),
+                    });
                 }
             });
         }
@@ -597,6 +743,18 @@ impl MQLRustCompiler {
         Ok(())
     }
 
+    pub fn invalidate_validation_cache(&self) {
+        *self.last_validation.lock().unwrap() = None;
+    }
+
+    pub fn is_watching(&self) -> bool {
+        !self.file_watchers.is_empty()
+    }
+
+    pub fn is_pipeline_running(&self) -> bool {
+        self.pipeline_running.load(Ordering::SeqCst)
+    }
+
     /// Clone for file watching (simplified version)
     fn clone_for_watching(&self) -> Arc<Mutex<Self>> {
         let clone = Self {
@@ -607,6 +765,7 @@ impl MQLRustCompiler {
             file_watchers: HashMap::new(),
             last_validation: Arc::new(Mutex::new(None)),
             validation_cache: Arc::new(Mutex::new(HashMap::new())),
+            pipeline_running: self.pipeline_running.clone(),
         };
         Arc::new(Mutex::new(clone))
     }
@@ -687,23 +846,46 @@ impl MQLRustCompiler {
 
     /// Pre-compilation validation pipeline
     pub fn run_precompilation_pipeline(&mut self) -> Result<PrecompilationResult, Box<dyn std::error::Error>> {
+        self.run_precompilation_pipeline_with_stages(&mut |_, _| {})
+    }
+
+    /// Pre-compilation pipeline reporting (stage, status) as each phase starts and finishes.
+    /// Only one pipeline runs at a time across this compiler and its watcher clones.
+    pub fn run_precompilation_pipeline_with_stages(
+        &mut self,
+        on_stage: &mut dyn FnMut(&str, &str),
+    ) -> Result<PrecompilationResult, Box<dyn std::error::Error>> {
+        if self.pipeline_running.swap(true, Ordering::SeqCst) {
+            return Err("Pre-compilation pipeline already running".into());
+        }
+        let _guard = PipelineRunGuard(self.pipeline_running.clone());
+
         println!("🦀 Running MQL Pre-compilation Pipeline");
+        on_stage("pipeline", "started");
         
         // Phase 1: Syntax and structure validation
         println!("📊 Phase 1: Syntax validation...");
+        on_stage("validation", "started");
         let validation_report = self.analyze_with_context()?;
+        on_stage("validation", "finished");
         
         // Phase 2: Dependency analysis
         println!("🔗 Phase 2: Dependency analysis...");
+        on_stage("dependencies", "started");
         let dependency_issues = self.analyze_dependencies_advanced()?;
+        on_stage("dependencies", "finished");
         
         // Phase 3: Performance analysis
         println!("⚡ Phase 3: Performance analysis...");
+        on_stage("performance", "started");
         let performance_warnings = self.analyze_performance_patterns()?;
+        on_stage("performance", "finished");
         
         // Phase 4: Generate fixes
         println!("🔧 Phase 4: Generating fixes...");
+        on_stage("fixes", "started");
         let auto_fixes = self.generate_fixes(&validation_report.errors)?;
+        on_stage("fixes", "finished");
         
         let result = PrecompilationResult {
             validation_report,
@@ -789,4 +971,55 @@ impl MQLRustCompiler {
             "Test with different broker environments".to_string(),
         ]
     }
-}
\ No newline at end of file
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Compile a single .mq4/.mq5 with MetaEditor's command line and parse its log.
+/// MetaEditor's exit code is unreliable, so success is taken from the "Result:" line.
+pub fn compile_with_metaeditor(metaeditor: &str, file: &Path) -> Result<MetaEditorCompileResult, String> {
+    let log_path = file.with_extension("compile.log");
+    std::process::Command::new(metaeditor)
+        .arg(format!("/compile:{}", file.display()))
+        .arg(format!("/log:{}", log_path.display()))
+        .status()
+        .map_err(|e| format!("Failed to launch MetaEditor: {}", e))?;
+
+    let bytes = fs::read(&log_path).map_err(|e| format!("MetaEditor log not found: {}", e))?;
+    let log_text = if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(&bytes).to_string()
+    };
+    let _ = fs::remove_file(&log_path);
+
+    let log: Vec<String> = log_text
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let result_regex = Regex::new(r"(\d+)\s+errors?,\s*(\d+)\s+warnings?").unwrap();
+    let (errors, warnings) = log
+        .iter()
+        .rev()
+        .find_map(|line| result_regex.captures(line))
+        .map(|caps| (caps[1].parse().unwrap_or(0), caps[2].parse().unwrap_or(0)))
+        .ok_or_else(|| "MetaEditor log has no result line".to_string())?;
+
+    Ok(MetaEditorCompileResult {
+        file: file.to_string_lossy().to_string(),
+        success: errors == 0,
+        errors,
+        warnings,
+        log,
+    })
+}
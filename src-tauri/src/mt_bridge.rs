@@ -6,11 +6,23 @@ use std::fs;
 use std::path::PathBuf;
 use std::io::{Read, Seek, SeekFrom};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(feature = "tauri-app")]
 use tauri::{Emitter, State};
 use notify::{Watcher, RecursiveMode, Event};
+use daavfx_core::{debounce, safe_mode};
+pub use daavfx_core::config::{
+    GeneralConfig, NewsFilterConfig, RiskManagementConfig, SessionConfig, TimeFiltersConfig,
+    TimePrioritySettings,
+};
+use daavfx_core::fs::{atomic_write, decode_setfile_bytes};
+use daavfx_core::setfile_keys::{
+    default_close_targets, get_logic_code, get_logic_global_key, get_logic_short,
+    get_logic_start_key, get_logic_suffix, trigger_action_to_int,
+};
 
 // Import the MQL Rust Compiler
-use crate::mql_rust_compiler::{MQLRustCompiler, ValidationReport, PrecompilationResult, CompilationError};
+use crate::mql_rust_compiler::{MQLRustCompiler, ValidationReport, PrecompilationResult, CompilationError, PipelineStageEvent, WatchOptions};
 
 // Path validation and sanitization utilities
 fn sanitize_and_validate_path(path: &PathBuf) -> Result<PathBuf, String> {
@@ -103,262 +115,14 @@ fn validate_path_within_base(path: &PathBuf, base_dir: &PathBuf) -> Result<PathB
     }
 }
 
-// Atomic write helper to prevent file corruption
-fn atomic_write(path: &PathBuf, content: &str) -> Result<(), String> {
-    // Create a temporary file in the same directory
-    let tmp_extension = format!("{}.tmp", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
-    let tmp_path = if let Some(ext) = path.extension() {
-        path.with_extension(format!("{}.{}", ext.to_string_lossy(), tmp_extension))
-    } else {
-        path.with_extension(tmp_extension)
-    };
-
-    // Write to the temporary file
-    fs::write(&tmp_path, content)
-        .map_err(|e| format!("Failed to write temporary file: {}", e))?;
-
-    // Rename temporary file to target file (atomic operation)
-    fs::rename(&tmp_path, path)
-        .map_err(|e| {
-            // Cleanup temp file if rename fails
-            let _ = fs::remove_file(&tmp_path);
-            format!("Failed to commit file (rename failed): {}", e)
-        })?;
-
-    Ok(())
-}
-
 // ============================================
-// ENCRYPTION / OBFUSCATION UTILITIES
+// CONFIG MODEL
 // ============================================
 
-const OBFUSCATION_KEY: &str = "DAAVFX_SECURE_STORAGE_KEY_2024";
-
-fn obfuscate_string(input: &str) -> String {
-    if input.is_empty() { return String::new(); }
-    // Check if already obfuscated to prevent double encryption
-    if input.starts_with("ENC:") { return input.to_string(); }
-    
-    // Simple XOR + Hex
-    let mut output = String::from("ENC:");
-    let key_bytes = OBFUSCATION_KEY.as_bytes();
-    for (i, b) in input.bytes().enumerate() {
-        let key_byte = key_bytes[i % key_bytes.len()];
-        let xored = b ^ key_byte;
-        output.push_str(&format!("{:02x}", xored));
-    }
-    output
-}
-
-fn deobfuscate_string(input: &str) -> String {
-    if !input.starts_with("ENC:") {
-        return input.to_string();
-    }
-    let hex_part = &input[4..];
-    let key_bytes = OBFUSCATION_KEY.as_bytes();
-    let mut decoded = Vec::new();
-    
-    // Parse hex
-    let mut chars = hex_part.chars();
-    let mut idx = 0;
-    while let (Some(h1), Some(h2)) = (chars.next(), chars.next()) {
-        if let Ok(byte) = u8::from_str_radix(&format!("{}{}", h1, h2), 16) {
-             let key_byte = key_bytes[idx % key_bytes.len()];
-             decoded.push(byte ^ key_byte);
-             idx += 1;
-        } else {
-             return input.to_string(); // Fail safe
-        }
-    }
-    
-    String::from_utf8(decoded).unwrap_or_else(|_| input.to_string())
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct MTConfig {
-    pub version: String,
-    pub platform: String, // "MT4" or "MT5"
-    pub timestamp: String,
-    pub total_inputs: usize,
-    #[serde(default)]
-    pub last_saved_at: Option<String>,
-    #[serde(default)]
-    pub last_saved_platform: Option<String>,
-    #[serde(default)]
-    pub current_set_name: Option<String>,
-    #[serde(default)]
-    pub tags: Option<Vec<String>>,
-    #[serde(default)]
-    pub comments: Option<String>,
-    pub general: GeneralConfig,
-    pub engines: Vec<EngineConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct GeneralConfig {
-    // License
-    pub license_key: String,
-    pub license_server_url: String,
-    pub require_license: bool,
-    pub license_check_interval: i32,
-    
-    // Config
-    pub config_file_name: String,
-    pub config_file_is_common: bool,
-    
-    // Trading (GLOBAL)
-    pub allow_buy: bool,
-    pub allow_sell: bool,
-    
-    // Logging
-    pub enable_logs: bool,
-
-    #[serde(default)]
-    pub use_direct_price_grid: bool,
-
-    #[serde(default)]
-    pub group_mode: Option<i32>,
-    #[serde(default)]
-    pub grid_unit: Option<i32>,
-    #[serde(default)]
-    pub pip_factor: Option<i32>,
-    
-    // Compounding
-    pub compounding_enabled: bool,
-    pub compounding_type: String,
-    pub compounding_target: f64,
-    pub compounding_increase: f64,
-    
-    // Restart Policy
-    pub restart_policy_power: String,
-    pub restart_policy_non_power: String,
-    pub close_non_power_on_power_close: bool,
-    pub hold_timeout_bars: i32,
-    
-    // Global System
-    pub magic_number: i32,
-    pub magic_number_buy: i32,
-    pub magic_number_sell: i32,
-    pub max_slippage_points: f64,
-    
-    // Risk Management
-    pub risk_management: RiskManagementConfig,
-    
-    // Time Filters
-    pub time_filters: TimeFiltersConfig,
-    
-    // News Filter
-    pub news_filter: NewsFilterConfig,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct RiskManagementConfig {
-    pub spread_filter_enabled: bool,
-    pub max_spread_points: f64,
-    pub equity_stop_enabled: bool,
-    pub equity_stop_value: f64,
-    pub drawdown_stop_enabled: bool,
-    pub max_drawdown_percent: f64,
-    #[serde(default)]
-    pub risk_action: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct TimeFiltersConfig {
-    #[serde(default)]
-    pub priority_settings: TimePrioritySettings,
-    pub sessions: Vec<SessionConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct TimePrioritySettings {
-    pub news_filter_overrides_session: bool,
-    pub session_filter_overrides_news: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct SessionConfig {
-    #[serde(default)]
-    pub session_number: i32,
-    pub enabled: bool,
-    pub day: i32,
-    pub start_hour: i32,
-    pub start_minute: i32,
-    pub end_hour: i32,
-    pub end_minute: i32,
-    #[serde(default)]
-    pub action: String,
-    #[serde(default)]
-    pub auto_restart: bool,
-    #[serde(default)]
-    pub restart_mode: String,
-    #[serde(default)]
-    pub restart_bars: i32,
-    #[serde(default)]
-    pub restart_minutes: i32,
-    #[serde(default)]
-    pub restart_pips: i32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct NewsFilterConfig {
-    pub enabled: bool,
-    pub api_key: String,
-    pub api_url: String,
-    pub countries: String,
-    pub impact_level: i32,
-    pub minutes_before: i32,
-    pub minutes_after: i32,
-    pub action: String,
-    #[serde(default)]
-    pub calendar_file: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EngineConfig {
-    pub engine_id: String, // "A", "B", "C"
-    pub engine_name: String,
-    pub max_power_orders: i32,
-    pub groups: Vec<GroupConfig>,
-}
-
-impl MTConfig {
-    pub fn obfuscate_sensitive_fields(&mut self) {
-        self.general.license_key = obfuscate_string(&self.general.license_key);
-        self.general.license_server_url = obfuscate_string(&self.general.license_server_url);
-        self.general.news_filter.api_key = obfuscate_string(&self.general.news_filter.api_key);
-        self.general.news_filter.api_url = obfuscate_string(&self.general.news_filter.api_url);
-    }
-    
-    pub fn deobfuscate_sensitive_fields(&mut self) {
-        self.general.license_key = deobfuscate_string(&self.general.license_key);
-        self.general.license_server_url = deobfuscate_string(&self.general.license_server_url);
-        self.general.news_filter.api_key = deobfuscate_string(&self.general.news_filter.api_key);
-        self.general.news_filter.api_url = deobfuscate_string(&self.general.news_filter.api_url);
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GroupConfig {
-    pub group_number: u8, // 1-20 (all groups supported)
-    pub enabled: bool,
-    
-    // ===== GROUP TRIGGER (Groups 2-20 only) =====
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub group_power_start: Option<i32>,       // gInput_GroupPowerStart_P{N} - # of Power A trades to trigger this group
-    
-    // ===== GROUP-LEVEL REVERSE/HEDGE CONTROLS (V17.04+) =====
-    #[serde(default)]
-    pub reverse_mode: bool,                   // gInput_Group{N}_ReverseMode
-    #[serde(default)]
-    pub hedge_mode: bool,                     // gInput_Group{N}_HedgeMode
-    #[serde(default = "default_logic_none")]
-    pub hedge_reference: String,              // gInput_Group{N}_HedgeReference
-    #[serde(default)]
-    pub entry_delay_bars: i32,                // gInput_Group{N}_EntryDelayBars
-    
-    pub logics: Vec<LogicConfig>,
-}
+/// The shared config model over this app's logic rows
+pub type MTConfig = daavfx_core::config::MTConfig<LogicConfig>;
+pub type EngineConfig = daavfx_core::config::EngineConfig<LogicConfig>;
+pub type GroupConfig = daavfx_core::config::GroupConfig<LogicConfig>;
 
 fn default_true() -> bool { true }
 fn default_logic_none() -> String { "Logic_None".to_string() }
@@ -574,6 +338,7 @@ impl MTBridgeState {
 
 // Tauri Commands
 
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn load_mt_config(
     platform: String,
@@ -605,6 +370,7 @@ pub async fn load_mt_config(
     Ok(config)
 }
 
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn save_mt_config(
     platform: String,
@@ -636,6 +402,7 @@ pub async fn save_mt_config(
     Ok(())
 }
 
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn set_mt_path(
     platform: String,
@@ -664,6 +431,7 @@ pub async fn set_mt_path(
     Ok(())
 }
 
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn start_file_watcher(
     platform: String,
@@ -705,7 +473,7 @@ pub async fn start_file_watcher(
     Ok(())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn get_default_mt4_path() -> Result<String, String> {
     // First check if MT4 terminal exists
     let terminal_paths = vec![
@@ -738,7 +506,7 @@ pub async fn get_default_mt4_path() -> Result<String, String> {
     Err("MT4 not found. Please set path manually in Settings.".to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn get_default_mt5_path() -> Result<String, String> {
     // First check if MT5 terminal exists
     let terminal_paths = vec![
@@ -862,7 +630,7 @@ fn get_optimization_values(field: &str, value: f64) -> (i32, f64, f64, f64) {
 
 
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn export_set_file(
     config: MTConfig,
     file_path: String,
@@ -947,7 +715,7 @@ pub fn export_set_file(
     lines.push(format!("gInput_UseDrawdownStop={}", if config.general.risk_management.drawdown_stop_enabled { 1 } else { 0 }));
     lines.push(format!("gInput_MaxDrawdownPercent={:.1}", config.general.risk_management.max_drawdown_percent));
     if let Some(risk_action) = config.general.risk_management.risk_action.as_deref() {
-        lines.push(format!("gInput_RiskAction={}", trigger_action_to_int(risk_action)));
+        lines.push(format!("gInput_RiskAction={}", trigger_action_code(risk_action)));
     }
     lines.push(String::new());
 
@@ -967,7 +735,7 @@ pub fn export_set_file(
     lines.push(format!("gInput_NewsImpactLevel={}", config.general.news_filter.impact_level));
     lines.push(format!("gInput_MinutesBeforeNews={}", config.general.news_filter.minutes_before));
     lines.push(format!("gInput_MinutesAfterNews={}", config.general.news_filter.minutes_after));
-    lines.push(format!("gInput_NewsAction={}", trigger_action_to_int(&config.general.news_filter.action)));
+    lines.push(format!("gInput_NewsAction={}", trigger_action_code(&config.general.news_filter.action)));
     if let Some(cf) = config.general.news_filter.calendar_file.as_deref() {
         lines.push(format!("gInput_NewsCalendarFile={}", cf));
     }
@@ -991,7 +759,7 @@ pub fn export_set_file(
         lines.push(format!("gInput_Session{}StartMinute={}", session_num, session.start_minute));
         lines.push(format!("gInput_Session{}EndHour={}", session_num, session.end_hour));
         lines.push(format!("gInput_Session{}EndMinute={}", session_num, session.end_minute));
-        lines.push(format!("gInput_Session{}Action={}", session_num, trigger_action_to_int(&session.action)));
+        lines.push(format!("gInput_Session{}Action={}", session_num, trigger_action_code(&session.action)));
     }
     lines.push(String::new());
     
@@ -1313,12 +1081,13 @@ pub fn export_set_file(
     Ok(())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn export_set_file_to_mt_common_files(
     config: MTConfig,
     platform: String,
     include_optimization_hints: bool,
 ) -> Result<String, String> {
+    safe_mode::ensure_writable("Exporting to Common Files")?;
     let common_dir = if let Some(home) = dirs::home_dir() {
         home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files")
     } else {
@@ -1341,7 +1110,7 @@ pub struct ActiveSetStatus {
     pub last_modified_ms: Option<u64>,
 }
 
-fn get_mt_common_files_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_mt_common_files_dir() -> Result<PathBuf, String> {
     if let Some(home) = dirs::home_dir() {
         Ok(home.join("AppData\\Roaming\\MetaQuotes\\Terminal\\Common\\Files"))
     } else {
@@ -1397,34 +1166,57 @@ fn _get_mt5_common_files_dir() -> Result<PathBuf, String> {
     }
 }
 
-fn decode_setfile_bytes(bytes: Vec<u8>) -> Result<String, String> {
-    if bytes.len() >= 2 && bytes[0] == 0xFF && bytes[1] == 0xFE {
-        let u16_vec: Vec<u16> = bytes[2..]
-            .chunks_exact(2)
-            .map(|c| u16::from_le_bytes([c[0], c[1]]))
-            .collect();
-        String::from_utf16(&u16_vec)
-            .map_err(|e| format!("Failed to parse UTF-16 .set file: {}", e))
-    } else {
-        String::from_utf8(bytes)
-            .map_err(|e| format!("Failed to parse .set file (not UTF-8 or UTF-16 LE): {}", e))
-    }
-}
+// ACTIVE.set.bak1..bak5 are kept next to ACTIVE.set, bak1 newest
+const ACTIVE_SET_BACKUPS: usize = 5;
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn export_active_set_file_to_mt_common_files(
     config: MTConfig,
     platform: String,
     include_optimization_hints: bool,
 ) -> Result<String, String> {
+    safe_mode::ensure_writable("Exporting the active set file")?;
     let common_dir = get_mt_common_files_dir()?;
+    let window = Duration::from_millis(debounce::DEFAULT_WINDOW_MS);
+    submit_active_set_export(common_dir, window, config, platform, include_optimization_hints)
+}
+
+fn submit_active_set_export(
+    common_dir: PathBuf,
+    window: Duration,
+    config: MTConfig,
+    platform: String,
+    include_optimization_hints: bool,
+) -> Result<String, String> {
+    let path_str = common_dir.join("ACTIVE.set").to_string_lossy().to_string();
+    // Slider drags send an export per tick; collapse them so the EA reloads once per window
+    let submitted = debounce::submit(
+        &path_str,
+        window,
+        move || write_active_set_file(&common_dir, config, platform, include_optimization_hints),
+        |file_path, err| log::warn!("Deferred export of {} failed: {}", file_path, err),
+    );
+    match submitted {
+        debounce::Submitted::Written(result) => result,
+        debounce::Submitted::Coalesced => Ok(path_str),
+    }
+}
+
+fn write_active_set_file(
+    common_dir: &PathBuf,
+    config: MTConfig,
+    platform: String,
+    include_optimization_hints: bool,
+) -> Result<String, String> {
     let file_path = common_dir.join("ACTIVE.set");
     let path_str = file_path.to_string_lossy().to_string();
+    // The running EA reads ACTIVE.set; keep what it was running on
+    daavfx_core::fs::rotate_backups(&file_path, ACTIVE_SET_BACKUPS)?;
     export_set_file(config, path_str.clone(), platform, include_optimization_hints, None, None, None)?;
     Ok(path_str)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn _export_vault_file_to_mt_common_files(
     source_file_path: String,
     terminal_type: String,
@@ -1492,7 +1284,7 @@ pub fn _export_vault_file_to_mt_common_files(
     Ok(target_path.to_string_lossy().to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn get_active_set_status() -> Result<ActiveSetStatus, String> {
     let common_dir = get_mt_common_files_dir()?;
     let file_path = common_dir.join("ACTIVE.set");
@@ -1552,7 +1344,7 @@ pub fn get_active_set_status() -> Result<ActiveSetStatus, String> {
 }
 
 /// Import config from MT4/MT5 .set file format
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn import_set_file(
     file_path: String,
 ) -> Result<MTConfig, String> {
@@ -1667,7 +1459,7 @@ pub struct VaultJson {
 }
 
 /// Export config to JSON format (proper MT4/MT5 compatible)
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn export_json_file(
     config: MTConfig,
     file_path: String,
@@ -1696,7 +1488,7 @@ pub async fn export_json_file(
 }
 
 /// Import config from JSON format
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn import_json_file(
     file_path: String,
 ) -> Result<MTConfig, String> {
@@ -1725,7 +1517,7 @@ pub async fn import_json_file(
 }
 
 /// Write text content to a file (for exporting generated setfile content)
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn write_text_file(
     file_path: String,
     content: String,
@@ -1754,7 +1546,7 @@ pub struct _ExportValidationResult {
 }
 
 /// Export .set file with validation feedback
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn _export_set_file_with_validation(
     config: MTConfig,
     file_path: String,
@@ -1809,7 +1601,7 @@ pub fn _export_set_file_with_validation(
 }
 
 /// Quick validation of a setfile path (check it exists and is readable)
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub fn _validate_set_file_path(file_path: String) -> Result<bool, String> {
     let path = std::path::Path::new(&file_path);
     if path.exists() && path.is_file() {
@@ -1910,7 +1702,7 @@ fn resolve_vault_path(vault_path_override: Option<String>) -> Result<PathBuf, St
     Ok(get_vault_path())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn list_vault_files(vault_path_override: Option<String>) -> Result<VaultListing, String> {
     let vault_path = resolve_vault_path(vault_path_override)?;
     if !vault_path.exists() {
@@ -2019,7 +1811,7 @@ pub async fn list_vault_files(vault_path_override: Option<String>) -> Result<Vau
     })
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn open_vault_folder(vault_path_override: Option<String>) -> Result<(), String> {
     let vault_path = resolve_vault_path(vault_path_override)?;
     if !vault_path.exists() {
@@ -2060,7 +1852,7 @@ pub struct VaultSizeResult {
     pub total_size: u64,
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn get_vault_size(vault_path_override: Option<String>) -> Result<VaultSizeResult, String> {
     let vault_path = resolve_vault_path(vault_path_override)?;
     if !vault_path.exists() {
@@ -2073,12 +1865,12 @@ pub async fn get_vault_size(vault_path_override: Option<String>) -> Result<Vault
     Ok(VaultSizeResult { total_size })
 }
 
-fn get_terminal_root_path() -> Result<PathBuf, String> {
+pub(crate) fn get_terminal_root_path() -> Result<PathBuf, String> {
     let appdata = std::env::var("APPDATA").map_err(|e| format!("APPDATA not available: {}", e))?;
     Ok(PathBuf::from(appdata).join("MetaQuotes").join("Terminal"))
 }
 
-fn find_latest_terminal_log(root: &PathBuf) -> Option<PathBuf> {
+pub(crate) fn find_latest_terminal_log(root: &PathBuf) -> Option<PathBuf> {
     let mut best: Option<(std::time::SystemTime, PathBuf)> = None;
     let entries = fs::read_dir(root).ok()?;
     for entry in entries.flatten() {
@@ -2111,7 +1903,7 @@ fn find_latest_terminal_log(root: &PathBuf) -> Option<PathBuf> {
     best.map(|(_, p)| p)
 }
 
-fn read_tail_lines(path: &PathBuf, max_lines: usize) -> Result<Vec<String>, String> {
+pub(crate) fn read_tail_lines(path: &PathBuf, max_lines: usize) -> Result<Vec<String>, String> {
     let mut f = fs::File::open(path).map_err(|e| format!("Failed to open log: {}", e))?;
     let size = f.metadata().map_err(|e| format!("Failed to stat log: {}", e))?.len();
     let chunk = 256_000u64.min(size);
@@ -2128,12 +1920,12 @@ fn read_tail_lines(path: &PathBuf, max_lines: usize) -> Result<Vec<String>, Stri
     Ok(lines.into_iter().map(|s| s.to_string()).collect())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn get_mt_terminal_root() -> Result<String, String> {
     Ok(get_terminal_root_path()?.to_string_lossy().to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn open_mt_terminal_root() -> Result<(), String> {
     let root = get_terminal_root_path()?;
     if !root.exists() {
@@ -2153,7 +1945,7 @@ pub async fn open_mt_terminal_root() -> Result<(), String> {
     Err("Open folder not supported on this OS".to_string())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn read_recent_terminal_log(lines: u32) -> Result<TerminalLogTail, String> {
     let root = get_terminal_root_path()?;
     if !root.exists() {
@@ -2167,7 +1959,7 @@ pub async fn read_recent_terminal_log(lines: u32) -> Result<TerminalLogTail, Str
     })
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn _export_vault_file(
     filename: String,
     target_path: String,
@@ -2196,7 +1988,7 @@ pub async fn _export_vault_file(
 }
 
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn save_to_vault(
     config: MTConfig,
     name: String,
@@ -2266,7 +2058,7 @@ pub async fn save_to_vault(
     Ok(())
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn _delete_from_vault(filename: String, vault_path_override: Option<String>) -> Result<(), String> {
     let vault_root = resolve_vault_path(vault_path_override)?;
     let file_path_buf = vault_root.join(filename);
@@ -2279,101 +2071,10 @@ pub async fn _delete_from_vault(filename: String, vault_path_override: Option<St
     Ok(())
 }
 
-// Helper functions for suffix generation
-fn get_logic_suffix(engine_id: &str, group: u8, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-    
-    let logic_char = match logic_name.to_uppercase().as_str() {
-        "POWER" => "P",
-        "REPOWER" => "R",
-        "SCALPER" | "SCALP" => "S",
-        "STOPPER" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    };
-    
-    format!("{}{}{}", prefix, logic_char, group)
-}
-
-fn get_logic_short(engine_id: &str, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-    
-    let logic_char = match logic_name.to_uppercase().as_str() {
-        "POWER" => "P",
-        "REPOWER" => "R",
-        "SCALPER" | "SCALP" => "S",
-        "STOPPER" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    };
-    
-    format!("{}{}", prefix, logic_char)
-}
-
-fn trigger_action_to_int(action: &str) -> i32 {
-    let s = action.trim();
-    if let Ok(n) = s.parse::<i32>() {
-        return n;
-    }
-    match s {
-        "TriggerAction_None" => 0,
-        "TriggerAction_StopEA" => 1,
-        "TriggerAction_StopEA_KeepTrades" => 2,
-        "TriggerAction_CloseAll" => 3,
-        "TriggerAction_KeepEA_CloseTrades" => 4,
-        "TriggerAction_StopEA_CloseTrades" => 5,
-        _ => 0,
-    }
-}
-
-fn get_logic_global_key(engine_id: &str, logic_name: &str) -> String {
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let name = match logic_name.to_uppercase().as_str() {
-        "POWER" => "Power",
-        "REPOWER" => "Repower",
-        "SCALPER" | "SCALP" => "Scalp",
-        "STOPPER" => "Stopper",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "Power",
-    };
-
-    format!("{}{}", prefix, name)
-}
-
-fn default_close_targets(engine_id: &str, logic_name: &str) -> String {
-    let upper = logic_name.to_uppercase();
-    if upper != "RPO" {
-        return String::new();
-    }
-
-    match engine_id {
-        "A" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO".to_string(),
-        "B" => "B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO".to_string(),
-        "C" => "A:Power,A:Repower,A:Scalp,A:Stopper,A:STO,A:SCA,A:RPO,B:Power,B:Repower,B:Scalp,B:Stopper,B:STO,B:SCA,B:RPO,C:Power,C:Repower,C:Scalp,C:Stopper,C:STO,C:SCA,C:RPO".to_string(),
-        _ => String::new(),
-    }
+/// EA code of a trigger action; names this EA build doesn't know export as
+/// TriggerAction_None
+fn trigger_action_code(action: &str) -> i32 {
+    trigger_action_to_int(action).unwrap_or(0)
 }
 
 fn normalize_trigger_type(raw: &str) -> String {
@@ -2441,32 +2142,6 @@ fn encode_partial_balance(raw: &str) -> i32 {
     }
 }
 
-fn get_logic_start_key(engine_id: &str, logic_name: &str) -> Option<String> {
-    let upper = logic_name.to_uppercase();
-    if upper == "POWER" {
-        return None;
-    }
-
-    let prefix = match engine_id {
-        "A" => "",
-        "B" => "B",
-        "C" => "C",
-        _ => "",
-    };
-
-    let suffix = match upper.as_str() {
-        "REPOWER" => "Repower",
-        "SCALPER" | "SCALP" => "Scalp",
-        "STOPPER" => "Stopper",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => return None,
-    };
-
-    Some(format!("Start{}{}", prefix, suffix))
-}
-
 // Parse helper functions - defined at module level for reuse
 fn get_bool(values: &std::collections::HashMap<String, String>, key: &str) -> bool {
     values.get(key).map(|v| v == "1" || v.to_lowercase() == "true").unwrap_or(false)
@@ -2544,10 +2219,12 @@ fn build_config_from_values(values: &std::collections::HashMap<String, String>)
                 let s = get_string(values, "gInput_RiskAction", "");
                 if s.is_empty() { None } else { Some(s) }
             },
+            ..Default::default()
         },
         time_filters: TimeFiltersConfig { 
             priority_settings: TimePrioritySettings::default(),
-            sessions 
+            sessions,
+            ..Default::default()
         },
         news_filter: NewsFilterConfig {
             enabled: get_bool(values, "gInput_EnableNewsFilter"),
@@ -2562,7 +2239,10 @@ fn build_config_from_values(values: &std::collections::HashMap<String, String>)
                 let s = get_string(values, "gInput_NewsCalendarFile", "");
                 if s.is_empty() { None } else { Some(s) }
             },
+            ..Default::default()
         },
+        // Buy/Sell overrides and magic bases are logic-canvas-main settings
+        ..Default::default()
     };
     
     // Build engines with full V4 DAAVFX parameter parsing
@@ -2584,6 +2264,7 @@ fn build_config_from_values(values: &std::collections::HashMap<String, String>)
         current_set_name: None,
         tags: None,
         comments: None,
+        annotations: None,
         general,
         engines,
     })
@@ -2885,6 +2566,8 @@ fn build_group_config(
         group_number: group_num,
         enabled,
         group_power_start,
+        group_power_start_b: None,
+        group_power_start_s: None,
         reverse_mode,
         hedge_mode,
         hedge_reference,
@@ -3130,6 +2813,8 @@ fn create_default_group(group_num: u8) -> GroupConfig {
         group_number: group_num,
         enabled: group_num == 1,
         group_power_start: if group_num > 1 { Some(1) } else { None },
+        group_power_start_b: None,
+        group_power_start_s: None,
         reverse_mode: false,
         hedge_mode: false,
         hedge_reference: "Logic_None".to_string(),
@@ -3252,20 +2937,6 @@ fn create_default_logic(logic_name: &str) -> LogicConfig {
     }
 }
 
-/// Get logic code from name
-fn get_logic_code(logic_name: &str) -> &'static str {
-    match logic_name {
-        "Power" => "P",
-        "Repower" => "R",
-        "Scalp" => "S",
-        "Stopper" => "ST",
-        "STO" => "STO",
-        "SCA" => "SCA",
-        "RPO" => "RPO",
-        _ => "P",
-    }
-}
-
 /// Decode trail step method from numeric/string value
 fn decode_trail_step_method(val: &str) -> String {
     match val {
@@ -3282,6 +2953,7 @@ fn decode_trail_step_method(val: &str) -> String {
 // ============================================
 
 /// Initialize the MQL Rust Compiler with current MT paths
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn initialize_mql_compiler(
     state: State<'_, MTBridgeState>,
@@ -3290,6 +2962,7 @@ pub async fn initialize_mql_compiler(
 }
 
 /// Run real-time MQL validation
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn validate_mql_code(
     _force_refresh: bool,
@@ -3306,6 +2979,7 @@ pub async fn validate_mql_code(
 }
 
 /// Run complete pre-compilation pipeline
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn run_precompilation_pipeline(
     state: State<'_, MTBridgeState>,
@@ -3321,11 +2995,13 @@ pub async fn run_precompilation_pipeline(
 }
 
 /// Apply automatic fixes generated by the compiler
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn apply_mql_fixes(
     fixes: std::collections::HashMap<String, String>,
     state: State<'_, MTBridgeState>,
 ) -> Result<(), String> {
+    safe_mode::ensure_writable("Applying MQL fixes")?;
     let compiler_guard = state.mql_compiler.lock().unwrap();
     
     if let Some(ref compiler) = *compiler_guard {
@@ -3336,20 +3012,28 @@ pub async fn apply_mql_fixes(
     }
 }
 
-/// Start real-time file watching for MQL validation
+/// Start real-time file watching for MQL validation.
+/// With `options.auto_pipeline` every save runs the pre-compilation pipeline and emits
+/// "mql-pipeline-stage" events (plus MetaEditor compilation when `metaeditor_path` is set).
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn start_mql_file_watching(
     app_handle: tauri::AppHandle,
+    options: Option<WatchOptions>,
     state: State<'_, MTBridgeState>,
 ) -> Result<(), String> {
     let mut compiler_guard = state.mql_compiler.lock().unwrap();
     
     if let Some(ref mut compiler) = *compiler_guard {
+        let stage_handle = app_handle.clone();
         let callback = move |errors: Vec<CompilationError>| {
             let _ = app_handle.emit("mql-validation-update", &errors);
         };
+        let on_stage = move |event: PipelineStageEvent| {
+            let _ = stage_handle.emit("mql-pipeline-stage", &event);
+        };
         
-        compiler.start_file_watching(callback)
+        compiler.start_file_watching_with_options(options.unwrap_or_default(), callback, on_stage)
             .map_err(|e| format!("Failed to start file watching: {}", e))
     } else {
         Err("MQL Compiler not initialized.".to_string())
@@ -3357,6 +3041,7 @@ pub async fn start_mql_file_watching(
 }
 
 /// Get MQL compiler status and statistics
+#[cfg(feature = "tauri-app")]
 #[tauri::command]
 pub async fn get_mql_compiler_status(
     state: State<'_, MTBridgeState>,
@@ -3375,7 +3060,7 @@ pub async fn get_mql_compiler_status(
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
-            watching_files: false, // Would need to track this
+            watching_files: compiler.is_watching(),
         })
     } else {
         Ok(MQLCompilerStatus {
@@ -3428,10 +3113,74 @@ mod tests {
         assert!(file_content.contains("gInput_MagicNumberPowerBuy=123"), "File missing buy magic number");
         assert!(file_content.contains("gInput_MagicNumberPowerSell=456"), "File missing sell magic number");
         assert!(file_content.contains("gInput_UseDirectPriceGrid=1"), "File missing UseDirectPriceGrid");
-        
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_unknown_trigger_actions_export_as_none() {
+        let mut config = MTConfig::default();
+        config.general.news_filter.action = "TriggerAction_Sometimes".to_string();
+        config.general.risk_management.risk_action = Some("TriggerAction_StopEA".to_string());
+
+        let file_path = std::env::temp_dir().join(format!("test_trigger_actions_{}.set", std::process::id()));
+        let result = export_set_file(config, file_path.to_string_lossy().to_string(), "MT4".to_string(), false, None, None, None);
+        assert!(result.is_ok(), "Export should succeed: {:?}", result);
+
+        let file_content = std::fs::read_to_string(&file_path).expect("Failed to read exported file");
+        assert!(file_content.contains("gInput_NewsAction=0\n"), "Unknown news action should export as 0");
+        assert!(file_content.contains("gInput_RiskAction=1\n"));
+
         std::fs::remove_file(&file_path).ok();
     }
 
+    #[test]
+    fn test_active_set_export_backs_up_previous_file() {
+        let common_dir = std::env::temp_dir().join(format!("daavfx_active_export_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&common_dir);
+        fs::create_dir_all(&common_dir).unwrap();
+        let active_path = common_dir.join("ACTIVE.set");
+        fs::write(&active_path, "gInput_MagicNumber=1").unwrap();
+
+        let config = MTConfig {
+            general: GeneralConfig { magic_number: 777, ..Default::default() },
+            ..Default::default()
+        };
+        let written = write_active_set_file(&common_dir, config, "MT4".to_string(), false).unwrap();
+
+        assert_eq!(written, active_path.to_string_lossy());
+        assert!(fs::read_to_string(&active_path).unwrap().contains("gInput_MagicNumber=777"));
+        let backup = daavfx_core::fs::backup_path(&active_path, 1);
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "gInput_MagicNumber=1");
+
+        let _ = fs::remove_dir_all(&common_dir);
+    }
+
+    #[test]
+    fn test_active_set_exports_are_coalesced() {
+        let common_dir = std::env::temp_dir().join(format!("daavfx_active_debounce_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&common_dir);
+        fs::create_dir_all(&common_dir).unwrap();
+        let active_path = common_dir.join("ACTIVE.set");
+        let window = Duration::from_millis(50);
+
+        for magic_number in [1, 2, 3] {
+            let config = MTConfig {
+                general: GeneralConfig { magic_number, ..Default::default() },
+                ..Default::default()
+            };
+            let path = submit_active_set_export(common_dir.clone(), window, config, "MT4".to_string(), false).unwrap();
+            assert_eq!(path, active_path.to_string_lossy());
+        }
+        // Only the first export was written; the last lands when the window closes
+        assert!(fs::read_to_string(&active_path).unwrap().contains("gInput_MagicNumber=1\n"));
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(fs::read_to_string(&active_path).unwrap().contains("gInput_MagicNumber=3\n"));
+        assert!(!daavfx_core::fs::backup_path(&active_path, 2).exists());
+
+        let _ = fs::remove_dir_all(&common_dir);
+    }
+
     #[test]
     fn test_build_config_from_values_includes_new_magic_number_fields() {
         use std::collections::HashMap;
@@ -3673,7 +3422,7 @@ fn extract_broker_name(terminal_path: &PathBuf) -> String {
     "Unknown Broker".to_string()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn get_mt4_settings() -> Result<MT4Settings, String> {
     let terminal_root = get_terminal_root_path()?;
     
@@ -3764,12 +3513,12 @@ pub async fn get_mt4_settings() -> Result<MT4Settings, String> {
     }
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn auto_detect_mt4_paths() -> Result<MT4Settings, String> {
     get_mt4_settings().await
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn configure_mt4_path(path: String) -> Result<MT4Settings, String> {
     let path_buf = PathBuf::from(&path);
     
@@ -3804,7 +3553,7 @@ pub async fn configure_mt4_path(path: String) -> Result<MT4Settings, String> {
     })
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn test_mt4_connection() -> Result<bool, String> {
     let settings = get_mt4_settings().await?;
     
@@ -3825,7 +3574,7 @@ pub async fn test_mt4_connection() -> Result<bool, String> {
     }
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "tauri-app", tauri::command)]
 pub async fn open_mt_folder(folder_type: String) -> Result<(), String> {
     let settings = get_mt4_settings().await?;
     
@@ -0,0 +1,553 @@
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::audit_log;
+use daavfx_core::obfuscation::{deobfuscate_string, obfuscate_string};
+
+const CHANNELS_FILE: &str = "notification_channels.json";
+const SEND_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+  Info,
+  Warning,
+  Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+  pub severity: Severity,
+  pub title: String,
+  pub message: String,
+  /// What raised it, e.g. "alert" or "config_push"
+  #[serde(default)]
+  pub source: String,
+}
+
+impl Notification {
+  fn text(&self) -> String {
+    let level = match self.severity {
+      Severity::Info => "INFO",
+      Severity::Warning => "WARNING",
+      Severity::Critical => "CRITICAL",
+    };
+    format!("[{}] {}\n{}", level, self.title, self.message)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelConfig {
+  Telegram {
+    bot_token: String,
+    chat_id: String,
+  },
+  /// POSTs the notification as JSON
+  Webhook {
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+  },
+  Smtp {
+    host: String,
+    #[serde(default = "default_smtp_port")]
+    port: u16,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    password: String,
+    from: String,
+    to: Vec<String>,
+    /// STARTTLS on `port`; off means implicit TLS (usually port 465)
+    #[serde(default = "default_true")]
+    starttls: bool,
+  },
+}
+
+fn default_smtp_port() -> u16 {
+  587
+}
+
+fn default_true() -> bool {
+  true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationChannel {
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  /// Lowest severity this channel receives
+  pub min_severity: Severity,
+  pub config: ChannelConfig,
+}
+
+/// A channel as shown to the UI: tokens, passwords, the webhook URL and header
+/// values are blanked, and saving a blank field keeps the stored one
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationChannelInfo {
+  #[serde(flatten)]
+  pub channel: NotificationChannel,
+  pub has_secret: bool,
+}
+
+impl From<&NotificationChannel> for NotificationChannelInfo {
+  fn from(channel: &NotificationChannel) -> Self {
+    let mut masked = channel.clone();
+    masked.config.map_secrets(|_| String::new());
+    NotificationChannelInfo {
+      has_secret: channel.config.has_secret(),
+      channel: masked,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryResult {
+  pub channel_id: String,
+  pub channel_name: String,
+  pub ok: bool,
+  pub error: Option<String>,
+}
+
+/// One way of delivering a notification
+pub trait Notifier {
+  fn send(&self, notification: &Notification) -> Result<(), String>;
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+  reqwest::blocking::Client::builder()
+    .timeout(Duration::from_secs(SEND_TIMEOUT_SECS))
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn check_status(response: reqwest::blocking::Response) -> Result<(), String> {
+  let status = response.status();
+  if status.is_success() {
+    Ok(())
+  } else {
+    Err(format!("Server answered {}", status))
+  }
+}
+
+impl Notifier for ChannelConfig {
+  fn send(&self, notification: &Notification) -> Result<(), String> {
+    match self {
+      ChannelConfig::Telegram { bot_token, chat_id } => {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
+        let body = serde_json::json!({ "chat_id": chat_id, "text": notification.text() });
+        let response = http_client()?
+          .post(url)
+          .json(&body)
+          .send()
+          // The URL carries the bot token, so it is left out of the error
+          .map_err(|e| format!("Failed to reach Telegram: {}", e.without_url()))?;
+        check_status(response)
+      }
+      ChannelConfig::Webhook { url, headers } => {
+        let mut request = http_client()?.post(url).json(notification);
+        for (name, value) in headers {
+          request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request
+          .send()
+          .map_err(|e| format!("Failed to reach webhook: {}", e.without_url()))?;
+        check_status(response)
+      }
+      ChannelConfig::Smtp {
+        host,
+        port,
+        username,
+        password,
+        from,
+        to,
+        starttls,
+      } => {
+        let mut builder = Message::builder()
+          .from(
+            from
+              .parse()
+              .map_err(|e| format!("Invalid sender '{}': {}", from, e))?,
+          )
+          .subject(&notification.title);
+        for recipient in to {
+          builder = builder.to(
+            recipient
+              .parse()
+              .map_err(|e| format!("Invalid recipient '{}': {}", recipient, e))?,
+          );
+        }
+        let email = builder
+          .body(notification.text())
+          .map_err(|e| format!("Failed to build email: {}", e))?;
+        let relay = if *starttls {
+          SmtpTransport::starttls_relay(host)
+        } else {
+          SmtpTransport::relay(host)
+        }
+        .map_err(|e| format!("Failed to connect to {}: {}", host, e))?;
+        let mut relay = relay
+          .port(*port)
+          .timeout(Some(Duration::from_secs(SEND_TIMEOUT_SECS)));
+        if !username.is_empty() {
+          relay = relay.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        relay
+          .build()
+          .send(&email)
+          .map(|_| ())
+          .map_err(|e| format!("Failed to send email: {}", e))
+      }
+    }
+  }
+}
+
+impl ChannelConfig {
+  /// Tokens, passwords, webhook URLs and header values are stored obfuscated,
+  /// like the config's API keys
+  fn map_secrets(&mut self, f: fn(&str) -> String) {
+    match self {
+      ChannelConfig::Telegram { bot_token, .. } => *bot_token = f(bot_token),
+      ChannelConfig::Webhook { url, headers } => {
+        *url = f(url);
+        for value in headers.values_mut() {
+          *value = f(value);
+        }
+      }
+      ChannelConfig::Smtp { password, .. } => *password = f(password),
+    }
+  }
+
+  fn has_secret(&self) -> bool {
+    match self {
+      ChannelConfig::Telegram { bot_token, .. } => !bot_token.is_empty(),
+      ChannelConfig::Webhook { url, headers } => {
+        !url.is_empty() || headers.values().any(|v| !v.is_empty())
+      }
+      ChannelConfig::Smtp { password, .. } => !password.is_empty(),
+    }
+  }
+
+  /// Fill secrets the UI sent back blank from the stored channel of the same type
+  fn keep_secrets(&mut self, stored: &ChannelConfig) {
+    fn keep(value: &mut String, stored: &str) {
+      if value.is_empty() {
+        *value = stored.to_string();
+      }
+    }
+    match (self, stored) {
+      (
+        ChannelConfig::Telegram { bot_token, .. },
+        ChannelConfig::Telegram {
+          bot_token: stored, ..
+        },
+      ) => keep(bot_token, stored),
+      (
+        ChannelConfig::Webhook { url, headers },
+        ChannelConfig::Webhook {
+          url: stored_url,
+          headers: stored_headers,
+        },
+      ) => {
+        keep(url, stored_url);
+        for (name, value) in headers.iter_mut() {
+          if let Some(stored) = stored_headers.get(name) {
+            keep(value, stored);
+          }
+        }
+      }
+      (
+        ChannelConfig::Smtp { password, .. },
+        ChannelConfig::Smtp {
+          password: stored, ..
+        },
+      ) => keep(password, stored),
+      _ => {}
+    }
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    match self {
+      ChannelConfig::Telegram { bot_token, chat_id } => {
+        if bot_token.trim().is_empty() || chat_id.trim().is_empty() {
+          return Err("Telegram needs a bot token and chat id".to_string());
+        }
+      }
+      ChannelConfig::Webhook { url, .. } => {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+          return Err(format!("Webhook URL must be http(s): '{}'", url));
+        }
+      }
+      ChannelConfig::Smtp { host, from, to, .. } => {
+        if host.trim().is_empty() || from.trim().is_empty() || to.is_empty() {
+          return Err("SMTP needs a host, sender and at least one recipient".to_string());
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+fn channels_path() -> Result<PathBuf, String> {
+  Ok(audit_log::app_data_dir()?.join(CHANNELS_FILE))
+}
+
+fn load_channels() -> Result<Vec<NotificationChannel>, String> {
+  let path = channels_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path)
+    .map_err(|e| format!("Failed to read notification channels: {}", e))?;
+  let mut channels: Vec<NotificationChannel> = serde_json::from_str(&content)
+    .map_err(|e| format!("Failed to parse notification channels: {}", e))?;
+  for channel in channels.iter_mut() {
+    channel.config.map_secrets(deobfuscate_string);
+  }
+  Ok(channels)
+}
+
+fn save_channels(channels: &[NotificationChannel]) -> Result<(), String> {
+  let path = channels_path()?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+  }
+  let mut stored = channels.to_vec();
+  for channel in stored.iter_mut() {
+    channel.config.map_secrets(obfuscate_string);
+  }
+  let json = serde_json::to_string_pretty(&stored)
+    .map_err(|e| format!("Failed to serialize notification channels: {}", e))?;
+  fs::write(&path, json).map_err(|e| format!("Failed to write notification channels: {}", e))
+}
+
+/// Channels that take a notification of `severity`
+fn routes_for(channels: &[NotificationChannel], severity: Severity) -> Vec<&NotificationChannel> {
+  channels
+    .iter()
+    .filter(|c| c.enabled && severity >= c.min_severity)
+    .collect()
+}
+
+fn deliver(channel: &NotificationChannel, notification: &Notification) -> DeliveryResult {
+  let sent = channel.config.send(notification);
+  if let Err(e) = &sent {
+    log::warn!("Notification channel '{}' failed: {}", channel.name, e);
+  }
+  DeliveryResult {
+    channel_id: channel.id.clone(),
+    channel_name: channel.name.clone(),
+    ok: sent.is_ok(),
+    error: sent.err(),
+  }
+}
+
+/// Send to every enabled channel whose minimum severity is met (blocking)
+pub fn dispatch(notification: &Notification) -> Result<Vec<DeliveryResult>, String> {
+  let channels = load_channels()?;
+  Ok(
+    routes_for(&channels, notification.severity)
+      .into_iter()
+      .map(|channel| deliver(channel, notification))
+      .collect(),
+  )
+}
+
+/// Fire-and-forget dispatch for callers that must not wait on the network
+pub fn dispatch_in_background(notification: Notification) {
+  std::thread::spawn(move || {
+    if let Err(e) = dispatch(&notification) {
+      log::warn!("{}", e);
+    }
+  });
+}
+
+#[tauri::command]
+pub fn list_notification_channels() -> Result<Vec<NotificationChannelInfo>, String> {
+  Ok(
+    load_channels()?
+      .iter()
+      .map(NotificationChannelInfo::from)
+      .collect(),
+  )
+}
+
+/// Create (empty id) or replace a channel; blank secrets keep the stored ones
+#[tauri::command]
+pub fn save_notification_channel(
+  mut channel: NotificationChannel,
+) -> Result<NotificationChannelInfo, String> {
+  if channel.name.trim().is_empty() {
+    return Err("Notification channel needs a name".to_string());
+  }
+  let mut channels = load_channels()?;
+  if channel.id.trim().is_empty() {
+    channel.id = Uuid::new_v4().to_string();
+  }
+  if let Some(stored) = channels.iter().find(|c| c.id == channel.id) {
+    channel.config.keep_secrets(&stored.config);
+  }
+  channel.config.validate()?;
+  match channels.iter_mut().find(|c| c.id == channel.id) {
+    Some(existing) => *existing = channel.clone(),
+    None => channels.push(channel.clone()),
+  }
+  save_channels(&channels)?;
+  Ok(NotificationChannelInfo::from(&channel))
+}
+
+#[tauri::command]
+pub fn delete_notification_channel(id: String) -> Result<(), String> {
+  let mut channels = load_channels()?;
+  let before = channels.len();
+  channels.retain(|c| c.id != id);
+  if channels.len() == before {
+    return Err(format!("Notification channel '{}' not found", id));
+  }
+  save_channels(&channels)
+}
+
+/// Send a test message through one channel regardless of its severity filter
+#[tauri::command]
+pub async fn test_notification_channel(id: String) -> Result<DeliveryResult, String> {
+  tokio::task::spawn_blocking(move || {
+    let channels = load_channels()?;
+    let channel = channels
+      .iter()
+      .find(|c| c.id == id)
+      .ok_or_else(|| format!("Notification channel '{}' not found", id))?;
+    let notification = Notification {
+      severity: Severity::Info,
+      title: "DAAVFX test notification".to_string(),
+      message: format!("Channel '{}' is set up correctly.", channel.name),
+      source: "test".to_string(),
+    };
+    Ok(deliver(channel, &notification))
+  })
+  .await
+  .map_err(|e| format!("Notification task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn send_notification(notification: Notification) -> Result<Vec<DeliveryResult>, String> {
+  tokio::task::spawn_blocking(move || dispatch(&notification))
+    .await
+    .map_err(|e| format!("Notification task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn channel(name: &str, min_severity: Severity, enabled: bool) -> NotificationChannel {
+    NotificationChannel {
+      id: name.to_string(),
+      name: name.to_string(),
+      enabled,
+      min_severity,
+      config: ChannelConfig::Webhook {
+        url: "https://example.com/hook".to_string(),
+        headers: HashMap::new(),
+      },
+    }
+  }
+
+  #[test]
+  fn test_routes_by_severity() {
+    let channels = vec![
+      channel("ops", Severity::Info, true),
+      channel("pager", Severity::Critical, true),
+      channel("muted", Severity::Info, false),
+    ];
+    let names = |severity| -> Vec<String> {
+      routes_for(&channels, severity)
+        .iter()
+        .map(|c| c.name.clone())
+        .collect()
+    };
+    assert_eq!(names(Severity::Info), vec!["ops"]);
+    assert_eq!(names(Severity::Warning), vec!["ops"]);
+    assert_eq!(names(Severity::Critical), vec!["ops", "pager"]);
+  }
+
+  #[test]
+  fn test_channel_json_and_secrets() {
+    let mut config: ChannelConfig = serde_json::from_str(
+      r#"{"type": "smtp", "host": "smtp.example.com", "username": "desk",
+          "password": "hunter2", "from": "desk@example.com", "to": ["risk@example.com"]}"#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    config.map_secrets(obfuscate_string);
+    match &config {
+      ChannelConfig::Smtp {
+        port,
+        password,
+        starttls,
+        ..
+      } => {
+        assert_eq!(*port, 587);
+        assert!(*starttls);
+        assert!(password.starts_with("ENC:"));
+      }
+      other => panic!("unexpected {:?}", other),
+    }
+    config.map_secrets(deobfuscate_string);
+    match &config {
+      ChannelConfig::Smtp { password, .. } => assert_eq!(password, "hunter2"),
+      other => panic!("unexpected {:?}", other),
+    }
+
+    let webhook = ChannelConfig::Webhook {
+      url: "ftp://example.com".to_string(),
+      headers: HashMap::new(),
+    };
+    assert!(webhook.validate().is_err());
+  }
+
+  #[test]
+  fn test_listed_channels_hide_secrets() {
+    let mut stored = channel("ops", Severity::Info, true);
+    if let ChannelConfig::Webhook { headers, .. } = &mut stored.config {
+      headers.insert("Authorization".to_string(), "Bearer abc".to_string());
+    }
+    let mut obfuscated = stored.config.clone();
+    obfuscated.map_secrets(obfuscate_string);
+    match &obfuscated {
+      ChannelConfig::Webhook { headers, .. } => {
+        assert!(headers["Authorization"].starts_with("ENC:"))
+      }
+      other => panic!("unexpected {:?}", other),
+    }
+
+    let info = NotificationChannelInfo::from(&stored);
+    assert!(info.has_secret);
+    let json = serde_json::to_string(&info).unwrap();
+    assert!(
+      !json.contains("Bearer abc") && !json.contains("example.com/hook"),
+      "{}",
+      json
+    );
+
+    // Saving the listed channel back unchanged keeps the stored secrets
+    let mut edited = info.channel.config.clone();
+    edited.keep_secrets(&stored.config);
+    match edited {
+      ChannelConfig::Webhook { url, headers } => {
+        assert_eq!(url, "https://example.com/hook");
+        assert_eq!(headers["Authorization"], "Bearer abc");
+      }
+      other => panic!("unexpected {:?}", other),
+    }
+  }
+}
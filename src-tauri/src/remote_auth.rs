@@ -0,0 +1,356 @@
+// API tokens for remote access. Calls from the app's own webview are
+// trusted; any other origin (a remote page let in by a capability) must send
+// a token in the Authorization header carrying the scope the command needs.
+// guard_invoke wraps the root app's invoke handler to enforce that; the
+// logic-canvas app has its own handler and serves nothing remotely.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::active_set_watchdog::sha256_hex;
+use crate::audit_log;
+
+const API_TOKENS_FILE: &str = "api_tokens.json";
+const TOKEN_PREFIX: &str = "dvx_";
+
+/// What a remote API token may do. A scope doesn't imply the others: a
+/// kill-switch token for a phone doesn't need to read the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope {
+  Read,
+  VaultWrite,
+  TerminalWrite,
+  TradeCommands,
+}
+
+/// A token as stored: only the SHA-256 of the secret is kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+  pub id: String,
+  pub name: String,
+  /// First characters of the secret, to tell tokens apart in the UI
+  pub hint: String,
+  pub token_hash: String,
+  pub scopes: Vec<Scope>,
+  pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiToken {
+  /// The only time the secret is returned
+  pub token: String,
+  pub info: ApiToken,
+}
+
+/// Commands never served remotely, whatever the token: folders and pickers,
+/// watchers and pollers, and anything that reads or changes credentials or
+/// safe mode
+const DESKTOP_ONLY: &[&str] = &[
+  "set_mt_path",
+  "start_file_watcher",
+  "open_vault_folder",
+  "open_mt_terminal_root",
+  "open_mt_folder",
+  "initialize_mql_compiler",
+  "run_precompilation_pipeline",
+  "start_mql_file_watching",
+  "configure_mt4_path",
+  "start_sync_poller",
+  "stop_sync_poller",
+  "start_alert_engine",
+  "stop_alert_engine",
+  "list_notification_channels",
+  "save_notification_channel",
+  "delete_notification_channel",
+  "test_notification_channel",
+  "send_notification",
+  "list_api_tokens",
+  "create_api_token",
+  "revoke_api_token",
+  "set_safe_mode",
+];
+
+/// Scope a command needs when called remotely. None means the command is
+/// desktop-only (see DESKTOP_ONLY) or unknown, and is never served.
+fn required_scope(command: &str) -> Option<Scope> {
+  let scope = match command {
+    "load_mt_config"
+    | "get_default_mt4_path"
+    | "get_default_mt5_path"
+    | "get_active_set_status"
+    | "import_set_file"
+    | "import_json_file"
+    | "list_vault_files"
+    | "get_vault_size"
+    | "get_mt_terminal_root"
+    | "read_recent_terminal_log"
+    | "validate_mql_code"
+    | "get_mql_compiler_status"
+    | "get_mt4_settings"
+    | "auto_detect_mt4_paths"
+    | "test_mt4_connection"
+    | "get_sync_paths"
+    | "read_sync_state"
+    | "list_ea_instances"
+    | "start_active_set_watchdog"
+    | "stop_active_set_watchdog"
+    | "read_audit_log"
+    | "get_account_snapshot"
+    | "list_alert_rules"
+    | "list_rotation_schedule"
+    | "get_next_rotation"
+    | "get_safe_mode" => Scope::Read,
+    "save_mt_config"
+    | "export_set_file"
+    | "export_json_file"
+    | "write_text_file"
+    | "save_to_vault"
+    | "save_alert_rule"
+    | "delete_alert_rule"
+    | "save_rotation_entry"
+    | "delete_rotation_entry" => Scope::VaultWrite,
+    "export_set_file_to_mt_common_files"
+    | "export_active_set_file_to_mt_common_files"
+    | "apply_mql_fixes"
+    | "push_config_to_ea"
+    | "start_rotation_scheduler"
+    | "stop_rotation_scheduler" => Scope::TerminalWrite,
+    "write_sync_commands"
+    | "broadcast_sync_commands"
+    | "request_kill_switch_token"
+    | "close_all_positions" => Scope::TradeCommands,
+    _ => return None,
+  };
+  Some(scope)
+}
+
+fn tokens_path() -> Result<PathBuf, String> {
+  Ok(audit_log::app_data_dir()?.join(API_TOKENS_FILE))
+}
+
+fn load_tokens() -> Result<Vec<ApiToken>, String> {
+  let path = tokens_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content =
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read API tokens: {}", e))?;
+  serde_json::from_str(&content).map_err(|e| format!("Failed to parse API tokens: {}", e))
+}
+
+fn save_tokens(tokens: &[ApiToken]) -> Result<(), String> {
+  let path = tokens_path()?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+  }
+  let json = serde_json::to_string_pretty(tokens)
+    .map_err(|e| format!("Failed to serialize API tokens: {}", e))?;
+  fs::write(&path, json).map_err(|e| format!("Failed to write API tokens: {}", e))
+}
+
+/// Compare without an early exit so response time doesn't leak the hash
+fn constant_time_eq(a: &str, b: &str) -> bool {
+  a.len() == b.len()
+    && a
+      .bytes()
+      .zip(b.bytes())
+      .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+      == 0
+}
+
+/// The token behind `secret` if it exists and carries the scope `command`
+/// needs. Errors start with "Unauthorized" (no valid token) or "Forbidden"
+/// (valid token, missing scope) so a server can map them to 401/403.
+fn check_token(tokens: &[ApiToken], secret: &str, command: &str) -> Result<ApiToken, String> {
+  let secret = secret.trim().trim_start_matches("Bearer ").trim();
+  let hash = sha256_hex(secret.as_bytes());
+  let token = tokens
+    .iter()
+    .find(|t| constant_time_eq(&t.token_hash, &hash))
+    .ok_or_else(|| "Unauthorized: unknown or revoked API token".to_string())?;
+  let scope = required_scope(command)
+    .ok_or_else(|| format!("Forbidden: '{}' is not available remotely", command))?;
+  if !token.scopes.contains(&scope) {
+    return Err(format!(
+      "Forbidden: '{}' needs the {:?} scope, token '{}' doesn't have it",
+      command, scope, token.name
+    ));
+  }
+  Ok(token.clone())
+}
+
+/// Webview origins the app serves itself: the bundled assets and the dev server
+fn is_app_origin(url: &tauri::Url) -> bool {
+  url.scheme() == "tauri"
+    || matches!(
+      url.host_str(),
+      Some("tauri.localhost" | "localhost" | "127.0.0.1")
+    )
+}
+
+/// Ok for the app's own webview; otherwise the Authorization header must hold
+/// a token with the command's scope
+fn authorize_call(
+  app_origin: bool,
+  authorization: Option<&str>,
+  command: &str,
+) -> Result<(), String> {
+  if app_origin {
+    return Ok(());
+  }
+  let secret = authorization
+    .filter(|s| !s.trim().is_empty())
+    .ok_or_else(|| "Unauthorized: remote calls need an API token".to_string())?;
+  check_token(&load_tokens()?, secret, command).map(|_| ())
+}
+
+/// Wrap the generated invoke handler so remote calls are checked before dispatch
+pub(crate) fn guard_invoke<R: tauri::Runtime>(
+  handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+  move |invoke| {
+    let app_origin = invoke
+      .message
+      .webview()
+      .url()
+      .map(|url| is_app_origin(&url))
+      .unwrap_or(false);
+    let authorization = invoke
+      .message
+      .headers()
+      .get("authorization")
+      .and_then(|v| v.to_str().ok())
+      .map(str::to_string);
+    let command = invoke.message.command().to_string();
+    if let Err(e) = authorize_call(app_origin, authorization.as_deref(), &command) {
+      log::warn!("Rejected remote call to '{}': {}", command, e);
+      invoke.resolver.reject(e);
+      return true;
+    }
+    handler(invoke)
+  }
+}
+
+fn generate_secret() -> String {
+  let bytes: [u8; 32] = rand::thread_rng().gen();
+  let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  format!("{}{}", TOKEN_PREFIX, hex)
+}
+
+#[tauri::command]
+pub fn list_api_tokens() -> Result<Vec<ApiToken>, String> {
+  load_tokens()
+}
+
+/// Issue a token with the given scopes; the secret is shown once and only its hash is kept
+#[tauri::command]
+pub fn create_api_token(name: String, scopes: Vec<Scope>) -> Result<CreatedApiToken, String> {
+  if name.trim().is_empty() {
+    return Err("API token needs a name".to_string());
+  }
+  if scopes.is_empty() {
+    return Err("API token needs at least one scope".to_string());
+  }
+  let mut tokens = load_tokens()?;
+  let secret = generate_secret();
+  let info = ApiToken {
+    id: Uuid::new_v4().to_string(),
+    name: name.trim().to_string(),
+    hint: secret.chars().take(TOKEN_PREFIX.len() + 6).collect(),
+    token_hash: sha256_hex(secret.as_bytes()),
+    scopes,
+    created_at: audit_log::now_timestamp(),
+  };
+  tokens.push(info.clone());
+  save_tokens(&tokens)?;
+  Ok(CreatedApiToken {
+    token: secret,
+    info,
+  })
+}
+
+#[tauri::command]
+pub fn revoke_api_token(id: String) -> Result<(), String> {
+  let mut tokens = load_tokens()?;
+  let before = tokens.len();
+  tokens.retain(|t| t.id != id);
+  if tokens.len() == before {
+    return Err(format!("API token '{}' not found", id));
+  }
+  save_tokens(&tokens)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn token(secret: &str, scopes: Vec<Scope>) -> ApiToken {
+    ApiToken {
+      id: "t1".to_string(),
+      name: "phone".to_string(),
+      hint: String::new(),
+      token_hash: sha256_hex(secret.as_bytes()),
+      scopes,
+      created_at: String::new(),
+    }
+  }
+
+  #[test]
+  fn test_check_token_enforces_scope_per_command() {
+    let tokens = vec![token("dvx_read", vec![Scope::Read])];
+
+    assert!(check_token(&tokens, "Bearer dvx_read", "read_sync_state").is_ok());
+    let err = check_token(&tokens, "dvx_read", "close_all_positions").unwrap_err();
+    assert!(err.starts_with("Forbidden"), "{}", err);
+    let err = check_token(&tokens, "dvx_wrong", "read_sync_state").unwrap_err();
+    assert!(err.starts_with("Unauthorized"), "{}", err);
+    // Desktop-only commands are never served
+    let err = check_token(&tokens, "dvx_read", "create_api_token").unwrap_err();
+    assert!(err.starts_with("Forbidden"), "{}", err);
+  }
+
+  #[test]
+  fn test_only_remote_calls_need_a_token() {
+    assert!(authorize_call(true, None, "close_all_positions").is_ok());
+    let err = authorize_call(false, None, "read_sync_state").unwrap_err();
+    assert!(err.starts_with("Unauthorized"), "{}", err);
+
+    let local = |url: &str| is_app_origin(&tauri::Url::parse(url).unwrap());
+    assert!(local("tauri://localhost"));
+    assert!(local("http://tauri.localhost/index.html"));
+    assert!(!local("https://dashboard.example.com"));
+  }
+
+  #[test]
+  fn test_every_registered_command_is_classified() {
+    let lib = include_str!("lib.rs");
+    let start = lib
+      .find("generate_handler![")
+      .expect("invoke handler in lib.rs");
+    let end = start + lib[start..].find(']').unwrap();
+    let commands: Vec<&str> = lib[start..end]
+      .lines()
+      .skip(1)
+      .filter_map(|line| line.trim().trim_end_matches(',').rsplit("::").next())
+      .filter(|name| !name.is_empty())
+      .collect();
+    assert!(commands.len() > 50, "parsed {} commands", commands.len());
+    for command in commands {
+      assert!(
+        required_scope(command).is_some() != DESKTOP_ONLY.contains(&command),
+        "'{}' must have exactly one of a scope or a desktop-only entry",
+        command
+      );
+    }
+  }
+
+  #[test]
+  fn test_scopes_serialize_kebab_case() {
+    let json = serde_json::to_string(&[Scope::VaultWrite, Scope::TradeCommands]).unwrap();
+    assert_eq!(json, r#"["vault-write","trade-commands"]"#);
+  }
+}
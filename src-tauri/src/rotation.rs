@@ -0,0 +1,384 @@
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+use uuid::Uuid;
+
+use crate::audit_log::{self, AuditEntry};
+use crate::mt_bridge::{
+  export_active_set_file_to_mt_common_files, import_json_file, import_set_file, MTConfig,
+};
+use crate::tactical_bridge::{push_config_to_ea, PushStatus};
+
+const ROTATION_SCHEDULE_FILE: &str = "rotation_schedule.json";
+const DEFAULT_ROTATION_INTERVAL_MS: u64 = 30_000;
+/// Far enough ahead to reach the next 5th weekday of a month
+const ROTATION_LOOKAHEAD_DAYS: i64 = 400;
+
+/// Bumped on every start/stop so only the latest scheduler thread keeps running
+static ROTATION_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationEntry {
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  pub platform: String,
+  /// EA instance id; None for the single-EA sync files
+  #[serde(default)]
+  pub instance_id: Option<String>,
+  /// Vault preset (.set or .json) that becomes ACTIVE.set
+  pub preset: String,
+  /// "mon" ... "sun" (full names work too)
+  pub weekday: String,
+  /// 1-5 limits the switch to that week of the month ("first Friday");
+  /// None switches every week
+  #[serde(default)]
+  pub week_of_month: Option<u8>,
+  /// Local time of day, "HH:MM"
+  pub time: String,
+  #[serde(default)]
+  pub last_run: Option<String>,
+  #[serde(default)]
+  pub last_outcome: Option<String>,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NextRotation {
+  pub entry_id: String,
+  pub name: String,
+  pub preset: String,
+  pub platform: String,
+  pub instance_id: Option<String>,
+  /// Local time, "YYYY-MM-DD HH:MM"
+  pub at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RotationEvent {
+  pub entry_id: String,
+  pub name: String,
+  pub preset: String,
+  pub platform: String,
+  pub instance_id: Option<String>,
+  /// "applied", "timeout", "rejected" or "failed"
+  pub outcome: String,
+  pub message: String,
+  pub timestamp: String,
+}
+
+fn schedule_path() -> Result<PathBuf, String> {
+  Ok(audit_log::app_data_dir()?.join(ROTATION_SCHEDULE_FILE))
+}
+
+fn load_schedule() -> Result<Vec<RotationEntry>, String> {
+  let path = schedule_path()?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content =
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read rotation schedule: {}", e))?;
+  serde_json::from_str(&content).map_err(|e| format!("Failed to parse rotation schedule: {}", e))
+}
+
+fn save_schedule(entries: &[RotationEntry]) -> Result<(), String> {
+  let path = schedule_path()?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+  }
+  let json = serde_json::to_string_pretty(entries)
+    .map_err(|e| format!("Failed to serialize rotation schedule: {}", e))?;
+  fs::write(&path, json).map_err(|e| format!("Failed to write rotation schedule: {}", e))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+  value
+    .trim()
+    .parse::<Weekday>()
+    .map_err(|_| format!("Unknown weekday '{}'", value))
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+  NaiveTime::parse_from_str(value.trim(), "%H:%M")
+    .map_err(|_| format!("Time must be HH:MM, got '{}'", value))
+}
+
+fn validate_entry(entry: &RotationEntry) -> Result<(), String> {
+  if entry.name.trim().is_empty() {
+    return Err("Rotation entry needs a name".to_string());
+  }
+  if entry.preset.trim().is_empty() {
+    return Err("Rotation entry needs a vault preset".to_string());
+  }
+  parse_weekday(&entry.weekday)?;
+  parse_time(&entry.time)?;
+  match entry.week_of_month {
+    Some(week) if !(1..=5).contains(&week) => {
+      Err(format!("Week of month must be 1-5, got {}", week))
+    }
+    _ => Ok(()),
+  }
+}
+
+/// The first scheduled switch strictly after `after` (local time)
+pub fn next_occurrence(entry: &RotationEntry, after: NaiveDateTime) -> Option<NaiveDateTime> {
+  let weekday = parse_weekday(&entry.weekday).ok()?;
+  let time = parse_time(&entry.time).ok()?;
+  (0..=ROTATION_LOOKAHEAD_DAYS)
+    .map(|days| after.date() + chrono::Duration::days(days))
+    .filter(|date| date.weekday() == weekday)
+    .filter(|date| {
+      entry
+        .week_of_month
+        .map_or(true, |week| date.day0() / 7 + 1 == week as u32)
+    })
+    .map(|date| date.and_time(time))
+    .find(|at| *at > after)
+}
+
+#[tauri::command]
+pub fn list_rotation_schedule() -> Result<Vec<RotationEntry>, String> {
+  load_schedule()
+}
+
+/// Create (empty id) or replace a rotation entry; returns the stored entry
+#[tauri::command]
+pub fn save_rotation_entry(mut entry: RotationEntry) -> Result<RotationEntry, String> {
+  validate_entry(&entry)?;
+  let mut entries = load_schedule()?;
+  if entry.id.trim().is_empty() {
+    entry.id = Uuid::new_v4().to_string();
+  }
+  match entries.iter_mut().find(|e| e.id == entry.id) {
+    Some(existing) => {
+      // Run history belongs to the scheduler, not the editor
+      entry.last_run = existing.last_run.clone();
+      entry.last_outcome = existing.last_outcome.clone();
+      *existing = entry.clone();
+    }
+    None => entries.push(entry.clone()),
+  }
+  save_schedule(&entries)?;
+  Ok(entry)
+}
+
+#[tauri::command]
+pub fn delete_rotation_entry(id: String) -> Result<(), String> {
+  let mut entries = load_schedule()?;
+  let before = entries.len();
+  entries.retain(|e| e.id != id);
+  if entries.len() == before {
+    return Err(format!("Rotation entry '{}' not found", id));
+  }
+  save_schedule(&entries)
+}
+
+/// The soonest upcoming switch across enabled entries
+#[tauri::command]
+pub fn get_next_rotation() -> Result<Option<NextRotation>, String> {
+  let now = Local::now().naive_local();
+  Ok(
+    load_schedule()?
+      .into_iter()
+      .filter(|e| e.enabled)
+      .filter_map(|e| next_occurrence(&e, now).map(|at| (at, e)))
+      .min_by_key(|(at, _)| *at)
+      .map(|(at, e)| NextRotation {
+        entry_id: e.id,
+        name: e.name,
+        preset: e.preset,
+        platform: e.platform,
+        instance_id: e.instance_id,
+        at: at.format("%Y-%m-%d %H:%M").to_string(),
+      }),
+  )
+}
+
+async fn load_preset(preset: &str) -> Result<MTConfig, String> {
+  if preset.to_lowercase().ends_with(".json") {
+    import_json_file(preset.to_string()).await
+  } else {
+    import_set_file(preset.to_string()).await
+  }
+}
+
+/// Export the entry's preset as ACTIVE.set and push it through the
+/// file-then-reload-command flow; returns (outcome, message)
+async fn rotate(entry: &RotationEntry) -> (String, String) {
+  let config = match load_preset(&entry.preset).await {
+    Ok(config) => config,
+    Err(e) => return ("failed".to_string(), e),
+  };
+  if let Err(e) =
+    export_active_set_file_to_mt_common_files(config.clone(), entry.platform.clone(), false)
+  {
+    return ("failed".to_string(), e);
+  }
+  match push_config_to_ea(
+    entry.platform.clone(),
+    config,
+    entry.instance_id.clone(),
+    None,
+    None,
+  )
+  .await
+  {
+    Ok(result) => {
+      let outcome = match result.status {
+        PushStatus::Applied => "applied",
+        PushStatus::Timeout => "timeout",
+        PushStatus::Rejected => "rejected",
+      };
+      (outcome.to_string(), result.message)
+    }
+    Err(e) => ("failed".to_string(), e),
+  }
+}
+
+fn record_rotation(entry: &RotationEntry, outcome: &str, message: &str) {
+  let audit = AuditEntry {
+    timestamp: audit_log::now_timestamp(),
+    action: "scheduled_rotation".to_string(),
+    platform: entry.platform.clone(),
+    instance_id: entry.instance_id.clone(),
+    scope: entry.preset.clone(),
+    command_id: None,
+    outcome: outcome.to_string(),
+    detail: format!("{}: {}", entry.name, message),
+  };
+  if let Err(e) = audit_log::record(&audit) {
+    log::error!("{}", e);
+  }
+
+  // Re-read so edits made while the push was waiting aren't lost
+  if let Ok(mut entries) = load_schedule() {
+    if let Some(stored) = entries.iter_mut().find(|e| e.id == entry.id) {
+      stored.last_run = Some(audit.timestamp.clone());
+      stored.last_outcome = Some(outcome.to_string());
+      if let Err(e) = save_schedule(&entries) {
+        log::error!("{}", e);
+      }
+    }
+  }
+}
+
+/// Check the stored schedule every `interval_ms` and switch ACTIVE.set when
+/// an entry comes due, emitting "config-rotated". Switches missed while the
+/// scheduler wasn't running are skipped, not replayed. Starting again
+/// replaces the running scheduler.
+#[tauri::command]
+pub fn start_rotation_scheduler(
+  interval_ms: Option<u64>,
+  app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+  load_schedule()?;
+  let interval = Duration::from_millis(
+    interval_ms
+      .unwrap_or(DEFAULT_ROTATION_INTERVAL_MS)
+      .max(1000),
+  );
+  let generation = ROTATION_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+  std::thread::spawn(move || {
+    let mut checked: HashMap<String, NaiveDateTime> = HashMap::new();
+    while ROTATION_GENERATION.load(Ordering::SeqCst) == generation {
+      let now = Local::now().naive_local();
+      for entry in load_schedule()
+        .unwrap_or_default()
+        .iter()
+        .filter(|e| e.enabled)
+      {
+        let since = checked.insert(entry.id.clone(), now).unwrap_or(now);
+        if !next_occurrence(entry, since).is_some_and(|at| at <= now) {
+          continue;
+        }
+        let (outcome, message) = tauri::async_runtime::block_on(rotate(entry));
+        record_rotation(entry, &outcome, &message);
+        let event = RotationEvent {
+          entry_id: entry.id.clone(),
+          name: entry.name.clone(),
+          preset: entry.preset.clone(),
+          platform: entry.platform.clone(),
+          instance_id: entry.instance_id.clone(),
+          outcome,
+          message,
+          timestamp: audit_log::now_timestamp(),
+        };
+        let _ = app_handle.emit("config-rotated", &event);
+      }
+      std::thread::sleep(interval);
+    }
+  });
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_rotation_scheduler() -> Result<(), String> {
+  ROTATION_GENERATION.fetch_add(1, Ordering::SeqCst);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(weekday: &str, week_of_month: Option<u8>, time: &str) -> RotationEntry {
+    RotationEntry {
+      id: "nfp".to_string(),
+      name: "NFP week".to_string(),
+      enabled: true,
+      platform: "MT5".to_string(),
+      instance_id: None,
+      preset: "nfp.set".to_string(),
+      weekday: weekday.to_string(),
+      week_of_month,
+      time: time.to_string(),
+      last_run: None,
+      last_outcome: None,
+    }
+  }
+
+  fn at(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+  }
+
+  #[test]
+  fn test_next_occurrence_weekly_and_nth_weekday() {
+    // 2026-10-16 is the third Friday of October
+    let now = at("2026-10-16 12:00");
+    assert_eq!(
+      next_occurrence(&entry("fri", None, "13:00"), now),
+      Some(at("2026-10-16 13:00"))
+    );
+    assert_eq!(
+      next_occurrence(&entry("Friday", None, "08:00"), now),
+      Some(at("2026-10-23 08:00"))
+    );
+    assert_eq!(
+      next_occurrence(&entry("fri", Some(1), "08:00"), now),
+      Some(at("2026-11-06 08:00"))
+    );
+    assert_eq!(
+      next_occurrence(&entry("fri", Some(5), "08:00"), now),
+      Some(at("2026-10-30 08:00"))
+    );
+    assert_eq!(next_occurrence(&entry("someday", None, "08:00"), now), None);
+  }
+
+  #[test]
+  fn test_validate_entry() {
+    assert!(validate_entry(&entry("mon", Some(2), "00:30")).is_ok());
+    assert!(validate_entry(&entry("mon", Some(6), "00:30")).is_err());
+    assert!(validate_entry(&entry("mon", None, "25:00")).is_err());
+  }
+}
@@ -0,0 +1,16 @@
+// Commands for the read-only switch in daavfx_core::safe_mode. While it is
+// on, Common Files exports, ACTIVE.set, sync commands, config pushes, the
+// kill switch and MQL fixes fail with a SafeModeBlocked error.
+
+use daavfx_core::safe_mode;
+
+#[tauri::command]
+pub fn get_safe_mode() -> bool {
+  safe_mode::is_enabled()
+}
+
+#[tauri::command]
+pub fn set_safe_mode(enabled: bool) -> bool {
+  safe_mode::set_enabled(enabled);
+  enabled
+}
@@ -1,10 +1,38 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
 use uuid::Uuid;
 
+use daavfx_core::safe_mode;
+
+use crate::audit_log::{self, AuditEntry};
+use crate::mt_bridge::{export_set_file, MTConfig};
+use crate::notifications::{self, Notification, Severity};
+
 const SYNC_STATE_FILE: &str = "DAAVFX_SyncState.json";
 const SYNC_COMMANDS_FILE: &str = "DAAVFX_SyncCommands.json";
+const SYNC_REGISTRY_FILE: &str = "DAAVFX_SyncRegistry.json";
+const ACCOUNT_SNAPSHOT_FILE: &str = "DAAVFX_Account.json";
+/// Instance id of the legacy single-EA sync files
+const DEFAULT_INSTANCE_ID: &str = "default";
+
+/// Sync state schema written by current EA builds; files without a
+/// schema_version are the original (v1) layout
+const SYNC_SCHEMA_VERSION: u32 = 2;
+const DEFAULT_STALE_AFTER_SECS: u64 = 30;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+const DEFAULT_PUSH_TIMEOUT_SECS: u64 = 20;
+const PUSH_ACK_POLL_MS: u64 = 250;
+/// How long a kill-switch confirmation token stays valid
+const KILL_SWITCH_TOKEN_SECS: i64 = 60;
+
+/// Bumped on every start/stop so only the latest poller thread keeps running
+static POLLER_GENERATION: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncGlobalBuySell {
@@ -31,8 +59,56 @@ pub struct SyncAccount {
   pub currency: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPosition {
+  pub ticket: i64,
+  pub symbol: String,
+  /// "buy" or "sell"
+  pub side: String,
+  pub volume: f64,
+  pub open_price: f64,
+  pub profit: f64,
+  pub magic: i32,
+  #[serde(default)]
+  pub comment: String,
+}
+
+/// A command the EA has picked up but not finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPendingCommand {
+  pub command_id: String,
+  pub command: String,
+  #[serde(default)]
+  pub status: String,
+}
+
+/// The EA's verdict on a command, keyed by the command's id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncAck {
+  pub command_id: String,
+  /// "applied" or "rejected"
+  pub status: String,
+  #[serde(default)]
+  pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncError {
+  #[serde(default)]
+  pub timestamp: String,
+  #[serde(default)]
+  pub code: i32,
+  pub message: String,
+}
+
+fn legacy_schema_version() -> u32 {
+  1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncState {
+  #[serde(default = "legacy_schema_version")]
+  pub schema_version: u32,
   pub version: String,
   pub timestamp: String,
   pub symbol: String,
@@ -40,6 +116,67 @@ pub struct SyncState {
   pub global_buy_sell: SyncGlobalBuySell,
   pub logic_states: Vec<SyncLogicState>,
   pub account: SyncAccount,
+  #[serde(default)]
+  pub positions: Vec<SyncPosition>,
+  #[serde(default)]
+  pub pending_commands: Vec<SyncPendingCommand>,
+  /// Unix seconds of the EA's last timer tick; 0 when the EA doesn't write one
+  #[serde(default)]
+  pub heartbeat: i64,
+  #[serde(default)]
+  pub errors: Vec<SyncError>,
+  #[serde(default)]
+  pub acknowledgements: Vec<SyncAck>,
+  /// Lowercase hex SHA-256 of the set file the EA last loaded; empty on
+  /// builds that don't report it
+  #[serde(default)]
+  pub active_set_hash: String,
+}
+
+impl SyncState {
+  /// Reject files from a newer EA and values no EA build writes
+  pub fn validate(&self) -> Result<(), String> {
+    let mut problems: Vec<String> = Vec::new();
+    if self.schema_version == 0 || self.schema_version > SYNC_SCHEMA_VERSION {
+      problems.push(format!(
+        "unsupported schema_version {} (expected 1-{})",
+        self.schema_version, SYNC_SCHEMA_VERSION
+      ));
+    }
+    if self.heartbeat < 0 {
+      problems.push(format!("negative heartbeat {}", self.heartbeat));
+    }
+    if !self.account.balance.is_finite() || !self.account.equity.is_finite() {
+      problems.push("account balance/equity is not a number".to_string());
+    }
+    for state in &self.logic_states {
+      if state.group < 1 || state.logic.trim().is_empty() {
+        problems.push(format!("invalid logic state group {} '{}'", state.group, state.logic));
+      }
+      if state.scale_reverse < 0.0 || state.scale_hedge < 0.0 {
+        problems.push(format!("negative scale for group {} {}", state.group, state.logic));
+      }
+    }
+    for position in &self.positions {
+      let side = position.side.to_lowercase();
+      if side != "buy" && side != "sell" {
+        problems.push(format!("position {} has side '{}'", position.ticket, position.side));
+      }
+      if position.volume <= 0.0 || !position.volume.is_finite() {
+        problems.push(format!("position {} has volume {}", position.ticket, position.volume));
+      }
+    }
+    for command in &self.pending_commands {
+      if command.command_id.trim().is_empty() {
+        problems.push(format!("pending '{}' command has no command_id", command.command));
+      }
+    }
+    if problems.is_empty() {
+      Ok(())
+    } else {
+      Err(format!("Invalid sync state: {}", problems.join("; ")))
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -117,7 +254,7 @@ fn get_mt5_common_files_dir() -> Result<PathBuf, String> {
   }
 }
 
-fn common_files_dir_for_platform(platform: &str) -> Result<PathBuf, String> {
+pub(crate) fn common_files_dir_for_platform(platform: &str) -> Result<PathBuf, String> {
   let p = platform.trim().to_uppercase();
   if p == "MT5" {
     get_mt5_common_files_dir()
@@ -151,11 +288,124 @@ fn safe_overwrite(path: &PathBuf, content: &str) -> Result<(), String> {
   Ok(())
 }
 
+/// Registry the EAs maintain when several run at once, one entry per instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRegistryEntry {
+  pub instance_id: String,
+  #[serde(default)]
+  pub symbol: String,
+  #[serde(default)]
+  pub magic_number: i32,
+  /// File names inside Common Files
+  pub state_file: String,
+  pub commands_file: String,
+  /// Account snapshot; defaults to DAAVFX_Account_<instance_id>.json
+  #[serde(default)]
+  pub account_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncRegistry {
+  #[serde(default)]
+  pub instances: Vec<SyncRegistryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EaInstance {
+  pub instance_id: String,
+  pub symbol: String,
+  pub magic_number: i32,
+  pub state_path: String,
+  pub commands_path: String,
+  pub last_heartbeat: Option<i64>,
+  pub online: bool,
+}
+
+/// Where one EA instance's state and commands live
+#[derive(Debug, Clone)]
+pub(crate) struct SyncRoute {
+  pub(crate) instance_id: String,
+  symbol: String,
+  magic_number: i32,
+  pub(crate) state_path: PathBuf,
+  pub(crate) commands_path: PathBuf,
+  pub(crate) account_path: PathBuf,
+}
+
+/// Registry file names must stay inside Common Files
+fn registry_file_name(name: &str) -> Result<&str, String> {
+  let name = name.trim();
+  if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+    return Err(format!("Invalid sync file name in registry: '{}'", name));
+  }
+  Ok(name)
+}
+
+fn legacy_route(common_dir: &Path) -> SyncRoute {
+  SyncRoute {
+    instance_id: DEFAULT_INSTANCE_ID.to_string(),
+    symbol: String::new(),
+    magic_number: 0,
+    state_path: common_dir.join(SYNC_STATE_FILE),
+    commands_path: common_dir.join(SYNC_COMMANDS_FILE),
+    account_path: common_dir.join(ACCOUNT_SNAPSHOT_FILE),
+  }
+}
+
+fn parse_registry(common_dir: &Path, content: &str) -> Result<Vec<SyncRoute>, String> {
+  let registry: SyncRegistry = serde_json::from_str(content)
+    .map_err(|e| format!("Failed to parse sync registry JSON: {}", e))?;
+  let mut routes: Vec<SyncRoute> = Vec::new();
+  for entry in registry.instances {
+    let instance_id = entry.instance_id.trim().to_string();
+    if instance_id.is_empty() || routes.iter().any(|r| r.instance_id == instance_id) {
+      continue;
+    }
+    let account_file = match &entry.account_file {
+      Some(name) => registry_file_name(name)?.to_string(),
+      None => format!("DAAVFX_Account_{}.json", instance_id),
+    };
+    routes.push(SyncRoute {
+      symbol: entry.symbol,
+      magic_number: entry.magic_number,
+      state_path: common_dir.join(registry_file_name(&entry.state_file)?),
+      commands_path: common_dir.join(registry_file_name(&entry.commands_file)?),
+      account_path: common_dir.join(account_file),
+      instance_id,
+    });
+  }
+  Ok(routes)
+}
+
+/// Every known EA instance: the registry's entries, or the single legacy
+/// sync file pair when no registry exists
+fn sync_routes(common_dir: &Path) -> Result<Vec<SyncRoute>, String> {
+  let registry_path = common_dir.join(SYNC_REGISTRY_FILE);
+  if !registry_path.exists() {
+    return Ok(vec![legacy_route(common_dir)]);
+  }
+  let content = fs::read_to_string(&registry_path)
+    .map_err(|e| format!("Failed to read sync registry: {}", e))?;
+  parse_registry(common_dir, &content)
+}
+
+/// The instance to address; without an id the legacy sync files are used
+pub(crate) fn sync_route(platform: &str, instance_id: Option<&str>) -> Result<SyncRoute, String> {
+  let common_dir = common_files_dir_for_platform(platform)?;
+  match instance_id.map(str::trim).filter(|id| !id.is_empty()) {
+    None => Ok(legacy_route(&common_dir)),
+    Some(id) => sync_routes(&common_dir)?
+      .into_iter()
+      .find(|route| route.instance_id == id)
+      .ok_or_else(|| format!("Unknown EA instance '{}'", id)),
+  }
+}
+
 #[tauri::command]
-pub fn get_sync_paths(platform: String) -> Result<SyncPaths, String> {
-  let common_dir = common_files_dir_for_platform(&platform)?;
-  let state_path = common_dir.join(SYNC_STATE_FILE);
-  let commands_path = common_dir.join(SYNC_COMMANDS_FILE);
+pub fn get_sync_paths(platform: String, instance_id: Option<String>) -> Result<SyncPaths, String> {
+  let route = sync_route(&platform, instance_id.as_deref())?;
+  let state_path = route.state_path;
+  let commands_path = route.commands_path;
 
   let state_exists = state_path.exists();
   let state_last_modified_ms = if state_exists {
@@ -177,9 +427,11 @@ pub fn get_sync_paths(platform: String) -> Result<SyncPaths, String> {
 }
 
 #[tauri::command]
-pub fn read_sync_state(platform: String) -> Result<Option<SyncState>, String> {
-  let common_dir = common_files_dir_for_platform(&platform)?;
-  let state_path = common_dir.join(SYNC_STATE_FILE);
+pub fn read_sync_state(
+  platform: String,
+  instance_id: Option<String>,
+) -> Result<Option<SyncState>, String> {
+  let state_path = sync_route(&platform, instance_id.as_deref())?.state_path;
   if !state_path.exists() {
     return Ok(None);
   }
@@ -188,27 +440,682 @@ pub fn read_sync_state(platform: String) -> Result<Option<SyncState>, String> {
     .map_err(|e| format!("Failed to read sync state: {}", e))?;
   let parsed: SyncState =
     serde_json::from_str(&content).map_err(|e| format!("Failed to parse sync state JSON: {}", e))?;
+  parsed.validate()?;
   Ok(Some(parsed))
 }
 
+pub(crate) fn assign_command_ids(commands: &mut [SyncCommandPayload]) {
+  for cmd in commands.iter_mut() {
+    if cmd.command_id.as_deref().unwrap_or("").is_empty() {
+      cmd.command_id = Some(Uuid::new_v4().to_string());
+    }
+  }
+}
+
+pub(crate) fn write_commands_file(
+  commands_path: &PathBuf,
+  commands: &[SyncCommandPayload],
+) -> Result<(), String> {
+  let json = serde_json::to_string_pretty(commands)
+    .map_err(|e| format!("Failed to serialize sync commands: {}", e))?;
+  safe_overwrite(commands_path, &json)
+}
+
+/// Write commands for one EA instance (the legacy sync file without an id)
 #[tauri::command]
 pub fn write_sync_commands(
   platform: String,
   mut commands: Vec<SyncCommandPayload>,
+  instance_id: Option<String>,
 ) -> Result<String, String> {
+  safe_mode::ensure_writable("Sending sync commands")?;
+  let commands_path = sync_route(&platform, instance_id.as_deref())?.commands_path;
+  assign_command_ids(&mut commands);
+  write_commands_file(&commands_path, &commands)?;
+  Ok(commands_path.to_string_lossy().to_string())
+}
+
+/// Write the same commands (same command ids) to every known EA instance;
+/// returns the command files written
+#[tauri::command]
+pub fn broadcast_sync_commands(
+  platform: String,
+  mut commands: Vec<SyncCommandPayload>,
+) -> Result<Vec<String>, String> {
+  safe_mode::ensure_writable("Broadcasting sync commands")?;
   let common_dir = common_files_dir_for_platform(&platform)?;
-  let commands_path = common_dir.join(SYNC_COMMANDS_FILE);
+  assign_command_ids(&mut commands);
+  let mut written = Vec::new();
+  for route in sync_routes(&common_dir)? {
+    write_commands_file(&route.commands_path, &commands)?;
+    written.push(route.commands_path.to_string_lossy().to_string());
+  }
+  Ok(written)
+}
 
-  for cmd in commands.iter_mut() {
-    if cmd.command_id.as_deref().unwrap_or("").is_empty() {
-      cmd.command_id = Some(Uuid::new_v4().to_string());
+#[derive(Debug, Clone, Serialize)]
+pub struct EaHeartbeatEvent {
+  pub platform: String,
+  pub instance_id: String,
+  /// Unix seconds of the last sign of life, None when there is no state file
+  pub last_heartbeat: Option<i64>,
+  pub stale_for_secs: u64,
+}
+
+pub(crate) fn unix_now() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs() as i64)
+    .unwrap_or(0)
+}
+
+/// EA heartbeat, falling back to the state file's mtime for EAs (or
+/// unreadable files) that don't carry one
+pub(crate) fn last_heartbeat(state_path: &Path) -> Option<i64> {
+  let modified = fs::metadata(state_path)
+    .ok()
+    .and_then(|m| m.modified().ok())
+    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+    .map(|d| d.as_secs() as i64)?;
+  let heartbeat = fs::read_to_string(state_path)
+    .ok()
+    .and_then(|content| serde_json::from_str::<SyncState>(&content).ok())
+    .map(|state| state.heartbeat)
+    .filter(|heartbeat| *heartbeat > 0);
+  Some(heartbeat.unwrap_or(modified))
+}
+
+pub(crate) fn stale_for_secs(heartbeat: Option<i64>) -> u64 {
+  heartbeat
+    .map(|h| (unix_now() - h).max(0) as u64)
+    .unwrap_or(u64::MAX)
+}
+
+/// Connected EA instances with their last heartbeat
+#[tauri::command]
+pub fn list_ea_instances(
+  platform: String,
+  stale_after_secs: Option<u64>,
+) -> Result<Vec<EaInstance>, String> {
+  let common_dir = common_files_dir_for_platform(&platform)?;
+  let stale_after = stale_after_secs.unwrap_or(DEFAULT_STALE_AFTER_SECS).max(1);
+  let mut instances = Vec::new();
+  for route in sync_routes(&common_dir)? {
+    let last_heartbeat = last_heartbeat(&route.state_path);
+    let (mut symbol, mut magic_number) = (route.symbol, route.magic_number);
+    if symbol.is_empty() {
+      if let Some(state) = fs::read_to_string(&route.state_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<SyncState>(&content).ok())
+      {
+        symbol = state.symbol;
+        magic_number = state.magic_number;
+      }
     }
+    instances.push(EaInstance {
+      instance_id: route.instance_id,
+      symbol,
+      magic_number,
+      state_path: route.state_path.to_string_lossy().to_string(),
+      commands_path: route.commands_path.to_string_lossy().to_string(),
+      last_heartbeat,
+      online: stale_for_secs(last_heartbeat) < stale_after,
+    });
   }
+  Ok(instances)
+}
 
-  let json = serde_json::to_string_pretty(&commands)
-    .map_err(|e| format!("Failed to serialize sync commands: {}", e))?;
+/// Poll every EA instance's sync state in the background and emit
+/// "ea-offline" once its heartbeat is older than `stale_after_secs`, then
+/// "ea-online" when it recovers. The registry is re-read each round so new
+/// instances are picked up. Starting again replaces the running poller.
+#[tauri::command]
+pub fn start_sync_poller(
+  platform: String,
+  stale_after_secs: Option<u64>,
+  interval_ms: Option<u64>,
+  app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+  let common_dir = common_files_dir_for_platform(&platform)?;
+  let stale_after = stale_after_secs.unwrap_or(DEFAULT_STALE_AFTER_SECS).max(1);
+  let interval = Duration::from_millis(interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS).max(250));
+  let generation = POLLER_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
 
-  safe_overwrite(&commands_path, &json)?;
-  Ok(commands_path.to_string_lossy().to_string())
+  std::thread::spawn(move || {
+    let mut offline: HashMap<String, bool> = HashMap::new();
+    while POLLER_GENERATION.load(Ordering::SeqCst) == generation {
+      let routes = sync_routes(&common_dir).unwrap_or_else(|_| vec![legacy_route(&common_dir)]);
+      for route in routes {
+        let heartbeat = last_heartbeat(&route.state_path);
+        let stale_for = stale_for_secs(heartbeat);
+        let is_stale = stale_for >= stale_after;
+        let was_offline = offline.entry(route.instance_id.clone()).or_insert(false);
+        if is_stale != *was_offline {
+          *was_offline = is_stale;
+          let event = EaHeartbeatEvent {
+            platform: platform.clone(),
+            instance_id: route.instance_id,
+            last_heartbeat: heartbeat,
+            stale_for_secs: if heartbeat.is_some() { stale_for } else { 0 },
+          };
+          let name = if is_stale { "ea-offline" } else { "ea-online" };
+          let _ = app_handle.emit(name, &event);
+        }
+      }
+      std::thread::sleep(interval);
+    }
+  });
+  Ok(())
+}
+
+#[tauri::command]
+pub fn stop_sync_poller() -> Result<(), String> {
+  POLLER_GENERATION.fetch_add(1, Ordering::SeqCst);
+  Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PushStatus {
+  Applied,
+  Timeout,
+  Rejected,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PushConfigResult {
+  pub status: PushStatus,
+  pub nonce: String,
+  pub file_path: String,
+  /// Whether the EA listed the reload as pending before the outcome
+  pub received: bool,
+  pub message: String,
+  pub elapsed_ms: u64,
+}
+
+/// What the sync state says about a pushed nonce: the ack if there is one,
+/// and whether the command was at least picked up
+fn push_progress(state: &SyncState, nonce: &str) -> (Option<SyncAck>, bool) {
+  let ack = state
+    .acknowledgements
+    .iter()
+    .find(|ack| ack.command_id == nonce)
+    .cloned();
+  let received = ack.is_some() || state.pending_commands.iter().any(|c| c.command_id == nonce);
+  (ack, received)
+}
+
+/// Push a config to a live EA and wait for it to confirm. The .set (or
+/// JSON) file goes to Common Files first, then a reload_config command
+/// carrying a fresh nonce as its command_id; the EA acknowledges the nonce
+/// in its sync state once it has applied or rejected the file.
+#[tauri::command]
+pub async fn push_config_to_ea(
+  platform: String,
+  config: MTConfig,
+  instance_id: Option<String>,
+  format: Option<String>,
+  timeout_secs: Option<u64>,
+) -> Result<PushConfigResult, String> {
+  safe_mode::ensure_writable("Pushing a config to the EA")?;
+  let route = sync_route(&platform, instance_id.as_deref())?;
+  let common_dir = route
+    .commands_path
+    .parent()
+    .map(Path::to_path_buf)
+    .ok_or_else(|| "Sync commands path has no parent directory".to_string())?;
+  let nonce = Uuid::new_v4().to_string();
+  let as_json = format.as_deref().map(str::trim).unwrap_or("set").eq_ignore_ascii_case("json");
+  let file_name = format!(
+    "DAAVFX_Push_{}.{}",
+    route.instance_id,
+    if as_json { "json" } else { "set" }
+  );
+  let file_path = common_dir.join(&file_name);
+
+  // Phase 1: the config file
+  if as_json {
+    let json = serde_json::to_string_pretty(&config)
+      .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    safe_overwrite(&file_path, &json)?;
+  } else {
+    export_set_file(
+      config,
+      file_path.to_string_lossy().to_string(),
+      platform.clone(),
+      false,
+      None,
+      None,
+      Some(format!("Push {}", nonce)),
+    )?;
+  }
+
+  // Phase 2: the reload command, then wait for the nonce to come back
+  let command = SyncCommandPayload {
+    command: "reload_config".to_string(),
+    group: None,
+    logic: None,
+    allow_buy: None,
+    allow_sell: None,
+    param_name: Some("config_file".to_string()),
+    param_value: Some(file_name),
+    command_id: Some(nonce.clone()),
+  };
+  write_commands_file(&route.commands_path, &[command])?;
+
+  let started = std::time::Instant::now();
+  let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_PUSH_TIMEOUT_SECS).max(1));
+  let mut received = false;
+  let result = |status, received, message: String| PushConfigResult {
+    status,
+    nonce: nonce.clone(),
+    file_path: file_path.to_string_lossy().to_string(),
+    received,
+    message,
+    elapsed_ms: started.elapsed().as_millis() as u64,
+  };
+  let outcome = loop {
+    let state = fs::read_to_string(&route.state_path)
+      .ok()
+      .and_then(|content| serde_json::from_str::<SyncState>(&content).ok());
+    if let Some(state) = state {
+      let (ack, seen) = push_progress(&state, &nonce);
+      received |= seen;
+      if let Some(ack) = ack {
+        let status = if ack.status.eq_ignore_ascii_case("applied") {
+          PushStatus::Applied
+        } else {
+          PushStatus::Rejected
+        };
+        break result(status, true, ack.message);
+      }
+    }
+    if started.elapsed() >= timeout {
+      let message = if received {
+        "EA received the reload but did not confirm it in time"
+      } else {
+        "EA did not pick up the reload command; is it running?"
+      };
+      break result(PushStatus::Timeout, received, message.to_string());
+    }
+    tokio::time::sleep(Duration::from_millis(PUSH_ACK_POLL_MS)).await;
+  };
+
+  notifications::dispatch_in_background(Notification {
+    severity: if outcome.status == PushStatus::Applied {
+      Severity::Info
+    } else {
+      Severity::Warning
+    },
+    title: format!("Config push to {}: {:?}", route.instance_id, outcome.status),
+    message: if outcome.message.is_empty() {
+      outcome.file_path.clone()
+    } else {
+      outcome.message.clone()
+    },
+    source: "config_push".to_string(),
+  });
+  Ok(outcome)
+}
+
+/// Which positions the kill switch flattens; empty means everything the EA
+/// manages
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KillSwitchScope {
+  #[serde(default)]
+  pub engine: Option<String>,
+  #[serde(default)]
+  pub magic_numbers: Vec<i32>,
 }
 
+impl KillSwitchScope {
+  fn normalized(mut self) -> Result<Self, String> {
+    self.engine = self
+      .engine
+      .map(|e| e.trim().to_uppercase())
+      .filter(|e| !e.is_empty());
+    if let Some(engine) = &self.engine {
+      if !["A", "B", "C"].contains(&engine.as_str()) {
+        return Err(format!("Unknown engine '{}'", engine));
+      }
+    }
+    self.magic_numbers.sort_unstable();
+    self.magic_numbers.dedup();
+    Ok(self)
+  }
+
+  /// "engine=A;magic=777,778", or "all", as the EA parses it
+  fn command_value(&self) -> String {
+    let mut parts = Vec::new();
+    if let Some(engine) = &self.engine {
+      parts.push(format!("engine={}", engine));
+    }
+    if !self.magic_numbers.is_empty() {
+      let magics: Vec<String> = self.magic_numbers.iter().map(i32::to_string).collect();
+      parts.push(format!("magic={}", magics.join(",")));
+    }
+    if parts.is_empty() {
+      "all".to_string()
+    } else {
+      parts.join(";")
+    }
+  }
+
+  fn describe(&self) -> String {
+    let mut parts = Vec::new();
+    if let Some(engine) = &self.engine {
+      parts.push(format!("engine {}", engine));
+    }
+    if !self.magic_numbers.is_empty() {
+      let magics: Vec<String> = self.magic_numbers.iter().map(i32::to_string).collect();
+      let noun = if magics.len() == 1 { "magic" } else { "magics" };
+      parts.push(format!("{} {}", noun, magics.join(",")));
+    }
+    if parts.is_empty() {
+      "all positions".to_string()
+    } else {
+      parts.join(", ")
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct PendingKillSwitch {
+  platform: String,
+  instance_id: Option<String>,
+  scope: KillSwitchScope,
+  expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KillSwitchToken {
+  /// Shown to the user, who has to type it back to confirm
+  pub token: String,
+  pub expires_at: i64,
+  pub scope: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KillSwitchResult {
+  pub command_id: String,
+  pub commands_path: String,
+  pub scope: String,
+}
+
+fn pending_kill_switches() -> &'static Mutex<HashMap<String, PendingKillSwitch>> {
+  static PENDING: OnceLock<Mutex<HashMap<String, PendingKillSwitch>>> = OnceLock::new();
+  PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Consume `token` if it was issued for exactly this close request
+fn take_kill_switch_token(
+  token: &str,
+  platform: &str,
+  instance_id: Option<&str>,
+  scope: &KillSwitchScope,
+  now: i64,
+) -> Result<(), String> {
+  let mut pending = pending_kill_switches().lock().unwrap();
+  pending.retain(|_, p| p.expires_at > now);
+  let key = token.trim().to_uppercase();
+  let issued = pending
+    .get(&key)
+    .ok_or_else(|| "Confirmation token is invalid or has expired".to_string())?;
+  if issued.platform != platform
+    || issued.instance_id.as_deref() != instance_id
+    || issued.scope != *scope
+  {
+    return Err("Confirmation token was issued for a different close request".to_string());
+  }
+  pending.remove(&key);
+  Ok(())
+}
+
+fn audit_kill_switch(
+  platform: &str,
+  instance_id: Option<&str>,
+  scope: &str,
+  command_id: Option<String>,
+  outcome: &str,
+  detail: String,
+) {
+  let entry = AuditEntry {
+    timestamp: audit_log::now_timestamp(),
+    action: "close_all_positions".to_string(),
+    platform: platform.to_string(),
+    instance_id: instance_id.map(str::to_string),
+    scope: scope.to_string(),
+    command_id,
+    outcome: outcome.to_string(),
+    detail,
+  };
+  if let Err(e) = audit_log::record(&entry) {
+    log::error!("{}", e);
+  }
+}
+
+/// First step of the kill switch: issue a short-lived token for this exact
+/// platform, instance and scope for the user to confirm with
+#[tauri::command]
+pub fn request_kill_switch_token(
+  platform: String,
+  instance_id: Option<String>,
+  scope: Option<KillSwitchScope>,
+) -> Result<KillSwitchToken, String> {
+  let scope = scope.unwrap_or_default().normalized()?;
+  sync_route(&platform, instance_id.as_deref())?;
+  let token = Uuid::new_v4().simple().to_string()[..6].to_uppercase();
+  let expires_at = unix_now() + KILL_SWITCH_TOKEN_SECS;
+  let description = scope.describe();
+  pending_kill_switches().lock().unwrap().insert(
+    token.clone(),
+    PendingKillSwitch {
+      platform,
+      instance_id,
+      scope,
+      expires_at,
+    },
+  );
+  Ok(KillSwitchToken {
+    token,
+    expires_at,
+    scope: description,
+  })
+}
+
+/// Emergency close of every position in `scope`, sent as a
+/// close_all_positions command. Needs the token from
+/// request_kill_switch_token; every attempt lands in the audit log.
+#[tauri::command]
+pub fn close_all_positions(
+  platform: String,
+  instance_id: Option<String>,
+  scope: Option<KillSwitchScope>,
+  confirmation_token: String,
+) -> Result<KillSwitchResult, String> {
+  let scope = scope.unwrap_or_default().normalized()?;
+  let description = scope.describe();
+  if let Err(e) = safe_mode::ensure_writable("Closing all positions") {
+    audit_kill_switch(&platform, instance_id.as_deref(), &description, None, "rejected", e.clone());
+    return Err(e);
+  }
+  if let Err(e) = take_kill_switch_token(
+    &confirmation_token,
+    &platform,
+    instance_id.as_deref(),
+    &scope,
+    unix_now(),
+  ) {
+    audit_kill_switch(&platform, instance_id.as_deref(), &description, None, "rejected", e.clone());
+    return Err(e);
+  }
+
+  let route = sync_route(&platform, instance_id.as_deref())?;
+  let command_id = Uuid::new_v4().to_string();
+  let command = SyncCommandPayload {
+    command: "close_all_positions".to_string(),
+    group: None,
+    logic: None,
+    allow_buy: None,
+    allow_sell: None,
+    param_name: Some("scope".to_string()),
+    param_value: Some(scope.command_value()),
+    command_id: Some(command_id.clone()),
+  };
+  let written = write_commands_file(&route.commands_path, &[command]);
+  let (outcome, detail) = match &written {
+    Ok(()) => ("sent", String::new()),
+    Err(e) => ("failed", e.clone()),
+  };
+  audit_kill_switch(
+    &platform,
+    instance_id.as_deref(),
+    &description,
+    Some(command_id.clone()),
+    outcome,
+    detail,
+  );
+  written?;
+  notifications::dispatch_in_background(Notification {
+    severity: Severity::Critical,
+    title: "Kill switch: close all positions".to_string(),
+    message: format!("{} on {} ({})", description, platform, route.instance_id),
+    source: "kill_switch".to_string(),
+  });
+  Ok(KillSwitchResult {
+    command_id,
+    commands_path: route.commands_path.to_string_lossy().to_string(),
+    scope: description,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const LEGACY_STATE: &str = r#"{
+    "version": "19.2",
+    "timestamp": "2026.03.02 10:00:00",
+    "symbol": "XAUUSD",
+    "magic_number": 777,
+    "global_buy_sell": { "allow_buy": true, "allow_sell": true },
+    "logic_states": [{
+      "group": 1, "logic": "POWER", "allow_buy": true, "allow_sell": false,
+      "reverse_enabled": false, "hedge_enabled": false, "scale_reverse": 100.0, "scale_hedge": 50.0
+    }],
+    "account": { "balance": 10000.0, "equity": 9950.0, "currency": "USD" }
+  }"#;
+
+  #[test]
+  fn test_sync_state_schema_and_validation() {
+    let mut state: SyncState = serde_json::from_str(LEGACY_STATE).unwrap();
+    assert_eq!(state.schema_version, 1);
+    assert!(state.positions.is_empty());
+    assert_eq!(state.heartbeat, 0);
+    assert!(state.validate().is_ok());
+
+    state.schema_version = SYNC_SCHEMA_VERSION;
+    state.heartbeat = 1_772_445_600;
+    state.positions.push(SyncPosition {
+      ticket: 1,
+      symbol: "XAUUSD".to_string(),
+      side: "BUY".to_string(),
+      volume: 0.1,
+      open_price: 2900.0,
+      profit: -5.0,
+      magic: 777,
+      comment: String::new(),
+    });
+    assert!(state.validate().is_ok());
+
+    state.schema_version = SYNC_SCHEMA_VERSION + 1;
+    state.positions[0].volume = 0.0;
+    state.logic_states[0].group = 0;
+    let err = state.validate().unwrap_err();
+    assert!(err.contains("unsupported schema_version"), "{}", err);
+    assert!(err.contains("position 1 has volume 0"), "{}", err);
+    assert!(err.contains("invalid logic state group 0"), "{}", err);
+  }
+
+  #[test]
+  fn test_sync_registry_routes() {
+    let dir = PathBuf::from("common");
+    let routes = parse_registry(
+      &dir,
+      r#"{"instances": [
+        {"instance_id": "gold-777", "symbol": "XAUUSD", "magic_number": 777,
+         "state_file": "DAAVFX_SyncState_777.json",
+         "commands_file": "DAAVFX_SyncCommands_777.json"},
+        {"instance_id": "gold-777", "state_file": "dup.json", "commands_file": "dup.json"},
+        {"instance_id": "eu-888", "state_file": "eu_state.json", "commands_file": "eu_cmd.json"}
+      ]}"#,
+    )
+    .unwrap();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].state_path, dir.join("DAAVFX_SyncState_777.json"));
+    assert_eq!(routes[1].commands_path, dir.join("eu_cmd.json"));
+    assert_eq!(routes[1].account_path, dir.join("DAAVFX_Account_eu-888.json"));
+
+    let escape = r#"{"instances": [
+      {"instance_id": "x", "state_file": "../x.json", "commands_file": "c.json"}
+    ]}"#;
+    assert!(parse_registry(&dir, escape).is_err());
+    assert_eq!(legacy_route(&dir).instance_id, DEFAULT_INSTANCE_ID);
+  }
+
+  #[test]
+  fn test_push_progress_reads_ack() {
+    let mut state: SyncState = serde_json::from_str(LEGACY_STATE).unwrap();
+    assert!(state.acknowledgements.is_empty());
+    assert!(!push_progress(&state, "n1").1);
+
+    state.pending_commands.push(SyncPendingCommand {
+      command_id: "n1".to_string(),
+      command: "reload_config".to_string(),
+      status: "loading".to_string(),
+    });
+    let (ack, received) = push_progress(&state, "n1");
+    assert!(ack.is_none() && received);
+
+    state.acknowledgements.push(SyncAck {
+      command_id: "n1".to_string(),
+      status: "rejected".to_string(),
+      message: "lot size out of range".to_string(),
+    });
+    let (ack, _) = push_progress(&state, "n1");
+    assert_eq!(ack.unwrap().message, "lot size out of range");
+    assert!(push_progress(&state, "other").0.is_none());
+  }
+
+  #[test]
+  fn test_kill_switch_scope_and_token() {
+    let scope = KillSwitchScope {
+      engine: Some(" b ".to_string()),
+      magic_numbers: vec![778, 777, 778],
+    }
+    .normalized()
+    .unwrap();
+    assert_eq!(scope.command_value(), "engine=B;magic=777,778");
+    assert_eq!(scope.describe(), "engine B, magics 777,778");
+    assert_eq!(KillSwitchScope::default().command_value(), "all");
+    let bad = KillSwitchScope {
+      engine: Some("Z".to_string()),
+      magic_numbers: Vec::new(),
+    };
+    assert!(bad.normalized().is_err());
+
+    pending_kill_switches().lock().unwrap().insert(
+      "ABC123".to_string(),
+      PendingKillSwitch {
+        platform: "MT4".to_string(),
+        instance_id: None,
+        scope: scope.clone(),
+        expires_at: 1_000,
+      },
+    );
+    let other = KillSwitchScope::default();
+    assert!(take_kill_switch_token("abc123", "MT4", None, &other, 900).is_err());
+    assert!(take_kill_switch_token("abc123", "MT4", None, &scope, 900).is_ok());
+    // Tokens are single use
+    assert!(take_kill_switch_token("abc123", "MT4", None, &scope, 900).is_err());
+  }
+}
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { SyncCommand, SyncPaths, SyncState } from "@/types/tactical-sync";
+import { listen } from "@tauri-apps/api/event";
+import type { EaHeartbeatEvent, EaInstance, SyncCommand, SyncPaths, SyncState } from "@/types/tactical-sync";
 
 export type MTPlatform = "MT4" | "MT5";
 
@@ -10,12 +11,16 @@ export interface TacticalSyncState {
   isMonitoring: boolean;
   lastUpdatedAt: number | null;
   error: string | null;
+  /** Set from the backend poller's "ea-offline" event, cleared on "ea-online" */
+  eaOffline: EaHeartbeatEvent | null;
 }
 
 export interface TacticalSyncActions {
   setMonitoring: (value: boolean) => void;
   refresh: () => Promise<void>;
   sendCommands: (commands: SyncCommand[]) => Promise<void>;
+  broadcastCommands: (commands: SyncCommand[]) => Promise<void>;
+  listInstances: () => Promise<EaInstance[]>;
   setGlobalBuySell: (allowBuy: boolean, allowSell: boolean) => Promise<void>;
   setLogicBuySell: (group: number, logic: string, allowBuy: boolean, allowSell: boolean) => Promise<void>;
   reloadConfig: () => Promise<void>;
@@ -33,12 +38,20 @@ const normalizeError = (err: unknown) => {
   }
 };
 
-export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [TacticalSyncState, TacticalSyncActions] {
+/** Legacy single-EA sync files, used when no instance is selected */
+const DEFAULT_INSTANCE_ID = "default";
+
+export function useTacticalSync(
+  platform: MTPlatform,
+  pollIntervalMs = 1500,
+  instanceId?: string,
+): [TacticalSyncState, TacticalSyncActions] {
   const [paths, setPaths] = useState<SyncPaths | null>(null);
   const [syncState, setSyncState] = useState<SyncState | null>(null);
   const [isMonitoring, setIsMonitoring] = useState(true);
   const [lastUpdatedAt, setLastUpdatedAt] = useState<number | null>(null);
   const [error, setError] = useState<string | null>(null);
+  const [eaOffline, setEaOffline] = useState<EaHeartbeatEvent | null>(null);
   const inflightRef = useRef<Promise<void> | null>(null);
 
   const refresh = useCallback(async () => {
@@ -47,9 +60,9 @@ export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [T
     const task = (async () => {
       try {
         setError(null);
-        const nextPaths = await invoke<SyncPaths>("get_sync_paths", { platform });
+        const nextPaths = await invoke<SyncPaths>("get_sync_paths", { platform, instanceId });
         setPaths(nextPaths);
-        const nextState = await invoke<SyncState | null>("read_sync_state", { platform });
+        const nextState = await invoke<SyncState | null>("read_sync_state", { platform, instanceId });
         setSyncState(nextState);
         setLastUpdatedAt(Date.now());
       } catch (err) {
@@ -62,13 +75,26 @@ export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [T
     });
 
     return inflightRef.current;
-  }, [platform]);
+  }, [platform, instanceId]);
 
   const sendCommands = useCallback(
     async (commands: SyncCommand[]) => {
       try {
         setError(null);
-        await invoke<string>("write_sync_commands", { platform, commands });
+        await invoke<string>("write_sync_commands", { platform, commands, instanceId });
+        await refresh();
+      } catch (err) {
+        setError(normalizeError(err));
+      }
+    },
+    [platform, instanceId, refresh],
+  );
+
+  const broadcastCommands = useCallback(
+    async (commands: SyncCommand[]) => {
+      try {
+        setError(null);
+        await invoke<string[]>("broadcast_sync_commands", { platform, commands });
         await refresh();
       } catch (err) {
         setError(normalizeError(err));
@@ -77,6 +103,15 @@ export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [T
     [platform, refresh],
   );
 
+  const listInstances = useCallback(async () => {
+    try {
+      return await invoke<EaInstance[]>("list_ea_instances", { platform });
+    } catch (err) {
+      setError(normalizeError(err));
+      return [];
+    }
+  }, [platform]);
+
   const setGlobalBuySell = useCallback(
     async (allowBuy: boolean, allowSell: boolean) => {
       await sendCommands([
@@ -121,9 +156,42 @@ export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [T
     return () => clearInterval(interval);
   }, [isMonitoring, pollIntervalMs, refresh]);
 
+  useEffect(() => {
+    if (!isMonitoring) return;
+    const unlisteners: Array<() => void> = [];
+    let cancelled = false;
+    const isOwnInstance = (payload: EaHeartbeatEvent) =>
+      payload.platform === platform && payload.instance_id === (instanceId ?? DEFAULT_INSTANCE_ID);
+
+    (async () => {
+      try {
+        const offline = await listen<EaHeartbeatEvent>("ea-offline", (event) => {
+          if (isOwnInstance(event.payload)) setEaOffline(event.payload);
+        });
+        const online = await listen<EaHeartbeatEvent>("ea-online", (event) => {
+          if (isOwnInstance(event.payload)) setEaOffline(null);
+        });
+        unlisteners.push(offline, online);
+        if (cancelled) {
+          unlisteners.forEach((unlisten) => unlisten());
+          return;
+        }
+        await invoke("start_sync_poller", { platform });
+      } catch (err) {
+        console.error("Failed to start sync poller:", err);
+      }
+    })();
+
+    return () => {
+      cancelled = true;
+      unlisteners.forEach((unlisten) => unlisten());
+      invoke("stop_sync_poller").catch(() => undefined);
+    };
+  }, [isMonitoring, platform, instanceId]);
+
   const state = useMemo<TacticalSyncState>(
-    () => ({ paths, syncState, isMonitoring, lastUpdatedAt, error }),
-    [paths, syncState, isMonitoring, lastUpdatedAt, error],
+    () => ({ paths, syncState, isMonitoring, lastUpdatedAt, error, eaOffline }),
+    [paths, syncState, isMonitoring, lastUpdatedAt, error, eaOffline],
   );
 
   const actions = useMemo<TacticalSyncActions>(
@@ -131,13 +199,25 @@ export function useTacticalSync(platform: MTPlatform, pollIntervalMs = 1500): [T
       setMonitoring: setIsMonitoring,
       refresh,
       sendCommands,
+      broadcastCommands,
+      listInstances,
       setGlobalBuySell,
       setLogicBuySell,
       reloadConfig,
       exportState,
       resetOverrides,
     }),
-    [refresh, sendCommands, setGlobalBuySell, setLogicBuySell, reloadConfig, exportState, resetOverrides],
+    [
+      refresh,
+      sendCommands,
+      broadcastCommands,
+      listInstances,
+      setGlobalBuySell,
+      setLogicBuySell,
+      reloadConfig,
+      exportState,
+      resetOverrides,
+    ],
   );
 
   return [state, actions];
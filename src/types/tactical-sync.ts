@@ -20,7 +20,37 @@ export interface SyncAccount {
   currency: string;
 }
 
+export interface SyncPosition {
+  ticket: number;
+  symbol: string;
+  side: "buy" | "sell" | string;
+  volume: number;
+  open_price: number;
+  profit: number;
+  magic: number;
+  comment: string;
+}
+
+export interface SyncPendingCommand {
+  command_id: string;
+  command: string;
+  status: string;
+}
+
+export interface SyncAck {
+  command_id: string;
+  status: "applied" | "rejected" | string;
+  message: string;
+}
+
+export interface SyncError {
+  timestamp: string;
+  code: number;
+  message: string;
+}
+
 export interface SyncState {
+  schema_version: number;
   version: string;
   timestamp: string;
   symbol: string;
@@ -28,6 +58,18 @@ export interface SyncState {
   global_buy_sell: SyncGlobalBuySell;
   logic_states: SyncLogicState[];
   account: SyncAccount;
+  positions: SyncPosition[];
+  pending_commands: SyncPendingCommand[];
+  heartbeat: number;
+  errors: SyncError[];
+  acknowledgements: SyncAck[];
+}
+
+export interface EaHeartbeatEvent {
+  platform: string;
+  instance_id: string;
+  last_heartbeat: number | null;
+  stale_for_secs: number;
 }
 
 export interface SyncPaths {
@@ -48,3 +90,146 @@ export interface SyncCommand {
   command_id?: string;
 }
 
+
+export interface EaInstance {
+  instance_id: string;
+  symbol: string;
+  magic_number: number;
+  state_path: string;
+  commands_path: string;
+  last_heartbeat: number | null;
+  online: boolean;
+}
+
+export type PushStatus = "Applied" | "Timeout" | "Rejected";
+
+export interface PushConfigResult {
+  status: PushStatus;
+  nonce: string;
+  file_path: string;
+  received: boolean;
+  message: string;
+  elapsed_ms: number;
+}
+
+export interface KillSwitchScope {
+  engine?: "A" | "B" | "C" | null;
+  magic_numbers?: number[];
+}
+
+export interface KillSwitchToken {
+  token: string;
+  expires_at: number;
+  scope: string;
+}
+
+export interface KillSwitchResult {
+  command_id: string;
+  commands_path: string;
+  scope: string;
+}
+
+export interface SnapshotPosition {
+  ticket: number;
+  symbol: string;
+  side: "buy" | "sell" | string;
+  volume: number;
+  open_price: number;
+  current_price: number;
+  profit: number;
+  swap: number;
+  commission: number;
+  magic: number;
+  comment: string;
+}
+
+export interface AccountSnapshot {
+  schema_version: number;
+  timestamp: number;
+  login: number | null;
+  server: string;
+  currency: string;
+  balance: number;
+  equity: number;
+  margin: number;
+  free_margin: number;
+  leverage: number | null;
+  positions: SnapshotPosition[];
+}
+
+export interface MagicExposure {
+  magic: number;
+  positions: number;
+  buy_volume: number;
+  sell_volume: number;
+  profit: number;
+}
+
+export interface AccountSnapshotReport {
+  profile: string;
+  path: string;
+  snapshot: AccountSnapshot;
+  age_secs: number;
+  floating_profit: number;
+  drawdown_percent: number;
+  margin_level: number | null;
+  exposure: MagicExposure[];
+}
+
+export type AlertCondition =
+  | { kind: "drawdown"; percent: number }
+  | { kind: "news_with_open_positions"; minutes: number; min_impact?: "low" | "medium" | "high" | null }
+  | { kind: "heartbeat_lost"; seconds: number }
+  | { kind: "terminal_log_match"; pattern: string };
+
+export interface AlertRule {
+  id: string;
+  name: string;
+  enabled: boolean;
+  platform: string;
+  profile?: string | null;
+  condition: AlertCondition;
+  stop_on_trigger: boolean;
+  severity: NotificationSeverity;
+}
+
+export interface AlertEvent {
+  rule_id: string;
+  rule_name: string;
+  platform: string;
+  profile: string | null;
+  message: string;
+  stop_sent: boolean;
+  timestamp: number;
+}
+
+export type NotificationSeverity = "info" | "warning" | "critical";
+
+export type NotificationChannelConfig =
+  | { type: "telegram"; bot_token: string; chat_id: string }
+  | { type: "webhook"; url: string; headers?: Record<string, string> }
+  | {
+      type: "smtp";
+      host: string;
+      port?: number;
+      username?: string;
+      password?: string;
+      from: string;
+      to: string[];
+      starttls?: boolean;
+    };
+
+export interface NotificationChannel {
+  id: string;
+  name: string;
+  enabled: boolean;
+  min_severity: NotificationSeverity;
+  config: NotificationChannelConfig;
+}
+
+export interface DeliveryResult {
+  channel_id: string;
+  channel_name: string;
+  ok: boolean;
+  error: string | null;
+}